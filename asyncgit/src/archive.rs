@@ -0,0 +1,75 @@
+use crate::{
+    error::Result,
+    sync::{self, ArchiveOptions},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+/// outcome of an archive job, the error as text
+type ArchiveResult = Option<(ArchiveOptions, Option<String>)>;
+
+/// writes archives in the background, big repos take a while
+pub struct AsyncArchive {
+    last: Arc<Mutex<ArchiveResult>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+}
+
+impl AsyncArchive {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// options of the finished job and its error (`None` if it
+    /// succeeded), can only be taken once
+    pub fn take_last(&mut self) -> Result<ArchiveResult> {
+        Ok(self.last.lock()?.take())
+    }
+
+    /// does nothing while another archive is written
+    pub fn request(&mut self, options: ArchiveOptions) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        *self.last.lock()? = None;
+        self.pending.store(true, Ordering::Relaxed);
+
+        let arc_last = Arc::clone(&self.last);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            let res = sync::archive(CWD, &options)
+                .err()
+                .map(|e| e.to_string());
+
+            match arc_last.lock() {
+                Ok(mut last) => *last = Some((options, res)),
+                Err(e) => log::error!("archive error: {}", e),
+            }
+
+            arc_pending.store(false, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Archive)
+                .expect("error sending archive");
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    error::Result,
+    sync::{self, CommitId},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// file at a commit
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobParams {
+    ///
+    pub commit: CommitId,
+    ///
+    pub path: String,
+}
+
+type Request = (BlobParams, Arc<Vec<u8>>);
+
+/// loads the raw content of a file at a commit
+pub struct AsyncBlob {
+    current: Arc<Mutex<Option<Request>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncBlob {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            current: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// content of the last finished fetch if it was for `params`
+    pub fn current(
+        &self,
+        params: &BlobParams,
+    ) -> Result<Option<Arc<Vec<u8>>>> {
+        Ok(self
+            .current
+            .lock()?
+            .as_ref()
+            .filter(|(fetched, _)| fetched == params)
+            .map(|(_, content)| Arc::clone(content)))
+    }
+
+    /// ignored while another fetch is pending, request again once
+    /// it finished
+    pub fn fetch(&mut self, params: BlobParams) -> Result<()> {
+        if self.is_pending() || self.current(&params)?.is_some() {
+            return Ok(());
+        }
+
+        let arc_current = Arc::clone(&self.current);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            if let Err(e) = Self::fetch_helper(params, &arc_current) {
+                log::error!("blob error: {}", e);
+            }
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Blob)
+                .expect("error sending blob");
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        params: BlobParams,
+        arc_current: &Arc<Mutex<Option<Request>>>,
+    ) -> Result<()> {
+        let content = sync::get_file_at_commit(
+            CWD,
+            params.commit,
+            &params.path,
+        )?;
+
+        *arc_current.lock()? = Some((params, Arc::new(content)));
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::Result,
-    sync::{self, CommitId},
+    sync::{self, CommitId, CommitStats},
     AsyncNotification, StatusItem, CWD,
 };
 use crossbeam_channel::Sender;
@@ -9,7 +9,9 @@ use std::sync::{
     Arc, Mutex,
 };
 
-type ResultType = Vec<StatusItem>;
+/// the files, their stats and the untracked files of a stash (see
+/// `sync::get_stash_untracked_files`)
+type ResultType = (Vec<StatusItem>, CommitStats, Vec<String>);
 struct Request<R, A>(R, A);
 
 ///
@@ -91,6 +93,8 @@ impl AsyncCommitFiles {
         >,
     ) -> Result<()> {
         let res = sync::get_commit_files(CWD, id)?;
+        let stats = sync::get_commit_stats(CWD, id)?;
+        let untracked = sync::get_stash_untracked_files(CWD, id)?;
 
         log::trace!(
             "get_commit_files: {} ({})",
@@ -100,7 +104,7 @@ impl AsyncCommitFiles {
 
         {
             let mut current = arc_current.lock()?;
-            *current = Some(Request(id, res));
+            *current = Some(Request(id, (res, stats, untracked)));
         }
 
         Ok(())
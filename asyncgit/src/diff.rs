@@ -1,7 +1,7 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, CommitId},
+    sync::{self, CommitId, DiffOptions},
     AsyncNotification, FileDiff, CWD,
 };
 use crossbeam_channel::Sender;
@@ -22,6 +22,8 @@ pub enum DiffType {
     Stage,
     /// diff against file in workdir
     WorkDir,
+    /// all staged files combined, `path` is ignored
+    AllStaged,
 }
 
 ///
@@ -29,8 +31,12 @@ pub enum DiffType {
 pub struct DiffParams {
     /// path to the file to diff
     pub path: String,
+    /// where the file was renamed from
+    pub old_path: Option<String>,
     /// what kind of diff
     pub diff_type: DiffType,
+    /// how the diff is made
+    pub options: DiffOptions,
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -143,17 +149,45 @@ impl AsyncDiff {
         arc_current: Arc<Mutex<Request<u64, FileDiff>>>,
         hash: u64,
     ) -> Result<bool> {
-        let res = match params.diff_type {
-            DiffType::Stage => {
-                sync::diff::get_diff(CWD, params.path.clone(), true)?
+        let res = match (&params.diff_type, &params.old_path) {
+            (DiffType::Stage, Some(old_path)) => {
+                sync::diff::get_diff_renamed(
+                    CWD,
+                    old_path,
+                    params.path.clone(),
+                    true,
+                    params.options,
+                )?
             }
-            DiffType::WorkDir => {
-                sync::diff::get_diff(CWD, params.path.clone(), false)?
+            (DiffType::WorkDir, Some(old_path)) => {
+                sync::diff::get_diff_renamed(
+                    CWD,
+                    old_path,
+                    params.path.clone(),
+                    false,
+                    params.options,
+                )?
             }
-            DiffType::Commit(id) => sync::diff::get_diff_commit(
+            (DiffType::Stage, None) => sync::diff::get_diff(
                 CWD,
-                id,
                 params.path.clone(),
+                true,
+                params.options,
+            )?,
+            (DiffType::WorkDir, None) => sync::diff::get_diff(
+                CWD,
+                params.path.clone(),
+                false,
+                params.options,
+            )?,
+            (DiffType::AllStaged, _) => {
+                sync::diff::get_diff_staged(CWD, params.options)?
+            }
+            (DiffType::Commit(id), _) => sync::diff::get_diff_commit(
+                CWD,
+                *id,
+                params.path.clone(),
+                params.options,
             )?,
         };
 
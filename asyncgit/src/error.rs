@@ -8,6 +8,9 @@ pub enum Error {
     #[error("git: no head found")]
     NoHead,
 
+    #[error("not available in a bare repository")]
+    BareRepo,
+
     #[error("io error:{0}")]
     Io(#[from] std::io::Error),
 
@@ -0,0 +1,108 @@
+use crate::{
+    error::Result,
+    sync::{self, FetchAllResult, RemoteProgress},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+///
+pub struct AsyncFetch {
+    progress: Arc<Mutex<Option<RemoteProgress>>>,
+    last: Arc<Mutex<Option<FetchAllResult>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncFetch {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            progress: Arc::new(Mutex::new(None)),
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// summary of the last finished fetch
+    pub fn last(&self) -> Result<Option<FetchAllResult>> {
+        Ok(self.last.lock()?.clone())
+    }
+
+    /// progress of the currently running fetch
+    pub fn progress(&self) -> Result<Option<RemoteProgress>> {
+        Ok(self.progress.lock()?.clone())
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// fetches all remotes with prune,
+    /// sends `AsyncNotification::Fetch` on progress and when done
+    pub fn request(&mut self) -> Result<()> {
+        log::trace!("request");
+
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        *self.progress.lock()? = None;
+
+        let arc_progress = Arc::clone(&self.progress);
+        let arc_last = Arc::clone(&self.last);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            let res = Self::fetch_helper(&arc_progress, &sender);
+
+            {
+                let mut last =
+                    arc_last.lock().expect("error locking fetch");
+                *last =
+                    Some(res.unwrap_or_else(|e| FetchAllResult {
+                        succeeded: Vec::new(),
+                        failed: vec![(String::new(), e.to_string())],
+                    }));
+            }
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Fetch)
+                .expect("error sending fetch");
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        arc_progress: &Arc<Mutex<Option<RemoteProgress>>>,
+        sender: &Sender<AsyncNotification>,
+    ) -> Result<FetchAllResult> {
+        let mut last_sent: Option<(usize, u8)> = None;
+
+        sync::fetch_all(CWD, |p| {
+            if let Ok(mut progress) = arc_progress.lock() {
+                *progress = Some(p.clone());
+            }
+
+            // only notify when something visible changed
+            let current = (p.remote_index, p.percent());
+            if last_sent != Some(current) {
+                last_sent = Some(current);
+                sender
+                    .send(AsyncNotification::Fetch)
+                    .expect("error sending fetch progress");
+            }
+        })
+    }
+}
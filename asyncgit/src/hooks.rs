@@ -0,0 +1,189 @@
+use crate::{
+    error::Result,
+    sync::{self, HookResult},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommitHooksResult {
+    /// all hooks passed, contains the (possibly altered) message and
+    /// the hooks that actually ran
+    Ok(String, Vec<&'static str>),
+    /// the named hook rejected the commit, the report starts with
+    /// a line naming the hook (and its exit code)
+    NotOk(&'static str, String),
+    /// hooks were cancelled by the user
+    Cancelled,
+}
+
+///
+pub struct AsyncHooks {
+    output: Arc<Mutex<Vec<String>>>,
+    last: Arc<Mutex<Option<CommitHooksResult>>>,
+    cancel: Arc<AtomicBool>,
+    timeout: Option<Duration>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncHooks {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            output: Arc::new(Mutex::new(Vec::new())),
+            last: Arc::new(Mutex::new(None)),
+            cancel: Arc::new(AtomicBool::new(false)),
+            timeout: None,
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// hooks running longer than `timeout` get killed
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// lines printed by the hooks of the current (or last) run
+    pub fn output(&self) -> Result<Vec<String>> {
+        Ok(self.output.lock()?.clone())
+    }
+
+    /// result of the last finished run, can only be taken once
+    pub fn take_last(&mut self) -> Result<Option<CommitHooksResult>> {
+        Ok(self.last.lock()?.take())
+    }
+
+    /// kills the currently running hook
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// runs `pre-commit` and `commit-msg` in the background,
+    /// sends `AsyncNotification::Hooks` for every line of output
+    /// and once done
+    pub fn run_commit_hooks(&mut self, msg: String) -> Result<()> {
+        log::trace!("run_commit_hooks");
+
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        self.output.lock()?.clear();
+        *self.last.lock()? = None;
+        self.cancel.store(false, Ordering::Relaxed);
+
+        let arc_output = Arc::clone(&self.output);
+        let arc_last = Arc::clone(&self.last);
+        let arc_cancel = Arc::clone(&self.cancel);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+        let timeout = self.timeout;
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            let res = Self::run_helper(
+                msg,
+                timeout,
+                &arc_output,
+                &arc_cancel,
+                &sender,
+            );
+
+            *arc_last.lock().expect("error locking hooks") =
+                Some(res);
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Hooks)
+                .expect("error sending hooks");
+        });
+
+        Ok(())
+    }
+
+    fn run_helper(
+        msg: String,
+        timeout: Option<Duration>,
+        arc_output: &Arc<Mutex<Vec<String>>>,
+        cancel: &AtomicBool,
+        sender: &Sender<AsyncNotification>,
+    ) -> CommitHooksResult {
+        let on_line = |line: &str| {
+            if let Ok(mut output) = arc_output.lock() {
+                output.push(line.to_string());
+            }
+
+            sender
+                .send(AsyncNotification::Hooks)
+                .expect("error sending hook output");
+        };
+
+        let mut ran = Vec::new();
+
+        let res = sync::hooks_pre_commit_streamed(
+            CWD, timeout, cancel, on_line,
+        );
+        if let Some(res) =
+            Self::check("pre-commit", res, cancel, &mut ran)
+        {
+            return res;
+        }
+
+        let mut msg = msg;
+        let res = sync::hooks_commit_msg_streamed(
+            CWD, &mut msg, timeout, cancel, on_line,
+        );
+        if let Some(res) =
+            Self::check("commit-msg", res, cancel, &mut ran)
+        {
+            return res;
+        }
+
+        CommitHooksResult::Ok(msg, ran)
+    }
+
+    /// returns `None` if the hook passed (or does not exist), a hook
+    /// that ran is added to `ran`
+    fn check(
+        hook: &'static str,
+        res: Result<HookResult>,
+        cancel: &AtomicBool,
+        ran: &mut Vec<&'static str>,
+    ) -> Option<CommitHooksResult> {
+        if cancel.load(Ordering::Relaxed) {
+            return Some(CommitHooksResult::Cancelled);
+        }
+
+        match res {
+            Ok(res) => {
+                if res != HookResult::NoHook {
+                    ran.push(hook);
+                }
+
+                res.error().map(|e| CommitHooksResult::NotOk(hook, e))
+            }
+            Err(e) => Some(CommitHooksResult::NotOk(
+                hook,
+                format!("{} hook failed:\n{}", hook, e),
+            )),
+        }
+    }
+}
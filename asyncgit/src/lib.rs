@@ -6,20 +6,38 @@
 #![deny(clippy::unwrap_used)]
 #![deny(clippy::panic)]
 
+mod archive;
+mod blob;
 pub mod cached;
 mod commit_files;
 mod diff;
 mod error;
+mod fetch;
+mod hooks;
+mod push;
 mod revlog;
+mod shortlog;
+mod signature;
+mod stats;
 mod status;
+mod submodules;
 pub mod sync;
 mod tags;
 
 pub use crate::{
+    archive::AsyncArchive,
+    blob::{AsyncBlob, BlobParams},
     commit_files::AsyncCommitFiles,
     diff::{AsyncDiff, DiffParams, DiffType},
+    fetch::AsyncFetch,
+    hooks::{AsyncHooks, CommitHooksResult},
+    push::{AsyncPush, PushOutcome},
     revlog::{AsyncLog, FetchStatus},
+    shortlog::AsyncShortlog,
+    signature::AsyncSignature,
+    stats::AsyncRepoStats,
     status::{AsyncStatus, StatusParams},
+    submodules::AsyncSubmoduleUpdate,
     sync::{
         diff::{DiffLine, DiffLineType, FileDiff},
         status::{StatusItem, StatusItemType},
@@ -46,6 +64,24 @@ pub enum AsyncNotification {
     CommitFiles,
     ///
     Tags,
+    ///
+    Fetch,
+    ///
+    Hooks,
+    ///
+    Shortlog,
+    ///
+    Blob,
+    ///
+    Signature,
+    ///
+    Stats,
+    ///
+    Archive,
+    ///
+    Push,
+    ///
+    SubmoduleUpdate,
 }
 
 /// current working director `./`
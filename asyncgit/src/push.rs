@@ -0,0 +1,125 @@
+use crate::{
+    error::Result,
+    sync::{self, PushProgress, PushRequest},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// how a push ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushOutcome {
+    ///
+    Pushed,
+    /// the `pre-push` hook stopped it, with what it printed
+    HookFailed(String),
+    /// the error as text
+    Failed(String),
+}
+
+/// outcome of a push
+type PushResult = Option<(PushRequest, PushOutcome)>;
+
+/// pushes in the background, the network and hooks take a while
+pub struct AsyncPush {
+    progress: Arc<Mutex<Option<PushProgress>>>,
+    last: Arc<Mutex<PushResult>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+    hooks_timeout: Option<Duration>,
+}
+
+impl AsyncPush {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            progress: Arc::new(Mutex::new(None)),
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+            hooks_timeout: None,
+        }
+    }
+
+    /// a `pre-push` hook running longer than `timeout` gets killed
+    pub fn hooks_timeout(
+        mut self,
+        timeout: Option<Duration>,
+    ) -> Self {
+        self.hooks_timeout = timeout;
+        self
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// what the currently running push is busy with
+    pub fn progress(&self) -> Result<Option<PushProgress>> {
+        Ok(*self.progress.lock()?)
+    }
+
+    /// request of the finished push and how it ended, can only be
+    /// taken once
+    pub fn take_last(&mut self) -> Result<PushResult> {
+        Ok(self.last.lock()?.take())
+    }
+
+    /// does nothing while another push runs,
+    /// sends `AsyncNotification::Push` on progress and when done
+    pub fn request(&mut self, request: PushRequest) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        *self.progress.lock()? = None;
+        *self.last.lock()? = None;
+        self.pending.store(true, Ordering::Relaxed);
+
+        let arc_progress = Arc::clone(&self.progress);
+        let arc_last = Arc::clone(&self.last);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+        let hooks_timeout = self.hooks_timeout;
+
+        rayon_core::spawn(move || {
+            let res = sync::push(CWD, &request, hooks_timeout, |p| {
+                if let Ok(mut progress) = arc_progress.lock() {
+                    *progress = Some(p);
+                }
+
+                sender
+                    .send(AsyncNotification::Push)
+                    .expect("error sending push progress");
+            });
+
+            let outcome = match res {
+                Ok(hook) => hook.error().map_or(
+                    PushOutcome::Pushed,
+                    PushOutcome::HookFailed,
+                ),
+                Err(e) => PushOutcome::Failed(e.to_string()),
+            };
+
+            match arc_last.lock() {
+                Ok(mut last) => *last = Some((request, outcome)),
+                Err(e) => log::error!("push error: {}", e),
+            }
+
+            arc_pending.store(false, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Push)
+                .expect("error sending push");
+        });
+
+        Ok(())
+    }
+}
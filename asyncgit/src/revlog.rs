@@ -33,6 +33,11 @@ pub struct AsyncLog {
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicBool>,
     background: Arc<AtomicBool>,
+    first_parent: Arc<AtomicBool>,
+    author: Arc<Mutex<Option<String>>>,
+    /// `HEAD` the list was walked from, with an author filter
+    /// it is not necessarily the first entry
+    walked_head: Option<CommitId>,
 }
 
 static LIMIT_COUNT: usize = 3000;
@@ -47,6 +52,9 @@ impl AsyncLog {
             sender: sender.clone(),
             pending: Arc::new(AtomicBool::new(false)),
             background: Arc::new(AtomicBool::new(false)),
+            first_parent: Arc::new(AtomicBool::new(false)),
+            author: Arc::new(Mutex::new(None)),
+            walked_head: None,
         }
     }
 
@@ -69,6 +77,11 @@ impl AsyncLog {
         Ok(Vec::from_iter(list[min..max].iter().cloned()))
     }
 
+    /// index of `id` in the log fetched so far
+    pub fn position(&self, id: CommitId) -> Result<Option<usize>> {
+        Ok(self.current.lock()?.iter().position(|e| *e == id))
+    }
+
     ///
     pub fn is_pending(&self) -> bool {
         self.pending.load(Ordering::Relaxed)
@@ -80,19 +93,57 @@ impl AsyncLog {
     }
 
     ///
-    fn current_head(&self) -> Result<CommitId> {
-        Ok(self
-            .current
-            .lock()?
-            .first()
-            .map_or(Oid::zero().into(), |f| *f))
+    pub fn is_first_parent(&self) -> bool {
+        self.first_parent.load(Ordering::Relaxed)
+    }
+
+    /// only walk along the first parent of merges,
+    /// the log is refetched on the next `fetch` if this changed
+    pub fn set_first_parent(&mut self, enabled: bool) -> Result<()> {
+        if self.first_parent.swap(enabled, Ordering::Relaxed)
+            != enabled
+        {
+            self.clear()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    pub fn author_filter(&self) -> Result<Option<String>> {
+        Ok(self.author.lock()?.clone())
+    }
+
+    /// only list commits authored by `email`,
+    /// the log is refetched on the next `fetch` if this changed
+    pub fn set_author_filter(
+        &mut self,
+        email: Option<String>,
+    ) -> Result<()> {
+        let changed = {
+            let mut author = self.author.lock()?;
+            let changed = *author != email;
+            *author = email;
+            changed
+        };
+
+        if changed {
+            self.clear()?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    fn current_head(&self) -> CommitId {
+        self.walked_head.unwrap_or_else(|| Oid::zero().into())
     }
 
     ///
     fn head_changed(&self) -> Result<bool> {
         if let Ok(head) = repo(CWD)?.head() {
             if let Some(head) = head.target() {
-                return Ok(head != self.current_head()?.into());
+                return Ok(head != self.current_head().into());
             }
         }
         Ok(false)
@@ -111,11 +162,18 @@ impl AsyncLog {
         }
 
         self.clear()?;
+        self.walked_head = repo(CWD)?
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(CommitId::new);
 
         let arc_current = Arc::clone(&self.current);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
         let arc_background = Arc::clone(&self.background);
+        let arc_first_parent = Arc::clone(&self.first_parent);
+        let arc_author = Arc::clone(&self.author);
 
         self.pending.store(true, Ordering::Relaxed);
 
@@ -125,6 +183,8 @@ impl AsyncLog {
             AsyncLog::fetch_helper(
                 arc_current,
                 arc_background,
+                arc_first_parent,
+                arc_author,
                 &sender,
             )
             .expect("failed to fetch");
@@ -140,11 +200,17 @@ impl AsyncLog {
     fn fetch_helper(
         arc_current: Arc<Mutex<Vec<CommitId>>>,
         arc_background: Arc<AtomicBool>,
+        arc_first_parent: Arc<AtomicBool>,
+        arc_author: Arc<Mutex<Option<String>>>,
         sender: &Sender<AsyncNotification>,
     ) -> Result<()> {
+        let first_parent = arc_first_parent.load(Ordering::Relaxed);
+        let author = arc_author.lock()?.clone();
         let mut entries = Vec::with_capacity(LIMIT_COUNT);
         let r = repo(CWD)?;
-        let mut walker = LogWalker::new(&r);
+        let mut walker = LogWalker::new(&r)
+            .first_parent(first_parent)
+            .author(author.clone());
         loop {
             entries.clear();
             let res_is_err =
@@ -152,6 +218,15 @@ impl AsyncLog {
 
             if !res_is_err {
                 let mut current = arc_current.lock()?;
+
+                // mode was toggled meanwhile, results are outdated
+                if arc_first_parent.load(Ordering::Relaxed)
+                    != first_parent
+                    || *arc_author.lock()? != author
+                {
+                    break;
+                }
+
                 current.extend(entries.iter());
             }
 
@@ -175,6 +250,7 @@ impl AsyncLog {
 
     fn clear(&mut self) -> Result<()> {
         self.current.lock()?.clear();
+        self.walked_head = None;
         Ok(())
     }
 
@@ -0,0 +1,113 @@
+use crate::{
+    error::Result,
+    sync::{self, AuthorSummary, LineStats},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// fetches the commits per author and, on demand, the lines
+/// changed by a single author (which means diffing all of their
+/// commits)
+pub struct AsyncShortlog {
+    summary: Arc<Mutex<Option<Vec<AuthorSummary>>>>,
+    lines: Arc<Mutex<Option<(String, LineStats)>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncShortlog {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            summary: Arc::new(Mutex::new(None)),
+            lines: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    ///
+    pub fn summary(&self) -> Result<Option<Vec<AuthorSummary>>> {
+        Ok(self.summary.lock()?.clone())
+    }
+
+    /// line stats of `email` if they were fetched already
+    pub fn lines(&self, email: &str) -> Result<Option<LineStats>> {
+        Ok(self
+            .lines
+            .lock()?
+            .as_ref()
+            .filter(|(fetched, _)| fetched == email)
+            .map(|(_, lines)| *lines))
+    }
+
+    /// throws away previous results and fetches the summary
+    pub fn fetch_summary(&mut self) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        *self.summary.lock()? = None;
+        *self.lines.lock()? = None;
+
+        let arc_summary = Arc::clone(&self.summary);
+
+        self.spawn(move || {
+            let res = sync::get_shortlog(CWD)?;
+            *arc_summary.lock()? = Some(res);
+            Ok(())
+        });
+
+        Ok(())
+    }
+
+    /// ignored while another fetch is pending, request again once
+    /// it finished
+    pub fn fetch_lines(&mut self, email: &str) -> Result<()> {
+        if self.is_pending() || self.lines(email)?.is_some() {
+            return Ok(());
+        }
+
+        let arc_lines = Arc::clone(&self.lines);
+        let email = email.to_string();
+
+        self.spawn(move || {
+            let res = sync::get_author_line_stats(CWD, &email)?;
+            *arc_lines.lock()? = Some((email, res));
+            Ok(())
+        });
+
+        Ok(())
+    }
+
+    fn spawn<F>(&mut self, job: F)
+    where
+        F: FnOnce() -> Result<()> + Send + 'static,
+    {
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            if let Err(e) = job() {
+                log::error!("shortlog error: {}", e);
+            }
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Shortlog)
+                .expect("error sending shortlog");
+        });
+    }
+}
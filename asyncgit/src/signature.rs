@@ -0,0 +1,90 @@
+use crate::{
+    error::Result,
+    sync::{self, CommitId, SignatureStatus},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+type Cache = HashMap<CommitId, SignatureStatus>;
+
+/// verifies commit signatures, results are cached per commit
+pub struct AsyncSignature {
+    cache: Arc<Mutex<Cache>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncSignature {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(Cache::new())),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// `None` if `id` was not verified yet
+    pub fn get(
+        &self,
+        id: CommitId,
+    ) -> Result<Option<SignatureStatus>> {
+        Ok(self.cache.lock()?.get(&id).cloned())
+    }
+
+    /// ignored while another verification is pending, request again
+    /// once it finished
+    pub fn fetch(&mut self, id: CommitId) -> Result<()> {
+        if self.is_pending() || self.cache.lock()?.contains_key(&id) {
+            return Ok(());
+        }
+
+        let arc_cache = Arc::clone(&self.cache);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            if let Err(e) = Self::fetch_helper(id, &arc_cache) {
+                log::error!("signature error: {}", e);
+            }
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Signature)
+                .expect("error sending signature");
+        });
+
+        Ok(())
+    }
+
+    fn fetch_helper(
+        id: CommitId,
+        arc_cache: &Arc<Mutex<Cache>>,
+    ) -> Result<()> {
+        // failures are cached too, retrying would fail the same way
+        let status = sync::verify_commit_signature(CWD, id)
+            .unwrap_or_else(|e| {
+                log::error!("signature error: {}", e);
+                SignatureStatus::Unknown(None)
+            });
+
+        arc_cache.lock()?.insert(id, status);
+
+        Ok(())
+    }
+}
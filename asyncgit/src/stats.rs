@@ -0,0 +1,80 @@
+use crate::{
+    error::Result,
+    sync::{self, CommitId, RepoStats},
+    AsyncNotification, CWD,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+/// stats and the `HEAD` they were computed for
+type StatsCache = Option<(Option<CommitId>, RepoStats)>;
+
+/// computes `RepoStats` in the background, the result is kept until
+/// `HEAD` moves
+pub struct AsyncRepoStats {
+    last: Arc<Mutex<StatsCache>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+}
+
+impl AsyncRepoStats {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// latest result, possibly of an older `HEAD`
+    pub fn last(&self) -> Result<Option<RepoStats>> {
+        Ok(self.last.lock()?.as_ref().map(|(_, stats)| stats.clone()))
+    }
+
+    /// does nothing if the cached stats are still up to date
+    pub fn fetch(&mut self) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        let head = sync::get_head(CWD).ok();
+
+        if matches!(&*self.last.lock()?, Some((id, _)) if *id == head)
+        {
+            return Ok(());
+        }
+
+        self.pending.store(true, Ordering::Relaxed);
+
+        let arc_last = Arc::clone(&self.last);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            match sync::get_repo_stats(CWD) {
+                Ok(stats) => match arc_last.lock() {
+                    Ok(mut last) => *last = Some((head, stats)),
+                    Err(e) => log::error!("repo stats error: {}", e),
+                },
+                Err(e) => log::error!("repo stats error: {}", e),
+            }
+
+            arc_pending.store(false, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::Stats)
+                .expect("error sending stats");
+        });
+
+        Ok(())
+    }
+}
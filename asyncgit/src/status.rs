@@ -1,19 +1,31 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, status::StatusType},
+    sync::{
+        self,
+        status::{
+            FsMonitorCache, RenameDetection, ShowUntrackedFiles,
+            StatusType,
+        },
+        FileStat,
+    },
     AsyncNotification, StatusItem, CWD,
 };
 use crossbeam_channel::Sender;
 use std::{
+    collections::HashMap,
     hash::Hash,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
-    time::{SystemTime, UNIX_EPOCH},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// default quiet period before a queued status request is fetched
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
 fn current_tick() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -21,9 +33,11 @@ fn current_tick() -> u64 {
         .as_millis() as u64
 }
 
-#[derive(Default, Hash, Clone)]
+#[derive(Default, Clone)]
 pub struct Status {
     pub items: Vec<StatusItem>,
+    /// by item path, only if asked for via `StatusParams::with_stats`
+    pub stats: HashMap<String, FileStat>,
 }
 
 ///
@@ -31,29 +45,58 @@ pub struct Status {
 pub struct StatusParams {
     tick: u64,
     status_type: StatusType,
-    include_untracked: bool,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    stats: bool,
 }
 
 impl StatusParams {
     ///
     pub fn new(
         status_type: StatusType,
-        include_untracked: bool,
+        show_untracked: ShowUntrackedFiles,
     ) -> Self {
         Self {
             tick: current_tick(),
             status_type,
-            include_untracked,
+            show_untracked,
+            renames: RenameDetection::default(),
+            stats: false,
         }
     }
+
+    ///
+    pub const fn renames(mut self, renames: RenameDetection) -> Self {
+        self.renames = renames;
+        self
+    }
+
+    /// diffs the listed files too, to tell how much each changed
+    pub const fn with_stats(mut self) -> Self {
+        self.stats = true;
+        self
+    }
 }
 
 struct Request<R, A>(R, Option<A>);
 
+/// latest request that arrived while a fetch was running
+struct QueuedRequest {
+    hash: u64,
+    status_type: StatusType,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    stats: bool,
+    requested: Instant,
+}
+
 ///
 pub struct AsyncStatus {
     current: Arc<Mutex<Request<u64, Status>>>,
     last: Arc<Mutex<Status>>,
+    queued: Arc<Mutex<Option<QueuedRequest>>>,
+    fsmonitor: Arc<Mutex<FsMonitorCache>>,
+    debounce: Duration,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicUsize>,
 }
@@ -64,11 +107,23 @@ impl AsyncStatus {
         Self {
             current: Arc::new(Mutex::new(Request(0, None))),
             last: Arc::new(Mutex::new(Status::default())),
+            queued: Arc::new(Mutex::new(None)),
+            fsmonitor: Arc::new(
+                Mutex::new(FsMonitorCache::default()),
+            ),
+            debounce: DEFAULT_DEBOUNCE,
             sender,
             pending: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// requests arriving while a fetch is running are coalesced
+    /// and only fetched once no new one came in for `debounce`
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
     ///
     pub fn last(&mut self) -> Result<Status> {
         let last = self.last.lock()?;
@@ -80,23 +135,19 @@ impl AsyncStatus {
         self.pending.load(Ordering::Relaxed) > 0
     }
 
-    ///
+    /// returns the status if it was already fetched for `params`,
+    /// otherwise a fetch is started (or queued if one is running)
     pub fn fetch(
         &mut self,
         params: StatusParams,
     ) -> Result<Option<Status>> {
-        if self.is_pending() {
-            log::trace!("request blocked, still pending");
-            return Ok(None);
-        }
-
         let hash_request = hash(&params);
 
         log::trace!(
-            "request: [hash: {}] (type: {:?}, untracked: {})",
+            "request: [hash: {}] (type: {:?}, untracked: {:?})",
             hash_request,
             params.status_type,
-            params.include_untracked,
+            params.show_untracked,
         );
 
         {
@@ -110,53 +161,137 @@ impl AsyncStatus {
             current.1 = None;
         }
 
+        {
+            // `pending` is only changed while holding this lock so
+            // the running fetch cannot miss the request
+            let mut queued = self.queued.lock()?;
+
+            *queued = Some(QueuedRequest {
+                hash: hash_request,
+                status_type: params.status_type,
+                show_untracked: params.show_untracked,
+                renames: params.renames,
+                stats: params.stats,
+                requested: Instant::now(),
+            });
+
+            if self.is_pending() {
+                log::trace!("request queued, still pending");
+                return Ok(None);
+            }
+
+            self.pending.fetch_add(1, Ordering::Relaxed);
+        }
+
         let arc_current = Arc::clone(&self.current);
         let arc_last = Arc::clone(&self.last);
+        let arc_queued = Arc::clone(&self.queued);
+        let arc_fsmonitor = Arc::clone(&self.fsmonitor);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
-        let status_type = params.status_type;
-        let include_untracked = params.include_untracked;
-
-        self.pending.fetch_add(1, Ordering::Relaxed);
+        let debounce = self.debounce;
 
         rayon_core::spawn(move || {
             Self::fetch_helper(
-                status_type,
-                include_untracked,
-                hash_request,
-                arc_current,
-                arc_last,
+                debounce,
+                &arc_queued,
+                &arc_fsmonitor,
+                &arc_pending,
+                &arc_current,
+                &arc_last,
+                &sender,
             )
             .expect("failed to fetch status");
+        });
+
+        Ok(None)
+    }
+
+    /// fetches queued requests until none is left, only the first
+    /// one is fetched right away
+    fn fetch_helper(
+        debounce: Duration,
+        arc_queued: &Arc<Mutex<Option<QueuedRequest>>>,
+        arc_fsmonitor: &Arc<Mutex<FsMonitorCache>>,
+        arc_pending: &Arc<AtomicUsize>,
+        arc_current: &Arc<Mutex<Request<u64, Status>>>,
+        arc_last: &Arc<Mutex<Status>>,
+        sender: &Sender<AsyncNotification>,
+    ) -> Result<()> {
+        let mut request = arc_queued.lock()?.take();
+
+        loop {
+            if let Some(request) = &request {
+                Self::fetch_request(
+                    request,
+                    arc_fsmonitor,
+                    arc_current,
+                    arc_last,
+                )?;
+            }
 
-            arc_pending.fetch_sub(1, Ordering::Relaxed);
+            let done = {
+                let queued = arc_queued.lock()?;
+                if queued.is_none() {
+                    arc_pending.fetch_sub(1, Ordering::Relaxed);
+                }
+                queued.is_none()
+            };
 
             sender
                 .send(AsyncNotification::Status)
                 .expect("error sending status");
-        });
 
-        Ok(None)
+            if done {
+                return Ok(());
+            }
+
+            request = Self::wait_for_quiet(debounce, arc_queued)?;
+        }
     }
 
-    fn fetch_helper(
-        status_type: StatusType,
-        include_untracked: bool,
-        hash_request: u64,
-        arc_current: Arc<Mutex<Request<u64, Status>>>,
-        arc_last: Arc<Mutex<Status>>,
+    /// takes the queued request once no newer one arrived for
+    /// `debounce`
+    fn wait_for_quiet(
+        debounce: Duration,
+        arc_queued: &Arc<Mutex<Option<QueuedRequest>>>,
+    ) -> Result<Option<QueuedRequest>> {
+        loop {
+            let quiet_for = {
+                let mut queued = arc_queued.lock()?;
+                let quiet_for = queued
+                    .as_ref()
+                    .map_or(debounce, |r| r.requested.elapsed());
+
+                if quiet_for >= debounce {
+                    return Ok(queued.take());
+                }
+
+                quiet_for
+            };
+
+            thread::sleep(debounce - quiet_for);
+        }
+    }
+
+    fn fetch_request(
+        request: &QueuedRequest,
+        arc_fsmonitor: &Arc<Mutex<FsMonitorCache>>,
+        arc_current: &Arc<Mutex<Request<u64, Status>>>,
+        arc_last: &Arc<Mutex<Status>>,
     ) -> Result<()> {
-        let res = Self::get_status(status_type, include_untracked)?;
+        let res =
+            Self::get_status(request, &mut *arc_fsmonitor.lock()?)?;
         log::trace!(
-            "status fetched: {} (type: {:?}, untracked: {})",
-            hash_request,
-            status_type,
-            include_untracked
+            "status fetched: {} (type: {:?}, untracked: {:?})",
+            request.hash,
+            request.status_type,
+            request.show_untracked
         );
 
         {
             let mut current = arc_current.lock()?;
-            if current.0 == hash_request {
+            if current.0 == request.hash {
                 current.1 = Some(res.clone());
             }
         }
@@ -170,15 +305,28 @@ impl AsyncStatus {
     }
 
     fn get_status(
-        status_type: StatusType,
-        include_untracked: bool,
+        request: &QueuedRequest,
+        fsmonitor: &mut FsMonitorCache,
     ) -> Result<Status> {
-        Ok(Status {
-            items: sync::status::get_status(
+        let items = sync::status::get_status_fsmonitor(
+            CWD,
+            request.status_type,
+            request.show_untracked,
+            request.renames,
+            fsmonitor,
+        )?;
+
+        let stats = if request.stats {
+            sync::status::get_status_stats(
                 CWD,
-                status_type,
-                include_untracked,
-            )?,
-        })
+                request.status_type,
+                request.renames,
+                &items,
+            )?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Status { items, stats })
     }
 }
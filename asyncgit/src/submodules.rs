@@ -0,0 +1,74 @@
+use crate::{error::Result, sync, AsyncNotification, CWD};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+/// outcome of a submodule update, the error as text
+type SubmoduleUpdateResult = Option<(String, Option<String>)>;
+
+/// updates submodules in the background, cloning them can take a
+/// long time on slow networks
+pub struct AsyncSubmoduleUpdate {
+    last: Arc<Mutex<SubmoduleUpdateResult>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicBool>,
+}
+
+impl AsyncSubmoduleUpdate {
+    ///
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// name of the updated submodule and its error (`None` if it
+    /// succeeded), can only be taken once
+    pub fn take_last(&mut self) -> Result<SubmoduleUpdateResult> {
+        Ok(self.last.lock()?.take())
+    }
+
+    /// does nothing while another submodule is updated
+    pub fn request(&mut self, name: String) -> Result<()> {
+        if self.is_pending() {
+            return Ok(());
+        }
+
+        *self.last.lock()? = None;
+        self.pending.store(true, Ordering::Relaxed);
+
+        let arc_last = Arc::clone(&self.last);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        rayon_core::spawn(move || {
+            let res = sync::submodule_update(CWD, &name)
+                .err()
+                .map(|e| e.to_string());
+
+            match arc_last.lock() {
+                Ok(mut last) => *last = Some((name, res)),
+                Err(e) => {
+                    log::error!("submodule update error: {}", e);
+                }
+            }
+
+            arc_pending.store(false, Ordering::Relaxed);
+
+            sender
+                .send(AsyncNotification::SubmoduleUpdate)
+                .expect("error sending submodule update");
+        });
+
+        Ok(())
+    }
+}
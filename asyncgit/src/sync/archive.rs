@@ -0,0 +1,129 @@
+//! snapshots of a tree written via `git archive`
+
+use super::utils::repo;
+use crate::error::{Error, Result};
+use scopetime::scope_time;
+use std::{path::PathBuf, process::Command};
+
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ArchiveFormat {
+    ///
+    Zip,
+    ///
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// name `git archive --format` expects, also the file extension
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Zip => "zip",
+            Self::TarGz => "tar.gz",
+        }
+    }
+}
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveOptions {
+    /// commit, tag or tree to archive, like `HEAD`
+    pub treeish: String,
+    ///
+    pub format: ArchiveFormat,
+    /// put in front of every path in the archive, like `project/`
+    pub prefix: String,
+    /// only archive this directory (relative to the repo root)
+    pub path: Option<String>,
+    /// file to write to
+    pub output: PathBuf,
+}
+
+/// writes the archive described by `options`
+pub fn archive(
+    repo_path: &str,
+    options: &ArchiveOptions,
+) -> Result<()> {
+    scope_time!("archive");
+
+    let repo = repo(repo_path)?;
+    let work_dir = repo.workdir().unwrap_or_else(|| repo.path());
+
+    let mut cmd = Command::new("git");
+    cmd.arg("archive")
+        .arg(format!("--format={}", options.format.name()))
+        .arg(format!("--prefix={}", options.prefix))
+        .arg("-o")
+        .arg(&options.output)
+        .arg(&options.treeish)
+        .current_dir(work_dir);
+
+    if let Some(path) = &options.path {
+        cmd.arg("--").arg(path);
+    }
+
+    let output = cmd.output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Generic(format!(
+            "git archive failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{
+        fs::{self, File},
+        io::{Read, Write},
+        path::Path,
+    };
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::create_dir(root.join("sub"))?;
+        File::create(root.join("sub/foo"))?.write_all(b"foo")?;
+        File::create(root.join("bar"))?.write_all(b"bar")?;
+        stage_add_file(repo_path, Path::new("sub/foo"))?;
+        stage_add_file(repo_path, Path::new("bar"))?;
+        commit(repo_path, "files")?;
+
+        let out = TempDir::new()?;
+        let mut options = ArchiveOptions {
+            treeish: String::from("HEAD"),
+            format: ArchiveFormat::Zip,
+            prefix: String::from("project/"),
+            path: Some(String::from("sub")),
+            output: out.path().join("project.zip"),
+        };
+
+        archive(repo_path, &options)?;
+
+        let mut zip = Vec::new();
+        File::open(&options.output)?.read_to_end(&mut zip)?;
+        assert!(zip.starts_with(b"PK"));
+        let zip = String::from_utf8_lossy(&zip);
+        assert!(zip.contains("project/sub/foo"));
+        assert!(!zip.contains("project/bar"));
+
+        options.format = ArchiveFormat::TarGz;
+        options.output = out.path().join("project.tar.gz");
+        archive(repo_path, &options)?;
+        assert!(options.output.is_file());
+
+        options.treeish = String::from("no-such-rev");
+        assert!(archive(repo_path, &options).is_err());
+
+        Ok(())
+    }
+}
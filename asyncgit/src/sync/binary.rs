@@ -0,0 +1,279 @@
+//! what a diff can show of binary files: the first bytes and, for
+//! common image formats, the dimensions and the picture itself
+
+use std::convert::TryFrom;
+
+/// bytes of each side kept for a hex dump
+pub const BINARY_HEAD_LEN: usize = 256;
+/// larger pictures are not kept for a preview
+pub const IMAGE_PREVIEW_LIMIT: usize = 4 * 1024 * 1024;
+
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    ///
+    Png,
+    ///
+    Gif,
+    ///
+    Jpeg,
+    ///
+    Bmp,
+    ///
+    Webp,
+}
+
+impl ImageFormat {
+    ///
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Gif => "gif",
+            Self::Jpeg => "jpeg",
+            Self::Bmp => "bmp",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+/// format and size in pixels read off the header of a picture
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ImageInfo {
+    ///
+    pub format: ImageFormat,
+    ///
+    pub width: u32,
+    ///
+    pub height: u32,
+}
+
+/// one side of a binary diff
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BinarySide {
+    /// the first `BINARY_HEAD_LEN` bytes
+    pub head: Vec<u8>,
+    /// set if the content is a picture
+    pub image: Option<ImageInfo>,
+}
+
+impl BinarySide {
+    fn new(content: &[u8]) -> Self {
+        Self {
+            head: content[..content.len().min(BINARY_HEAD_LEN)]
+                .to_vec(),
+            image: image_info(content),
+        }
+    }
+}
+
+/// old and new side of a binary diff, `None` for a side that does not
+/// exist (added or deleted file) or could not be read
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct BinaryPreview {
+    ///
+    pub old: Option<BinarySide>,
+    ///
+    pub new: Option<BinarySide>,
+    /// the new picture (or the old one of a deleted file) if it is
+    /// not larger than `IMAGE_PREVIEW_LIMIT`
+    pub image: Option<Vec<u8>>,
+}
+
+impl BinaryPreview {
+    pub(crate) fn new(
+        old: Option<&[u8]>,
+        new: Option<&[u8]>,
+    ) -> Self {
+        let old_side = old.map(BinarySide::new);
+        let new_side = new.map(BinarySide::new);
+
+        let image = match (&new_side, new) {
+            (Some(side), Some(content)) => {
+                side.image.map(|_| content)
+            }
+            _ => {
+                old_side.as_ref().and_then(|side| side.image).and(old)
+            }
+        }
+        .filter(|content| content.len() <= IMAGE_PREVIEW_LIMIT)
+        .map(<[u8]>::to_vec);
+
+        Self {
+            old: old_side,
+            new: new_side,
+            image,
+        }
+    }
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 2)?;
+    Some(u32::from(u16::from_be_bytes([b[0], b[1]])))
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 2)?;
+    Some(u32::from(u16::from_le_bytes([b[0], b[1]])))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// 24 bit little endian plus one, as webp stores sizes
+fn u24_le_plus_one(bytes: &[u8], at: usize) -> Option<u32> {
+    let b = bytes.get(at..at + 3)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], 0]) + 1)
+}
+
+/// the size is in the first start of frame segment
+fn jpeg_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        if *bytes.get(pos)? != 0xff {
+            return None;
+        }
+        let marker = *bytes.get(pos + 1)?;
+        let len = usize::try_from(u16_be(bytes, pos + 2)?).ok()?;
+
+        // SOF0 to SOF15 without DHT, JPG and DAC
+        if (0xc0..=0xcf).contains(&marker)
+            && !matches!(marker, 0xc4 | 0xc8 | 0xcc)
+        {
+            return Some((
+                u16_be(bytes, pos + 7)?,
+                u16_be(bytes, pos + 5)?,
+            ));
+        }
+
+        pos += 2 + len;
+    }
+}
+
+fn webp_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    match bytes.get(12..16)? {
+        b"VP8 " => Some((
+            u16_le(bytes, 26)? & 0x3fff,
+            u16_le(bytes, 28)? & 0x3fff,
+        )),
+        b"VP8L" => {
+            let bits = u32_le(bytes, 21)?;
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        b"VP8X" => Some((
+            u24_le_plus_one(bytes, 24)?,
+            u24_le_plus_one(bytes, 27)?,
+        )),
+        _ => None,
+    }
+}
+
+/// format and dimensions if `content` starts like a png, gif, jpeg,
+/// bmp or webp picture
+pub fn image_info(content: &[u8]) -> Option<ImageInfo> {
+    let (format, (width, height)) = if content
+        .starts_with(b"\x89PNG\r\n\x1a\n")
+    {
+        (
+            ImageFormat::Png,
+            (u32_be(content, 16)?, u32_be(content, 20)?),
+        )
+    } else if content.starts_with(b"GIF87a")
+        || content.starts_with(b"GIF89a")
+    {
+        (ImageFormat::Gif, (u16_le(content, 6)?, u16_le(content, 8)?))
+    } else if content.starts_with(b"\xff\xd8") {
+        (ImageFormat::Jpeg, jpeg_size(content)?)
+    } else if content.starts_with(b"BM") {
+        // negative heights are top-down bitmaps
+        let height =
+            i32::from_le_bytes(u32_le(content, 22)?.to_le_bytes());
+        (
+            ImageFormat::Bmp,
+            (u32_le(content, 18)?, height.unsigned_abs()),
+        )
+    } else if content.starts_with(b"RIFF")
+        && content.get(8..12) == Some(b"WEBP")
+    {
+        (ImageFormat::Webp, webp_size(content)?)
+    } else {
+        return None;
+    };
+
+    Some(ImageInfo {
+        format,
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_info() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&640_u32.to_be_bytes());
+        png.extend_from_slice(&480_u32.to_be_bytes());
+        assert_eq!(
+            image_info(&png),
+            Some(ImageInfo {
+                format: ImageFormat::Png,
+                width: 640,
+                height: 480
+            })
+        );
+
+        let gif = b"GIF89a\x20\x00\x10\x00";
+        assert_eq!(
+            image_info(gif).map(|i| (i.width, i.height)),
+            Some((32, 16))
+        );
+
+        // SOI, an APP0 segment, SOF0 of 100 rows and 200 columns
+        let jpeg = b"\xff\xd8\xff\xe0\x00\x04ab\xff\xc0\x00\x11\x08\x00\x64\x00\xc8";
+        assert_eq!(
+            image_info(jpeg).map(|i| (i.format, i.width, i.height)),
+            Some((ImageFormat::Jpeg, 200, 100))
+        );
+
+        let mut bmp = b"BM".to_vec();
+        bmp.resize(18, 0);
+        bmp.extend_from_slice(&3_u32.to_le_bytes());
+        bmp.extend_from_slice(&(-2_i32).to_le_bytes());
+        assert_eq!(
+            image_info(&bmp).map(|i| (i.width, i.height)),
+            Some((3, 2))
+        );
+
+        assert_eq!(image_info(b"\x00\x01binary"), None);
+        assert_eq!(image_info(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    #[test]
+    fn test_binary_preview() {
+        let gif = b"GIF89a\x01\x00\x01\x00";
+        let data = vec![0_u8; 1000];
+
+        let preview = BinaryPreview::new(Some(&data), Some(gif));
+        assert_eq!(preview.old.unwrap().head.len(), BINARY_HEAD_LEN);
+        assert_eq!(preview.image.as_deref(), Some(&gif[..]));
+
+        // deleted picture
+        let preview = BinaryPreview::new(Some(gif), None);
+        assert!(preview.new.is_none());
+        assert_eq!(preview.image.as_deref(), Some(&gif[..]));
+
+        assert_eq!(
+            BinaryPreview::new(Some(gif), Some(&data)).image,
+            None
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! bisecting using git's own `BISECT_START` and `refs/bisect/*`
+//! so a session can be continued on the command line
+
+use super::{
+    hooks::hooks_post_checkout,
+    utils::{get_head_repo, repo},
+    CommitId, HookResult,
+};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, Oid, Repository, Sort};
+use scopetime::scope_time;
+use std::{fs, path::PathBuf, time::Duration};
+
+const BISECT_START: &str = "BISECT_START";
+const REF_BAD: &str = "refs/bisect/bad";
+const REF_GOOD_PREFIX: &str = "refs/bisect/good-";
+
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BisectMark {
+    ///
+    Good,
+    ///
+    Bad,
+}
+
+///
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BisectState {
+    ///
+    pub bad: Option<CommitId>,
+    ///
+    pub good: Vec<CommitId>,
+    /// commits that can still be the first bad one
+    /// (only known once a good and a bad commit are marked)
+    pub remaining: usize,
+    /// rough estimate of steps left
+    pub steps: usize,
+    /// commit to test next
+    pub next: Option<CommitId>,
+}
+
+impl BisectState {
+    /// the first bad commit once the range is narrowed down to it
+    pub fn first_bad(&self) -> Option<CommitId> {
+        if self.remaining == 1 {
+            self.bad
+        } else {
+            None
+        }
+    }
+}
+
+/// returns `None` if no bisect is in progress
+pub fn bisect_state(repo_path: &str) -> Result<Option<BisectState>> {
+    scope_time!("bisect_state");
+
+    let repo = repo(repo_path)?;
+
+    if !start_file(&repo).exists() {
+        return Ok(None);
+    }
+
+    Ok(Some(get_state(&repo)?))
+}
+
+/// marks `id` (starting a bisect if needed) and checks out the
+/// next commit to test, returns the `post-checkout` hook result
+/// along the new state
+pub fn bisect_mark(
+    repo_path: &str,
+    id: CommitId,
+    mark: BisectMark,
+    hooks_timeout: Option<Duration>,
+) -> Result<(BisectState, HookResult)> {
+    scope_time!("bisect_mark");
+
+    let repo = repo(repo_path)?;
+
+    if !start_file(&repo).exists() {
+        start(&repo)?;
+    }
+
+    let refname = match mark {
+        BisectMark::Bad => String::from(REF_BAD),
+        BisectMark::Good => {
+            format!("{}{}", REF_GOOD_PREFIX, id.to_string())
+        }
+    };
+    repo.reference(&refname, id.into(), true, "bisect mark")?;
+
+    let state = get_state(&repo)?;
+
+    let hook = if let Some(next) = state.next {
+        let prev = get_head_repo(&repo)?;
+        checkout_detached(&repo, next.into())?;
+        hooks_post_checkout(
+            repo_path,
+            prev,
+            next,
+            true,
+            hooks_timeout,
+        )?
+    } else {
+        HookResult::NoHook
+    };
+
+    Ok((state, hook))
+}
+
+/// ends bisecting and returns to the original `HEAD`, returns the
+/// `post-checkout` hook result
+pub fn bisect_reset(
+    repo_path: &str,
+    hooks_timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("bisect_reset");
+
+    let repo = repo(repo_path)?;
+
+    let start_file = start_file(&repo);
+    if !start_file.exists() {
+        return Err(Error::Generic(String::from(
+            "no bisect in progress",
+        )));
+    }
+
+    let original = fs::read_to_string(&start_file)?;
+    let original = original.trim();
+    let prev = get_head_repo(&repo)?;
+    let branch_ref = format!("refs/heads/{}", original);
+
+    if let Ok(branch) = repo.find_reference(&branch_ref) {
+        let commit = branch.peel_to_commit()?;
+        repo.checkout_tree(
+            commit.as_object(),
+            Some(CheckoutBuilder::new().safe()),
+        )?;
+        repo.set_head(&branch_ref)?;
+    } else {
+        checkout_detached(&repo, Oid::from_str(original)?)?;
+    }
+
+    for reference in repo.references_glob("refs/bisect/*")? {
+        reference?.delete()?;
+    }
+    fs::remove_file(start_file)?;
+
+    hooks_post_checkout(
+        repo_path,
+        prev,
+        get_head_repo(&repo)?,
+        true,
+        hooks_timeout,
+    )
+}
+
+fn start_file(repo: &Repository) -> PathBuf {
+    repo.path().join(BISECT_START)
+}
+
+fn start(repo: &Repository) -> Result<()> {
+    let head = repo.head()?;
+
+    let original = if head.is_branch() {
+        head.shorthand().map(String::from)
+    } else {
+        head.target().map(|id| id.to_string())
+    }
+    .ok_or_else(|| Error::Generic(String::from("invalid HEAD")))?;
+
+    fs::write(start_file(repo), format!("{}\n", original))?;
+
+    Ok(())
+}
+
+fn get_state(repo: &Repository) -> Result<BisectState> {
+    let bad = repo
+        .find_reference(REF_BAD)
+        .ok()
+        .and_then(|r| r.target())
+        .map(CommitId::new);
+
+    let mut good = Vec::new();
+    for reference in
+        repo.references_glob(&format!("{}*", REF_GOOD_PREFIX))?
+    {
+        if let Some(id) = reference?.target() {
+            good.push(CommitId::new(id));
+        }
+    }
+
+    let mut state = BisectState {
+        bad,
+        good,
+        ..BisectState::default()
+    };
+
+    if let Some(bad) = state.bad {
+        if !state.good.is_empty() {
+            let candidates = candidates(repo, bad, &state.good)?;
+
+            state.remaining = candidates.len();
+            state.steps = log2(state.remaining);
+            if state.remaining > 1 {
+                state.next = Some(candidates[state.remaining / 2]);
+            }
+        }
+    }
+
+    Ok(state)
+}
+
+/// commits reachable from `bad` but not from any `good` one,
+/// newest first
+fn candidates(
+    repo: &Repository,
+    bad: CommitId,
+    good: &[CommitId],
+) -> Result<Vec<CommitId>> {
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL)?;
+    walk.push(bad.into())?;
+    for id in good {
+        walk.hide((*id).into())?;
+    }
+
+    let mut res = Vec::new();
+    for id in walk {
+        res.push(CommitId::new(id?));
+    }
+
+    Ok(res)
+}
+
+fn checkout_detached(repo: &Repository, id: Oid) -> Result<()> {
+    let commit = repo.find_commit(id)?;
+    repo.checkout_tree(
+        commit.as_object(),
+        Some(CheckoutBuilder::new().safe()),
+    )?;
+    repo.set_head_detached(id)?;
+
+    Ok(())
+}
+
+const fn log2(value: usize) -> usize {
+    let mut res = 0;
+    let mut value = value;
+    while value > 1 {
+        value >>= 1;
+        res += 1;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, get_head, stage_add_file, tests::repo_init_empty,
+    };
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_bisect() -> Result<()> {
+        let file_path = Path::new("foo");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let mut ids = Vec::new();
+        for i in 0..8 {
+            File::create(&root.join(file_path))?
+                .write_all(format!("{}", i).as_bytes())?;
+            stage_add_file(repo_path, file_path)?;
+            ids.push(commit(repo_path, &format!("c{}", i))?);
+        }
+
+        assert_eq!(bisect_state(repo_path)?, None);
+
+        let (state, _) =
+            bisect_mark(repo_path, ids[7], BisectMark::Bad, None)?;
+        assert_eq!(state.next, None);
+
+        let (state, _) =
+            bisect_mark(repo_path, ids[0], BisectMark::Good, None)?;
+        assert_eq!(state.remaining, 7);
+        assert_eq!(state.next, Some(ids[4]));
+        assert_eq!(get_head(repo_path)?, ids[4]);
+        assert_eq!(fs::read_to_string(root.join(file_path))?, "4");
+
+        bisect_mark(repo_path, ids[4], BisectMark::Good, None)?;
+        assert_eq!(get_head(repo_path)?, ids[6]);
+
+        bisect_mark(repo_path, ids[6], BisectMark::Bad, None)?;
+        assert_eq!(get_head(repo_path)?, ids[5]);
+
+        let (state, _) =
+            bisect_mark(repo_path, ids[5], BisectMark::Bad, None)?;
+        assert_eq!(state.first_bad(), Some(ids[5]));
+        assert_eq!(bisect_state(repo_path)?, Some(state));
+
+        bisect_reset(repo_path, None)?;
+
+        assert_eq!(bisect_state(repo_path)?, None);
+        assert_eq!(get_head(repo_path)?, ids[7]);
+        assert!(repo.head()?.is_branch());
+        assert_eq!(fs::read_to_string(root.join(file_path))?, "7");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_without_bisect() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(bisect_reset(repo_path, None).is_err());
+    }
+}
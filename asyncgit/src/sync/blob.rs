@@ -0,0 +1,129 @@
+//! raw file content at a given commit
+
+use super::{utils::repo, CommitId};
+use crate::error::{Error, Result};
+use git2::{AttrCheckFlags, Repository};
+use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// how many leading bytes `is_binary` looks at (same as git)
+const BINARY_CHECK_LEN: usize = 8000;
+/// what libgit2 returns for set (`diff`) and unset (`-diff`)
+/// attributes
+const ATTR_TRUE: &str = "[internal]__TRUE__";
+const ATTR_FALSE: &str = "[internal]__FALSE__";
+
+/// content is considered binary if it contains a null byte in its
+/// first 8000 bytes, the heuristic git uses to decide whether to diff
+pub fn is_binary(content: &[u8]) -> bool {
+    content.iter().take(BINARY_CHECK_LEN).any(|b| *b == 0)
+}
+
+/// whether `.gitattributes` declare `path` (relative to the workdir)
+/// binary (`binary` or `-diff`) or text (`diff`), `None` if they do
+/// not say and the content has to decide
+pub(crate) fn binary_attr(
+    repo: &Repository,
+    path: &Path,
+) -> Result<Option<bool>> {
+    let attr =
+        repo.get_attr(path, "diff", AttrCheckFlags::FILE_THEN_INDEX)?;
+
+    Ok(match attr {
+        Some(ATTR_FALSE) => Some(true),
+        Some(ATTR_TRUE) => Some(false),
+        _ => None,
+    })
+}
+
+/// raw (possibly binary) content of `path` as of commit `id`
+pub fn get_file_at_commit(
+    repo_path: &str,
+    id: CommitId,
+    path: &str,
+) -> Result<Vec<u8>> {
+    scope_time!("get_file_at_commit");
+
+    let repo = repo(repo_path)?;
+    let tree = repo.find_commit(id.into())?.tree()?;
+    let entry = tree.get_path(Path::new(path))?;
+
+    let blob = entry.to_object(&repo)?.into_blob().map_err(|_| {
+        Error::Generic(format!("'{}' is not a file", path))
+    })?;
+
+    Ok(blob.content().to_vec())
+}
+
+/// writes the content of `path` as of commit `id` verbatim to `dest`,
+/// overwriting it if it exists
+pub fn save_file_at_commit(
+    repo_path: &str,
+    id: CommitId,
+    path: &str,
+    dest: &Path,
+) -> Result<()> {
+    scope_time!("save_file_at_commit");
+
+    let content = get_file_at_commit(repo_path, id, path)?;
+    fs::write(dest, content)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{commit, stage_add_file, tests::repo_init};
+    use std::{fs::File, io::Write};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_binary() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR";
+        let elf = b"\x7fELF\x02\x01\x01\x00\x00\x00";
+
+        assert!(is_binary(png));
+        assert!(is_binary(elf));
+        assert!(!is_binary(b""));
+        assert!(!is_binary(b"fn main() {}\n"));
+        assert!(!is_binary("gr\u{fc}\u{df}e \u{1f600}\n".as_bytes()));
+
+        let mut late_null = vec![b'a'; BINARY_CHECK_LEN];
+        late_null.push(0);
+        assert!(!is_binary(&late_null));
+    }
+
+    #[test]
+    fn test_save_binary_file() -> Result<()> {
+        let file_path = Path::new("foo.bin");
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let old = [0_u8, 159, 146, 150, b'\r', b'\n', 0];
+        File::create(root.join(file_path))?.write_all(&old)?;
+        stage_add_file(repo_path, file_path)?;
+        let id = commit(repo_path, "c1")?;
+
+        File::create(root.join(file_path))?.write_all(b"new")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "c2")?;
+
+        assert_eq!(
+            get_file_at_commit(repo_path, id, "foo.bin")?,
+            old
+        );
+
+        let out = TempDir::new()?;
+        let dest = out.path().join("out.bin");
+        fs::write(&dest, b"overwritten")?;
+
+        save_file_at_commit(repo_path, id, "foo.bin", &dest)?;
+        assert_eq!(fs::read(&dest)?, old);
+
+        assert!(get_file_at_commit(repo_path, id, "missing").is_err());
+
+        Ok(())
+    }
+}
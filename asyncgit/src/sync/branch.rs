@@ -2,13 +2,17 @@
 
 use crate::{
     error::{Error, Result},
-    sync::utils,
+    sync::{utils, CommitId},
+};
+use git2::{
+    Branch, BranchType, DescribeFormatOptions, DescribeOptions,
+    ErrorCode,
 };
 use scopetime::scope_time;
 
 /// returns the branch-name head is currently pointing to
 /// this might be expensive, see `cached::BranchName`
-pub(crate) fn get_branch_name(repo_path: &str) -> Result<String> {
+pub fn get_branch_name(repo_path: &str) -> Result<String> {
     scope_time!("get_branch_name");
 
     let repo = utils::repo(repo_path)?;
@@ -27,10 +31,120 @@ pub(crate) fn get_branch_name(repo_path: &str) -> Result<String> {
     Err(Error::NoHead)
 }
 
+/// head pointing to a commit instead of a branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetachedHead {
+    ///
+    pub id: CommitId,
+    /// nearest tag reachable from the commit
+    pub tag: Option<String>,
+}
+
+/// `None` if head points to a branch (or nowhere yet)
+pub fn get_detached_head(
+    repo_path: &str,
+) -> Result<Option<DetachedHead>> {
+    scope_time!("get_detached_head");
+
+    let repo = utils::repo(repo_path)?;
+
+    if !repo.head_detached()? {
+        return Ok(None);
+    }
+
+    let id = utils::get_head_repo(&repo)?;
+    let tag = repo
+        .describe(DescribeOptions::new().describe_tags())
+        .and_then(|describe| {
+            describe.format(Some(
+                DescribeFormatOptions::new().abbreviated_size(0),
+            ))
+        })
+        .ok();
+
+    Ok(Some(DetachedHead { id, tag }))
+}
+
+/// commits the current branch is ahead/behind of its upstream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchCompare {
+    ///
+    pub ahead: usize,
+    ///
+    pub behind: usize,
+}
+
+/// compares the branch head points to with its upstream,
+/// `None` if head is detached or the branch tracks no upstream
+pub fn branch_compare_upstream(
+    repo_path: &str,
+) -> Result<Option<BranchCompare>> {
+    scope_time!("branch_compare_upstream");
+
+    let repo = utils::repo(repo_path)?;
+
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(None);
+    }
+
+    let upstream = match Branch::wrap(head).upstream() {
+        Ok(upstream) => upstream,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let head_id = repo.head()?.peel_to_commit()?.id();
+    let upstream_id = upstream.get().peel_to_commit()?.id();
+
+    let (ahead, behind) =
+        repo.graph_ahead_behind(head_id, upstream_id)?;
+
+    Ok(Some(BranchCompare { ahead, behind }))
+}
+
+/// creates the branch `name` at commit `id` without checking it out,
+/// fails if a branch of that name exists already
+pub fn create_branch(
+    repo_path: &str,
+    name: &str,
+    id: CommitId,
+) -> Result<()> {
+    scope_time!("create_branch");
+
+    let repo = utils::repo(repo_path)?;
+
+    let commit = repo.find_commit(id.into())?;
+    repo.branch(name, &commit, false)?;
+
+    Ok(())
+}
+
+/// names of all local branches, sorted
+pub fn get_local_branches(repo_path: &str) -> Result<Vec<String>> {
+    scope_time!("get_local_branches");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut names = Vec::new();
+    for b in repo.branches(Some(BranchType::Local))? {
+        if let Some(name) = b?.0.name()? {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::{repo_init, repo_init_empty};
+    use crate::sync::{
+        commit, stage_add_file,
+        tests::{repo_init, repo_init_empty},
+    };
+    use std::{fs::File, io::Write, path::Path};
 
     #[test]
     fn test_smoke() {
@@ -44,6 +158,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_detached_head() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(get_detached_head(repo_path).unwrap(), None);
+
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+
+        assert_eq!(
+            get_detached_head(repo_path).unwrap(),
+            Some(DetachedHead {
+                id: head.into(),
+                tag: None
+            })
+        );
+
+        let sig = repo.signature().unwrap();
+        repo.tag(
+            "v1.0",
+            &repo.find_object(head, None).unwrap(),
+            &sig,
+            "",
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_detached_head(repo_path).unwrap().unwrap().tag,
+            Some(String::from("v1.0"))
+        );
+    }
+
     #[test]
     fn test_empty_repo() {
         let (_td, repo) = repo_init_empty().unwrap();
@@ -55,4 +204,62 @@ mod tests {
             Err(Error::NoHead)
         ));
     }
+
+    #[test]
+    fn test_create_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let head = repo.head().unwrap().target().unwrap();
+
+        create_branch(repo_path, "feature", head.into()).unwrap();
+
+        assert_eq!(
+            repo.find_branch("feature", git2::BranchType::Local)
+                .unwrap()
+                .get()
+                .target(),
+            Some(head)
+        );
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "master"
+        );
+        assert!(
+            create_branch(repo_path, "feature", head.into()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_compare_upstream() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(branch_compare_upstream(repo_path).unwrap(), None);
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("upstream", &head, false).unwrap();
+        repo.find_branch("master", git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("upstream"))
+            .unwrap();
+
+        let file_path = Path::new("foo");
+        File::create(&root.join(file_path))
+            .unwrap()
+            .write_all(b"a")
+            .unwrap();
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "ahead").unwrap();
+
+        assert_eq!(
+            branch_compare_upstream(repo_path).unwrap(),
+            Some(BranchCompare {
+                ahead: 1,
+                behind: 0
+            })
+        );
+    }
 }
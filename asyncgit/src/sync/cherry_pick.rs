@@ -0,0 +1,81 @@
+//! applying commits of elsewhere onto head via `git cherry-pick`, so
+//! a stop on conflicts can be dealt with like on the command line
+
+use super::{patches::run_git, CommitId};
+use crate::error::Result;
+use scopetime::scope_time;
+
+/// applies `commits` in the given order (oldest first for a range),
+/// on conflicts git stops and waits for `operation_action`
+pub fn cherry_pick(
+    repo_path: &str,
+    commits: &[CommitId],
+) -> Result<()> {
+    scope_time!("cherry_pick");
+
+    let ids: Vec<String> =
+        commits.iter().map(CommitId::to_string).collect();
+
+    let mut args = vec!["cherry-pick"];
+    args.extend(ids.iter().map(String::as_str));
+
+    run_git(repo_path, &args)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, operation_action, operation_state, stage_add_file,
+        tests::repo_init, OperationAction, RepoOperation,
+    };
+    use std::{fs, path::Path};
+
+    #[test]
+    fn test_cherry_pick() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let initial = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let file_path = Path::new("foo");
+        fs::write(root.join(file_path), "a\n").unwrap();
+        stage_add_file(repo_path, file_path).unwrap();
+        let first = commit(repo_path, "add foo").unwrap();
+        fs::write(root.join(file_path), "b\n").unwrap();
+        stage_add_file(repo_path, file_path).unwrap();
+        let second = commit(repo_path, "change foo").unwrap();
+
+        repo.reset(initial.as_object(), git2::ResetType::Hard, None)
+            .unwrap();
+
+        // `change foo` alone does not apply, it conflicts
+        assert!(cherry_pick(repo_path, &[second]).is_err());
+        assert_eq!(
+            operation_state(repo_path).unwrap().unwrap().operation,
+            RepoOperation::CherryPick
+        );
+
+        operation_action(
+            repo_path,
+            RepoOperation::CherryPick,
+            OperationAction::Abort,
+        )
+        .unwrap();
+
+        cherry_pick(repo_path, &[first, second]).unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("change foo"));
+        assert_eq!(
+            head.parent(0).unwrap().summary(),
+            Some("add foo")
+        );
+        assert_eq!(
+            fs::read_to_string(root.join(file_path)).unwrap(),
+            "b\n"
+        );
+    }
+}
@@ -1,13 +1,61 @@
-use super::{get_head, utils::repo, CommitId};
-use crate::error::Result;
-use git2::{ErrorCode, ObjectType, Repository, Signature};
+use super::{
+    get_head,
+    merge::{clear_squash_msg, merge_heads},
+    utils::repo,
+    CommitId,
+};
+use crate::error::{Error, Result};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone,
+};
+use git2::{
+    ErrorCode, ObjectType, Repository, RepositoryState, Signature,
+    Time,
+};
 use scopetime::scope_time;
 
+/// who and when of a commit instead of the configured identity and
+/// now, the committer stays the configured one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorOverride {
+    /// `Name <email>` like `git commit --author`
+    pub author: Option<String>,
+    /// `YYYY-MM-DD[ HH:MM[:SS]][ +HHMM]`, RFC 3339 or the raw
+    /// `<seconds> <+HHMM>` of git, local time without an offset
+    pub date: Option<String>,
+}
+
+impl AuthorOverride {
+    /// nothing to override
+    pub const fn is_empty(&self) -> bool {
+        self.author.is_none() && self.date.is_none()
+    }
+}
+
 ///
 pub fn amend(
     repo_path: &str,
     id: CommitId,
     msg: &str,
+) -> Result<CommitId> {
+    amend_with(repo_path, id, msg, None)
+}
+
+/// like `amend`, replacing the author of the commit
+pub fn amend_as(
+    repo_path: &str,
+    id: CommitId,
+    msg: &str,
+    author: &Signature,
+) -> Result<CommitId> {
+    amend_with(repo_path, id, msg, Some(author))
+}
+
+fn amend_with(
+    repo_path: &str,
+    id: CommitId,
+    msg: &str,
+    author: Option<&Signature>,
 ) -> Result<CommitId> {
     scope_time!("commit");
 
@@ -20,7 +68,7 @@ pub fn amend(
 
     let new_id = commit.amend(
         Some("HEAD"),
-        None,
+        author,
         None,
         None,
         Some(msg),
@@ -33,7 +81,7 @@ pub fn amend(
 /// Wrap Repository::signature to allow unknown user.name.
 ///
 /// See <https://github.com/extrawurst/gitui/issues/79>.
-fn signature_allow_undefined_name(
+pub(crate) fn signature_allow_undefined_name(
     repo: &Repository,
 ) -> std::result::Result<Signature<'_>, git2::Error> {
     match repo.signature() {
@@ -49,8 +97,122 @@ fn signature_allow_undefined_name(
     }
 }
 
-/// this does not run any git hooks
+/// `Name <email>`
+fn parse_author(author: &str) -> Result<(&str, &str)> {
+    let invalid = || {
+        Error::Generic(format!(
+            "invalid author `{}`, expected `Name <email>`",
+            author
+        ))
+    };
+
+    let author = author.trim();
+    let open = author.find('<').ok_or_else(invalid)?;
+    let name = author[..open].trim();
+    let email = author[open + 1..]
+        .strip_suffix('>')
+        .ok_or_else(invalid)?
+        .trim();
+
+    if name.is_empty() || email.is_empty() || email.contains('>') {
+        return Err(invalid());
+    }
+
+    Ok((name, email))
+}
+
+/// see `AuthorOverride::date`
+fn parse_author_date(date: &str) -> Result<Time> {
+    fn with_offset(date: &str) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(date)
+            .or_else(|_| {
+                DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z")
+            })
+            .or_else(|_| {
+                DateTime::parse_from_str(date, "%Y-%m-%d %H:%M %z")
+            })
+            .or_else(|_| DateTime::parse_from_str(date, "%s %z"))
+            .ok()
+    }
+
+    fn local(date: &str) -> Option<DateTime<FixedOffset>> {
+        let naive =
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(
+                        date,
+                        "%Y-%m-%d %H:%M",
+                    )
+                })
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .ok()?
+                        .and_hms_opt(0, 0, 0)
+                })?;
+
+        Local
+            .from_local_datetime(&naive)
+            .single()
+            .map(|time| time.with_timezone(time.offset()))
+    }
+
+    let date = date.trim();
+    let time = with_offset(date).or_else(|| local(date)).ok_or_else(
+        || Error::Generic(format!("invalid date `{}`", date)),
+    )?;
+
+    Ok(Time::new(
+        time.timestamp(),
+        time.offset().local_minus_utc() / 60,
+    ))
+}
+
+/// the author to commit as, what is not overridden is taken from
+/// the configured identity and now, fails on a malformed author or
+/// date
+pub fn author_signature(
+    repo_path: &str,
+    author: &AuthorOverride,
+) -> Result<Signature<'static>> {
+    let repo = repo(repo_path)?;
+    let configured = signature_allow_undefined_name(&repo)?;
+
+    let (name, email) = match &author.author {
+        Some(author) => parse_author(author)?,
+        None => (
+            configured.name().unwrap_or_default(),
+            configured.email().unwrap_or_default(),
+        ),
+    };
+    let time = match &author.date {
+        Some(date) => parse_author_date(date)?,
+        None => configured.when(),
+    };
+
+    Ok(Signature::new(name, email, &time)?)
+}
+
+/// this does not run any git hooks, finishes a merge in progress
 pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
+    commit_with(repo_path, msg, None)
+}
+
+/// like `commit` with someone else as the author, the committer is
+/// still the configured identity
+pub fn commit_as(
+    repo_path: &str,
+    msg: &str,
+    author: &Signature,
+) -> Result<CommitId> {
+    commit_with(repo_path, msg, Some(author))
+}
+
+fn commit_with(
+    repo_path: &str,
+    msg: &str,
+    author: Option<&Signature>,
+) -> Result<CommitId> {
     scope_time!("commit");
 
     let repo = repo(repo_path)?;
@@ -60,27 +222,38 @@ pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let parents = if let Ok(id) = get_head(repo_path) {
+    let mut parents = if let Ok(id) = get_head(repo_path) {
         vec![repo.find_commit(id.into())?]
     } else {
         Vec::new()
     };
 
+    let merging = repo.state() == RepositoryState::Merge;
+    if merging {
+        parents.extend(merge_heads(&repo)?);
+    }
+
     let parents = parents.iter().collect::<Vec<_>>();
 
-    Ok(repo
-        .commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            msg,
-            &tree,
-            parents.as_slice(),
-        )?
-        .into())
+    let id = repo.commit(
+        Some("HEAD"),
+        author.unwrap_or(&signature),
+        &signature,
+        msg,
+        &tree,
+        parents.as_slice(),
+    )?;
+
+    if merging {
+        repo.cleanup_state()?;
+    }
+    clear_squash_msg(&repo)?;
+
+    Ok(id.into())
 }
 
-/// Tag a commit.
+/// Tag a commit, annotated with `message` or a lightweight tag if
+/// there is none. The tagger is taken from `user.name`/`user.email`.
 ///
 /// This function will return an `Err(…)` variant if the tag’s name is refused
 /// by git or if the tag already exists.
@@ -88,17 +261,24 @@ pub fn tag(
     repo_path: &str,
     commit_id: &CommitId,
     tag: &str,
+    message: Option<&str>,
 ) -> Result<CommitId> {
     scope_time!("tag");
 
     let repo = repo(repo_path)?;
 
-    let signature = signature_allow_undefined_name(&repo)?;
     let object_id = commit_id.get_oid();
     let target =
         repo.find_object(object_id, Some(ObjectType::Commit))?;
 
-    Ok(repo.tag(tag, &target, &signature, "", false)?.into())
+    let id = if let Some(message) = message {
+        let signature = signature_allow_undefined_name(&repo)?;
+        repo.tag(tag, &target, &signature, message, false)?
+    } else {
+        repo.tag_lightweight(tag, &target, false)?
+    };
+
+    Ok(id.into())
 }
 
 #[cfg(test)]
@@ -112,7 +292,10 @@ mod tests {
         utils::get_head,
         LogWalker,
     };
-    use commit::{amend, tag};
+    use commit::{
+        amend, author_signature, commit_as, parse_author_date, tag,
+        AuthorOverride,
+    };
     use git2::Repository;
     use std::{fs::File, io::Write, path::Path};
 
@@ -208,6 +391,71 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_commit_as() -> Result<()> {
+        let file_path = Path::new("foo");
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join(file_path))?.write_all(b"test")?;
+        stage_add_file(repo_path, file_path)?;
+
+        let author = author_signature(
+            repo_path,
+            &AuthorOverride {
+                author: Some(String::from("Jo Doe <jo@doe.org>")),
+                date: Some(String::from("2020-02-29 13:14 +0100")),
+            },
+        )?;
+        let id = commit_as(repo_path, "on behalf", &author)?;
+
+        let commit = repo.find_commit(id.into())?;
+        assert_eq!(commit.author().name(), Some("Jo Doe"));
+        assert_eq!(commit.author().email(), Some("jo@doe.org"));
+        assert_eq!(commit.author().when().seconds(), 1_582_978_440);
+        assert_eq!(commit.author().when().offset_minutes(), 60);
+        assert_eq!(commit.committer().name(), Some("name"));
+        assert_eq!(commit.committer().email(), Some("email"));
+
+        // only the date, the configured author stays
+        let author = author_signature(
+            repo_path,
+            &AuthorOverride {
+                author: None,
+                date: Some(String::from("1582978440 -0230")),
+            },
+        )?;
+        assert_eq!(author.name(), Some("name"));
+        assert_eq!(author.when().seconds(), 1_582_978_440);
+        assert_eq!(author.when().offset_minutes(), -150);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_author_override_invalid() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        for author in &["Jo Doe", "<jo@doe.org>", "Jo <jo@doe.org"] {
+            assert!(author_signature(
+                repo_path,
+                &AuthorOverride {
+                    author: Some((*author).to_string()),
+                    date: None,
+                },
+            )
+            .is_err());
+        }
+
+        assert!(parse_author_date("2020-02-30").is_err());
+        assert!(parse_author_date("yesterday").is_err());
+        assert!(parse_author_date("2020-02-29").is_ok());
+        assert!(parse_author_date("2020-02-29T13:14:00Z").is_ok());
+    }
+
     #[test]
     fn test_tag() -> Result<()> {
         let file_path = Path::new("foo");
@@ -222,21 +470,24 @@ mod tests {
 
         let new_id = commit(repo_path, "commit msg")?;
 
-        tag(repo_path, &new_id, "tag")?;
+        tag(repo_path, &new_id, "tag", None)?;
 
         assert_eq!(
             get_tags(repo_path).unwrap()[&new_id],
             vec!["tag"]
         );
 
-        assert!(matches!(tag(repo_path, &new_id, "tag"), Err(_)));
+        assert!(matches!(
+            tag(repo_path, &new_id, "tag", None),
+            Err(_)
+        ));
 
         assert_eq!(
             get_tags(repo_path).unwrap()[&new_id],
             vec!["tag"]
         );
 
-        tag(repo_path, &new_id, "second-tag")?;
+        tag(repo_path, &new_id, "second-tag", None)?;
 
         assert_eq!(
             get_tags(repo_path).unwrap()[&new_id],
@@ -245,4 +496,55 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_tag_annotated() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.config()?.set_str("user.name", "tagger")?;
+        let head = get_head(repo_path)?;
+
+        let light = tag(repo_path, &head, "light", None)?;
+        let annotated =
+            tag(repo_path, &head, "v1.0", Some("release notes"))?;
+
+        let mut found = Vec::new();
+        repo.tag_foreach(|id, name| {
+            found.push((
+                id,
+                String::from_utf8_lossy(name).to_string(),
+            ));
+            true
+        })?;
+        found.sort_by(|a, b| a.1.cmp(&b.1));
+
+        assert_eq!(
+            found,
+            vec![
+                (light.get_oid(), String::from("refs/tags/light")),
+                (annotated.get_oid(), String::from("refs/tags/v1.0")),
+            ]
+        );
+
+        // a lightweight tag is a ref to the commit itself
+        assert_eq!(light, head);
+        assert!(repo.find_tag(light.get_oid()).is_err());
+
+        let annotated = repo.find_tag(annotated.get_oid())?;
+        assert_eq!(annotated.target_id(), head.get_oid());
+        assert_eq!(annotated.message(), Some("release notes"));
+        assert_eq!(
+            annotated.tagger().unwrap().name(),
+            Some("tagger")
+        );
+
+        assert_eq!(
+            get_tags(repo_path).unwrap()[&head],
+            vec!["light", "v1.0"]
+        );
+
+        Ok(())
+    }
 }
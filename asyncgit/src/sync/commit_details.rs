@@ -1,4 +1,6 @@
-use super::{commits_info::get_message, utils::repo, CommitId};
+use super::{
+    commits_info::get_message, notes::get_note, utils::repo, CommitId,
+};
 use crate::error::Result;
 use git2::Signature;
 use scopetime::scope_time;
@@ -75,6 +77,8 @@ pub struct CommitDetails {
     pub message: Option<CommitMessage>,
     ///
     pub hash: String,
+    /// of `git notes`
+    pub note: Option<String>,
 }
 
 ///
@@ -104,6 +108,7 @@ pub fn get_commit_details(
         committer,
         message: Some(msg),
         hash: id.to_string(),
+        note: get_note(repo_path, id)?,
     };
 
     Ok(details)
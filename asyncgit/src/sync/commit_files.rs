@@ -1,7 +1,63 @@
-use super::{stash::is_stash_commit, utils::repo, CommitId};
+use super::{
+    diff::DiffOptions, stash::is_stash_commit, utils::repo, CommitId,
+};
 use crate::{error::Result, StatusItem, StatusItemType};
-use git2::{Diff, DiffDelta, DiffOptions, Repository};
+use git2::{
+    Delta, Diff, DiffDelta, DiffOptions as GitDiffOptions, Patch,
+    Repository,
+};
 use scopetime::scope_time;
+use std::{collections::HashMap, ops::AddAssign};
+
+/// how much of a single file changed
+#[derive(Default, Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct FileStat {
+    /// lines added
+    pub insertions: usize,
+    /// lines removed
+    pub deletions: usize,
+    /// no lines are counted then, only the sizes tell
+    pub binary: bool,
+    /// bytes before the change
+    pub old_size: u64,
+    /// bytes after the change
+    pub new_size: u64,
+}
+
+impl FileStat {
+    /// lines added and removed
+    pub const fn lines(&self) -> usize {
+        self.insertions + self.deletions
+    }
+
+    /// bytes the file grew (or shrank if negative)
+    pub fn size_delta(&self) -> i64 {
+        (self.new_size as i64).saturating_sub(self.old_size as i64)
+    }
+}
+
+impl AddAssign for FileStat {
+    fn add_assign(&mut self, other: Self) {
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+        self.binary |= other.binary;
+        self.old_size += other.old_size;
+        self.new_size += other.new_size;
+    }
+}
+
+/// summary of the changes a commit introduces
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct CommitStats {
+    /// amount of files changed
+    pub files: usize,
+    /// lines added
+    pub insertions: usize,
+    /// lines removed
+    pub deletions: usize,
+    /// per changed file path
+    pub file_stats: HashMap<String, FileStat>,
+}
 
 /// get all files that are part of a commit
 pub fn get_commit_files(
@@ -12,7 +68,8 @@ pub fn get_commit_files(
 
     let repo = repo(repo_path)?;
 
-    let diff = get_commit_diff(&repo, id, None)?;
+    let diff =
+        get_commit_diff(&repo, id, None, DiffOptions::default())?;
 
     let mut res = Vec::new();
 
@@ -25,6 +82,14 @@ pub fn get_commit_files(
                     .map(|p| p.to_str().unwrap_or("").to_string())
                     .unwrap_or_default(),
                 status: StatusItemType::from(delta.status()),
+                old_path: match delta.status() {
+                    Delta::Renamed | Delta::Copied => delta
+                        .old_file()
+                        .path()
+                        .and_then(|p| p.to_str())
+                        .map(String::from),
+                    _ => None,
+                },
             });
             true
         },
@@ -36,11 +101,101 @@ pub fn get_commit_files(
     Ok(res)
 }
 
-///
+/// paths of the untracked files a stash saved in its third parent,
+/// empty for other commits
+pub fn get_stash_untracked_files(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<Vec<String>> {
+    scope_time!("get_stash_untracked_files");
+
+    if !is_stash_commit(repo_path, &id)? {
+        return Ok(Vec::new());
+    }
+
+    let repo = repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    commit.parent_id(2).map_or_else(
+        |_| Ok(Vec::new()),
+        |untracked| {
+            Ok(get_commit_files(repo_path, CommitId::new(untracked))?
+                .into_iter()
+                .map(|item| item.path)
+                .collect())
+        },
+    )
+}
+
+/// get the diff statistics of a commit, in total and per file
+pub fn get_commit_stats(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<CommitStats> {
+    scope_time!("get_commit_stats");
+
+    let repo = repo(repo_path)?;
+
+    let diff =
+        get_commit_diff(&repo, id, None, DiffOptions::default())?;
+    let stats = diff.stats()?;
+
+    Ok(CommitStats {
+        files: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+        file_stats: file_stats(&diff)?.into_iter().collect(),
+    })
+}
+
+/// stat of each delta by its (new) path
+pub(crate) fn file_stats(
+    diff: &Diff,
+) -> Result<Vec<(String, FileStat)>> {
+    let mut res = Vec::with_capacity(diff.deltas().len());
+
+    for (idx, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+
+        // the sizes are known once the content got loaded
+        let stat = match Patch::from_diff(diff, idx)? {
+            Some(patch) => {
+                let (_, insertions, deletions) =
+                    patch.line_stats()?;
+                let delta = patch.delta();
+                FileStat {
+                    insertions,
+                    deletions,
+                    binary: delta.flags().is_binary(),
+                    old_size: delta.old_file().size(),
+                    new_size: delta.new_file().size(),
+                }
+            }
+            None => FileStat {
+                binary: true,
+                old_size: delta.old_file().size(),
+                new_size: delta.new_file().size(),
+                ..FileStat::default()
+            },
+        };
+
+        res.push((path, stat));
+    }
+
+    Ok(res)
+}
+
+/// `options` only apply to the diff of a `pathspec`
 pub(crate) fn get_commit_diff(
     repo: &Repository,
     id: CommitId,
     pathspec: Option<String>,
+    options: DiffOptions,
 ) -> Result<Diff<'_>> {
     // scope_time!("get_commit_diff");
 
@@ -53,9 +208,10 @@ pub(crate) fn get_commit_diff(
     };
 
     let mut opt = pathspec.as_ref().map(|p| {
-        let mut opts = DiffOptions::new();
+        let mut opts = GitDiffOptions::new();
         opts.pathspec(p);
         opts.show_binary(true);
+        options.apply(&mut opts);
         opts
     });
 
@@ -74,6 +230,7 @@ pub(crate) fn get_commit_diff(
                 repo,
                 CommitId::new(untracked_commit),
                 pathspec,
+                options,
             )?;
 
             diff.merge(&untracked_diff)?;
@@ -85,7 +242,9 @@ pub(crate) fn get_commit_diff(
 
 #[cfg(test)]
 mod tests {
-    use super::get_commit_files;
+    use super::{
+        get_commit_files, get_commit_stats, get_stash_untracked_files,
+    };
     use crate::{
         error::Result,
         sync::{
@@ -114,6 +273,39 @@ mod tests {
 
         assert_eq!(diff.len(), 1);
         assert_eq!(diff[0].status, StatusItemType::New);
+        assert!(get_stash_untracked_files(repo_path, id)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let file_path1 = Path::new("file1.txt");
+        let file_path2 = Path::new("file2.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path1))?.write_all(b"a\nb\n")?;
+        stage_add_file(repo_path, file_path1)?;
+        commit(repo_path, "c1")?;
+
+        File::create(&root.join(file_path1))?
+            .write_all(b"a\nc\nd\n")?;
+        File::create(&root.join(file_path2))?.write_all(b"new\n")?;
+        stage_add_file(repo_path, file_path1)?;
+        stage_add_file(repo_path, file_path2)?;
+        let id = commit(repo_path, "c2")?;
+
+        let stats = get_commit_stats(repo_path, id)?;
+
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.insertions, 3);
+        assert_eq!(stats.deletions, 1);
+        assert_eq!(stats.file_stats["file1.txt"].insertions, 2);
+        assert_eq!(stats.file_stats["file1.txt"].deletions, 1);
+        assert_eq!(stats.file_stats["file2.txt"].lines(), 1);
+        assert!(!stats.file_stats["file2.txt"].binary);
 
         Ok(())
     }
@@ -128,7 +320,7 @@ mod tests {
         File::create(&root.join(file_path))?
             .write_all(b"test file1 content")?;
 
-        let id = stash_save(repo_path, None, true, false)?;
+        let id = stash_save(repo_path, None, true, false, false)?;
 
         let diff = get_commit_files(repo_path, id)?;
 
@@ -156,13 +348,17 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (2, 0));
 
-        let id = stash_save(repo_path, None, true, false)?;
+        let id = stash_save(repo_path, None, true, false, false)?;
 
         let diff = get_commit_files(repo_path, id)?;
 
         assert_eq!(diff.len(), 2);
         assert_eq!(diff[0].status, StatusItemType::Modified);
         assert_eq!(diff[1].status, StatusItemType::New);
+        assert_eq!(
+            get_stash_untracked_files(repo_path, id)?,
+            vec![String::from("file2.txt")]
+        );
 
         Ok(())
     }
@@ -0,0 +1,188 @@
+//! sync git api for reading and writing config values
+
+use super::utils::repo;
+use crate::error::{Error, Result};
+use git2::{Config, ConfigLevel, Repository};
+use scopetime::scope_time;
+use std::{env, path::Path};
+
+/// config file a value was read from or is written to
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConfigScope {
+    /// `.git/config` of the repository
+    Local,
+    /// `~/.gitconfig` (or the xdg one if it exists)
+    Global,
+    /// system wide config
+    System,
+    /// any other level (xdg, program data, ...)
+    Other,
+}
+
+impl From<ConfigLevel> for ConfigScope {
+    fn from(level: ConfigLevel) -> Self {
+        match level {
+            ConfigLevel::Local => Self::Local,
+            ConfigLevel::Global => Self::Global,
+            ConfigLevel::System => Self::System,
+            _ => Self::Other,
+        }
+    }
+}
+
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigValue {
+    ///
+    pub key: String,
+    /// effective value, `None` if not set at any level
+    pub value: Option<String>,
+    /// where the effective value comes from
+    pub scope: Option<ConfigScope>,
+}
+
+/// effective values of `keys`, the local config overrides the
+/// global one which overrides the system one
+pub fn get_config_values(
+    repo_path: &str,
+    keys: &[&str],
+) -> Result<Vec<ConfigValue>> {
+    scope_time!("get_config_values");
+
+    let repo = repo(repo_path)?;
+    let config = repo.config()?;
+
+    keys.iter()
+        .map(|key| match config.get_entry(key) {
+            Ok(entry) => Ok(ConfigValue {
+                key: (*key).to_string(),
+                value: entry.value().map(String::from),
+                scope: Some(entry.level().into()),
+            }),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                Ok(ConfigValue {
+                    key: (*key).to_string(),
+                    value: None,
+                    scope: None,
+                })
+            }
+            Err(e) => Err(e.into()),
+        })
+        .collect()
+}
+
+/// writes `key` to the local or global config, an empty `value`
+/// removes it from there
+pub fn set_config_value(
+    repo_path: &str,
+    key: &str,
+    value: &str,
+    scope: ConfigScope,
+) -> Result<()> {
+    scope_time!("set_config_value");
+
+    let repo = repo(repo_path)?;
+
+    let mut config = match scope {
+        ConfigScope::Local => {
+            repo.config()?.open_level(ConfigLevel::Local)?
+        }
+        ConfigScope::Global => global_config(&repo)?,
+        _ => {
+            return Err(Error::Generic(format!(
+                "can not write to the {:?} config",
+                scope
+            )))
+        }
+    };
+
+    if value.is_empty() {
+        match config.remove(key) {
+            Err(e) if e.code() != git2::ErrorCode::NotFound => {
+                return Err(e.into())
+            }
+            _ => (),
+        }
+    } else {
+        config.set_str(key, value)?;
+    }
+
+    Ok(())
+}
+
+fn global_config(repo: &Repository) -> Result<Config> {
+    if let Ok(config) = repo.config()?.open_global() {
+        return Ok(config);
+    }
+
+    // there is no global config yet, create the one git would use
+    let home = env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .ok_or_else(|| {
+            Error::Generic(String::from("home directory not found"))
+        })?;
+
+    Ok(Config::open(&Path::new(&home).join(".gitconfig"))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init_empty;
+
+    #[test]
+    fn test_get_and_set() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let values = get_config_values(
+            repo_path,
+            &["user.name", "gitui.unset"],
+        )
+        .unwrap();
+
+        assert_eq!(values[0].value.as_deref(), Some("name"));
+        assert_eq!(values[0].scope, Some(ConfigScope::Local));
+        assert_eq!(values[1].value, None);
+        assert_eq!(values[1].scope, None);
+
+        set_config_value(
+            repo_path,
+            "gitui.test",
+            "foo",
+            ConfigScope::Local,
+        )
+        .unwrap();
+        let values =
+            get_config_values(repo_path, &["gitui.test"]).unwrap();
+        assert_eq!(values[0].value.as_deref(), Some("foo"));
+
+        set_config_value(
+            repo_path,
+            "gitui.test",
+            "",
+            ConfigScope::Local,
+        )
+        .unwrap();
+        // removing what is not set is fine
+        set_config_value(
+            repo_path,
+            "gitui.test",
+            "",
+            ConfigScope::Local,
+        )
+        .unwrap();
+        let values =
+            get_config_values(repo_path, &["gitui.test"]).unwrap();
+        assert_eq!(values[0].value, None);
+
+        assert!(set_config_value(
+            repo_path,
+            "gitui.test",
+            "foo",
+            ConfigScope::System
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,632 @@
+//! the stages of unmerged files and resolving them by picking the
+//! version of one side, for the whole file or per conflict
+
+use super::{
+    blob::is_binary,
+    utils::{repo, work_dir},
+};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, DiffOptions, IndexConflict, IndexEntry,
+    Patch, Repository,
+};
+use scopetime::scope_time;
+use std::{fs, ops::Range, path::Path, str};
+
+/// one side of a conflict
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// the current branch (stage 2)
+    Ours,
+    /// what is merged in (stage 3)
+    Theirs,
+}
+
+/// how a file ended up conflicted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// changed on both sides
+    BothModified,
+    /// added on both sides, there is no base
+    BothAdded,
+    /// deleted on our side, changed on theirs
+    DeletedByUs,
+    /// deleted on their side, changed on ours
+    DeletedByThem,
+}
+
+/// a stretch of a conflicted file, the lines keep their line breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictRegion {
+    /// the same on both sides or changed on one side only
+    Merged(Vec<String>),
+    /// changed differently on both sides
+    Conflict {
+        ///
+        base: Vec<String>,
+        ///
+        ours: Vec<String>,
+        ///
+        theirs: Vec<String>,
+    },
+}
+
+/// the stages of a conflicted file lined up against each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    ///
+    pub kind: ConflictKind,
+    /// none if a side deleted the file, only the whole file can be
+    /// taken then
+    pub regions: Vec<ConflictRegion>,
+}
+
+impl ConflictFile {
+    /// number of regions in conflict
+    pub fn conflicts(&self) -> usize {
+        self.regions
+            .iter()
+            .filter(|region| {
+                matches!(region, ConflictRegion::Conflict { .. })
+            })
+            .count()
+    }
+
+    /// the content with the side `picks` has for each conflict in
+    /// turn, `None` while a conflict has no pick
+    pub fn resolve(&self, picks: &[ConflictSide]) -> Option<String> {
+        let mut picks = picks.iter();
+        let mut content = String::new();
+
+        for region in &self.regions {
+            let lines = match region {
+                ConflictRegion::Merged(lines) => lines,
+                ConflictRegion::Conflict { ours, theirs, .. } => {
+                    match picks.next()? {
+                        ConflictSide::Ours => ours,
+                        ConflictSide::Theirs => theirs,
+                    }
+                }
+            };
+            content.extend(lines.iter().map(String::as_str));
+        }
+
+        Some(content)
+    }
+}
+
+/// lines of the base one side replaced by lines of its own
+struct Change {
+    base: Range<usize>,
+    side: Range<usize>,
+}
+
+/// 0-based lines of a hunk side, an empty one starts after `start`
+fn hunk_lines(start: u32, lines: u32) -> Range<usize> {
+    let start = start as usize;
+    let lines = lines as usize;
+    if lines == 0 {
+        start..start
+    } else {
+        start - 1..start - 1 + lines
+    }
+}
+
+fn changes(base: &str, side: &str) -> Result<Vec<Change>> {
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let patch = Patch::from_buffers(
+        base.as_bytes(),
+        None,
+        side.as_bytes(),
+        None,
+        Some(&mut opts),
+    )?;
+
+    (0..patch.num_hunks())
+        .map(|idx| {
+            let (hunk, _) = patch.hunk(idx)?;
+            Ok(Change {
+                base: hunk_lines(hunk.old_start(), hunk.old_lines()),
+                side: hunk_lines(hunk.new_start(), hunk.new_lines()),
+            })
+        })
+        .collect()
+}
+
+fn lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(String::from).collect()
+}
+
+fn push_merged(regions: &mut Vec<ConflictRegion>, lines: &[String]) {
+    if lines.is_empty() {
+        return;
+    }
+
+    if let Some(ConflictRegion::Merged(merged)) = regions.last_mut() {
+        merged.extend_from_slice(lines);
+    } else {
+        regions.push(ConflictRegion::Merged(lines.to_vec()));
+    }
+}
+
+/// the lines of a side for `base` lines `range`, which `changes`
+/// (if any) lie in
+fn side_lines<'a>(
+    range: &Range<usize>,
+    changes: &[Change],
+    base: &'a [String],
+    side: &'a [String],
+) -> &'a [String] {
+    match (changes.first(), changes.last()) {
+        (Some(first), Some(last)) => {
+            // the side matches the base around its changes
+            &side[first.side.start - (first.base.start - range.start)
+                ..last.side.end + (range.end - last.base.end)]
+        }
+        _ => &base[range.clone()],
+    }
+}
+
+/// lines up the changes both sides made to `base` (like `diff3`):
+/// changes of one side are merged, changes of both sides that
+/// overlap or touch are a conflict unless they are the same
+fn merge_regions(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+) -> Result<Vec<ConflictRegion>> {
+    let (base_lines, ours_lines, theirs_lines) =
+        (lines(base), lines(ours), lines(theirs));
+    let ours_changes = changes(base, ours)?;
+    let theirs_changes = changes(base, theirs)?;
+
+    let mut regions = Vec::new();
+    let (mut pos, mut next_ours, mut next_theirs) = (0, 0, 0);
+
+    loop {
+        let start = match (
+            ours_changes.get(next_ours),
+            theirs_changes.get(next_theirs),
+        ) {
+            (None, None) => break,
+            (Some(ours), None) => ours.base.start,
+            (None, Some(theirs)) => theirs.base.start,
+            (Some(ours), Some(theirs)) => {
+                ours.base.start.min(theirs.base.start)
+            }
+        };
+
+        // grows as long as a change of either side joins in
+        let (first_ours, first_theirs) = (next_ours, next_theirs);
+        let mut end = start;
+        loop {
+            if let Some(change) = ours_changes
+                .get(next_ours)
+                .filter(|change| change.base.start <= end)
+            {
+                end = end.max(change.base.end);
+                next_ours += 1;
+            } else if let Some(change) = theirs_changes
+                .get(next_theirs)
+                .filter(|change| change.base.start <= end)
+            {
+                end = end.max(change.base.end);
+                next_theirs += 1;
+            } else {
+                break;
+            }
+        }
+
+        push_merged(&mut regions, &base_lines[pos..start]);
+
+        let range = start..end;
+        let ours = side_lines(
+            &range,
+            &ours_changes[first_ours..next_ours],
+            &base_lines,
+            &ours_lines,
+        );
+        let theirs = side_lines(
+            &range,
+            &theirs_changes[first_theirs..next_theirs],
+            &base_lines,
+            &theirs_lines,
+        );
+
+        if first_ours == next_ours || ours == theirs {
+            push_merged(&mut regions, theirs);
+        } else if first_theirs == next_theirs {
+            push_merged(&mut regions, ours);
+        } else {
+            regions.push(ConflictRegion::Conflict {
+                base: base_lines[range].to_vec(),
+                ours: ours.to_vec(),
+                theirs: theirs.to_vec(),
+            });
+        }
+
+        pos = end;
+    }
+
+    push_merged(&mut regions, &base_lines[pos..]);
+
+    Ok(regions)
+}
+
+fn find_conflict(
+    repo: &Repository,
+    path: &str,
+) -> Result<IndexConflict> {
+    let index = repo.index()?;
+    let conflicts = index.conflicts()?;
+
+    for conflict in conflicts {
+        let conflict = conflict?;
+        let found =
+            [&conflict.ancestor, &conflict.our, &conflict.their]
+                .iter()
+                .filter_map(|entry| entry.as_ref())
+                .any(|entry| entry.path == path.as_bytes());
+
+        if found {
+            return Ok(conflict);
+        }
+    }
+
+    Err(Error::Generic(format!("{} is not conflicted", path)))
+}
+
+/// the base, our and their version of the conflicted `path`, split
+/// into what merges and what conflicts. binary files (and text that
+/// is not utf8, which would not survive being written back) can only
+/// be resolved as a whole
+pub fn get_conflict(
+    repo_path: &str,
+    path: &str,
+) -> Result<ConflictFile> {
+    scope_time!("get_conflict");
+
+    let repo = repo(repo_path)?;
+    let conflict = find_conflict(&repo, path)?;
+    let content = |entry: &Option<IndexEntry>| -> Result<_> {
+        entry
+            .as_ref()
+            .map(|entry| {
+                Ok(repo.find_blob(entry.id)?.content().to_vec())
+            })
+            .transpose()
+    };
+    let base = content(&conflict.ancestor)?;
+    let ours = content(&conflict.our)?;
+    let theirs = content(&conflict.their)?;
+
+    let kind = match (&base, &ours, &theirs) {
+        (_, None, _) => ConflictKind::DeletedByUs,
+        (_, _, None) => ConflictKind::DeletedByThem,
+        (None, _, _) => ConflictKind::BothAdded,
+        _ => ConflictKind::BothModified,
+    };
+
+    let (ours, theirs) = match (ours, theirs) {
+        (Some(ours), Some(theirs)) => (ours, theirs),
+        _ => {
+            return Ok(ConflictFile {
+                kind,
+                regions: Vec::new(),
+            })
+        }
+    };
+    let base = base.unwrap_or_default();
+
+    let text = |content: &[u8]| {
+        if is_binary(content) {
+            None
+        } else {
+            str::from_utf8(content).ok().map(String::from)
+        }
+    };
+
+    let (base, ours, theirs) =
+        match (text(&base), text(&ours), text(&theirs)) {
+            (Some(base), Some(ours), Some(theirs)) => {
+                (base, ours, theirs)
+            }
+            _ => {
+                return Err(Error::Generic(format!(
+                    "{} is binary or not utf8, take ours or theirs",
+                    path
+                )))
+            }
+        };
+
+    let regions = merge_regions(&base, &ours, &theirs)?;
+
+    Ok(ConflictFile { kind, regions })
+}
+
+/// stages what the work dir has of `path` (or that it is gone) as
+/// its resolution
+fn stage_resolution(
+    repo: &Repository,
+    path: &str,
+    exists: bool,
+) -> Result<()> {
+    // drops every stage of the path and with them the conflict
+    let mut index = repo.index()?;
+    index.remove_path(Path::new(path))?;
+    if exists {
+        index.add_path(Path::new(path))?;
+    }
+    index.write()?;
+
+    Ok(())
+}
+
+/// resolves the conflicted `path` with `content`, like the result of
+/// `ConflictFile::resolve`, written to the work dir and staged
+pub fn write_conflict_resolution(
+    repo_path: &str,
+    path: &str,
+    content: &str,
+) -> Result<()> {
+    scope_time!("write_conflict_resolution");
+
+    let repo = repo(repo_path)?;
+    find_conflict(&repo, path)?;
+
+    fs::write(work_dir(&repo)?.join(path), content)?;
+
+    stage_resolution(&repo, path, true)
+}
+
+/// resolves the conflicted `path` with the version of `side`, which
+/// ends up in the work dir and staged (or removed from both if that
+/// side deleted it)
+pub fn resolve_conflict(
+    repo_path: &str,
+    path: &str,
+    side: ConflictSide,
+) -> Result<()> {
+    scope_time!("resolve_conflict");
+
+    let repo = repo(repo_path)?;
+    let conflict = find_conflict(&repo, path)?;
+    let kept = match side {
+        ConflictSide::Ours => conflict.our,
+        ConflictSide::Theirs => conflict.their,
+    };
+
+    if kept.is_some() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().path(path);
+        match side {
+            ConflictSide::Ours => checkout.use_ours(true),
+            ConflictSide::Theirs => checkout.use_theirs(true),
+        };
+        repo.checkout_index(None, Some(&mut checkout))?;
+    } else {
+        let file = work_dir(&repo)?.join(path);
+        if file.exists() {
+            fs::remove_file(file)?;
+        }
+    }
+
+    stage_resolution(&repo, path, kept.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, merge_branch,
+        status::{
+            get_status, ShowUntrackedFiles, StatusItemType,
+            StatusType,
+        },
+        tests::repo_init,
+        utils::{stage_add_file, stage_addremoved},
+        MergeOutcome, MergeStrategy,
+    };
+
+    #[test]
+    fn test_resolve_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let checkout = |branch: &str| {
+            repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+            repo.checkout_head(Some(CheckoutBuilder::new().force()))
+                .unwrap();
+        };
+        let commit_files = |content: &str| {
+            for name in &["a", "b"] {
+                fs::write(root.join(name), content).unwrap();
+                stage_add_file(repo_path, Path::new(name)).unwrap();
+            }
+            commit(repo_path, content).unwrap();
+        };
+
+        commit_files("base");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        checkout("feature");
+        commit_files("theirs");
+        checkout("master");
+        commit_files("ours");
+
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "feature",
+                MergeStrategy::NoFastForward
+            )
+            .unwrap(),
+            MergeOutcome::Conflicts
+        );
+        let conflicted = || {
+            get_status(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::All,
+            )
+            .unwrap()
+            .into_iter()
+            .filter(|item| item.status == StatusItemType::Conflicted)
+            .count()
+        };
+        assert_eq!(conflicted(), 2);
+
+        resolve_conflict(repo_path, "a", ConflictSide::Ours).unwrap();
+        resolve_conflict(repo_path, "b", ConflictSide::Theirs)
+            .unwrap();
+
+        assert_eq!(conflicted(), 0);
+        assert!(!repo.index().unwrap().has_conflicts());
+        assert_eq!(
+            fs::read_to_string(root.join("a")).unwrap(),
+            "ours"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("b")).unwrap(),
+            "theirs"
+        );
+        assert!(resolve_conflict(repo_path, "a", ConflictSide::Ours)
+            .is_err());
+    }
+
+    fn text(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    #[test]
+    fn test_merge_regions() {
+        let base = "a\nb\nc\nd\ne\nf\ng\n";
+        let ours = "a\nbo\nc\nd\ne\nfo\ng\n";
+        let theirs = "a\nbt\nc\ndt\ne\nf\ng\nh\n";
+
+        let file = ConflictFile {
+            kind: ConflictKind::BothModified,
+            regions: merge_regions(base, ours, theirs).unwrap(),
+        };
+        assert_eq!(
+            file.regions,
+            vec![
+                ConflictRegion::Merged(text(&["a"])),
+                ConflictRegion::Conflict {
+                    base: text(&["b"]),
+                    ours: text(&["bo"]),
+                    theirs: text(&["bt"]),
+                },
+                ConflictRegion::Merged(text(&[
+                    "c", "dt", "e", "fo", "g", "h"
+                ])),
+            ]
+        );
+        assert_eq!(file.conflicts(), 1);
+        assert_eq!(file.resolve(&[]), None);
+        assert_eq!(
+            file.resolve(&[ConflictSide::Theirs]).unwrap(),
+            "a\nbt\nc\ndt\ne\nfo\ng\nh\n"
+        );
+
+        // the same change on both sides merges, touching ones do not
+        assert_eq!(
+            merge_regions("a\nb\n", "x\nb\n", "x\nb\n").unwrap(),
+            vec![ConflictRegion::Merged(text(&["x", "b"]))]
+        );
+        assert_eq!(
+            merge_regions("a\nb\n", "x\nb\n", "a\ny\n").unwrap(),
+            vec![ConflictRegion::Conflict {
+                base: text(&["a", "b"]),
+                ours: text(&["x", "b"]),
+                theirs: text(&["a", "y"]),
+            }]
+        );
+        // no base, like files added on both sides
+        assert_eq!(
+            merge_regions("", "o\n", "t\n").unwrap(),
+            vec![ConflictRegion::Conflict {
+                base: Vec::new(),
+                ours: text(&["o"]),
+                theirs: text(&["t"]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_conflict() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let checkout = |branch: &str| {
+            repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+            repo.checkout_head(Some(CheckoutBuilder::new().force()))
+                .unwrap();
+        };
+
+        fs::write(root.join("a"), "1\n2\n3\n").unwrap();
+        fs::write(root.join("b"), "b\n").unwrap();
+        fs::write(root.join("c"), b"caf\xe9\n").unwrap();
+        stage_add_file(repo_path, Path::new("a")).unwrap();
+        stage_add_file(repo_path, Path::new("b")).unwrap();
+        stage_add_file(repo_path, Path::new("c")).unwrap();
+        commit(repo_path, "base").unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        checkout("feature");
+        fs::write(root.join("a"), "1\n2t\n3\n").unwrap();
+        fs::write(root.join("c"), b"caf\xe9 t\n").unwrap();
+        stage_add_file(repo_path, Path::new("a")).unwrap();
+        stage_add_file(repo_path, Path::new("c")).unwrap();
+        fs::remove_file(root.join("b")).unwrap();
+        stage_addremoved(repo_path, Path::new("b")).unwrap();
+        commit(repo_path, "theirs").unwrap();
+
+        checkout("master");
+        fs::write(root.join("a"), "1\n2o\n3\n").unwrap();
+        fs::write(root.join("b"), "bo\n").unwrap();
+        fs::write(root.join("c"), b"caf\xe9 o\n").unwrap();
+        stage_add_file(repo_path, Path::new("a")).unwrap();
+        stage_add_file(repo_path, Path::new("b")).unwrap();
+        stage_add_file(repo_path, Path::new("c")).unwrap();
+        commit(repo_path, "ours").unwrap();
+
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "feature",
+                MergeStrategy::NoFastForward
+            )
+            .unwrap(),
+            MergeOutcome::Conflicts
+        );
+
+        assert_eq!(
+            get_conflict(repo_path, "b").unwrap(),
+            ConflictFile {
+                kind: ConflictKind::DeletedByThem,
+                regions: Vec::new(),
+            }
+        );
+
+        // latin1 would come back with replacement characters
+        assert!(get_conflict(repo_path, "c").is_err());
+
+        let file = get_conflict(repo_path, "a").unwrap();
+        assert_eq!(file.kind, ConflictKind::BothModified);
+        assert_eq!(file.conflicts(), 1);
+
+        let content = file.resolve(&[ConflictSide::Ours]).unwrap();
+        write_conflict_resolution(repo_path, "a", &content).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("a")).unwrap(),
+            "1\n2o\n3\n"
+        );
+        assert!(
+            write_conflict_resolution(repo_path, "a", "").is_err()
+        );
+        assert!(get_conflict(repo_path, "b").is_ok());
+    }
+}
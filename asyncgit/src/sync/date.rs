@@ -0,0 +1,230 @@
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Utc};
+use std::fmt::Write;
+
+/// how commit timestamps are rendered
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateFormat {
+    /// `2 hours ago`
+    Relative,
+    /// `2020-01-15T10:30:00+00:00`
+    Iso,
+    /// `2020-01-15`
+    Short,
+    /// relative for recent commits, leaving out what is obvious
+    /// otherwise (like git's `--date=human`)
+    Human,
+    /// any strftime format string supported by `chrono::format`
+    Custom(String),
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        Self::Human
+    }
+}
+
+impl From<&str> for DateFormat {
+    fn from(format: &str) -> Self {
+        match format {
+            "relative" => Self::Relative,
+            "iso" => Self::Iso,
+            "short" => Self::Short,
+            "human" => Self::Human,
+            custom => Self::Custom(custom.to_string()),
+        }
+    }
+}
+
+/// formats `time` (secs since Unix epoch) in the local timezone, the
+/// raw seconds if that is out of range
+pub fn format_commit_time(time: i64, format: &DateFormat) -> String {
+    Local.timestamp_opt(time, 0).single().map_or_else(
+        || time.to_string(),
+        |local| format_time(&local, format, Utc::now()),
+    )
+}
+
+fn format_time<Tz: TimeZone>(
+    time: &DateTime<Tz>,
+    format: &DateFormat,
+    now: DateTime<Utc>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match format {
+        DateFormat::Relative => relative(time, now),
+        DateFormat::Iso => {
+            time.format("%Y-%m-%dT%H:%M:%S%:z").to_string()
+        }
+        DateFormat::Short => time.format("%Y-%m-%d").to_string(),
+        DateFormat::Human => human(time, now),
+        DateFormat::Custom(custom) => {
+            // invalid format strings fail while formatting
+            let mut res = String::new();
+            if write!(res, "{}", time.format(custom)).is_ok() {
+                res
+            } else {
+                format_time(time, &DateFormat::Iso, now)
+            }
+        }
+    }
+}
+
+fn human<Tz: TimeZone>(
+    time: &DateTime<Tz>,
+    now: DateTime<Utc>,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let age = now.signed_duration_since(time.clone());
+    let this_year = now.with_timezone(&time.timezone()).year();
+
+    if age < Duration::days(1) {
+        relative(time, now)
+    } else if age < Duration::days(7) {
+        time.format("%a %H:%M").to_string()
+    } else if time.year() == this_year {
+        time.format("%b %-d").to_string()
+    } else {
+        time.format("%b %-d %Y").to_string()
+    }
+}
+
+fn relative<Tz: TimeZone>(
+    time: &DateTime<Tz>,
+    now: DateTime<Utc>,
+) -> String {
+    let secs = now.signed_duration_since(time.clone()).num_seconds();
+    // commits from the future (skewed clocks) count as just now
+    let secs = secs.max(0);
+
+    let (amount, unit) = match secs {
+        s if s < 60 => (s, "second"),
+        s if s < 60 * 60 => (s / 60, "minute"),
+        s if s < 60 * 60 * 24 => (s / (60 * 60), "hour"),
+        s if s < 60 * 60 * 24 * 7 => (s / (60 * 60 * 24), "day"),
+        s if s < 60 * 60 * 24 * 30 => {
+            (s / (60 * 60 * 24 * 7), "week")
+        }
+        s if s < 60 * 60 * 24 * 365 => {
+            (s / (60 * 60 * 24 * 30), "month")
+        }
+        s => (s / (60 * 60 * 24 * 365), "year"),
+    };
+
+    format!(
+        "{} {}{} ago",
+        amount,
+        unit,
+        if amount == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    // 2020-01-15T10:30:00+00:00
+    const TIME: i64 = 1_579_084_200;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(DateFormat::from("human"), DateFormat::Human);
+        assert_eq!(DateFormat::from("iso"), DateFormat::Iso);
+        assert_eq!(
+            DateFormat::from("%d.%m.%Y"),
+            DateFormat::Custom(String::from("%d.%m.%Y"))
+        );
+    }
+
+    #[test]
+    fn test_fixed_formats() {
+        let now = utc(TIME + 10);
+
+        assert_eq!(
+            format_time(&utc(TIME), &DateFormat::Iso, now),
+            "2020-01-15T10:30:00+00:00"
+        );
+        assert_eq!(
+            format_time(&utc(TIME), &DateFormat::Short, now),
+            "2020-01-15"
+        );
+        assert_eq!(
+            format_time(
+                &utc(TIME),
+                &DateFormat::Custom(String::from("%d.%m.%Y %H:%M")),
+                now
+            ),
+            "15.01.2020 10:30"
+        );
+        assert_eq!(
+            format_time(
+                &utc(TIME),
+                &DateFormat::Custom(String::from("%Q")),
+                now
+            ),
+            "2020-01-15T10:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_relative() {
+        let format = DateFormat::Relative;
+        let time = utc(TIME);
+
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 1)),
+            "1 second ago"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 2 * 60 * 60)),
+            "2 hours ago"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 3 * 24 * 60 * 60)),
+            "3 days ago"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME - 10)),
+            "0 seconds ago"
+        );
+    }
+
+    #[test]
+    fn test_human() {
+        let format = DateFormat::Human;
+        let time = utc(TIME);
+        let day = 24 * 60 * 60;
+
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 5 * 60)),
+            "5 minutes ago"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 2 * day)),
+            "Wed 10:30"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 30 * day)),
+            "Jan 15"
+        );
+        assert_eq!(
+            format_time(&time, &format, utc(TIME + 365 * day)),
+            "Jan 15 2020"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        assert_eq!(
+            format_commit_time(i64::MAX, &DateFormat::Iso),
+            i64::MAX.to_string()
+        );
+    }
+}
@@ -1,18 +1,101 @@
 //! sync git api for fetching a diff
 
 use super::{
+    binary::BinaryPreview,
+    blob::{binary_attr, is_binary},
     commit_files::get_commit_diff,
+    filemode::mode_bits,
+    status::{get_status, ShowUntrackedFiles, StatusType},
     utils::{self, get_head_repo, work_dir},
     CommitId,
 };
 use crate::{error::Error, error::Result, hash};
 use git2::{
-    Delta, Diff, DiffDelta, DiffFormat, DiffHunk, DiffOptions, Patch,
+    Delta, Diff, DiffDelta, DiffFile, DiffFindOptions, DiffFormat,
+    DiffHunk, DiffOptions as GitDiffOptions, FileMode, Patch,
     Repository,
 };
 use scopetime::scope_time;
 use std::{cell::RefCell, fs, path::Path, rc::Rc};
 
+/// how a diff is made, hunks have to be staged or reset with the
+/// options they were shown with
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DiffOptions {
+    /// lines of unchanged context around changes, like
+    /// `git diff -U<n>`
+    pub context_lines: u32,
+    /// anything but `Show` is for display only, such diffs do not
+    /// apply to the files
+    pub whitespace: DiffWhitespace,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 3,
+            whitespace: DiffWhitespace::Show,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// these options showing all whitespace changes, only such
+    /// diffs apply to the files
+    pub(crate) const fn applicable(self) -> Self {
+        Self {
+            whitespace: DiffWhitespace::Show,
+            ..self
+        }
+    }
+
+    pub(crate) fn apply(self, opt: &mut GitDiffOptions) {
+        opt.context_lines(self.context_lines);
+        self.whitespace.apply(opt);
+    }
+}
+
+/// how whitespace changes show up in diffs
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiffWhitespace {
+    ///
+    Show,
+    /// `git diff --ignore-space-at-eol`
+    IgnoreEol,
+    /// `git diff -b`
+    IgnoreChange,
+    /// `git diff -w`
+    IgnoreAll,
+}
+
+impl DiffWhitespace {
+    /// the mode after this one, back to `Show` after the last
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Show => Self::IgnoreEol,
+            Self::IgnoreEol => Self::IgnoreChange,
+            Self::IgnoreChange => Self::IgnoreAll,
+            Self::IgnoreAll => Self::Show,
+        }
+    }
+
+    /// like `ignored at line ends`, reads after `whitespace`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Show => "shown",
+            Self::IgnoreEol => "ignored at line ends",
+            Self::IgnoreChange => "changes ignored",
+            Self::IgnoreAll => "ignored",
+        }
+    }
+
+    fn apply(self, opt: &mut GitDiffOptions) {
+        opt.ignore_whitespace_eol(self == Self::IgnoreEol);
+        opt.ignore_whitespace_change(self == Self::IgnoreChange);
+        opt.ignore_whitespace(self == Self::IgnoreAll);
+    }
+}
+
 /// type of diff of a single line
 #[derive(Copy, Clone, PartialEq, Hash, Debug)]
 pub enum DiffLineType {
@@ -82,6 +165,113 @@ pub struct FileDiff {
     pub sizes: (u64, u64),
     /// size delta in bytes
     pub size_delta: i64,
+    /// no hunks because git treats the content as binary
+    pub binary: bool,
+    /// compared across a rename, the hunks cannot be staged or reset
+    /// one by one
+    pub renamed: bool,
+    /// old and new mode (like `0o100755`) if they differ
+    pub modes: Option<(u32, u32)>,
+    /// what can be shown of a binary file instead of hunks
+    pub binary_preview: Option<BinaryPreview>,
+}
+
+const NO_NEWLINE: &str = "\\ No newline at end of file\n";
+
+impl FileDiff {
+    /// unified patch of `path` that `git apply` accepts, limited to
+    /// the hunk at index `hunk` if given
+    pub fn patch(&self, path: &str, hunk: Option<usize>) -> String {
+        let first_header = self
+            .hunks
+            .first()
+            .and_then(|hunk| hunk.lines.first())
+            .map(|line| line.content.as_str())
+            .unwrap_or_default();
+        let new_file = first_header.starts_with("@@ -0,0 ");
+        let deleted_file = first_header.contains(" +0,0 @@");
+
+        let mut patch = format!("diff --git a/{0} b/{0}\n", path);
+        if new_file {
+            patch.push_str("new file mode 100644\n");
+            patch.push_str("--- /dev/null\n");
+        } else {
+            if deleted_file {
+                patch.push_str("deleted file mode 100644\n");
+            }
+            patch.push_str(&format!("--- a/{}\n", path));
+        }
+        if deleted_file {
+            patch.push_str("+++ /dev/null\n");
+        } else {
+            patch.push_str(&format!("+++ b/{}\n", path));
+        }
+
+        let hunks =
+            self.hunks.iter().enumerate().filter(|(idx, _)| {
+                hunk.map_or(true, |hunk| hunk == *idx)
+            });
+
+        for (_, hunk) in hunks {
+            for line in &hunk.lines {
+                // marker git2 reports as a line of its own
+                if line.content.starts_with("\n\\") {
+                    continue;
+                }
+
+                patch.push_str(match line.line_type {
+                    DiffLineType::Header => "",
+                    DiffLineType::Add => "+",
+                    DiffLineType::Delete => "-",
+                    DiffLineType::None => " ",
+                });
+                patch.push_str(&line.content);
+
+                if !line.content.ends_with('\n') {
+                    patch.push('\n');
+                    patch.push_str(NO_NEWLINE);
+                }
+            }
+        }
+
+        patch
+    }
+
+    /// line number in the new version of the file at the diff line
+    /// `index` (counted over all hunks), deleted lines point at the
+    /// line that follows them
+    pub fn new_line_number(&self, index: usize) -> Option<usize> {
+        let mut cursor = 0;
+
+        for hunk in &self.hunks {
+            if index >= cursor + hunk.lines.len() {
+                cursor += hunk.lines.len();
+                continue;
+            }
+
+            // `@@ -old_start,old_lines +new_start,new_lines @@`
+            let mut line = hunk
+                .lines
+                .first()
+                .filter(|line| line.content.starts_with("@@ "))
+                .and_then(|line| line.content.split(" +").nth(1))
+                .and_then(|new| new.split(&[',', ' '][..]).next())
+                .and_then(|start| start.parse::<usize>().ok())?;
+
+            for diff_line in
+                hunk.lines.iter().take(index - cursor).skip(1)
+            {
+                if diff_line.line_type != DiffLineType::Delete {
+                    line += 1;
+                }
+            }
+
+            // `+0,0` of a deleted file
+            return Some(line.max(1));
+        }
+
+        None
+    }
 }
 
 pub(crate) fn get_diff_raw<'a>(
@@ -89,12 +279,14 @@ pub(crate) fn get_diff_raw<'a>(
     p: &str,
     stage: bool,
     reverse: bool,
+    options: DiffOptions,
 ) -> Result<Diff<'a>> {
     // scope_time!("get_diff_raw");
 
-    let mut opt = DiffOptions::new();
+    let mut opt = GitDiffOptions::new();
     opt.pathspec(p);
     opt.reverse(reverse);
+    options.apply(&mut opt);
 
     let diff = if stage {
         // diff against head
@@ -128,14 +320,199 @@ pub fn get_diff(
     repo_path: &str,
     p: String,
     stage: bool,
+    options: DiffOptions,
 ) -> Result<FileDiff> {
     scope_time!("get_diff");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo);
-    let diff = get_diff_raw(&repo, &p, stage, false)?;
+    let diff = get_diff_raw(&repo, &p, stage, false, options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&diff, &repo, options)
+}
+
+/// like `get_diff` for a file renamed from `old_path`, shows the
+/// changes of the content across the rename
+pub fn get_diff_renamed(
+    repo_path: &str,
+    old_path: &str,
+    p: String,
+    stage: bool,
+    options: DiffOptions,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_renamed");
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut opt = GitDiffOptions::new();
+    opt.pathspec(old_path).pathspec(&p);
+    options.apply(&mut opt);
+
+    let tree = match get_head_repo(&repo) {
+        Ok(id) => Some(repo.find_commit(id.into())?.tree()?),
+        // nothing committed yet
+        Err(_) => None,
+    };
+
+    let mut diff = if stage {
+        repo.diff_tree_to_index(tree.as_ref(), None, Some(&mut opt))?
+    } else {
+        opt.include_untracked(true);
+        opt.recurse_untracked_dirs(true);
+        repo.diff_index_to_workdir(None, Some(&mut opt))?
+    };
+    diff.find_similar(Some(
+        DiffFindOptions::new()
+            .renames(true)
+            .for_untracked(true)
+            .rename_threshold(0),
+    ))?;
+
+    let mut res = raw_diff_to_file_diff(&diff, &repo, options)?;
+    res.renamed =
+        diff.deltas().any(|delta| delta.status() == Delta::Renamed);
+
+    Ok(res)
+}
+
+/// returns the diff of all staged files combined (like `git commit -v`),
+/// each file is introduced by a header line containing its path
+pub fn get_diff_staged(
+    repo_path: &str,
+    options: DiffOptions,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_staged");
+
+    let mut res = FileDiff::default();
+
+    for item in get_status(
+        repo_path,
+        StatusType::Stage,
+        ShowUntrackedFiles::No,
+    )? {
+        let diff =
+            get_diff(repo_path, item.path.clone(), true, options)?;
+
+        let mut lines = vec![DiffLine {
+            content: format!("{}\n", item.path),
+            line_type: DiffLineType::Header,
+        }];
+        if diff.binary {
+            lines.push(DiffLine {
+                content: String::from("Binary files differ\n"),
+                line_type: DiffLineType::None,
+            });
+        }
+
+        res.lines += diff.lines + lines.len();
+        res.hunks.push(Hunk {
+            header_hash: hash(&item.path),
+            lines,
+        });
+        res.sizes =
+            (res.sizes.0 + diff.sizes.0, res.sizes.1 + diff.sizes.1);
+        res.size_delta += diff.size_delta;
+        res.hunks.extend(diff.hunks);
+    }
+
+    Ok(res)
+}
+
+/// everything that is staged compared to `HEAD` with renames and
+/// copies detected
+fn get_diff_staged_raw(repo: &Repository) -> Result<Diff<'_>> {
+    let tree = match get_head_repo(repo) {
+        Ok(id) => Some(repo.find_commit(id.into())?.tree()?),
+        // nothing committed yet
+        Err(_) => None,
+    };
+
+    let mut diff = repo.diff_tree_to_index(
+        tree.as_ref(),
+        Some(&repo.index()?),
+        None,
+    )?;
+    diff.find_similar(Some(
+        DiffFindOptions::new().renames(true).copies(true),
+    ))?;
+
+    Ok(diff)
+}
+
+/// unified patch of what a commit would contain right now,
+/// including renames and mode changes (like `git diff --cached -M -C`)
+pub fn get_staged_patch(repo_path: &str) -> Result<String> {
+    scope_time!("get_staged_patch");
+
+    let repo = utils::repo(repo_path)?;
+    let diff = get_diff_staged_raw(&repo)?;
+
+    let mut patch = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        let origin = line.origin();
+        if matches!(origin, '+' | '-' | ' ') {
+            patch.push(origin);
+        }
+        patch.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+
+    Ok(patch)
+}
+
+/// `get_staged_patch` as a single `FileDiff`, the file headers
+/// (paths, renames, modes) become header lines of their own hunk
+pub fn get_staged_preview(repo_path: &str) -> Result<FileDiff> {
+    scope_time!("get_staged_preview");
+
+    let repo = utils::repo(repo_path)?;
+    let diff = get_diff_staged_raw(&repo)?;
+
+    let mut res = FileDiff::default();
+    diff.print(DiffFormat::Patch, |delta, _, line| {
+        let content = String::from_utf8_lossy(line.content());
+
+        match line.origin() {
+            'F' | 'H' => {
+                if line.origin() == 'F' {
+                    res.sizes = (
+                        res.sizes.0 + delta.old_file().size(),
+                        res.sizes.1 + delta.new_file().size(),
+                    );
+                    res.binary |= delta.flags().is_binary();
+                }
+
+                res.hunks.push(Hunk {
+                    header_hash: hash(&content),
+                    lines: content
+                        .lines()
+                        .map(|line| DiffLine {
+                            content: format!("{}\n", line),
+                            line_type: DiffLineType::Header,
+                        })
+                        .collect(),
+                });
+            }
+            origin => {
+                if let Some(hunk) = res.hunks.last_mut() {
+                    hunk.lines.push(DiffLine {
+                        content: content.into_owned(),
+                        line_type: match origin {
+                            '<' | '-' => DiffLineType::Delete,
+                            '>' | '+' => DiffLineType::Add,
+                            _ => DiffLineType::None,
+                        },
+                    });
+                }
+            }
+        }
+        true
+    })?;
+
+    res.lines = res.hunks.iter().map(|h| h.lines.len()).sum();
+    res.size_delta =
+        (res.sizes.1 as i64).saturating_sub(res.sizes.0 as i64);
+
+    Ok(res)
 }
 
 /// returns diff of a specific file inside a commit
@@ -144,20 +521,21 @@ pub fn get_diff_commit(
     repo_path: &str,
     id: CommitId,
     p: String,
+    options: DiffOptions,
 ) -> Result<FileDiff> {
     scope_time!("get_diff_commit");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo);
-    let diff = get_commit_diff(&repo, id, Some(p))?;
+    let diff = get_commit_diff(&repo, id, Some(p), options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&diff, &repo, options)
 }
 
 ///
 fn raw_diff_to_file_diff<'a>(
     diff: &'a Diff,
-    work_dir: &Path,
+    repo: &Repository,
+    options: DiffOptions,
 ) -> Result<FileDiff> {
     let res = Rc::new(RefCell::new(FileDiff::default()));
     {
@@ -187,6 +565,7 @@ fn raw_diff_to_file_diff<'a>(
                 );
                 res.size_delta = (res.sizes.1 as i64)
                     .saturating_sub(res.sizes.0 as i64);
+                res.binary |= delta.flags().is_binary();
             }
             if let Some(hunk) = hunk {
                 let hunk_header = HunkHeader::from(hunk);
@@ -233,24 +612,48 @@ fn raw_diff_to_file_diff<'a>(
                         )
                     })?;
 
-                let newfile_path = work_dir.join(relative_path);
+                let newfile_path =
+                    work_dir(repo)?.join(relative_path);
 
                 if let Some(newfile_content) =
                     new_file_content(&newfile_path)
                 {
-                    let mut patch = Patch::from_buffers(
-                        &[],
-                        None,
-                        newfile_content.as_slice(),
-                        Some(&newfile_path),
-                        None,
-                    )?;
-
-                    patch
-                    .print(&mut |delta, hunk:Option<DiffHunk>, line: git2::DiffLine| {
-                        put(delta,hunk,line);
-                        true
-                    })?;
+                    // without a repo the patch cannot look up the
+                    // attributes itself
+                    let attr = binary_attr(repo, relative_path)?;
+                    let binary = attr.unwrap_or_else(|| {
+                        is_binary(&newfile_content)
+                    });
+
+                    if binary {
+                        let mut res = res.borrow_mut();
+                        res.binary = true;
+                        res.binary_preview =
+                            Some(BinaryPreview::new(
+                                None,
+                                Some(&newfile_content),
+                            ));
+                        res.sizes = (0, newfile_content.len() as u64);
+                        res.size_delta = res.sizes.1 as i64;
+                    } else {
+                        let mut opt = GitDiffOptions::new();
+                        opt.force_text(attr == Some(false));
+                        opt.context_lines(options.context_lines);
+
+                        let mut patch = Patch::from_buffers(
+                            &[],
+                            None,
+                            newfile_content.as_slice(),
+                            Some(&newfile_path),
+                            Some(&mut opt),
+                        )?;
+
+                        patch
+                        .print(&mut |delta, hunk:Option<DiffHunk>, line: git2::DiffLine| {
+                            put(delta,hunk,line);
+                            true
+                        })?;
+                    }
 
                     true
                 } else {
@@ -282,12 +685,57 @@ fn raw_diff_to_file_diff<'a>(
 
         if new_file_diff {
             res.borrow_mut().untracked = true;
+        } else if diff.deltas().len() == 1 {
+            if res.borrow().binary {
+                res.borrow_mut().binary_preview =
+                    diff.deltas().next().map(|delta| {
+                        BinaryPreview::new(
+                            side_content(repo, &delta.old_file())
+                                .as_deref(),
+                            side_content(repo, &delta.new_file())
+                                .as_deref(),
+                        )
+                    });
+            }
+
+            res.borrow_mut().modes =
+                diff.deltas().next().and_then(|delta| {
+                    let old = delta.old_file().mode();
+                    let new = delta.new_file().mode();
+                    if old == new
+                        || old == FileMode::Unreadable
+                        || new == FileMode::Unreadable
+                    {
+                        None
+                    } else {
+                        Some((mode_bits(old), mode_bits(new)))
+                    }
+                });
         }
     }
     let res = Rc::try_unwrap(res).expect("rc error");
     Ok(res.into_inner())
 }
 
+/// content of a side of a delta, the work dir file if it is not in
+/// the object database
+fn side_content(
+    repo: &Repository,
+    file: &DiffFile,
+) -> Option<Vec<u8>> {
+    if !file.exists() {
+        return None;
+    }
+
+    repo.find_blob(file.id())
+        .map(|blob| blob.content().to_vec())
+        .ok()
+        .or_else(|| {
+            let path = work_dir(repo).ok()?.join(file.path()?);
+            fs::read(path).ok()
+        })
+}
+
 fn new_file_content(path: &Path) -> Option<Vec<u8>> {
     if let Ok(meta) = fs::symlink_metadata(path) {
         if meta.file_type().is_symlink() {
@@ -308,12 +756,17 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_diff, get_diff_commit};
+    use super::{
+        get_diff, get_diff_commit, get_diff_raw, get_diff_staged,
+        get_staged_patch, get_staged_preview, DiffLineType,
+        DiffOptions, DiffWhitespace,
+    };
     use crate::error::Result;
     use crate::sync::{
-        commit, stage_add_file,
-        status::{get_status, StatusType},
+        commit, stage_add_file, stage_addremoved, stage_hunk,
+        status::{get_status, ShowUntrackedFiles, StatusType},
         tests::{get_statuses, repo_init, repo_init_empty},
+        BinarySide,
     };
     use std::{
         fs::{self, File},
@@ -337,12 +790,17 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        let diff =
-            get_diff(repo_path, "foo/bar.txt".to_string(), false)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            "foo/bar.txt".to_string(),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
         assert_eq!(diff.hunks[0].lines[1].content, "test\n");
+        assert!(!diff.binary);
     }
 
     #[test]
@@ -369,12 +827,107 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             true,
+            DiffOptions::default(),
         )
         .unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
     }
 
+    #[test]
+    fn test_diff_staged() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(get_diff_staged(repo_path, DiffOptions::default())
+            .unwrap()
+            .hunks
+            .is_empty());
+
+        File::create(&root.join("a.txt"))
+            .unwrap()
+            .write_all(b"a\n")
+            .unwrap();
+        File::create(&root.join("b.txt"))
+            .unwrap()
+            .write_all(b"b\n")
+            .unwrap();
+        File::create(&root.join("c.bin"))
+            .unwrap()
+            .write_all(b"\x00\x01")
+            .unwrap();
+        File::create(&root.join("unstaged.txt"))
+            .unwrap()
+            .write_all(b"c\n")
+            .unwrap();
+
+        stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+        stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+        stage_add_file(repo_path, Path::new("c.bin")).unwrap();
+
+        let diff = get_diff_staged(repo_path, DiffOptions::default())
+            .unwrap();
+
+        // a path header and a single hunk per staged text file
+        assert_eq!(diff.hunks.len(), 5);
+        assert_eq!(diff.hunks[0].lines[0].content, "a.txt\n");
+        assert_eq!(diff.hunks[2].lines[0].content, "b.txt\n");
+        assert_eq!(
+            diff.hunks[4].lines[1].content,
+            "Binary files differ\n"
+        );
+        assert_eq!(
+            diff.lines,
+            diff.hunks.iter().map(|h| h.lines.len()).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_staged_patch() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content = b"1\n2\n3\n4\n5\n6\n7\n8\n";
+        File::create(root.join("old.txt"))?.write_all(content)?;
+        File::create(root.join("run.sh"))?.write_all(b"true\n")?;
+        stage_add_file(repo_path, Path::new("old.txt"))?;
+        stage_add_file(repo_path, Path::new("run.sh"))?;
+        commit(repo_path, "files")?;
+
+        assert!(get_staged_patch(repo_path)?.is_empty());
+
+        fs::rename(root.join("old.txt"), root.join("new.txt"))?;
+        stage_addremoved(repo_path, Path::new("old.txt"))?;
+        stage_add_file(repo_path, Path::new("new.txt"))?;
+
+        let mut index = repo.index()?;
+        index.read(true)?;
+        let mut entry =
+            index.get_path(Path::new("run.sh"), 0).unwrap();
+        entry.mode = 0o100_755;
+        index.add(&entry)?;
+        index.write()?;
+
+        let patch = get_staged_patch(repo_path)?;
+        assert!(
+            patch.contains("rename from old.txt\nrename to new.txt")
+        );
+        assert!(patch.contains("old mode 100644\nnew mode 100755"));
+        assert!(!patch.contains("deleted file"));
+
+        let preview = get_staged_preview(repo_path)?;
+        assert_eq!(preview.hunks.len(), 2);
+        assert!(preview.hunks.iter().all(|hunk| hunk
+            .lines
+            .iter()
+            .all(|line| line.line_type == DiffLineType::Header)));
+        assert_eq!(preview.lines, patch.lines().count(),);
+
+        Ok(())
+    }
+
     static HUNK_A: &str = r"
 1   start
 2
@@ -418,8 +971,12 @@ mod tests {
                 .unwrap();
         }
 
-        let res = get_status(repo_path, StatusType::WorkingDir, true)
-            .unwrap();
+        let res = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::All,
+        )
+        .unwrap();
         assert_eq!(res.len(), 1);
         assert_eq!(res[0].path, "bar.txt");
 
@@ -436,12 +993,164 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 1));
 
-        let res = get_diff(repo_path, "bar.txt".to_string(), false)
-            .unwrap();
+        let res = get_diff(
+            repo_path,
+            "bar.txt".to_string(),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
 
         assert_eq!(res.hunks.len(), 2)
     }
 
+    #[test]
+    fn test_context_lines() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file_path = root.join("bar.txt");
+
+        fs::write(&file_path, HUNK_A)?;
+        stage_add_file(repo_path, Path::new("bar.txt"))?;
+        fs::write(&file_path, HUNK_B)?;
+
+        let narrow = DiffOptions {
+            context_lines: 0,
+            ..DiffOptions::default()
+        };
+        let diff = get_diff(
+            repo_path,
+            "bar.txt".to_string(),
+            false,
+            narrow,
+        )?;
+        assert_eq!(diff.hunks.len(), 2);
+        assert_eq!(diff.hunks[0].lines.len(), 3);
+
+        let wide = DiffOptions {
+            context_lines: 5,
+            ..DiffOptions::default()
+        };
+        let diff =
+            get_diff(repo_path, "bar.txt".to_string(), false, wide)?;
+        assert_eq!(diff.hunks.len(), 1);
+
+        stage_hunk(
+            repo_path,
+            "bar.txt".to_string(),
+            diff.hunks[0].header_hash,
+            wide,
+        )?;
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_patch_applies() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file_path = root.join("bar.txt");
+
+        fs::write(&file_path, HUNK_A).unwrap();
+        stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+        fs::write(&file_path, HUNK_B).unwrap();
+
+        let diff = get_diff(
+            repo_path,
+            "bar.txt".to_string(),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
+        let patch = diff.patch("bar.txt", None);
+        assert!(patch.starts_with(
+            "diff --git a/bar.txt b/bar.txt\n--- a/bar.txt\n"
+        ));
+        assert_eq!(
+            diff.patch("bar.txt", Some(1)).matches("@@ -").count(),
+            1
+        );
+
+        fs::write(&file_path, HUNK_A).unwrap();
+        repo.apply(
+            &git2::Diff::from_buffer(patch.as_bytes()).unwrap(),
+            git2::ApplyLocation::WorkDir,
+            None,
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), HUNK_B);
+    }
+
+    #[test]
+    fn test_new_line_number() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file_path = root.join("bar.txt");
+
+        fs::write(&file_path, "a\nb\nc\nd\n").unwrap();
+        stage_add_file(repo_path, Path::new("bar.txt")).unwrap();
+        fs::write(&file_path, "a\nx\ny\nc\nd\n").unwrap();
+
+        let diff = get_diff(
+            repo_path,
+            "bar.txt".to_string(),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
+        let lines = diff
+            .hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .map(|line| line.content.trim_end())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            vec!["@@ -1,4 +1,5 @@", "a", "b", "x", "y", "c", "d"]
+        );
+
+        assert_eq!(diff.new_line_number(0), Some(1));
+        assert_eq!(diff.new_line_number(1), Some(1));
+        // the deleted `b` was where `x` is now
+        assert_eq!(diff.new_line_number(2), Some(2));
+        assert_eq!(diff.new_line_number(4), Some(3));
+        assert_eq!(diff.new_line_number(6), Some(5));
+        assert_eq!(diff.new_line_number(7), None);
+    }
+
+    #[test]
+    fn test_patch_new_file() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file_path = root.join("foo.txt");
+
+        fs::write(&file_path, "test").unwrap();
+
+        let diff = get_diff(
+            repo_path,
+            "foo.txt".to_string(),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
+        let patch = diff.patch("foo.txt", None);
+        assert!(patch.contains("--- /dev/null\n+++ b/foo.txt\n"));
+
+        fs::remove_file(&file_path).unwrap();
+        repo.apply(
+            &git2::Diff::from_buffer(patch.as_bytes()).unwrap(),
+            git2::ApplyLocation::WorkDir,
+            None,
+        )
+        .unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "test");
+    }
+
     #[test]
     fn test_diff_newfile_in_sub_dir_current_dir() {
         let file_path = Path::new("foo/foo.txt");
@@ -460,12 +1169,51 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            DiffOptions::default(),
         )
         .unwrap();
 
         assert_eq!(diff.hunks[0].lines[1].content, "test");
     }
 
+    #[test]
+    fn test_diff_whitespace() -> Result<()> {
+        let file_path = Path::new("bar");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join(file_path))?.write_all(b"a b\n")?;
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "commit").unwrap();
+
+        File::create(root.join(file_path))?.write_all(b"a  b \n")?;
+
+        let insertions = |whitespace| {
+            get_diff_raw(
+                &repo,
+                "bar",
+                false,
+                false,
+                DiffOptions {
+                    whitespace,
+                    ..DiffOptions::default()
+                },
+            )
+            .unwrap()
+            .stats()
+            .unwrap()
+            .insertions()
+        };
+
+        assert_eq!(insertions(DiffWhitespace::Show), 1);
+        assert_eq!(insertions(DiffWhitespace::IgnoreEol), 1);
+        assert_eq!(insertions(DiffWhitespace::IgnoreChange), 0);
+        assert_eq!(insertions(DiffWhitespace::IgnoreAll), 0);
+
+        Ok(())
+    }
+
     #[test]
     fn test_diff_delta_size() -> Result<()> {
         let file_path = Path::new("bar");
@@ -486,12 +1234,14 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            DiffOptions::default(),
         )
         .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (1, 2));
         assert_eq!(diff.size_delta, 1);
+        assert!(diff.binary);
 
         Ok(())
     }
@@ -510,12 +1260,50 @@ mod tests {
             repo_path,
             String::from(file_path.to_str().unwrap()),
             false,
+            DiffOptions::default(),
         )
         .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (0, 2));
         assert_eq!(diff.size_delta, 2);
+        assert!(diff.binary);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_preview_of_image() -> Result<()> {
+        let file_path = Path::new("pic.gif");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?
+            .write_all(b"GIF89a\x02\x00\x01\x00\x00")?;
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "").unwrap();
+
+        let bigger = b"GIF89a\x04\x00\x03\x00\x00";
+        File::create(&root.join(file_path))?.write_all(bigger)?;
+
+        let diff = get_diff(
+            repo_path,
+            String::from(file_path.to_str().unwrap()),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
+
+        assert!(diff.binary);
+        let preview = diff.binary_preview.unwrap();
+        let size = |side: Option<BinarySide>| {
+            side.and_then(|side| side.image)
+                .map(|image| (image.width, image.height))
+        };
+        assert_eq!(size(preview.old), Some((2, 1)));
+        assert_eq!(size(preview.new), Some((4, 3)));
+        assert_eq!(preview.image.as_deref(), Some(&bigger[..]));
 
         Ok(())
     }
@@ -540,12 +1328,57 @@ mod tests {
 
         let id = commit(repo_path, "").unwrap();
 
-        let diff =
-            get_diff_commit(repo_path, id, String::new()).unwrap();
+        let diff = get_diff_commit(
+            repo_path,
+            id,
+            String::new(),
+            DiffOptions::default(),
+        )
+        .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (1, 2));
         assert_eq!(diff.size_delta, 1);
+        assert!(diff.binary);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_by_gitattributes() -> Result<()> {
+        let file_path = Path::new("foo.dat");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(".gitattributes"))?
+            .write_all(b"*.dat binary\n")?;
+        File::create(&root.join(file_path))?.write_all(b"test\n")?;
+
+        let diff = get_diff(
+            repo_path,
+            String::from("foo.dat"),
+            false,
+            DiffOptions::default(),
+        )?;
+
+        assert!(diff.binary);
+        assert_eq!(diff.hunks.len(), 0);
+
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "commit")?;
+
+        File::create(&root.join(file_path))?.write_all(b"test2\n")?;
+
+        let diff = get_diff(
+            repo_path,
+            String::from("foo.dat"),
+            false,
+            DiffOptions::default(),
+        )?;
+
+        assert!(diff.binary);
+        assert_eq!(diff.hunks.len(), 0);
 
         Ok(())
     }
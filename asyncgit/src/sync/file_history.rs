@@ -0,0 +1,161 @@
+//! commits touching a single file, optionally following renames
+//! (like `git log --follow`)
+
+use super::{utils::repo, CommitId};
+use crate::error::Result;
+use git2::{Delta, DiffFindOptions, Oid, Repository, Sort, Tree};
+use scopetime::scope_time;
+use std::path::Path;
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileHistoryEntry {
+    ///
+    pub id: CommitId,
+    /// path of the file in this commit
+    pub path: String,
+}
+
+/// returns the commits reachable from `HEAD` that changed `path`,
+/// newest first. with `follow` the history continues under the
+/// old path once a commit renamed the file.
+pub fn get_file_history(
+    repo_path: &str,
+    path: &str,
+    follow: bool,
+) -> Result<Vec<FileHistoryEntry>> {
+    scope_time!("get_file_history");
+
+    let repo = repo(repo_path)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    walk.push_head()?;
+
+    let mut path = String::from(path);
+    let mut res = Vec::new();
+
+    for id in walk {
+        let commit = repo.find_commit(id?)?;
+        let tree = commit.tree()?;
+
+        let current = entry_id(&tree, &path);
+        if current.is_none() {
+            continue;
+        }
+
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let previous =
+            parent_tree.as_ref().and_then(|t| entry_id(t, &path));
+        if previous == current {
+            continue;
+        }
+
+        res.push(FileHistoryEntry {
+            id: CommitId::new(commit.id()),
+            path: path.clone(),
+        });
+
+        if follow && previous.is_none() {
+            if let Some(parent_tree) = parent_tree {
+                if let Some(old_path) =
+                    renamed_from(&repo, &parent_tree, &tree, &path)?
+                {
+                    path = old_path;
+                }
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+fn entry_id(tree: &Tree, path: &str) -> Option<Oid> {
+    tree.get_path(Path::new(path)).ok().map(|entry| entry.id())
+}
+
+/// the path `path` was renamed from between `old` and `new`
+fn renamed_from(
+    repo: &Repository,
+    old: &Tree,
+    new: &Tree,
+    path: &str,
+) -> Result<Option<String>> {
+    let mut diff =
+        repo.diff_tree_to_tree(Some(old), Some(new), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    Ok(diff
+        .deltas()
+        .filter(|delta| delta.status() == Delta::Renamed)
+        .filter(|delta| {
+            delta.new_file().path() == Some(Path::new(path))
+        })
+        .find_map(|delta| delta.old_file().path())
+        .and_then(Path::to_str)
+        .map(String::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file, stage_addremoved,
+        tests::repo_init_empty,
+    };
+    use std::{
+        fs::{self, File},
+        io::Write,
+    };
+
+    #[test]
+    fn test_follow_rename() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |path: &str, content: &str| -> Result<()> {
+            File::create(root.join(path))?
+                .write_all(content.as_bytes())?;
+            stage_add_file(repo_path, Path::new(path))?;
+            Ok(())
+        };
+
+        write("foo", "line 1\nline 2\nline 3\n")?;
+        let c1 = commit(repo_path, "add foo")?;
+
+        write("foo", "line 1\nline 2\nline 3\nline 4\n")?;
+        let c2 = commit(repo_path, "change foo")?;
+
+        write("other", "other")?;
+        commit(repo_path, "unrelated")?;
+
+        fs::rename(root.join("foo"), root.join("bar"))?;
+        stage_addremoved(repo_path, Path::new("foo"))?;
+        stage_add_file(repo_path, Path::new("bar"))?;
+        let c3 = commit(repo_path, "rename")?;
+
+        write("bar", "line 1\nline 2\nline 3\nline 4\nline 5\n")?;
+        let c4 = commit(repo_path, "change bar")?;
+
+        let ids = |res: Vec<FileHistoryEntry>| {
+            res.into_iter().map(|e| e.id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(get_file_history(repo_path, "bar", false)?),
+            vec![c4, c3]
+        );
+
+        let res = get_file_history(repo_path, "bar", true)?;
+        assert_eq!(ids(res.clone()), vec![c4, c3, c2, c1]);
+        assert_eq!(res[1].path, "bar");
+        assert_eq!(res[2].path, "foo");
+
+        Ok(())
+    }
+}
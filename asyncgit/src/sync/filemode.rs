@@ -0,0 +1,211 @@
+//! permission only changes (`old mode 100644`/`new mode 100755`),
+//! staged and unstaged apart from the content
+
+use super::{
+    diff::{get_diff_raw, DiffOptions},
+    utils::{repo, work_dir},
+};
+use crate::error::{Error, Result};
+use git2::{FileMode, Repository};
+use scopetime::scope_time;
+use std::{convert::TryFrom, path::Path};
+
+/// mode of a regular file
+const MODE_FILE: u32 = 0o100_644;
+/// mode of an executable file
+const MODE_EXECUTABLE: u32 = 0o100_755;
+
+/// the bits git writes for `mode`
+pub(crate) const fn mode_bits(mode: FileMode) -> u32 {
+    match mode {
+        FileMode::Unreadable => 0,
+        FileMode::Tree => 0o040_000,
+        FileMode::Blob => MODE_FILE,
+        FileMode::BlobExecutable => MODE_EXECUTABLE,
+        FileMode::Link => 0o120_000,
+        FileMode::Commit => 0o160_000,
+    }
+}
+
+fn set_index_mode(
+    repo: &Repository,
+    path: &str,
+    mode: u32,
+) -> Result<()> {
+    let mut index = repo.index()?;
+    let mut entry =
+        index.get_path(Path::new(path), 0).ok_or_else(|| {
+            Error::Generic(format!("{} is not tracked", path))
+        })?;
+
+    entry.mode = mode;
+    index.add(&entry)?;
+    index.write()?;
+
+    Ok(())
+}
+
+/// stages the mode `path` has in the work dir, not its content
+pub fn stage_mode_change(repo_path: &str, path: &str) -> Result<()> {
+    scope_time!("stage_mode_change");
+
+    let repo = repo(repo_path)?;
+    let diff = get_diff_raw(
+        &repo,
+        path,
+        false,
+        false,
+        DiffOptions::default(),
+    )?;
+    let mode = diff
+        .deltas()
+        .next()
+        .map(|delta| mode_bits(delta.new_file().mode()))
+        .ok_or_else(|| {
+            Error::Generic(format!("{} has no changes", path))
+        })?;
+
+    set_index_mode(&repo, path, mode)
+}
+
+/// puts the mode of head back into the index, keeping the content
+pub fn unstage_mode_change(
+    repo_path: &str,
+    path: &str,
+) -> Result<()> {
+    scope_time!("unstage_mode_change");
+
+    let repo = repo(repo_path)?;
+    let entry =
+        repo.head()?.peel_to_tree()?.get_path(Path::new(path))?;
+    let mode = u32::try_from(entry.filemode()).map_err(|_| {
+        Error::Generic(format!("invalid mode of {}", path))
+    })?;
+
+    set_index_mode(&repo, path, mode)
+}
+
+/// flips the executable bit of the work dir file, `None` where the
+/// file system has none
+#[cfg(unix)]
+fn toggle_work_file(file: &Path) -> Result<Option<bool>> {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let mut permissions = fs::metadata(file)?.permissions();
+    let mode = permissions.mode();
+    let executable = mode & 0o111 == 0;
+    permissions.set_mode(if executable {
+        // executable for whoever may read it
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    });
+    fs::set_permissions(file, permissions)?;
+
+    Ok(Some(executable))
+}
+
+#[cfg(not(unix))]
+fn toggle_work_file(_file: &Path) -> Result<Option<bool>> {
+    Ok(None)
+}
+
+/// makes `path` executable or not anymore in the work dir and in the
+/// index too if `index` is set, returns whether it is executable now
+pub fn toggle_executable(
+    repo_path: &str,
+    path: &str,
+    index: bool,
+) -> Result<bool> {
+    scope_time!("toggle_executable");
+
+    let repo = repo(repo_path)?;
+    let executable =
+        match toggle_work_file(&work_dir(&repo)?.join(path))? {
+            Some(executable) => executable,
+            None if index => repo
+                .index()?
+                .get_path(Path::new(path), 0)
+                .map_or(true, |entry| entry.mode != MODE_EXECUTABLE),
+            None => {
+                return Err(Error::Generic(String::from(
+                    "the work dir has no executable bit here",
+                )))
+            }
+        };
+
+    if index {
+        set_index_mode(
+            &repo,
+            path,
+            if executable {
+                MODE_EXECUTABLE
+            } else {
+                MODE_FILE
+            },
+        )?;
+    }
+
+    Ok(executable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, diff::get_diff, stage_add_file, tests::repo_init,
+    };
+    use std::fs;
+
+    #[test]
+    #[cfg(unix)]
+    fn test_mode_change() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join("run.sh"), "echo hi\n").unwrap();
+        stage_add_file(repo_path, Path::new("run.sh")).unwrap();
+        commit(repo_path, "add run.sh").unwrap();
+
+        let modes = |stage| {
+            get_diff(
+                repo_path,
+                String::from("run.sh"),
+                stage,
+                DiffOptions::default(),
+            )
+            .unwrap()
+            .modes
+        };
+
+        assert!(
+            toggle_executable(repo_path, "run.sh", false).unwrap()
+        );
+        let diff = get_diff(
+            repo_path,
+            String::from("run.sh"),
+            false,
+            DiffOptions::default(),
+        )
+        .unwrap();
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.modes, Some((MODE_FILE, MODE_EXECUTABLE)));
+        assert_eq!(modes(true), None);
+
+        stage_mode_change(repo_path, "run.sh").unwrap();
+        assert_eq!(modes(false), None);
+        assert_eq!(modes(true), Some((MODE_FILE, MODE_EXECUTABLE)));
+
+        unstage_mode_change(repo_path, "run.sh").unwrap();
+        assert_eq!(modes(false), Some((MODE_FILE, MODE_EXECUTABLE)));
+        assert_eq!(modes(true), None);
+
+        // back to where it was, in both places
+        assert!(
+            !toggle_executable(repo_path, "run.sh", true).unwrap()
+        );
+        assert_eq!(modes(false), None);
+        assert_eq!(modes(true), None);
+    }
+}
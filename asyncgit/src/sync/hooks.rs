@@ -1,39 +1,186 @@
-use super::utils::{repo, work_dir};
+use super::{utils::repo, CommitId};
 use crate::error::{Error, Result};
 use scopetime::scope_time;
 use std::{
-    fs::File,
-    io::{Read, Write},
+    fs::{self, File},
+    io::{BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
-    process::Command,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-const HOOK_POST_COMMIT: &str = ".git/hooks/post-commit";
-const HOOK_COMMIT_MSG: &str = ".git/hooks/commit-msg";
-const HOOK_COMMIT_MSG_TEMP_FILE: &str = ".git/COMMIT_EDITMSG";
+const HOOK_POST_COMMIT: &str = "post-commit";
+const HOOK_PRE_COMMIT: &str = "pre-commit";
+const HOOK_COMMIT_MSG: &str = "commit-msg";
+const HOOK_PREPARE_COMMIT_MSG: &str = "prepare-commit-msg";
+const HOOK_PRE_PUSH: &str = "pre-push";
+const HOOK_POST_CHECKOUT: &str = "post-checkout";
+const HOOK_POST_MERGE: &str = "post-merge";
+const HOOK_PRE_REBASE: &str = "pre-rebase";
+const HOOK_POST_REWRITE: &str = "post-rewrite";
+const HOOK_APPLYPATCH_MSG: &str = "applypatch-msg";
+const HOOK_PRE_APPLYPATCH: &str = "pre-applypatch";
+const HOOK_POST_APPLYPATCH: &str = "post-applypatch";
+const HOOK_PRE_AUTO_GC: &str = "pre-auto-gc";
+const FSMONITOR_HOOK: &str = "core.fsmonitor";
+const FSMONITOR_HOOK_VERSION: &str = "core.fsmonitorHookVersion";
+/// the hooks gitui runs itself or git runs on its behalf
+const HOOKS: [&str; 13] = [
+    HOOK_PRE_COMMIT,
+    HOOK_PREPARE_COMMIT_MSG,
+    HOOK_COMMIT_MSG,
+    HOOK_POST_COMMIT,
+    HOOK_PRE_REBASE,
+    HOOK_POST_REWRITE,
+    HOOK_POST_CHECKOUT,
+    HOOK_POST_MERGE,
+    HOOK_PRE_PUSH,
+    HOOK_APPLYPATCH_MSG,
+    HOOK_PRE_APPLYPATCH,
+    HOOK_POST_APPLYPATCH,
+    HOOK_PRE_AUTO_GC,
+];
+
+const HOOK_COMMIT_MSG_TEMP_FILE: &str = "COMMIT_EDITMSG";
+const HOOKS_DIR: &str = "hooks";
+const HOOK_CANCELLED: &str = "hook cancelled";
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// this hook is documented here https://git-scm.com/docs/githooks#_commit_msg
 /// we use the same convention as other git clients to create a temp file containing
-/// the commit message at `.git/COMMIT_EDITMSG` and pass it's relative path as the only
-/// parameter to the hook script.
+/// the commit message at `COMMIT_EDITMSG` inside the git dir and pass its path as the
+/// only parameter to the hook script.
 pub fn hooks_commit_msg(
     repo_path: &str,
     msg: &mut String,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    hooks_commit_msg_streamed(
+        repo_path,
+        msg,
+        timeout,
+        &AtomicBool::new(false),
+        |_| (),
+    )
+}
+
+/// like `hooks_commit_msg` but passes every line the hook prints to
+/// `on_line` while it runs, setting `cancel` kills the hook
+pub fn hooks_commit_msg_streamed<F: FnMut(&str)>(
+    repo_path: &str,
+    msg: &mut String,
+    timeout: Option<Duration>,
+    cancel: &AtomicBool,
+    on_line: F,
 ) -> Result<HookResult> {
     scope_time!("hooks_commit_msg");
 
-    let work_dir = work_dir_as_string(repo_path)?;
+    run_msg_hook(
+        &HookEnv::new(repo_path, true, timeout)?,
+        HOOK_COMMIT_MSG,
+        &[],
+        msg,
+        cancel,
+        on_line,
+    )
+}
+
+/// where the message passed to `prepare-commit-msg` comes from
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PrepareCommitMsgSource {
+    /// given by the user (`-m`)
+    Message,
+    /// `commit.template`
+    Template,
+    /// `MERGE_MSG` of a merge in progress
+    Merge,
+    /// `SQUASH_MSG`
+    Squash,
+    /// message of an existing commit (`--amend`, `-c`)
+    Commit(CommitId),
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_prepare_commit_msg
+/// it runs before the message gets edited and may alter it, gets
+/// the message file, the `source` of the message and the commit id
+/// for `PrepareCommitMsgSource::Commit`. a `NotOk` has to abort the
+/// commit
+pub fn hooks_prepare_commit_msg(
+    repo_path: &str,
+    source: PrepareCommitMsgSource,
+    msg: &mut String,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_prepare_commit_msg");
+
+    let id;
+    let args: &[&str] = match source {
+        PrepareCommitMsgSource::Message => &["message"],
+        PrepareCommitMsgSource::Template => &["template"],
+        PrepareCommitMsgSource::Merge => &["merge"],
+        PrepareCommitMsgSource::Squash => &["squash"],
+        PrepareCommitMsgSource::Commit(commit) => {
+            id = commit.to_string();
+            &["commit", &id]
+        }
+    };
+
+    run_msg_hook(
+        &HookEnv::new(repo_path, true, timeout)?,
+        HOOK_PREPARE_COMMIT_MSG,
+        args,
+        msg,
+        &AtomicBool::new(false),
+        |_| (),
+    )
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_applypatch_msg
+/// it gets the message of the patch about to be applied the same way
+/// `commit-msg` does and may alter it, a `NotOk` has to abort
+/// applying the patch
+pub fn hooks_applypatch_msg(
+    repo_path: &str,
+    msg: &mut String,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_applypatch_msg");
+
+    run_msg_hook(
+        &HookEnv::new(repo_path, false, timeout)?,
+        HOOK_APPLYPATCH_MSG,
+        &[],
+        msg,
+        &AtomicBool::new(false),
+        |_| (),
+    )
+}
 
-    if hook_runable(work_dir.as_str(), HOOK_COMMIT_MSG) {
-        let temp_file = Path::new(work_dir.as_str())
-            .join(HOOK_COMMIT_MSG_TEMP_FILE);
+/// writes `msg` to a temp file, passes its path (followed by `args`)
+/// to the hook and reads the possibly altered msg back
+fn run_msg_hook<F: FnMut(&str)>(
+    env: &HookEnv,
+    hook_name: &str,
+    args: &[&str],
+    msg: &mut String,
+    cancel: &AtomicBool,
+    on_line: F,
+) -> Result<HookResult> {
+    if let Some(hook) = env.hook(hook_name) {
+        let temp_file = env.git_dir.join(HOOK_COMMIT_MSG_TEMP_FILE);
         File::create(&temp_file)?.write_all(msg.as_bytes())?;
 
-        let res = run_hook(
-            work_dir.as_str(),
-            HOOK_COMMIT_MSG,
-            &[HOOK_COMMIT_MSG_TEMP_FILE],
-        );
+        let temp_path = temp_file.to_string_lossy().to_string();
+        let mut hook_args = vec![temp_path.as_str()];
+        hook_args.extend_from_slice(args);
+
+        let res =
+            run_hook(env, &hook, &hook_args, &[], cancel, on_line);
 
         // load possibly altered msg
         msg.clear();
@@ -41,27 +188,550 @@ pub fn hooks_commit_msg(
 
         Ok(res)
     } else {
-        Ok(HookResult::Ok)
+        Ok(HookResult::NoHook)
+    }
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_pre_commit
+pub fn hooks_pre_commit(
+    repo_path: &str,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    hooks_pre_commit_streamed(
+        repo_path,
+        timeout,
+        &AtomicBool::new(false),
+        |_| (),
+    )
+}
+
+/// like `hooks_pre_commit` but passes every line the hook prints to
+/// `on_line` while it runs, setting `cancel` kills the hook
+pub fn hooks_pre_commit_streamed<F: FnMut(&str)>(
+    repo_path: &str,
+    timeout: Option<Duration>,
+    cancel: &AtomicBool,
+    on_line: F,
+) -> Result<HookResult> {
+    scope_time!("hooks_pre_commit");
+
+    let env = HookEnv::new(repo_path, true, timeout)?;
+
+    if let Some(hook) = env.hook(HOOK_PRE_COMMIT) {
+        Ok(run_hook(&env, &hook, &[], &[], cancel, on_line))
+    } else {
+        Ok(HookResult::NoHook)
     }
 }
 
 ///
-pub fn hooks_post_commit(repo_path: &str) -> Result<HookResult> {
+pub fn hooks_post_commit(
+    repo_path: &str,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
     scope_time!("hooks_post_commit");
 
+    let env = HookEnv::new(repo_path, true, timeout)?;
+
+    if let Some(hook) = env.hook(HOOK_POST_COMMIT) {
+        Ok(run_hook(
+            &env,
+            &hook,
+            &[],
+            &[],
+            &AtomicBool::new(false),
+            |_| (),
+        ))
+    } else {
+        Ok(HookResult::NoHook)
+    }
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_post_checkout
+/// it gets the previous and the new `HEAD` and whether a branch
+/// (`1`) or only files (`0`) were checked out. it cannot affect the
+/// checkout, a `NotOk` is only worth reporting
+pub fn hooks_post_checkout(
+    repo_path: &str,
+    prev_head: CommitId,
+    new_head: CommitId,
+    branch_checkout: bool,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_post_checkout");
+
+    run_named_hook(
+        repo_path,
+        HOOK_POST_CHECKOUT,
+        &[
+            &prev_head.to_string(),
+            &new_head.to_string(),
+            flag(branch_checkout),
+        ],
+        &[],
+        timeout,
+    )
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_post_merge
+/// it gets whether the merge was a squash (`1`) or not (`0`). it
+/// cannot affect the merge, a `NotOk` is only worth reporting
+pub fn hooks_post_merge(
+    repo_path: &str,
+    squash: bool,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_post_merge");
+
+    run_named_hook(
+        repo_path,
+        HOOK_POST_MERGE,
+        &[flag(squash)],
+        &[],
+        timeout,
+    )
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_pre_rebase
+/// it gets the upstream the series was forked from and the branch
+/// being rebased (`None` for the current one), a `NotOk` has to
+/// abort the rebase
+pub fn hooks_pre_rebase(
+    repo_path: &str,
+    upstream: &str,
+    branch: Option<&str>,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_pre_rebase");
+
+    let mut args = vec![upstream];
+    args.extend(branch);
+
+    run_named_hook(repo_path, HOOK_PRE_REBASE, &args, &[], timeout)
+}
+
+/// command that rewrote commits, passed to `post-rewrite`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RewriteCommand {
+    ///
+    Amend,
+    ///
+    Rebase,
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_post_rewrite
+/// it gets the command as argument and an `<old-sha> <new-sha>`
+/// line per rewritten commit on stdin. it cannot undo the rewrite,
+/// a `NotOk` is only worth reporting
+pub fn hooks_post_rewrite(
+    repo_path: &str,
+    command: RewriteCommand,
+    rewritten: &[(CommitId, CommitId)],
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_post_rewrite");
+
+    let command = match command {
+        RewriteCommand::Amend => "amend",
+        RewriteCommand::Rebase => "rebase",
+    };
+
+    let stdin = rewritten
+        .iter()
+        .map(|(old, new)| {
+            format!("{} {}\n", old.to_string(), new.to_string())
+        })
+        .collect::<String>();
+
+    run_named_hook(
+        repo_path,
+        HOOK_POST_REWRITE,
+        &[command],
+        stdin.as_bytes(),
+        timeout,
+    )
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_pre_applypatch
+/// it runs after a patch was applied but before committing it, a
+/// `NotOk` has to leave the result uncommitted
+pub fn hooks_pre_applypatch(
+    repo_path: &str,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_pre_applypatch");
+
+    run_named_hook(repo_path, HOOK_PRE_APPLYPATCH, &[], &[], timeout)
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_post_applypatch
+/// it runs after the patch was committed, a `NotOk` is only worth
+/// reporting
+pub fn hooks_post_applypatch(
+    repo_path: &str,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_post_applypatch");
+
+    run_named_hook(repo_path, HOOK_POST_APPLYPATCH, &[], &[], timeout)
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_pre_auto_gc
+/// a `NotOk` has to skip the automatic garbage collection
+pub fn hooks_pre_auto_gc(
+    repo_path: &str,
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_pre_auto_gc");
+
+    run_named_hook(repo_path, HOOK_PRE_AUTO_GC, &[], &[], timeout)
+}
+
+/// runs the hook `name` (e.g. `pre-auto-gc`) from the repo's hooks
+/// dir with `args` and `stdin` (ignored if the hook does not read
+/// it), `HookResult::NoHook` if there is no such hook
+pub fn run_named_hook(
+    repo_path: &str,
+    name: &str,
+    args: &[&str],
+    stdin: &[u8],
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    if name.is_empty()
+        || name.contains(&['/', '\\'][..])
+        || name == ".."
+    {
+        return Err(Error::Generic(format!(
+            "invalid hook name: '{}'",
+            name
+        )));
+    }
+
+    let env = HookEnv::new(repo_path, false, timeout)?;
+
+    if let Some(hook) = env.hook(name) {
+        Ok(run_hook(
+            &env,
+            &hook,
+            args,
+            stdin,
+            &AtomicBool::new(false),
+            |_| (),
+        ))
+    } else {
+        Ok(HookResult::NoHook)
+    }
+}
+
+/// a hook script where git looks for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookStatus {
+    /// like `pre-commit`
+    pub name: &'static str,
+    ///
+    pub path: PathBuf,
+    ///
+    pub exists: bool,
+    /// only executable hooks run, existing ones that are not are
+    /// silently skipped
+    pub executable: bool,
+}
+
+/// the state of every hook gitui knows about
+pub fn hooks_status(repo_path: &str) -> Result<Vec<HookStatus>> {
+    scope_time!("hooks_status");
+
+    let env = HookEnv::new(repo_path, false, None)?;
+
+    Ok(HOOKS
+        .iter()
+        .map(|name| {
+            let path = env.hooks_dir.join(name);
+            HookStatus {
+                name,
+                exists: path.exists(),
+                executable: env.hook(name).is_some(),
+                path,
+            }
+        })
+        .collect())
+}
+
+const fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+/// a ref update that is about to be pushed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrePushRef {
+    ///
+    pub local_ref: String,
+    /// `None` when the remote ref gets deleted
+    pub local_id: Option<CommitId>,
+    ///
+    pub remote_ref: String,
+    /// `None` when the remote ref does not exist yet
+    pub remote_id: Option<CommitId>,
+}
+
+impl PrePushRef {
+    fn to_line(&self) -> String {
+        let id = |id: Option<CommitId>| {
+            id.map_or_else(
+                || git2::Oid::zero().to_string(),
+                |id| id.to_string(),
+            )
+        };
+
+        format!(
+            "{} {} {} {}\n",
+            self.local_ref,
+            id(self.local_id),
+            self.remote_ref,
+            id(self.remote_id)
+        )
+    }
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_pre_push
+/// it gets the remote name and url as arguments and one line per
+/// ref update on stdin, a push has to be aborted if it is `NotOk`
+pub fn hooks_pre_push(
+    repo_path: &str,
+    remote: &str,
+    url: &str,
+    updates: &[PrePushRef],
+    timeout: Option<Duration>,
+) -> Result<HookResult> {
+    scope_time!("hooks_pre_push");
+
+    let stdin =
+        updates.iter().map(PrePushRef::to_line).collect::<String>();
+
+    run_named_hook(
+        repo_path,
+        HOOK_PRE_PUSH,
+        &[remote, url],
+        stdin.as_bytes(),
+        timeout,
+    )
+}
+
+/// whether `hooks_pre_push` has something to run
+pub(crate) fn has_pre_push_hook(repo_path: &str) -> Result<bool> {
+    let env = HookEnv::new(repo_path, false, None)?;
+
+    Ok(env.hook(HOOK_PRE_PUSH).is_some())
+}
+
+/// paths reported by the fsmonitor hook
+#[derive(Debug, Clone, PartialEq)]
+pub struct FsMonitorChanges {
+    /// pass this to the next query to get the changes since this one
+    pub token: String,
+    /// `None` if anything might have changed
+    pub paths: Option<Vec<String>>,
+}
+
+/// this hook is documented here https://git-scm.com/docs/githooks#_fsmonitor_watchman
+/// it is configured via `core.fsmonitor` and lists the paths that
+/// changed since `token` (an empty one asks for everything).
+/// returns `None` if no hook is configured
+pub fn hooks_fsmonitor(
+    repo_path: &str,
+    token: &str,
+) -> Result<Option<FsMonitorChanges>> {
+    scope_time!("hooks_fsmonitor");
+
+    let repo = repo(repo_path)?;
+    let config = repo.config()?;
+
+    let hook = match config.get_string(FSMONITOR_HOOK) {
+        Ok(hook) => hook,
+        Err(_) => return Ok(None),
+    };
+
+    // a boolean selects git's builtin daemon, we cannot talk to it
+    if hook.is_empty()
+        || git2::Config::parse_bool(hook.as_str()).is_ok()
+    {
+        return Ok(None);
+    }
+
     let work_dir = work_dir_as_string(repo_path)?;
-    let work_dir_str = work_dir.as_str();
 
-    if hook_runable(work_dir_str, HOOK_POST_COMMIT) {
-        Ok(run_hook(work_dir_str, HOOK_POST_COMMIT, &[]))
+    match config.get_i32(FSMONITOR_HOOK_VERSION) {
+        Ok(1) => query_fsmonitor_v1(&work_dir, &hook, token),
+        Ok(2) => query_fsmonitor_v2(&work_dir, &hook, token),
+        Ok(version) => Err(Error::Generic(format!(
+            "unsupported fsmonitor hook version: {}",
+            version
+        ))),
+        // like git try the newer protocol first
+        Err(_) => query_fsmonitor_v2(&work_dir, &hook, token)
+            .or_else(|_| query_fsmonitor_v1(&work_dir, &hook, token)),
+    }
+    .map(Some)
+}
+
+/// version 1 gets a timestamp in nanoseconds, the token of the next
+/// query is the time this one started
+fn query_fsmonitor_v1(
+    work_dir: &str,
+    hook: &str,
+    token: &str,
+) -> Result<FsMonitorChanges> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| Error::Generic(e.to_string()))?
+        .as_nanos()
+        .to_string();
+
+    let since = if token.is_empty() { "0" } else { token };
+    let output = run_fsmonitor(work_dir, hook, "1", since)?;
+
+    Ok(FsMonitorChanges {
+        token: now,
+        paths: fsmonitor_paths(output.split(|b| *b == 0)),
+    })
+}
+
+/// version 2 answers with the next token followed by the paths
+fn query_fsmonitor_v2(
+    work_dir: &str,
+    hook: &str,
+    token: &str,
+) -> Result<FsMonitorChanges> {
+    let output = run_fsmonitor(work_dir, hook, "2", token)?;
+    let mut fields = output.split(|b| *b == 0);
+
+    let token = fields
+        .next()
+        .map(|t| String::from_utf8_lossy(t).to_string())
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| {
+            Error::Generic(String::from(
+                "fsmonitor hook did not return a token",
+            ))
+        })?;
+
+    Ok(FsMonitorChanges {
+        token,
+        paths: fsmonitor_paths(fields),
+    })
+}
+
+/// a single `/` means everything has to be rescanned
+fn fsmonitor_paths<'a, I: Iterator<Item = &'a [u8]>>(
+    fields: I,
+) -> Option<Vec<String>> {
+    let mut paths = Vec::new();
+
+    for field in fields.filter(|f| !f.is_empty()) {
+        if field == b"/" {
+            return None;
+        }
+
+        paths.push(String::from_utf8_lossy(field).to_string());
+    }
+
+    Some(paths)
+}
+
+/// `hook` is a command line like git runs it, not necessarily a
+/// script inside `.git/hooks`
+fn run_fsmonitor(
+    work_dir: &str,
+    hook: &str,
+    version: &str,
+    token: &str,
+) -> Result<Vec<u8>> {
+    let output = Command::new("bash")
+        .args(&["-c", &format!("{} \"$@\"", hook), hook])
+        .args(&[version, token])
+        .current_dir(work_dir)
+        .stdin(Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        Ok(output.stdout)
     } else {
-        Ok(HookResult::Ok)
+        Err(Error::Generic(format!(
+            "fsmonitor hook failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )))
+    }
+}
+
+/// where a hook runs and the environment git documents for it
+struct HookEnv {
+    work_dir: String,
+    /// `.git` or `.git/worktrees/<name>` for a linked worktree
+    git_dir: PathBuf,
+    /// shared by all worktrees like git does
+    hooks_dir: PathBuf,
+    vars: Vec<(&'static str, String)>,
+    /// hooks running longer get killed and result in
+    /// `HookResult::TimedOut`, `None` lets them run forever
+    timeout: Option<Duration>,
+}
+
+impl HookEnv {
+    /// `with_index` sets `GIT_INDEX_FILE` like git does for the
+    /// hooks run while committing
+    fn new(
+        repo_path: &str,
+        with_index: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        let repo = repo(repo_path)?;
+        let work_dir = work_dir_as_string(repo_path)?;
+        let git_dir = repo.path().to_path_buf();
+
+        let mut vars =
+            vec![("GIT_DIR", git_dir.to_string_lossy().to_string())];
+
+        if with_index {
+            vars.push((
+                "GIT_INDEX_FILE",
+                git_dir.join("index").to_string_lossy().to_string(),
+            ));
+        }
+
+        // relative path (with trailing slash) of the subdirectory
+        // gitui was started in
+        if let (Ok(cwd), Ok(root)) =
+            (fs::canonicalize(repo_path), fs::canonicalize(&work_dir))
+        {
+            if let Ok(prefix) = cwd.strip_prefix(root) {
+                if !prefix.as_os_str().is_empty() {
+                    vars.push((
+                        "GIT_PREFIX",
+                        format!("{}/", prefix.to_string_lossy()),
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            work_dir,
+            hooks_dir: common_dir(&git_dir)?.join(HOOKS_DIR),
+            git_dir,
+            vars,
+            timeout,
+        })
     }
 }
 
+/// a bare repo runs its hooks in the git dir, like git
 fn work_dir_as_string(repo_path: &str) -> Result<String> {
     let repo = repo(repo_path)?;
-    work_dir(&repo)
+    repo.workdir()
+        .unwrap_or_else(|| repo.path())
         .to_str()
         .map(|s| s.to_string())
         .ok_or_else(|| {
@@ -71,11 +741,30 @@ fn work_dir_as_string(repo_path: &str) -> Result<String> {
         })
 }
 
-fn hook_runable(path: &str, hook: &str) -> bool {
-    let path = Path::new(path);
-    let path = path.join(hook);
+impl HookEnv {
+    /// path of the hook script if there is a runnable one
+    fn hook(&self, name: &str) -> Option<PathBuf> {
+        let path = self.hooks_dir.join(name);
+
+        if path.exists() && is_executable(path.clone()) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+}
+
+/// the git dir of the main repo, linked worktrees point to it via
+/// a `commondir` file (relative to their own git dir)
+fn common_dir(git_dir: &Path) -> Result<PathBuf> {
+    let commondir_file = git_dir.join("commondir");
 
-    path.exists() && is_executable(path)
+    if commondir_file.exists() {
+        let common = fs::read_to_string(commondir_file)?;
+        Ok(git_dir.join(common.trim_end()))
+    } else {
+        Ok(git_dir.to_path_buf())
+    }
 }
 
 ///
@@ -83,18 +772,63 @@ fn hook_runable(path: &str, hook: &str) -> bool {
 pub enum HookResult {
     /// Everything went fine
     Ok,
+    /// There is no (executable) hook of that name, nothing ran
+    NoHook,
     /// Hook returned error
-    NotOk(String),
+    NotOk {
+        /// name of the hook, like `pre-commit`
+        hook: String,
+        /// `None` if the hook got killed (e.g. cancelled)
+        code: Option<i32>,
+        /// stdout followed by stderr
+        output: String,
+    },
+    /// Hook got killed after running longer than the timeout
+    TimedOut {
+        ///
+        hook: String,
+        ///
+        elapsed: Duration,
+    },
+}
+
+impl HookResult {
+    /// `None` if the hook passed, otherwise what to report
+    pub fn error(self) -> Option<String> {
+        match self {
+            Self::Ok | Self::NoHook => None,
+            Self::NotOk { hook, code, output } => Some(match code {
+                Some(code) => format!(
+                    "{} hook failed (exit {}):\n{}",
+                    hook, code, output
+                ),
+                None => format!("{} hook failed:\n{}", hook, output),
+            }),
+            Self::TimedOut { hook, elapsed } => Some(format!(
+                "{} hook timed out after {}s",
+                hook,
+                elapsed.as_secs()
+            )),
+        }
+    }
 }
 
 /// this function calls hook scripts based on conventions documented here
 /// https://git-scm.com/docs/githooks
-fn run_hook(
-    path: &str,
-    hook_script: &str,
+///
+/// stdout and stderr are read line by line and forwarded to `on_line`
+/// while the hook runs, `cancel` and the timeout are polled to kill
+/// it (and whatever it started) early
+fn run_hook<F: FnMut(&str)>(
+    env: &HookEnv,
+    hook_script: &Path,
     args: &[&str],
+    stdin: &[u8],
+    cancel: &AtomicBool,
+    mut on_line: F,
 ) -> HookResult {
-    let mut bash_args = vec![hook_script.to_string()];
+    let mut bash_args =
+        vec![hook_script.to_string_lossy().to_string()];
     bash_args.extend_from_slice(
         &args
             .iter()
@@ -102,24 +836,171 @@ fn run_hook(
             .collect::<Vec<String>>(),
     );
 
-    let output = Command::new("bash")
+    let mut command = Command::new("bash");
+    command
         .args(bash_args)
-        .current_dir(path)
-        .output();
+        .current_dir(&env.work_dir)
+        .envs(env.vars.iter().map(|(k, v)| (k, v)))
+        .stdin(if stdin.is_empty() {
+            Stdio::null()
+        } else {
+            Stdio::piped()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // a group of its own to kill along with what it started
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
 
-    let output = output.expect("general hook error");
+    let mut child = command.spawn().expect("general hook error");
 
-    if output.status.success() {
+    if let Some(mut input) = child.stdin.take() {
+        let stdin = stdin.to_vec();
+        // hooks are free to not read their input, ignore broken pipes
+        thread::spawn(move || input.write_all(&stdin).ok());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, false, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, true, tx);
+    }
+
+    let mut out = String::new();
+    let mut err = String::new();
+    let mut output_open = true;
+    let start = Instant::now();
+
+    loop {
+        let timed_out =
+            env.timeout.map_or(false, |t| start.elapsed() >= t);
+
+        if timed_out || cancel.load(Ordering::Relaxed) {
+            // the hook might already be gone, nothing left to do then.
+            // dropping `rx` stops the readers once the pipes close
+            kill_hook(&mut child);
+            child.wait().ok();
+
+            return if timed_out {
+                HookResult::TimedOut {
+                    hook: hook_name(hook_script),
+                    elapsed: start.elapsed(),
+                }
+            } else {
+                HookResult::NotOk {
+                    hook: hook_name(hook_script),
+                    code: None,
+                    output: HOOK_CANCELLED.to_string(),
+                }
+            };
+        }
+
+        if !output_open {
+            // the hook closed its output but might not be done yet
+            if child.try_wait().expect("general hook error").is_some()
+            {
+                break;
+            }
+
+            thread::sleep(CANCEL_POLL_INTERVAL);
+            continue;
+        }
+
+        // no reading past the timeout
+        let poll = env.timeout.map_or(CANCEL_POLL_INTERVAL, |t| {
+            t.saturating_sub(start.elapsed())
+                .min(CANCEL_POLL_INTERVAL)
+        });
+
+        match rx.recv_timeout(poll) {
+            Ok((is_err, line)) => {
+                on_line(line.trim_end_matches(&['\r', '\n'][..]));
+
+                if is_err {
+                    err.push_str(&line);
+                } else {
+                    out.push_str(&line);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => (),
+            Err(RecvTimeoutError::Disconnected) => {
+                output_open = false;
+            }
+        }
+    }
+
+    let status = child.wait().expect("general hook error");
+
+    if status.success() {
         HookResult::Ok
     } else {
-        let err = String::from_utf8_lossy(&output.stderr);
-        let out = String::from_utf8_lossy(&output.stdout);
-        let formatted = format!("{}{}", out, err);
+        HookResult::NotOk {
+            hook: hook_name(hook_script),
+            code: status.code(),
+            output: format!("{}{}", out, err),
+        }
+    }
+}
 
-        HookResult::NotOk(formatted)
+/// kills the hook and everything it started, it leads a process
+/// group of its own
+#[cfg(unix)]
+fn kill_hook(child: &mut Child) {
+    let killed = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(false, |status| status.success());
+
+    if !killed {
+        child.kill().ok();
     }
 }
 
+#[cfg(not(unix))]
+fn kill_hook(child: &mut Child) {
+    child.kill().ok();
+}
+
+/// `pre-commit` for `.git/hooks/pre-commit`
+fn hook_name(hook_script: &Path) -> String {
+    hook_script.file_name().map_or_else(
+        || hook_script.to_string_lossy().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    )
+}
+
+/// sends every line (including its line break) read from `reader`
+fn forward_lines<R: Read + Send + 'static>(
+    reader: R,
+    is_err: bool,
+    tx: mpsc::Sender<(bool, String)>,
+) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+
+        while let Ok(read) = reader.read_until(b'\n', &mut buf) {
+            if read == 0 {
+                break;
+            }
+
+            let line = String::from_utf8_lossy(&buf).to_string();
+            if tx.send((is_err, line)).is_err() {
+                break;
+            }
+            buf.clear();
+        }
+    });
+}
+
 #[cfg(not(windows))]
 fn is_executable(path: PathBuf) -> bool {
     use std::os::unix::fs::PermissionsExt;
@@ -145,6 +1026,15 @@ mod tests {
     use crate::sync::tests::repo_init;
     use std::fs::{self, File};
 
+    /// the result of `hook` exiting with 1 after printing `output`
+    fn rejected(hook: &str, output: impl Into<String>) -> HookResult {
+        HookResult::NotOk {
+            hook: String::from(hook),
+            code: Some(1),
+            output: output.into(),
+        }
+    }
+
     #[test]
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
@@ -152,17 +1042,24 @@ mod tests {
         let repo_path = root.as_os_str().to_str().unwrap();
 
         let mut msg = String::from("test");
-        let res = hooks_commit_msg(repo_path, &mut msg).unwrap();
+        let res =
+            hooks_commit_msg(repo_path, &mut msg, None).unwrap();
 
-        assert_eq!(res, HookResult::Ok);
+        assert_eq!(res, HookResult::NoHook);
 
-        let res = hooks_post_commit(repo_path).unwrap();
+        let res = hooks_pre_commit(repo_path, None).unwrap();
 
-        assert_eq!(res, HookResult::Ok);
+        assert_eq!(res, HookResult::NoHook);
+
+        let res = hooks_post_commit(repo_path, None).unwrap();
+
+        assert_eq!(res, HookResult::NoHook);
     }
 
-    fn create_hook(path: &Path, hook_path: &str, hook_script: &[u8]) {
-        File::create(&path.join(hook_path))
+    fn create_hook(path: &Path, hook: &str, hook_script: &[u8]) {
+        let hook_path = path.join(".git").join(HOOKS_DIR).join(hook);
+
+        File::create(&hook_path)
             .unwrap()
             .write_all(hook_script)
             .unwrap();
@@ -170,13 +1067,42 @@ mod tests {
         #[cfg(not(windows))]
         {
             Command::new("chmod")
-                .args(&["+x", hook_path])
-                .current_dir(path)
+                .arg("+x")
+                .arg(&hook_path)
                 .output()
                 .unwrap();
         }
     }
 
+    #[test]
+    #[cfg(not(windows))]
+    fn test_hooks_status() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        create_hook(root, HOOK_PRE_COMMIT, b"#!/bin/sh\nexit 0\n");
+        File::create(root.join(".git/hooks").join(HOOK_COMMIT_MSG))
+            .unwrap()
+            .write_all(b"#!/bin/sh\nexit 0\n")
+            .unwrap();
+
+        let status = hooks_status(repo_path).unwrap();
+        let find = |name: &str| {
+            status.iter().find(|hook| hook.name == name).unwrap()
+        };
+
+        assert_eq!(status.len(), HOOKS.len());
+        assert!(find(HOOK_PRE_COMMIT).exists);
+        assert!(find(HOOK_PRE_COMMIT).executable);
+        assert!(find(HOOK_COMMIT_MSG).exists);
+        assert!(!find(HOOK_COMMIT_MSG).executable);
+        assert!(!find(HOOK_POST_COMMIT).exists);
+        assert!(find(HOOK_POST_COMMIT)
+            .path
+            .ends_with("hooks/post-commit"));
+    }
+
     #[test]
     fn test_hooks_commit_msg_ok() {
         let (_td, repo) = repo_init().unwrap();
@@ -191,7 +1117,8 @@ exit 0
         create_hook(root, HOOK_COMMIT_MSG, hook);
 
         let mut msg = String::from("test");
-        let res = hooks_commit_msg(repo_path, &mut msg).unwrap();
+        let res =
+            hooks_commit_msg(repo_path, &mut msg, None).unwrap();
 
         assert_eq!(res, HookResult::Ok);
 
@@ -214,12 +1141,10 @@ exit 1
         create_hook(root, HOOK_COMMIT_MSG, hook);
 
         let mut msg = String::from("test");
-        let res = hooks_commit_msg(repo_path, &mut msg).unwrap();
+        let res =
+            hooks_commit_msg(repo_path, &mut msg, None).unwrap();
 
-        assert_eq!(
-            res,
-            HookResult::NotOk(String::from("rejected\n"))
-        );
+        assert_eq!(res, rejected(HOOK_COMMIT_MSG, "rejected\n"));
 
         assert_eq!(msg, String::from("msg\n"));
     }
@@ -243,14 +1168,14 @@ exit 1
         fs::create_dir_all(&subfolder).unwrap();
 
         let mut msg = String::from("test");
-        let res =
-            hooks_commit_msg(subfolder.to_str().unwrap(), &mut msg)
-                .unwrap();
+        let res = hooks_commit_msg(
+            subfolder.to_str().unwrap(),
+            &mut msg,
+            None,
+        )
+        .unwrap();
 
-        assert_eq!(
-            res,
-            HookResult::NotOk(String::from("rejected\n"))
-        );
+        assert_eq!(res, rejected(HOOK_COMMIT_MSG, "rejected\n"));
 
         assert_eq!(msg, String::from("msg\n"));
     }
@@ -270,7 +1195,8 @@ exit 0
         create_hook(root, HOOK_COMMIT_MSG, hook);
 
         let mut msg = String::from("test");
-        let res = hooks_commit_msg(repo_path, &mut msg).unwrap();
+        let res =
+            hooks_commit_msg(repo_path, &mut msg, None).unwrap();
 
         assert_eq!(res, HookResult::Ok);
         assert_eq!(msg, String::from("msg\n"));
@@ -293,11 +1219,664 @@ exit 1
         fs::create_dir_all(&subfolder).unwrap();
 
         let res =
-            hooks_post_commit(subfolder.to_str().unwrap()).unwrap();
+            hooks_post_commit(subfolder.to_str().unwrap(), None)
+                .unwrap();
 
+        assert_eq!(res, rejected(HOOK_POST_COMMIT, "rejected\n"));
+    }
+
+    #[test]
+    fn test_pre_commit_hook_reject() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo 'rejected'
+exit 1
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let res = hooks_pre_commit(repo_path, None).unwrap();
+
+        assert_eq!(res, rejected(HOOK_PRE_COMMIT, "rejected\n"));
+    }
+
+    #[test]
+    fn test_pre_commit_hook_streamed() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo 'line 1'
+echo 'line 2' >&2
+exit 0
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let mut lines = Vec::new();
+        let res = hooks_pre_commit_streamed(
+            repo_path,
+            None,
+            &AtomicBool::new(false),
+            |l| lines.push(l.to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(res, HookResult::Ok);
+        lines.sort();
+        assert_eq!(lines, vec!["line 1", "line 2"]);
+    }
+
+    #[test]
+    fn test_pre_commit_hook_cancel() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo 'started'
+sleep 10
+exit 0
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let cancel = AtomicBool::new(false);
+        let res = hooks_pre_commit_streamed(
+            repo_path,
+            None,
+            &cancel,
+            |_| cancel.store(true, Ordering::Relaxed),
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            HookResult::NotOk {
+                hook: String::from(HOOK_PRE_COMMIT),
+                code: None,
+                output: String::from(HOOK_CANCELLED),
+            }
+        );
+    }
+
+    #[test]
+    fn test_pre_push_hook_stdin() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+read local_ref local_sha remote_ref remote_sha
+echo \"$1 $2\"
+echo \"$local_ref $local_sha $remote_ref $remote_sha\"
+[ \"$remote_sha\" = 0000000000000000000000000000000000000000 ] && exit 1
+exit 0
+        ";
+
+        create_hook(root, HOOK_PRE_PUSH, hook);
+
+        let head = crate::sync::get_head(repo_path).unwrap();
+        let mut update = PrePushRef {
+            local_ref: String::from("refs/heads/master"),
+            local_id: Some(head),
+            remote_ref: String::from("refs/heads/master"),
+            remote_id: None,
+        };
+
+        let res = hooks_pre_push(
+            repo_path,
+            "origin",
+            "https://example.com/repo.git",
+            &[update.clone()],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            rejected(
+                HOOK_PRE_PUSH,
+                format!(
+                    "origin https://example.com/repo.git\n\
+                 refs/heads/master {} refs/heads/master {}\n",
+                    head.to_string(),
+                    git2::Oid::zero()
+                )
+            )
+        );
+
+        update.remote_id = Some(head);
+
+        let res = hooks_pre_push(
+            repo_path,
+            "origin",
+            "https://example.com/repo.git",
+            &[update],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(res, HookResult::Ok);
+    }
+
+    #[test]
+    fn test_fsmonitor() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(hooks_fsmonitor(repo_path, "").unwrap(), None);
+
+        let hook = b"#!/bin/sh
+if [ \"$1\" = 2 ]; then
+    printf 'next\\0%s\\0b/c\\0' \"$2\"
+else
+    printf 'a\\0/\\0'
+fi
+";
+
+        let hook_path = ".git/hooks/fsmonitor-test";
+        create_hook(root, "fsmonitor-test", hook);
+
+        let mut config = repo.config().unwrap();
+        config.set_str(FSMONITOR_HOOK, hook_path).unwrap();
+
+        let res = hooks_fsmonitor(repo_path, "token").unwrap();
+        assert_eq!(
+            res,
+            Some(FsMonitorChanges {
+                token: String::from("next"),
+                paths: Some(vec![
+                    String::from("token"),
+                    String::from("b/c")
+                ]),
+            })
+        );
+
+        config.set_i32(FSMONITOR_HOOK_VERSION, 1).unwrap();
+
+        let res = hooks_fsmonitor(repo_path, "").unwrap().unwrap();
+        assert_eq!(res.paths, None);
+        assert!(res.token.parse::<u128>().is_ok());
+
+        config.set_str(FSMONITOR_HOOK, "true").unwrap();
+        assert_eq!(hooks_fsmonitor(repo_path, "").unwrap(), None);
+    }
+
+    #[test]
+    fn test_post_checkout_args() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo \"$1 $2 $3\"
+exit 1
+        ";
+
+        create_hook(root, HOOK_POST_CHECKOUT, hook);
+
+        let head = crate::sync::get_head(repo_path).unwrap();
+        let prev = CommitId::new(git2::Oid::zero());
+
+        let res =
+            hooks_post_checkout(repo_path, prev, head, true, None)
+                .unwrap();
+        assert_eq!(
+            res,
+            rejected(
+                HOOK_POST_CHECKOUT,
+                format!(
+                    "{} {} 1\n",
+                    prev.to_string(),
+                    head.to_string()
+                )
+            )
+        );
+
+        let res =
+            hooks_post_checkout(repo_path, head, head, false, None)
+                .unwrap();
         assert_eq!(
             res,
-            HookResult::NotOk(String::from("rejected\n"))
+            rejected(
+                HOOK_POST_CHECKOUT,
+                format!("{0} {0} 0\n", head.to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_post_merge_args() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(
+            hooks_post_merge(repo_path, false, None).unwrap(),
+            HookResult::NoHook
+        );
+
+        let hook = b"
+#!/bin/sh
+echo \"squash: $1\"
+[ \"$1\" = 0 ]
+        ";
+
+        create_hook(root, HOOK_POST_MERGE, hook);
+
+        assert_eq!(
+            hooks_post_merge(repo_path, false, None).unwrap(),
+            HookResult::Ok
+        );
+        assert_eq!(
+            hooks_post_merge(repo_path, true, None).unwrap(),
+            rejected(HOOK_POST_MERGE, "squash: 1\n")
+        );
+    }
+
+    #[test]
+    fn test_pre_rebase_args() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo \"$# $1 $2\"
+[ \"$2\" != topic ]
+        ";
+
+        create_hook(root, HOOK_PRE_REBASE, hook);
+
+        assert_eq!(
+            hooks_pre_rebase(repo_path, "main", None, None).unwrap(),
+            HookResult::Ok
+        );
+        assert_eq!(
+            hooks_pre_rebase(repo_path, "main", Some("topic"), None)
+                .unwrap(),
+            rejected(HOOK_PRE_REBASE, "2 main topic\n")
+        );
+    }
+
+    #[test]
+    fn test_post_rewrite_stdin() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo \"$1\"
+cat
+exit 1
+        ";
+
+        create_hook(root, HOOK_POST_REWRITE, hook);
+
+        let id = |c: char| {
+            CommitId::new(
+                git2::Oid::from_str(&c.to_string().repeat(40))
+                    .unwrap(),
+            )
+        };
+
+        let res = hooks_post_rewrite(
+            repo_path,
+            RewriteCommand::Rebase,
+            &[(id('a'), id('b')), (id('c'), id('d'))],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            rejected(
+                HOOK_POST_REWRITE,
+                format!(
+                    "rebase\n{} {}\n{} {}\n",
+                    "a".repeat(40),
+                    "b".repeat(40),
+                    "c".repeat(40),
+                    "d".repeat(40)
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_hook_env_in_subfolder() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo \"$GIT_DIR|$GIT_INDEX_FILE|$GIT_PREFIX\"
+exit 1
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let subfolder = root.join("foo/bar/");
+        fs::create_dir_all(&subfolder).unwrap();
+
+        let res = hooks_pre_commit(subfolder.to_str().unwrap(), None)
+            .unwrap();
+
+        let git_dir = repo.path().to_str().unwrap();
+        assert_eq!(
+            res,
+            rejected(
+                HOOK_PRE_COMMIT,
+                format!(
+                    "{}|{}|foo/bar/\n",
+                    git_dir,
+                    repo.path().join("index").to_str().unwrap()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_hook_timeout() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+sleep 10
+exit 0
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let env = HookEnv::new(
+            repo_path,
+            true,
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap();
+
+        let res = run_hook(
+            &env,
+            &env.hook(HOOK_PRE_COMMIT).unwrap(),
+            &[],
+            &[],
+            &AtomicBool::new(false),
+            |_| (),
+        );
+
+        assert!(matches!(
+            res,
+            HookResult::TimedOut { ref hook, elapsed }
+                if hook == "pre-commit"
+                    && elapsed >= Duration::from_millis(200)
+                    && elapsed < Duration::from_secs(10)
+        ));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_hook_timeout_kills_children() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+sleep 10 &
+echo $! > sleep.pid
+wait
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let env = HookEnv::new(
+            repo_path,
+            true,
+            Some(Duration::from_millis(200)),
+        )
+        .unwrap();
+
+        let res = run_hook(
+            &env,
+            &env.hook(HOOK_PRE_COMMIT).unwrap(),
+            &[],
+            &[],
+            &AtomicBool::new(false),
+            |_| (),
+        );
+        assert!(matches!(res, HookResult::TimedOut { .. }));
+
+        // killed processes stay zombies until reaped
+        let pid = fs::read_to_string(root.join("sleep.pid")).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let running = || {
+            fs::read_to_string(&stat)
+                .map_or(false, |stat| !stat.contains(") Z "))
+        };
+
+        let start = Instant::now();
+        while running() && start.elapsed() < Duration::from_secs(2) {
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!running());
+    }
+
+    /// linked worktree of the repo at `root` at `<dir>/wt`
+    fn add_worktree(root: &Path, dir: &Path) -> PathBuf {
+        let path = dir.join("wt");
+
+        let res = Command::new("git")
+            .args(&["worktree", "add", "--detach"])
+            .arg(&path)
+            .current_dir(root)
+            .output()
+            .unwrap();
+        assert!(res.status.success());
+
+        path
+    }
+
+    #[test]
+    fn test_pre_commit_hook_in_worktree() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let wt_dir = tempfile::TempDir::new().unwrap();
+        let worktree = add_worktree(root, wt_dir.path());
+        let wt_path = worktree.to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+pwd -P
+exit 1
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, hook);
+
+        let res = hooks_pre_commit(wt_path, None).unwrap();
+
+        assert_eq!(
+            res,
+            rejected(
+                HOOK_PRE_COMMIT,
+                format!(
+                    "{}\n",
+                    fs::canonicalize(&worktree).unwrap().display()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn test_commit_msg_hook_in_worktree() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let wt_dir = tempfile::TempDir::new().unwrap();
+        let worktree = add_worktree(root, wt_dir.path());
+        let wt_path = worktree.to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo 'msg from worktree' > $1
+exit 0
+        ";
+
+        create_hook(root, HOOK_COMMIT_MSG, hook);
+
+        let mut msg = String::from("test");
+        let res = hooks_commit_msg(wt_path, &mut msg, None).unwrap();
+
+        assert_eq!(res, HookResult::Ok);
+        assert_eq!(msg, String::from("msg from worktree\n"));
+    }
+
+    #[test]
+    fn test_run_named_hook_stdin() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo \"$@\"
+while read -r line; do
+    echo \"> $line\"
+done
+exit 1
+        ";
+
+        create_hook(root, "my-hook", hook);
+
+        let res = run_named_hook(
+            repo_path,
+            "my-hook",
+            &["a", "b"],
+            b"first\nsecond line\nthird\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            rejected(
+                "my-hook",
+                String::from(
+                    "a b\n> first\n> second line\n> third\n"
+                )
+            )
+        );
+
+        assert_eq!(
+            run_named_hook(repo_path, "missing", &[], &[], None)
+                .unwrap(),
+            HookResult::NoHook
+        );
+        assert!(run_named_hook(repo_path, "../hook", &[], &[], None)
+            .is_err());
+        assert!(
+            run_named_hook(repo_path, "", &[], &[], None).is_err()
+        );
+    }
+
+    #[test]
+    fn test_applypatch_hooks() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = b"
+#!/bin/sh
+echo 'fixed msg' > $1
+exit 0
+        ";
+
+        create_hook(root, HOOK_APPLYPATCH_MSG, hook);
+
+        let mut msg = String::from("msg");
+        let res =
+            hooks_applypatch_msg(repo_path, &mut msg, None).unwrap();
+
+        assert_eq!(res, HookResult::Ok);
+        assert_eq!(msg, String::from("fixed msg\n"));
+
+        let hook = b"
+#!/bin/sh
+echo 'rejected'
+exit 1
+        ";
+
+        create_hook(root, HOOK_PRE_APPLYPATCH, hook);
+        create_hook(root, HOOK_PRE_AUTO_GC, hook);
+
+        assert_eq!(
+            hooks_pre_applypatch(repo_path, None).unwrap(),
+            rejected(HOOK_PRE_APPLYPATCH, "rejected\n")
+        );
+        assert_eq!(
+            hooks_pre_auto_gc(repo_path, None).unwrap(),
+            rejected(HOOK_PRE_AUTO_GC, "rejected\n")
+        );
+        assert_eq!(
+            hooks_post_applypatch(repo_path, None).unwrap(),
+            HookResult::NoHook
+        );
+    }
+
+    #[test]
+    fn test_prepare_commit_msg_merge() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        crate::sync::merge::tests::start_merge(&repo, repo_path);
+
+        let hook = b"
+#!/bin/sh
+echo \"source: $2 $3\" >> $1
+exit 0
+        ";
+
+        create_hook(root, HOOK_PREPARE_COMMIT_MSG, hook);
+
+        let mut msg =
+            crate::sync::merge_msg(repo_path).unwrap().unwrap();
+        let res = hooks_prepare_commit_msg(
+            repo_path,
+            PrepareCommitMsgSource::Merge,
+            &mut msg,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(res, HookResult::Ok);
+        assert!(msg.starts_with("Merge branch 'other'"));
+        assert!(msg.ends_with("source: merge \n"));
+
+        let head = crate::sync::get_head(repo_path).unwrap();
+        let mut msg = String::new();
+        hooks_prepare_commit_msg(
+            repo_path,
+            PrepareCommitMsgSource::Commit(head),
+            &mut msg,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            msg,
+            format!("source: commit {}\n", head.to_string())
         );
     }
 }
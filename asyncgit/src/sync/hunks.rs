@@ -1,5 +1,5 @@
 use super::{
-    diff::{get_diff_raw, HunkHeader},
+    diff::{get_diff_raw, DiffOptions, HunkHeader},
     utils::repo,
 };
 use crate::{
@@ -9,17 +9,24 @@ use crate::{
 use git2::{ApplyLocation, ApplyOptions, Diff};
 use scopetime::scope_time;
 
-///
+/// `options` have to be the ones the hunk was shown with
 pub fn stage_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: DiffOptions,
 ) -> Result<()> {
     scope_time!("stage_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff = get_diff_raw(
+        &repo,
+        &file_path,
+        false,
+        false,
+        options.applicable(),
+    )?;
 
     let mut opt = ApplyOptions::new();
     opt.hunk_callback(|hunk| {
@@ -38,12 +45,19 @@ pub fn reset_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: DiffOptions,
 ) -> Result<()> {
     scope_time!("reset_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, false, false)?;
+    let diff = get_diff_raw(
+        &repo,
+        &file_path,
+        false,
+        false,
+        options.applicable(),
+    )?;
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
     if let Some(hunk_index) = hunk_index {
@@ -55,7 +69,13 @@ pub fn reset_hunk(
             res
         });
 
-        let diff = get_diff_raw(&repo, &file_path, false, true)?;
+        let diff = get_diff_raw(
+            &repo,
+            &file_path,
+            false,
+            true,
+            options.applicable(),
+        )?;
 
         repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut opt))?;
 
@@ -96,12 +116,19 @@ pub fn unstage_hunk(
     repo_path: &str,
     file_path: String,
     hunk_hash: u64,
+    options: DiffOptions,
 ) -> Result<bool> {
     scope_time!("revert_hunk");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, &file_path, true, false)?;
+    let diff = get_diff_raw(
+        &repo,
+        &file_path,
+        true,
+        false,
+        options.applicable(),
+    )?;
     let diff_count_positive = diff.deltas().len();
 
     let hunk_index = find_hunk_index(&diff, hunk_hash);
@@ -109,7 +136,13 @@ pub fn unstage_hunk(
         return Err(Error::Generic("hunk not found".to_string()));
     }
 
-    let diff = get_diff_raw(&repo, &file_path, true, true)?;
+    let diff = get_diff_raw(
+        &repo,
+        &file_path,
+        true,
+        true,
+        options.applicable(),
+    )?;
 
     assert_eq!(diff.deltas().len(), diff_count_positive);
 
@@ -166,12 +199,14 @@ mod tests {
             sub_path.to_str().unwrap(),
             String::from(file_path.to_str().unwrap()),
             false,
+            DiffOptions::default(),
         )?;
 
         assert!(reset_hunk(
             repo_path,
             String::from(file_path.to_str().unwrap()),
             diff.hunks[0].header_hash,
+            DiffOptions::default(),
         )
         .is_err());
 
@@ -0,0 +1,247 @@
+//! index bits telling git to leave changes of a tracked file alone
+//! (`git update-index --assume-unchanged`/`--skip-worktree`), handy
+//! for local tweaks of committed config files
+
+use super::utils::{repo, work_dir};
+use crate::error::{Error, Result};
+use git2::{Index, IndexEntry};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// `GIT_INDEX_ENTRY_VALID` of the index flags
+const ASSUME_UNCHANGED: u16 = 0x8000;
+/// `GIT_INDEX_ENTRY_EXTENDED`, the entry has extended flags
+const EXTENDED: u16 = 0x4000;
+/// `GIT_INDEX_ENTRY_SKIP_WORKTREE` of the extended index flags
+const SKIP_WORKTREE: u16 = 1 << 14;
+
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndexBit {
+    /// git does not even look at the file
+    AssumeUnchanged,
+    /// git keeps the version of the index, even when checking out
+    SkipWorktree,
+}
+
+impl IndexBit {
+    /// as the option of `git update-index`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AssumeUnchanged => "assume-unchanged",
+            Self::SkipWorktree => "skip-worktree",
+        }
+    }
+
+    fn is_set(self, entry: &IndexEntry) -> bool {
+        match self {
+            Self::AssumeUnchanged => {
+                entry.flags & ASSUME_UNCHANGED != 0
+            }
+            Self::SkipWorktree => {
+                entry.flags_extended & SKIP_WORKTREE != 0
+            }
+        }
+    }
+}
+
+/// a tracked file with at least one of the bits set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiddenFile {
+    ///
+    pub path: String,
+    ///
+    pub assume_unchanged: bool,
+    ///
+    pub skip_worktree: bool,
+}
+
+/// sets or clears `bit` of the tracked file at `path`
+pub fn set_index_bit(
+    repo_path: &str,
+    path: &str,
+    bit: IndexBit,
+    set: bool,
+) -> Result<()> {
+    scope_time!("set_index_bit");
+
+    let repo = repo(repo_path)?;
+    let mut index = repo.index()?;
+    let mut entry =
+        index.get_path(Path::new(path), 0).ok_or_else(|| {
+            Error::Generic(format!("{} is not tracked", path))
+        })?;
+
+    let (flags, mask) = match bit {
+        IndexBit::AssumeUnchanged => {
+            (&mut entry.flags, ASSUME_UNCHANGED)
+        }
+        IndexBit::SkipWorktree => {
+            (&mut entry.flags_extended, SKIP_WORKTREE)
+        }
+    };
+    if set {
+        *flags |= mask;
+    } else {
+        *flags &= !mask;
+    }
+
+    if entry.flags_extended == 0 {
+        entry.flags &= !EXTENDED;
+    } else {
+        entry.flags |= EXTENDED;
+    }
+
+    index.add(&entry)?;
+    index.write()?;
+
+    Ok(())
+}
+
+/// paths with a bit set that are in the work dir, the ones a sparse
+/// checkout skips on purpose are left out
+fn hidden_paths(index: &Index, work_dir: &Path) -> Vec<HiddenFile> {
+    index
+        .iter()
+        .filter_map(|entry| {
+            let assume_unchanged =
+                IndexBit::AssumeUnchanged.is_set(&entry);
+            let skip_worktree = IndexBit::SkipWorktree.is_set(&entry);
+            let path =
+                String::from_utf8_lossy(&entry.path).to_string();
+
+            if assume_unchanged
+                || (skip_worktree && work_dir.join(&path).exists())
+            {
+                Some(HiddenFile {
+                    path,
+                    assume_unchanged,
+                    skip_worktree,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// tracked files whose changes git does not look at because of one
+/// of the bits
+pub fn get_hidden_files(repo_path: &str) -> Result<Vec<HiddenFile>> {
+    scope_time!("get_hidden_files");
+
+    let repo = repo(repo_path)?;
+
+    Ok(hidden_paths(&repo.index()?, work_dir(&repo)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_all, stage_add_file,
+        status::{get_status, ShowUntrackedFiles, StatusType},
+        tests::repo_init,
+    };
+    use std::{fs, process::Command};
+
+    #[test]
+    fn test_index_bits_round_trip() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join("a.conf"), "a").unwrap();
+        fs::write(root.join("b.conf"), "b").unwrap();
+        stage_add_file(repo_path, Path::new("a.conf")).unwrap();
+        stage_add_file(repo_path, Path::new("b.conf")).unwrap();
+        commit(repo_path, "add configs").unwrap();
+
+        set_index_bit(
+            repo_path,
+            "a.conf",
+            IndexBit::AssumeUnchanged,
+            true,
+        )
+        .unwrap();
+        set_index_bit(
+            repo_path,
+            "b.conf",
+            IndexBit::SkipWorktree,
+            true,
+        )
+        .unwrap();
+
+        // git itself agrees
+        let output = Command::new("git")
+            .args(["ls-files", "-v"])
+            .current_dir(root)
+            .output()
+            .unwrap();
+        let listed = String::from_utf8_lossy(&output.stdout);
+        assert!(listed.contains("h a.conf"));
+        assert!(listed.contains("S b.conf"));
+
+        assert_eq!(
+            get_hidden_files(repo_path).unwrap(),
+            vec![
+                HiddenFile {
+                    path: String::from("a.conf"),
+                    assume_unchanged: true,
+                    skip_worktree: false,
+                },
+                HiddenFile {
+                    path: String::from("b.conf"),
+                    assume_unchanged: false,
+                    skip_worktree: true,
+                },
+            ]
+        );
+
+        fs::write(root.join("a.conf"), "local a").unwrap();
+        fs::write(root.join("b.conf"), "local b").unwrap();
+        let status = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::Normal,
+        )
+        .unwrap();
+        assert!(status.is_empty());
+
+        stage_add_all(repo_path, "*").unwrap();
+        let staged = get_status(
+            repo_path,
+            StatusType::Stage,
+            ShowUntrackedFiles::Normal,
+        )
+        .unwrap();
+        assert!(staged.is_empty());
+
+        set_index_bit(
+            repo_path,
+            "a.conf",
+            IndexBit::AssumeUnchanged,
+            false,
+        )
+        .unwrap();
+        set_index_bit(
+            repo_path,
+            "b.conf",
+            IndexBit::SkipWorktree,
+            false,
+        )
+        .unwrap();
+
+        assert!(get_hidden_files(repo_path).unwrap().is_empty());
+        assert_eq!(
+            get_status(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::Normal,
+            )
+            .unwrap()
+            .len(),
+            2
+        );
+    }
+}
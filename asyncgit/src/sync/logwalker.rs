@@ -6,6 +6,8 @@ use git2::{Repository, Revwalk};
 pub struct LogWalker<'a> {
     repo: &'a Repository,
     revwalk: Option<Revwalk<'a>>,
+    first_parent: bool,
+    author: Option<String>,
 }
 
 impl<'a> LogWalker<'a> {
@@ -14,9 +16,36 @@ impl<'a> LogWalker<'a> {
         Self {
             repo,
             revwalk: None,
+            first_parent: false,
+            author: None,
         }
     }
 
+    /// only follow the first parent of merge commits
+    /// (like `git log --first-parent`)
+    pub fn first_parent(mut self, enabled: bool) -> Self {
+        self.first_parent = enabled;
+        self
+    }
+
+    /// only list commits authored by `email`
+    pub fn author(mut self, email: Option<String>) -> Self {
+        self.author = email;
+        self
+    }
+
+    fn matches(
+        repo: &Repository,
+        author: Option<&str>,
+        id: git2::Oid,
+    ) -> bool {
+        author.map_or(true, |email| {
+            repo.find_commit(id).map_or(false, |commit| {
+                commit.author().email() == Some(email)
+            })
+        })
+    }
+
     ///
     pub fn read(
         &mut self,
@@ -28,12 +57,20 @@ impl<'a> LogWalker<'a> {
         if self.revwalk.is_none() {
             let mut walk = self.repo.revwalk()?;
             walk.push_head()?;
+            if self.first_parent {
+                walk.simplify_first_parent()?;
+            }
             self.revwalk = Some(walk);
         }
 
+        let author = self.author.as_deref();
         if let Some(ref mut walk) = self.revwalk {
             for id in walk {
                 if let Ok(id) = id {
+                    if !Self::matches(self.repo, author, id) {
+                        continue;
+                    }
+
                     out.push(id.into());
                     count += 1;
 
@@ -112,4 +149,73 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_first_parent() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+
+        let sig = repo.signature()?;
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+
+        let base =
+            repo.commit(None, &sig, &sig, "base", &tree, &[])?;
+        let base = repo.find_commit(base)?;
+        let side =
+            repo.commit(None, &sig, &sig, "side", &tree, &[&base])?;
+        let side = repo.find_commit(side)?;
+        let main =
+            repo.commit(None, &sig, &sig, "main", &tree, &[&base])?;
+        let main = repo.find_commit(main)?;
+        let merge = repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "merge",
+            &tree,
+            &[&main, &side],
+        )?;
+
+        let mut items = Vec::new();
+        LogWalker::new(&repo).read(&mut items, 100)?;
+        assert_eq!(items.len(), 4);
+
+        let mut items = Vec::new();
+        LogWalker::new(&repo)
+            .first_parent(true)
+            .read(&mut items, 100)?;
+        assert_eq!(
+            items,
+            vec![merge.into(), main.id().into(), base.id().into()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_author() -> Result<()> {
+        let file_path = Path::new("foo");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let mut ids = Vec::new();
+        for email in
+            &["a@example.com", "b@example.com", "a@example.com"]
+        {
+            repo.config()?.set_str("user.email", email)?;
+            File::create(&root.join(file_path))?
+                .write_all(email.as_bytes())?;
+            stage_add_file(repo_path, file_path).unwrap();
+            ids.push(commit(repo_path, "msg").unwrap());
+        }
+
+        let mut items = Vec::new();
+        let mut walk = LogWalker::new(&repo)
+            .author(Some(String::from("a@example.com")));
+        walk.read(&mut items, 100).unwrap();
+
+        assert_eq!(items, vec![ids[2], ids[0]]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,390 @@
+//! merging branches via `git merge` and the state of a merge that
+//! stopped before committing
+
+use super::{patches::run_git, utils::repo};
+use crate::error::Result;
+use git2::{BranchType, Commit, Oid, Repository, RepositoryState};
+use scopetime::scope_time;
+use std::fs;
+
+const MERGE_HEAD_FILE: &str = "MERGE_HEAD";
+const MERGE_MSG_FILE: &str = "MERGE_MSG";
+const SQUASH_MSG_FILE: &str = "SQUASH_MSG";
+
+/// keeps `git merge` from running `post-merge` itself, which it only
+/// does for some strategies, see `merge_branch`
+const NO_HOOKS: [&str; 2] = ["-c", "core.hooksPath=/dev/null"];
+
+/// how `merge_branch` joins a branch into the current one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// only moves the current branch forward, fails if it can not
+    FastForwardOnly,
+    /// always prepares a merge commit
+    NoFastForward,
+    /// stages the changes of the branch for an ordinary commit
+    Squash,
+}
+
+impl MergeStrategy {
+    ///
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::FastForwardOnly => "fast-forward only",
+            Self::NoFastForward => "merge commit (no-ff)",
+            Self::Squash => "squash",
+        }
+    }
+
+    /// the `git merge` flags
+    const fn flags(self) -> &'static [&'static str] {
+        match self {
+            Self::FastForwardOnly => &["--ff-only"],
+            Self::NoFastForward => &["--no-ff", "--no-commit"],
+            Self::Squash => &["--squash"],
+        }
+    }
+}
+
+/// what is left to do after `merge_branch`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// the branch has nothing the current one does not have
+    UpToDate,
+    /// the current branch moved forward, nothing to commit
+    FastForwarded,
+    /// the changes are staged and the message is prepared, see
+    /// `merge_msg` and `squash_msg`
+    ReadyToCommit,
+    /// stopped on conflicts, the message lists them
+    Conflicts,
+}
+
+/// merges the local `branch` into the current one via `git merge`,
+/// which seeds the message. a merge commit is not made right away, so
+/// it gets committed (and runs the commit hooks) like any other
+/// commit.
+///
+/// `git merge --no-commit` never runs `post-merge`, not even once the
+/// merge gets committed, so it is left to the caller for every
+/// strategy: `hooks_post_merge` after a fast-forward or squash and
+/// after the commit of a merge
+pub fn merge_branch(
+    repo_path: &str,
+    branch: &str,
+    strategy: MergeStrategy,
+) -> Result<MergeOutcome> {
+    scope_time!("merge_branch");
+
+    let repo = repo(repo_path)?;
+    let head = repo.head()?.peel_to_commit()?.id();
+    let theirs = repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+
+    if head == theirs || repo.graph_descendant_of(head, theirs)? {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    let mut args = NO_HOOKS.to_vec();
+    args.push("merge");
+    args.extend(strategy.flags());
+    args.push(branch);
+
+    match run_git(repo_path, &args) {
+        Ok(_) if strategy == MergeStrategy::FastForwardOnly => {
+            Ok(MergeOutcome::FastForwarded)
+        }
+        Ok(_) => Ok(MergeOutcome::ReadyToCommit),
+        Err(_) if repo.index()?.has_conflicts() => {
+            Ok(MergeOutcome::Conflicts)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// the prepared message (`MERGE_MSG`) if a merge is waiting to be
+/// committed
+pub fn merge_msg(repo_path: &str) -> Result<Option<String>> {
+    scope_time!("merge_msg");
+
+    let repo = repo(repo_path)?;
+
+    if repo.state() != RepositoryState::Merge {
+        return Ok(None);
+    }
+
+    let path = repo.path().join(MERGE_MSG_FILE);
+    Ok(Some(if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    }))
+}
+
+/// the prepared message (`SQUASH_MSG`) of a squash merge that is
+/// waiting to be committed
+pub fn squash_msg(repo_path: &str) -> Result<Option<String>> {
+    scope_time!("squash_msg");
+
+    let repo = repo(repo_path)?;
+
+    let path = repo.path().join(SQUASH_MSG_FILE);
+    Ok(if path.exists() {
+        Some(fs::read_to_string(path)?)
+    } else {
+        None
+    })
+}
+
+/// `git commit` removes the message of a squash merge once it is used
+pub(crate) fn clear_squash_msg(repo: &Repository) -> Result<()> {
+    let path = repo.path().join(SQUASH_MSG_FILE);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// commits listed in `MERGE_HEAD`, empty if no merge is in progress
+pub(crate) fn merge_heads(
+    repo: &Repository,
+) -> Result<Vec<Commit<'_>>> {
+    if repo.state() != RepositoryState::Merge {
+        return Ok(Vec::new());
+    }
+
+    fs::read_to_string(repo.path().join(MERGE_HEAD_FILE))?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(
+            |line| Ok(repo.find_commit(Oid::from_str(line.trim())?)?),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, commit_files::get_commit_files, get_head,
+        stage_add_file, tests::repo_init,
+    };
+    use std::{fs::File, io::Write, path::Path};
+
+    /// leaves `repo` in the middle of merging a branch `other`
+    /// without conflicts
+    pub fn start_merge(repo: &Repository, repo_path: &str) {
+        let root = repo.path().parent().unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+
+        repo.branch("other", &head, false).unwrap();
+        repo.set_head("refs/heads/other").unwrap();
+        File::create(root.join("other"))
+            .unwrap()
+            .write_all(b"other")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("other")).unwrap();
+        commit(repo_path, "on other").unwrap();
+
+        repo.set_head("refs/heads/master").unwrap();
+        repo.checkout_head(Some(
+            git2::build::CheckoutBuilder::new().force(),
+        ))
+        .unwrap();
+        File::create(root.join("master"))
+            .unwrap()
+            .write_all(b"master")
+            .unwrap();
+        stage_add_file(repo_path, Path::new("master")).unwrap();
+        commit(repo_path, "on master").unwrap();
+
+        let other = repo
+            .find_branch("other", BranchType::Local)
+            .unwrap()
+            .into_reference();
+        let other =
+            repo.reference_to_annotated_commit(&other).unwrap();
+        repo.merge(&[&other], None, None).unwrap();
+    }
+
+    #[test]
+    fn test_merge_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let checkout = |branch: &str| {
+            repo.set_head(&format!("refs/heads/{}", branch)).unwrap();
+            repo.checkout_head(Some(
+                git2::build::CheckoutBuilder::new().force(),
+            ))
+            .unwrap();
+        };
+        let commit_file = |name: &str, content: &str| {
+            fs::write(root.join(name), content).unwrap();
+            stage_add_file(repo_path, Path::new(name)).unwrap();
+            commit(repo_path, name).unwrap()
+        };
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        checkout("feature");
+        let feature = commit_file("feature", "feature");
+        checkout("master");
+
+        assert_eq!(
+            merge_branch(repo_path, "master", MergeStrategy::Squash)
+                .unwrap(),
+            MergeOutcome::UpToDate
+        );
+
+        // diverged
+        commit_file("master", "master");
+        assert!(merge_branch(
+            repo_path,
+            "feature",
+            MergeStrategy::FastForwardOnly
+        )
+        .is_err());
+        assert!(!root.join("feature").exists());
+
+        assert_eq!(
+            merge_branch(repo_path, "feature", MergeStrategy::Squash)
+                .unwrap(),
+            MergeOutcome::ReadyToCommit
+        );
+        assert_eq!(merge_msg(repo_path).unwrap(), None);
+        let msg = squash_msg(repo_path).unwrap().unwrap();
+        assert!(msg.starts_with("Squashed commit of the following"));
+        let id = commit(repo_path, "squashed").unwrap();
+        assert_eq!(
+            repo.find_commit(id.into()).unwrap().parent_count(),
+            1
+        );
+        assert_eq!(squash_msg(repo_path).unwrap(), None);
+
+        repo.branch(
+            "later",
+            &repo.find_commit(id.into()).unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "feature",
+                MergeStrategy::NoFastForward
+            )
+            .unwrap(),
+            MergeOutcome::ReadyToCommit
+        );
+        let msg = merge_msg(repo_path).unwrap().unwrap();
+        assert!(msg.starts_with("Merge branch 'feature'"));
+        let id = commit(repo_path, &msg).unwrap();
+        let merge = repo.find_commit(id.into()).unwrap();
+        assert_eq!(merge.parent_id(1).unwrap(), feature.into());
+
+        // `later` is behind master now
+        checkout("later");
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "master",
+                MergeStrategy::FastForwardOnly
+            )
+            .unwrap(),
+            MergeOutcome::FastForwarded
+        );
+        assert_eq!(get_head(repo_path).unwrap(), id);
+
+        // the same file changed on both sides
+        commit_file("master", "later");
+        assert_eq!(
+            merge_branch(repo_path, "feature", MergeStrategy::Squash)
+                .unwrap(),
+            MergeOutcome::UpToDate
+        );
+        checkout("feature");
+        commit_file("master", "feature");
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "later",
+                MergeStrategy::NoFastForward
+            )
+            .unwrap(),
+            MergeOutcome::Conflicts
+        );
+        assert_eq!(repo.state(), RepositoryState::Merge);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_merge_branch_leaves_hook() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let hook = repo.path().join("hooks").join("post-merge");
+        fs::write(&hook, "#!/bin/sh\ntouch ran-post-merge\n")
+            .unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("feature", &head, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        fs::write(root.join("feature"), "feature").unwrap();
+        stage_add_file(repo_path, Path::new("feature")).unwrap();
+        commit(repo_path, "feature").unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+        repo.checkout_head(Some(
+            git2::build::CheckoutBuilder::new().force(),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "feature",
+                MergeStrategy::FastForwardOnly
+            )
+            .unwrap(),
+            MergeOutcome::FastForwarded
+        );
+        // the caller runs it, not `git merge` behind its back
+        assert!(!root.join("ran-post-merge").exists());
+    }
+
+    #[test]
+    fn test_merge_commit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(merge_msg(repo_path).unwrap(), None);
+
+        start_merge(&repo, repo_path);
+
+        let msg = merge_msg(repo_path).unwrap().unwrap();
+        assert!(msg.starts_with("Merge branch 'other'"));
+
+        let id = commit(repo_path, &msg).unwrap();
+        assert_eq!(get_head(repo_path).unwrap(), id);
+
+        let merge = repo.find_commit(id.into()).unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(repo.state(), RepositoryState::Clean);
+        assert_eq!(merge_msg(repo_path).unwrap(), None);
+
+        // compared to the first parent the merge adds `other`
+        let files = get_commit_files(repo_path, id).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "other");
+    }
+}
@@ -1,45 +1,144 @@
 //! sync git api
 
+mod archive;
+mod binary;
+mod bisect;
+mod blob;
 mod branch;
+mod cherry_pick;
 mod commit;
 mod commit_details;
 mod commit_files;
 mod commits_info;
+mod config;
+mod conflicts;
+mod date;
 pub mod diff;
+mod file_history;
+mod filemode;
 mod hooks;
 mod hunks;
 mod ignore;
+mod index_bits;
 mod logwalker;
+mod merge;
+mod notes;
+mod patches;
+mod remotes;
 mod reset;
+mod shortlog;
+mod signature;
+mod sparse;
 mod stash;
+mod state;
+mod stats;
 pub mod status;
+mod submodules;
 mod tags;
+mod undo;
 pub mod utils;
+mod whitespace;
 
-pub(crate) use branch::get_branch_name;
+pub use archive::{archive, ArchiveFormat, ArchiveOptions};
+pub use binary::{BinaryPreview, BinarySide, ImageFormat, ImageInfo};
+pub use bisect::{
+    bisect_mark, bisect_reset, bisect_state, BisectMark, BisectState,
+};
+pub use blob::{get_file_at_commit, is_binary, save_file_at_commit};
+pub use branch::{
+    branch_compare_upstream, create_branch, get_branch_name,
+    get_detached_head, get_local_branches, BranchCompare,
+    DetachedHead,
+};
 
-pub use commit::{amend, commit, tag};
+pub use cherry_pick::cherry_pick;
+pub use commit::{
+    amend, amend_as, author_signature, commit, commit_as, tag,
+    AuthorOverride,
+};
 pub use commit_details::{
     get_commit_details, CommitDetails, CommitMessage,
 };
-pub use commit_files::get_commit_files;
+pub use commit_files::{
+    get_commit_files, get_commit_stats, get_stash_untracked_files,
+    CommitStats, FileStat,
+};
 pub use commits_info::{get_commits_info, CommitId, CommitInfo};
-pub use diff::get_diff_commit;
-pub use hooks::{hooks_commit_msg, hooks_post_commit, HookResult};
+pub use config::{
+    get_config_values, set_config_value, ConfigScope, ConfigValue,
+};
+pub use conflicts::{
+    get_conflict, resolve_conflict, write_conflict_resolution,
+    ConflictFile, ConflictKind, ConflictRegion, ConflictSide,
+};
+pub use date::{format_commit_time, DateFormat};
+pub use diff::{
+    get_diff_commit, get_diff_staged, get_staged_patch,
+    get_staged_preview, DiffOptions, DiffWhitespace,
+};
+pub use file_history::{get_file_history, FileHistoryEntry};
+pub use filemode::{
+    stage_mode_change, toggle_executable, unstage_mode_change,
+};
+pub use hooks::{
+    hooks_applypatch_msg, hooks_commit_msg,
+    hooks_commit_msg_streamed, hooks_fsmonitor,
+    hooks_post_applypatch, hooks_post_checkout, hooks_post_commit,
+    hooks_post_merge, hooks_post_rewrite, hooks_pre_applypatch,
+    hooks_pre_auto_gc, hooks_pre_commit, hooks_pre_commit_streamed,
+    hooks_pre_push, hooks_pre_rebase, hooks_prepare_commit_msg,
+    hooks_status, run_named_hook,
+    FsMonitorChanges, HookResult, HookStatus, PrePushRef,
+    PrepareCommitMsgSource, RewriteCommand,
+};
 pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
 pub use ignore::add_to_ignore;
+pub use index_bits::{
+    get_hidden_files, set_index_bit, HiddenFile, IndexBit,
+};
 pub use logwalker::LogWalker;
+pub use merge::{
+    merge_branch, merge_msg, squash_msg, MergeOutcome, MergeStrategy,
+};
+pub use notes::{delete_note, get_note, set_note};
+pub use patches::{
+    am_in_progress, apply_mailbox, format_patch, format_patch_stdout,
+};
+pub use remotes::{
+    fetch_all, fetch_remote, get_remote_urls, get_remotes, push,
+    set_remote_url, validate_remote_url, FetchAllResult, PushMode,
+    PushProgress, PushRequest, PushTarget, RemoteProgress,
+    RemoteUrls,
+};
 pub use reset::{reset_stage, reset_workdir};
-pub use stash::{get_stashes, stash_apply, stash_drop, stash_save};
-pub use tags::{get_tags, CommitTags, Tags};
+pub use shortlog::{
+    get_author_line_stats, get_shortlog, AuthorSummary, LineStats,
+};
+pub use signature::{verify_commit_signature, SignatureStatus};
+pub use stash::{
+    get_stashes, stash_apply, stash_branch, stash_drop, stash_save,
+    stash_save_paths, StashApplyOutcome,
+};
+pub use state::{
+    operation_action, operation_state, OperationAction,
+    OperationState, RepoOperation,
+};
+pub use stats::{get_repo_stats, RepoStats};
+pub use submodules::{
+    get_submodules, submodule_init, submodule_path, submodule_update,
+    SubmoduleInfo, SubmoduleStatus,
+};
+pub use tags::{compare_versions, get_tags, CommitTags, Tags};
+pub use undo::{snapshot_discard, snapshot_stash, undo, Undo};
 pub use utils::{
-    get_head, is_bare_repo, is_repo, stage_add_all, stage_add_file,
-    stage_addremoved,
+    get_head, get_pseudo_ref, is_bare_repo, is_repo, stage_add_all,
+    stage_add_file, stage_addremoved,
 };
+pub use whitespace::strip_staged_whitespace;
 
 #[cfg(test)]
 mod tests {
-    use super::status::{get_status, StatusType};
+    use super::status::{get_status, ShowUntrackedFiles, StatusType};
     use crate::error::Result;
     use git2::Repository;
     use std::process::Command;
@@ -86,12 +185,20 @@ mod tests {
     /// helper returning amount of files with changes in the (wd,stage)
     pub fn get_statuses(repo_path: &str) -> (usize, usize) {
         (
-            get_status(repo_path, StatusType::WorkingDir, true)
-                .unwrap()
-                .len(),
-            get_status(repo_path, StatusType::Stage, true)
-                .unwrap()
-                .len(),
+            get_status(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::All,
+            )
+            .unwrap()
+            .len(),
+            get_status(
+                repo_path,
+                StatusType::Stage,
+                ShowUntrackedFiles::All,
+            )
+            .unwrap()
+            .len(),
         )
     }
 
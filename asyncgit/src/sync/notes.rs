@@ -0,0 +1,83 @@
+//! `git notes` of commits in the default notes ref
+//! (`refs/notes/commits` unless `core.notesRef` says otherwise)
+
+use super::{
+    commit::signature_allow_undefined_name, utils::repo, CommitId,
+};
+use crate::error::Result;
+use git2::ErrorCode;
+use scopetime::scope_time;
+
+/// the note attached to commit `id`, if any
+pub fn get_note(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<Option<String>> {
+    scope_time!("get_note");
+
+    let repo = repo(repo_path)?;
+
+    let note = match repo.find_note(None, id.into()) {
+        Ok(note) => note,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Some(String::from_utf8_lossy(note.message_bytes()).into()))
+}
+
+/// attaches `message` as note to commit `id`, replacing its note
+pub fn set_note(
+    repo_path: &str,
+    id: CommitId,
+    message: &str,
+) -> Result<()> {
+    scope_time!("set_note");
+
+    let repo = repo(repo_path)?;
+    let sig = signature_allow_undefined_name(&repo)?;
+
+    repo.note(&sig, &sig, None, id.into(), message, true)?;
+
+    Ok(())
+}
+
+/// removes the note of commit `id`
+pub fn delete_note(repo_path: &str, id: CommitId) -> Result<()> {
+    scope_time!("delete_note");
+
+    let repo = repo(repo_path)?;
+    let sig = signature_allow_undefined_name(&repo)?;
+
+    repo.note_delete(id.into(), None, &sig, &sig)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{get_commit_details, tests::repo_init};
+
+    #[test]
+    fn test_notes() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let id =
+            CommitId::new(repo.head().unwrap().target().unwrap());
+
+        assert_eq!(get_note(repo_path, id).unwrap(), None);
+        assert!(delete_note(repo_path, id).is_err());
+
+        set_note(repo_path, id, "reviewed").unwrap();
+        set_note(repo_path, id, "reviewed\n\nby me").unwrap();
+        assert_eq!(
+            get_commit_details(repo_path, id).unwrap().note,
+            Some(String::from("reviewed\n\nby me"))
+        );
+
+        delete_note(repo_path, id).unwrap();
+        assert_eq!(get_note(repo_path, id).unwrap(), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! mail patches: exported via `git format-patch` and applied via
+//! `git am` (libgit2 cannot read the mailbox format)
+
+use super::{utils::repo, CommitId};
+use crate::error::{Error, Result};
+use git2::RepositoryState;
+use scopetime::scope_time;
+use std::{path::Path, process::Command};
+
+/// writes one numbered `.patch` file per commit (in the given
+/// order) into `out_dir`, returns the paths of the written files
+pub fn format_patch(
+    repo_path: &str,
+    commits: &[CommitId],
+    out_dir: &Path,
+) -> Result<Vec<String>> {
+    scope_time!("format_patch");
+
+    let mut files = Vec::with_capacity(commits.len());
+
+    for (idx, id) in commits.iter().enumerate() {
+        let out = run_git(
+            repo_path,
+            &[
+                "format-patch",
+                "-o",
+                &out_dir.to_string_lossy(),
+                "--start-number",
+                &(idx + 1).to_string(),
+                "-1",
+                &id.to_string(),
+            ],
+        )?;
+
+        files.extend(
+            out.lines()
+                .filter(|line| !line.is_empty())
+                .map(String::from),
+        );
+    }
+
+    Ok(files)
+}
+
+/// the patches `format_patch` would write, concatenated
+pub fn format_patch_stdout(
+    repo_path: &str,
+    commits: &[CommitId],
+) -> Result<String> {
+    scope_time!("format_patch_stdout");
+
+    let mut res = String::new();
+
+    for (idx, id) in commits.iter().enumerate() {
+        res.push_str(&run_git(
+            repo_path,
+            &[
+                "format-patch",
+                "--stdout",
+                "--start-number",
+                &(idx + 1).to_string(),
+                "-1",
+                &id.to_string(),
+            ],
+        )?);
+    }
+
+    Ok(res)
+}
+
+/// applies the patch files in the given order via `git am`, if a
+/// patch does not apply the session stops (see `am_in_progress`)
+pub fn apply_mailbox(
+    repo_path: &str,
+    files: &[String],
+) -> Result<()> {
+    scope_time!("apply_mailbox");
+
+    let mut args = vec!["am", "--3way"];
+    args.extend(files.iter().map(String::as_str));
+
+    run_git(repo_path, &args)?;
+
+    Ok(())
+}
+
+/// `true` while a `git am` session waits for `operation_action`
+pub fn am_in_progress(repo_path: &str) -> Result<bool> {
+    let repo = repo(repo_path)?;
+
+    Ok(matches!(
+        repo.state(),
+        RepositoryState::ApplyMailbox
+            | RepositoryState::ApplyMailboxOrRebase
+    ) && repo
+        .path()
+        .join("rebase-apply")
+        .join("applying")
+        .exists())
+}
+
+/// runs git in the work dir, fails with the output of git if it
+/// does not succeed. git must not wait for an editor or a password
+/// prompt behind the ui
+pub(crate) fn run_git(
+    repo_path: &str,
+    args: &[&str],
+) -> Result<String> {
+    let repo = repo(repo_path)?;
+    let work_dir = repo.workdir().unwrap_or_else(|| repo.path());
+
+    let output = Command::new("git")
+        .args(args)
+        .env("GIT_EDITOR", "true")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .current_dir(work_dir)
+        .output()?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        // the subcommand, behind any `-c key=value` options
+        let name = args
+            .iter()
+            .find(|arg| !arg.starts_with('-') && !arg.contains('='))
+            .unwrap_or(&"");
+        Err(Error::Generic(format!(
+            "git {} failed:\n{}{}",
+            name,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, operation_action, stage_add_file, tests::repo_init,
+        OperationAction, RepoOperation,
+    };
+    use std::{fs::File, io::Write};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_patch() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let file_path = Path::new("foo");
+        File::create(root.join(file_path))?.write_all(b"a")?;
+        stage_add_file(repo_path, file_path)?;
+        let first = commit(repo_path, "add foo")?;
+        File::create(root.join(file_path))?.write_all(b"b")?;
+        stage_add_file(repo_path, file_path)?;
+        let second = commit(repo_path, "change foo")?;
+
+        let out = TempDir::new()?;
+        let files =
+            format_patch(repo_path, &[first, second], out.path())?;
+
+        assert_eq!(files.len(), 2);
+        assert!(files[0].ends_with("0001-add-foo.patch"));
+        assert!(files[1].ends_with("0002-change-foo.patch"));
+        assert!(files.iter().all(|f| Path::new(f).is_file()));
+
+        let text = format_patch_stdout(repo_path, &[second])?;
+        assert!(text.contains("Subject: [PATCH] change foo"));
+        assert!(text.contains("+b"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_mailbox() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let initial = repo.head()?.peel_to_commit()?;
+
+        let file_path = Path::new("foo");
+        File::create(root.join(file_path))?.write_all(b"a\n")?;
+        stage_add_file(repo_path, file_path)?;
+        let first = commit(repo_path, "add foo")?;
+        File::create(root.join(file_path))?.write_all(b"b\n")?;
+        stage_add_file(repo_path, file_path)?;
+        let second = commit(repo_path, "change foo")?;
+
+        let out = TempDir::new()?;
+        let files =
+            format_patch(repo_path, &[first, second], out.path())?;
+
+        repo.reset(initial.as_object(), git2::ResetType::Hard, None)?;
+        apply_mailbox(repo_path, &files)?;
+
+        let head = repo.head()?.peel_to_commit()?;
+        assert_eq!(head.summary(), Some("change foo"));
+        assert_eq!(head.parent(0)?.summary(), Some("add foo"));
+        assert!(!am_in_progress(repo_path)?);
+
+        // `change foo` does not apply on top of `initial`
+        repo.reset(initial.as_object(), git2::ResetType::Hard, None)?;
+        assert!(apply_mailbox(repo_path, &files[1..]).is_err());
+        assert!(am_in_progress(repo_path)?);
+
+        operation_action(
+            repo_path,
+            RepoOperation::ApplyMailbox,
+            OperationAction::Abort,
+        )?;
+        assert!(!am_in_progress(repo_path)?);
+        assert_eq!(repo.head()?.peel_to_commit()?.id(), initial.id());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,757 @@
+//! sync git api for remotes
+
+use super::{
+    hooks::{has_pre_push_hook, hooks_pre_push, PrePushRef},
+    patches::run_git,
+    utils::repo,
+    CommitId, HookResult,
+};
+use crate::error::{Error, Result};
+use git2::{FetchOptions, FetchPrune, RemoteCallbacks};
+use scopetime::scope_time;
+use std::time::Duration;
+
+/// progress of a single remote while fetching all remotes
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct RemoteProgress {
+    /// name of the remote currently being fetched
+    pub remote: String,
+    /// index of the remote in the list of all remotes
+    pub remote_index: usize,
+    /// amount of remotes in total
+    pub remote_count: usize,
+    /// objects received so far from this remote
+    pub received_objects: usize,
+    /// total objects to receive from this remote
+    pub total_objects: usize,
+}
+
+impl RemoteProgress {
+    /// progress of the current remote in percent (0-100)
+    pub fn percent(&self) -> u8 {
+        #[allow(clippy::cast_possible_truncation)]
+        (self.received_objects * 100)
+            .checked_div(self.total_objects)
+            .map_or(100, |percent| percent.min(100) as u8)
+    }
+}
+
+/// summary of fetching all remotes
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct FetchAllResult {
+    /// remotes that were fetched successfully
+    pub succeeded: Vec<String>,
+    /// remotes that failed to fetch, with their error
+    pub failed: Vec<(String, String)>,
+}
+
+impl FetchAllResult {
+    /// `true` if not a single remote failed
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// returns the names of all configured remotes
+pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
+    scope_time!("get_remotes");
+
+    let repo = repo(repo_path)?;
+    let remotes = repo.remotes()?;
+
+    Ok(remotes.iter().flatten().map(String::from).collect())
+}
+
+/// where a remote fetches from and pushes to
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct RemoteUrls {
+    /// `remote.<name>.url`
+    pub url: Option<String>,
+    /// `remote.<name>.pushurl`, pushes go to `url` without one
+    pub push_url: Option<String>,
+}
+
+impl RemoteUrls {
+    /// the url a push goes to
+    pub fn push_target(&self) -> Option<&str> {
+        self.push_url.as_deref().or(self.url.as_deref())
+    }
+}
+
+/// schemes a remote url may have, `user@host:path` is ssh too
+const URL_SCHEMES: [&str; 4] = ["https", "ssh", "git", "file"];
+
+/// fetch and push url of the remote
+pub fn get_remote_urls(
+    repo_path: &str,
+    remote: &str,
+) -> Result<RemoteUrls> {
+    scope_time!("get_remote_urls");
+
+    let repo = repo(repo_path)?;
+    let remote = repo.find_remote(remote)?;
+
+    Ok(RemoteUrls {
+        url: remote.url().map(String::from),
+        push_url: remote.pushurl().map(String::from),
+    })
+}
+
+/// errors naming the problem unless `url` is an absolute path or uses
+/// one of the `URL_SCHEMES`
+pub fn validate_remote_url(url: &str) -> Result<()> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(Error::Generic(String::from("empty url")));
+    }
+
+    if let Some(pos) = url.find("://") {
+        let scheme = &url[..pos];
+        return if URL_SCHEMES.contains(&scheme) && url.len() > pos + 3
+        {
+            Ok(())
+        } else {
+            Err(Error::Generic(format!(
+                "unsupported scheme '{}', use one of: {}",
+                scheme,
+                URL_SCHEMES.join(", ")
+            )))
+        };
+    }
+
+    // scp like `git@host:path`, the colon before any slash
+    let scp_like = url.find(':').map_or(false, |colon| {
+        colon > 0 && !url[..colon].contains('/')
+    });
+    if scp_like || url.starts_with('/') {
+        Ok(())
+    } else {
+        Err(Error::Generic(format!(
+            "'{}' is neither a url nor an absolute path",
+            url
+        )))
+    }
+}
+
+/// sets the fetch url of the remote or its push url if `push`
+pub fn set_remote_url(
+    repo_path: &str,
+    remote: &str,
+    url: &str,
+    push: bool,
+) -> Result<()> {
+    scope_time!("set_remote_url");
+
+    validate_remote_url(url)?;
+
+    let repo = repo(repo_path)?;
+    if push {
+        repo.remote_set_pushurl(remote, Some(url.trim()))?;
+    } else {
+        repo.remote_set_url(remote, url.trim())?;
+    }
+
+    Ok(())
+}
+
+/// fetches a single remote with pruning of stale remote branches.
+/// `progress` is called with (received, total) objects
+pub fn fetch_remote<F>(
+    repo_path: &str,
+    remote: &str,
+    mut progress: F,
+) -> Result<()>
+where
+    F: FnMut(usize, usize),
+{
+    scope_time!("fetch_remote");
+
+    let repo = repo(repo_path)?;
+    let mut remote = repo.find_remote(remote)?;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.transfer_progress(|p| {
+        progress(p.received_objects(), p.total_objects());
+        true
+    });
+
+    let mut options = FetchOptions::new();
+    options.prune(FetchPrune::On);
+    options.remote_callbacks(callbacks);
+
+    remote.fetch(&[] as &[&str], Some(&mut options), None)?;
+
+    Ok(())
+}
+
+/// fetches all configured remotes (with prune).
+/// a failing remote does not abort fetching the others,
+/// see `FetchAllResult` for which ones succeeded and which failed
+pub fn fetch_all<F>(
+    repo_path: &str,
+    mut progress: F,
+) -> Result<FetchAllResult>
+where
+    F: FnMut(&RemoteProgress),
+{
+    scope_time!("fetch_all");
+
+    let remotes = get_remotes(repo_path)?;
+    let remote_count = remotes.len();
+
+    let mut res = FetchAllResult::default();
+
+    for (remote_index, remote) in remotes.into_iter().enumerate() {
+        let mut state = RemoteProgress {
+            remote: remote.clone(),
+            remote_index,
+            remote_count,
+            ..RemoteProgress::default()
+        };
+
+        progress(&state);
+
+        let fetch_res =
+            fetch_remote(repo_path, &remote, |received, total| {
+                state.received_objects = received;
+                state.total_objects = total;
+                progress(&state);
+            });
+
+        match fetch_res {
+            Ok(_) => res.succeeded.push(remote),
+            Err(e) => {
+                log::error!("fetch of '{}' failed: {}", remote, e);
+                res.failed.push((remote, e.to_string()));
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// how `push` treats a remote branch that is not an ancestor of
+/// the local one
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PushMode {
+    /// fast-forward only
+    Normal,
+    /// overwrite whatever the remote branch points to
+    Force,
+    /// overwrite the remote branch only if it still points to where
+    /// our remote-tracking branch says, like `--force-with-lease`
+    ForceWithLease,
+}
+
+impl PushMode {
+    /// the `git push` flag
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "",
+            Self::Force => "--force",
+            Self::ForceWithLease => "--force-with-lease",
+        }
+    }
+}
+
+/// what a push sends, to the ref of the same name on the remote
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushTarget {
+    ///
+    Branch(String),
+    ///
+    Tag(String),
+}
+
+impl PushTarget {
+    /// name of the branch or tag
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Branch(name) | Self::Tag(name) => name,
+        }
+    }
+
+    /// the full name of the ref, like `refs/tags/v1.0`
+    pub fn refname(&self) -> String {
+        match self {
+            Self::Branch(name) => format!("refs/heads/{}", name),
+            Self::Tag(name) => format!("refs/tags/{}", name),
+        }
+    }
+}
+
+/// a local branch or tag to push to a remote
+#[derive(Debug, Clone, PartialEq)]
+pub struct PushRequest {
+    ///
+    pub remote: String,
+    ///
+    pub target: PushTarget,
+    ///
+    pub mode: PushMode,
+    /// skips the `pre-push` hook, like `git push --no-verify`
+    pub no_verify: bool,
+}
+
+/// what a running push is busy with
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PushProgress {
+    /// the `pre-push` hook runs, only reported if there is one
+    Hook,
+    /// git talks to the remote
+    Transfer,
+}
+
+/// `git push <remote> <ref>:<ref>` of `request.target`, like that it
+/// uses the credentials git is set up with (ssh-agent, credential
+/// helpers). a rejected ref update is an error naming the reason.
+///
+/// the `pre-push` hook runs before that (unless `no_verify` is set),
+/// here rather than in git so its output can be shown. if it fails
+/// nothing gets pushed and its result is returned, the caller has to
+/// report it.
+///
+/// a tag has no remote-tracking ref, so `ForceWithLease` forces like
+/// `Force` for one
+pub fn push<F>(
+    repo_path: &str,
+    request: &PushRequest,
+    hooks_timeout: Option<Duration>,
+    mut progress: F,
+) -> Result<HookResult>
+where
+    F: FnMut(PushProgress),
+{
+    scope_time!("push");
+
+    let hook = if request.no_verify || !has_pre_push_hook(repo_path)?
+    {
+        HookResult::NoHook
+    } else {
+        progress(PushProgress::Hook);
+
+        let hook = hooks_pre_push(
+            repo_path,
+            &request.remote,
+            &push_url(repo_path, &request.remote),
+            &[pre_push_ref(repo_path, request)?],
+            hooks_timeout,
+        )?;
+        if hook != HookResult::Ok {
+            return Ok(hook);
+        }
+        hook
+    };
+
+    progress(PushProgress::Transfer);
+
+    let mode = match (&request.target, request.mode) {
+        (PushTarget::Tag(_), PushMode::ForceWithLease) => {
+            PushMode::Force
+        }
+        (_, mode) => mode,
+    };
+
+    let refname = request.target.refname();
+    let refspec = format!("{}:{}", refname, refname);
+
+    let mut args = vec!["push", "--no-verify"];
+    if mode != PushMode::Normal {
+        args.push(mode.name());
+    }
+    args.push(&request.remote);
+    args.push(&refspec);
+
+    run_git(repo_path, &args)?;
+
+    Ok(hook)
+}
+
+/// what the `pre-push` hook gets as url, `remote` itself if that is
+/// no configured remote (like git does for a path given as remote)
+fn push_url(repo_path: &str, remote: &str) -> String {
+    get_remote_urls(repo_path, remote)
+        .ok()
+        .and_then(|urls| urls.push_target().map(String::from))
+        .unwrap_or_else(|| remote.to_string())
+}
+
+/// the ref update `request` makes. what the remote ref points to is
+/// taken from the remote-tracking branch, a tag is assumed to be new
+/// on the remote since there is no such thing for tags
+fn pre_push_ref(
+    repo_path: &str,
+    request: &PushRequest,
+) -> Result<PrePushRef> {
+    let repo = repo(repo_path)?;
+    let refname = request.target.refname();
+
+    let local_id = CommitId::new(repo.refname_to_id(&refname)?);
+    let remote_id = match &request.target {
+        PushTarget::Branch(name) => repo
+            .refname_to_id(&format!(
+                "refs/remotes/{}/{}",
+                request.remote, name
+            ))
+            .ok()
+            .map(CommitId::new),
+        PushTarget::Tag(_) => None,
+    };
+
+    Ok(PrePushRef {
+        local_ref: refname.clone(),
+        local_id: Some(local_id),
+        remote_ref: refname,
+        remote_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        get_branch_name, tag,
+        tests::{repo_init, repo_init_empty},
+        CommitId,
+    };
+    use git2::{Oid, Repository};
+    use std::fs;
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(get_remotes(repo_path).unwrap().is_empty());
+
+        let res = fetch_all(repo_path, |_| ()).unwrap();
+
+        assert!(res.is_ok());
+        assert!(res.succeeded.is_empty());
+    }
+
+    #[test]
+    fn test_remote_urls() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        repo.remote("origin", "https://example.com/a.git").unwrap();
+
+        let urls = get_remote_urls(repo_path, "origin").unwrap();
+        assert_eq!(
+            urls.push_target(),
+            Some("https://example.com/a.git")
+        );
+
+        set_remote_url(
+            repo_path,
+            "origin",
+            "git@example.com:b.git",
+            true,
+        )
+        .unwrap();
+        set_remote_url(
+            repo_path,
+            "origin",
+            "ssh://example.com/c",
+            false,
+        )
+        .unwrap();
+        let urls = get_remote_urls(repo_path, "origin").unwrap();
+        assert_eq!(urls.url.as_deref(), Some("ssh://example.com/c"));
+        assert_eq!(urls.push_target(), Some("git@example.com:b.git"));
+
+        assert!(set_remote_url(
+            repo_path,
+            "origin",
+            "ftp://x/y",
+            false
+        )
+        .is_err());
+        assert!(validate_remote_url("file:///srv/repo.git").is_ok());
+        assert!(validate_remote_url("/srv/repo.git").is_ok());
+        assert!(validate_remote_url("relative/repo").is_err());
+        assert!(validate_remote_url("https://").is_err());
+    }
+
+    #[test]
+    fn test_fetch_all_prune() {
+        let (_upstream_td, upstream) = repo_init().unwrap();
+        let upstream_path =
+            upstream.path().parent().unwrap().to_str().unwrap();
+
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        repo.remote("origin", upstream_path).unwrap();
+        repo.remote("broken", "/does/not/exist").unwrap();
+
+        {
+            let head = upstream.head().unwrap().target().unwrap();
+            let commit = upstream.find_commit(head).unwrap();
+            upstream.branch("stale", &commit, false).unwrap();
+        }
+
+        let mut updates = Vec::new();
+        let res = fetch_all(repo_path, |p| updates.push(p.clone()))
+            .unwrap();
+
+        assert_eq!(res.succeeded, vec![String::from("origin")]);
+        assert_eq!(res.failed.len(), 1);
+        assert_eq!(res.failed[0].0, "broken");
+        assert!(updates.iter().any(|p| p.remote == "broken"
+            && p.remote_index == 0
+            && p.remote_count == 2));
+
+        assert!(repo
+            .find_reference("refs/remotes/origin/stale")
+            .is_ok());
+
+        upstream
+            .find_branch("stale", git2::BranchType::Local)
+            .unwrap()
+            .delete()
+            .unwrap();
+
+        let res = fetch_all(repo_path, |_| ()).unwrap();
+        assert_eq!(res.succeeded, vec![String::from("origin")]);
+
+        assert!(repo
+            .find_reference("refs/remotes/origin/stale")
+            .is_err());
+    }
+
+    #[test]
+    fn test_push_force_with_lease() {
+        let upstream_td = tempfile::TempDir::new().unwrap();
+        let upstream =
+            Repository::init_bare(upstream_td.path()).unwrap();
+        let upstream_path = upstream_td.path().to_str().unwrap();
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        repo.remote("origin", upstream_path).unwrap();
+
+        let branch = get_branch_name(repo_path).unwrap();
+        let branch_ref = format!("refs/heads/{}", branch);
+        let tracking_ref = format!("refs/remotes/origin/{}", branch);
+
+        // a new commit on top of `parent` that the branch points to
+        let commit = |repo: &Repository, parent: Oid, msg: &str| {
+            let sig = git2::Signature::now("name", "email").unwrap();
+            let parent = repo.find_commit(parent).unwrap();
+            let id = repo
+                .commit(
+                    None,
+                    &sig,
+                    &sig,
+                    msg,
+                    &parent.tree().unwrap(),
+                    &[&parent],
+                )
+                .unwrap();
+            repo.reference(&branch_ref, id, true, "").unwrap();
+            id
+        };
+
+        let mut request = PushRequest {
+            remote: String::from("origin"),
+            target: PushTarget::Branch(branch.clone()),
+            mode: PushMode::Normal,
+            no_verify: false,
+        };
+
+        let c1 = repo.refname_to_id(&branch_ref).unwrap();
+        let c2 = commit(&repo, c1, "c2");
+        push(repo_path, &request, None, |_| ()).unwrap();
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c2);
+        assert_eq!(repo.refname_to_id(&tracking_ref).unwrap(), c2);
+
+        // rewritten history
+        let c3 = commit(&repo, c1, "c3");
+        assert!(push(repo_path, &request, None, |_| ()).is_err());
+
+        request.mode = PushMode::ForceWithLease;
+        push(repo_path, &request, None, |_| ()).unwrap();
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c3);
+
+        // someone else pushed in the meantime
+        let c4 = commit(&upstream, c3, "c4");
+        let c5 = commit(&repo, c1, "c5");
+        assert!(matches!(
+            push(repo_path, &request, None, |_| ()),
+            Err(Error::Generic(msg)) if msg.contains("stale info")
+        ));
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c4);
+
+        request.mode = PushMode::Force;
+        push(repo_path, &request, None, |_| ()).unwrap();
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c5);
+    }
+
+    #[test]
+    fn test_push_tag() {
+        let upstream_td = tempfile::TempDir::new().unwrap();
+        let upstream =
+            Repository::init_bare(upstream_td.path()).unwrap();
+        let upstream_path = upstream_td.path().to_str().unwrap();
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        repo.remote("origin", upstream_path).unwrap();
+
+        let head =
+            CommitId::new(repo.head().unwrap().target().unwrap());
+        tag(repo_path, &head, "v1", None).unwrap();
+
+        let mut request = PushRequest {
+            remote: String::from("origin"),
+            target: PushTarget::Tag(String::from("v1")),
+            mode: PushMode::Normal,
+            no_verify: false,
+        };
+        push(repo_path, &request, None, |_| ()).unwrap();
+        assert_eq!(
+            upstream.refname_to_id("refs/tags/v1").unwrap(),
+            head.get_oid()
+        );
+
+        // the tag moved to a new annotated tag object
+        repo.tag_delete("v1").unwrap();
+        tag(repo_path, &head, "v1", Some("release")).unwrap();
+        assert!(push(repo_path, &request, None, |_| ()).is_err());
+
+        request.mode = PushMode::Force;
+        push(repo_path, &request, None, |_| ()).unwrap();
+        assert_eq!(
+            upstream.refname_to_id("refs/tags/v1").unwrap(),
+            repo.refname_to_id("refs/tags/v1").unwrap()
+        );
+
+        request.target = PushTarget::Tag(String::from("missing"));
+        assert!(push(repo_path, &request, None, |_| ()).is_err());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_push_pre_push_hook() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let upstream_td = tempfile::TempDir::new().unwrap();
+        let upstream =
+            Repository::init_bare(upstream_td.path()).unwrap();
+        let upstream_path = upstream_td.path().to_str().unwrap();
+
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        repo.remote("origin", upstream_path).unwrap();
+
+        let hook = repo.path().join("hooks").join("pre-push");
+        fs::write(
+            &hook,
+            "#!/bin/sh
+echo \"$1 $2\" > pre-push-args
+cat > pre-push-stdin
+[ -e allow-push ] || { echo \"push rejected\"; exit 1; }
+",
+        )
+        .unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        let branch = get_branch_name(repo_path).unwrap();
+        let branch_ref = format!("refs/heads/{}", branch);
+        let mut request = PushRequest {
+            remote: String::from("origin"),
+            target: PushTarget::Branch(branch),
+            mode: PushMode::Normal,
+            no_verify: false,
+        };
+
+        // a new commit on top of HEAD
+        let commit = |msg: &str| {
+            let sig = git2::Signature::now("name", "email").unwrap();
+            let parent =
+                repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                msg,
+                &parent.tree().unwrap(),
+                &[&parent],
+            )
+            .unwrap()
+        };
+
+        let c1 = repo.refname_to_id(&branch_ref).unwrap();
+        let mut progress = Vec::new();
+        assert_eq!(
+            push(repo_path, &request, None, |p| progress.push(p))
+                .unwrap(),
+            HookResult::NotOk {
+                hook: String::from("pre-push"),
+                code: Some(1),
+                output: String::from("push rejected\n"),
+            }
+        );
+        assert_eq!(progress, vec![PushProgress::Hook]);
+        assert!(upstream.refname_to_id(&branch_ref).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("pre-push-args")).unwrap(),
+            format!("origin {}\n", upstream_path)
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("pre-push-stdin")).unwrap(),
+            format!(
+                "{} {} {} {}\n",
+                branch_ref,
+                c1,
+                branch_ref,
+                Oid::zero()
+            )
+        );
+
+        fs::write(root.join("allow-push"), "").unwrap();
+        assert_eq!(
+            push(repo_path, &request, None, |_| ()).unwrap(),
+            HookResult::Ok
+        );
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c1);
+
+        // the remote side is known once pushed
+        let c2 = commit("c2");
+        let mut progress = Vec::new();
+        push(repo_path, &request, None, |p| progress.push(p))
+            .unwrap();
+        assert_eq!(
+            progress,
+            vec![PushProgress::Hook, PushProgress::Transfer]
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("pre-push-stdin")).unwrap(),
+            format!("{} {} {} {}\n", branch_ref, c2, branch_ref, c1)
+        );
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c2);
+
+        // skipping the hook pushes what it would reject
+        fs::remove_file(root.join("allow-push")).unwrap();
+        fs::remove_file(root.join("pre-push-stdin")).unwrap();
+        let c3 = commit("c3");
+        request.no_verify = true;
+        let mut progress = Vec::new();
+        assert_eq!(
+            push(repo_path, &request, None, |p| progress.push(p))
+                .unwrap(),
+            HookResult::NoHook
+        );
+        assert_eq!(progress, vec![PushProgress::Transfer]);
+        assert!(!root.join("pre-push-stdin").exists());
+        assert_eq!(upstream.refname_to_id(&branch_ref).unwrap(), c3);
+    }
+}
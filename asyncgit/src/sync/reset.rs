@@ -44,7 +44,7 @@ mod tests {
     use crate::error::Result;
     use crate::sync::{
         commit,
-        status::{get_status, StatusType},
+        status::{get_status, ShowUntrackedFiles, StatusType},
         tests::{
             debug_cmd_print, get_statuses, repo_init, repo_init_empty,
         },
@@ -88,8 +88,12 @@ mod tests {
         let root = repo.path().parent().unwrap();
         let repo_path = root.as_os_str().to_str().unwrap();
 
-        let res = get_status(repo_path, StatusType::WorkingDir, true)
-            .unwrap();
+        let res = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::All,
+        )
+        .unwrap();
         assert_eq!(res.len(), 0);
 
         let file_path = root.join("bar.txt");
@@ -311,4 +315,23 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (0, 0));
     }
+
+    #[test]
+    fn test_reset_untracked_dir_with_slash() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::create_dir_all(root.join("foo/bar")).unwrap();
+        File::create(root.join("foo/bar/baz.txt"))
+            .unwrap()
+            .write_all(b"test\nfoo")
+            .unwrap();
+
+        // untracked dirs are listed like this in the status
+        reset_workdir(repo_path, "foo/").unwrap();
+
+        assert_eq!(get_statuses(repo_path), (0, 0));
+        assert!(!root.join("foo/bar/baz.txt").exists());
+    }
 }
@@ -0,0 +1,184 @@
+//! commits per author (like `git shortlog -sne`)
+
+use super::utils::repo;
+use crate::error::Result;
+use git2::{Commit, Repository};
+use scopetime::scope_time;
+use std::collections::HashMap;
+
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorSummary {
+    /// name used in the most recent commit
+    pub name: String,
+    ///
+    pub email: String,
+    ///
+    pub commits: usize,
+    /// time of the oldest commit (secs since Unix epoch)
+    pub first_time: i64,
+    /// time of the newest commit (secs since Unix epoch)
+    pub last_time: i64,
+}
+
+///
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LineStats {
+    ///
+    pub insertions: usize,
+    ///
+    pub deletions: usize,
+}
+
+/// authors of all commits reachable from `HEAD` grouped by email,
+/// most commits first
+pub fn get_shortlog(repo_path: &str) -> Result<Vec<AuthorSummary>> {
+    scope_time!("get_shortlog");
+
+    let repo = repo(repo_path)?;
+
+    let mut authors: HashMap<String, AuthorSummary> = HashMap::new();
+
+    for commit in head_commits(&repo)? {
+        let commit = commit?;
+        let author = commit.author();
+        let email = author.email().unwrap_or_default().to_string();
+        let time = author.when().seconds();
+
+        let summary =
+            authors.entry(email.clone()).or_insert_with(|| {
+                AuthorSummary {
+                    name: String::new(),
+                    email,
+                    commits: 0,
+                    first_time: time,
+                    last_time: time,
+                }
+            });
+
+        summary.commits += 1;
+        summary.first_time = summary.first_time.min(time);
+        if time >= summary.last_time || summary.name.is_empty() {
+            summary.last_time = time;
+            summary.name =
+                author.name().unwrap_or_default().to_string();
+        }
+    }
+
+    let mut res =
+        authors.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+    res.sort_by(|a, b| {
+        b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(res)
+}
+
+/// lines added and removed by the commits of `email` reachable from
+/// `HEAD` (compared to their first parent)
+pub fn get_author_line_stats(
+    repo_path: &str,
+    email: &str,
+) -> Result<LineStats> {
+    scope_time!("get_author_line_stats");
+
+    let repo = repo(repo_path)?;
+
+    let mut res = LineStats::default();
+
+    for commit in head_commits(&repo)? {
+        let commit = commit?;
+        if commit.author().email() != Some(email) {
+            continue;
+        }
+
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let stats = repo
+            .diff_tree_to_tree(
+                parent_tree.as_ref(),
+                Some(&tree),
+                None,
+            )?
+            .stats()?;
+
+        res.insertions += stats.insertions();
+        res.deletions += stats.deletions();
+    }
+
+    Ok(res)
+}
+
+fn head_commits(
+    repo: &Repository,
+) -> Result<impl Iterator<Item = Result<Commit>>> {
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+
+    Ok(walk.map(move |id| Ok(repo.find_commit(id?)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file, tests::repo_init_empty,
+    };
+    use std::{fs::File, io::Write, path::Path};
+
+    #[test]
+    fn test_shortlog() -> Result<()> {
+        let file_path = Path::new("foo");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let commit_as = |name: &str, content: &str| -> Result<()> {
+            repo.config()?.set_str("user.name", name)?;
+            repo.config()?.set_str(
+                "user.email",
+                &format!("{}@example.com", name),
+            )?;
+            File::create(root.join(file_path))?
+                .write_all(content.as_bytes())?;
+            stage_add_file(repo_path, file_path)?;
+            commit(repo_path, "msg")?;
+            Ok(())
+        };
+
+        commit_as("alice", "a\nb\n")?;
+        commit_as("bob", "a\nc\nd\n")?;
+        commit_as("alice", "a\n")?;
+        commit_as("alice", "a\ne\n")?;
+
+        let res = get_shortlog(repo_path)?;
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].name, "alice");
+        assert_eq!(res[0].commits, 3);
+        assert!(res[0].first_time <= res[0].last_time);
+        assert_eq!(res[1].email, "bob@example.com");
+        assert_eq!(res[1].commits, 1);
+
+        assert_eq!(
+            get_author_line_stats(repo_path, "alice@example.com")?,
+            LineStats {
+                insertions: 3,
+                deletions: 2,
+            }
+        );
+        assert_eq!(
+            get_author_line_stats(repo_path, "bob@example.com")?,
+            LineStats {
+                insertions: 2,
+                deletions: 1,
+            }
+        );
+
+        Ok(())
+    }
+}
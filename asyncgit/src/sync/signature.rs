@@ -0,0 +1,388 @@
+//! verifies gpg, x509 and ssh commit signatures like
+//! `git log --show-signature` by calling the configured program
+
+use super::{utils::repo, CommitId};
+use crate::error::{Error, Result};
+use git2::{ErrorCode, Repository};
+use scopetime::scope_time;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{self, Command, Output, Stdio},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// names tried for the signature file before giving up
+const SIGNATURE_FILE_ATTEMPTS: usize = 16;
+
+/// result of verifying the signature of a commit
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignatureStatus {
+    /// commit carries no signature
+    Unsigned,
+    /// signature verified, contains the signer
+    Good(String),
+    /// signature does not match the commit
+    Bad(Option<String>),
+    /// signature could not be checked (unknown, expired or revoked
+    /// key, missing program or allowed signers)
+    Unknown(Option<String>),
+}
+
+/// verifies the signature of commit `id` according to `gpg.format`
+pub fn verify_commit_signature(
+    repo_path: &str,
+    id: CommitId,
+) -> Result<SignatureStatus> {
+    scope_time!("verify_commit_signature");
+
+    let repo = repo(repo_path)?;
+
+    let (signature, data) =
+        match repo.extract_signature(&id.into(), None) {
+            Ok(res) => res,
+            Err(e) if e.code() == ErrorCode::NotFound => {
+                return Ok(SignatureStatus::Unsigned)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+    // like git the signature is handed over as a file and the
+    // signed data on stdin
+    let sig_file = SignatureFile::create(id, &signature)?;
+
+    let format = config_string(&repo, "gpg.format")
+        .unwrap_or_else(|| String::from("openpgp"));
+
+    let res = match format.as_str() {
+        "ssh" => verify_ssh(&repo, sig_file.path(), &data),
+        "x509" => {
+            let program = config_string(&repo, "gpg.x509.program")
+                .unwrap_or_else(|| String::from("gpgsm"));
+            verify_gpg(&program, sig_file.path(), &data)
+        }
+        _ => {
+            let program = config_string(&repo, "gpg.openpgp.program")
+                .or_else(|| config_string(&repo, "gpg.program"))
+                .unwrap_or_else(|| String::from("gpg"));
+            verify_gpg(&program, sig_file.path(), &data)
+        }
+    };
+
+    res
+}
+
+/// the signature in the temp dir, removed again when dropped
+struct SignatureFile(PathBuf);
+
+impl SignatureFile {
+    /// created exclusively (and only readable by us) so nothing that
+    /// was put at the path in the shared temp dir, like a symlink,
+    /// gets followed
+    fn create(id: CommitId, signature: &[u8]) -> Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.subsec_nanos())
+            .unwrap_or_default();
+
+        for attempt in 0..SIGNATURE_FILE_ATTEMPTS {
+            let path = env::temp_dir().join(format!(
+                "gitui-signature-{}-{}-{}-{}",
+                process::id(),
+                id.to_string(),
+                nanos,
+                attempt
+            ));
+
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+
+            match options.open(&path) {
+                Ok(mut file) => {
+                    let sig_file = Self(path);
+                    file.write_all(signature)?;
+                    return Ok(sig_file);
+                }
+                Err(e)
+                    if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(Error::Generic(String::from(
+            "no unused name for the signature file",
+        )))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SignatureFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.0) {
+            log::error!("signature file not removed: {}", e);
+        }
+    }
+}
+
+fn config_string(repo: &Repository, key: &str) -> Option<String> {
+    repo.config().ok()?.get_string(key).ok()
+}
+
+/// `None` if `program` could not be started
+fn run_with_stdin(
+    cmd: &mut Command,
+    stdin: &[u8],
+) -> Result<Option<Output>> {
+    let mut child = match cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            log::error!("signature verification: {}", e);
+            return Ok(None);
+        }
+    };
+
+    // written while the output is read, a program that fills up its
+    // stdout pipe before it read all of its input blocks otherwise
+    let writer = child.stdin.take().map(|mut input| {
+        let stdin = stdin.to_vec();
+        thread::spawn(move || input.write_all(&stdin))
+    });
+
+    let output = child.wait_with_output()?;
+
+    if let Some(writer) = writer {
+        match writer.join() {
+            // done without reading all of it, its output tells
+            Ok(Err(e)) if e.kind() != io::ErrorKind::BrokenPipe => {
+                return Err(e.into())
+            }
+            Ok(_) => (),
+            Err(_) => {
+                return Err(Error::Generic(String::from(
+                    "writing the signed data failed",
+                )))
+            }
+        }
+    }
+
+    Ok(Some(output))
+}
+
+fn verify_gpg(
+    program: &str,
+    sig_file: &Path,
+    data: &[u8],
+) -> Result<SignatureStatus> {
+    let output = run_with_stdin(
+        Command::new(program)
+            .arg("--status-fd=1")
+            .arg("--verify")
+            .arg(sig_file)
+            .arg("-"),
+        data,
+    )?;
+
+    Ok(output.map_or(SignatureStatus::Unknown(None), |output| {
+        parse_gpg_status(&String::from_utf8_lossy(&output.stdout))
+    }))
+}
+
+/// interprets the `--status-fd` output of gpg/gpgsm
+fn parse_gpg_status(status: &str) -> SignatureStatus {
+    let mut res = SignatureStatus::Unknown(None);
+
+    for line in status.lines() {
+        let mut parts = line.splitn(4, ' ');
+        if parts.next() != Some("[GNUPG:]") {
+            continue;
+        }
+
+        let keyword = parts.next().unwrap_or_default();
+        let key = parts.next();
+        let signer = parts.next().or(key).map(String::from);
+
+        match keyword {
+            "GOODSIG" => {
+                res =
+                    SignatureStatus::Good(signer.unwrap_or_default());
+            }
+            "BADSIG" => return SignatureStatus::Bad(signer),
+            "EXPSIG" | "EXPKEYSIG" | "REVKEYSIG" => {
+                return SignatureStatus::Unknown(signer)
+            }
+            // key not available, only its id is known
+            "ERRSIG" => {
+                return SignatureStatus::Unknown(
+                    key.map(String::from),
+                )
+            }
+            _ => (),
+        }
+    }
+
+    res
+}
+
+fn verify_ssh(
+    repo: &Repository,
+    sig_file: &Path,
+    data: &[u8],
+) -> Result<SignatureStatus> {
+    let program = config_string(repo, "gpg.ssh.program")
+        .unwrap_or_else(|| String::from("ssh-keygen"));
+
+    let allowed_signers = if let Some(file) =
+        config_string(repo, "gpg.ssh.allowedSignersFile")
+    {
+        file
+    } else {
+        return Ok(SignatureStatus::Unknown(None));
+    };
+
+    let principal = match Command::new(&program)
+        .args(&["-Y", "find-principals", "-f", &allowed_signers])
+        .arg("-s")
+        .arg(sig_file)
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .map(String::from)
+        }
+        Ok(_) => None,
+        Err(e) => {
+            log::error!("signature verification: {}", e);
+            None
+        }
+    };
+
+    let principal = if let Some(principal) = principal {
+        principal
+    } else {
+        return Ok(SignatureStatus::Unknown(None));
+    };
+
+    let output = run_with_stdin(
+        Command::new(&program)
+            .args(&["-Y", "verify", "-n", "git", "-f"])
+            .arg(&allowed_signers)
+            .arg("-I")
+            .arg(&principal)
+            .arg("-s")
+            .arg(sig_file),
+        data,
+    )?;
+
+    Ok(match output {
+        Some(output) if output.status.success() => {
+            SignatureStatus::Good(principal)
+        }
+        Some(_) => SignatureStatus::Bad(Some(principal)),
+        None => SignatureStatus::Unknown(Some(principal)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file, tests::repo_init_empty,
+    };
+    use std::fs::File;
+
+    #[test]
+    fn test_unsigned() -> Result<()> {
+        let file_path = Path::new("foo");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(&root.join(file_path))?.write_all(b"a")?;
+        stage_add_file(repo_path, file_path).unwrap();
+        let id = commit(repo_path, "msg").unwrap();
+
+        assert_eq!(
+            verify_commit_signature(repo_path, id)?,
+            SignatureStatus::Unsigned
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_file() -> Result<()> {
+        let id = CommitId::new(git2::Oid::zero());
+
+        let first = SignatureFile::create(id, b"sig")?;
+        let second = SignatureFile::create(id, b"sig")?;
+        assert_ne!(first.path(), second.path());
+        assert_eq!(fs::read(first.path())?, b"sig");
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_run_with_stdin_large() -> Result<()> {
+        // more than fits into the pipes both ways
+        let data = vec![b'x'; 1024 * 1024];
+
+        let output =
+            run_with_stdin(&mut Command::new("cat"), &data)?.unwrap();
+
+        assert_eq!(output.stdout.len(), data.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_gpg_status() {
+        assert_eq!(
+            parse_gpg_status(
+                "[GNUPG:] NEWSIG\n[GNUPG:] GOODSIG 0123ABCD Jane <j@x.org>\n[GNUPG:] TRUST_ULTIMATE 0 pgp\n"
+            ),
+            SignatureStatus::Good(String::from("Jane <j@x.org>"))
+        );
+        assert_eq!(
+            parse_gpg_status(
+                "[GNUPG:] BADSIG 0123ABCD Jane <j@x.org>"
+            ),
+            SignatureStatus::Bad(Some(String::from(
+                "Jane <j@x.org>"
+            )))
+        );
+        assert_eq!(
+            parse_gpg_status(
+                "[GNUPG:] ERRSIG 0123ABCD 1 8 00 1600000000 9 -"
+            ),
+            SignatureStatus::Unknown(Some(String::from("0123ABCD")))
+        );
+        assert_eq!(
+            parse_gpg_status(""),
+            SignatureStatus::Unknown(None)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! sparse checkouts: files outside of the patterns in
+//! `.git/info/sparse-checkout` are not in the work dir on purpose
+
+use crate::error::Result;
+use git2::Repository;
+use std::{collections::HashSet, fs};
+
+/// `GIT_INDEX_ENTRY_SKIP_WORKTREE` of the extended index flags
+const SKIP_WORKTREE: u16 = 1 << 14;
+
+struct Pattern {
+    glob: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl Pattern {
+    /// a line of the sparse-checkout file (gitignore syntax)
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+
+        Some(Self {
+            anchored: line.contains('/'),
+            glob: String::from(line.trim_start_matches('/')),
+            negated,
+            dir_only,
+        })
+    }
+
+    /// whether the pattern matches `path` or one of its parent dirs
+    fn matches(&self, path: &str) -> bool {
+        let components = path.split('/').collect::<Vec<_>>();
+
+        (1..=components.len()).any(|len| {
+            let is_dir = len < components.len();
+            if self.dir_only && !is_dir {
+                return false;
+            }
+
+            if self.anchored {
+                glob_match(&self.glob, &components[..len].join("/"))
+            } else {
+                glob_match(&self.glob, components[len - 1])
+            }
+        })
+    }
+}
+
+/// `*` and `?` stop at `/`, `**` does not
+fn glob_match(glob: &str, text: &str) -> bool {
+    match glob.chars().next() {
+        None => text.is_empty(),
+        Some('*') if glob.starts_with("**") => {
+            let rest = glob[2..].trim_start_matches('/');
+            // the rest has to match from the start of a component
+            std::iter::once(0)
+                .chain(
+                    text.match_indices('/').map(|(idx, _)| idx + 1),
+                )
+                .any(|idx| glob_match(rest, &text[idx..]))
+        }
+        Some('*') => {
+            let rest = &glob[1..];
+            text.char_indices()
+                .take_while(|(_, c)| *c != '/')
+                .map(|(idx, _)| idx)
+                .chain(std::iter::once(
+                    text.find('/').unwrap_or(text.len()),
+                ))
+                .any(|idx| glob_match(rest, &text[idx..]))
+        }
+        Some(g) => text.chars().next().map_or(false, |t| {
+            (t == g || (g == '?' && t != '/'))
+                && glob_match(
+                    &glob[g.len_utf8()..],
+                    &text[t.len_utf8()..],
+                )
+        }),
+    }
+}
+
+/// the sparse checkout of a repo if `core.sparseCheckout` is on
+pub(crate) struct SparseCheckout {
+    patterns: Vec<Pattern>,
+    /// index entries git marked as not checked out
+    skipped: HashSet<String>,
+}
+
+impl SparseCheckout {
+    ///
+    pub fn load(repo: &Repository) -> Result<Option<Self>> {
+        let enabled = repo
+            .config()?
+            .get_bool("core.sparseCheckout")
+            .unwrap_or_default();
+        if !enabled {
+            return Ok(None);
+        }
+
+        let patterns = fs::read_to_string(
+            repo.path().join("info").join("sparse-checkout"),
+        )
+        .unwrap_or_default()
+        .lines()
+        .filter_map(Pattern::parse)
+        .collect();
+
+        let skipped = repo
+            .index()?
+            .iter()
+            .filter(|entry| entry.flags_extended & SKIP_WORKTREE != 0)
+            .map(|entry| String::from_utf8_lossy(&entry.path).into())
+            .collect();
+
+        Ok(Some(Self { patterns, skipped }))
+    }
+
+    /// `true` if `path` is not supposed to be in the work dir
+    pub fn excludes(&self, path: &str) -> bool {
+        if self.skipped.contains(path) {
+            return true;
+        }
+
+        // the last matching pattern decides
+        !self
+            .patterns
+            .iter()
+            .rev()
+            .find(|pattern| pattern.matches(path))
+            .map_or(false, |pattern| !pattern.negated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sparse(patterns: &str) -> SparseCheckout {
+        SparseCheckout {
+            patterns: patterns
+                .lines()
+                .filter_map(Pattern::parse)
+                .collect(),
+            skipped: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn test_cone_patterns() {
+        let sparse = sparse("/*\n!/*/\n/sub/\n!/sub/*/\n/sub/x/\n");
+
+        assert!(!sparse.excludes("README"));
+        assert!(sparse.excludes("other/file"));
+        assert!(!sparse.excludes("sub/file"));
+        assert!(sparse.excludes("sub/y/file"));
+        assert!(!sparse.excludes("sub/x/deep/file"));
+    }
+
+    #[test]
+    fn test_glob_patterns() {
+        let sparse = sparse("*.md\ndocs/**/*.txt\n!docs/old/\n");
+
+        assert!(!sparse.excludes("README.md"));
+        assert!(!sparse.excludes("sub/notes.md"));
+        assert!(sparse.excludes("src/main.rs"));
+        assert!(!sparse.excludes("docs/a/b/c.txt"));
+        assert!(sparse.excludes("docs/old/c.txt"));
+        assert!(sparse.excludes("docs/c.rs"));
+    }
+}
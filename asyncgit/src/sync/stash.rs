@@ -1,7 +1,15 @@
-use super::{utils::repo, CommitId};
+use super::{
+    hooks::{hooks_post_checkout, HookResult},
+    patches::run_git,
+    utils::{get_head_repo, repo},
+    CommitId,
+};
 use crate::error::{Error, Result};
-use git2::{Oid, Repository, StashFlags};
+use git2::{
+    BranchType, Oid, Repository, StashApplyOptions, StashFlags,
+};
 use scopetime::scope_time;
+use std::time::Duration;
 
 ///
 pub fn get_stashes(repo_path: &str) -> Result<Vec<CommitId>> {
@@ -41,22 +49,139 @@ pub fn stash_drop(repo_path: &str, stash_id: CommitId) -> Result<()> {
     Ok(())
 }
 
-///
+/// how applying a stash went
+#[derive(Debug, Clone, PartialEq)]
+pub enum StashApplyOutcome {
+    /// the stash is kept
+    Applied,
+    /// applied and dropped
+    Popped,
+    /// applied with conflicts in these paths, the stash is kept even
+    /// if it was to be popped
+    Conflicts(Vec<String>),
+}
+
+/// applies the stash and drops it afterwards with `pop`, unless
+/// that left conflicts. an error means nothing got applied, like
+/// when local changes are in the way
 pub fn stash_apply(
     repo_path: &str,
     stash_id: CommitId,
-) -> Result<()> {
+    pop: bool,
+) -> Result<StashApplyOutcome> {
     scope_time!("stash_apply");
 
     let mut repo = repo(repo_path)?;
 
     let index = get_stash_index(&mut repo, stash_id.get_oid())?;
 
+    // `stash_pop` would drop the stash despite conflicts
     repo.stash_apply(index, None)?;
 
+    let conflicts = conflicted_paths(&repo)?;
+    if !conflicts.is_empty() {
+        return Ok(StashApplyOutcome::Conflicts(conflicts));
+    }
+
+    if pop {
+        repo.stash_drop(index)?;
+        Ok(StashApplyOutcome::Popped)
+    } else {
+        Ok(StashApplyOutcome::Applied)
+    }
+}
+
+/// like `git stash branch`: checks out a new branch `name` at the
+/// commit the stash was made on, applies the stash there with its
+/// staged changes staged again and drops it unless that left
+/// conflicts. local changes in the way of the checkout leave
+/// everything as it was, if the apply fails the new branch stays
+/// checked out and the stash is kept. returns the result of the
+/// `post-checkout` hook the checkout runs along the outcome
+pub fn stash_branch(
+    repo_path: &str,
+    stash_id: CommitId,
+    name: &str,
+    hooks_timeout: Option<Duration>,
+) -> Result<(StashApplyOutcome, HookResult)> {
+    scope_time!("stash_branch");
+
+    let mut repo = repo(repo_path)?;
+
+    let index = get_stash_index(&mut repo, stash_id.get_oid())?;
+    let prev = get_head_repo(&repo)?;
+    checkout_new_branch(&repo, name, stash_id)?;
+    let hook = hooks_post_checkout(
+        repo_path,
+        prev,
+        get_head_repo(&repo)?,
+        true,
+        hooks_timeout,
+    )?;
+
+    let mut options = StashApplyOptions::new();
+    options.reinstantiate_index();
+    if let Err(e) = repo.stash_apply(index, Some(&mut options)) {
+        let mut msg = format!(
+            "{} is checked out but the stash could not be applied: {}",
+            name, e
+        );
+        if let Some(report) = hook.error() {
+            msg = format!("{}\n{}", msg, report);
+        }
+        return Err(Error::Generic(msg));
+    }
+
+    let conflicts = conflicted_paths(&repo)?;
+    if !conflicts.is_empty() {
+        return Ok((StashApplyOutcome::Conflicts(conflicts), hook));
+    }
+
+    repo.stash_drop(index)?;
+
+    Ok((StashApplyOutcome::Popped, hook))
+}
+
+/// creates the branch `name` at the commit the stash was made on and
+/// checks it out, not leaving the branch behind if that fails
+fn checkout_new_branch(
+    repo: &Repository,
+    name: &str,
+    stash_id: CommitId,
+) -> Result<()> {
+    let base = repo.find_commit(stash_id.into())?.parent(0)?;
+
+    repo.branch(name, &base, false)?;
+    // a safe checkout gives up before touching any file
+    if let Err(e) = repo.checkout_tree(base.as_object(), None) {
+        repo.find_branch(name, BranchType::Local)?.delete()?;
+        return Err(e.into());
+    }
+    repo.set_head(&format!("refs/heads/{}", name))?;
+
     Ok(())
 }
 
+/// paths with unmerged entries in the index
+fn conflicted_paths(repo: &Repository) -> Result<Vec<String>> {
+    let index = repo.index()?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        if let Some(entry) =
+            conflict.our.or(conflict.their).or(conflict.ancestor)
+        {
+            paths.push(String::from_utf8_lossy(&entry.path).into());
+        }
+    }
+
+    Ok(paths)
+}
+
 fn get_stash_index(
     repo: &mut Repository,
     stash_id: Oid,
@@ -77,11 +202,14 @@ fn get_stash_index(
     })
 }
 
-///
+/// stashes the changes, untracked and ignored files only if asked
+/// to, leaving the staged changes in the index and work dir too with
+/// `keep_index`
 pub fn stash_save(
     repo_path: &str,
     message: Option<&str>,
     include_untracked: bool,
+    include_ignored: bool,
     keep_index: bool,
 ) -> Result<CommitId> {
     scope_time!("stash_save");
@@ -95,6 +223,9 @@ pub fn stash_save(
     if include_untracked {
         options.insert(StashFlags::INCLUDE_UNTRACKED);
     }
+    if include_ignored {
+        options.insert(StashFlags::INCLUDE_IGNORED);
+    }
     if keep_index {
         options.insert(StashFlags::KEEP_INDEX)
     }
@@ -104,14 +235,50 @@ pub fn stash_save(
     Ok(CommitId::new(id))
 }
 
+/// stashes the changes (untracked ones too) of `paths` only, via git
+/// itself as libgit2 stashes everything
+pub fn stash_save_paths(
+    repo_path: &str,
+    message: Option<&str>,
+    paths: &[String],
+) -> Result<CommitId> {
+    scope_time!("stash_save_paths");
+
+    let last = get_stashes(repo_path)?.first().copied();
+
+    let mut args = vec!["stash", "push", "--include-untracked"];
+    if let Some(message) = message {
+        args.extend(&["-m", message]);
+    }
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+
+    run_git(repo_path, &args)?;
+
+    match get_stashes(repo_path)?.first().copied() {
+        Some(id) if Some(id) != last => Ok(id),
+        _ => Err(Error::Generic(String::from("nothing to stash"))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::sync::{
-        commit, get_commit_files, get_commits_info, stage_add_file,
+        commit, get_commit_files, get_commits_info, operation_state,
+        stage_add_file,
+        status::{
+            get_status, ShowUntrackedFiles, StatusItemType,
+            StatusType,
+        },
         tests::{debug_cmd_print, get_statuses, repo_init},
+        RepoOperation,
+    };
+    use std::{
+        fs::{self, File},
+        io::Write,
+        path::Path,
     };
-    use std::{fs::File, io::Write, path::Path};
 
     #[test]
     fn test_smoke() {
@@ -120,7 +287,7 @@ mod tests {
         let repo_path = root.as_os_str().to_str().unwrap();
 
         assert_eq!(
-            stash_save(repo_path, None, true, false).is_ok(),
+            stash_save(repo_path, None, true, false, false).is_ok(),
             false
         );
 
@@ -138,7 +305,7 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        stash_save(repo_path, None, true, false)?;
+        stash_save(repo_path, None, true, false, false)?;
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
@@ -154,7 +321,7 @@ mod tests {
         File::create(&root.join("foo.txt"))?
             .write_all(b"test\nfoo")?;
 
-        stash_save(repo_path, Some("foo"), true, false)?;
+        stash_save(repo_path, Some("foo"), true, false, false)?;
 
         let res = get_stashes(repo_path)?;
 
@@ -168,6 +335,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_stash_paths() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("foo.txt"))?.write_all(b"foo")?;
+        File::create(root.join("bar.txt"))?.write_all(b"bar")?;
+
+        stash_save_paths(
+            repo_path,
+            Some("foo only"),
+            &[String::from("foo.txt")],
+        )?;
+
+        assert!(!root.join("foo.txt").exists());
+        assert!(root.join("bar.txt").exists());
+        assert_eq!(get_stashes(repo_path)?.len(), 1);
+
+        assert!(stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("foo.txt")]
+        )
+        .is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_stash_nothing_untracked() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
@@ -177,9 +373,245 @@ mod tests {
         File::create(&root.join("foo.txt"))?
             .write_all(b"test\nfoo")?;
 
+        assert!(stash_save(
+            repo_path,
+            Some("foo"),
+            false,
+            false,
+            false
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_pop_untracked() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::create_dir(root.join("dir"))?;
+        fs::write(root.join("dir/foo.txt"), "foo\n")?;
+
+        let stash = stash_save(repo_path, None, true, false, false)?;
+        assert!(!root.join("dir/foo.txt").exists());
+
+        assert_eq!(
+            stash_apply(repo_path, stash, true)?,
+            StashApplyOutcome::Popped
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("dir/foo.txt"))?,
+            "foo\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_keep_index() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("staged.txt"))?
+            .write_all(b"staged")?;
+        stage_add_file(repo_path, Path::new("staged.txt"))?;
+        File::create(root.join("unstaged.txt"))?.write_all(b"wd")?;
+        assert_eq!(get_statuses(repo_path), (1, 1));
+
+        stash_save(repo_path, None, true, false, true)?;
+
+        // the staged file stays staged and in the work dir
+        assert_eq!(get_statuses(repo_path), (0, 1));
+        let mut index = repo.index()?;
+        index.read(true)?;
+        let entry =
+            index.get_path(Path::new("staged.txt"), 0).unwrap();
+        assert_eq!(repo.find_blob(entry.id)?.content(), b"staged");
+        assert_eq!(fs::read(root.join("staged.txt"))?, b"staged");
+        assert!(!root.join("unstaged.txt").exists());
+
+        // the stash has both
+        let stash = get_stashes(repo_path)?[0];
+        assert_eq!(get_commit_files(repo_path, stash)?.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_ignored() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join(".gitignore"))?
+            .write_all(b"*.log\n")?;
+        stage_add_file(repo_path, Path::new(".gitignore"))?;
+        commit(repo_path, "ignore logs")?;
+        File::create(root.join("build.log"))?.write_all(b"log")?;
+        File::create(root.join("new.txt"))?.write_all(b"new")?;
+
+        // only the ignored file, the untracked one stays
+        stash_save(repo_path, None, false, true, false)?;
+
+        assert!(!root.join("build.log").exists());
+        assert!(root.join("new.txt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_pop() -> Result<()> {
+        let file = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join(file), "a\n")?;
+        stage_add_file(repo_path, file)?;
+        commit(repo_path, "add foo")?;
+
+        fs::write(root.join(file), "b\n")?;
+        let stash = stash_save(repo_path, None, true, false, false)?;
+
+        assert_eq!(
+            stash_apply(repo_path, stash, false)?,
+            StashApplyOutcome::Applied
+        );
+        // local changes are in the way
+        fs::write(root.join(file), "x\n")?;
+        assert!(stash_apply(repo_path, stash, true).is_err());
+        assert_eq!(fs::read_to_string(root.join(file))?, "x\n");
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+
+        debug_cmd_print(repo_path, "git checkout -f HEAD");
+        assert_eq!(
+            stash_apply(repo_path, stash, true)?,
+            StashApplyOutcome::Popped
+        );
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        // a commit changing the same line makes it conflict
+        let stash = stash_save(repo_path, None, true, false, false)?;
+        fs::write(root.join(file), "c\n")?;
+        stage_add_file(repo_path, file)?;
+        commit(repo_path, "c")?;
+
+        assert_eq!(
+            stash_apply(repo_path, stash, true)?,
+            StashApplyOutcome::Conflicts(vec![String::from(
+                "foo.txt"
+            )])
+        );
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+        assert_eq!(
+            operation_state(repo_path)?.map(|state| state.operation),
+            Some(RepoOperation::Conflicts)
+        );
+        assert_eq!(
+            get_status(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::Normal
+            )?[0]
+                .status,
+            StatusItemType::Conflicted
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_branch() -> Result<()> {
+        let file = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let head =
+            || repo.head().unwrap().shorthand().unwrap().to_string();
+
+        fs::write(root.join(file), "a\n")?;
+        stage_add_file(repo_path, file)?;
+        commit(repo_path, "add foo")?;
+
+        fs::write(root.join(file), "b\n")?;
+        stage_add_file(repo_path, file)?;
+        let stash = stash_save(repo_path, None, true, false, false)?;
+        fs::write(root.join(file), "c\n")?;
+        stage_add_file(repo_path, file)?;
+        commit(repo_path, "c")?;
+
+        // local changes are in the way of the checkout
+        fs::write(root.join(file), "x\n")?;
+        assert!(stash_branch(repo_path, stash, "fix", None).is_err());
+        assert_eq!(head(), "master");
+        assert!(repo.find_branch("fix", BranchType::Local).is_err());
+        assert_eq!(fs::read_to_string(root.join(file))?, "x\n");
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+
+        debug_cmd_print(repo_path, "git checkout -f HEAD");
+        assert_eq!(
+            stash_branch(repo_path, stash, "fix", None)?,
+            (StashApplyOutcome::Popped, HookResult::NoHook)
+        );
+        assert_eq!(head(), "fix");
+        assert_eq!(fs::read_to_string(root.join(file))?, "b\n");
+        assert_eq!(
+            get_statuses(repo_path),
+            (0, 1),
+            "the change is staged again"
+        );
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        // the checkout carries local changes the stash touches too
+        let stash = stash_save(repo_path, None, true, false, false)?;
+        fs::write(root.join(file), "y\n")?;
         assert!(
-            stash_save(repo_path, Some("foo"), false, false).is_err()
+            stash_branch(repo_path, stash, "other", None).is_err()
         );
+        assert_eq!(head(), "other");
+        assert_eq!(fs::read_to_string(root.join(file))?, "y\n");
+        assert_eq!(get_stashes(repo_path)?, vec![stash]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_stash_branch_post_checkout() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = Path::new("foo.txt");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join(file), "a\n")?;
+        stage_add_file(repo_path, file)?;
+        let base = commit(repo_path, "add foo")?;
+        fs::write(root.join(file), "b\n")?;
+        let stash = stash_save(repo_path, None, true, false, false)?;
+        fs::write(root.join(file), "c\n")?;
+        stage_add_file(repo_path, file)?;
+        let head = commit(repo_path, "c")?;
+
+        let hook = repo.path().join("hooks").join("post-checkout");
+        fs::write(&hook, "#!/bin/sh\necho \"$1 $2 $3\"\nexit 1\n")?;
+        fs::set_permissions(
+            &hook,
+            fs::Permissions::from_mode(0o755),
+        )?;
+
+        let (outcome, hook) =
+            stash_branch(repo_path, stash, "fix", None)?;
+        assert_eq!(outcome, StashApplyOutcome::Popped);
+        assert!(hook.error().unwrap().contains(&format!(
+            "{} {} 1",
+            head.to_string(),
+            base.to_string()
+        )));
 
         Ok(())
     }
@@ -0,0 +1,254 @@
+//! git operations that stopped half way (like a merge with
+//! conflicts) and how to go on with them
+
+use super::{patches::run_git, utils::repo};
+use crate::error::{Error, Result};
+use git2::{Repository, RepositoryState};
+use scopetime::scope_time;
+use std::fs;
+
+/// operation the repo is in the middle of
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RepoOperation {
+    ///
+    Merge,
+    ///
+    Rebase,
+    /// `git am`
+    ApplyMailbox,
+    ///
+    CherryPick,
+    ///
+    Revert,
+    ///
+    Bisect,
+    /// unmerged files left by something git does not track as an
+    /// operation, like applying a stash
+    Conflicts,
+}
+
+impl RepoOperation {
+    /// shown while it is in progress
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "MERGING",
+            Self::Rebase => "REBASING",
+            Self::ApplyMailbox => "APPLYING PATCHES",
+            Self::CherryPick => "CHERRY-PICKING",
+            Self::Revert => "REVERTING",
+            Self::Bisect => "BISECTING",
+            Self::Conflicts => "CONFLICTS",
+        }
+    }
+
+    /// whether `action` is one git offers for the operation
+    pub const fn supports(self, action: OperationAction) -> bool {
+        match (self, action) {
+            // resolving and staging the files is all there is to do
+            (Self::Conflicts, _) => false,
+            (_, OperationAction::Abort) => true,
+            (Self::Bisect, _)
+            | (Self::Merge, OperationAction::Skip) => false,
+            _ => true,
+        }
+    }
+
+    const fn command(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::ApplyMailbox => "am",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+            Self::Bisect => "bisect",
+            Self::Conflicts => "",
+        }
+    }
+}
+
+/// how to go on with a stopped operation
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OperationAction {
+    /// go on once the conflicts are resolved (and staged)
+    Continue,
+    /// drop the current commit or patch and go on
+    Skip,
+    /// go back to where the operation started
+    Abort,
+}
+
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OperationState {
+    ///
+    pub operation: RepoOperation,
+    /// current step and number of steps if git keeps track
+    pub progress: Option<(usize, usize)>,
+}
+
+/// the operation in progress, `None` if there is none
+pub fn operation_state(
+    repo_path: &str,
+) -> Result<Option<OperationState>> {
+    scope_time!("operation_state");
+
+    let repo = repo(repo_path)?;
+    let git_dir = repo.path();
+
+    let (operation, progress) = match repo.state() {
+        RepositoryState::Clean => {
+            if git_dir.join("BISECT_START").exists() {
+                (RepoOperation::Bisect, None)
+            } else if repo.index()?.has_conflicts() {
+                (RepoOperation::Conflicts, None)
+            } else {
+                return Ok(None);
+            }
+        }
+        RepositoryState::Merge => (RepoOperation::Merge, None),
+        RepositoryState::Revert | RepositoryState::RevertSequence => {
+            (RepoOperation::Revert, None)
+        }
+        RepositoryState::CherryPick
+        | RepositoryState::CherryPickSequence => {
+            (RepoOperation::CherryPick, None)
+        }
+        RepositoryState::Bisect => (RepoOperation::Bisect, None),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => (
+            RepoOperation::Rebase,
+            progress(&repo, "rebase-merge", "msgnum", "end").or_else(
+                || progress(&repo, "rebase-apply", "next", "last"),
+            ),
+        ),
+        RepositoryState::ApplyMailbox
+        | RepositoryState::ApplyMailboxOrRebase => (
+            // `git rebase` with the apply backend uses the same dir
+            if git_dir.join("rebase-apply").join("applying").exists()
+            {
+                RepoOperation::ApplyMailbox
+            } else {
+                RepoOperation::Rebase
+            },
+            progress(&repo, "rebase-apply", "next", "last"),
+        ),
+    };
+
+    Ok(Some(OperationState {
+        operation,
+        progress,
+    }))
+}
+
+/// step and total stored in two files of `dir` in the git dir
+fn progress(
+    repo: &Repository,
+    dir: &str,
+    step: &str,
+    total: &str,
+) -> Option<(usize, usize)> {
+    let read = |file: &str| -> Option<usize> {
+        fs::read_to_string(repo.path().join(dir).join(file))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+
+    Some((read(step)?, read(total)?))
+}
+
+/// continues, skips or aborts `operation` via git itself, fails with
+/// the output of git if that does not work out (like conflicts left)
+pub fn operation_action(
+    repo_path: &str,
+    operation: RepoOperation,
+    action: OperationAction,
+) -> Result<()> {
+    scope_time!("operation_action");
+
+    let args = match (operation, action) {
+        (RepoOperation::Conflicts, _) => {
+            return Err(Error::Generic(String::from(
+                "resolve the conflicts and stage the files",
+            )))
+        }
+        (RepoOperation::Bisect, _) => vec!["bisect", "reset"],
+        (_, OperationAction::Continue) => {
+            vec![operation.command(), "--continue"]
+        }
+        (_, OperationAction::Skip) => {
+            vec![operation.command(), "--skip"]
+        }
+        (_, OperationAction::Abort) => {
+            vec![operation.command(), "--abort"]
+        }
+    };
+
+    run_git(repo_path, &args)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{merge::tests::start_merge, tests::repo_init};
+
+    #[test]
+    fn test_no_operation() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert_eq!(operation_state(repo_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_merge_abort() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        start_merge(&repo, repo_path);
+
+        assert_eq!(
+            operation_state(repo_path).unwrap(),
+            Some(OperationState {
+                operation: RepoOperation::Merge,
+                progress: None,
+            })
+        );
+
+        operation_action(
+            repo_path,
+            RepoOperation::Merge,
+            OperationAction::Abort,
+        )
+        .unwrap();
+
+        assert_eq!(operation_state(repo_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rebase_progress() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let dir = repo.path().join("rebase-merge");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("interactive"), "").unwrap();
+        fs::write(dir.join("msgnum"), "3\n").unwrap();
+        fs::write(dir.join("end"), "7\n").unwrap();
+
+        assert_eq!(
+            operation_state(repo_path).unwrap(),
+            Some(OperationState {
+                operation: RepoOperation::Rebase,
+                progress: Some((3, 7)),
+            })
+        );
+    }
+}
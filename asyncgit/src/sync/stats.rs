@@ -0,0 +1,134 @@
+//! overview numbers of a repository
+
+use super::{get_branch_name, utils::repo};
+use crate::error::Result;
+use git2::BranchType;
+use scopetime::scope_time;
+use std::{collections::HashSet, fs, io, path::Path};
+
+///
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RepoStats {
+    /// commits reachable from `HEAD`
+    pub commits: usize,
+    /// distinct author emails of those commits
+    pub authors: usize,
+    /// time of the oldest of those commits (secs since Unix epoch)
+    pub first_commit_time: Option<i64>,
+    /// `None` on a detached or unborn `HEAD`
+    pub branch: Option<String>,
+    /// local branches
+    pub branches: usize,
+    ///
+    pub tags: usize,
+    /// bytes used by the `.git` directory
+    pub size_on_disk: u64,
+    /// entries in the index
+    pub tracked_files: usize,
+}
+
+/// walks the whole history, this can take a while in big
+/// repositories
+pub fn get_repo_stats(repo_path: &str) -> Result<RepoStats> {
+    scope_time!("get_repo_stats");
+
+    let repo = repo(repo_path)?;
+
+    let mut res = RepoStats {
+        branch: get_branch_name(repo_path).ok(),
+        branches: repo.branches(Some(BranchType::Local))?.count(),
+        tags: repo.tag_names(None)?.len(),
+        size_on_disk: dir_size(repo.path())?,
+        tracked_files: repo.index()?.len(),
+        ..RepoStats::default()
+    };
+
+    // an unborn HEAD has no history yet
+    if repo.head().is_ok() {
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+
+        let mut authors = HashSet::new();
+
+        for id in walk {
+            let commit = repo.find_commit(id?)?;
+            let time = commit.time().seconds();
+
+            res.commits += 1;
+            res.first_commit_time = Some(
+                res.first_commit_time.map_or(time, |t| t.min(time)),
+            );
+            authors.insert(
+                commit
+                    .author()
+                    .email()
+                    .unwrap_or_default()
+                    .to_string(),
+            );
+        }
+
+        res.authors = authors.len();
+    }
+
+    Ok(res)
+}
+
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+
+        size += if meta.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            meta.len()
+        };
+    }
+
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file, tag, tests::repo_init_empty,
+    };
+    use std::{fs::File, io::Write};
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let stats = get_repo_stats(repo_path)?;
+        assert_eq!(stats.commits, 0);
+        assert_eq!(stats.first_commit_time, None);
+        assert_eq!(stats.branch, None);
+        assert!(stats.size_on_disk > 0);
+
+        let file_path = Path::new("foo");
+        File::create(root.join(file_path))?.write_all(b"a")?;
+        stage_add_file(repo_path, file_path)?;
+        let id = commit(repo_path, "first")?;
+        File::create(root.join(file_path))?.write_all(b"b")?;
+        stage_add_file(repo_path, file_path)?;
+        commit(repo_path, "second")?;
+
+        tag(repo_path, &id, "v1", None)?;
+
+        let stats = get_repo_stats(repo_path)?;
+        assert_eq!(stats.commits, 2);
+        assert_eq!(stats.authors, 1);
+        assert!(stats.first_commit_time.is_some());
+        assert_eq!(stats.branch.as_deref(), Some("master"));
+        assert_eq!(stats.branches, 1);
+        assert_eq!(stats.tags, 1);
+        assert_eq!(stats.tracked_files, 1);
+
+        Ok(())
+    }
+}
@@ -1,9 +1,24 @@
 //! sync git api for fetching a status
 
-use crate::{error::Error, error::Result, sync::utils};
-use git2::{Delta, Status, StatusOptions, StatusShow};
+use crate::{
+    error::Error,
+    error::Result,
+    sync::{
+        commit_files::{file_stats, FileStat},
+        hooks::hooks_fsmonitor,
+        sparse::SparseCheckout,
+        utils,
+    },
+};
+use git2::{
+    Delta, Diff, DiffFindOptions, DiffOptions, Oid, Repository,
+    Status, StatusOptions, StatusShow,
+};
 use scopetime::scope_time;
-use std::path::Path;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 ///
 #[derive(Copy, Clone, Hash, PartialEq, Debug)]
@@ -17,12 +32,18 @@ pub enum StatusItemType {
     ///
     Renamed,
     ///
+    Copied,
+    ///
     Typechange,
+    /// unmerged, left with conflict markers
+    Conflicted,
 }
 
 impl From<Status> for StatusItemType {
     fn from(s: Status) -> Self {
-        if s.is_index_new() || s.is_wt_new() {
+        if s.is_conflicted() {
+            Self::Conflicted
+        } else if s.is_index_new() || s.is_wt_new() {
             Self::New
         } else if s.is_index_deleted() || s.is_wt_deleted() {
             Self::Deleted
@@ -42,7 +63,9 @@ impl From<Delta> for StatusItemType {
             Delta::Added => StatusItemType::New,
             Delta::Deleted => StatusItemType::Deleted,
             Delta::Renamed => StatusItemType::Renamed,
+            Delta::Copied => StatusItemType::Copied,
             Delta::Typechange => StatusItemType::Typechange,
+            Delta::Conflicted => StatusItemType::Conflicted,
             _ => StatusItemType::Modified,
         }
     }
@@ -55,6 +78,63 @@ pub struct StatusItem {
     pub path: String,
     ///
     pub status: StatusItemType,
+    /// where a renamed or copied file came from
+    pub old_path: Option<String>,
+}
+
+/// which untracked files are listed, like git's
+/// `status.showUntrackedFiles`
+#[derive(Copy, Clone, Hash, PartialEq, Debug)]
+pub enum ShowUntrackedFiles {
+    /// none
+    No,
+    /// untracked dirs as a whole (`dir/`) instead of their files
+    Normal,
+    /// every untracked file
+    All,
+}
+
+impl Default for ShowUntrackedFiles {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl ShowUntrackedFiles {
+    /// `status.showUntrackedFiles` of the repo, `Normal` if unset
+    pub fn from_config(repo_path: &str) -> Result<Self> {
+        scope_time!("ShowUntrackedFiles::from_config");
+
+        let repo = utils::repo(repo_path)?;
+        let value = repo
+            .config()?
+            .get_string("status.showUntrackedFiles")
+            .unwrap_or_default();
+
+        Ok(match value.to_lowercase().as_str() {
+            "no" | "false" | "off" | "0" => Self::No,
+            "all" => Self::All,
+            _ => Self::Normal,
+        })
+    }
+
+    /// `no` -> `normal` -> `all` -> `no`
+    pub const fn next(self) -> Self {
+        match self {
+            Self::No => Self::Normal,
+            Self::Normal => Self::All,
+            Self::All => Self::No,
+        }
+    }
+
+    /// the value as in the git config
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::No => "no",
+            Self::Normal => "normal",
+            Self::All => "all",
+        }
+    }
 }
 
 ///
@@ -84,24 +164,470 @@ impl Into<StatusShow> for StatusType {
     }
 }
 
-///
+/// how renames (and copies, which takes a lot longer as every file
+/// is a possible source) get detected in a status list
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct RenameDetection {
+    /// similarity (in percent) a deleted and a new file need to be
+    /// listed as a rename, like `git status -M<n>`
+    pub threshold: u16,
+    ///
+    pub copies: bool,
+}
+
+impl Default for RenameDetection {
+    fn default() -> Self {
+        Self {
+            threshold: 50,
+            copies: false,
+        }
+    }
+}
+
+impl RenameDetection {
+    fn find_options(self) -> DiffFindOptions {
+        let threshold = self.threshold.min(100);
+
+        let mut opts = DiffFindOptions::new();
+        opts.renames(true)
+            .for_untracked(true)
+            .rename_threshold(threshold)
+            .copies(self.copies)
+            .copies_from_unmodified(self.copies)
+            .copy_threshold(threshold);
+        opts
+    }
+}
+
+fn status_diff<'a>(
+    repo: &'a Repository,
+    status_type: StatusType,
+    opts: &mut DiffOptions,
+) -> Result<Diff<'a>> {
+    let head = match utils::get_head_repo(repo) {
+        Ok(id) => Some(repo.find_commit(id.into())?.tree()?),
+        // nothing committed yet
+        Err(_) => None,
+    };
+
+    Ok(match status_type {
+        StatusType::WorkingDir => {
+            repo.diff_index_to_workdir(None, Some(opts))?
+        }
+        StatusType::Stage => {
+            repo.diff_tree_to_index(head.as_ref(), None, Some(opts))?
+        }
+        StatusType::Both => repo.diff_tree_to_workdir_with_index(
+            head.as_ref(),
+            Some(opts),
+        )?,
+    })
+}
+
+/// turns pairs of deleted and new `items` into a single renamed one
+/// (listed by its new path), new ones copied from elsewhere become
+/// copies if that is looked for
+fn detect_renames(
+    repo: &Repository,
+    status_type: StatusType,
+    renames: RenameDetection,
+    items: &mut Vec<StatusItem>,
+) -> Result<()> {
+    let copies = renames.copies;
+    let has = |status| items.iter().any(|item| item.status == status);
+    if !has(StatusItemType::New)
+        || !(copies || has(StatusItemType::Deleted))
+    {
+        return Ok(());
+    }
+
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    if copies {
+        // unchanged files can be the source of a copy
+        opts.include_unmodified(true);
+    } else {
+        for item in items.iter().filter(|item| {
+            matches!(
+                item.status,
+                StatusItemType::New | StatusItemType::Deleted
+            )
+        }) {
+            opts.pathspec(item.path.trim_end_matches('/'));
+        }
+    }
+
+    let mut diff = status_diff(repo, status_type, &mut opts)?;
+    diff.find_similar(Some(&mut renames.find_options()))?;
+
+    let mut renamed = HashSet::new();
+    for delta in diff.deltas() {
+        let status = match delta.status() {
+            Delta::Renamed | Delta::Copied => {
+                StatusItemType::from(delta.status())
+            }
+            _ => continue,
+        };
+        let path = |file: git2::DiffFile| {
+            file.path().and_then(|p| p.to_str()).map(String::from)
+        };
+        let (old_path, new_path) =
+            match (path(delta.old_file()), path(delta.new_file())) {
+                (Some(old_path), Some(new_path)) => {
+                    (old_path, new_path)
+                }
+                _ => continue,
+            };
+
+        if let Some(item) = items.iter_mut().find(|item| {
+            item.path == new_path
+                && item.status == StatusItemType::New
+        }) {
+            item.status = status;
+            if status == StatusItemType::Renamed {
+                renamed.insert(old_path.clone());
+            }
+            item.old_path = Some(old_path);
+        }
+    }
+
+    items.retain(|item| {
+        item.status != StatusItemType::Deleted
+            || !renamed.contains(&item.path)
+    });
+
+    Ok(())
+}
+
+/// with the default `RenameDetection`
 pub fn get_status(
     repo_path: &str,
     status_type: StatusType,
-    include_untracked: bool,
+    show_untracked: ShowUntrackedFiles,
+) -> Result<Vec<StatusItem>> {
+    get_status_renames(
+        repo_path,
+        status_type,
+        show_untracked,
+        RenameDetection::default(),
+    )
+}
+
+///
+pub fn get_status_renames(
+    repo_path: &str,
+    status_type: StatusType,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
 ) -> Result<Vec<StatusItem>> {
     scope_time!("get_status");
 
     let repo = utils::repo(repo_path)?;
 
-    let statuses = repo.statuses(Some(
-        StatusOptions::default()
-            .show(status_type.into())
-            .update_index(true)
-            .include_untracked(include_untracked)
-            .renames_head_to_index(true)
-            .recurse_untracked_dirs(true),
-    ))?;
+    get_status_repo(&repo, status_type, show_untracked, renames, None)
+}
+
+/// lines and bytes changed per item of a status list, the untracked
+/// folders of `ShowUntrackedFiles::Normal` sum up their files
+pub fn get_status_stats(
+    repo_path: &str,
+    status_type: StatusType,
+    renames: RenameDetection,
+    items: &[StatusItem],
+) -> Result<HashMap<String, FileStat>> {
+    scope_time!("get_status_stats");
+
+    let mut res = HashMap::with_capacity(items.len());
+    // no pathspec would mean all files
+    if items.is_empty() {
+        return Ok(res);
+    }
+
+    let repo = utils::repo(repo_path)?;
+
+    let mut opts = DiffOptions::new();
+    opts.include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    for item in items {
+        opts.pathspec(item.path.trim_end_matches('/'));
+        if let Some(old_path) = &item.old_path {
+            opts.pathspec(old_path);
+        }
+    }
+
+    let mut diff = status_diff(&repo, status_type, &mut opts)?;
+    // the status lists renamed files once, by their new path
+    let mut find = renames.find_options();
+    diff.find_similar(Some(find.copies(false)))?;
+
+    let dirs = items
+        .iter()
+        .filter(|item| item.path.ends_with('/'))
+        .map(|item| item.path.as_str())
+        .collect::<Vec<_>>();
+
+    for (path, stat) in file_stats(&diff)? {
+        let key = dirs
+            .iter()
+            .find(|dir| path.starts_with(*dir))
+            .map_or(path.clone(), |dir| (*dir).to_string());
+
+        *res.entry(key).or_insert_with(FileStat::default) += stat;
+    }
+
+    Ok(res)
+}
+
+/// `true` if `path` has staged changes but none in the work dir, so
+/// staging it again after an edit takes along nothing unwanted
+pub fn is_fully_staged(repo_path: &str, path: &Path) -> Result<bool> {
+    scope_time!("is_fully_staged");
+
+    let repo = utils::repo(repo_path)?;
+    let status = repo.status_file(path)?;
+
+    Ok(status.intersects(
+        Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_RENAMED
+            | Status::INDEX_TYPECHANGE,
+    ) && !status.intersects(
+        Status::WT_MODIFIED
+            | Status::WT_DELETED
+            | Status::WT_RENAMED
+            | Status::WT_TYPECHANGE,
+    ))
+}
+
+/// id, mode, flags and extended flags of an index entry, the flags
+/// cover `assume-unchanged` and `skip-worktree`
+type IndexState = (Oid, u32, u16, u16);
+
+/// what the last fsmonitor based status saw, only valid for the
+/// `repo_path` it was used with
+#[derive(Default)]
+pub struct FsMonitorCache {
+    token: Option<String>,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    index: HashMap<Vec<u8>, IndexState>,
+    dirty: HashSet<String>,
+}
+
+/// like `get_status` but only checks the paths the `core.fsmonitor`
+/// hook reported since the last call (plus those that were dirty or
+/// changed in the index). falls back to a full scan if no hook is
+/// configured, it fails, an ignore file changed or for anything but
+/// `StatusType::WorkingDir`
+pub fn get_status_fsmonitor(
+    repo_path: &str,
+    status_type: StatusType,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    cache: &mut FsMonitorCache,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_status_fsmonitor");
+
+    if status_type != StatusType::WorkingDir {
+        return get_status_renames(
+            repo_path,
+            status_type,
+            show_untracked,
+            renames,
+        );
+    }
+
+    let token = cache.token.take().unwrap_or_default();
+    let changes = match hooks_fsmonitor(repo_path, &token) {
+        Ok(Some(changes)) => changes,
+        Ok(None) => {
+            *cache = FsMonitorCache::default();
+            return get_status_renames(
+                repo_path,
+                status_type,
+                show_untracked,
+                renames,
+            );
+        }
+        Err(e) => {
+            log::error!("fsmonitor failed, full scan: {}", e);
+            *cache = FsMonitorCache::default();
+            return get_status_renames(
+                repo_path,
+                status_type,
+                show_untracked,
+                renames,
+            );
+        }
+    };
+
+    let repo = utils::repo(repo_path)?;
+    let index = index_entries(&repo)?;
+
+    let paths = match changes.paths {
+        Some(changed)
+            if !token.is_empty()
+                && cache.show_untracked == show_untracked
+                && cache.renames == renames
+                && !changed
+                    .iter()
+                    .any(|path| is_ignore_file(path)) =>
+        {
+            let mut paths = changed;
+            if show_untracked == ShowUntrackedFiles::Normal {
+                let dirs = untracked_dirs(&paths, &index);
+                paths.extend(dirs);
+            }
+            paths.extend(cache.dirty.drain());
+            paths.extend(changed_index_paths(&cache.index, &index));
+            Some(paths)
+        }
+        _ => None,
+    };
+
+    let res = if paths.as_ref().map_or(false, Vec::is_empty) {
+        Vec::new()
+    } else {
+        get_status_repo(
+            &repo,
+            status_type,
+            show_untracked,
+            renames,
+            paths.as_deref(),
+        )?
+    };
+
+    *cache = FsMonitorCache {
+        token: Some(changes.token),
+        show_untracked,
+        renames,
+        index,
+        dirty: res
+            .iter()
+            .flat_map(|item| {
+                item.old_path.iter().chain(Some(&item.path)).cloned()
+            })
+            .collect(),
+    };
+
+    Ok(res)
+}
+
+fn index_entries(
+    repo: &Repository,
+) -> Result<HashMap<Vec<u8>, IndexState>> {
+    Ok(repo
+        .index()?
+        .iter()
+        .map(|entry| {
+            (
+                entry.path,
+                (
+                    entry.id,
+                    entry.mode,
+                    entry.flags,
+                    entry.flags_extended,
+                ),
+            )
+        })
+        .collect())
+}
+
+/// any untracked file might be (un)ignored now
+fn is_ignore_file(path: &str) -> bool {
+    path == ".gitignore"
+        || path.ends_with("/.gitignore")
+        || path == ".git/info/exclude"
+}
+
+/// the outermost dir without tracked files around each of `paths`,
+/// `ShowUntrackedFiles::Normal` reports those instead of the files
+fn untracked_dirs(
+    paths: &[String],
+    index: &HashMap<Vec<u8>, IndexState>,
+) -> Vec<String> {
+    let tracked_dirs = index
+        .keys()
+        .flat_map(|path| {
+            let path = String::from_utf8_lossy(path);
+            path.match_indices('/')
+                .map(|(idx, _)| String::from(&path[..idx]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<HashSet<_>>();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            path.match_indices('/')
+                .map(|(idx, _)| &path[..idx])
+                .find(|dir| !tracked_dirs.contains(*dir))
+                .map(|dir| format!("{}/", dir))
+        })
+        .collect()
+}
+
+/// the workdir status of these paths might have changed without
+/// the files being touched
+fn changed_index_paths(
+    old: &HashMap<Vec<u8>, IndexState>,
+    new: &HashMap<Vec<u8>, IndexState>,
+) -> Vec<String> {
+    let removed = old.keys().filter(|path| !new.contains_key(*path));
+    let changed = new
+        .iter()
+        .filter(|(path, entry)| old.get(*path) != Some(entry))
+        .map(|(path, _)| path);
+
+    removed
+        .chain(changed)
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect()
+}
+
+/// a dir matches everything below it, but libgit2 drops the entry of
+/// an untracked dir if the common prefix of all pathspecs reaches
+/// into it. the parent of a top level dir is the whole workdir though
+fn parent_dir(dir: &str) -> &str {
+    dir.rfind('/').map_or(dir, |idx| &dir[..idx])
+}
+
+/// only `paths` are checked if given
+fn get_status_repo(
+    repo: &Repository,
+    status_type: StatusType,
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    paths: Option<&[String]>,
+) -> Result<Vec<StatusItem>> {
+    let mut options = StatusOptions::default();
+    options
+        .show(status_type.into())
+        .update_index(true)
+        .include_untracked(show_untracked != ShowUntrackedFiles::No)
+        .recurse_untracked_dirs(
+            show_untracked == ShowUntrackedFiles::All,
+        );
+
+    if let Some(paths) = paths {
+        let work_dir = utils::work_dir(repo)?;
+
+        // exact paths let libgit2 skip everything else
+        options.disable_pathspec_match(true);
+        for path in paths {
+            let is_dir = path.ends_with('/');
+            let path = path.trim_end_matches('/');
+            if is_dir || work_dir.join(path).is_dir() {
+                options.pathspec(parent_dir(path));
+            } else {
+                options.pathspec(path);
+            }
+        }
+    }
+
+    let statuses = repo.statuses(Some(&mut options))?;
+    let sparse = SparseCheckout::load(repo)?;
 
     let mut res = Vec::with_capacity(statuses.len());
 
@@ -128,15 +654,514 @@ pub fn get_status(
             })?,
         };
 
+        // libgit2 does not know about sparse checkouts and reports
+        // everything that was left out as deleted
+        if status == Status::WT_DELETED
+            && sparse.as_ref().map_or(false, |s| s.excludes(&path))
+        {
+            continue;
+        }
+
         res.push(StatusItem {
             path,
             status: StatusItemType::from(status),
+            old_path: None,
         });
     }
 
+    detect_renames(repo, status_type, renames, &mut res)?;
+
     res.sort_by(|a, b| {
         Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
     });
 
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, set_index_bit, stage_add_file,
+        tests::repo_init_empty, IndexBit,
+    };
+    use std::{fs::File, io::Write, process::Command};
+
+    #[test]
+    fn test_sparse_checkout_not_deleted() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        std::fs::create_dir(root.join("sub"))?;
+        File::create(root.join("a"))?.write_all(b"a")?;
+        File::create(root.join("sub/b"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        stage_add_file(repo_path, Path::new("sub/b"))?;
+        commit(repo_path, "c1")?;
+
+        // only top level files are checked out
+        repo.config()?.set_bool("core.sparseCheckout", true)?;
+        std::fs::create_dir_all(repo.path().join("info"))?;
+        File::create(repo.path().join("info/sparse-checkout"))?
+            .write_all(b"/*\n!/*/\n")?;
+        std::fs::remove_dir_all(root.join("sub"))?;
+        std::fs::remove_file(root.join("a"))?;
+
+        let items = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::All,
+        )?;
+
+        assert_eq!(
+            items,
+            vec![StatusItem {
+                path: String::from("a"),
+                status: StatusItemType::Deleted,
+                old_path: None,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_show_untracked_files() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        std::fs::create_dir(root.join("sub"))?;
+        File::create(root.join("a"))?.write_all(b"a")?;
+        File::create(root.join("sub/b"))?.write_all(b"b")?;
+
+        let paths = |show_untracked| -> Result<Vec<String>> {
+            Ok(get_status(
+                repo_path,
+                StatusType::WorkingDir,
+                show_untracked,
+            )?
+            .into_iter()
+            .map(|item| item.path)
+            .collect())
+        };
+
+        assert!(paths(ShowUntrackedFiles::No)?.is_empty());
+        assert_eq!(paths(ShowUntrackedFiles::Normal)?, ["a", "sub/"]);
+        assert_eq!(paths(ShowUntrackedFiles::All)?, ["a", "sub/b"]);
+
+        assert_eq!(
+            ShowUntrackedFiles::from_config(repo_path)?,
+            ShowUntrackedFiles::Normal
+        );
+        repo.config()?.set_str("status.showUntrackedFiles", "no")?;
+        assert_eq!(
+            ShowUntrackedFiles::from_config(repo_path)?,
+            ShowUntrackedFiles::No
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_stats() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("a"))?.write_all(b"a\nb\n")?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        commit(repo_path, "c1")?;
+
+        File::create(root.join("a"))?.write_all(b"a\nc\nd\n")?;
+        std::fs::create_dir(root.join("sub"))?;
+        File::create(root.join("sub/b"))?.write_all(b"1\n2\n")?;
+        File::create(root.join("sub/c"))?.write_all(b"3\n")?;
+        File::create(root.join("bin"))?.write_all(&[0, 1, 2, 3])?;
+
+        let items = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::Normal,
+        )?;
+        let stats = get_status_stats(
+            repo_path,
+            StatusType::WorkingDir,
+            RenameDetection::default(),
+            &items,
+        )?;
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats["a"].insertions, 2);
+        assert_eq!(stats["a"].deletions, 1);
+        assert_eq!(stats["sub/"].insertions, 3);
+        assert!(stats["bin"].binary);
+        assert_eq!(stats["bin"].size_delta(), 4);
+
+        stage_add_file(repo_path, Path::new("a"))?;
+        let items = get_status(
+            repo_path,
+            StatusType::Stage,
+            ShowUntrackedFiles::No,
+        )?;
+        let stats = get_status_stats(
+            repo_path,
+            StatusType::Stage,
+            RenameDetection::default(),
+            &items,
+        )?;
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats["a"].lines(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_renames() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let content = b"1\n2\n3\n4\n5\n6\n7\n8\n";
+        File::create(root.join("a"))?.write_all(content)?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        commit(repo_path, "c1")?;
+
+        std::fs::rename(root.join("a"), root.join("b"))?;
+        File::create(root.join("b"))?
+            .write_all(b"1\n2\n3\n4\n5\n6\n7\n9\n")?;
+
+        let renamed = vec![StatusItem {
+            path: String::from("b"),
+            status: StatusItemType::Renamed,
+            old_path: Some(String::from("a")),
+        }];
+
+        let items = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::All,
+        )?;
+        assert_eq!(items, renamed);
+
+        let stats = get_status_stats(
+            repo_path,
+            StatusType::WorkingDir,
+            RenameDetection::default(),
+            &items,
+        )?;
+        assert_eq!(stats["b"].lines(), 2);
+
+        let diff = crate::sync::diff::get_diff_renamed(
+            repo_path,
+            "a",
+            String::from("b"),
+            false,
+            crate::sync::DiffOptions::default(),
+        )?;
+        assert!(diff.renamed);
+        assert_eq!(diff.hunks.len(), 1);
+
+        stage_add_file(repo_path, Path::new("b"))?;
+        crate::sync::stage_addremoved(repo_path, Path::new("a"))?;
+
+        let items = get_status(
+            repo_path,
+            StatusType::Stage,
+            ShowUntrackedFiles::All,
+        )?;
+        assert_eq!(items, renamed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_fully_staged() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let file = Path::new("a");
+
+        File::create(root.join(file))?.write_all(b"a")?;
+        assert!(!is_fully_staged(repo_path, file)?);
+
+        stage_add_file(repo_path, file)?;
+        assert!(is_fully_staged(repo_path, file)?);
+
+        File::create(root.join(file))?.write_all(b"b")?;
+        assert!(!is_fully_staged(repo_path, file)?);
+
+        stage_add_file(repo_path, file)?;
+        commit(repo_path, "c1")?;
+        assert!(!is_fully_staged(repo_path, file)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsmonitor_limits_paths() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |path: &str, content: &[u8]| -> Result<()> {
+            File::create(root.join(path))?.write_all(content)?;
+            Ok(())
+        };
+
+        write("a", b"a")?;
+        write("b", b"b")?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        stage_add_file(repo_path, Path::new("b"))?;
+        commit(repo_path, "c1")?;
+
+        write(
+            ".git/fsmonitor",
+            b"printf 'tok\\0'\ncat .git/changed\n",
+        )?;
+        Command::new("chmod")
+            .args(&["+x", ".git/fsmonitor"])
+            .current_dir(root)
+            .output()?;
+        repo.config()?.set_str("core.fsmonitor", ".git/fsmonitor")?;
+
+        let paths =
+            |cache: &mut FsMonitorCache| -> Result<Vec<String>> {
+                Ok(get_status_fsmonitor(
+                    repo_path,
+                    StatusType::WorkingDir,
+                    ShowUntrackedFiles::All,
+                    RenameDetection::default(),
+                    cache,
+                )?
+                .into_iter()
+                .map(|item| item.path)
+                .collect())
+            };
+
+        let mut cache = FsMonitorCache::default();
+
+        write(".git/changed", b"")?;
+        assert!(paths(&mut cache)?.is_empty());
+
+        write("a", b"a2")?;
+        write("b", b"b2")?;
+
+        write(".git/changed", b"a\0")?;
+        assert_eq!(paths(&mut cache)?, vec!["a"]);
+
+        // dirty paths are checked even if not reported again
+        write(".git/changed", b"")?;
+        assert_eq!(paths(&mut cache)?, vec!["a"]);
+
+        write(".git/changed", b"/\0")?;
+        assert_eq!(paths(&mut cache)?, vec!["a", "b"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsmonitor_dir_entries() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |path: &str, content: &[u8]| -> Result<()> {
+            File::create(root.join(path))?.write_all(content)?;
+            Ok(())
+        };
+
+        std::fs::create_dir_all(root.join("sub/new"))?;
+        write("sub/a", b"a")?;
+        write("subway", b"s")?;
+        stage_add_file(repo_path, Path::new("sub/a"))?;
+        stage_add_file(repo_path, Path::new("subway"))?;
+        commit(repo_path, "c1")?;
+
+        write(
+            ".git/fsmonitor",
+            b"printf 'tok\\0'\ncat .git/changed\n",
+        )?;
+        Command::new("chmod")
+            .args(["+x", ".git/fsmonitor"])
+            .current_dir(root)
+            .output()?;
+        repo.config()?.set_str("core.fsmonitor", ".git/fsmonitor")?;
+
+        let paths =
+            |cache: &mut FsMonitorCache| -> Result<Vec<String>> {
+                Ok(get_status_fsmonitor(
+                    repo_path,
+                    StatusType::WorkingDir,
+                    ShowUntrackedFiles::Normal,
+                    RenameDetection::default(),
+                    cache,
+                )?
+                .into_iter()
+                .map(|item| item.path)
+                .collect())
+            };
+
+        let mut cache = FsMonitorCache::default();
+
+        write("sub/new/c", b"c")?;
+        write(".git/changed", b"")?;
+        assert_eq!(paths(&mut cache)?, vec!["sub/new/"]);
+
+        write("sub/new/d", b"d")?;
+        write("sub/a", b"a2")?;
+        write("subway", b"s2")?;
+
+        // dirs are checked through their parent, not its siblings
+        write(".git/changed", b"sub/new/d\0")?;
+        assert_eq!(paths(&mut cache)?, vec!["sub/a", "sub/new/"]);
+
+        // the untracked dir stays dirty
+        write(".git/changed", b"")?;
+        assert_eq!(paths(&mut cache)?, vec!["sub/a", "sub/new/"]);
+
+        write(".git/changed", b"sub/\0")?;
+        assert_eq!(paths(&mut cache)?, vec!["sub/a", "sub/new/"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsmonitor_rename_detection_changed() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("a"))?
+            .write_all(b"1\n2\n3\n4\n5\n6\n7\n8\n")?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        commit(repo_path, "c1")?;
+        std::fs::remove_file(root.join("a"))?;
+        File::create(root.join("b"))?
+            .write_all(b"1\n2\n3\n4\n5\n6\n7\n9\n")?;
+
+        fsmonitor_from_file(&repo)?;
+        File::create(root.join(".git/changed"))?;
+        let mut cache = FsMonitorCache::default();
+        let mut items = |renames| -> Result<Vec<StatusItemType>> {
+            Ok(get_status_fsmonitor(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::All,
+                renames,
+                &mut cache,
+            )?
+            .into_iter()
+            .map(|item| item.status)
+            .collect())
+        };
+
+        assert_eq!(
+            items(RenameDetection::default())?,
+            vec![StatusItemType::Renamed]
+        );
+        assert_eq!(
+            items(RenameDetection {
+                threshold: 100,
+                copies: false,
+            })?,
+            vec![StatusItemType::Deleted, StatusItemType::New]
+        );
+
+        Ok(())
+    }
+
+    /// a hook reporting the content of `.git/changed`
+    fn fsmonitor_from_file(repo: &Repository) -> Result<()> {
+        let root = repo.path().parent().unwrap();
+        File::create(root.join(".git/fsmonitor"))?
+            .write_all(b"printf 'tok\\0'\ncat .git/changed\n")?;
+        Command::new("chmod")
+            .args(["+x", ".git/fsmonitor"])
+            .current_dir(root)
+            .output()?;
+        repo.config()?.set_str("core.fsmonitor", ".git/fsmonitor")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsmonitor_ignore_file_changed() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let write = |path: &str, content: &[u8]| -> Result<()> {
+            File::create(root.join(path))?.write_all(content)?;
+            Ok(())
+        };
+
+        write(".gitignore", b"*.log\n")?;
+        stage_add_file(repo_path, Path::new(".gitignore"))?;
+        commit(repo_path, "c1")?;
+        write("a.log", b"a")?;
+
+        fsmonitor_from_file(&repo)?;
+        let mut cache = FsMonitorCache::default();
+        let mut paths = || -> Result<Vec<String>> {
+            Ok(get_status_fsmonitor(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::All,
+                RenameDetection::default(),
+                &mut cache,
+            )?
+            .into_iter()
+            .map(|item| item.path)
+            .collect())
+        };
+
+        write(".git/changed", b"")?;
+        assert!(paths()?.is_empty());
+
+        // only the ignore file is reported
+        write(".gitignore", b"")?;
+        write(".git/changed", b".gitignore\0")?;
+        assert_eq!(paths()?, vec![".gitignore", "a.log"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsmonitor_index_bit_cleared() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        File::create(root.join("a"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("a"))?;
+        commit(repo_path, "c1")?;
+        File::create(root.join("a"))?.write_all(b"a2")?;
+        set_index_bit(repo_path, "a", IndexBit::SkipWorktree, true)?;
+
+        fsmonitor_from_file(&repo)?;
+        let mut cache = FsMonitorCache::default();
+        let mut paths = || -> Result<Vec<String>> {
+            Ok(get_status_fsmonitor(
+                repo_path,
+                StatusType::WorkingDir,
+                ShowUntrackedFiles::All,
+                RenameDetection::default(),
+                &mut cache,
+            )?
+            .into_iter()
+            .map(|item| item.path)
+            .collect())
+        };
+
+        File::create(root.join(".git/changed"))?.write_all(b"")?;
+        assert!(paths()?.is_empty());
+
+        // neither id nor mode of the entry change
+        set_index_bit(repo_path, "a", IndexBit::SkipWorktree, false)?;
+        assert_eq!(paths()?, vec!["a"]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+//! sync git api for submodules
+
+use super::{utils::repo, CommitId};
+use crate::error::{Error, Result};
+use git2::{Repository, Submodule, SubmoduleIgnore};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// state of a submodule in the working directory
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SubmoduleStatus {
+    /// not initialized or not cloned yet
+    Uninitialized,
+    /// checked out commit differs from the one recorded in the
+    /// superproject (`git submodule update` is needed)
+    OutOfDate,
+    /// contains local modifications or untracked files
+    Modified,
+    /// checked out at the recorded commit without changes
+    UpToDate,
+}
+
+///
+#[derive(Clone, Debug)]
+pub struct SubmoduleInfo {
+    ///
+    pub name: String,
+    /// path relative to the superproject's working directory
+    pub path: String,
+    /// configured url
+    pub url: Option<String>,
+    /// commit recorded in the superproject
+    pub id: Option<CommitId>,
+    ///
+    pub status: SubmoduleStatus,
+}
+
+impl From<git2::SubmoduleStatus> for SubmoduleStatus {
+    fn from(s: git2::SubmoduleStatus) -> Self {
+        if s.is_wd_uninitialized() || !s.is_in_wd() {
+            Self::Uninitialized
+        } else if s.is_wd_modified() {
+            Self::OutOfDate
+        } else if s.contains(git2::SubmoduleStatus::WD_INDEX_MODIFIED)
+            || s.is_wd_wd_modified()
+            || s.is_wd_untracked()
+        {
+            Self::Modified
+        } else {
+            Self::UpToDate
+        }
+    }
+}
+
+/// returns all submodules of the repository
+pub fn get_submodules(repo_path: &str) -> Result<Vec<SubmoduleInfo>> {
+    scope_time!("get_submodules");
+
+    let repo = repo(repo_path)?;
+
+    let res = repo
+        .submodules()?
+        .iter()
+        .map(|sub| submodule_info(&repo, sub))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(res)
+}
+
+fn submodule_info(
+    repo: &Repository,
+    sub: &Submodule,
+) -> Result<SubmoduleInfo> {
+    let name = sub
+        .name()
+        .ok_or_else(|| {
+            Error::Generic(String::from("invalid submodule name"))
+        })?
+        .to_string();
+
+    let status =
+        repo.submodule_status(&name, SubmoduleIgnore::None)?;
+
+    Ok(SubmoduleInfo {
+        path: sub.path().to_string_lossy().to_string(),
+        url: sub.url().map(String::from),
+        id: sub
+            .index_id()
+            .or_else(|| sub.head_id())
+            .map(CommitId::new),
+        status: status.into(),
+        name,
+    })
+}
+
+/// initializes the submodule `name` (like `git submodule init`)
+pub fn submodule_init(repo_path: &str, name: &str) -> Result<()> {
+    scope_time!("submodule_init");
+
+    let repo = repo(repo_path)?;
+    let mut sub = repo.find_submodule(name)?;
+
+    sub.init(false)?;
+
+    Ok(())
+}
+
+/// clones if needed and checks out the recorded commit of the
+/// submodule `name` (like `git submodule update --init`)
+pub fn submodule_update(repo_path: &str, name: &str) -> Result<()> {
+    scope_time!("submodule_update");
+
+    let repo = repo(repo_path)?;
+    let mut sub = repo.find_submodule(name)?;
+
+    sub.update(true, None)?;
+
+    Ok(())
+}
+
+/// returns the absolute path of the submodule `name`,
+/// to be used to open it as a nested repository
+pub fn submodule_path(repo_path: &str, name: &str) -> Result<String> {
+    scope_time!("submodule_path");
+
+    let repo = repo(repo_path)?;
+    let sub = repo.find_submodule(name)?;
+
+    let work_dir = repo.workdir().ok_or_else(|| {
+        Error::Generic(String::from("repository has no workdir"))
+    })?;
+
+    let path = work_dir.join(sub.path());
+
+    if !is_checked_out(&path) {
+        return Err(Error::Generic(format!(
+            "submodule '{}' is not checked out",
+            name
+        )));
+    }
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn is_checked_out(path: &Path) -> bool {
+    Repository::open(path).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init;
+    use git2::build::CheckoutBuilder;
+
+    fn add_submodule(
+        repo: &Repository,
+        upstream: &Repository,
+    ) -> Repository {
+        let upstream_path =
+            upstream.path().parent().unwrap().to_str().unwrap();
+        let head = upstream.head().unwrap().target().unwrap();
+
+        let mut sub = repo
+            .submodule(upstream_path, Path::new("sub"), true)
+            .unwrap();
+
+        let sub_repo = sub.open().unwrap();
+        sub_repo
+            .find_remote("origin")
+            .unwrap()
+            .fetch(
+                &["+refs/heads/*:refs/remotes/origin/*"],
+                None,
+                None,
+            )
+            .unwrap();
+        sub_repo.set_head_detached(head).unwrap();
+        sub_repo
+            .checkout_head(Some(CheckoutBuilder::new().force()))
+            .unwrap();
+
+        sub.add_finalize().unwrap();
+
+        sub_repo
+    }
+
+    #[test]
+    fn test_smoke() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        assert!(get_submodules(repo_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_status_and_update() {
+        let (_upstream_td, upstream) = repo_init().unwrap();
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        let sub_repo = add_submodule(&repo, &upstream);
+
+        let subs = get_submodules(repo_path).unwrap();
+        assert_eq!(subs.len(), 1);
+        assert_eq!(subs[0].path, "sub");
+        assert_eq!(
+            subs[0].url.as_deref(),
+            upstream.path().parent().unwrap().to_str()
+        );
+        assert_eq!(subs[0].status, SubmoduleStatus::UpToDate);
+
+        // move the submodule away from the recorded commit
+        {
+            let sig = git2::Signature::now("name", "email").unwrap();
+            let head = sub_repo.head().unwrap().target().unwrap();
+            let parent = sub_repo.find_commit(head).unwrap();
+            let tree = parent.tree().unwrap();
+            sub_repo
+                .commit(
+                    Some("HEAD"),
+                    &sig,
+                    &sig,
+                    "m",
+                    &tree,
+                    &[&parent],
+                )
+                .unwrap();
+        }
+
+        let subs = get_submodules(repo_path).unwrap();
+        assert_eq!(subs[0].status, SubmoduleStatus::OutOfDate);
+
+        submodule_update(repo_path, "sub").unwrap();
+
+        let subs = get_submodules(repo_path).unwrap();
+        assert_eq!(subs[0].status, SubmoduleStatus::UpToDate);
+
+        assert!(submodule_path(repo_path, "sub")
+            .unwrap()
+            .ends_with("sub"));
+    }
+}
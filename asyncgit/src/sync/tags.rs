@@ -1,7 +1,7 @@
 use super::{utils::repo, CommitId};
 use crate::error::Result;
 use scopetime::scope_time;
-use std::collections::BTreeMap;
+use std::{cmp::Ordering, collections::BTreeMap};
 
 /// all tags pointing to a single commit
 pub type CommitTags = Vec<String>;
@@ -44,6 +44,82 @@ pub fn get_tags(repo_path: &str) -> Result<Tags> {
     Ok(res)
 }
 
+/// `major.minor.patch` of a tag like `v1.2.3-rc.1+build`, build
+/// metadata takes no part in the order
+struct Version<'a> {
+    core: [u64; 3],
+    pre: Option<&'a str>,
+}
+
+impl<'a> Version<'a> {
+    fn parse(tag: &'a str) -> Option<Self> {
+        let tag = tag.strip_prefix('v').unwrap_or(tag);
+        let tag = tag.split('+').next()?;
+        let (core, pre) = match tag.find('-') {
+            Some(pos) => (&tag[..pos], Some(&tag[pos + 1..])),
+            None => (tag, None),
+        };
+
+        let mut parts = core.split('.');
+        let mut version = [0; 3];
+        for part in &mut version {
+            *part = parts.next()?.parse().ok()?;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self { core: version, pre })
+    }
+}
+
+/// pre-release identifiers one by one, numbers before words
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let order = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => {
+                match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => a.cmp(b),
+                }
+            }
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+}
+
+/// orders tag names as semantic versions (`v1.9.0` before
+/// `v1.10.0`, pre-releases before their release), names that are no
+/// version come after the versions in plain order
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Some(version_a), Some(version_b)) => version_a
+            .core
+            .cmp(&version_b.core)
+            .then_with(|| match (version_a.pre, version_b.pre) {
+                (None, None) => Ordering::Equal,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(pre_a), Some(pre_b)) => {
+                    compare_pre(pre_a, pre_b)
+                }
+            })
+            .then_with(|| a.cmp(b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +158,33 @@ mod tests {
             vec!["a", "b"]
         );
     }
+
+    #[test]
+    fn test_compare_versions() {
+        let mut tags = vec![
+            "v1.10.0",
+            "release",
+            "v1.9.0",
+            "1.9.0-rc.10",
+            "1.9.0-rc.2",
+            "1.9.0-beta",
+            "v2.0",
+            "v1.9.0+build",
+        ];
+        tags.sort_by(|a, b| compare_versions(a, b));
+
+        assert_eq!(
+            tags,
+            vec![
+                "1.9.0-beta",
+                "1.9.0-rc.2",
+                "1.9.0-rc.10",
+                "v1.9.0",
+                "v1.9.0+build",
+                "v1.10.0",
+                "release",
+                "v2.0",
+            ]
+        );
+    }
 }
@@ -0,0 +1,395 @@
+//! what it takes to revert destructive operations: discarded changes
+//! go into a dangling commit first, dropped stashes are stored again
+
+use super::{
+    utils::{repo, work_dir},
+    CommitId,
+};
+use crate::error::Result;
+use git2::{
+    Index, IndexEntry, IndexTime, ObjectType, Repository, Signature,
+    TreeWalkMode, TreeWalkResult,
+};
+use scopetime::scope_time;
+use std::{convert::TryFrom, fs, path::Path};
+
+/// how to revert an operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Undo {
+    /// the work dir at `paths` (files or folders) as of the snapshot
+    Discard {
+        ///
+        paths: Vec<String>,
+        /// commit holding the files under `paths`
+        snapshot: CommitId,
+    },
+    /// a stash that was dropped
+    StashDrop {
+        ///
+        id: CommitId,
+        ///
+        message: String,
+    },
+}
+
+/// git's mode of a symlink, the blob holds its target
+const SYMLINK_MODE: u32 = 0o120_000;
+
+/// files and symlinks (not ignored) in the work dir at `path`,
+/// relative to it. ignored dirs are not entered
+fn workdir_files(
+    repo: &Repository,
+    path: &str,
+) -> Result<Vec<String>> {
+    fn walk(
+        repo: &Repository,
+        work_dir: &Path,
+        dir: &Path,
+        out: &mut Vec<String>,
+    ) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let full_path = entry.path();
+            let rel = match full_path.strip_prefix(work_dir) {
+                Ok(rel) => rel,
+                Err(_) => continue,
+            };
+            if repo.is_path_ignored(rel).unwrap_or_default() {
+                continue;
+            }
+
+            // symlinks are not followed but kept as such
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    walk(repo, work_dir, &full_path, out)?;
+                }
+            } else if file_type.is_file() || file_type.is_symlink() {
+                out.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+        Ok(())
+    }
+
+    let work_dir = work_dir(repo)?;
+    let path = path.trim_end_matches('/');
+    let full_path = work_dir.join(path);
+
+    let mut files = Vec::new();
+    if repo.is_path_ignored(path).unwrap_or_default() {
+        return Ok(files);
+    }
+    if let Ok(meta) = fs::symlink_metadata(&full_path) {
+        if meta.is_dir() {
+            walk(repo, work_dir, &full_path, &mut files)?;
+        } else {
+            files.push(path.to_string());
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn read_symlink(path: &Path) -> Result<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(fs::read_link(path)?.as_os_str().as_bytes().to_vec())
+}
+
+#[cfg(not(unix))]
+fn read_symlink(path: &Path) -> Result<Vec<u8>> {
+    Ok(fs::read_link(path)?
+        .to_string_lossy()
+        .replace('\\', "/")
+        .into_bytes())
+}
+
+#[cfg(unix)]
+fn write_symlink(path: &Path, target: &[u8]) -> Result<()> {
+    use std::os::unix::{ffi::OsStrExt, fs::symlink};
+    symlink(std::ffi::OsStr::from_bytes(target), path)?;
+    Ok(())
+}
+
+/// the target goes into a plain file, like git does without
+/// `core.symlinks`
+#[cfg(not(unix))]
+fn write_symlink(path: &Path, target: &[u8]) -> Result<()> {
+    fs::write(path, target)?;
+    Ok(())
+}
+
+/// keeps the work dir at `paths` (files or folders) in a commit no
+/// ref points to, before the changes there get discarded
+pub fn snapshot_discard(
+    repo_path: &str,
+    paths: &[&str],
+) -> Result<Undo> {
+    scope_time!("snapshot_discard");
+
+    let repo = repo(repo_path)?;
+    let work_dir = work_dir(&repo)?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        files.extend(workdir_files(&repo, path)?);
+    }
+
+    let mut index = Index::new()?;
+    for file in files {
+        let full_path = work_dir.join(&file);
+        let (content, mode) = if fs::symlink_metadata(&full_path)?
+            .file_type()
+            .is_symlink()
+        {
+            (read_symlink(&full_path)?, SYMLINK_MODE)
+        } else {
+            (fs::read(&full_path)?, 0o100_644)
+        };
+        let id = repo.blob(&content)?;
+
+        index.add(&IndexEntry {
+            ctime: IndexTime::new(0, 0),
+            mtime: IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: 0,
+            id,
+            flags: 0,
+            flags_extended: 0,
+            path: file.into_bytes(),
+        })?;
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(&repo)?)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("gitui", "gitui"))?;
+    let snapshot = repo.commit(
+        None,
+        &signature,
+        &signature,
+        &format!("gitui: discard {}", paths.join(" ")),
+        &tree,
+        &[],
+    )?;
+
+    Ok(Undo::Discard {
+        paths: paths.iter().map(|path| (*path).to_string()).collect(),
+        snapshot: snapshot.into(),
+    })
+}
+
+/// remembers the stash before it gets dropped
+pub fn snapshot_stash(repo_path: &str, id: CommitId) -> Result<Undo> {
+    let repo = repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    Ok(Undo::StashDrop {
+        id,
+        message: commit.message().unwrap_or_default().to_string(),
+    })
+}
+
+/// puts the discarded files back (removing the ones that did not
+/// exist) or stores the dropped stash again, on top of the list
+pub fn undo(repo_path: &str, undo: &Undo) -> Result<()> {
+    scope_time!("undo");
+
+    let repo = repo(repo_path)?;
+
+    match undo {
+        Undo::Discard { paths, snapshot } => {
+            let work_dir = work_dir(&repo)?;
+            let tree =
+                repo.find_commit((*snapshot).into())?.tree()?;
+
+            let mut kept = Vec::new();
+            tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                if entry.kind() == Some(ObjectType::Blob) {
+                    kept.push((
+                        format!(
+                            "{}{}",
+                            dir,
+                            entry.name().unwrap_or_default()
+                        ),
+                        entry.id(),
+                        entry.filemode(),
+                    ));
+                }
+                TreeWalkResult::Ok
+            })?;
+
+            for path in paths {
+                for file in workdir_files(&repo, path)? {
+                    if !kept.iter().any(|(kept, ..)| *kept == file) {
+                        fs::remove_file(work_dir.join(file))?;
+                    }
+                }
+            }
+
+            for (file, id, mode) in kept {
+                let full_path = work_dir.join(file);
+                if let Some(parent) = full_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                // writing would follow a symlink in the way
+                if fs::symlink_metadata(&full_path)
+                    .map_or(false, |meta| {
+                        meta.file_type().is_symlink()
+                    })
+                {
+                    fs::remove_file(&full_path)?;
+                }
+
+                let blob = repo.find_blob(id)?;
+                if u32::try_from(mode).ok() == Some(SYMLINK_MODE) {
+                    write_symlink(&full_path, blob.content())?;
+                } else {
+                    fs::write(full_path, blob.content())?;
+                }
+            }
+        }
+        Undo::StashDrop { id, message } => {
+            repo.reference_ensure_log("refs/stash")?;
+            repo.reference(
+                "refs/stash",
+                (*id).into(),
+                true,
+                message,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        get_stashes, reset_workdir, stash_drop, stash_save,
+        tests::repo_init,
+    };
+
+    #[test]
+    fn test_undo_discard() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("dir/new.txt"), "new").unwrap();
+        fs::write(root.join("dir/other.txt"), "other").unwrap();
+
+        let snapshot =
+            snapshot_discard(repo_path, &["dir/"]).unwrap();
+        reset_workdir(repo_path, "dir/").unwrap();
+        assert!(!root.join("dir/new.txt").exists());
+
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("dir/later.txt"), "later").unwrap();
+
+        undo(repo_path, &snapshot).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(root.join("dir/new.txt")).unwrap(),
+            "new"
+        );
+        assert!(root.join("dir/other.txt").exists());
+        assert!(!root.join("dir/later.txt").exists());
+    }
+
+    #[test]
+    fn test_undo_discard_files() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::write(root.join("b.txt"), "b").unwrap();
+
+        let snapshot =
+            snapshot_discard(repo_path, &["a.txt", "b.txt"]).unwrap();
+        reset_workdir(repo_path, "a.txt").unwrap();
+        reset_workdir(repo_path, "b.txt").unwrap();
+
+        undo(repo_path, &snapshot).unwrap();
+
+        assert!(root.join("a.txt").exists());
+        assert!(root.join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_workdir_files_skips_ignored_dirs() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+
+        fs::write(root.join(".gitignore"), "ignored/\n*.log\n")
+            .unwrap();
+        fs::create_dir_all(root.join("dir/ignored/deep")).unwrap();
+        fs::write(root.join("dir/ignored/deep/x.txt"), "x").unwrap();
+        fs::write(root.join("dir/a.txt"), "a").unwrap();
+        fs::write(root.join("dir/b.log"), "b").unwrap();
+
+        assert_eq!(
+            workdir_files(&repo, "dir/").unwrap(),
+            vec![String::from("dir/a.txt")]
+        );
+        assert!(workdir_files(&repo, "dir/ignored")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_undo_discard_symlink() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::create_dir(root.join("dir")).unwrap();
+        fs::write(root.join("dir/a.txt"), "a").unwrap();
+        std::os::unix::fs::symlink("a.txt", root.join("dir/link"))
+            .unwrap();
+
+        let snapshot =
+            snapshot_discard(repo_path, &["dir/"]).unwrap();
+        reset_workdir(repo_path, "dir/").unwrap();
+        assert!(fs::symlink_metadata(root.join("dir/link")).is_err());
+
+        undo(repo_path, &snapshot).unwrap();
+
+        assert_eq!(
+            fs::read_link(root.join("dir/link")).unwrap(),
+            Path::new("a.txt")
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("dir/a.txt")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_undo_stash_drop() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join("foo.txt"), "foo").unwrap();
+        let id =
+            stash_save(repo_path, Some("keep"), true, false, false)
+                .unwrap();
+
+        let snapshot = snapshot_stash(repo_path, id).unwrap();
+        stash_drop(repo_path, id).unwrap();
+        assert!(get_stashes(repo_path).unwrap().is_empty());
+
+        undo(repo_path, &snapshot).unwrap();
+
+        assert_eq!(get_stashes(repo_path).unwrap(), vec![id]);
+    }
+}
@@ -35,22 +35,18 @@ pub(crate) fn repo(repo_path: &str) -> Result<Repository> {
         Vec::<&Path>::new(),
     )?;
 
-    if repo.is_bare() {
-        return Err(Error::Generic("bare repo".to_string()));
-    }
-
     Ok(repo)
 }
 
-///
-pub(crate) fn work_dir(repo: &Repository) -> &Path {
-    repo.workdir().expect("unable to query workdir")
+/// fails with `Error::BareRepo` for bare repos
+pub(crate) fn work_dir(repo: &Repository) -> Result<&Path> {
+    repo.workdir().ok_or(Error::BareRepo)
 }
 
 ///
 pub fn repo_work_dir(repo_path: &str) -> Result<String> {
     let repo = repo(repo_path)?;
-    if let Some(workdir) = work_dir(&repo).to_str() {
+    if let Some(workdir) = work_dir(&repo)?.to_str() {
         Ok(workdir.to_string())
     } else {
         Err(Error::Generic("invalid workdir".to_string()))
@@ -76,6 +72,23 @@ pub fn get_head_repo(repo: &Repository) -> Result<CommitId> {
     }
 }
 
+/// commit the pseudo ref `name` (like `ORIG_HEAD` or `FETCH_HEAD`)
+/// points to, `None` if git did not write it
+pub fn get_pseudo_ref(
+    repo_path: &str,
+    name: &str,
+) -> Result<Option<CommitId>> {
+    scope_time!("get_pseudo_ref");
+
+    let repo = repo(repo_path)?;
+
+    if !repo.path().join(name).is_file() {
+        return Ok(None);
+    }
+
+    Ok(Some(repo.refname_to_id(name)?.into()))
+}
+
 /// add a file diff from workingdir to stage (will not add removed files see `stage_addremoved`)
 pub fn stage_add_file(repo_path: &str, path: &Path) -> Result<()> {
     scope_time!("stage_add_file");
@@ -123,7 +136,7 @@ mod tests {
     use super::*;
     use crate::sync::{
         commit,
-        status::{get_status, StatusType},
+        status::{get_status, ShowUntrackedFiles, StatusType},
         tests::{
             debug_cmd_print, get_statuses, repo_init, repo_init_empty,
         },
@@ -134,6 +147,44 @@ mod tests {
         path::Path,
     };
 
+    #[test]
+    fn test_pseudo_refs() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+        let head = get_head(repo_path).unwrap();
+        let hash = head.to_string();
+
+        assert_eq!(
+            get_pseudo_ref(repo_path, "ORIG_HEAD").unwrap(),
+            None
+        );
+
+        fs::write(
+            repo.path().join("ORIG_HEAD"),
+            format!("{}\n", hash),
+        )
+        .unwrap();
+        // several lines of the form git fetch writes
+        fs::write(
+            repo.path().join("FETCH_HEAD"),
+            format!(
+                "{}\t\tbranch 'master' of origin\n{}\tnot-for-merge\tbranch 'x' of origin\n",
+                hash, hash
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_pseudo_ref(repo_path, "ORIG_HEAD").unwrap(),
+            Some(head)
+        );
+        assert_eq!(
+            get_pseudo_ref(repo_path, "FETCH_HEAD").unwrap(),
+            Some(head)
+        );
+    }
+
     #[test]
     fn test_stage_add_smoke() {
         let file_path = Path::new("foo");
@@ -178,7 +229,9 @@ mod tests {
         let repo_path = root.as_os_str().to_str().unwrap();
 
         let status_count = |s: StatusType| -> usize {
-            get_status(repo_path, s, true).unwrap().len()
+            get_status(repo_path, s, ShowUntrackedFiles::All)
+                .unwrap()
+                .len()
         };
 
         fs::create_dir_all(&root.join("a/d"))?;
@@ -207,7 +260,9 @@ mod tests {
         let repo_path = root.as_os_str().to_str().unwrap();
 
         let status_count = |s: StatusType| -> usize {
-            get_status(repo_path, s, true).unwrap().len()
+            get_status(repo_path, s, ShowUntrackedFiles::All)
+                .unwrap()
+                .len()
         };
 
         let full_path = &root.join(file_path);
@@ -241,7 +296,9 @@ mod tests {
         let repo_path = root.as_os_str().to_str().unwrap();
 
         let status_count = |s: StatusType| -> usize {
-            get_status(repo_path, s, true).unwrap().len()
+            get_status(repo_path, s, ShowUntrackedFiles::All)
+                .unwrap()
+                .len()
         };
 
         let sub = &root.join("sub");
@@ -284,4 +341,43 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_bare_repo() -> Result<()> {
+        let td = tempfile::TempDir::new()?;
+        let repo = git2::Repository::init_bare(td.path())?;
+        let repo_path = td.path().as_os_str().to_str().unwrap();
+
+        let sig = git2::Signature::now("name", "email")?;
+        let blob = repo.blob(b"foo")?;
+        let mut tree = repo.treebuilder(None)?;
+        tree.insert("foo", blob, 0o100_644)?;
+        let tree = repo.find_tree(tree.write()?)?;
+        let id =
+            repo.commit(Some("HEAD"), &sig, &sig, "c1", &tree, &[])?;
+
+        assert!(is_bare_repo(repo_path)?);
+        assert_eq!(get_head(repo_path)?, CommitId::new(id));
+        assert_eq!(
+            crate::sync::get_diff_commit(
+                repo_path,
+                CommitId::new(id),
+                String::new(),
+                crate::sync::DiffOptions::default()
+            )?
+            .hunks
+            .len(),
+            1
+        );
+        assert!(matches!(
+            repo_work_dir(repo_path),
+            Err(Error::BareRepo)
+        ));
+        assert!(matches!(
+            crate::sync::add_to_ignore(repo_path, "foo"),
+            Err(Error::BareRepo)
+        ));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,260 @@
+//! cleaning the staged content before a commit, for projects that
+//! reject trailing whitespace
+
+use super::{
+    blob::{binary_attr, is_binary},
+    utils::{repo, work_dir},
+};
+use crate::error::Result;
+use git2::{Delta, Repository};
+use scopetime::scope_time;
+use std::{convert::TryFrom, fs, path::Path};
+
+/// a submodule, its id is a commit of the submodule
+const GITLINK_MODE: u32 = 0o160_000;
+/// its content is the link target, not text
+const SYMLINK_MODE: u32 = 0o120_000;
+
+/// `content` without spaces and tabs at the end of its lines, the
+/// line endings (`\r\n` too) stay as they are
+fn strip_trailing(content: &[u8]) -> Vec<u8> {
+    content
+        .split(|b| *b == b'\n')
+        .map(|line| {
+            let cr = line.ends_with(b"\r");
+            let text =
+                if cr { &line[..line.len() - 1] } else { line };
+            let end = text
+                .iter()
+                .rposition(|b| *b != b' ' && *b != b'\t')
+                .map_or(0, |pos| pos + 1);
+
+            let mut line = text[..end].to_vec();
+            if cr {
+                line.push(b'\r');
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join(&b'\n')
+}
+
+/// paths added or changed in the index compared to head
+fn staged_paths(repo: &Repository) -> Result<Vec<String>> {
+    let head_tree =
+        repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let diff =
+        repo.diff_tree_to_index(head_tree.as_ref(), None, None)?;
+
+    Ok(diff
+        .deltas()
+        .filter(|delta| {
+            matches!(
+                delta.status(),
+                Delta::Added
+                    | Delta::Modified
+                    | Delta::Renamed
+                    | Delta::Copied
+            )
+        })
+        .filter_map(|delta| {
+            delta
+                .new_file()
+                .path()
+                .map(|path| path.to_string_lossy().to_string())
+        })
+        .collect())
+}
+
+/// strips trailing whitespace off the staged text files (the ones
+/// `.gitattributes` or their content call binary are left alone) and
+/// stages the result, the work dir is cleaned too unless the file has
+/// unstaged changes there, returns the paths that changed
+pub fn strip_staged_whitespace(
+    repo_path: &str,
+) -> Result<Vec<String>> {
+    scope_time!("strip_staged_whitespace");
+
+    let repo = repo(repo_path)?;
+    let work_dir = work_dir(&repo)?;
+    let mut index = repo.index()?;
+
+    let mut cleaned = Vec::new();
+    for path in staged_paths(&repo)? {
+        let mut entry = if let Some(entry) =
+            index.get_path(Path::new(&path), 0)
+        {
+            entry
+        } else {
+            continue;
+        };
+
+        if entry.mode == GITLINK_MODE || entry.mode == SYMLINK_MODE {
+            continue;
+        }
+
+        let attr = binary_attr(&repo, Path::new(&path))?;
+        let blob = repo.find_blob(entry.id)?;
+        let content = blob.content();
+        if attr == Some(true)
+            || (attr.is_none() && is_binary(content))
+        {
+            continue;
+        }
+
+        let stripped = strip_trailing(content);
+        if stripped == content {
+            continue;
+        }
+
+        let file = work_dir.join(&path);
+        if fs::read(&file).ok().as_deref() == Some(content) {
+            fs::write(&file, &stripped)?;
+            index.add_path(Path::new(&path))?;
+        } else {
+            entry.id = repo.blob(&stripped)?;
+            entry.file_size =
+                u32::try_from(stripped.len()).unwrap_or(u32::MAX);
+            index.add(&entry)?;
+        }
+
+        cleaned.push(path);
+    }
+
+    if !cleaned.is_empty() {
+        index.write()?;
+    }
+
+    Ok(cleaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        commit, stage_add_file,
+        status::{get_status, ShowUntrackedFiles, StatusType},
+        tests::repo_init,
+    };
+    use git2::{IndexEntry, IndexTime, Oid};
+
+    #[test]
+    fn test_strip_trailing() {
+        assert_eq!(strip_trailing(b"a \nb\t\t\n  \nc"), b"a\nb\n\nc");
+        assert_eq!(strip_trailing(b"a \r\nb\r\n"), b"a\r\nb\r\n");
+        assert_eq!(strip_trailing(b"  a"), b"  a");
+    }
+
+    #[test]
+    fn test_strip_staged_whitespace() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        fs::write(root.join(".gitattributes"), "*.bin binary\n")
+            .unwrap();
+        fs::write(root.join("clean.txt"), "clean\n").unwrap();
+        fs::write(root.join("dirty.txt"), "dirty  \n").unwrap();
+        fs::write(root.join("data.bin"), "data  \n").unwrap();
+        fs::write(root.join("unstaged.txt"), "old \n").unwrap();
+        for file in
+            &["clean.txt", "dirty.txt", "data.bin", "unstaged.txt"]
+        {
+            stage_add_file(repo_path, Path::new(file)).unwrap();
+        }
+        fs::write(root.join("unstaged.txt"), "new \n").unwrap();
+
+        assert_eq!(
+            strip_staged_whitespace(repo_path).unwrap(),
+            vec![
+                String::from("dirty.txt"),
+                String::from("unstaged.txt")
+            ]
+        );
+
+        assert_eq!(
+            fs::read_to_string(root.join("dirty.txt")).unwrap(),
+            "dirty\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("data.bin")).unwrap(),
+            "data  \n"
+        );
+        // unstaged changes are kept, only the index got cleaned
+        assert_eq!(
+            fs::read_to_string(root.join("unstaged.txt")).unwrap(),
+            "new \n"
+        );
+        let mut index = repo.index().unwrap();
+        index.read(true).unwrap();
+        let entry =
+            index.get_path(Path::new("unstaged.txt"), 0).unwrap();
+        assert_eq!(
+            repo.find_blob(entry.id).unwrap().content(),
+            b"old\n"
+        );
+
+        let workdir = get_status(
+            repo_path,
+            StatusType::WorkingDir,
+            ShowUntrackedFiles::Normal,
+        )
+        .unwrap();
+        assert_eq!(workdir.len(), 2);
+        assert!(workdir.iter().all(|item| item.path != "dirty.txt"));
+    }
+
+    #[test]
+    fn test_strip_skips_submodules_and_symlinks() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path = root.as_os_str().to_str().unwrap();
+
+        // a submodule bump, the commit only exists in the submodule
+        let mut index = repo.index().unwrap();
+        index
+            .add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: GITLINK_MODE,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: Oid::from_str(
+                    "0123456789abcdef0123456789abcdef01234567",
+                )
+                .unwrap(),
+                flags: 0,
+                flags_extended: 0,
+                path: b"sub".to_vec(),
+            })
+            .unwrap();
+        index.write().unwrap();
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("target ", root.join("link"))
+                .unwrap();
+            stage_add_file(repo_path, Path::new("link")).unwrap();
+        }
+
+        assert!(strip_staged_whitespace(repo_path)
+            .unwrap()
+            .is_empty());
+        commit(repo_path, "bump").unwrap();
+
+        // the link target is kept
+        #[cfg(unix)]
+        {
+            let mut index = repo.index().unwrap();
+            index.read(true).unwrap();
+            let entry = index.get_path(Path::new("link"), 0).unwrap();
+            assert_eq!(
+                repo.find_blob(entry.id).unwrap().content(),
+                b"target "
+            );
+        }
+    }
+}
@@ -2,38 +2,67 @@ use crate::{
     accessors,
     cmdbar::CommandBar,
     components::{
-        event_pump, CommandBlocking, CommandInfo, CommitComponent,
-        Component, DrawableComponent, ExternalEditorComponent,
-        HelpComponent, InspectCommitComponent, MsgComponent,
-        ResetComponent, StashMsgComponent, TagCommitComponent,
+        event_pump, ApplyPatchComponent, ArchiveComponent,
+        CommandBlocking, CommandInfo, CommitComponent, Component,
+        ConflictComponent, ContributorsComponent,
+        CreateBranchComponent, DiffComponent, DrawableComponent,
+        ExportPatchComponent, ExternalEditorComponent, FindComponent,
+        GoToLineComponent, HelpComponent, HexViewComponent,
+        HiddenFilesComponent, InspectCommitComponent,
+        MergeBranchComponent, MsgComponent, NoteComponent,
+        PaletteComponent, PushComponent, ResetComponent,
+        SaveFileComponent, StagedPreviewComponent, StashMsgComponent,
+        StatsComponent, TagCommitComponent,
     },
+    config::Config,
     input::{Input, InputEvent, InputState},
-    keys,
+    keys::{KeyAction, KeyClash, KeyConfig, SharedKeyConfig},
     queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings::{self, commands, order},
-    tabs::{Revlog, StashList, Stashing, Status},
-    ui::style::{SharedTheme, Theme},
+    tabs::{
+        ConfigTab, Revlog, StashList, Stashing, Status, Submodules,
+    },
+    ui::{
+        self,
+        graphics::GraphicsProtocol,
+        notifications::{Notification, Notifications},
+        style::{SharedTheme, Theme},
+    },
 };
 use anyhow::{anyhow, Result};
-use asyncgit::{sync, AsyncNotification, CWD};
+use asyncgit::{
+    hash,
+    sync::{self, DetachedHead, DiffOptions},
+    AsyncNotification, CWD,
+};
 use crossbeam_channel::Sender;
-use crossterm::event::{Event, KeyEvent};
+use crossterm::event::{Event, KeyEvent, MouseButton, MouseEvent};
 use std::{
+    borrow::Cow,
     cell::{Cell, RefCell},
+    io::{self, Write},
     path::Path,
     rc::Rc,
+    time::{Duration, Instant},
 };
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Margin, Rect},
-    widgets::{Block, Borders, Tabs},
+    layout::{
+        Alignment, Constraint, Direction, Layout, Margin, Rect,
+    },
+    widgets::{Block, Borders, Clear, Paragraph, Tabs, Text},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
+
+/// how many operations can be undone
+const UNDO_LIMIT: usize = 10;
 
 ///
 pub struct App {
     do_quit: bool,
     help: HelpComponent,
+    palette: PaletteComponent,
     msg: MsgComponent,
     reset: ResetComponent,
     commit: CommitComponent,
@@ -41,47 +70,136 @@ pub struct App {
     inspect_commit_popup: InspectCommitComponent,
     external_editor_popup: ExternalEditorComponent,
     tag_commit_popup: TagCommitComponent,
+    note_popup: NoteComponent,
+    create_branch_popup: CreateBranchComponent,
+    merge_branch_popup: MergeBranchComponent,
+    conflict_popup: ConflictComponent,
+    save_file_popup: SaveFileComponent,
+    export_patch_popup: ExportPatchComponent,
+    apply_patch_popup: ApplyPatchComponent,
+    hidden_files_popup: HiddenFilesComponent,
+    goto_line_popup: GoToLineComponent,
+    find_popup: FindComponent,
+    staged_preview_popup: StagedPreviewComponent,
+    hexview_popup: HexViewComponent,
+    contributors_popup: ContributorsComponent,
+    stats_popup: StatsComponent,
+    archive_popup: ArchiveComponent,
+    push_popup: PushComponent,
     cmdbar: RefCell<CommandBar>,
     tab: usize,
+    /// status and stashing need a work dir, bare repos have none
+    disabled_tabs: Vec<usize>,
+    /// where the tab bar was drawn last, to map mouse events
+    tabs_area: Cell<Rect>,
+    /// shown next to the tabs, head is not on a branch
+    detached_head: Option<String>,
+    /// discards and stash drops of this session, latest last
+    undo_stack: Vec<sync::Undo>,
     revlog: Revlog,
     status_tab: Status,
     stashing_tab: Stashing,
     stashlist_tab: StashList,
+    submodules_tab: Submodules,
+    config_tab: ConfigTab,
+    notifications: Notifications,
     queue: Queue,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
     input: Input,
 
     // "Flags"
+    /// the focused diff takes the whole screen
+    fullscreen: bool,
+    fullscreen_hides_tabbar: bool,
     requires_redraw: Cell<bool>,
-    file_to_open: Option<String>,
+    /// how the terminal draws pictures of binary diffs
+    graphics: Option<GraphicsProtocol>,
+    /// hash of the picture on screen and its area
+    shown_image: Cell<Option<u64>>,
+    file_to_open: Option<(String, Option<usize>)>,
+    submodule_to_open: Option<String>,
 }
 
 // public interface
 impl App {
     ///
+    #[allow(clippy::too_many_lines)]
     pub fn new(
         sender: &Sender<AsyncNotification>,
         input: Input,
+        config: Config,
+        theme: Theme,
     ) -> Self {
         let queue = Queue::default();
 
-        let theme = Rc::new(Theme::init());
+        let config = Rc::new(config);
+        let theme = Rc::new(theme);
+        let key_config = Rc::new(KeyConfig::new(
+            &config.keybindings,
+            config.key_preset,
+            config.ui.mouse_scroll_lines,
+        ));
+
+        let diff_options = Rc::new(Cell::new(DiffOptions {
+            context_lines: config.diff.context_lines,
+            ..DiffOptions::default()
+        }));
+
+        let mut notifications = Notifications::new(
+            Duration::from_secs(config.ui.notification_timeout_secs),
+            theme.clone(),
+        );
 
-        Self {
+        let clashes = key_config
+            .clashes()
+            .iter()
+            .map(KeyClash::describe)
+            .collect::<Vec<_>>();
+        if !clashes.is_empty() {
+            log::warn!("conflicting key bindings: {:?}", clashes);
+            notifications.push(Notification::warning(
+                strings::KEY_CLASHES,
+                clashes.join("\n"),
+            ));
+        }
+
+        let mut app = Self {
             input,
-            reset: ResetComponent::new(queue.clone(), theme.clone()),
+            fullscreen: false,
+            graphics: GraphicsProtocol::detect(
+                config.ui.image_preview,
+            ),
+            fullscreen_hides_tabbar: config
+                .ui
+                .fullscreen_hides_tabbar,
+            notifications,
+            reset: ResetComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
             commit: CommitComponent::new(
                 queue.clone(),
+                sender,
+                &diff_options,
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
             stashmsg_popup: StashMsgComponent::new(
                 queue.clone(),
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
             inspect_commit_popup: InspectCommitComponent::new(
                 &queue,
                 sender,
+                &diff_options,
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
             external_editor_popup: ExternalEditorComponent::new(
                 theme.clone(),
@@ -89,25 +207,188 @@ impl App {
             tag_commit_popup: TagCommitComponent::new(
                 queue.clone(),
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            note_popup: NoteComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            create_branch_popup: CreateBranchComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            merge_branch_popup: MergeBranchComponent::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+                &config,
+            ),
+            conflict_popup: ConflictComponent::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            save_file_popup: SaveFileComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            export_patch_popup: ExportPatchComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            apply_patch_popup: ApplyPatchComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            hidden_files_popup: HiddenFilesComponent::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            goto_line_popup: GoToLineComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            find_popup: FindComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            staged_preview_popup: StagedPreviewComponent::new(
+                &queue,
+                &diff_options,
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            hexview_popup: HexViewComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            contributors_popup: ContributorsComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            stats_popup: StatsComponent::new(
+                sender,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            archive_popup: ArchiveComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            push_popup: PushComponent::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
             do_quit: false,
-            cmdbar: RefCell::new(CommandBar::new(theme.clone())),
-            help: HelpComponent::new(theme.clone()),
-            msg: MsgComponent::new(theme.clone()),
+            cmdbar: RefCell::new(CommandBar::new(
+                theme.clone(),
+                key_config.clone(),
+            )),
+            help: HelpComponent::new(
+                theme.clone(),
+                key_config.clone(),
+            ),
+            palette: PaletteComponent::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            msg: MsgComponent::new(theme.clone(), key_config.clone()),
             tab: 0,
-            revlog: Revlog::new(&queue, sender, theme.clone()),
-            status_tab: Status::new(&queue, sender, theme.clone()),
+            disabled_tabs: if sync::is_bare_repo(CWD)
+                .unwrap_or_default()
+            {
+                vec![0, 2]
+            } else {
+                Vec::new()
+            },
+            tabs_area: Cell::new(Rect::default()),
+            detached_head: None,
+            undo_stack: Vec::new(),
+            revlog: Revlog::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                &config,
+            ),
+            status_tab: Status::new(
+                &queue,
+                sender,
+                &diff_options,
+                theme.clone(),
+                key_config.clone(),
+                &config,
+            ),
             stashing_tab: Stashing::new(
                 sender,
                 &queue,
                 theme.clone(),
+                key_config.clone(),
+                &config,
+            ),
+            stashlist_tab: StashList::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            submodules_tab: Submodules::new(
+                &queue,
+                sender,
+                theme.clone(),
+                key_config.clone(),
+            ),
+            config_tab: ConfigTab::new(
+                &queue,
+                theme.clone(),
+                key_config.clone(),
+                config,
             ),
-            stashlist_tab: StashList::new(&queue, theme.clone()),
             queue,
             theme,
+            key_config,
             requires_redraw: Cell::new(false),
+            shown_image: Cell::new(None),
             file_to_open: None,
+            submodule_to_open: None,
+        };
+
+        if !app.tab_available(app.tab) {
+            if let Err(e) = app.set_tab(1) {
+                log::error!("failed to open the log: {}", e);
+            }
         }
+
+        app
     }
 
     ///
@@ -116,32 +397,56 @@ impl App {
 
         self.cmdbar.borrow_mut().refresh_width(fsize.width);
 
-        let chunks_main = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Length(2),
-                    Constraint::Min(2),
-                    Constraint::Length(self.cmdbar.borrow().height()),
-                ]
-                .as_ref(),
-            )
-            .split(fsize);
-
-        self.cmdbar.borrow().draw(f, chunks_main[2]);
+        let fullscreen = if self.fullscreen {
+            self.fullscreen_diff()
+        } else {
+            None
+        };
 
-        self.draw_tabs(f, chunks_main[0]);
+        let main =
+            if fullscreen.is_some() && self.fullscreen_hides_tabbar {
+                fsize
+            } else {
+                let chunks_main = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Length(2),
+                            Constraint::Min(2),
+                            Constraint::Length(
+                                self.cmdbar.borrow().height(),
+                            ),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(fsize);
+
+                self.cmdbar.borrow().draw(f, chunks_main[2]);
+
+                self.draw_tabs(f, chunks_main[0]);
+
+                chunks_main[1]
+            };
+
+        if let Some(diff) = fullscreen {
+            f.render_widget(Clear, main);
+            diff.draw(f, main)?;
+        } else {
+            //TODO: macro because of generic draw call
+            match self.tab {
+                0 => self.status_tab.draw(f, main)?,
+                1 => self.revlog.draw(f, main)?,
+                2 => self.stashing_tab.draw(f, main)?,
+                3 => self.stashlist_tab.draw(f, main)?,
+                4 => self.submodules_tab.draw(f, main)?,
+                5 => self.config_tab.draw(f, main)?,
+                _ => return Err(anyhow!("unknown tab")),
+            };
+        }
 
-        //TODO: macro because of generic draw call
-        match self.tab {
-            0 => self.status_tab.draw(f, chunks_main[1])?,
-            1 => self.revlog.draw(f, chunks_main[1])?,
-            2 => self.stashing_tab.draw(f, chunks_main[1])?,
-            3 => self.stashlist_tab.draw(f, chunks_main[1])?,
-            _ => return Err(anyhow!("unknown tab")),
-        };
+        self.draw_popups(f, fullscreen.is_some())?;
 
-        self.draw_popups(f)?;
+        self.notifications.draw(f, main);
 
         Ok(())
     }
@@ -155,65 +460,84 @@ impl App {
                 return Ok(());
             }
 
+            if self.check_fullscreen_key(ev) {
+                self.update_commands();
+                return Ok(());
+            }
+
             let mut flags = NeedsUpdate::empty();
 
             if event_pump(ev, self.components_mut().as_mut_slice())? {
                 flags.insert(NeedsUpdate::COMMANDS);
             } else if let Event::Key(k) = ev {
-                let new_flags = match k {
-                    keys::TAB_TOGGLE => {
-                        self.toggle_tabs(false)?;
-                        NeedsUpdate::COMMANDS
-                    }
-                    keys::TAB_TOGGLE_REVERSE
-                    | keys::TAB_TOGGLE_REVERSE_WINDOWS => {
-                        self.toggle_tabs(true)?;
-                        NeedsUpdate::COMMANDS
-                    }
-                    keys::TAB_1
-                    | keys::TAB_2
-                    | keys::TAB_3
-                    | keys::TAB_4 => {
-                        self.switch_tab(k)?;
-                        NeedsUpdate::COMMANDS
-                    }
-
-                    keys::CMD_BAR_TOGGLE => {
-                        self.cmdbar.borrow_mut().toggle_more();
-                        NeedsUpdate::empty()
-                    }
-
-                    _ => NeedsUpdate::empty(),
+                let keys = Rc::clone(&self.key_config);
+                let new_flags = if keys
+                    .matches(&k, KeyAction::TabToggle)
+                {
+                    self.toggle_tabs(false)?;
+                    NeedsUpdate::COMMANDS
+                } else if keys
+                    .matches(&k, KeyAction::TabToggleReverse)
+                {
+                    self.toggle_tabs(true)?;
+                    NeedsUpdate::COMMANDS
+                } else if let Some(tab) = self.tab_of_key(&k) {
+                    self.set_tab(tab)?;
+                    NeedsUpdate::COMMANDS
+                } else if keys
+                    .matches(&k, KeyAction::NotificationDetails)
+                {
+                    self.show_notification_details()?;
+                    NeedsUpdate::COMMANDS
+                } else if keys.matches(&k, KeyAction::OpenStats) {
+                    self.stats_popup.open()?;
+                    NeedsUpdate::COMMANDS
+                } else if keys.matches(&k, KeyAction::OpenPush) {
+                    self.push_popup.open()?;
+                    NeedsUpdate::COMMANDS
+                } else if keys.matches(&k, KeyAction::Undo) {
+                    self.request_undo();
+                    NeedsUpdate::COMMANDS
+                } else if keys.matches(&k, KeyAction::CmdBarToggle) {
+                    self.cmdbar.borrow_mut().toggle_more();
+                    NeedsUpdate::empty()
+                } else {
+                    NeedsUpdate::empty()
                 };
 
                 flags.insert(new_flags);
+            } else if let Event::Mouse(m) = ev {
+                flags.insert(self.tabs_mouse_event(m)?);
             }
 
             let new_flags = self.process_queue()?;
             flags.insert(new_flags);
 
-            if flags.contains(NeedsUpdate::ALL) {
-                self.update()?;
-            }
-            //TODO: make this a queue event?
-            //NOTE: set when any tree component changed selection
-            if flags.contains(NeedsUpdate::DIFF) {
-                self.status_tab.update_diff()?;
-                self.inspect_commit_popup.update_diff()?;
-            }
-            if flags.contains(NeedsUpdate::COMMANDS) {
-                self.update_commands();
+            // leaving the maximized diff restores the layout
+            if self.fullscreen && self.fullscreen_diff().is_none() {
+                self.fullscreen = false;
             }
+
+            self.apply_flags(flags)?;
         } else if let InputEvent::State(polling_state) = ev {
             self.external_editor_popup.hide();
             if let InputState::Paused = polling_state {
-                let result = match self.file_to_open.take() {
-                    Some(path) => {
-                        ExternalEditorComponent::open_file_in_editor(
-                            Path::new(&path),
-                        )
+                let result = if let Some(path) =
+                    self.submodule_to_open.take()
+                {
+                    Submodules::open_nested(&path)
+                } else {
+                    match self.file_to_open.take() {
+                        Some((path, line)) => {
+                            ExternalEditorComponent::edit_file(
+                                Path::new(&path),
+                                line,
+                            )
+                            // show the edits right away
+                            .and_then(|()| self.update())
+                        }
+                        None => self.commit.show_editor(),
                     }
-                    None => self.commit.show_editor(),
                 };
 
                 if let Err(e) = result {
@@ -231,6 +555,21 @@ impl App {
         Ok(())
     }
 
+    /// dismisses timed out notifications, returns whether a redraw
+    /// is needed
+    pub fn update_notifications(&mut self) -> bool {
+        if self.notifications.is_empty() {
+            return false;
+        }
+
+        let expired = self.notifications.expire(Instant::now());
+        if expired {
+            self.update_commands();
+        }
+
+        expired
+    }
+
     //TODO: do we need this?
     /// forward ticking to components that require it
     pub fn update(&mut self) -> Result<()> {
@@ -240,12 +579,40 @@ impl App {
         self.revlog.update()?;
         self.stashing_tab.update()?;
         self.stashlist_tab.update()?;
+        self.submodules_tab.update()?;
+        self.config_tab.update()?;
+        self.commit.update()?;
+        self.staged_preview_popup.update()?;
+
+        self.detached_head = sync::get_detached_head(CWD)
+            .ok()
+            .flatten()
+            .map(|head| Self::format_detached_head(&head));
 
         self.update_commands();
 
         Ok(())
     }
 
+    /// spell checks the commit message once typing paused, true if
+    /// that needs a redraw
+    pub fn update_spell_check(&mut self) -> bool {
+        self.commit.update_spell_check()
+    }
+
+    /// works off the next step of marked files being staged,
+    /// unstaged or reset, true if that needs a redraw
+    pub fn update_batch(&mut self) -> Result<bool> {
+        if !self.status_tab.step_batch() {
+            return Ok(false);
+        }
+
+        let flags = self.process_queue()?;
+        self.apply_flags(flags)?;
+
+        Ok(true)
+    }
+
     ///
     pub fn update_git(
         &mut self,
@@ -257,6 +624,13 @@ impl App {
         self.stashing_tab.update_git(ev)?;
         self.revlog.update_git(ev)?;
         self.inspect_commit_popup.update_git(ev)?;
+        self.commit.update_git(ev)?;
+        self.contributors_popup.update_git(ev)?;
+        self.stats_popup.update_git(ev)?;
+        self.archive_popup.update_git(ev)?;
+        self.submodules_tab.update_git(ev)?;
+        self.push_popup.update_git(ev)?;
+        self.hexview_popup.update_git(ev)?;
 
         //TODO: better system for this
         // can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
@@ -276,18 +650,59 @@ impl App {
             || self.revlog.any_work_pending()
             || self.stashing_tab.anything_pending()
             || self.inspect_commit_popup.any_work_pending()
+            || self.commit.any_work_pending()
+            || self.contributors_popup.any_work_pending()
+            || self.stats_popup.any_work_pending()
+            || self.archive_popup.any_work_pending()
+            || self.submodules_tab.any_work_pending()
+            || self.push_popup.any_work_pending()
+            || self.hexview_popup.any_work_pending()
             || self.input.is_state_changing()
     }
 
     ///
     pub fn requires_redraw(&self) -> bool {
-        if self.requires_redraw.get() {
+        if self.requires_redraw.get() || self.image_stale() {
             self.requires_redraw.set(false);
+            self.shown_image.set(None);
             true
         } else {
             false
         }
     }
+
+    /// puts the picture of the binary diff on screen, after the
+    /// frame is drawn since the terminal draws it on top
+    pub fn draw_image<W: Write>(
+        &self,
+        out: &mut W,
+    ) -> io::Result<()> {
+        let protocol = if let Some(protocol) = self.graphics {
+            protocol
+        } else {
+            return Ok(());
+        };
+
+        let image = self.shown_image_data();
+        let key = image.map(|image| hash(&image));
+        if key == self.shown_image.get() {
+            return Ok(());
+        }
+
+        if self.shown_image.get().is_some() {
+            if let Some(clear) = protocol.clear() {
+                out.write_all(clear.as_bytes())?;
+            }
+        }
+        if let Some((area, data)) = image {
+            out.write_all(protocol.draw(data, area).as_bytes())?;
+        }
+        out.flush()?;
+
+        self.shown_image.set(key);
+
+        Ok(())
+    }
 }
 
 // private impls
@@ -299,20 +714,39 @@ impl App {
             reset,
             commit,
             stashmsg_popup,
+            save_file_popup,
+            export_patch_popup,
+            apply_patch_popup,
+            hidden_files_popup,
+            goto_line_popup,
+            find_popup,
+            staged_preview_popup,
+            hexview_popup,
+            contributors_popup,
+            stats_popup,
+            archive_popup,
+            push_popup,
             inspect_commit_popup,
             external_editor_popup,
             tag_commit_popup,
+            note_popup,
+            create_branch_popup,
+            merge_branch_popup,
+            conflict_popup,
+            palette,
             help,
             revlog,
             status_tab,
             stashing_tab,
-            stashlist_tab
+            stashlist_tab,
+            submodules_tab,
+            config_tab
         ]
     );
 
     fn check_quit_key(&mut self, ev: Event) -> bool {
         if let Event::Key(e) = ev {
-            if let keys::EXIT = e {
+            if self.key_config.matches(&e, KeyAction::Exit) {
                 self.do_quit = true;
                 return true;
             }
@@ -320,39 +754,186 @@ impl App {
         false
     }
 
+    /// toggles `fullscreen` before any component sees the key, the
+    /// commit inspection would swallow it otherwise
+    fn check_fullscreen_key(&mut self, ev: Event) -> bool {
+        if let Event::Key(e) = ev {
+            if self
+                .key_config
+                .matches(&e, KeyAction::ToggleFullscreen)
+                && self.fullscreen_diff().is_some()
+                && !self.popup_above_diff()
+            {
+                self.fullscreen = !self.fullscreen;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// the diff that can take the whole screen: the focused one of
+    /// the commit inspection or the status tab
+    fn fullscreen_diff(&self) -> Option<&DiffComponent> {
+        if self.inspect_commit_popup.is_visible() {
+            self.inspect_commit_popup.focused_diff()
+        } else if self.tab == 0
+            && (!self.any_popup_visible() || self.popup_above_diff())
+        {
+            self.status_tab.focused_diff()
+        } else {
+            None
+        }
+    }
+
+    /// the picture of the visible diff and where it goes
+    fn shown_image_data(&self) -> Option<(Rect, &[u8])> {
+        if self.inspect_commit_popup.is_visible() {
+            if self.popup_above_diff() {
+                None
+            } else {
+                self.inspect_commit_popup.diff().image()
+            }
+        } else if self.tab == 0 && !self.any_popup_visible() {
+            self.status_tab.diff().image()
+        } else {
+            None
+        }
+    }
+
+    /// whether a picture has to go that only drawing the whole
+    /// screen anew removes
+    fn image_stale(&self) -> bool {
+        let shown = self.shown_image.get();
+
+        self.graphics
+            .map_or(false, |protocol| protocol.clear().is_none())
+            && shown.is_some()
+            && shown
+                != self.shown_image_data().map(|image| hash(&image))
+    }
+
+    /// popups that can be opened on top of a diff without taking
+    /// away its focus
+    fn popup_above_diff(&self) -> bool {
+        self.help.is_visible()
+            || self.msg.is_visible()
+            || self.save_file_popup.is_visible()
+            || self.hexview_popup.is_visible()
+            || self.goto_line_popup.is_visible()
+            || self.find_popup.is_visible()
+    }
+
+    /// the diff a jump goes to, like `fullscreen_diff`
+    fn focused_diff_mut(&mut self) -> Option<&mut DiffComponent> {
+        if self.inspect_commit_popup.is_visible() {
+            self.inspect_commit_popup.focused_diff_mut()
+        } else if self.tab == 0 {
+            self.status_tab.focused_diff_mut()
+        } else {
+            None
+        }
+    }
+
     fn get_tabs(&mut self) -> Vec<&mut dyn Component> {
         vec![
             &mut self.status_tab,
             &mut self.revlog,
             &mut self.stashing_tab,
             &mut self.stashlist_tab,
+            &mut self.submodules_tab,
+            &mut self.config_tab,
         ]
     }
 
     fn toggle_tabs(&mut self, reverse: bool) -> Result<()> {
         let tabs_len = self.get_tabs().len();
-        let new_tab = if reverse {
-            self.tab.wrapping_sub(1).min(tabs_len.saturating_sub(1))
-        } else {
-            self.tab.saturating_add(1) % tabs_len
-        };
+        let mut new_tab = self.tab;
+        // the log is always available
+        loop {
+            new_tab = if reverse {
+                new_tab
+                    .wrapping_sub(1)
+                    .min(tabs_len.saturating_sub(1))
+            } else {
+                new_tab.saturating_add(1) % tabs_len
+            };
+
+            if self.tab_available(new_tab) {
+                break;
+            }
+        }
 
         self.set_tab(new_tab)
     }
+    fn tab_available(&self, tab: usize) -> bool {
+        !self.disabled_tabs.contains(&tab)
+    }
+
+    fn tab_of_key(&self, k: &KeyEvent) -> Option<usize> {
+        [
+            KeyAction::Tab1,
+            KeyAction::Tab2,
+            KeyAction::Tab3,
+            KeyAction::Tab4,
+            KeyAction::Tab5,
+            KeyAction::Tab6,
+        ]
+        .iter()
+        .position(|action| self.key_config.matches(k, *action))
+    }
 
-    fn switch_tab(&mut self, k: KeyEvent) -> Result<()> {
-        match k {
-            keys::TAB_1 => self.set_tab(0)?,
-            keys::TAB_2 => self.set_tab(1)?,
-            keys::TAB_3 => self.set_tab(2)?,
-            keys::TAB_4 => self.set_tab(3)?,
+    /// click on a tab title selects it, scrolling over the tab bar
+    /// cycles through the tabs
+    fn tabs_mouse_event(
+        &mut self,
+        ev: MouseEvent,
+    ) -> Result<NeedsUpdate> {
+        if self.any_popup_visible() {
+            return Ok(NeedsUpdate::empty());
+        }
+
+        let area = self.tabs_area.get();
+        let on_tabs =
+            |column, row| ui::rect_contains(area, column, row);
+
+        match ev {
+            MouseEvent::Down(MouseButton::Left, column, row, _)
+                if on_tabs(column, row) =>
+            {
+                if let Some(tab) =
+                    tab_at(&Self::tab_titles(), area, column)
+                {
+                    self.set_tab(tab)?;
+                    return Ok(NeedsUpdate::COMMANDS);
+                }
+            }
+            MouseEvent::ScrollDown(column, row, _)
+                if on_tabs(column, row) =>
+            {
+                self.toggle_tabs(false)?;
+                return Ok(NeedsUpdate::COMMANDS);
+            }
+            MouseEvent::ScrollUp(column, row, _)
+                if on_tabs(column, row) =>
+            {
+                self.toggle_tabs(true)?;
+                return Ok(NeedsUpdate::COMMANDS);
+            }
             _ => (),
         }
 
-        Ok(())
+        Ok(NeedsUpdate::empty())
     }
 
     fn set_tab(&mut self, tab: usize) -> Result<()> {
+        if !self.tab_available(tab) {
+            self.notifications.push(Notification::warning(
+                strings::TAB_BARE_REPO,
+                strings::TAB_BARE_REPO_DETAILS,
+            ));
+            return Ok(());
+        }
+
         let tabs = self.get_tabs();
         for (i, t) in tabs.into_iter().enumerate() {
             if tab == i {
@@ -369,7 +950,10 @@ impl App {
 
     fn update_commands(&mut self) {
         self.help.set_cmds(self.commands(true));
-        self.cmdbar.borrow_mut().set_cmds(self.commands(false));
+
+        let cmds = self.commands(false);
+        self.palette.set_cmds(&cmds);
+        self.cmdbar.borrow_mut().set_cmds(cmds);
     }
 
     fn process_queue(&mut self) -> Result<NeedsUpdate> {
@@ -388,28 +972,36 @@ impl App {
         Ok(flags)
     }
 
+    fn apply_flags(&mut self, flags: NeedsUpdate) -> Result<()> {
+        if flags.contains(NeedsUpdate::ALL) {
+            self.update()?;
+        }
+        //TODO: make this a queue event?
+        //NOTE: set when any tree component changed selection
+        if flags.contains(NeedsUpdate::DIFF) {
+            self.status_tab.update_diff()?;
+            self.inspect_commit_popup.update_diff()?;
+        }
+        if flags.contains(NeedsUpdate::TAGS) {
+            self.revlog.refresh_tags()?;
+        }
+        if flags.contains(NeedsUpdate::COMMANDS) {
+            self.update_commands();
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_lines)]
     fn process_internal_event(
         &mut self,
         ev: InternalEvent,
     ) -> Result<NeedsUpdate> {
         let mut flags = NeedsUpdate::empty();
         match ev {
-            InternalEvent::ConfirmedAction(action) => match action {
-                Action::Reset(r) => {
-                    if self.status_tab.reset(&r) {
-                        flags.insert(NeedsUpdate::ALL);
-                    }
-                }
-                Action::StashDrop(s) => {
-                    if StashList::drop(s) {
-                        flags.insert(NeedsUpdate::ALL);
-                    }
-                }
-                Action::ResetHunk(path, hash) => {
-                    sync::reset_hunk(CWD, path, hash)?;
-                    flags.insert(NeedsUpdate::ALL);
-                }
-            },
+            InternalEvent::ConfirmedAction(action) => {
+                flags.insert(self.process_confirmed_action(action)?);
+            }
             InternalEvent::ConfirmAction(action) => {
                 self.reset.open(action)?;
                 flags.insert(NeedsUpdate::COMMANDS);
@@ -419,6 +1011,10 @@ impl App {
                 flags
                     .insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
             }
+            InternalEvent::Notify(notification) => {
+                self.notifications.push(notification);
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
             InternalEvent::Update(u) => flags.insert(u),
             InternalEvent::OpenCommit => self.commit.show()?,
             InternalEvent::PopupStashing(opts) => {
@@ -428,6 +1024,86 @@ impl App {
             InternalEvent::TagCommit(id) => {
                 self.tag_commit_popup.open(id)?;
             }
+            InternalEvent::OpenNote(id) => {
+                self.note_popup.open(id)?;
+            }
+            InternalEvent::PushTag(tag) => {
+                self.push_popup.open_tag(tag)?;
+            }
+            InternalEvent::CreateBranch(id) => {
+                self.create_branch_popup.open(id)?;
+            }
+            InternalEvent::StashBranch(id) => {
+                self.create_branch_popup.open_stash(id)?;
+            }
+            InternalEvent::OpenContributors => {
+                self.contributors_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenConflict(path) => {
+                self.conflict_popup.open(path);
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenMergeBranch => {
+                self.merge_branch_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::FilterLogByAuthor(email) => {
+                self.revlog.set_author_filter(Some(email))?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::SaveFile(id, path) => {
+                self.save_file_popup.open(id, path)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::ExportPatches(commits) => {
+                self.export_patch_popup.open(commits)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenArchive(treeish) => {
+                self.archive_popup.open(treeish)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::ApplyPatches => {
+                self.apply_patch_popup.show()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenHiddenFiles => {
+                self.hidden_files_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::PreviewStaged => {
+                self.staged_preview_popup.show()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenHexView(id, path) => {
+                self.hexview_popup.open(id, path)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenGoToLine => {
+                self.goto_line_popup.show()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::GoToLine(target) => {
+                if self.hexview_popup.is_visible() {
+                    self.hexview_popup.go_to(target);
+                } else if let Some(diff) = self.focused_diff_mut() {
+                    diff.go_to(target)?;
+                }
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenFind => {
+                self.find_popup.show()?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::Find(search) => {
+                if self.hexview_popup.is_visible() {
+                    self.hexview_popup.set_search(search);
+                } else if let Some(diff) = self.focused_diff_mut() {
+                    diff.set_search(search)?;
+                }
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
             InternalEvent::TabSwitch => self.set_tab(0)?,
             InternalEvent::InspectCommit(id, tags) => {
                 self.inspect_commit_popup.open(id, tags)?;
@@ -439,6 +1115,14 @@ impl App {
                 self.file_to_open = path;
                 flags.insert(NeedsUpdate::COMMANDS)
             }
+            InternalEvent::OpenSubmodule(path) => {
+                self.input.set_polling(false);
+                self.submodule_to_open = Some(path);
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::ExecuteAction(action) => {
+                self.execute_action(action)?;
+            }
         };
 
         Ok(flags)
@@ -473,6 +1157,46 @@ impl App {
             .order(order::NAV),
         );
 
+        res.push(CommandInfo::new(
+            commands::TOGGLE_FULLSCREEN,
+            true,
+            self.fullscreen_diff().is_some(),
+        ));
+
+        res.push(
+            CommandInfo::new(
+                commands::OPEN_PUSH,
+                true,
+                !self.any_popup_visible(),
+            )
+            .hidden(),
+        );
+
+        res.push(
+            CommandInfo::new(
+                commands::UNDO,
+                !self.undo_stack.is_empty(),
+                !self.any_popup_visible(),
+            )
+            .hidden(),
+        );
+
+        res.push(
+            CommandInfo::new(
+                commands::OPEN_STATS,
+                true,
+                !self.any_popup_visible(),
+            )
+            .hidden(),
+        );
+
+        res.push(CommandInfo::new(
+            commands::NOTIFICATION_DETAILS,
+            true,
+            self.notifications.has_details()
+                && !self.any_popup_visible(),
+        ));
+
         res.push(
             CommandInfo::new(
                 commands::QUIT,
@@ -485,20 +1209,227 @@ impl App {
         res
     }
 
+    fn show_notification_details(&mut self) -> Result<()> {
+        if let Some(notification) = self.notifications.take_details()
+        {
+            self.msg.show_msg(&format!(
+                "{}\n{}",
+                notification.msg,
+                notification.details.unwrap_or_default()
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    fn process_confirmed_action(
+        &mut self,
+        action: Action,
+    ) -> Result<NeedsUpdate> {
+        let mut flags = NeedsUpdate::empty();
+        match action {
+            Action::Reset(r) => {
+                if self.remember_undo(sync::snapshot_discard(
+                    CWD,
+                    &[r.path.as_str()],
+                ))? && self.status_tab.reset(&r)
+                {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::ResetItems(items) => {
+                let paths = items
+                    .iter()
+                    .map(|item| item.path.as_str())
+                    .collect::<Vec<_>>();
+                if self.remember_undo(sync::snapshot_discard(
+                    CWD, &paths,
+                ))? {
+                    self.status_tab.reset_marked(items);
+                }
+            }
+            Action::StashDrop(s) => {
+                if self.remember_undo(sync::snapshot_stash(CWD, s))?
+                    && StashList::drop(s)
+                {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::ResetHunk(path, hash, options) => {
+                if self.remember_undo(sync::snapshot_discard(
+                    CWD,
+                    &[path.as_str()],
+                ))? {
+                    sync::reset_hunk(CWD, path, hash, options)?;
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::OverwriteFile(item) => {
+                SaveFileComponent::save(&self.queue, &item);
+            }
+            Action::ForcePush(request) => {
+                self.push_popup.push(request)?;
+            }
+            Action::CherryPick(commits) => {
+                let notification =
+                    match sync::cherry_pick(CWD, &commits) {
+                        Ok(()) => Notification::success(format!(
+                            "{} {}",
+                            strings::NOTIFY_CHERRY_PICKED,
+                            commits.len()
+                        )),
+                        Err(e) => Notification::error(
+                            strings::CHERRY_PICK_FAILED,
+                            e.to_string(),
+                        ),
+                    };
+                self.notifications.push(notification);
+                // a stop on conflicts shows up in the status tab
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::DeleteNote(id) => {
+                NoteComponent::delete(&self.queue, id);
+            }
+            Action::BisectStart(id, mark) => {
+                self.revlog.bisect_mark(id, mark)?;
+            }
+            Action::Undo(_) => {
+                if let Some(undo) = self.undo_stack.pop() {
+                    let notification = match sync::undo(CWD, &undo) {
+                        Ok(()) => Notification::success(format!(
+                            "{}: {}",
+                            strings::UNDO_DONE,
+                            Self::describe_undo(&undo)
+                        )),
+                        Err(e) => Notification::error(
+                            strings::UNDO_FAILED,
+                            e.to_string(),
+                        ),
+                    };
+                    self.notifications.push(notification);
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+        }
+
+        Ok(flags)
+    }
+
+    /// keeps what it takes to revert an operation, `false` (and the
+    /// operation should not run) if that failed
+    fn remember_undo<E: std::fmt::Display>(
+        &mut self,
+        undo: std::result::Result<sync::Undo, E>,
+    ) -> Result<bool> {
+        match undo {
+            Ok(undo) => {
+                self.undo_stack.push(undo);
+                if self.undo_stack.len() > UNDO_LIMIT {
+                    self.undo_stack.remove(0);
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                self.msg.show_msg(&format!(
+                    "{}\n{}",
+                    strings::UNDO_SNAPSHOT_FAILED,
+                    e
+                ))?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// asks to revert the latest operation
+    fn request_undo(&self) {
+        let event = self.undo_stack.last().map_or_else(
+            || {
+                InternalEvent::Notify(Notification::success(
+                    strings::UNDO_NOTHING,
+                ))
+            },
+            |undo| {
+                InternalEvent::ConfirmAction(Action::Undo(format!(
+                    "{}?",
+                    Self::describe_undo(undo)
+                )))
+            },
+        );
+
+        self.queue.borrow_mut().push_back(event);
+    }
+
+    fn describe_undo(undo: &sync::Undo) -> String {
+        match undo {
+            sync::Undo::Discard { paths, .. } => match paths
+                .as_slice()
+            {
+                [path] => {
+                    format!("{} '{}'", strings::UNDO_DISCARD, path)
+                }
+                paths => format!(
+                    "{} {} {}",
+                    strings::UNDO_DISCARD,
+                    paths.len(),
+                    strings::UNDO_FILES
+                ),
+            },
+            sync::Undo::StashDrop { message, .. } => {
+                format!(
+                    "{} '{}'",
+                    strings::UNDO_STASH_DROP,
+                    message.trim()
+                )
+            }
+        }
+    }
+
+    /// replays the first key bound to `action`, as the palette
+    /// only offers what the current view handles
+    fn execute_action(&mut self, action: KeyAction) -> Result<()> {
+        if let Some(key) =
+            self.key_config.keys(action).first().copied()
+        {
+            self.event(InputEvent::Input(Event::Key(key)))?;
+        }
+
+        Ok(())
+    }
+
     fn any_popup_visible(&self) -> bool {
         self.commit.is_visible()
             || self.help.is_visible()
+            || self.palette.is_visible()
             || self.reset.is_visible()
             || self.msg.is_visible()
             || self.stashmsg_popup.is_visible()
             || self.inspect_commit_popup.is_visible()
             || self.external_editor_popup.is_visible()
             || self.tag_commit_popup.is_visible()
+            || self.note_popup.is_visible()
+            || self.create_branch_popup.is_visible()
+            || self.merge_branch_popup.is_visible()
+            || self.conflict_popup.is_visible()
+            || self.save_file_popup.is_visible()
+            || self.export_patch_popup.is_visible()
+            || self.apply_patch_popup.is_visible()
+            || self.hidden_files_popup.is_visible()
+            || self.goto_line_popup.is_visible()
+            || self.find_popup.is_visible()
+            || self.staged_preview_popup.is_visible()
+            || self.contributors_popup.is_visible()
+            || self.stats_popup.is_visible()
+            || self.archive_popup.is_visible()
+            || self.push_popup.is_visible()
+            || self.hexview_popup.is_visible()
     }
 
+    /// `fullscreen` skips the commit inspection, its diff was drawn
+    /// on the whole screen already
     fn draw_popups<B: Backend>(
         &self,
         f: &mut Frame<B>,
+        fullscreen: bool,
     ) -> Result<()> {
         let size = Layout::default()
             .direction(Direction::Vertical)
@@ -513,16 +1444,47 @@ impl App {
 
         self.commit.draw(f, size)?;
         self.stashmsg_popup.draw(f, size)?;
+        // popups opened from inspecting a commit have to be on top
+        if !fullscreen {
+            self.inspect_commit_popup.draw(f, size)?;
+        }
+        self.staged_preview_popup.draw(f, size)?;
+        self.hexview_popup.draw(f, size)?;
+        self.contributors_popup.draw(f, size)?;
+        self.stats_popup.draw(f, size)?;
+        self.archive_popup.draw(f, size)?;
+        self.push_popup.draw(f, size)?;
         self.reset.draw(f, size)?;
         self.help.draw(f, size)?;
+        self.palette.draw(f, size)?;
         self.msg.draw(f, size)?;
-        self.inspect_commit_popup.draw(f, size)?;
         self.external_editor_popup.draw(f, size)?;
         self.tag_commit_popup.draw(f, size)?;
+        self.note_popup.draw(f, size)?;
+        self.create_branch_popup.draw(f, size)?;
+        self.merge_branch_popup.draw(f, size)?;
+        self.conflict_popup.draw(f, size)?;
+        self.save_file_popup.draw(f, size)?;
+        self.export_patch_popup.draw(f, size)?;
+        self.apply_patch_popup.draw(f, size)?;
+        self.hidden_files_popup.draw(f, size)?;
+        self.goto_line_popup.draw(f, size)?;
+        self.find_popup.draw(f, size)?;
 
         Ok(())
     }
 
+    fn tab_titles() -> [&'static str; 6] {
+        [
+            strings::TAB_STATUS,
+            strings::TAB_LOG,
+            strings::TAB_STASHING,
+            strings::TAB_STASHES,
+            strings::TAB_SUBMODULES,
+            strings::TAB_CONFIG,
+        ]
+    }
+
     //TODO: make this dynamic
     fn draw_tabs<B: Backend>(&self, f: &mut Frame<B>, r: Rect) {
         let r = r.inner(&Margin {
@@ -530,12 +1492,7 @@ impl App {
             horizontal: 1,
         });
 
-        let tabs = &[
-            strings::TAB_STATUS,
-            strings::TAB_LOG,
-            strings::TAB_STASHING,
-            strings::TAB_STASHES,
-        ];
+        self.tabs_area.set(r);
 
         f.render_widget(
             Tabs::default()
@@ -544,12 +1501,101 @@ impl App {
                         .borders(Borders::BOTTOM)
                         .border_style(self.theme.block(false)),
                 )
-                .titles(tabs)
+                .titles(&Self::tab_titles())
                 .style(self.theme.tab(false))
                 .highlight_style(self.theme.tab(true))
                 .divider(strings::TAB_DIVIDER)
                 .select(self.tab),
             r,
         );
+
+        let mut txt = Vec::new();
+
+        if let Some(detached) = &self.detached_head {
+            txt.push(Text::Styled(
+                Cow::from(detached.as_str()),
+                self.theme.text_danger(),
+            ));
+        }
+
+        if self.tab == 0 {
+            // a detached head has no upstream to begin with
+            if let Some(summary) = self
+                .status_tab
+                .upstream_summary()
+                .filter(|_| self.detached_head.is_none())
+            {
+                if !txt.is_empty() {
+                    txt.push(Text::Raw(Cow::from(" | ")));
+                }
+                txt.push(Text::Raw(Cow::from(summary)));
+            }
+        }
+
+        if !txt.is_empty() {
+            f.render_widget(
+                Paragraph::new(txt.iter())
+                    .style(self.theme.text(true, false))
+                    .alignment(Alignment::Right),
+                Rect { height: 1, ..r },
+            );
+        }
+    }
+
+    /// like `detached HEAD at 1a2b3c4 (v1.0)`
+    fn format_detached_head(head: &DetachedHead) -> String {
+        let id =
+            head.id.to_string().chars().take(7).collect::<String>();
+
+        head.tag.as_ref().map_or_else(
+            || format!("{} {}", strings::DETACHED_HEAD, id),
+            |tag| {
+                format!("{} {} ({})", strings::DETACHED_HEAD, id, tag)
+            },
+        )
+    }
+}
+
+/// index of the tab whose title (including the padding around it)
+/// is at `column`, follows the layout of `tui::widgets::Tabs`
+fn tab_at(titles: &[&str], area: Rect, column: u16) -> Option<usize> {
+    let (column, right) =
+        (usize::from(column), usize::from(area.right()));
+    let mut x = usize::from(area.left());
+
+    for (idx, title) in titles.iter().enumerate() {
+        let start = x;
+        x += title.width() + 2;
+
+        if start >= right {
+            break;
+        }
+
+        if column >= start && column < x.min(right) {
+            return Some(idx);
+        }
+
+        x += strings::TAB_DIVIDER.width();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_at() {
+        let titles = ["Status [1]", "Log [2]"];
+        let area = Rect::new(1, 0, 40, 2);
+        assert_eq!(tab_at(&titles, area, 0), None);
+        assert_eq!(tab_at(&titles, area, 1), Some(0));
+        assert_eq!(tab_at(&titles, area, 12), Some(0));
+        assert_eq!(tab_at(&titles, area, 13), None);
+        assert_eq!(tab_at(&titles, area, 16), Some(1));
+        assert_eq!(tab_at(&titles, area, 24), Some(1));
+        assert_eq!(tab_at(&titles, area, 25), None);
+        assert_eq!(tab_at(&titles, Rect::new(1, 0, 5, 2), 12), None);
     }
 }
@@ -0,0 +1,424 @@
+use crate::{
+    config::ClipboardConfig, strings, ui::notifications::Notification,
+};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    ffi::OsStr,
+    io::{self, ErrorKind, Write},
+    process::{Command, Stdio},
+};
+
+/// base64 payload most terminals still accept in one osc52 sequence
+const OSC52_MAX_LEN: usize = 100_000;
+/// gnu screen drops longer passthrough strings
+const SCREEN_CHUNK_LEN: usize = 76;
+
+/// ways to get text into the system clipboard, tried in the order
+/// of `clipboard.backends` in the config
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardBackend {
+    /// built-in clipboard access, needs the `native-clipboard`
+    /// feature
+    Native,
+    /// `wl-copy`, `xclip`, `xsel`, `pbcopy` or `clip`
+    External,
+    /// escape sequence asking the terminal to set the clipboard,
+    /// works over ssh but can't tell if the terminal supports it
+    Osc52,
+}
+
+/// copies `text` with the user's `clipboard.command` or else the
+/// first backend that succeeds, returns a warning if only part of
+/// `text` could be copied
+pub fn copy_string(
+    text: &str,
+    config: &ClipboardConfig,
+) -> Result<Option<String>> {
+    if config.force_osc52 {
+        return copy_osc52(text);
+    }
+
+    if let Some((program, args)) =
+        config.command.as_ref().and_then(|cmd| cmd.split_first())
+    {
+        match copy_with_command(program, args, text) {
+            Ok(()) => {
+                log::debug!("clipboard: copied via {}", program);
+                return Ok(None);
+            }
+            Err(e) => {
+                log::debug!("clipboard: {} failed: {}", program, e);
+            }
+        }
+    }
+
+    for backend in &config.backends {
+        let res = match backend {
+            ClipboardBackend::Native => {
+                copy_native(text).map(|()| None)
+            }
+            ClipboardBackend::External => {
+                copy_external(text).map(|()| None)
+            }
+            ClipboardBackend::Osc52 => copy_osc52(text),
+        };
+
+        match res {
+            Ok(warning) => {
+                log::debug!("clipboard: copied via {:?}", backend);
+                return Ok(warning);
+            }
+            Err(e) => {
+                log::debug!("clipboard: {:?} failed: {}", backend, e);
+            }
+        }
+    }
+
+    Err(anyhow!("no clipboard backend succeeded"))
+}
+
+/// copies `text` and reports the outcome, `what` describes the
+/// copied text in the notification
+pub fn copy_notification(
+    text: &str,
+    what: &str,
+    config: &ClipboardConfig,
+) -> Notification {
+    match copy_string(text, config) {
+        Ok(None) => Notification::success(format!(
+            "{} {}",
+            strings::NOTIFY_COPIED,
+            what
+        )),
+        Ok(Some(warning)) => {
+            Notification::warning(strings::NOTIFY_COPIED, warning)
+        }
+        Err(e) => Notification::error("copy failed", e.to_string()),
+    }
+}
+
+/// reads the clipboard with the first of `clipboard.backends` that
+/// succeeds, osc52 can only write to it
+pub fn paste_string(config: &ClipboardConfig) -> Result<String> {
+    if config.force_osc52 {
+        return Err(anyhow!("osc52 can not read the clipboard"));
+    }
+
+    for backend in &config.backends {
+        let res = match backend {
+            ClipboardBackend::Native => paste_native(),
+            ClipboardBackend::External => paste_external(),
+            ClipboardBackend::Osc52 => continue,
+        };
+
+        match res {
+            Ok(text) => {
+                log::debug!("clipboard: pasted via {:?}", backend);
+                return Ok(text);
+            }
+            Err(e) => {
+                log::debug!("clipboard: {:?} failed: {}", backend, e);
+            }
+        }
+    }
+
+    Err(anyhow!("no clipboard backend could read the clipboard"))
+}
+
+#[cfg(feature = "native-clipboard")]
+fn copy_native(text: &str) -> Result<()> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| anyhow!("{}", e))?;
+    clipboard
+        .set_text(text.to_string())
+        .map_err(|e| anyhow!("{}", e))
+}
+
+#[cfg(not(feature = "native-clipboard"))]
+fn copy_native(_text: &str) -> Result<()> {
+    Err(anyhow!("built without the native-clipboard feature"))
+}
+
+#[cfg(feature = "native-clipboard")]
+fn paste_native() -> Result<String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| anyhow!("{}", e))?;
+    clipboard.get_text().map_err(|e| anyhow!("{}", e))
+}
+
+#[cfg(not(feature = "native-clipboard"))]
+fn paste_native() -> Result<String> {
+    Err(anyhow!("built without the native-clipboard feature"))
+}
+
+/// programs reading the text from stdin, the first one that can be
+/// started is used
+fn external_commands() -> Vec<(&'static str, &'static [&'static str])>
+{
+    let mut commands: Vec<(&str, &[&str])> = Vec::new();
+
+    if cfg!(windows) {
+        commands.push(("clip", &[]));
+    } else if cfg!(target_os = "macos") {
+        commands.push(("pbcopy", &[]));
+    } else {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", &[]));
+        }
+        commands.push(("xclip", &["-selection", "clipboard"]));
+        commands.push(("xsel", &["--clipboard", "--input"]));
+    }
+
+    commands
+}
+
+/// runs `program` with `text` on stdin, its exit status tells whether
+/// that worked
+fn copy_with_command<S: AsRef<OsStr>>(
+    program: &str,
+    args: &[S],
+    text: &str,
+) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // closed once written, the child is waited for in any case
+    let written = child
+        .stdin
+        .take()
+        .map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes()));
+
+    let status = child.wait()?;
+
+    match written {
+        // it is done without reading (all of) the text
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => {
+            Err(anyhow!("writing to {} failed: {}", program, e))
+        }
+        _ if status.success() => Ok(()),
+        _ => Err(anyhow!("{} exited with {}", program, status)),
+    }
+}
+
+/// programs writing the clipboard to stdout, counterparts of
+/// `external_commands`
+fn external_paste_commands(
+) -> Vec<(&'static str, &'static [&'static str])> {
+    let mut commands: Vec<(&str, &[&str])> = Vec::new();
+
+    if cfg!(windows) {
+        commands.push((
+            "powershell",
+            &["-NoProfile", "-Command", "Get-Clipboard"],
+        ));
+    } else if cfg!(target_os = "macos") {
+        commands.push(("pbpaste", &[]));
+    } else {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-paste", &["--no-newline"]));
+        }
+        commands.push(("xclip", &["-selection", "clipboard", "-o"]));
+        commands.push(("xsel", &["--clipboard", "--output"]));
+    }
+
+    commands
+}
+
+/// stdout of `program`
+fn paste_with_command(
+    program: &str,
+    args: &[&str],
+) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(anyhow!("{} exited with {}", program, output.status))
+    }
+}
+
+fn paste_external() -> Result<String> {
+    for (program, args) in external_paste_commands() {
+        if let Ok(text) = paste_with_command(program, args) {
+            return Ok(text);
+        }
+    }
+
+    Err(anyhow!("no clipboard program found"))
+}
+
+fn copy_external(text: &str) -> Result<()> {
+    for (program, args) in external_commands() {
+        if copy_with_command(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+
+    Err(anyhow!("no clipboard program found"))
+}
+
+/// the controlling terminal, so the sequence does not end up in
+/// the middle of a frame written to stdout
+#[cfg(unix)]
+fn terminal_writer() -> Box<dyn Write> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .map_or_else(
+            |_| Box::new(io::stdout()) as Box<dyn Write>,
+            |tty| Box::new(tty),
+        )
+}
+
+#[cfg(not(unix))]
+fn terminal_writer() -> Box<dyn Write> {
+    Box::new(io::stdout())
+}
+
+fn copy_osc52(text: &str) -> Result<Option<String>> {
+    let (text, warning) = clip_osc52(text);
+
+    let mut writer = terminal_writer();
+    writer.write_all(osc52(text).as_bytes())?;
+    writer.flush()?;
+
+    Ok(warning)
+}
+
+/// cuts `text` (at a char boundary) to what fits into `OSC52_MAX_LEN`
+fn clip_osc52(text: &str) -> (&str, Option<String>) {
+    let max = OSC52_MAX_LEN / 4 * 3;
+    if text.len() <= max {
+        return (text, None);
+    }
+
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    (
+        &text[..end],
+        Some(format!(
+            "only the first {} of {} bytes were copied",
+            end,
+            text.len()
+        )),
+    )
+}
+
+/// wraps the sequence for tmux and screen, which would swallow it
+/// otherwise
+fn osc52(text: &str) -> String {
+    let seq = format!("\x1b]52;c;{}\x07", base64::encode(text));
+
+    let tmux = env::var_os("TMUX").is_some();
+    let screen = matches!(
+        env::var("TERM"),
+        Ok(term) if term.starts_with("screen")
+    );
+
+    passthrough(seq, tmux, screen)
+}
+
+fn passthrough(seq: String, tmux: bool, screen: bool) -> String {
+    if tmux {
+        format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b"))
+    } else if screen {
+        let mut out = String::new();
+        for chunk in seq.as_bytes().chunks(SCREEN_CHUNK_LEN) {
+            out.push_str("\x1bP");
+            out.push_str(&String::from_utf8_lossy(chunk));
+            out.push_str("\x1b\\");
+        }
+        out
+    } else {
+        seq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52() {
+        let seq = String::from("\x1b]52;c;Z2l0dWk=\x07");
+
+        assert_eq!(passthrough(seq.clone(), false, false), seq);
+        assert_eq!(
+            passthrough(seq, true, false),
+            "\x1bPtmux;\x1b\x1b]52;c;Z2l0dWk=\x07\x1b\\"
+        );
+
+        let long = "x".repeat(SCREEN_CHUNK_LEN + 1);
+        assert_eq!(
+            passthrough(long, false, true),
+            format!(
+                "\x1bP{}\x1b\\\x1bPx\x1b\\",
+                "x".repeat(SCREEN_CHUNK_LEN)
+            )
+        );
+    }
+
+    #[test]
+    fn test_clip_osc52() {
+        assert_eq!(clip_osc52("gitui"), ("gitui", None));
+
+        let long = "ä".repeat(OSC52_MAX_LEN);
+        let (clipped, warning) = clip_osc52(&long);
+        assert!(warning.is_some());
+        assert!(base64::encode(clipped).len() <= OSC52_MAX_LEN);
+    }
+
+    #[test]
+    fn test_no_backends() {
+        let config = ClipboardConfig {
+            command: None,
+            backends: Vec::new(),
+            force_osc52: false,
+        };
+        assert!(copy_string("foo", &config).is_err());
+        assert!(paste_string(&config).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_without_reading() {
+        // more than fits into the pipe, `true` never reads it
+        let text = "x".repeat(1024 * 1024);
+        assert!(copy_with_command::<&str>("true", &[], &text).is_ok());
+        assert!(
+            copy_with_command::<&str>("false", &[], &text).is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command() {
+        let mut config = ClipboardConfig {
+            command: Some(vec![String::from("true")]),
+            backends: Vec::new(),
+            force_osc52: false,
+        };
+        assert!(copy_string("foo", &config).is_ok());
+
+        // failing commands fall through to the backends
+        config.command = Some(vec![String::from("false")]);
+        assert!(copy_string("foo", &config).is_err());
+    }
+}
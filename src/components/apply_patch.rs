@@ -0,0 +1,193 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{notifications::Notification, style::SharedTheme},
+};
+use anyhow::{anyhow, Result};
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use std::{env, fs, path::Path};
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for `.patch` files and applies them via `git am`
+pub struct ApplyPatchComponent {
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ApplyPatchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ApplyPatchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::APPLY_PATCH_CONFIRM,
+                !self.input.get_text().trim().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.confirm();
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl ApplyPatchComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::APPLY_PATCH_POPUP_TITLE,
+                strings::APPLY_PATCH_POPUP_MSG,
+            ),
+            queue,
+            key_config,
+        }
+    }
+
+    fn confirm(&mut self) {
+        if self.input.get_text().trim().is_empty() {
+            return;
+        }
+
+        let notification = match patch_files(self.input.get_text()) {
+            Ok(files) => Self::apply(&files),
+            Err(e) => Notification::error(
+                "apply patches failed",
+                e.to_string(),
+            ),
+        };
+
+        let mut queue = self.queue.borrow_mut();
+        queue.push_back(InternalEvent::Notify(notification));
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        drop(queue);
+
+        self.input.clear();
+        self.hide();
+    }
+
+    fn apply(files: &[String]) -> Notification {
+        match sync::apply_mailbox(CWD, files) {
+            Ok(()) => Notification::success(format!(
+                "applied {} patch(es)",
+                files.len()
+            )),
+            Err(e) => {
+                log::error!("apply patches error: {}", e);
+
+                if sync::am_in_progress(CWD).unwrap_or_default() {
+                    Notification::error(
+                        strings::AM_STOPPED_MSG,
+                        e.to_string(),
+                    )
+                } else {
+                    Notification::error(
+                        "apply patches failed",
+                        e.to_string(),
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// absolute paths of the files in `input`, directories are replaced
+/// by the `.patch` files in them (sorted, like `format-patch` names
+/// them)
+fn patch_files(input: &str) -> Result<Vec<String>> {
+    let cwd = env::current_dir()?;
+    let mut files = Vec::new();
+
+    for entry in input.split_whitespace() {
+        let path = cwd.join(entry);
+
+        if path.is_dir() {
+            let mut patches = fs::read_dir(&path)?
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.is_file()
+                        && p.extension()
+                            .map_or(false, |e| e == "patch")
+                })
+                .collect::<Vec<_>>();
+            patches.sort();
+
+            files.extend(patches.iter().map(|p| path_string(p)));
+        } else {
+            files.push(path_string(&path));
+        }
+    }
+
+    if files.is_empty() {
+        return Err(anyhow!("no .patch files found"));
+    }
+
+    Ok(files)
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
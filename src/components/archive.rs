@@ -0,0 +1,355 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{ArchiveFormat, ArchiveOptions},
+    AsyncArchive, AsyncNotification,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::{borrow::Cow, env, path::PathBuf, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// rows of the popup, the last one starts the export
+const FIELDS: [&str; 6] =
+    ["output", "format", "prefix", "tree-ish", "subtree", ""];
+const FIELD_OUTPUT: usize = 0;
+const FIELD_FORMAT: usize = 1;
+const FIELD_PREFIX: usize = 2;
+const FIELD_TREEISH: usize = 3;
+const FIELD_PATH: usize = 4;
+const FIELD_EXPORT: usize = 5;
+
+/// asks for the options of `git archive` and writes the archive in
+/// the background
+pub struct ArchiveComponent {
+    options: ArchiveOptions,
+    selection: usize,
+    input: TextInputComponent,
+    git_archive: AsyncArchive,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl ArchiveComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            options: ArchiveOptions {
+                treeish: String::from("HEAD"),
+                format: ArchiveFormat::Zip,
+                prefix: String::new(),
+                path: None,
+                output: PathBuf::new(),
+            },
+            selection: 0,
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config,
+                "",
+                strings::ARCHIVE_EDIT_MSG,
+            ),
+            git_archive: AsyncArchive::new(sender),
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// suggests `<repo dir>.zip` in the current directory with the
+    /// repo dir as prefix
+    pub fn open(&mut self, treeish: String) -> Result<()> {
+        let cwd = env::current_dir()?;
+        let name = cwd.file_name().map_or_else(
+            || String::from("archive"),
+            |name| name.to_string_lossy().into_owned(),
+        );
+
+        self.options = ArchiveOptions {
+            treeish,
+            format: ArchiveFormat::Zip,
+            prefix: format!("{}/", name),
+            path: None,
+            output: cwd.join(format!(
+                "{}.{}",
+                name,
+                ArchiveFormat::Zip.name()
+            )),
+        };
+        self.selection = FIELD_EXPORT;
+
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_archive.is_pending()
+    }
+
+    /// reports the finished archive, closes the popup on success
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if ev != AsyncNotification::Archive {
+            return Ok(());
+        }
+
+        if let Some((options, err)) = self.git_archive.take_last()? {
+            let notification = if let Some(e) = err {
+                Notification::error(strings::ARCHIVE_FAILED, e)
+            } else {
+                self.hide();
+                Notification::success(format!(
+                    "{} {}",
+                    strings::ARCHIVE_WRITTEN,
+                    options.output.display()
+                ))
+            };
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Notify(notification));
+        }
+
+        Ok(())
+    }
+
+    fn value(&self, field: usize) -> String {
+        match field {
+            FIELD_OUTPUT => {
+                self.options.output.to_string_lossy().into_owned()
+            }
+            FIELD_FORMAT => String::from(self.options.format.name()),
+            FIELD_PREFIX => self.options.prefix.clone(),
+            FIELD_TREEISH => self.options.treeish.clone(),
+            FIELD_PATH => {
+                self.options.path.clone().unwrap_or_default()
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        match self.selection {
+            FIELD_FORMAT => self.toggle_format(),
+            FIELD_EXPORT => {
+                self.git_archive.request(self.options.clone())?;
+            }
+            field => {
+                self.input.set_title(FIELDS[field].to_string());
+                self.input.set_text(self.value(field));
+                self.input.cursor_end();
+                self.input.show()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the extension of the output follows the format
+    fn toggle_format(&mut self) {
+        let old = self.options.format;
+        let new = match old {
+            ArchiveFormat::Zip => ArchiveFormat::TarGz,
+            ArchiveFormat::TarGz => ArchiveFormat::Zip,
+        };
+
+        let output =
+            self.options.output.to_string_lossy().into_owned();
+        if let Some(stem) =
+            output.strip_suffix(&format!(".{}", old.name()))
+        {
+            self.options.output =
+                PathBuf::from(format!("{}.{}", stem, new.name()));
+        }
+
+        self.options.format = new;
+    }
+
+    fn save_field(&mut self) {
+        let value = self.input.get_text().trim().to_string();
+
+        match self.selection {
+            FIELD_OUTPUT if !value.is_empty() => {
+                // git runs in the work dir, not necessarily ours
+                self.options.output = env::current_dir().map_or_else(
+                    |_| PathBuf::from(&value),
+                    |cwd| cwd.join(&value),
+                );
+            }
+            FIELD_PREFIX => self.options.prefix = value,
+            FIELD_TREEISH if !value.is_empty() => {
+                self.options.treeish = value;
+            }
+            FIELD_PATH => {
+                self.options.path =
+                    Some(value).filter(|path| !path.is_empty());
+            }
+            _ => (),
+        }
+
+        self.input.hide();
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        FIELDS
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                let selected = idx == self.selection;
+                let line = if idx == FIELD_EXPORT {
+                    format!(
+                        "\n{}\n",
+                        if self.any_work_pending() {
+                            strings::ARCHIVE_WRITING
+                        } else {
+                            strings::ARCHIVE_EXPORT
+                        }
+                    )
+                } else {
+                    format!("{:<10}{}\n", label, self.value(idx))
+                };
+
+                Text::Styled(
+                    Cow::from(line),
+                    self.theme.text(true, selected),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for ArchiveComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect_absolute(70, 10, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter()).block(
+                    Block::default()
+                        .title(strings::ARCHIVE_TITLE)
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true)),
+                ),
+                area,
+            );
+
+            self.input.draw(f, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ArchiveComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.input.is_visible() {
+            self.input.commands(out, force_all);
+            return CommandBlocking::Blocking;
+        }
+
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::ARCHIVE_ENTER,
+                !self.any_work_pending(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        if self.input.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(k) = ev {
+                if self.key_config.matches(&k, KeyAction::Enter) {
+                    self.save_field();
+                }
+            }
+
+            // the input is modal
+            return Ok(true);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if keys.matches(&k, KeyAction::ExitPopup) {
+                self.hide();
+            } else if keys.matches(&k, KeyAction::MoveUp) {
+                self.selection = self.selection.saturating_sub(1);
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                self.selection =
+                    (self.selection + 1).min(FIELD_EXPORT);
+            } else if keys.matches(&k, KeyAction::Enter)
+                && !self.any_work_pending()
+            {
+                self.enter()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -1,19 +1,24 @@
 use super::{
     filetree::FileTreeComponent,
-    utils::filetree::{FileTreeItem, FileTreeItemKind},
+    utils::filetree::{dir_counts, FileTreeItem, FileTreeItemKind},
     CommandBlocking, DrawableComponent,
 };
 use crate::{
     components::{CommandInfo, Component},
-    keys,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings,
-    ui::style::SharedTheme,
+    tabs::StashingOptions,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::{cached, sync, StatusItem, StatusItemType, CWD};
+use asyncgit::{
+    cached,
+    sync::{self, ConflictSide, FileStat, IndexBit},
+    StatusItem, StatusItemType, CWD,
+};
 use crossterm::event::Event;
-use std::path::Path;
+use std::{collections::HashMap, path::Path, rc::Rc};
 use strings::commands;
 use tui::{backend::Backend, layout::Rect, Frame};
 
@@ -33,6 +38,60 @@ macro_rules! try_or_popup {
     };
 }
 
+/// marked files worked off at once, more take several steps with
+/// the progress in the title
+const BATCH_STEP: usize = 100;
+
+/// what is done to the marked files
+enum BatchItems {
+    Stage(Vec<StatusItem>),
+    Unstage(Vec<StatusItem>),
+    Reset(Vec<ResetItem>),
+}
+
+/// marked files worked off a step at a time
+struct Batch {
+    items: BatchItems,
+    done: usize,
+    /// resets that failed, reported already
+    failed: usize,
+}
+
+impl Batch {
+    fn len(&self) -> usize {
+        match &self.items {
+            BatchItems::Stage(items) | BatchItems::Unstage(items) => {
+                items.len()
+            }
+            BatchItems::Reset(items) => items.len(),
+        }
+    }
+
+    const fn name(&self) -> &'static str {
+        match self.items {
+            BatchItems::Stage(_) => strings::BATCH_STAGING,
+            BatchItems::Unstage(_) => strings::BATCH_UNSTAGING,
+            BatchItems::Reset(_) => strings::BATCH_RESETTING,
+        }
+    }
+
+    fn finished(&self) -> String {
+        let (msg, count) = match self.items {
+            BatchItems::Stage(_) => {
+                (strings::NOTIFY_STAGED, self.len())
+            }
+            BatchItems::Unstage(_) => {
+                (strings::NOTIFY_UNSTAGED, self.len())
+            }
+            BatchItems::Reset(_) => {
+                (strings::NOTIFY_RESET, self.len() - self.failed)
+            }
+        };
+
+        format!("{} {}", msg, count)
+    }
+}
+
 ///
 pub struct ChangesComponent {
     title: String,
@@ -40,6 +99,8 @@ pub struct ChangesComponent {
     is_working_dir: bool,
     queue: Queue,
     branch_name: cached::BranchName,
+    batch: Option<Batch>,
+    key_config: SharedKeyConfig,
 }
 
 impl ChangesComponent {
@@ -50,6 +111,7 @@ impl ChangesComponent {
         is_working_dir: bool,
         queue: Queue,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
     ) -> Self {
         Self {
             title: title.into(),
@@ -58,28 +120,143 @@ impl ChangesComponent {
                 focus,
                 Some(queue.clone()),
                 theme,
+                key_config.clone(),
             ),
             is_working_dir,
             queue,
             branch_name: cached::BranchName::new(CWD),
+            batch: None,
+            key_config,
         }
     }
 
     pub fn update(&mut self) -> Result<()> {
-        if self.is_working_dir {
-            if let Ok(branch_name) = self.branch_name.lookup() {
-                self.files.set_title(format!(
-                    "{} - {{{}}}",
-                    &self.title, branch_name,
-                ))
+        self.update_title();
+        Ok(())
+    }
+
+    fn update_title(&mut self) {
+        let mut title = if self.is_working_dir {
+            match self.branch_name.lookup() {
+                Ok(branch_name) => {
+                    format!("{} - {{{}}}", &self.title, branch_name)
+                }
+                // detached, the tab bar says where head is
+                Err(_) => self.title.clone(),
             }
+        } else {
+            self.title.clone()
+        };
+
+        if let Some(batch) = &self.batch {
+            title = format!(
+                "{} ({} {}/{})",
+                title,
+                batch.name(),
+                batch.done,
+                batch.len()
+            );
         }
-        Ok(())
+
+        self.files.set_title(title);
+    }
+
+    /// whether marked files are still being worked off
+    pub const fn is_batching(&self) -> bool {
+        self.batch.is_some()
+    }
+
+    fn start_batch(&mut self, items: BatchItems) {
+        self.batch = Some(Batch {
+            items,
+            done: 0,
+            failed: 0,
+        });
+        self.step_batch();
+    }
+
+    /// works off the next `BATCH_STEP` marked files, returns whether
+    /// there were any. the marks stay if staging fails
+    pub fn step_batch(&mut self) -> bool {
+        let mut batch = match self.batch.take() {
+            Some(batch) => batch,
+            None => return false,
+        };
+
+        let end = (batch.done + BATCH_STEP).min(batch.len());
+        let result = match &batch.items {
+            BatchItems::Stage(items) => items[batch.done..end]
+                .iter()
+                .try_for_each(Self::stage_file),
+            BatchItems::Unstage(items) => items[batch.done..end]
+                .iter()
+                .try_for_each(Self::unstage_file),
+            BatchItems::Reset(items) => {
+                batch.failed += items[batch.done..end]
+                    .iter()
+                    .filter(|item| !self.reset(item))
+                    .count();
+                Ok(())
+            }
+        };
+        batch.done = end;
+
+        if let Err(e) = result {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "staging error:\n{}",
+                    e
+                )),
+            );
+        } else if batch.done < batch.len() {
+            self.batch = Some(batch);
+        } else {
+            self.files.clear_marks();
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::success(batch.finished()),
+            ));
+        }
+
+        if self.batch.is_none() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+        self.update_title();
+
+        true
+    }
+
+    /// resets the marked files, `items` are the ones confirmed
+    pub fn reset_marked(&mut self, items: Vec<ResetItem>) {
+        self.start_batch(BatchItems::Reset(items));
     }
 
     ///
-    pub fn set_items(&mut self, list: &[StatusItem]) -> Result<()> {
+    pub fn reset(&self, item: &ResetItem) -> bool {
+        if let Err(e) = sync::reset_workdir(CWD, item.path.as_str()) {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "reset failed:\n{}",
+                    e
+                )),
+            );
+
+            false
+        } else {
+            true
+        }
+    }
+
+    /// `stats` per item path, shown next to each file
+    pub fn set_items(
+        &mut self,
+        list: &[StatusItem],
+        stats: HashMap<String, FileStat>,
+    ) -> Result<()> {
+        self.files.set_file_stats(stats)?;
         self.files.update(list)?;
+        self.files.set_dir_counts(dir_counts(list));
         Ok(())
     }
 
@@ -99,33 +276,97 @@ impl ChangesComponent {
         self.files.is_empty()
     }
 
+    ///
+    pub fn set_flat(&mut self, flat: bool) -> Result<()> {
+        self.files.set_flat(flat)
+    }
+
+    ///
+    pub const fn is_flat(&self) -> bool {
+        self.files.is_flat()
+    }
+
+    ///
+    pub fn set_sort_by_size(&mut self, by_size: bool) -> Result<()> {
+        self.files.set_sort_by_size(by_size)
+    }
+
+    ///
+    pub const fn is_sorted_by_size(&self) -> bool {
+        self.files.is_sorted_by_size()
+    }
+
     ///
     pub fn is_file_seleted(&self) -> bool {
         self.files.is_file_seleted()
     }
 
+    /// a rename takes its old path along
+    fn stage_file(item: &StatusItem) -> Result<()> {
+        let path = Path::new(item.path.as_str());
+        match (item.status, &item.old_path) {
+            (StatusItemType::Renamed, Some(old_path)) => {
+                sync::stage_add_file(CWD, path)?;
+                sync::stage_addremoved(CWD, Path::new(old_path))?;
+            }
+            (StatusItemType::Deleted, _) => {
+                sync::stage_addremoved(CWD, path)?
+            }
+            // an untracked dir listed as a whole
+            _ if item.path.ends_with('/') => {
+                sync::stage_add_all(CWD, item.path.as_str())?
+            }
+            _ => sync::stage_add_file(CWD, path)?,
+        };
+
+        Ok(())
+    }
+
+    /// a rename takes its old path along
+    fn unstage_file(item: &StatusItem) -> Result<()> {
+        sync::reset_stage(CWD, item.path.as_str())?;
+        if let (StatusItemType::Renamed, Some(old_path)) =
+            (item.status, &item.old_path)
+        {
+            sync::reset_stage(CWD, old_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// stages or unstages all marked files
+    fn index_add_remove_marked(&mut self) {
+        let items = self.files.marked_items();
+        self.start_batch(if self.is_working_dir {
+            BatchItems::Stage(items)
+        } else {
+            BatchItems::Unstage(items)
+        });
+    }
+
     fn index_add_remove(&mut self) -> Result<bool> {
+        if self.files.has_marks() {
+            self.index_add_remove_marked();
+            return Ok(true);
+        }
+
         if let Some(tree_item) = self.selection() {
             if self.is_working_dir {
                 if let FileTreeItemKind::File(i) = tree_item.kind {
-                    let path = Path::new(i.path.as_str());
-                    match i.status {
-                        StatusItemType::Deleted => {
-                            sync::stage_addremoved(CWD, path)?
-                        }
-                        _ => sync::stage_add_file(CWD, path)?,
-                    };
+                    Self::stage_file(&i)?;
 
                     return Ok(true);
                 } else {
                     //TODO: check if we can handle the one file case with it aswell
-                    sync::stage_add_all(
-                        CWD,
-                        tree_item.info.full_path.as_str(),
-                    )?;
+                    let path = tree_item.info.full_path.as_str();
+                    sync::stage_add_all(CWD, path)?;
+                    self.warn_hidden(path)?;
 
                     return Ok(true);
                 }
+            } else if let FileTreeItemKind::File(i) = tree_item.kind {
+                Self::unstage_file(&i)?;
+                return Ok(true);
             } else {
                 let path = tree_item.info.full_path.as_str();
                 sync::reset_stage(CWD, path)?;
@@ -138,6 +379,7 @@ impl ChangesComponent {
 
     fn index_add_all(&mut self) -> Result<()> {
         sync::stage_add_all(CWD, "*")?;
+        self.warn_hidden("*")?;
 
         self.queue
             .borrow_mut()
@@ -157,6 +399,36 @@ impl ChangesComponent {
     }
 
     fn dispatch_reset_workdir(&mut self) -> bool {
+        if self.files.has_marks() {
+            let items = self
+                .files
+                .marked_items()
+                .iter()
+                .flat_map(Self::reset_items)
+                .collect();
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::ResetItems(
+                    items,
+                )),
+            );
+
+            return true;
+        }
+
+        if let Some(FileTreeItemKind::File(item)) =
+            self.selection().map(|tree_item| tree_item.kind)
+        {
+            if item.status == StatusItemType::Renamed {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(Action::ResetItems(
+                        Self::reset_items(&item),
+                    )),
+                );
+
+                return true;
+            }
+        }
+
         if let Some(tree_item) = self.selection() {
             let is_folder =
                 matches!(tree_item.kind, FileTreeItemKind::Path(_));
@@ -174,6 +446,225 @@ impl ChangesComponent {
         false
     }
 
+    /// a rename is undone (or stashed) on both sides
+    fn reset_items(item: &StatusItem) -> Vec<ResetItem> {
+        let old_path = if item.status == StatusItemType::Renamed {
+            item.old_path.clone()
+        } else {
+            None
+        };
+
+        std::iter::once(item.path.clone())
+            .chain(old_path)
+            .map(|path| ResetItem {
+                // an untracked dir listed as a whole
+                is_folder: path.ends_with('/'),
+                path,
+            })
+            .collect()
+    }
+
+    fn stash_marked(&self) {
+        let paths = self
+            .files
+            .marked_items()
+            .iter()
+            .flat_map(Self::reset_items)
+            .map(|item| item.path)
+            .collect();
+
+        self.queue.borrow_mut().push_back(
+            InternalEvent::PopupStashing(StashingOptions {
+                stash_untracked: true,
+                stash_ignored: false,
+                keep_index: false,
+                paths,
+            }),
+        );
+    }
+
+    /// staging a folder (or `*` for all) leaves out files git is told
+    /// to not look at, better say so
+    fn warn_hidden(&self, path: &str) -> Result<()> {
+        let folder = format!("{}/", path);
+        let hidden: Vec<String> = sync::get_hidden_files(CWD)?
+            .into_iter()
+            .filter(|file| {
+                path == "*" || file.path.starts_with(&folder)
+            })
+            .map(|file| file.path)
+            .collect();
+
+        if !hidden.is_empty() {
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::warning(
+                    format!(
+                        "{} {}",
+                        strings::NOTIFY_HIDDEN_NOT_STAGED,
+                        hidden.len()
+                    ),
+                    hidden.join("\n"),
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// sets `bit` of the selected file, so its changes vanish from
+    /// the list until cleared in the hidden files popup
+    fn hide_changes(&self, bit: IndexBit) -> bool {
+        if let Some(FileTreeItem {
+            kind: FileTreeItemKind::File(item),
+            ..
+        }) = self.selection()
+        {
+            if item.status == StatusItemType::New {
+                return false;
+            }
+
+            if let Err(e) =
+                sync::set_index_bit(CWD, &item.path, bit, true)
+            {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "{} error:\n{}",
+                        bit.name(),
+                        e
+                    )),
+                );
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::success(
+                        format!("{}: {}", bit.name(), item.path),
+                    )),
+                );
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// flips the executable bit of the selected file in the work dir
+    /// and, in the stage list, in the index too
+    fn toggle_executable(&self) -> bool {
+        if let Some(FileTreeItem {
+            kind: FileTreeItemKind::File(item),
+            ..
+        }) = self.selection()
+        {
+            match sync::toggle_executable(
+                CWD,
+                &item.path,
+                !self.is_working_dir,
+            ) {
+                Ok(executable) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Notify(Notification::success(
+                            format!(
+                                "{} {}",
+                                if executable {
+                                    strings::NOTIFY_EXECUTABLE
+                                } else {
+                                    strings::NOTIFY_NOT_EXECUTABLE
+                                },
+                                item.path
+                            ),
+                        )),
+                    );
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+                }
+                Err(e) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "toggle executable error:\n{}",
+                            e
+                        )),
+                    );
+                }
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    /// the selected file of the work dir list if it is conflicted
+    fn conflicted_selection(&self) -> Option<StatusItem> {
+        match self.selection() {
+            Some(FileTreeItem {
+                kind: FileTreeItemKind::File(item),
+                ..
+            }) if self.is_working_dir
+                && item.status == StatusItemType::Conflicted =>
+            {
+                Some(item)
+            }
+            _ => None,
+        }
+    }
+
+    /// resolves the selected conflicted file with the version of
+    /// `side`
+    fn take_side(&self, side: ConflictSide) -> bool {
+        if let Some(item) = self.conflicted_selection() {
+            match sync::resolve_conflict(CWD, &item.path, side) {
+                Ok(()) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Notify(Notification::success(
+                            format!(
+                                "{} {}",
+                                match side {
+                                    ConflictSide::Ours => {
+                                        strings::NOTIFY_TOOK_OURS
+                                    }
+                                    ConflictSide::Theirs => {
+                                        strings::NOTIFY_TOOK_THEIRS
+                                    }
+                                },
+                                item.path
+                            ),
+                        )),
+                    );
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+                }
+                Err(e) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "resolve conflict error:\n{}",
+                            e
+                        )),
+                    );
+                }
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    fn open_conflict(&self) -> bool {
+        if let Some(item) = self.conflicted_selection() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenConflict(item.path));
+            return true;
+        }
+
+        false
+    }
+
     fn add_to_ignore(&mut self) -> bool {
         if let Some(tree_item) = self.selection() {
             if let Err(e) =
@@ -219,6 +710,23 @@ impl Component for ChangesComponent {
         self.files.commands(out, force_all);
 
         let some_selection = self.selection().is_some();
+        let marks = self.files.has_marks();
+
+        out.push(CommandInfo::new(
+            commands::MARK_ITEM,
+            some_selection,
+            self.focused(),
+        ));
+        out.push(CommandInfo::new(
+            commands::UNMARK_ALL,
+            true,
+            self.focused() && marks,
+        ));
+        out.push(CommandInfo::new(
+            commands::STASH_MARKED,
+            marks,
+            self.focused(),
+        ));
 
         if self.is_working_dir {
             out.push(CommandInfo::new(
@@ -241,6 +749,38 @@ impl Component for ChangesComponent {
                 some_selection,
                 self.focused(),
             ));
+            out.push(CommandInfo::new(
+                commands::ASSUME_UNCHANGED,
+                self.is_file_seleted(),
+                self.focused(),
+            ));
+            out.push(CommandInfo::new(
+                commands::SKIP_WORKTREE,
+                self.is_file_seleted(),
+                self.focused(),
+            ));
+            out.push(CommandInfo::new(
+                commands::TOGGLE_EXECUTABLE,
+                self.is_file_seleted(),
+                self.focused(),
+            ));
+
+            let conflicted = self.conflicted_selection().is_some();
+            out.push(CommandInfo::new(
+                commands::TAKE_OURS,
+                true,
+                self.focused() && conflicted,
+            ));
+            out.push(CommandInfo::new(
+                commands::TAKE_THEIRS,
+                true,
+                self.focused() && conflicted,
+            ));
+            out.push(CommandInfo::new(
+                commands::CONFLICT_VIEW,
+                true,
+                self.focused() && conflicted,
+            ));
         } else {
             out.push(CommandInfo::new(
                 commands::UNSTAGE_ITEM,
@@ -252,6 +792,11 @@ impl Component for ChangesComponent {
                 some_selection,
                 self.focused(),
             ));
+            out.push(CommandInfo::new(
+                commands::TOGGLE_EXECUTABLE,
+                self.is_file_seleted(),
+                self.focused(),
+            ));
             out.push(
                 CommandInfo::new(
                     commands::COMMIT_OPEN,
@@ -270,59 +815,102 @@ impl Component for ChangesComponent {
             return Ok(true);
         }
 
+        // the marks are in use until the batch is done
+        if self.is_batching() {
+            return Ok(false);
+        }
+
         if self.focused() {
             if let Event::Key(e) = ev {
-                return match e {
-                    keys::OPEN_COMMIT
-                        if !self.is_working_dir
-                            && !self.is_empty() =>
-                    {
-                        self.queue
-                            .borrow_mut()
-                            .push_back(InternalEvent::OpenCommit);
-                        Ok(true)
-                    }
-                    keys::STATUS_STAGE_FILE => {
+                let keys = Rc::clone(&self.key_config);
+
+                return if keys.matches(&e, KeyAction::OpenCommit)
+                    && !self.is_working_dir
+                    && !self.is_empty()
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenCommit);
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::StatusMarkFile)
+                {
+                    self.files.toggle_mark();
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::ExitPopup)
+                    && self.files.has_marks()
+                {
+                    self.files.clear_marks();
+                    Ok(true)
+                } else if keys
+                    .matches(&e, KeyAction::StatusStashMarked)
+                    && self.files.has_marks()
+                {
+                    self.stash_marked();
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::StatusStageFile)
+                {
+                    try_or_popup!(
+                        self,
+                        "staging error:",
+                        self.index_add_remove()
+                    );
+
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::StatusStageAll)
+                    && !self.is_empty()
+                {
+                    if self.is_working_dir {
                         try_or_popup!(
                             self,
                             "staging error:",
-                            self.index_add_remove()
+                            self.index_add_all()
                         );
-
-                        self.queue.borrow_mut().push_back(
-                            InternalEvent::Update(NeedsUpdate::ALL),
-                        );
-
-                        Ok(true)
+                    } else {
+                        self.stage_remove_all()?;
                     }
 
-                    keys::STATUS_STAGE_ALL if !self.is_empty() => {
-                        if self.is_working_dir {
-                            try_or_popup!(
-                                self,
-                                "staging error:",
-                                self.index_add_all()
-                            );
-                        } else {
-                            self.stage_remove_all()?;
-                        }
-
-                        Ok(true)
-                    }
-
-                    keys::STATUS_RESET_FILE
-                        if self.is_working_dir =>
-                    {
-                        Ok(self.dispatch_reset_workdir())
-                    }
-
-                    keys::STATUS_IGNORE_FILE
-                        if self.is_working_dir
-                            && !self.is_empty() =>
-                    {
-                        Ok(self.add_to_ignore())
-                    }
-                    _ => Ok(false),
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::StatusResetFile)
+                    && self.is_working_dir
+                {
+                    Ok(self.dispatch_reset_workdir())
+                } else if keys
+                    .matches(&e, KeyAction::StatusIgnoreFile)
+                    && self.is_working_dir
+                    && !self.is_empty()
+                {
+                    Ok(self.add_to_ignore())
+                } else if keys
+                    .matches(&e, KeyAction::StatusAssumeUnchanged)
+                    && self.is_working_dir
+                {
+                    Ok(self.hide_changes(IndexBit::AssumeUnchanged))
+                } else if keys
+                    .matches(&e, KeyAction::StatusSkipWorktree)
+                    && self.is_working_dir
+                {
+                    Ok(self.hide_changes(IndexBit::SkipWorktree))
+                } else if keys
+                    .matches(&e, KeyAction::StatusToggleExecutable)
+                {
+                    Ok(self.toggle_executable())
+                } else if keys.matches(&e, KeyAction::StatusTakeOurs)
+                {
+                    Ok(self.take_side(ConflictSide::Ours))
+                } else if keys
+                    .matches(&e, KeyAction::StatusTakeTheirs)
+                {
+                    Ok(self.take_side(ConflictSide::Theirs))
+                } else if keys
+                    .matches(&e, KeyAction::StatusConflictView)
+                {
+                    Ok(self.open_conflict())
+                } else {
+                    Ok(false)
                 };
             }
         }
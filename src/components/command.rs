@@ -1,3 +1,6 @@
+use crate::keys::{key_name, KeyAction, KeyConfig};
+use itertools::Itertools;
+
 ///
 #[derive(Copy, Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub struct CommandText {
@@ -9,6 +12,11 @@ pub struct CommandText {
     pub group: &'static str,
     ///
     pub hide_help: bool,
+    /// what the command runs, lets the palette execute it
+    pub action: Option<KeyAction>,
+    /// actions whose keys are shown instead of the one of `action`,
+    /// for commands spread over several keys
+    pub shown_keys: &'static [KeyAction],
 }
 
 impl CommandText {
@@ -23,6 +31,8 @@ impl CommandText {
             desc,
             group,
             hide_help: false,
+            action: None,
+            shown_keys: &[],
         }
     }
     ///
@@ -31,6 +41,49 @@ impl CommandText {
         tmp.hide_help = true;
         tmp
     }
+    ///
+    pub const fn action(self, action: KeyAction) -> Self {
+        let mut tmp = self;
+        tmp.action = Some(action);
+        tmp
+    }
+    ///
+    pub const fn shown_keys(
+        self,
+        actions: &'static [KeyAction],
+    ) -> Self {
+        let mut tmp = self;
+        tmp.shown_keys = actions;
+        tmp
+    }
+    /// the name without the keys in brackets
+    pub fn label(&self) -> &'static str {
+        self.name.split(" [").next().unwrap_or(self.name)
+    }
+    /// the label with the keys bound in `key_config`, like
+    /// `Stage [s]`, the name as written without any action
+    pub fn hint(&self, key_config: &KeyConfig) -> String {
+        let actions = if self.shown_keys.is_empty() {
+            self.action.as_slice()
+        } else {
+            self.shown_keys
+        };
+        if actions.is_empty() {
+            return self.name.to_string();
+        }
+
+        let keys = actions
+            .iter()
+            .filter_map(|action| key_config.keys(*action).first())
+            .map(key_name)
+            .join(",");
+
+        if keys.is_empty() {
+            self.label().to_string()
+        } else {
+            format!("{} [{}]", self.label(), keys)
+        }
+    }
 }
 
 ///
@@ -76,11 +129,42 @@ impl CommandInfo {
         res
     }
     ///
-    pub fn print(&self, out: &mut String) {
-        out.push_str(self.text.name);
+    pub fn print(&self, out: &mut String, key_config: &KeyConfig) {
+        out.push_str(&self.text.hint(key_config));
     }
     ///
     pub fn show_in_quickbar(&self) -> bool {
         self.quick_bar && self.available
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keys::KeyPreset, strings::commands};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hint_follows_key_config() {
+        let config = KeyConfig::default();
+        assert_eq!(
+            commands::STAGE_ALL.hint(&config),
+            "Stage All [a]"
+        );
+        assert_eq!(
+            commands::SCROLL.hint(&config),
+            "Scroll [up,down]"
+        );
+
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            KeyAction::StatusStageAll,
+            vec![String::from("ctrl+a")],
+        );
+        let config = KeyConfig::new(&bindings, KeyPreset::Default, 3);
+        assert_eq!(
+            commands::STAGE_ALL.hint(&config),
+            "Stage All [ctrl+a]"
+        );
+    }
+}
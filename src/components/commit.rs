@@ -1,31 +1,107 @@
 use super::{
-    textinput::TextInputComponent, visibility_blocking,
-    CommandBlocking, CommandInfo, Component, DrawableComponent,
-    ExternalEditorComponent,
+    textinput::TextInputComponent,
+    utils::{commit_lint, conventional},
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DiffComponent, DrawableComponent, ExternalEditorComponent,
+    SharedDiffOptions,
 };
 use crate::{
-    get_app_config_path, keys,
+    config::{CommitLintMode, SharedConfig, TrailingWhitespace},
+    get_app_config_path,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{InternalEvent, NeedsUpdate, Queue},
+    spell::{self, SpellCheck},
     strings::{self, commands},
-    ui::style::SharedTheme,
+    ui::{
+        self,
+        notifications::{Notification, NotificationLevel},
+        style::SharedTheme,
+    },
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, CommitId, HookResult},
-    CWD,
+    sync::{
+        self, AuthorOverride, CommitId, PrepareCommitMsgSource,
+        RewriteCommand,
+    },
+    AsyncDiff, AsyncHooks, AsyncNotification, CommitHooksResult,
+    DiffParams, DiffType, FileDiff, CWD,
 };
-use crossterm::event::Event;
+use crossbeam_channel::Sender;
+use crossterm::event::{Event, KeyEvent};
 use std::{
+    borrow::Cow,
+    convert::TryFrom,
     fs::File,
     io::{Read, Write},
+    ops::Range,
     path::PathBuf,
+    rc::Rc,
 };
-use tui::{backend::Backend, layout::Rect, Frame};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// subject length the counter turns red at, the yellow one is
+/// `commit.subject_max_len`
+const SUBJECT_HARD_LIMIT: usize = 72;
+
+/// steps of the conventional commit assist, it puts the prefix in
+/// front of the message
+enum Assist {
+    Off,
+    /// index into `CommitComponent::types`
+    PickType(usize),
+    /// type picked, index into `conventional_commits.scopes` after a
+    /// first entry for none
+    PickScope(String, usize),
+    /// type picked, the input holds the scope until it is confirmed
+    Scope {
+        commit_type: String,
+        msg: String,
+    },
+    /// the input holds the author override until it is confirmed
+    Author {
+        msg: String,
+    },
+    /// author confirmed, the input holds the date
+    AuthorDate {
+        author: Option<String>,
+        msg: String,
+    },
+    /// spelling suggestions for the word at `range` of the message
+    Suggest {
+        range: Range<usize>,
+        words: Vec<String>,
+        selection: usize,
+    },
+}
 
 pub struct CommitComponent {
     input: TextInputComponent,
+    assist: Assist,
+    /// committing as someone else or backdated
+    author: AuthorOverride,
+    spell: SpellCheck,
+    /// message whose unusual chars were warned about already,
+    /// committing it again proceeds
+    acknowledged: Option<String>,
+    diff: DiffComponent,
+    verbose: bool,
+    verify: bool,
     amend: Option<CommitId>,
+    /// finishing a merge, the message was seeded from `MERGE_MSG`
+    merging: bool,
+    git_hooks: AsyncHooks,
+    git_diff: AsyncDiff,
     queue: Queue,
+    config: SharedConfig,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for CommitComponent {
@@ -34,7 +110,81 @@ impl DrawableComponent for CommitComponent {
         f: &mut Frame<B>,
         rect: Rect,
     ) -> Result<()> {
-        self.input.draw(f, rect)?;
+        if self.verbose && self.is_visible() {
+            let area = ui::centered_rect(60, 80, f.size());
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Percentage(25),
+                        Constraint::Percentage(75),
+                    ]
+                    .as_ref(),
+                )
+                .split(area);
+
+            let input_area = ui::rect_min(10, 3, chunks[0]);
+            self.input.draw_in(f, input_area);
+
+            f.render_widget(Clear, chunks[1]);
+            self.diff.draw(f, chunks[1])?;
+
+            self.draw_subject_counter(f, input_area);
+            self.draw_lint(f, input_area);
+        } else {
+            self.input.draw(f, rect)?;
+
+            if self.is_visible() {
+                let input_area = ui::rect_min(
+                    10,
+                    3,
+                    ui::centered_rect(60, 20, f.size()),
+                );
+                self.draw_subject_counter(f, input_area);
+                self.draw_lint(f, input_area);
+            }
+        }
+
+        match &self.assist {
+            Assist::PickType(selection) => self.draw_picker(
+                f,
+                strings::COMMIT_TYPE_TITLE,
+                &self
+                    .types()
+                    .iter()
+                    .map(|(name, desc)| {
+                        format!("{:<10}{}", name, desc)
+                    })
+                    .collect::<Vec<_>>(),
+                *selection,
+            ),
+            Assist::PickScope(_, selection) => self.draw_picker(
+                f,
+                strings::COMMIT_SCOPE_TITLE,
+                &self.scopes(),
+                *selection,
+            ),
+            Assist::Suggest {
+                range,
+                words,
+                selection,
+            } => self.draw_picker(
+                f,
+                &format!(
+                    "{} `{}`",
+                    strings::COMMIT_SUGGESTIONS_TITLE,
+                    &self.input.get_text()[range.clone()]
+                ),
+                words,
+                *selection,
+            ),
+            _ => (),
+        }
+
+        if self.git_hooks.is_pending() {
+            self.draw_hooks_output(f)?;
+        }
 
         Ok(())
     }
@@ -46,6 +196,57 @@ impl Component for CommitComponent {
         out: &mut Vec<CommandInfo>,
         force_all: bool,
     ) -> CommandBlocking {
+        if self.git_hooks.is_pending() {
+            out.push(CommandInfo::new(
+                commands::COMMIT_HOOKS_CANCEL,
+                true,
+                true,
+            ));
+
+            return CommandBlocking::Blocking;
+        }
+
+        match self.assist {
+            Assist::PickType(_) if !force_all => {
+                out.push(CommandInfo::new(
+                    commands::COMMIT_PICK_TYPE,
+                    true,
+                    true,
+                ));
+            }
+            Assist::PickScope(..) if !force_all => {
+                out.push(CommandInfo::new(
+                    commands::COMMIT_PICK_SCOPE,
+                    true,
+                    true,
+                ));
+            }
+            Assist::Scope { .. } if !force_all => {
+                out.push(CommandInfo::new(
+                    commands::COMMIT_CONFIRM_SCOPE,
+                    true,
+                    true,
+                ));
+            }
+            Assist::Author { .. } | Assist::AuthorDate { .. }
+                if !force_all =>
+            {
+                out.push(CommandInfo::new(
+                    commands::COMMIT_CONFIRM_AUTHOR,
+                    true,
+                    true,
+                ));
+            }
+            Assist::Suggest { .. } if !force_all => {
+                out.push(CommandInfo::new(
+                    commands::COMMIT_PICK_SUGGESTION,
+                    true,
+                    true,
+                ));
+            }
+            _ => (),
+        }
+
         self.input.commands(out, force_all);
 
         if self.is_visible() || force_all {
@@ -66,6 +267,40 @@ impl Component for CommitComponent {
                 true,
                 true,
             ));
+
+            out.push(CommandInfo::new(
+                commands::COMMIT_TYPE,
+                !self.types().is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::COMMIT_AUTHOR,
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::COMMIT_SPELL_SUGGEST,
+                self.input.word_at_cursor().is_some(),
+                self.config.ui.spell_check,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::COMMIT_TOGGLE_DIFF,
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::COMMIT_NO_VERIFY,
+                true,
+                self.config.commit.allow_no_verify,
+            ));
+
+            if self.verbose {
+                self.diff.commands(out, force_all);
+            }
         }
 
         visibility_blocking(self)
@@ -73,29 +308,60 @@ impl Component for CommitComponent {
 
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.is_visible() {
-            if self.input.event(ev)? {
+            if self.git_hooks.is_pending() {
+                if let Event::Key(e) = ev {
+                    if self
+                        .key_config
+                        .matches(&e, KeyAction::ExitPopup)
+                    {
+                        self.git_hooks.cancel();
+                    }
+                }
+
+                // wait for the hooks before accepting any other input
                 return Ok(true);
             }
 
-            if let Event::Key(e) = ev {
-                match e {
-                    keys::ENTER if self.can_commit() => {
-                        self.commit()?;
-                    }
+            if self.assist_event(ev) {
+                return Ok(true);
+            }
 
-                    keys::COMMIT_AMEND if self.can_amend() => {
-                        self.amend()?;
-                    }
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
 
-                    keys::OPEN_COMMIT_EDITOR => {
-                        self.queue.borrow_mut().push_back(
-                            InternalEvent::OpenExternalEditor(None),
-                        );
-                        self.hide();
-                    }
+            if self.verbose && self.diff.event(ev)? {
+                return Ok(true);
+            }
 
-                    _ => (),
-                };
+            if let Event::Key(e) = ev {
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&e, KeyAction::Enter)
+                    && self.can_commit()
+                {
+                    self.commit()?;
+                } else if keys.matches(&e, KeyAction::CommitAmend)
+                    && self.can_amend()
+                {
+                    self.amend()?;
+                } else if keys
+                    .matches(&e, KeyAction::OpenCommitEditor)
+                {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::OpenExternalEditor(None),
+                    );
+                    self.hide();
+                } else if keys
+                    .matches(&e, KeyAction::CommitToggleDiff)
+                {
+                    self.toggle_diff()?;
+                } else if keys.matches(&e, KeyAction::CommitNoVerify)
+                    && self.config.commit.allow_no_verify
+                {
+                    self.verify = !self.verify;
+                    self.update_title();
+                }
 
                 // stop key event propagation
                 return Ok(true);
@@ -115,27 +381,230 @@ impl Component for CommitComponent {
 
     fn show(&mut self) -> Result<()> {
         self.amend = None;
+        self.merging = false;
+        self.verify = true;
+        self.author = AuthorOverride::default();
+        self.acknowledged = None;
+        self.spell.reset();
+        self.input.set_misspelled(Vec::new());
 
         self.input.clear();
-        self.input.set_title(strings::COMMIT_TITLE.into());
+
+        let prepared = if let Some(msg) = sync::merge_msg(CWD)? {
+            Some((msg, PrepareCommitMsgSource::Merge))
+        } else {
+            sync::squash_msg(CWD)?
+                .map(|msg| (msg, PrepareCommitMsgSource::Squash))
+        };
+        if let Some((msg, source)) = prepared {
+            if !self.prepare_merge_msg(msg, source)? {
+                return Ok(());
+            }
+        }
+
+        self.update_title();
         self.input.show()?;
 
+        self.assist = if self.config.conventional_commits.enabled
+            && !self.types().is_empty()
+            && !self.merging
+        {
+            Assist::PickType(0)
+        } else {
+            Assist::Off
+        };
+
+        if self.verbose {
+            // hides the previous commit's diff until the new one arrives
+            self.diff.clear(true)?;
+            self.update_diff()?;
+        }
+
         Ok(())
     }
 }
 
 impl CommitComponent {
     ///
-    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: Queue,
+        sender: &Sender<AsyncNotification>,
+        diff_options: &SharedDiffOptions,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        let mut input = TextInputComponent::new(
+            queue.clone(),
+            theme.clone(),
+            key_config.clone(),
+            config.clone(),
+            "",
+            strings::COMMIT_MSG,
+        )
+        .multiline()
+        .highlight_prefix(conventional::prefix_len);
+        if config.commit.lint_message {
+            input = input.line_limit(config.commit.body_line_length);
+        }
+
         Self {
-            queue,
             amend: None,
-            input: TextInputComponent::new(
-                theme,
-                "",
-                strings::COMMIT_MSG,
+            merging: false,
+            assist: Assist::Off,
+            author: AuthorOverride::default(),
+            spell: SpellCheck::default(),
+            acknowledged: None,
+            git_hooks: AsyncHooks::new(sender)
+                .timeout(config.hooks.timeout()),
+            git_diff: AsyncDiff::new(sender.clone()),
+            diff: DiffComponent::new(
+                &queue,
+                true,
+                diff_options.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
+            verbose: false,
+            verify: true,
+            input,
+            queue,
+            config,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_hooks.is_pending() || self.git_diff.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        match ev {
+            AsyncNotification::Hooks => self.update_hooks()?,
+            AsyncNotification::Diff => self.update_diff_result()?,
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// commits once the hooks run in the background passed
+    fn update_hooks(&mut self) -> Result<()> {
+        if self.git_hooks.is_pending() {
+            return Ok(());
+        }
+
+        match self.git_hooks.take_last()? {
+            Some(CommitHooksResult::Ok(msg, ran)) => {
+                self.finish_commit(&msg, &ran)?
+            }
+            Some(CommitHooksResult::NotOk(hook, e)) => {
+                log::error!("{} hook error: {}", hook, e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::from_report(
+                        NotificationLevel::Error,
+                        &e,
+                    )),
+                );
+            }
+            Some(CommitHooksResult::Cancelled) | None => (),
+        }
+
+        Ok(())
+    }
+
+    fn draw_hooks_output<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+    ) -> Result<()> {
+        let area = ui::centered_rect(60, 50, f.size());
+        let height = area.height.saturating_sub(2) as usize;
+
+        let output = self.git_hooks.output()?;
+        let txt = output
+            .iter()
+            .skip(output.len().saturating_sub(height))
+            .map(|l| Text::Raw(Cow::from(format!("{}\n", l))))
+            .collect::<Vec<_>>();
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(txt.iter())
+                .block(
+                    Block::default()
+                        .title(strings::COMMIT_HOOKS_TITLE)
+                        .title_style(self.theme.title(true))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                )
+                .alignment(Alignment::Left),
+            area,
+        );
+
+        Ok(())
+    }
+
+    /// refreshes the staged diff if it is currently shown
+    pub fn update(&mut self) -> Result<()> {
+        if self.verbose && self.is_visible() {
+            self.update_diff()?;
+        }
+
+        Ok(())
+    }
+
+    /// the staged diff is computed in the background,
+    /// `update_diff_result` shows it once done
+    fn update_diff(&mut self) -> Result<()> {
+        // re-runs the last request, the index may have changed
+        self.git_diff.refresh()?;
+
+        if let Some(diff) = self.git_diff.request(DiffParams {
+            path: String::new(),
+            old_path: None,
+            diff_type: DiffType::AllStaged,
+            options: self.diff.options(),
+        })? {
+            self.show_diff(diff)?;
+        }
+
+        Ok(())
+    }
+
+    fn update_diff_result(&mut self) -> Result<()> {
+        if self.verbose && self.is_visible() {
+            if let Some((_, diff)) = self.git_diff.last()? {
+                self.show_diff(diff)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn show_diff(&mut self, diff: FileDiff) -> Result<()> {
+        self.diff.update(
+            String::from(strings::COMMIT_DIFF_TITLE),
+            true,
+            diff,
+        )
+    }
+
+    fn toggle_diff(&mut self) -> Result<()> {
+        self.verbose = !self.verbose;
+        self.diff.focus(self.verbose);
+
+        if self.verbose {
+            self.update_diff()?;
         }
+
+        Ok(())
     }
 
     pub fn show_editor(&mut self) -> Result<()> {
@@ -178,54 +647,795 @@ impl CommitComponent {
         self.input.set_text(message);
         self.input.show()?;
 
+        // the stage might have changed while the editor was open
+        self.update()?;
+
         Ok(())
     }
 
+    /// the standard types (with what they mean) followed by the
+    /// ones of `conventional_commits.types`
+    fn types(&self) -> Vec<(String, String)> {
+        self.config
+            .conventional_commits
+            .standard_types
+            .iter()
+            .map(|t| (t.clone(), conventional::description(t).into()))
+            .chain(
+                self.config
+                    .conventional_commits
+                    .types
+                    .iter()
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// entries of the scope picker, the first one for none
+    fn scopes(&self) -> Vec<String> {
+        std::iter::once(String::from(strings::COMMIT_NO_SCOPE))
+            .chain(
+                self.config
+                    .conventional_commits
+                    .scopes
+                    .iter()
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// puts the prefix in front of `msg`, replacing the one it had
+    fn apply_prefix(
+        &mut self,
+        msg: &str,
+        commit_type: &str,
+        scope: &str,
+    ) {
+        self.input.set_text(format!(
+            "{}{}",
+            conventional::prefix(commit_type, scope),
+            &msg[conventional::prefix_len(msg)..]
+        ));
+        self.input.cursor_end();
+        self.update_title();
+        self.assist = Assist::Off;
+    }
+
+    /// keys of the pickers and the scope prompt, any other key while
+    /// picking skips the assist
+    fn assist_event(&mut self, ev: Event) -> bool {
+        let e = if let Event::Key(e) = ev {
+            e
+        } else {
+            return false;
+        };
+
+        match self.assist {
+            Assist::Off => {
+                if self
+                    .key_config
+                    .matches(&e, KeyAction::CommitPickType)
+                    && !self.types().is_empty()
+                {
+                    self.assist = Assist::PickType(0);
+                    return true;
+                }
+                if self
+                    .key_config
+                    .matches(&e, KeyAction::CommitAuthor)
+                {
+                    let msg = self.input.get_text().clone();
+                    self.input.set_title(String::from(
+                        strings::COMMIT_AUTHOR_TITLE,
+                    ));
+                    self.input.set_text(
+                        self.author
+                            .author
+                            .clone()
+                            .unwrap_or_default(),
+                    );
+                    self.input.cursor_end();
+                    self.assist = Assist::Author { msg };
+                    return true;
+                }
+                if self
+                    .key_config
+                    .matches(&e, KeyAction::CommitSpellSuggest)
+                    && self.config.ui.spell_check
+                {
+                    if let Some(range) = self.input.word_at_cursor() {
+                        self.suggest(range);
+                    }
+                    return true;
+                }
+                false
+            }
+            Assist::PickType(selection) => {
+                self.pick_type_event(&e, selection)
+            }
+            Assist::PickScope(ref commit_type, selection) => {
+                let commit_type = commit_type.clone();
+                self.pick_scope_event(&e, commit_type, selection)
+            }
+            Assist::Scope {
+                ref commit_type,
+                ref msg,
+            } => {
+                let (commit_type, msg) =
+                    (commit_type.clone(), msg.clone());
+                self.scope_event(&e, &commit_type, msg)
+            }
+            Assist::Author { ref msg } => {
+                let msg = msg.clone();
+                self.author_event(&e, None, false, msg)
+            }
+            Assist::AuthorDate {
+                ref author,
+                ref msg,
+            } => {
+                let (author, msg) = (author.clone(), msg.clone());
+                self.author_event(&e, author, true, msg)
+            }
+            Assist::Suggest {
+                ref range,
+                ref words,
+                selection,
+            } => {
+                let (range, words) = (range.clone(), words.clone());
+                self.suggest_event(&e, range, words, selection)
+            }
+        }
+    }
+
+    /// checks the message once typing paused, true if the misspelled
+    /// words changed and it needs to be drawn again
+    pub fn update_spell_check(&mut self) -> bool {
+        if !self.config.ui.spell_check || !self.is_visible() {
+            return false;
+        }
+
+        match self.spell.poll(self.input.get_text()) {
+            Some(Ok(words)) => {
+                self.input.set_misspelled(words);
+                true
+            }
+            Some(Err(e)) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::SPELL_CHECK_FAILED,
+                        e.to_string(),
+                    )),
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// opens the suggestions for the word at `range`
+    fn suggest(&mut self, range: Range<usize>) {
+        let word = self.input.get_text()[range.clone()].to_string();
+
+        match spell::suggestions(&word) {
+            Ok(words) if words.is_empty() => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::warning(
+                        strings::COMMIT_NO_SUGGESTIONS,
+                        word,
+                    )),
+                );
+            }
+            Ok(words) => {
+                self.assist = Assist::Suggest {
+                    range,
+                    words,
+                    selection: 0,
+                };
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::SPELL_CHECK_FAILED,
+                        e.to_string(),
+                    )),
+                );
+            }
+        }
+    }
+
+    fn suggest_event(
+        &mut self,
+        e: &KeyEvent,
+        range: Range<usize>,
+        words: Vec<String>,
+        selection: usize,
+    ) -> bool {
+        let keys = Rc::clone(&self.key_config);
+
+        if keys.matches(e, KeyAction::MoveDown) {
+            let selection =
+                (selection + 1).min(words.len().saturating_sub(1));
+            self.assist = Assist::Suggest {
+                range,
+                words,
+                selection,
+            };
+        } else if keys.matches(e, KeyAction::MoveUp) {
+            self.assist = Assist::Suggest {
+                range,
+                words,
+                selection: selection.saturating_sub(1),
+            };
+        } else if keys.matches(e, KeyAction::Enter) {
+            self.input.replace_range(range, &words[selection]);
+            self.assist = Assist::Off;
+        } else if keys.matches(e, KeyAction::ExitPopup) {
+            self.assist = Assist::Off;
+        } else {
+            self.assist = Assist::Off;
+            return false;
+        }
+
+        true
+    }
+
+    /// the author prompt and the `dating` prompt after it with the
+    /// `author` confirmed, each one is validated on enter
+    fn author_event(
+        &mut self,
+        e: &KeyEvent,
+        author: Option<String>,
+        dating: bool,
+        msg: String,
+    ) -> bool {
+        if self.key_config.matches(e, KeyAction::Enter) {
+            let text = self.input.get_text().trim().to_string();
+            let text =
+                if text.is_empty() { None } else { Some(text) };
+            let candidate = if dating {
+                AuthorOverride {
+                    author,
+                    date: text.clone(),
+                }
+            } else {
+                AuthorOverride {
+                    author: text.clone(),
+                    date: None,
+                }
+            };
+
+            if let Err(e) = sync::author_signature(CWD, &candidate) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::COMMIT_INVALID_AUTHOR,
+                        e.to_string(),
+                    )),
+                );
+            } else if !dating {
+                self.input.set_title(String::from(
+                    strings::COMMIT_DATE_TITLE,
+                ));
+                self.input.set_text(
+                    self.author.date.clone().unwrap_or_default(),
+                );
+                self.input.cursor_end();
+                self.assist =
+                    Assist::AuthorDate { author: text, msg };
+            } else {
+                self.author = candidate;
+                self.restore_msg(msg);
+            }
+        } else if self.key_config.matches(e, KeyAction::ExitPopup) {
+            self.restore_msg(msg);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// back to the message typed before a prompt
+    fn restore_msg(&mut self, msg: String) {
+        self.input.set_text(msg);
+        self.input.cursor_end();
+        self.update_title();
+        self.assist = Assist::Off;
+    }
+
+    fn pick_type_event(
+        &mut self,
+        e: &KeyEvent,
+        selection: usize,
+    ) -> bool {
+        let keys = Rc::clone(&self.key_config);
+        let types = self.types();
+
+        if keys.matches(e, KeyAction::MoveDown) {
+            self.assist = Assist::PickType(
+                (selection + 1).min(types.len().saturating_sub(1)),
+            );
+        } else if keys.matches(e, KeyAction::MoveUp) {
+            self.assist =
+                Assist::PickType(selection.saturating_sub(1));
+        } else if keys.matches(e, KeyAction::Enter) {
+            let commit_type = types[selection].0.clone();
+            if self.config.conventional_commits.scopes.is_empty() {
+                self.input.set_title(format!(
+                    "{}: {}",
+                    strings::COMMIT_SCOPE_TITLE,
+                    commit_type
+                ));
+                let msg = self.input.get_text().clone();
+                self.input.clear();
+                self.assist = Assist::Scope { commit_type, msg };
+            } else {
+                self.assist = Assist::PickScope(commit_type, 0);
+            }
+        } else if keys.matches(e, KeyAction::ExitPopup) {
+            self.assist = Assist::Off;
+        } else {
+            self.assist = Assist::Off;
+            return false;
+        }
+
+        true
+    }
+
+    fn pick_scope_event(
+        &mut self,
+        e: &KeyEvent,
+        commit_type: String,
+        selection: usize,
+    ) -> bool {
+        let keys = Rc::clone(&self.key_config);
+        let scopes = self.scopes();
+
+        if keys.matches(e, KeyAction::MoveDown) {
+            self.assist = Assist::PickScope(
+                commit_type,
+                (selection + 1).min(scopes.len().saturating_sub(1)),
+            );
+        } else if keys.matches(e, KeyAction::MoveUp) {
+            self.assist = Assist::PickScope(
+                commit_type,
+                selection.saturating_sub(1),
+            );
+        } else if keys.matches(e, KeyAction::Enter) {
+            let scope = if selection == 0 {
+                ""
+            } else {
+                scopes[selection].as_str()
+            };
+            let msg = self.input.get_text().clone();
+            self.apply_prefix(&msg, &commit_type, scope);
+        } else if keys.matches(e, KeyAction::ExitPopup) {
+            self.assist = Assist::Off;
+        } else {
+            self.assist = Assist::Off;
+            return false;
+        }
+
+        true
+    }
+
+    /// the message typed before comes back on cancel
+    fn scope_event(
+        &mut self,
+        e: &KeyEvent,
+        commit_type: &str,
+        msg: String,
+    ) -> bool {
+        if self.key_config.matches(e, KeyAction::Enter) {
+            let scope = self.input.get_text().clone();
+            self.apply_prefix(&msg, commit_type, &scope);
+        } else if self.key_config.matches(e, KeyAction::ExitPopup) {
+            self.restore_msg(msg);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    fn lint_violations(&self) -> Vec<String> {
+        let config = &self.config.commit;
+        if config.lint == CommitLintMode::Off {
+            return Vec::new();
+        }
+
+        commit_lint::lint(
+            self.input.get_text(),
+            config.subject_max_len,
+            config.body_wrap,
+        )
+    }
+
+    /// length of the subject on the top border of `input_area`
+    fn draw_subject_counter<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        input_area: Rect,
+    ) {
+        if !self.config.commit.lint_message {
+            return;
+        }
+
+        let len = self
+            .input
+            .get_text()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .width();
+        let style = if len >= SUBJECT_HARD_LIMIT {
+            self.theme.notification(NotificationLevel::Error)
+        } else if len >= self.config.commit.subject_max_len {
+            self.theme.notification(NotificationLevel::Warning)
+        } else {
+            self.theme.text(false, false)
+        };
+
+        let counter = format!(" {} ", len);
+        let width = u16::try_from(counter.len()).unwrap_or_default();
+        if input_area.width < width + 2 {
+            return;
+        }
+
+        let area = Rect {
+            x: input_area.right() - width - 1,
+            width,
+            height: 1,
+            ..input_area
+        };
+        f.render_widget(
+            Paragraph::new(
+                [Text::Styled(Cow::from(counter), style)].iter(),
+            ),
+            area,
+        );
+    }
+
+    /// violations right below the message in `input_area`
+    fn draw_lint<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        input_area: Rect,
+    ) {
+        let mut violations = self.lint_violations();
+        violations.extend(commit_lint::unusual_chars(
+            self.input.get_text(),
+        ));
+        if violations.is_empty() {
+            return;
+        }
+
+        let y = input_area.y + input_area.height;
+        let height = u16::try_from(violations.len())
+            .unwrap_or(u16::MAX)
+            .saturating_add(2)
+            .min(f.size().height.saturating_sub(y));
+        let area = Rect {
+            y,
+            height,
+            ..input_area
+        };
+
+        let style = self.theme.notification(
+            if self.config.commit.lint == CommitLintMode::Block {
+                NotificationLevel::Error
+            } else {
+                NotificationLevel::Warning
+            },
+        );
+
+        let txt = violations
+            .iter()
+            .map(|v| {
+                Text::Styled(Cow::from(format!("{}\n", v)), style)
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(txt.iter()).block(
+                Block::default()
+                    .title(strings::COMMIT_LINT_TITLE)
+                    .borders(Borders::ALL)
+                    .border_style(style),
+            ),
+            area,
+        );
+    }
+
+    fn draw_picker<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        title: &str,
+        entries: &[String],
+        selection: usize,
+    ) {
+        let height =
+            u16::try_from(entries.len()).unwrap_or(u16::MAX) + 2;
+        let area = ui::centered_rect_absolute(50, height, f.size());
+
+        let txt = entries
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                Text::Styled(
+                    Cow::from(format!("{}\n", t)),
+                    self.theme.text(true, i == selection),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(txt.iter())
+                .block(
+                    Block::default()
+                        .title(title)
+                        .title_style(self.theme.title(true))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                )
+                .scroll(
+                    u16::try_from(selection)
+                        .unwrap_or_default()
+                        .saturating_sub(
+                            area.height.saturating_sub(3),
+                        ),
+                )
+                .alignment(Alignment::Left),
+            area,
+        );
+    }
+
     fn commit(&mut self) -> Result<()> {
+        if self.config.conventional_commits.enabled {
+            if let Err(e) = conventional::validate(
+                self.input.get_text(),
+                &self
+                    .types()
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            ) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::COMMIT_NOT_CONVENTIONAL,
+                        e.to_string(),
+                    )),
+                );
+                return Ok(());
+            }
+        }
+
+        if self.config.commit.lint == CommitLintMode::Block {
+            let violations = self.lint_violations();
+            if !violations.is_empty() {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::COMMIT_LINT_FAILED,
+                        violations.join("\n"),
+                    )),
+                );
+                return Ok(());
+            }
+        }
+
+        let unusual =
+            commit_lint::unusual_chars(self.input.get_text());
+        if !unusual.is_empty()
+            && self.acknowledged.as_ref()
+                != Some(self.input.get_text())
+        {
+            self.acknowledged = Some(self.input.get_text().clone());
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::warning(
+                    strings::COMMIT_UNUSUAL_CHARS,
+                    unusual.join("\n"),
+                ),
+            ));
+            return Ok(());
+        }
+
         self.commit_msg(self.input.get_text().clone())
     }
 
+    fn update_title(&mut self) {
+        let title = if self.amend.is_some() {
+            strings::COMMIT_TITLE_AMEND
+        } else if self.merging {
+            strings::COMMIT_TITLE_MERGE
+        } else {
+            strings::COMMIT_TITLE
+        };
+
+        let mut title = String::from(title);
+        if !self.verify {
+            title.push_str(strings::COMMIT_TITLE_NO_VERIFY);
+        }
+        if !self.author.is_empty() {
+            let parts = [&self.author.author, &self.author.date];
+            title = format!(
+                "{}{} {}",
+                title,
+                strings::COMMIT_TITLE_AUTHOR,
+                parts
+                    .iter()
+                    .filter_map(|part| part.as_deref())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        self.input.set_title(title);
+    }
+
+    /// with the author override if there is one
+    fn commit_or_amend(&self, msg: &str) -> Result<CommitId> {
+        if self.author.is_empty() {
+            return Ok(match self.amend {
+                Some(amend) => sync::amend(CWD, amend, msg)?,
+                None => sync::commit(CWD, msg)?,
+            });
+        }
+
+        let author = sync::author_signature(CWD, &self.author)?;
+        Ok(match self.amend {
+            Some(amend) => sync::amend_as(CWD, amend, msg, &author)?,
+            None => sync::commit_as(CWD, msg, &author)?,
+        })
+    }
+
     fn commit_msg(&mut self, msg: String) -> Result<()> {
-        let mut msg = msg;
-        if let HookResult::NotOk(e) =
-            sync::hooks_commit_msg(CWD, &mut msg)?
+        if self.config.commit.trailing_whitespace
+            == TrailingWhitespace::Strip
+            && !self.strip_whitespace()
         {
-            log::error!("commit-msg hook error: {}", e);
-            self.queue.borrow_mut().push_back(
-                InternalEvent::ShowErrorMsg(format!(
-                    "commit-msg hook error:\n{}",
-                    e
-                )),
-            );
             return Ok(());
         }
 
-        let res = if let Some(amend) = self.amend {
-            sync::amend(CWD, amend, &msg)
+        // `--no-verify` skips `pre-commit` and `commit-msg`,
+        // otherwise they run in the background and we commit in
+        // `update_git` once they passed
+        if self.verify {
+            self.git_hooks.run_commit_hooks(msg)?;
+            Ok(())
         } else {
-            sync::commit(CWD, &msg)
+            self.finish_commit(&msg, &[])
+        }
+    }
+
+    /// cleans the stage before the hooks see it, false if that failed
+    fn strip_whitespace(&self) -> bool {
+        match sync::strip_staged_whitespace(CWD) {
+            Ok(cleaned) => {
+                if !cleaned.is_empty() {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Notify(Notification::success(
+                            format!(
+                                "{} {}",
+                                strings::NOTIFY_WHITESPACE_STRIPPED,
+                                cleaned.len()
+                            ),
+                        )),
+                    );
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+                }
+                true
+            }
+            Err(e) => {
+                log::error!("strip whitespace error: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::WHITESPACE_STRIP_FAILED,
+                        e.to_string(),
+                    )),
+                );
+                false
+            }
+        }
+    }
+
+    /// `hooks_passed` are mentioned in the notification
+    fn finish_commit(
+        &mut self,
+        msg: &str,
+        hooks_passed: &[&str],
+    ) -> Result<()> {
+        // a squash was merged (and ran `post-merge`) before already
+        let merge_commit =
+            self.merging && sync::merge_msg(CWD)?.is_some();
+
+        let id = match self.commit_or_amend(msg) {
+            Ok(id) => id,
+            Err(e) => {
+                log::error!("commit error: {}", &e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        "commit failed",
+                        e.to_string(),
+                    )),
+                );
+                return Ok(());
+            }
         };
-        if let Err(e) = res {
-            log::error!("commit error: {}", &e);
-            self.queue.borrow_mut().push_back(
-                InternalEvent::ShowErrorMsg(format!(
-                    "commit failed:\n{}",
-                    &e
-                )),
-            );
-            return Ok(());
+
+        if let Some(amend) = self.amend {
+            if let Some(e) = sync::hooks_post_rewrite(
+                CWD,
+                RewriteCommand::Amend,
+                &[(amend, id)],
+                self.config.hooks.timeout(),
+            )?
+            .error()
+            {
+                log::error!("post-rewrite hook error: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::from_report(
+                        NotificationLevel::Warning,
+                        &e,
+                    )),
+                );
+            }
         }
 
-        if let HookResult::NotOk(e) = sync::hooks_post_commit(CWD)? {
+        if let Some(e) =
+            sync::hooks_post_commit(CWD, self.config.hooks.timeout())?
+                .error()
+        {
             log::error!("post-commit hook error: {}", e);
-            self.queue.borrow_mut().push_back(
-                InternalEvent::ShowErrorMsg(format!(
-                    "post-commit hook error:\n{}",
-                    e
-                )),
-            );
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::from_report(
+                    NotificationLevel::Warning,
+                    &e,
+                ),
+            ));
+        }
+
+        if merge_commit {
+            if let Some(e) = sync::hooks_post_merge(
+                CWD,
+                false,
+                self.config.hooks.timeout(),
+            )?
+            .error()
+            {
+                log::error!("post-merge hook error: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::from_report(
+                        NotificationLevel::Warning,
+                        &e,
+                    )),
+                );
+            }
         }
 
+        self.queue.borrow_mut().push_back(InternalEvent::Notify(
+            Notification::success(format!(
+                "{} {}{}",
+                if self.amend.is_some() {
+                    strings::NOTIFY_AMENDED
+                } else {
+                    strings::NOTIFY_COMMITTED
+                },
+                id.to_string().chars().take(7).collect::<String>(),
+                if hooks_passed.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        " ({} {})",
+                        hooks_passed.join(", "),
+                        strings::NOTIFY_HOOKS_PASSED
+                    )
+                }
+            )),
+        ));
+
         self.hide();
 
         self.queue
@@ -239,8 +1449,50 @@ impl CommitComponent {
         !self.input.get_text().is_empty()
     }
 
+    /// runs `prepare-commit-msg` on the message of the (squash)
+    /// merge like `git commit` does and puts the result into the
+    /// input, returns false if the hook rejected the commit
+    fn prepare_merge_msg(
+        &mut self,
+        msg: String,
+        source: PrepareCommitMsgSource,
+    ) -> Result<bool> {
+        let mut msg = msg;
+        let res = sync::hooks_prepare_commit_msg(
+            CWD,
+            source,
+            &mut msg,
+            self.config.hooks.timeout(),
+        )?;
+
+        if let Some(e) = res.error() {
+            log::error!("prepare-commit-msg hook error: {}", e);
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::from_report(
+                    NotificationLevel::Error,
+                    &e,
+                ),
+            ));
+            return Ok(false);
+        }
+
+        // git strips the comments (e.g. the list of conflicts) too
+        let msg = msg
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.merging = true;
+        self.input.set_text(msg.trim_end().to_string());
+        self.input.cursor_end();
+
+        Ok(true)
+    }
+
     fn can_amend(&self) -> bool {
-        self.amend.is_none()
+        !self.merging
+            && self.amend.is_none()
             && sync::get_head(CWD).is_ok()
             && self.input.get_text().is_empty()
     }
@@ -251,7 +1503,7 @@ impl CommitComponent {
 
         let details = sync::get_commit_details(CWD, id)?;
 
-        self.input.set_title(strings::COMMIT_TITLE_AMEND.into());
+        self.update_title();
 
         if let Some(msg) = details.message {
             self.input.set_text(msg.combine());
@@ -1,15 +1,18 @@
 use crate::{
     components::{
-        dialog_paragraph, utils::time_to_string, CommandBlocking,
-        CommandInfo, Component, DrawableComponent, ScrollType,
+        dialog_paragraph, CommandBlocking, CommandInfo, Component,
+        DrawableComponent, ScrollType,
     },
-    keys,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
     strings::{self, commands, order},
     ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, CommitDetails, CommitId, CommitMessage},
+    sync::{
+        self, CommitDetails, CommitId, CommitMessage, SignatureStatus,
+    },
     CWD,
 };
 use crossterm::event::Event;
@@ -26,8 +29,11 @@ use tui::{
 
 pub struct DetailsComponent {
     data: Option<CommitDetails>,
+    signature: Option<SignatureStatus>,
     tags: Vec<String>,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
     focused: bool,
     current_size: Cell<(u16, u16)>,
     scroll_top: Cell<usize>,
@@ -38,11 +44,19 @@ type WrappedCommitMessage<'a> =
 
 impl DetailsComponent {
     ///
-    pub const fn new(theme: SharedTheme, focused: bool) -> Self {
+    pub const fn new(
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+        focused: bool,
+    ) -> Self {
         Self {
             data: None,
+            signature: None,
             tags: Vec::new(),
             theme,
+            key_config,
+            config,
             focused,
             current_size: Cell::new((0, 0)),
             scroll_top: Cell::new(0),
@@ -71,6 +85,54 @@ impl DetailsComponent {
         Ok(())
     }
 
+    /// `None` while the signature is being verified
+    pub fn set_signature(
+        &mut self,
+        signature: Option<SignatureStatus>,
+    ) {
+        self.signature = signature;
+    }
+
+    /// empty for unsigned commits and while verifying
+    fn get_signature_text(&self) -> Vec<Text> {
+        let (text, style) = match &self.signature {
+            None | Some(SignatureStatus::Unsigned) => return vec![],
+            Some(SignatureStatus::Good(signer)) => (
+                format!(
+                    "{} {}",
+                    strings::commit::SIGNATURE_GOOD,
+                    signer
+                ),
+                self.theme.text(true, false),
+            ),
+            Some(SignatureStatus::Bad(signer)) => (
+                format!(
+                    "{} {}",
+                    strings::commit::SIGNATURE_BAD,
+                    signer.as_deref().unwrap_or_default()
+                ),
+                self.theme.text_danger(),
+            ),
+            Some(SignatureStatus::Unknown(signer)) => (
+                format!(
+                    "{} {}",
+                    strings::commit::SIGNATURE_UNKNOWN,
+                    signer.as_deref().unwrap_or_default()
+                ),
+                self.theme.text(false, false),
+            ),
+        };
+
+        vec![
+            Text::Styled(
+                Cow::from(strings::commit::DETAILS_SIGNATURE),
+                self.theme.text(false, false),
+            ),
+            Text::Styled(Cow::from(text), style),
+            Text::Raw(Cow::from("\n")),
+        ]
+    }
+
     fn wrap_commit_details(
         message: &CommitMessage,
         width: usize,
@@ -103,11 +165,48 @@ impl DetailsComponent {
         (vec![], vec![])
     }
 
+    /// the `git notes` of the commit, set apart from the message by
+    /// an empty line and the note marker
+    fn get_wrapped_note(&self, width: usize) -> Vec<Cow<'_, str>> {
+        let note = if let Some(note) =
+            self.data.as_ref().and_then(|data| data.note.as_ref())
+        {
+            note
+        } else {
+            return vec![];
+        };
+
+        let width = width.saturating_sub(
+            strings::commit::DETAILS_NOTE_INDENT.len(),
+        );
+
+        std::iter::once(Cow::from(""))
+            .chain(
+                textwrap::wrap(note.trim_end(), width.max(1))
+                    .into_iter()
+                    .enumerate()
+                    .map(|(idx, line)| {
+                        Cow::from(format!(
+                            "{}{}",
+                            if idx == 0 {
+                                strings::commit::DETAILS_NOTE_PREFIX
+                            } else {
+                                strings::commit::DETAILS_NOTE_INDENT
+                            },
+                            line
+                        ))
+                    }),
+            )
+            .collect()
+    }
+
     fn get_number_of_lines(&self, width: usize) -> usize {
         let (wrapped_title, wrapped_message) =
             self.get_wrapped_lines(width);
 
-        wrapped_title.len() + wrapped_message.len()
+        wrapped_title.len()
+            + wrapped_message.len()
+            + self.get_wrapped_note(width).len()
     }
 
     fn get_theme_for_line(&self, bold: bool) -> Style {
@@ -130,8 +229,10 @@ impl DetailsComponent {
 
         let (wrapped_title, wrapped_message) =
             self.get_wrapped_lines(width);
+        let wrapped_note = self.get_wrapped_note(width);
+        let note_start = wrapped_title.len() + wrapped_message.len();
 
-        [&wrapped_title[..], &wrapped_message[..]]
+        [&wrapped_title[..], &wrapped_message[..], &wrapped_note[..]]
             .concat()
             .iter()
             .enumerate()
@@ -140,7 +241,15 @@ impl DetailsComponent {
             .map(|(i, line)| {
                 Text::Styled(
                     line.clone(),
-                    self.get_theme_for_line(i < wrapped_title.len()),
+                    if i >= note_start {
+                        self.theme
+                            .text(false, false)
+                            .modifier(Modifier::ITALIC)
+                    } else {
+                        self.get_theme_for_line(
+                            i < wrapped_title.len(),
+                        )
+                    },
                 )
             })
             .intersperse(newline)
@@ -149,6 +258,7 @@ impl DetailsComponent {
 
     fn get_text_info(&self) -> Vec<Text> {
         let new_line = Text::Raw(Cow::from("\n"));
+        let date_format = self.config.revlog.date_format();
 
         if let Some(ref data) = self.data {
             let mut res = vec![
@@ -169,9 +279,9 @@ impl DetailsComponent {
                     self.theme.text(false, false),
                 ),
                 Text::Styled(
-                    Cow::from(time_to_string(
+                    Cow::from(sync::format_commit_time(
                         data.author.time,
-                        false,
+                        &date_format,
                     )),
                     self.theme.text(true, false),
                 ),
@@ -197,9 +307,9 @@ impl DetailsComponent {
                         self.theme.text(false, false),
                     ),
                     Text::Styled(
-                        Cow::from(time_to_string(
+                        Cow::from(sync::format_commit_time(
                             committer.time,
-                            false,
+                            &date_format,
                         )),
                         self.theme.text(true, false),
                     ),
@@ -219,6 +329,8 @@ impl DetailsComponent {
                 new_line.clone(),
             ]);
 
+            res.extend(self.get_signature_text());
+
             if !self.tags.is_empty() {
                 res.push(Text::Styled(
                     Cow::from(strings::commit::DETAILS_TAGS),
@@ -289,7 +401,7 @@ impl DrawableComponent for DetailsComponent {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                [Constraint::Length(8), Constraint::Min(10)].as_ref(),
+                [Constraint::Length(9), Constraint::Min(10)].as_ref(),
             )
             .split(rect);
 
@@ -357,21 +469,21 @@ impl Component for DetailsComponent {
     fn event(&mut self, event: Event) -> Result<bool> {
         if self.focused {
             if let Event::Key(e) = event {
-                return match e {
-                    keys::MOVE_UP => {
-                        self.move_scroll_top(ScrollType::Up)
-                    }
-                    keys::MOVE_DOWN => {
-                        self.move_scroll_top(ScrollType::Down)
-                    }
-                    keys::HOME | keys::SHIFT_UP => {
-                        self.move_scroll_top(ScrollType::Home)
-                    }
-                    keys::END | keys::SHIFT_DOWN => {
-                        self.move_scroll_top(ScrollType::End)
-                    }
-                    _ => Ok(false),
+                let keys = &self.key_config;
+                let move_type = if keys.matches(&e, KeyAction::MoveUp)
+                {
+                    ScrollType::Up
+                } else if keys.matches(&e, KeyAction::MoveDown) {
+                    ScrollType::Down
+                } else if keys.matches(&e, KeyAction::Home) {
+                    ScrollType::Home
+                } else if keys.matches(&e, KeyAction::End) {
+                    ScrollType::End
+                } else {
+                    return Ok(false);
                 };
+
+                return self.move_scroll_top(move_type);
             }
         }
 
@@ -1,23 +1,32 @@
 mod details;
 
 use super::{
-    command_pump, event_pump, CommandBlocking, CommandInfo,
-    Component, DrawableComponent, FileTreeComponent,
+    command_pump, event_pump, utils::filetree::dir_counts,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    FileTreeComponent,
 };
 use crate::{
-    accessors, keys, queue::Queue, strings, ui::style::SharedTheme,
+    accessors,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::Queue,
+    strings,
+    ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{CommitId, CommitTags},
-    AsyncCommitFiles, AsyncNotification,
+    sync::{CommitId, CommitStats, CommitTags},
+    AsyncCommitFiles, AsyncNotification, AsyncSignature,
+    DiffLineType,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use details::DetailsComponent;
+use std::borrow::Cow;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Paragraph, Text},
     Frame,
 };
 
@@ -25,7 +34,13 @@ pub struct CommitDetailsComponent {
     details: DetailsComponent,
     file_tree: FileTreeComponent,
     git_commit_files: AsyncCommitFiles,
+    git_signature: AsyncSignature,
+    stats: Option<CommitStats>,
+    /// files a stash saved in its untracked files commit
+    untracked: usize,
     visible: bool,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
 }
 
 impl CommitDetailsComponent {
@@ -36,28 +51,51 @@ impl CommitDetailsComponent {
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
     ) -> Self {
         Self {
-            details: DetailsComponent::new(theme.clone(), false),
+            details: DetailsComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                config,
+                false,
+            ),
             git_commit_files: AsyncCommitFiles::new(sender),
+            git_signature: AsyncSignature::new(sender),
             file_tree: FileTreeComponent::new(
                 "",
                 false,
                 Some(queue.clone()),
-                theme,
+                theme.clone(),
+                key_config.clone(),
             ),
+            stats: None,
+            untracked: 0,
             visible: false,
+            theme,
+            key_config,
         }
     }
 
     fn get_files_title(&self) -> String {
         let files_count = self.file_tree.file_count();
 
-        format!(
-            "{} {}",
-            strings::commit::DETAILS_FILES_TITLE,
-            files_count
-        )
+        if self.untracked > 0 {
+            format!(
+                "{} {} ({} {})",
+                strings::commit::DETAILS_FILES_TITLE,
+                files_count,
+                self.untracked,
+                strings::commit::DETAILS_FILES_UNTRACKED
+            )
+        } else {
+            format!(
+                "{} {}",
+                strings::commit::DETAILS_FILES_TITLE,
+                files_count
+            )
+        }
     }
 
     ///
@@ -67,13 +105,21 @@ impl CommitDetailsComponent {
         tags: Option<CommitTags>,
     ) -> Result<()> {
         self.details.set_commit(id, tags)?;
+        self.update_signature(id)?;
 
         if let Some(id) = id {
             if let Some((fetched_id, res)) =
                 self.git_commit_files.current()?
             {
                 if fetched_id == id {
-                    self.file_tree.update(res.as_slice())?;
+                    let (files, stats, untracked) = res;
+                    self.untracked = untracked.len();
+                    self.file_tree.set_untracked(untracked);
+                    self.file_tree.update(files.as_slice())?;
+                    self.file_tree
+                        .set_file_stats(stats.file_stats.clone())?;
+                    self.file_tree.set_dir_counts(dir_counts(&files));
+                    self.stats = Some(stats);
                     self.file_tree.set_title(self.get_files_title());
 
                     return Ok(());
@@ -81,6 +127,9 @@ impl CommitDetailsComponent {
             }
 
             self.file_tree.clear()?;
+            self.stats = None;
+            self.untracked = 0;
+            self.file_tree.set_untracked(Vec::new());
             self.git_commit_files.fetch(id)?;
         }
 
@@ -89,15 +138,64 @@ impl CommitDetailsComponent {
         Ok(())
     }
 
+    fn update_signature(
+        &mut self,
+        id: Option<CommitId>,
+    ) -> Result<()> {
+        let signature = if let Some(id) = id {
+            let signature = self.git_signature.get(id)?;
+            if signature.is_none() {
+                self.git_signature.fetch(id)?;
+            }
+            signature
+        } else {
+            None
+        };
+
+        self.details.set_signature(signature);
+
+        Ok(())
+    }
+
     ///
     pub fn any_work_pending(&self) -> bool {
         self.git_commit_files.is_pending()
+            || self.git_signature.is_pending()
     }
 
     ///
     pub const fn files(&self) -> &FileTreeComponent {
         &self.file_tree
     }
+
+    fn get_stats_text(&self) -> Vec<Text> {
+        self.stats.as_ref().map_or_else(Vec::new, |stats| {
+            vec![
+                Text::Raw(Cow::from(format!(
+                    "{} {}, ",
+                    stats.files,
+                    strings::commit::STATS_FILES_CHANGED
+                ))),
+                Text::Styled(
+                    Cow::from(format!(
+                        "{} {}",
+                        stats.insertions,
+                        strings::commit::STATS_INSERTIONS
+                    )),
+                    self.theme.diff_line(DiffLineType::Add, false),
+                ),
+                Text::Raw(Cow::from(", ")),
+                Text::Styled(
+                    Cow::from(format!(
+                        "{} {}",
+                        stats.deletions,
+                        strings::commit::STATS_DELETIONS
+                    )),
+                    self.theme.diff_line(DiffLineType::Delete, false),
+                ),
+            ]
+        })
+    }
 }
 
 impl DrawableComponent for CommitDetailsComponent {
@@ -111,14 +209,19 @@ impl DrawableComponent for CommitDetailsComponent {
             .constraints(
                 [
                     Constraint::Percentage(60),
-                    Constraint::Percentage(40),
+                    Constraint::Length(1),
+                    Constraint::Min(3),
                 ]
                 .as_ref(),
             )
             .split(rect);
 
         self.details.draw(f, chunks[0])?;
-        self.file_tree.draw(f, chunks[1])?;
+
+        let stats = self.get_stats_text();
+        f.render_widget(Paragraph::new(stats.iter()), chunks[1]);
+
+        self.file_tree.draw(f, chunks[2])?;
 
         Ok(())
     }
@@ -148,23 +251,23 @@ impl Component for CommitDetailsComponent {
 
         if self.focused() {
             if let Event::Key(e) = ev {
-                return match e {
-                    keys::FOCUS_BELOW if (self.details.focused()) => {
-                        self.details.focus(false);
-                        self.file_tree.focus(true);
-
-                        return Ok(true);
-                    }
-                    keys::FOCUS_ABOVE
-                        if (self.file_tree.focused()) =>
-                    {
-                        self.file_tree.focus(false);
-                        self.details.focus(true);
-
-                        return Ok(true);
-                    }
-                    _ => Ok(false),
-                };
+                let keys = &self.key_config;
+
+                if keys.matches(&e, KeyAction::FocusBelow)
+                    && self.details.focused()
+                {
+                    self.details.focus(false);
+                    self.file_tree.focus(true);
+
+                    return Ok(true);
+                } else if keys.matches(&e, KeyAction::FocusAbove)
+                    && self.file_tree.focused()
+                {
+                    self.file_tree.focus(false);
+                    self.details.focus(true);
+
+                    return Ok(true);
+                }
             }
         }
 
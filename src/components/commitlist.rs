@@ -1,19 +1,21 @@
 use super::utils::logitems::{ItemBatch, LogEntry};
 use crate::{
     components::{
-        CommandBlocking, CommandInfo, Component, DrawableComponent,
-        ScrollType,
+        mouse_scroll, CommandBlocking, CommandInfo, Component,
+        DrawableComponent, ScrollType,
     },
-    keys,
-    strings::commands,
+    config::{SharedConfig, TagSort},
+    keys::{KeyAction, SharedKeyConfig},
+    strings::{self, commands},
     ui::calc_scroll_top,
     ui::style::{SharedTheme, Theme},
 };
 use anyhow::Result;
-use asyncgit::sync::Tags;
-use crossterm::event::Event;
+use asyncgit::sync::{self, CommitId, Tags};
+use crossterm::event::{Event, MouseEvent};
 use std::{
-    borrow::Cow, cell::Cell, cmp, convert::TryFrom, time::Instant,
+    borrow::Cow, cell::Cell, cmp, collections::BTreeSet,
+    convert::TryFrom, rc::Rc, time::Instant,
 };
 use tui::{
     backend::Backend,
@@ -23,35 +25,54 @@ use tui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-const ELEMENTS_PER_LINE: usize = 10;
+const ELEMENTS_PER_LINE: usize = 11;
+
+/// in front of commits marked one by one
+const MARKER: &str = "* ";
 
 ///
 pub struct CommitList {
     title: String,
     selection: usize,
+    marked: Option<usize>,
+    /// commits marked one by one, by id to survive reloads of the log
+    picked: BTreeSet<CommitId>,
     branch: Option<String>,
     count_total: usize,
     items: ItemBatch,
     scroll_state: (Instant, f32),
     tags: Option<Tags>,
     current_size: Cell<(u16, u16)>,
+    current_area: Cell<Rect>,
     scroll_top: Cell<usize>,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
 }
 
 impl CommitList {
     ///
-    pub fn new(title: &str, theme: SharedTheme) -> Self {
+    pub fn new(
+        title: &str,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
         Self {
             items: ItemBatch::default(),
             selection: 0,
+            marked: None,
+            picked: BTreeSet::new(),
             branch: None,
             count_total: 0,
             scroll_state: (Instant::now(), 0_f32),
             tags: None,
             current_size: Cell::new((0, 0)),
+            current_area: Cell::new(Rect::default()),
             scroll_top: Cell::new(0),
             theme,
+            key_config,
+            config,
             title: String::from(title),
         }
     }
@@ -61,6 +82,11 @@ impl CommitList {
         &mut self.items
     }
 
+    ///
+    pub fn set_title(&mut self, title: &str) {
+        self.title = String::from(title);
+    }
+
     ///
     pub fn set_branch(&mut self, name: Option<String>) {
         self.branch = name;
@@ -71,6 +97,64 @@ impl CommitList {
         self.selection
     }
 
+    /// clamped to the commits known so far
+    pub fn select(&mut self, idx: usize) {
+        self.selection = cmp::min(idx, self.selection_max());
+    }
+
+    /// starts a range at the selection or drops the current one
+    pub fn toggle_mark(&mut self) {
+        self.marked = if self.marked.is_some() {
+            None
+        } else {
+            Some(self.selection)
+        };
+    }
+
+    ///
+    pub fn clear_mark(&mut self) {
+        self.marked = None;
+    }
+
+    /// marks or unmarks the selected commit and moves on to the next
+    pub fn toggle_pick(&mut self) {
+        if let Some(id) = self.selected_entry().map(|e| e.id) {
+            if !self.picked.remove(&id) {
+                self.picked.insert(id);
+            }
+            self.select(self.selection + 1);
+        }
+    }
+
+    /// commits marked one by one, in no particular order
+    pub const fn picked(&self) -> &BTreeSet<CommitId> {
+        &self.picked
+    }
+
+    ///
+    pub fn has_marks(&self) -> bool {
+        self.marked.is_some() || !self.picked.is_empty()
+    }
+
+    /// drops the range and all marked commits, returns true if there
+    /// was anything to drop
+    pub fn clear_marks(&mut self) -> bool {
+        let any = self.has_marks();
+        self.marked = None;
+        self.picked.clear();
+        any
+    }
+
+    /// first index (newest commit) and length of the marked range,
+    /// only the selection if nothing is marked
+    pub fn selected_range(&self) -> (usize, usize) {
+        let mark = self.marked.unwrap_or(self.selection);
+        let start = cmp::min(mark, self.selection);
+        let end = cmp::max(mark, self.selection);
+
+        (start, end - start + 1)
+    }
+
     ///
     pub fn current_size(&self) -> (u16, u16) {
         self.current_size.get()
@@ -81,6 +165,8 @@ impl CommitList {
         self.count_total = total;
         self.selection =
             cmp::min(self.selection, self.selection_max());
+        self.marked =
+            self.marked.map(|m| cmp::min(m, self.selection_max()));
     }
 
     ///
@@ -100,7 +186,16 @@ impl CommitList {
     }
 
     ///
-    pub fn set_tags(&mut self, tags: Tags) {
+    pub fn set_tags(&mut self, mut tags: Tags) {
+        for names in tags.values_mut() {
+            match self.config.tags.sort {
+                TagSort::Name => names.sort(),
+                TagSort::Version => {
+                    names
+                        .sort_by(|a, b| sync::compare_versions(a, b));
+                }
+            }
+        }
         self.tags = Some(tags);
     }
 
@@ -111,6 +206,21 @@ impl CommitList {
         )
     }
 
+    /// moves the selection if the mouse wheel turned above the list
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> Result<bool> {
+        let mut selection_changed = false;
+
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                selection_changed |= self.move_selection(scroll)?;
+            }
+        }
+
+        Ok(selection_changed)
+    }
+
     fn move_selection(&mut self, scroll: ScrollType) -> Result<bool> {
         self.update_scroll_speed();
 
@@ -174,6 +284,7 @@ impl CommitList {
 
     fn add_entry<'a>(
         e: &'a LogEntry,
+        time: String,
         selected: bool,
         txt: &mut Vec<Text<'a>>,
         tags: Option<String>,
@@ -194,16 +305,18 @@ impl CommitList {
 
         txt.push(splitter.clone());
 
+        let time_width = UnicodeWidthStr::width(time.as_str());
+
         // commit timestamp
         txt.push(Text::Styled(
-            Cow::from(e.time.as_str()),
+            Cow::from(time),
             theme.commit_time(selected),
         ));
 
         txt.push(splitter.clone());
 
         let author_width =
-            (width.saturating_sub(19) / 3).max(3).min(20);
+            (width.saturating_sub(9 + time_width) / 3).max(3).min(20);
         let author = string_width_align(&e.author, author_width);
 
         // commit author
@@ -235,16 +348,41 @@ impl CommitList {
     }
 
     fn get_text(&self, height: usize, width: usize) -> Vec<Text> {
-        let selection = self.relative_selection();
+        let (range_start, range_len) = self.selected_range();
+        let range = range_start..range_start + range_len;
+        let offset =
+            self.scroll_top.get() + self.items.index_offset();
 
         let mut txt = Vec::with_capacity(height * ELEMENTS_PER_LINE);
+        // the marker column is only there while something is marked
+        let marker_width = if self.picked.is_empty() {
+            0
+        } else {
+            MARKER.len()
+        };
+
+        let date_format = self.config.revlog.date_format();
+        let times = self
+            .items
+            .iter()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|e| sync::format_commit_time(e.time, &date_format))
+            .collect::<Vec<_>>();
+        // relative dates differ in length, keep the columns aligned
+        let time_width = times
+            .iter()
+            .map(|t| UnicodeWidthStr::width(t.as_str()))
+            .max()
+            .unwrap_or_default();
 
-        for (idx, e) in self
+        for ((idx, e), time) in self
             .items
             .iter()
             .skip(self.scroll_top.get())
             .take(height)
             .enumerate()
+            .zip(times)
         {
             let tags = if let Some(tags) =
                 self.tags.as_ref().and_then(|t| t.get(&e.id))
@@ -254,13 +392,27 @@ impl CommitList {
                 None
             };
 
+            let selected = range.contains(&(idx + offset));
+
+            if marker_width > 0 {
+                txt.push(Text::Styled(
+                    Cow::from(if self.picked.contains(&e.id) {
+                        MARKER
+                    } else {
+                        "  "
+                    }),
+                    self.theme.commit_hash(selected),
+                ));
+            }
+
             Self::add_entry(
                 e,
-                idx + self.scroll_top.get() == selection,
+                string_width_align(&time, time_width),
+                selected,
                 &mut txt,
                 tags,
                 &self.theme,
-                width,
+                width.saturating_sub(marker_width),
             );
         }
 
@@ -284,6 +436,7 @@ impl DrawableComponent for CommitList {
             area.height.saturating_sub(2),
         );
         self.current_size.set(current_size);
+        self.current_area.set(area);
 
         let height_in_lines = self.current_size.get().1 as usize;
         let selection = self.relative_selection();
@@ -297,12 +450,21 @@ impl DrawableComponent for CommitList {
         let branch_post_fix =
             self.branch.as_ref().map(|b| format!("- {{{}}}", b));
 
+        let tags_post_fix = if self.tags.is_some()
+            && self.config.tags.sort == TagSort::Version
+        {
+            strings::LOG_TAGS_BY_VERSION
+        } else {
+            ""
+        };
+
         let title = format!(
-            "{} {}/{} {}",
+            "{} {}/{} {}{}",
             self.title,
             self.count_total.saturating_sub(self.selection),
             self.count_total,
             branch_post_fix.as_deref().unwrap_or(""),
+            tags_post_fix,
         );
 
         f.render_widget(
@@ -331,29 +493,27 @@ impl DrawableComponent for CommitList {
 impl Component for CommitList {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if let Event::Key(k) = ev {
-            let selection_changed = match k {
-                keys::MOVE_UP => {
+            let keys = Rc::clone(&self.key_config);
+            let selection_changed =
+                if keys.matches(&k, KeyAction::MoveUp) {
                     self.move_selection(ScrollType::Up)?
-                }
-                keys::MOVE_DOWN => {
+                } else if keys.matches(&k, KeyAction::MoveDown) {
                     self.move_selection(ScrollType::Down)?
-                }
-                keys::SHIFT_UP | keys::HOME => {
+                } else if keys.matches(&k, KeyAction::Home) {
                     self.move_selection(ScrollType::Home)?
-                }
-                keys::SHIFT_DOWN | keys::END => {
+                } else if keys.matches(&k, KeyAction::End) {
                     self.move_selection(ScrollType::End)?
-                }
-                keys::PAGE_UP => {
+                } else if keys.matches(&k, KeyAction::PageUp) {
                     self.move_selection(ScrollType::PageUp)?
-                }
-                keys::PAGE_DOWN => {
+                } else if keys.matches(&k, KeyAction::PageDown) {
                     self.move_selection(ScrollType::PageDown)?
-                }
-                _ => false,
-            };
+                } else {
+                    false
+                };
 
             return Ok(selection_changed);
+        } else if let Event::Mouse(m) = ev {
+            return self.handle_mouse_event(m);
         }
 
         Ok(false)
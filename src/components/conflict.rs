@@ -0,0 +1,383 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{
+        self, ConflictFile, ConflictKind, ConflictRegion,
+        ConflictSide,
+    },
+    DiffLineType, CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, convert::TryFrom, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// base, ours and theirs of a conflicted file one above the other,
+/// a side is picked per conflict and the result written and staged
+pub struct ConflictComponent {
+    path: String,
+    file: Option<ConflictFile>,
+    picks: Vec<Option<ConflictSide>>,
+    selection: usize,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl ConflictComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            path: String::new(),
+            file: None,
+            picks: Vec::new(),
+            selection: 0,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// shows nothing but an error if the file is not conflicted
+    pub fn open(&mut self, path: String) {
+        match sync::get_conflict(CWD, &path) {
+            Ok(file) => {
+                self.picks = vec![None; file.conflicts()];
+                self.selection = 0;
+                self.file = Some(file);
+                self.path = path;
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "conflict error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+    }
+
+    /// whether only the whole file can be taken
+    fn whole_file(&self) -> bool {
+        self.file
+            .as_ref()
+            .map_or(false, |file| file.regions.is_empty())
+    }
+
+    fn pick(&mut self, side: ConflictSide) {
+        if self.whole_file() {
+            self.take_file(side);
+        } else if let Some(pick) = self.picks.get_mut(self.selection)
+        {
+            *pick = Some(side);
+            // on to the next one
+            self.selection = (self.selection + 1)
+                .min(self.picks.len().saturating_sub(1));
+        }
+    }
+
+    fn take_file(&mut self, side: ConflictSide) {
+        let result = sync::resolve_conflict(CWD, &self.path, side);
+        self.done(result.map_err(Into::into));
+    }
+
+    /// writes the picked sides, once every conflict has one
+    fn write(&mut self) {
+        let picks: Option<Vec<ConflictSide>> =
+            self.picks.iter().copied().collect();
+        let content = self
+            .file
+            .as_ref()
+            .zip(picks)
+            .and_then(|(file, picks)| file.resolve(&picks));
+
+        if let Some(content) = content {
+            let result = sync::write_conflict_resolution(
+                CWD, &self.path, &content,
+            );
+            self.done(result.map_err(Into::into));
+        } else {
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::warning(strings::CONFLICT_UNPICKED, ""),
+            ));
+        }
+    }
+
+    fn done(&mut self, result: Result<()>) {
+        let event = match result {
+            Ok(()) => InternalEvent::Notify(Notification::success(
+                format!("{} {}", strings::NOTIFY_RESOLVED, self.path),
+            )),
+            Err(e) => InternalEvent::ShowErrorMsg(format!(
+                "resolve conflict error:\n{}",
+                e
+            )),
+        };
+
+        self.hide();
+        let mut queue = self.queue.borrow_mut();
+        queue.push_back(event);
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn side_style(
+        &self,
+        conflict: usize,
+        side: ConflictSide,
+    ) -> DiffLineType {
+        match self.picks.get(conflict).copied().flatten() {
+            Some(pick) if pick == side => DiffLineType::Add,
+            Some(_) => DiffLineType::Delete,
+            None => DiffLineType::None,
+        }
+    }
+
+    /// the lines and the one the selected conflict starts at
+    fn get_text(&self, file: &ConflictFile) -> (Vec<Text>, u16) {
+        let mut txt = Vec::new();
+        let mut selected_line = 0;
+        let styled =
+            |line: &str, typ: DiffLineType, selected: bool| {
+                Text::Styled(
+                    Cow::from(format!(
+                        "{}\n",
+                        line.trim_end_matches('\n')
+                    )),
+                    self.theme.diff_line(typ, selected),
+                )
+            };
+
+        if file.regions.is_empty() {
+            txt.push(styled(
+                match file.kind {
+                    ConflictKind::DeletedByUs => {
+                        strings::CONFLICT_DELETED_BY_US
+                    }
+                    _ => strings::CONFLICT_DELETED_BY_THEM,
+                },
+                DiffLineType::Header,
+                false,
+            ));
+            txt.push(styled(
+                strings::CONFLICT_TAKE_FILE,
+                DiffLineType::None,
+                false,
+            ));
+            return (txt, 0);
+        }
+
+        if file.kind == ConflictKind::BothAdded {
+            txt.push(styled(
+                strings::CONFLICT_BOTH_ADDED,
+                DiffLineType::Header,
+                false,
+            ));
+        }
+
+        let mut conflict = 0;
+        for region in &file.regions {
+            match region {
+                ConflictRegion::Merged(lines) => {
+                    for line in lines {
+                        txt.push(styled(
+                            line,
+                            DiffLineType::None,
+                            false,
+                        ));
+                    }
+                }
+                ConflictRegion::Conflict { base, ours, theirs } => {
+                    let selected = conflict == self.selection;
+                    if selected {
+                        selected_line = txt.len();
+                    }
+
+                    let parts = [
+                        (
+                            strings::CONFLICT_MARKER_OURS,
+                            ours,
+                            self.side_style(
+                                conflict,
+                                ConflictSide::Ours,
+                            ),
+                        ),
+                        (
+                            strings::CONFLICT_MARKER_BASE,
+                            base,
+                            DiffLineType::Header,
+                        ),
+                        (
+                            strings::CONFLICT_MARKER_THEIRS,
+                            theirs,
+                            self.side_style(
+                                conflict,
+                                ConflictSide::Theirs,
+                            ),
+                        ),
+                    ];
+                    for (marker, lines, typ) in &parts {
+                        txt.push(styled(
+                            marker,
+                            DiffLineType::Header,
+                            selected,
+                        ));
+                        for line in *lines {
+                            txt.push(styled(line, *typ, false));
+                        }
+                    }
+                    txt.push(styled(
+                        strings::CONFLICT_MARKER_END,
+                        DiffLineType::Header,
+                        selected,
+                    ));
+
+                    conflict += 1;
+                }
+            }
+        }
+
+        (txt, u16::try_from(selected_line).unwrap_or(u16::MAX))
+    }
+}
+
+impl DrawableComponent for ConflictComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if let Some(file) = &self.file {
+            let area = ui::centered_rect(90, 80, f.size());
+            let (txt, selected_line) = self.get_text(file);
+            let picked = self
+                .picks
+                .iter()
+                .filter(|pick| pick.is_some())
+                .count();
+            let title = format!(
+                "{} {} ({}/{})",
+                strings::CONFLICT_TITLE,
+                self.path,
+                picked,
+                self.picks.len()
+            );
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(txt.iter())
+                    .block(
+                        Block::default()
+                            .title(title.as_str())
+                            .borders(Borders::ALL)
+                            .border_style(self.theme.block(true))
+                            .title_style(self.theme.title(true)),
+                    )
+                    .scroll(
+                        selected_line.saturating_sub(area.height / 3),
+                    ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ConflictComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            let whole_file = self.whole_file();
+
+            out.push(CommandInfo::new(
+                commands::CONFLICT_NAVIGATE,
+                self.picks.len() > 1,
+                !whole_file,
+            ));
+            out.push(CommandInfo::new(
+                commands::CONFLICT_PICK_OURS,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CONFLICT_PICK_THEIRS,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CONFLICT_WRITE,
+                self.picks.iter().all(Option::is_some),
+                !whole_file,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.is_visible() {
+            return Ok(false);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if keys.matches(&k, KeyAction::ExitPopup) {
+                self.hide();
+            } else if keys.matches(&k, KeyAction::MoveUp) {
+                self.selection = self.selection.saturating_sub(1);
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                self.selection = (self.selection + 1)
+                    .min(self.picks.len().saturating_sub(1));
+            } else if keys.matches(&k, KeyAction::StatusTakeOurs) {
+                self.pick(ConflictSide::Ours);
+            } else if keys.matches(&k, KeyAction::StatusTakeTheirs) {
+                self.pick(ConflictSide::Theirs);
+            } else if keys.matches(&k, KeyAction::Enter)
+                && !self.whole_file()
+            {
+                self.write();
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.file.is_some()
+    }
+
+    fn hide(&mut self) {
+        self.file = None;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
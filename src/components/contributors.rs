@@ -0,0 +1,326 @@
+use super::{
+    mouse_scroll, visibility_blocking, CommandBlocking, CommandInfo,
+    Component, DrawableComponent, ScrollType,
+};
+use crate::{
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{format_commit_time, AuthorSummary, DateFormat},
+    AsyncNotification, AsyncShortlog,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::{Event, MouseEvent};
+use std::{borrow::Cow, cell::Cell, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// commits per author of the current branch
+pub struct ContributorsComponent {
+    git_shortlog: AsyncShortlog,
+    items: Vec<AuthorSummary>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_area: Cell<Rect>,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl ContributorsComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            git_shortlog: AsyncShortlog::new(sender),
+            items: Vec::new(),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_area: Cell::new(Rect::default()),
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.items.clear();
+        self.selection = 0;
+        self.git_shortlog.fetch_summary()?;
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_shortlog.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.visible && ev == AsyncNotification::Shortlog {
+            if let Some(items) = self.git_shortlog.summary()? {
+                self.items = items;
+            }
+
+            self.fetch_lines()?;
+        }
+
+        Ok(())
+    }
+
+    fn fetch_lines(&mut self) -> Result<()> {
+        if let Some(email) = self.selected().map(|a| a.email.clone())
+        {
+            self.git_shortlog.fetch_lines(&email)?;
+        }
+
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&AuthorSummary> {
+        self.items.get(self.selection)
+    }
+
+    /// moves the selection if the mouse wheel turned above the list
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> Result<()> {
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                self.move_selection(scroll)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) -> Result<()> {
+        let max = self.items.len().saturating_sub(1);
+        let old = self.selection;
+
+        self.selection = match scroll {
+            ScrollType::Up => old.saturating_sub(1),
+            ScrollType::Down => old.saturating_add(1).min(max),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+            _ => old,
+        };
+
+        if old != self.selection {
+            self.fetch_lines()?;
+        }
+
+        Ok(())
+    }
+
+    fn filter_log(&mut self) {
+        if let Some(email) = self.selected().map(|a| a.email.clone())
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::FilterLogByAuthor(email));
+            self.hide();
+        }
+    }
+
+    fn title(&self) -> String {
+        let total: usize = self.items.iter().map(|a| a.commits).sum();
+
+        format!("{} ({} commits)", strings::CONTRIBUTORS_TITLE, total)
+    }
+
+    fn details_text(&self) -> Vec<Text> {
+        let author = if let Some(author) = self.selected() {
+            author
+        } else {
+            return vec![Text::Raw(Cow::from(
+                if self.any_work_pending() {
+                    strings::LOADING_TEXT
+                } else {
+                    ""
+                },
+            ))];
+        };
+
+        let date =
+            |time| format_commit_time(time, &DateFormat::Short);
+
+        let lines = match self.git_shortlog.lines(&author.email) {
+            Ok(Some(lines)) => {
+                format!("+{} -{}", lines.insertions, lines.deletions)
+            }
+            _ => String::from(strings::LOADING_TEXT),
+        };
+
+        vec![
+            Text::Styled(
+                Cow::from(format!(
+                    "{} <{}>\n",
+                    author.name, author.email
+                )),
+                self.theme.commit_author(false),
+            ),
+            Text::Raw(Cow::from(format!(
+                "commits: {}, first: {}, last: {}\n",
+                author.commits,
+                date(author.first_time),
+                date(author.last_time)
+            ))),
+            Text::Raw(Cow::from(format!("lines: {}", lines))),
+        ]
+    }
+}
+
+impl DrawableComponent for ContributorsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect(70, 70, f.size());
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Min(3), Constraint::Length(5)]
+                        .as_ref(),
+                )
+                .split(area);
+
+            self.current_area.set(chunks[0]);
+
+            let height = chunks[0].height.saturating_sub(2) as usize;
+            self.scroll_top.set(ui::calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            let items = self
+                .items
+                .iter()
+                .enumerate()
+                .map(|(idx, author)| {
+                    Text::Styled(
+                        Cow::from(format!(
+                            "{:>6}  {} <{}>\n",
+                            author.commits, author.name, author.email
+                        )),
+                        self.theme.text(true, idx == self.selection),
+                    )
+                })
+                .skip(self.scroll_top.get());
+
+            f.render_widget(Clear, area);
+
+            ui::draw_list(
+                f,
+                chunks[0],
+                &self.title(),
+                items,
+                None,
+                true,
+                &self.theme,
+            );
+
+            f.render_widget(
+                Paragraph::new(self.details_text().iter()).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(false)),
+                ),
+                chunks[1],
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ContributorsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(commands::SCROLL, true, true));
+
+            out.push(CommandInfo::new(
+                commands::CONTRIBUTORS_FILTER,
+                self.selected().is_some(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                let keys = Rc::clone(&self.key_config);
+                if keys.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                } else if keys.matches(&e, KeyAction::MoveUp) {
+                    self.move_selection(ScrollType::Up)?;
+                } else if keys.matches(&e, KeyAction::MoveDown) {
+                    self.move_selection(ScrollType::Down)?;
+                } else if keys.matches(&e, KeyAction::Home) {
+                    self.move_selection(ScrollType::Home)?;
+                } else if keys.matches(&e, KeyAction::End) {
+                    self.move_selection(ScrollType::End)?;
+                } else if keys
+                    .matches(&e, KeyAction::ContributorsFilter)
+                {
+                    self.filter_log();
+                }
+            } else if let Event::Mouse(m) = ev {
+                self.handle_mouse_event(m)?;
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{
+        notifications::{Notification, NotificationLevel},
+        style::SharedTheme,
+    },
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, CommitId, StashApplyOutcome},
+    CWD,
+};
+use crossterm::event::Event;
+use std::time::Duration;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// where the new branch starts
+#[derive(Copy, Clone)]
+enum BranchStart {
+    /// a commit of the log, the branch is not checked out
+    Commit(CommitId),
+    /// where the stash was made, the stash is popped onto the
+    /// checked out branch
+    Stash(CommitId),
+}
+
+/// asks for the name of a branch to create at a commit of the log or
+/// to pop a stash onto
+pub struct CreateBranchComponent {
+    input: TextInputComponent,
+    start: Option<BranchStart>,
+    hooks_timeout: Option<Duration>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CreateBranchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for CreateBranchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::CREATE_BRANCH_CONFIRM_MSG,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.create_branch();
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl CreateBranchComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            hooks_timeout: config.hooks.timeout(),
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::CREATE_BRANCH_POPUP_TITLE,
+                strings::CREATE_BRANCH_POPUP_MSG,
+            ),
+            queue,
+            key_config,
+            start: None,
+        }
+    }
+
+    ///
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        self.start = Some(BranchStart::Commit(id));
+        self.input
+            .set_title(strings::CREATE_BRANCH_POPUP_TITLE.into());
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// like `git stash branch`
+    pub fn open_stash(&mut self, id: CommitId) -> Result<()> {
+        self.start = Some(BranchStart::Stash(id));
+        self.input
+            .set_title(strings::STASH_BRANCH_POPUP_TITLE.into());
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn create_branch(&mut self) {
+        match self.start {
+            Some(BranchStart::Commit(id)) => self.branch_at(id),
+            Some(BranchStart::Stash(id)) => self.stash_branch(id),
+            None => (),
+        }
+    }
+
+    /// pops the stash onto the new branch, conflicts are shown in the
+    /// status tab
+    fn stash_branch(&mut self, stash_id: CommitId) {
+        let name = self.input.get_text().clone();
+        self.hide();
+
+        match sync::stash_branch(
+            CWD,
+            stash_id,
+            &name,
+            self.hooks_timeout,
+        ) {
+            Ok((outcome, hook)) => {
+                if let Some(e) = hook.error() {
+                    log::error!("post-checkout hook error: {}", e);
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Notify(
+                            Notification::from_report(
+                                NotificationLevel::Warning,
+                                &e,
+                            ),
+                        ),
+                    );
+                }
+
+                let notification = match outcome {
+                    StashApplyOutcome::Conflicts(paths) => {
+                        Notification::warning(
+                            strings::STASH_APPLY_CONFLICTS,
+                            paths.join("\n"),
+                        )
+                    }
+                    _ => Notification::success(format!(
+                        "{} {}",
+                        strings::NOTIFY_STASH_BRANCH,
+                        name
+                    )),
+                };
+
+                self.input.clear();
+                let mut queue = self.queue.borrow_mut();
+                queue.push_back(InternalEvent::Notify(notification));
+                queue.push_back(InternalEvent::TabSwitch);
+                queue.push_back(InternalEvent::Update(
+                    NeedsUpdate::ALL,
+                ));
+            }
+            Err(e) => {
+                log::error!("e: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        "stash branch failed",
+                        e.to_string(),
+                    )),
+                );
+            }
+        }
+    }
+
+    /// the branch is not checked out
+    fn branch_at(&mut self, commit_id: CommitId) {
+        match sync::create_branch(
+            CWD,
+            self.input.get_text(),
+            commit_id,
+        ) {
+            Ok(()) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::success(
+                        format!(
+                            "{} {}",
+                            strings::NOTIFY_BRANCH_CREATED,
+                            self.input.get_text()
+                        ),
+                    )),
+                );
+
+                self.input.clear();
+                self.hide();
+
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+            Err(e) => {
+                self.hide();
+                log::error!("e: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        "branch failed",
+                        e.to_string(),
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -1,15 +1,36 @@
-use super::{CommandBlocking, DrawableComponent, ScrollType};
+use super::{
+    hexview::{format_row, BYTES_PER_ROW},
+    mouse_scroll,
+    utils::search::{self, Search},
+    CommandBlocking, DrawableComponent, GoTo, ScrollType,
+};
 use crate::{
+    clipboard,
     components::{CommandInfo, Component},
-    keys,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings::{self, commands},
-    ui::{calc_scroll_top, style::SharedTheme},
+    ui::{
+        calc_scroll_top,
+        graphics::GraphicsProtocol,
+        notifications::Notification,
+        style::{SharedTheme, Theme},
+    },
+};
+use asyncgit::{
+    hash,
+    sync::{
+        self, BinarySide, DiffOptions, DiffWhitespace, ImageInfo,
+    },
+    DiffLine, DiffLineType, FileDiff, CWD,
 };
-use asyncgit::{hash, sync, DiffLine, DiffLineType, FileDiff, CWD};
 use bytesize::ByteSize;
-use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use crossterm::event::{Event, MouseEvent};
+use std::{
+    borrow::Cow, cell::Cell, cmp, convert::TryFrom, path::Path,
+    rc::Rc,
+};
 use tui::{
     backend::Backend,
     layout::Rect,
@@ -20,11 +41,37 @@ use tui::{
 
 use anyhow::Result;
 
+/// `c` of a hunk header like `@@ -a,b +c,d @@`
+fn hunk_new_start(header: &str) -> Option<usize> {
+    header
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?
+        .split(',')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// `mode changed: 100644 → 100755`
+fn mode_change((old, new): (u32, u32)) -> String {
+    format!(
+        "{}{:o} \u{2192} {:o}",
+        strings::DIFF_MODE_CHANGED,
+        old,
+        new
+    )
+}
+
+/// how diffs are requested, changed from any diff view
+pub type SharedDiffOptions = Rc<Cell<DiffOptions>>;
+
 #[derive(Default)]
 struct Current {
     path: String,
     is_stage: bool,
     hash: u64,
+    /// what the shown hunks were made with
+    options: DiffOptions,
 }
 
 ///
@@ -34,27 +81,50 @@ pub struct DiffComponent {
     selection: usize,
     selected_hunk: Option<usize>,
     current_size: Cell<(u16, u16)>,
+    current_area: Cell<Rect>,
+    /// where the terminal draws the picture of a binary diff
+    image_area: Cell<Option<Rect>>,
+    search: Option<Search>,
     focused: bool,
     current: Current,
+    options: SharedDiffOptions,
     scroll_top: Cell<usize>,
-    queue: Option<Queue>,
+    /// hunks can't be staged or reset
+    immutable: bool,
+    queue: Queue,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
 }
 
 impl DiffComponent {
     ///
-    pub fn new(queue: Option<Queue>, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: &Queue,
+        immutable: bool,
+        options: SharedDiffOptions,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
         Self {
             focused: false,
-            queue,
+            immutable,
+            queue: queue.clone(),
             current: Current::default(),
+            options,
             pending: false,
             selected_hunk: None,
             diff: None,
             current_size: Cell::new((0, 0)),
+            current_area: Cell::new(Rect::default()),
+            image_area: Cell::new(None),
+            search: None,
             selection: 0,
             scroll_top: Cell::new(0),
             theme,
+            key_config,
+            config,
         }
     }
     ///
@@ -64,10 +134,18 @@ impl DiffComponent {
             .map(|diff| diff.lines > 1)
             .unwrap_or_default()
     }
+    /// to request diffs with
+    pub fn options(&self) -> DiffOptions {
+        self.options.get()
+    }
     ///
     pub fn current(&self) -> (String, bool) {
         (self.current.path.clone(), self.current.is_stage)
     }
+    /// line of the new file version at the cursor
+    pub fn selected_line_number(&self) -> Option<usize> {
+        self.diff.as_ref()?.new_line_number(self.selection)
+    }
     ///
     pub fn clear(&mut self, pending: bool) -> Result<()> {
         self.current = Current::default();
@@ -89,12 +167,15 @@ impl DiffComponent {
         self.pending = false;
 
         let hash = hash(&diff);
+        // an equal diff might still come from other options
+        self.current.options = self.options.get();
 
         if self.current.hash != hash {
             self.current = Current {
                 path,
                 is_stage,
                 hash,
+                options: self.current.options,
             };
 
             self.selected_hunk =
@@ -108,6 +189,21 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// scrolls if the mouse wheel turned above the diff
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> Result<bool> {
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                self.move_selection(scroll)?;
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
     fn move_selection(
         &mut self,
         move_type: ScrollType,
@@ -144,6 +240,259 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// selects the target line and scrolls it into view
+    pub fn go_to(&mut self, target: GoTo) -> Result<()> {
+        if let Some(diff) = &self.diff {
+            if diff.lines > 0 {
+                self.selection = Self::line_of(diff, target)
+                    .min(diff.lines.saturating_sub(1));
+                self.selected_hunk =
+                    Self::find_selected_hunk(diff, self.selection)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// highlights the matches of `search` and selects the first line
+    /// with one from the selection on
+    pub fn set_search(
+        &mut self,
+        search: Option<Search>,
+    ) -> Result<()> {
+        self.search = search;
+
+        let lines = self.match_lines();
+        let first = lines
+            .iter()
+            .find(|line| **line >= self.selection)
+            .or_else(|| lines.first());
+        if let Some(line) = first.copied() {
+            self.select_line(line)?;
+        }
+
+        Ok(())
+    }
+
+    /// selects the next (or previous) line with a match
+    fn find_step(&mut self, forward: bool) -> Result<()> {
+        let lines = self.match_lines();
+        if let Some(line) =
+            search::step(&lines, self.selection, forward)
+        {
+            self.select_line(line)?;
+        }
+
+        Ok(())
+    }
+
+    fn select_line(&mut self, line: usize) -> Result<()> {
+        if let Some(diff) = &self.diff {
+            self.selection = line;
+            self.selected_hunk =
+                Self::find_selected_hunk(diff, self.selection)?;
+        }
+
+        Ok(())
+    }
+
+    /// indices of the lines with a match, as the lines are shown
+    fn match_lines(&self) -> Vec<usize> {
+        match (&self.search, &self.diff) {
+            (Some(search), Some(diff)) => diff
+                .hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .enumerate()
+                .filter(|(_, line)| {
+                    let shown = line
+                        .content
+                        .trim_matches(|c| c == '\n' || c == '\r')
+                        .replace('\t', "  ");
+                    !search.find(&shown).is_empty()
+                })
+                .map(|(idx, _)| idx)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// index in the diff of the first hunk line of the target or of
+    /// the first shown line after a target line that is not shown
+    fn line_of(diff: &FileDiff, target: GoTo) -> usize {
+        match target {
+            GoTo::Hunk(hunk) => diff
+                .hunks
+                .iter()
+                .take(hunk.saturating_sub(1))
+                .map(|hunk| hunk.lines.len())
+                .sum(),
+            GoTo::Line(wanted) => {
+                let mut line_number = 0;
+                for (idx, line) in diff
+                    .hunks
+                    .iter()
+                    .flat_map(|hunk| hunk.lines.iter())
+                    .enumerate()
+                {
+                    match line.line_type {
+                        DiffLineType::Header => {
+                            line_number =
+                                hunk_new_start(&line.content)
+                                    .unwrap_or(line_number);
+                        }
+                        DiffLineType::Delete => (),
+                        _ => {
+                            if line_number >= wanted {
+                                return idx;
+                            }
+                            line_number += 1;
+                        }
+                    }
+                }
+
+                diff.lines
+            }
+        }
+    }
+
+    /// the picture of a binary diff and where the terminal is to draw
+    /// it, see `add_binary_preview`
+    pub fn image(&self) -> Option<(Rect, &[u8])> {
+        let area = self.image_area.get()?;
+        let preview = self.diff.as_ref()?.binary_preview.as_ref()?;
+
+        preview.image.as_deref().map(|image| (area, image))
+    }
+
+    /// old and new size of a binary file
+    fn add_sizes(&self, res: &mut Vec<Text>, diff: &FileDiff) {
+        let is_positive = diff.size_delta >= 0;
+        let delta_byte_size =
+            ByteSize::b(diff.size_delta.abs() as u64);
+        let sign = if is_positive { "+" } else { "-" };
+        res.extend(vec![
+            Text::Raw(Cow::from("size: ")),
+            Text::Styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.0))),
+                self.theme.text(false, false),
+            ),
+            Text::Raw(Cow::from(" -> ")),
+            Text::Styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.1))),
+                self.theme.text(false, false),
+            ),
+            Text::Raw(Cow::from(" (")),
+            Text::Styled(
+                Cow::from(format!("{}{:}", sign, delta_byte_size)),
+                self.theme.diff_line(
+                    if is_positive {
+                        DiffLineType::Add
+                    } else {
+                        DiffLineType::Delete
+                    },
+                    false,
+                ),
+            ),
+            Text::Raw(Cow::from(")")),
+        ]);
+    }
+
+    /// dimensions of pictures, then room for the terminal to draw the
+    /// picture or a hex dump of the first bytes
+    fn add_binary_preview(
+        &self,
+        res: &mut Vec<Text>,
+        diff: &FileDiff,
+        height: u16,
+    ) {
+        self.image_area.set(None);
+        let preview = if let Some(preview) = &diff.binary_preview {
+            preview
+        } else {
+            return;
+        };
+
+        let image = |side: &Option<BinarySide>| side.as_ref()?.image;
+        let (old, new) = (image(&preview.old), image(&preview.new));
+        if old.is_some() || new.is_some() {
+            let size = |info: Option<ImageInfo>| {
+                info.map_or_else(
+                    || String::from("-"),
+                    |info| {
+                        format!(
+                            "{} {}x{}",
+                            info.format.name(),
+                            info.width,
+                            info.height
+                        )
+                    },
+                )
+            };
+            res.push(Text::Raw(Cow::from(format!(
+                "\n{}{} -> {}",
+                strings::DIFF_IMAGE,
+                size(old),
+                size(new)
+            ))));
+        }
+        res.push(Text::Raw(Cow::from("\n\n")));
+
+        let used = res
+            .iter()
+            .map(|text| match text {
+                Text::Raw(s) | Text::Styled(s, _) => {
+                    s.matches('\n').count()
+                }
+            })
+            .sum::<usize>();
+        let format = new.or(old).map(|info| info.format);
+        let protocol =
+            GraphicsProtocol::detect(self.config.ui.image_preview);
+
+        if let (Some(_), Some(format), Some(protocol)) =
+            (&preview.image, format, protocol)
+        {
+            let area = self.current_area.get();
+            let top = u16::try_from(used).unwrap_or(u16::MAX);
+            if protocol.supports(format)
+                && area.height > top.saturating_add(3)
+                && area.width > 2
+            {
+                self.image_area.set(Some(Rect::new(
+                    area.x + 1,
+                    area.y + 1 + top,
+                    area.width - 2,
+                    area.height - 2 - top,
+                )));
+                return;
+            }
+        }
+
+        let rows = usize::from(height).saturating_sub(used);
+        let decimal = self.config.hexview.decimal_offsets;
+        if let Some(side) =
+            preview.new.as_ref().or(preview.old.as_ref())
+        {
+            res.extend(
+                side.head
+                    .chunks(BYTES_PER_ROW)
+                    .enumerate()
+                    .take(rows)
+                    .map(|(row, bytes)| {
+                        Text::Raw(Cow::from(format!(
+                            "{}\n",
+                            format_row(
+                                row * BYTES_PER_ROW,
+                                bytes,
+                                decimal,
+                            )
+                        )))
+                    }),
+            );
+        }
+    }
+
     fn find_selected_hunk(
         diff: &FileDiff,
         line_selected: usize,
@@ -171,44 +520,25 @@ impl DiffComponent {
         let mut res = Vec::new();
         if let Some(diff) = &self.diff {
             if diff.hunks.is_empty() {
-                let is_positive = diff.size_delta >= 0;
-                let delta_byte_size =
-                    ByteSize::b(diff.size_delta.abs() as u64);
-                let sign = if is_positive { "+" } else { "-" };
-                res.extend(vec![
-                    Text::Raw(Cow::from("size: ")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.0)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Text::Raw(Cow::from(" -> ")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.1)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Text::Raw(Cow::from(" (")),
-                    Text::Styled(
-                        Cow::from(format!(
-                            "{}{:}",
-                            sign, delta_byte_size
-                        )),
-                        self.theme.diff_line(
-                            if is_positive {
-                                DiffLineType::Add
-                            } else {
-                                DiffLineType::Delete
-                            },
-                            false,
-                        ),
-                    ),
-                    Text::Raw(Cow::from(")")),
-                ]);
+                if diff.binary {
+                    res.push(Text::Raw(Cow::from(
+                        strings::DIFF_BINARY,
+                    )));
+                }
+
+                if let Some(modes) = diff.modes {
+                    res.push(Text::Raw(Cow::from(format!(
+                        "{}\n",
+                        mode_change(modes)
+                    ))));
+                    if !diff.binary {
+                        // same content, the sizes say nothing
+                        return Ok(res);
+                    }
+                }
+
+                self.add_sizes(&mut res, diff);
+                self.add_binary_preview(&mut res, diff, height);
             } else {
                 let selection = self.selection;
 
@@ -247,6 +577,11 @@ impl DiffComponent {
                                     i == hunk_len as usize - 1,
                                     &self.theme,
                                 );
+                                if let Some(search) = &self.search {
+                                    Self::highlight_matches(
+                                        &mut res, search,
+                                    );
+                                }
                                 lines_added += 1;
                             }
 
@@ -311,6 +646,34 @@ impl DiffComponent {
         ));
     }
 
+    /// splits the line `add_line` pushed last at the matches
+    fn highlight_matches(text: &mut Vec<Text>, search: &Search) {
+        let (content, style) = match text.pop() {
+            Some(Text::Styled(content, style)) => (content, style),
+            last => {
+                text.extend(last);
+                return;
+            }
+        };
+
+        let mut pos = 0;
+        for range in search.find(&content) {
+            text.push(Text::Styled(
+                Cow::from(content[pos..range.start].to_string()),
+                style,
+            ));
+            text.push(Text::Styled(
+                Cow::from(content[range.clone()].to_string()),
+                Theme::search_match(style),
+            ));
+            pos = range.end;
+        }
+        text.push(Text::Styled(
+            Cow::from(content[pos..].to_string()),
+            style,
+        ));
+    }
+
     fn hunk_visible(
         hunk_min: usize,
         hunk_max: usize,
@@ -340,6 +703,7 @@ impl DiffComponent {
                     CWD,
                     self.current.path.clone(),
                     hash,
+                    self.current.options,
                 )?;
                 self.queue_update();
             }
@@ -356,7 +720,12 @@ impl DiffComponent {
                     sync::stage_add_file(CWD, Path::new(&path))?;
                 } else {
                     let hash = diff.hunks[hunk].header_hash;
-                    sync::stage_hunk(CWD, path, hash)?;
+                    sync::stage_hunk(
+                        CWD,
+                        path,
+                        hash,
+                        self.current.options,
+                    )?;
                 }
 
                 self.queue_update();
@@ -366,51 +735,175 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// nothing but the mode changed, enter stages or unstages that
+    fn mode_only(&self) -> bool {
+        self.diff.as_ref().map_or(false, |diff| {
+            diff.hunks.is_empty()
+                && !diff.binary
+                && diff.modes.is_some()
+        })
+    }
+
+    fn stage_mode(&mut self) -> Result<()> {
+        if self.current.is_stage {
+            sync::unstage_mode_change(CWD, &self.current.path)?;
+        } else {
+            sync::stage_mode_change(CWD, &self.current.path)?;
+        }
+        self.queue_update();
+
+        Ok(())
+    }
+
     fn queue_update(&mut self) {
         self.queue
-            .as_ref()
-            .expect("try using queue in immutable diff")
             .borrow_mut()
             .push_back(InternalEvent::Update(NeedsUpdate::ALL));
     }
 
+    /// the whole file or the selected hunk as patch
+    fn copy_patch(&self, hunk_only: bool) {
+        if let Some(diff) = &self.diff {
+            let (hunk, what) = if hunk_only {
+                if self.selected_hunk.is_none() {
+                    return;
+                }
+                (self.selected_hunk, strings::NOTIFY_COPIED_HUNK)
+            } else {
+                (None, strings::NOTIFY_COPIED_PATCH)
+            };
+
+            self.copy(&diff.patch(&self.current.path, hunk), what);
+        }
+    }
+
+    /// context and added lines of the selected hunk, without the
+    /// `+`/` ` prefixes
+    fn copy_lines(&self) {
+        if let (Some(diff), Some(hunk)) =
+            (&self.diff, self.selected_hunk)
+        {
+            let code = diff.hunks[hunk]
+                .lines
+                .iter()
+                .filter(|line| {
+                    matches!(
+                        line.line_type,
+                        DiffLineType::None | DiffLineType::Add
+                    ) && !line.content.starts_with("\n\\")
+                })
+                .map(|line| line.content.as_str())
+                .collect::<String>();
+
+            self.copy(&code, strings::NOTIFY_COPIED_LINES);
+        }
+    }
+
+    /// applies to every diff shown from now on, not just this one
+    fn change_context(&self, more: bool) {
+        let mut options = self.options.get();
+        let lines = if more {
+            options.context_lines.saturating_add(1)
+        } else {
+            options.context_lines.saturating_sub(1)
+        };
+        options.context_lines = lines;
+        self.options.set(options);
+
+        let mut queue = self.queue.borrow_mut();
+        queue.push_back(InternalEvent::Notify(
+            Notification::success(format!(
+                "{} {}",
+                strings::DIFF_CONTEXT_LINES,
+                lines
+            )),
+        ));
+        queue.push_back(InternalEvent::Update(
+            NeedsUpdate::ALL | NeedsUpdate::DIFF,
+        ));
+    }
+
+    /// cycles through showing and ignoring whitespace changes in
+    /// every diff shown from now on
+    fn toggle_whitespace(&self) {
+        let mut options = self.options.get();
+        let whitespace = options.whitespace.next();
+        options.whitespace = whitespace;
+        self.options.set(options);
+
+        let mut queue = self.queue.borrow_mut();
+        queue.push_back(InternalEvent::Notify(
+            Notification::success(format!(
+                "{} {}",
+                strings::DIFF_WHITESPACE,
+                whitespace.name()
+            )),
+        ));
+        queue.push_back(InternalEvent::Update(
+            NeedsUpdate::ALL | NeedsUpdate::DIFF,
+        ));
+    }
+
+    /// hunks of diffs ignoring whitespace do not apply to the files,
+    /// warns about that
+    fn whitespace_shown(&self) -> bool {
+        let shown =
+            self.current.options.whitespace == DiffWhitespace::Show;
+        if !shown {
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::warning(
+                    strings::DIFF_WHITESPACE_HUNKS,
+                    strings::DIFF_WHITESPACE_HUNKS_DETAILS,
+                ),
+            ));
+        }
+
+        shown
+    }
+
+    fn copy(&self, text: &str, what: &str) {
+        let notification = clipboard::copy_notification(
+            text,
+            what,
+            &self.config.clipboard,
+        );
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+    }
+
     fn reset_hunk(&self) -> Result<()> {
         if let Some(diff) = &self.diff {
             if let Some(hunk) = self.selected_hunk {
                 let hash = diff.hunks[hunk].header_hash;
 
-                self.queue
-                    .as_ref()
-                    .expect("try using queue in immutable diff")
-                    .borrow_mut()
-                    .push_back(InternalEvent::ConfirmAction(
-                        Action::ResetHunk(
-                            self.current.path.clone(),
-                            hash,
-                        ),
-                    ));
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(Action::ResetHunk(
+                        self.current.path.clone(),
+                        hash,
+                        self.current.options,
+                    )),
+                );
             }
         }
         Ok(())
     }
 
     fn reset_untracked(&self) -> Result<()> {
-        self.queue
-            .as_ref()
-            .expect("try using queue in immutable diff")
-            .borrow_mut()
-            .push_back(InternalEvent::ConfirmAction(Action::Reset(
-                ResetItem {
-                    path: self.current.path.clone(),
-                    is_folder: false,
-                },
-            )));
+        self.queue.borrow_mut().push_back(
+            InternalEvent::ConfirmAction(Action::Reset(ResetItem {
+                path: self.current.path.clone(),
+                is_folder: false,
+            })),
+        );
 
         Ok(())
     }
 
     fn is_immutable(&self) -> bool {
-        self.queue.is_none()
+        self.immutable
+            || self.diff.as_ref().map_or(false, |diff| diff.renamed)
     }
 
     const fn is_stage(&self) -> bool {
@@ -428,6 +921,7 @@ impl DrawableComponent for DiffComponent {
             r.width.saturating_sub(2),
             r.height.saturating_sub(2),
         ));
+        self.current_area.set(r);
 
         self.scroll_top.set(calc_scroll_top(
             self.scroll_top.get(),
@@ -435,8 +929,28 @@ impl DrawableComponent for DiffComponent {
             self.selection,
         ));
 
-        let title =
-            format!("{}{}", strings::TITLE_DIFF, self.current.path);
+        let whitespace = self.current.options.whitespace;
+        let title = if whitespace == DiffWhitespace::Show {
+            format!("{}{}", strings::TITLE_DIFF, self.current.path)
+        } else {
+            format!(
+                "{}{} ({} {})",
+                strings::TITLE_DIFF,
+                self.current.path,
+                strings::DIFF_WHITESPACE,
+                whitespace.name()
+            )
+        };
+        let title = match &self.diff {
+            Some(FileDiff {
+                hunks,
+                modes: Some(modes),
+                ..
+            }) if !hunks.is_empty() => {
+                format!("{} ({})", title, mode_change(*modes))
+            }
+            _ => title,
+        };
 
         let txt = if self.pending {
             vec![Text::Styled(
@@ -483,20 +997,92 @@ impl Component for DiffComponent {
             .hidden(),
         );
 
+        out.push(CommandInfo::new(
+            commands::DIFF_COPY_PATCH,
+            self.diff.is_some(),
+            self.focused,
+        ));
+        out.push(
+            CommandInfo::new(
+                commands::DIFF_COPY_HUNK,
+                self.selected_hunk.is_some(),
+                self.focused,
+            )
+            .hidden(),
+        );
+        out.push(
+            CommandInfo::new(
+                commands::DIFF_COPY_LINES,
+                self.selected_hunk.is_some(),
+                self.focused,
+            )
+            .hidden(),
+        );
+        out.push(
+            CommandInfo::new(
+                commands::DIFF_MORE_CONTEXT,
+                true,
+                self.focused,
+            )
+            .hidden(),
+        );
+        out.push(
+            CommandInfo::new(
+                commands::DIFF_LESS_CONTEXT,
+                self.options.get().context_lines > 0,
+                self.focused,
+            )
+            .hidden(),
+        );
+
+        out.push(
+            CommandInfo::new(
+                commands::DIFF_TOGGLE_WHITESPACE,
+                true,
+                self.focused,
+            )
+            .hidden(),
+        );
+        out.push(CommandInfo::new(
+            commands::DIFF_GOTO_LINE,
+            self.diff.is_some(),
+            self.focused,
+        ));
+        out.push(CommandInfo::new(
+            commands::DIFF_FIND,
+            self.diff.is_some(),
+            self.focused,
+        ));
+        out.push(CommandInfo::new(
+            commands::DIFF_FIND_NEXT,
+            true,
+            self.focused && self.search.is_some(),
+        ));
+        out.push(CommandInfo::new(
+            commands::DIFF_FIND_PREV,
+            true,
+            self.focused && self.search.is_some(),
+        ));
+
         if !self.is_immutable() {
+            let hunk_selected = (self.selected_hunk.is_some()
+                && self.current.options.whitespace
+                    == DiffWhitespace::Show)
+                || self.mode_only();
+
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_REMOVE,
-                self.selected_hunk.is_some(),
+                hunk_selected,
                 self.focused && self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_ADD,
-                self.selected_hunk.is_some(),
+                hunk_selected,
                 self.focused && !self.is_stage(),
             ));
             out.push(CommandInfo::new(
                 commands::DIFF_HUNK_REVERT,
-                self.selected_hunk.is_some(),
+                hunk_selected,
                 self.focused && !self.is_stage(),
             ));
         }
@@ -505,55 +1091,103 @@ impl Component for DiffComponent {
     }
 
     fn event(&mut self, ev: Event) -> Result<bool> {
+        if let Event::Mouse(m) = ev {
+            return self.handle_mouse_event(m);
+        }
+
         if self.focused {
             if let Event::Key(e) = ev {
-                return match e {
-                    keys::MOVE_DOWN => {
-                        self.move_selection(ScrollType::Down)?;
-                        Ok(true)
-                    }
-                    keys::SHIFT_DOWN | keys::END => {
-                        self.move_selection(ScrollType::End)?;
-                        Ok(true)
-                    }
-                    keys::HOME | keys::SHIFT_UP => {
-                        self.move_selection(ScrollType::Home)?;
-                        Ok(true)
-                    }
-                    keys::MOVE_UP => {
-                        self.move_selection(ScrollType::Up)?;
-                        Ok(true)
-                    }
-                    keys::PAGE_UP => {
-                        self.move_selection(ScrollType::PageUp)?;
-                        Ok(true)
-                    }
-                    keys::PAGE_DOWN => {
-                        self.move_selection(ScrollType::PageDown)?;
-                        Ok(true)
-                    }
-                    keys::ENTER if !self.is_immutable() => {
+                let keys = Rc::clone(&self.key_config);
+
+                return if keys.matches(&e, KeyAction::MoveDown) {
+                    self.move_selection(ScrollType::Down)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::End) {
+                    self.move_selection(ScrollType::End)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::Home) {
+                    self.move_selection(ScrollType::Home)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::MoveUp) {
+                    self.move_selection(ScrollType::Up)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::PageUp) {
+                    self.move_selection(ScrollType::PageUp)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::PageDown) {
+                    self.move_selection(ScrollType::PageDown)?;
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::Enter)
+                    && !self.is_immutable()
+                {
+                    if self.mode_only() {
+                        self.stage_mode()?;
+                    } else if self.whitespace_shown() {
                         if self.current.is_stage {
                             self.unstage_hunk()?;
                         } else {
                             self.stage_hunk()?;
                         }
-                        Ok(true)
                     }
-                    keys::DIFF_RESET_HUNK
-                        if !self.is_immutable()
-                            && !self.is_stage() =>
-                    {
-                        if let Some(diff) = &self.diff {
-                            if diff.untracked {
-                                self.reset_untracked()?;
-                            } else {
-                                self.reset_hunk()?;
-                            }
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffResetHunk)
+                    && !self.is_immutable()
+                    && !self.is_stage()
+                {
+                    if let Some(diff) = &self.diff {
+                        if diff.untracked {
+                            self.reset_untracked()?;
+                        } else if self.whitespace_shown() {
+                            self.reset_hunk()?;
                         }
-                        Ok(true)
                     }
-                    _ => Ok(false),
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffCopyPatch) {
+                    self.copy_patch(false);
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffCopyHunk) {
+                    self.copy_patch(true);
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffCopyLines) {
+                    self.copy_lines();
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffGoToLine)
+                    && self.diff.is_some()
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenGoToLine);
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffFind)
+                    && self.diff.is_some()
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenFind);
+                    Ok(true)
+                } else if self.search.is_some()
+                    && (keys.matches(&e, KeyAction::DiffFindNext)
+                        || keys.matches(&e, KeyAction::DiffFindPrev))
+                {
+                    self.find_step(
+                        keys.matches(&e, KeyAction::DiffFindNext),
+                    )?;
+                    Ok(true)
+                } else if keys
+                    .matches(&e, KeyAction::DiffToggleWhitespace)
+                {
+                    self.toggle_whitespace();
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffMoreContext)
+                {
+                    self.change_context(true);
+                    Ok(true)
+                } else if keys.matches(&e, KeyAction::DiffLessContext)
+                {
+                    self.change_context(false);
+                    Ok(true)
+                } else {
+                    Ok(false)
                 };
             }
         }
@@ -572,6 +1206,7 @@ impl Component for DiffComponent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use asyncgit::sync::diff::Hunk;
 
     #[test]
     fn test_lineendings() {
@@ -597,4 +1232,42 @@ mod tests {
             panic!("err")
         }
     }
+
+    #[test]
+    fn test_line_of() {
+        let line = |line_type, content: &str| DiffLine {
+            content: content.to_string(),
+            line_type,
+        };
+        let hunk = |lines| Hunk {
+            header_hash: 0,
+            lines,
+        };
+        let diff = FileDiff {
+            hunks: vec![
+                hunk(vec![
+                    line(DiffLineType::Header, "@@ -3,2 +3,2 @@\n"),
+                    line(DiffLineType::None, "three\n"),
+                    line(DiffLineType::Delete, "old four\n"),
+                    line(DiffLineType::Add, "four\n"),
+                ]),
+                hunk(vec![
+                    line(DiffLineType::Header, "@@ -10 +10 @@ fn\n"),
+                    line(DiffLineType::Add, "ten\n"),
+                ]),
+            ],
+            lines: 6,
+            ..FileDiff::default()
+        };
+
+        let line_of = |target| DiffComponent::line_of(&diff, target);
+        assert_eq!(line_of(GoTo::Line(1)), 1);
+        assert_eq!(line_of(GoTo::Line(4)), 3);
+        // not shown, the next line that is
+        assert_eq!(line_of(GoTo::Line(7)), 5);
+        assert_eq!(line_of(GoTo::Line(99)), 6);
+        assert_eq!(line_of(GoTo::Hunk(2)), 4);
+        assert_eq!(line_of(GoTo::Hunk(0)), 0);
+        assert_eq!(hunk_new_start("@@ -1,3 +12,4 @@"), Some(12));
+    }
 }
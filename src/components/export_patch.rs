@@ -0,0 +1,162 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, CommitId},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{env, path::Path};
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for a directory and writes commits into it as `.patch` files
+pub struct ExportPatchComponent {
+    input: TextInputComponent,
+    commits: Vec<CommitId>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ExportPatchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ExportPatchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::EXPORT_PATCH_CONFIRM,
+                !self.input.get_text().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.confirm()?;
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl ExportPatchComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::EXPORT_PATCH_POPUP_TITLE,
+                strings::EXPORT_PATCH_POPUP_MSG,
+            ),
+            queue,
+            key_config,
+            commits: Vec::new(),
+        }
+    }
+
+    /// suggests the current directory as destination
+    pub fn open(&mut self, commits: Vec<CommitId>) -> Result<()> {
+        self.input
+            .set_text(env::current_dir()?.to_string_lossy().into());
+        self.commits = commits;
+        self.show()?;
+
+        Ok(())
+    }
+
+    fn confirm(&mut self) -> Result<()> {
+        if self.input.get_text().is_empty() {
+            return Ok(());
+        }
+
+        let dir = env::current_dir()?.join(self.input.get_text());
+        let commits = std::mem::take(&mut self.commits);
+
+        self.queue.borrow_mut().push_back(InternalEvent::Notify(
+            Self::export(&commits, &dir),
+        ));
+
+        self.input.clear();
+        self.hide();
+
+        Ok(())
+    }
+
+    fn export(commits: &[CommitId], dir: &Path) -> Notification {
+        match sync::format_patch(CWD, commits, dir) {
+            Ok(files) => Notification {
+                details: Some(files.join("\n")),
+                ..Notification::success(format!(
+                    "exported {} patch(es) to {}",
+                    files.len(),
+                    dir.display()
+                ))
+            },
+            Err(e) => {
+                log::error!("export patch error: {}", e);
+                Notification::error(
+                    "export patch failed",
+                    e.to_string(),
+                )
+            }
+        }
+    }
+}
@@ -7,15 +7,25 @@ use crate::{
     ui::{self, style::SharedTheme},
 };
 use anyhow::{anyhow, Result};
-use asyncgit::{sync::utils::repo_work_dir, CWD};
+use asyncgit::{
+    sync::{self, utils::repo_work_dir},
+    CWD,
+};
 use crossterm::{
-    event::Event,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    event::{DisableMouseCapture, EnableMouseCapture, Event},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
     ExecutableCommand,
 };
 use scopeguard::defer;
-use std::ffi::OsStr;
-use std::{env, io, path::Path, process::Command};
+use std::ffi::{OsStr, OsString};
+use std::{
+    env, io,
+    path::Path,
+    process::{Command, Stdio},
+};
 use tui::{
     backend::Backend,
     layout::Rect,
@@ -40,6 +50,15 @@ impl ExternalEditorComponent {
 
     /// opens file at given `path` in an available editor
     pub fn open_file_in_editor(path: &Path) -> Result<()> {
+        Self::open_file_at_line(path, None)
+    }
+
+    /// like `open_file_in_editor`, starting at `line` if the editor
+    /// is known to take one
+    fn open_file_at_line(
+        path: &Path,
+        line: Option<usize>,
+    ) -> Result<()> {
         let work_dir = repo_work_dir(CWD)?;
 
         let path = if path.is_relative() {
@@ -52,16 +71,17 @@ impl ExternalEditorComponent {
             return Err(anyhow!("file not found: {:?}", path));
         }
 
+        io::stdout().execute(DisableMouseCapture)?;
         io::stdout().execute(LeaveAlternateScreen)?;
+        // line based editors need the terminal in cooked mode
+        disable_raw_mode()?;
         defer! {
             io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+            enable_raw_mode().expect("reset terminal");
+            io::stdout().execute(EnableMouseCapture).expect("reset terminal");
         }
 
-        let editor = env::var("GIT_EDITOR")
-            .ok()
-            .or_else(|| env::var("VISUAL").ok())
-            .or_else(|| env::var("EDITOR").ok())
-            .unwrap_or_else(|| String::from("vi"));
+        let editor = Self::editor();
 
         // TODO: proper handling arguments containing whitespaces
         // This does not do the right thing if the input is `editor --something "with spaces"`
@@ -71,19 +91,109 @@ impl ExternalEditorComponent {
             anyhow!("unable to read editor command")
         })?;
 
-        let mut editor: Vec<&OsStr> =
-            editor.map(|s| OsStr::new(s)).collect();
-
-        editor.push(path.as_os_str());
+        let mut args: Vec<OsString> =
+            editor.map(OsString::from).collect();
+        args.extend(Self::file_args(command, &path, line));
 
         Command::new(command)
             .current_dir(work_dir)
-            .args(editor)
+            .args(args)
+            .stdin(Self::terminal_input())
             .status()
             .map_err(|e| anyhow!("\"{}\": {}", command, e))?;
 
         Ok(())
     }
+
+    /// the file argument(s), with the line in the syntax of the
+    /// `command` if it is one of the editors that take it
+    fn file_args(
+        command: &str,
+        path: &Path,
+        line: Option<usize>,
+    ) -> Vec<OsString> {
+        let program = Path::new(command)
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .unwrap_or_default();
+
+        match line {
+            Some(line)
+                if matches!(
+                    program,
+                    "vi" | "vim"
+                        | "nvim"
+                        | "gvim"
+                        | "nano"
+                        | "emacs"
+                        | "emacsclient"
+                        | "micro"
+                        | "kak"
+                        | "joe"
+                        | "ne"
+                        | "mg"
+                ) =>
+            {
+                vec![
+                    OsString::from(format!("+{}", line)),
+                    path.into(),
+                ]
+            }
+            Some(line) if matches!(program, "code" | "codium") => {
+                let mut arg = path.as_os_str().to_os_string();
+                arg.push(format!(":{}", line));
+                vec![OsString::from("--goto"), arg]
+            }
+            Some(line) if matches!(program, "hx" | "subl") => {
+                let mut arg = path.as_os_str().to_os_string();
+                arg.push(format!(":{}", line));
+                vec![arg]
+            }
+            _ => vec![path.into()],
+        }
+    }
+
+    /// editors need a controlling terminal to read keys from, stdin
+    /// of gitui might not be one (like when it is piped)
+    fn terminal_input() -> Stdio {
+        #[cfg(unix)]
+        if let Ok(tty) = std::fs::File::open("/dev/tty") {
+            return Stdio::from(tty);
+        }
+
+        Stdio::inherit()
+    }
+
+    /// opens the file of the repo at `path` like `open_file_in_editor`,
+    /// at `line` if given. if all its changes were staged the edit gets
+    /// staged, too
+    pub fn edit_file(path: &Path, line: Option<usize>) -> Result<()> {
+        let restage = sync::status::is_fully_staged(CWD, path)
+            .unwrap_or_default();
+
+        Self::open_file_at_line(path, line)?;
+
+        if restage {
+            sync::stage_add_file(CWD, path)?;
+        }
+
+        Ok(())
+    }
+
+    /// the editor git would use
+    fn editor() -> String {
+        env::var("GIT_EDITOR")
+            .ok()
+            .or_else(|| {
+                sync::get_config_values(CWD, &["core.editor"])
+                    .ok()
+                    .and_then(|mut values| values.pop())
+                    .and_then(|value| value.value)
+            })
+            .or_else(|| env::var("VISUAL").ok())
+            .or_else(|| env::var("EDITOR").ok())
+            .unwrap_or_else(|| String::from("vi"))
+    }
 }
 
 impl DrawableComponent for ExternalEditorComponent {
@@ -151,3 +261,28 @@ impl Component for ExternalEditorComponent {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_args() {
+        let path = Path::new("src/main.rs");
+        let args = |command, line| {
+            ExternalEditorComponent::file_args(command, path, line)
+        };
+
+        assert_eq!(args("vim", None), vec!["src/main.rs"]);
+        assert_eq!(
+            args("/usr/bin/nvim", Some(3)),
+            vec!["+3", "src/main.rs"]
+        );
+        assert_eq!(
+            args("code", Some(3)),
+            vec!["--goto", "src/main.rs:3"]
+        );
+        assert_eq!(args("hx", Some(3)), vec!["src/main.rs:3"]);
+        assert_eq!(args("unknown", Some(3)), vec!["src/main.rs"]);
+    }
+}
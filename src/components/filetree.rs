@@ -1,24 +1,42 @@
 use super::{
+    mouse_scroll,
     utils::{
-        filetree::{FileTreeItem, FileTreeItemKind},
+        filetree::{DirCounts, FileTreeItem, FileTreeItemKind},
         statustree::{MoveSelection, StatusTree},
     },
-    CommandBlocking, DrawableComponent,
+    CommandBlocking, DrawableComponent, ScrollType,
 };
 use crate::{
     components::{CommandInfo, Component},
-    keys,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings::{self, commands, order},
     ui,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{hash, StatusItem, StatusItemType};
-use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, convert::From, path::Path};
+use asyncgit::{hash, sync::FileStat, StatusItem, StatusItemType};
+use bytesize::ByteSize;
+use crossterm::event::{Event, MouseEvent};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    convert::From,
+    ffi::OsStr,
+    path::Path,
+    rc::Rc,
+};
 use tui::{backend::Backend, layout::Rect, widgets::Text, Frame};
 
+/// in front of marked files
+const MARKER: char = '*';
+
+/// changes of more lines (or bytes of a binary) are flagged as large
+const LARGE_CHANGE_LINES: usize = 500;
+const LARGE_CHANGE_BYTES: u64 = 1024 * 1024;
+
 ///
 pub struct FileTreeComponent {
     title: String,
@@ -29,7 +47,14 @@ pub struct FileTreeComponent {
     show_selection: bool,
     queue: Option<Queue>,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
     scroll_top: Cell<usize>,
+    current_area: Cell<Rect>,
+    /// as given to `update`, to sort it again
+    list: Vec<StatusItem>,
+    file_stats: HashMap<String, FileStat>,
+    dir_counts: HashMap<String, DirCounts>,
+    untracked: HashSet<String>,
 }
 
 impl FileTreeComponent {
@@ -39,6 +64,7 @@ impl FileTreeComponent {
         focus: bool,
         queue: Option<Queue>,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
     ) -> Self {
         Self {
             title: title.to_string(),
@@ -48,8 +74,14 @@ impl FileTreeComponent {
             show_selection: focus,
             queue,
             theme,
+            key_config,
             scroll_top: Cell::new(0),
+            current_area: Cell::new(Rect::default()),
             pending: true,
+            list: Vec::new(),
+            file_stats: HashMap::new(),
+            dir_counts: HashMap::new(),
+            untracked: HashSet::new(),
         }
     }
 
@@ -58,13 +90,30 @@ impl FileTreeComponent {
         self.pending = false;
         let new_hash = hash(list);
         if self.current_hash != new_hash {
-            self.tree.update(list)?;
+            self.list = list.to_vec();
             self.current_hash = new_hash;
+            self.update_tree()?;
         }
 
         Ok(())
     }
 
+    /// biggest changes first if sorted by size, ties keep their order
+    fn update_tree(&mut self) -> Result<()> {
+        if self.tree.is_keep_order() {
+            let stats = &self.file_stats;
+            let mut list = self.list.clone();
+            list.sort_by_key(|item| {
+                Reverse(stats.get(&item.path).map(|stat| {
+                    (stat.lines(), stat.size_delta().unsigned_abs())
+                }))
+            });
+            self.tree.update(&list)
+        } else {
+            self.tree.update(&self.list)
+        }
+    }
+
     ///
     pub fn selection(&self) -> Option<FileTreeItem> {
         self.tree.selected_item()
@@ -101,10 +150,86 @@ impl FileTreeComponent {
         self.title = title;
     }
 
+    /// per file path, shown next to each file
+    pub fn set_file_stats(
+        &mut self,
+        stats: HashMap<String, FileStat>,
+    ) -> Result<()> {
+        let resort =
+            self.tree.is_keep_order() && stats != self.file_stats;
+        self.file_stats = stats;
+
+        if resort {
+            self.update_tree()?;
+        }
+
+        Ok(())
+    }
+
+    /// lists the files by the size of their change, biggest first,
+    /// instead of by path
+    pub fn set_sort_by_size(&mut self, by_size: bool) -> Result<()> {
+        self.tree.set_keep_order(by_size);
+        self.update_tree()
+    }
+
+    ///
+    pub const fn is_sorted_by_size(&self) -> bool {
+        self.tree.is_keep_order()
+    }
+
+    /// changed files per directory, shown next to each directory
+    pub fn set_dir_counts(
+        &mut self,
+        counts: HashMap<String, DirCounts>,
+    ) {
+        self.dir_counts = counts;
+    }
+
+    /// paths of the files that were untracked (like the ones a stash
+    /// saved), labeled as such
+    pub fn set_untracked(&mut self, paths: Vec<String>) {
+        self.untracked = paths.into_iter().collect();
+    }
+
+    /// lists the files without their folders
+    pub fn set_flat(&mut self, flat: bool) -> Result<()> {
+        self.tree.set_flat(flat)
+    }
+
+    ///
+    pub const fn is_flat(&self) -> bool {
+        self.tree.is_flat()
+    }
+
+    /// marks the selected file (or folder) and moves on to the next
+    pub fn toggle_mark(&mut self) {
+        self.tree.toggle_mark();
+        self.move_selection(MoveSelection::Down);
+    }
+
+    ///
+    pub fn has_marks(&self) -> bool {
+        self.tree.has_marks()
+    }
+
+    ///
+    pub fn marked_items(&self) -> Vec<StatusItem> {
+        self.tree.marked_items()
+    }
+
+    /// returns true if anything was marked
+    pub fn clear_marks(&mut self) -> bool {
+        self.tree.clear_marks()
+    }
+
     ///
     pub fn clear(&mut self) -> Result<()> {
         self.current_hash = 0;
         self.pending = true;
+        self.list.clear();
+        self.file_stats.clear();
+        self.dir_counts.clear();
         self.tree.update(&[])
     }
 
@@ -120,6 +245,22 @@ impl FileTreeComponent {
         }
     }
 
+    /// moves the selection if the mouse wheel turned above the list
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> bool {
+        let dir = match mouse_scroll(ev, self.current_area.get()) {
+            Some(ScrollType::Up) => MoveSelection::Up,
+            Some(ScrollType::Down) => MoveSelection::Down,
+            _ => return false,
+        };
+
+        let mut changed = false;
+        for _ in 0..self.key_config.mouse_scroll_lines() {
+            changed |= self.move_selection(dir);
+        }
+
+        changed
+    }
+
     fn move_selection(&mut self, dir: MoveSelection) -> bool {
         let changed = self.tree.move_selection(dir);
 
@@ -134,18 +275,31 @@ impl FileTreeComponent {
         changed
     }
 
-    fn item_to_text<'a>(
+    fn item_to_text(
+        &self,
         item: &FileTreeItem,
         width: u16,
         selected: bool,
-        theme: &'a SharedTheme,
-    ) -> Option<Text<'a>> {
+    ) -> Option<Text<'_>> {
+        let tree = &self.tree;
+
         let indent_str = if item.info.indent == 0 {
             String::from("")
         } else {
             format!("{:w$}", " ", w = (item.info.indent as usize) * 2)
         };
 
+        // the marker column is only there while something is marked
+        let indent_str = match &item.kind {
+            _ if !tree.has_marks() => indent_str,
+            FileTreeItemKind::File(status_item)
+                if tree.is_marked(&status_item.path) =>
+            {
+                format!("{}{}", MARKER, indent_str)
+            }
+            _ => format!(" {}", indent_str),
+        };
+
         if !item.info.visible {
             return None;
         }
@@ -154,10 +308,8 @@ impl FileTreeComponent {
             FileTreeItemKind::File(status_item) => {
                 let status_char =
                     Self::item_status_char(status_item.status);
-                let file = Path::new(&status_item.path)
-                    .file_name()
-                    .and_then(std::ffi::OsStr::to_str)
-                    .expect("invalid path.");
+                let file =
+                    self.file_text(status_item, &item.info.path);
 
                 let txt = if selected {
                     format!(
@@ -173,44 +325,154 @@ impl FileTreeComponent {
 
                 Some(Text::Styled(
                     Cow::from(txt),
-                    theme.item(status_item.status, selected),
+                    self.theme.item(status_item.status, selected),
                 ))
             }
 
             FileTreeItemKind::Path(path_collapsed) => {
                 let collapse_char =
                     if path_collapsed.0 { '▸' } else { '▾' };
+                let path = self
+                    .dir_counts
+                    .get(&item.info.full_path)
+                    .map_or_else(
+                        || item.info.path.clone(),
+                        |counts| {
+                            format!(
+                                "{} {}",
+                                item.info.path,
+                                Self::dir_counts_text(counts)
+                            )
+                        },
+                    );
 
                 let txt = if selected {
                     format!(
                         "  {}{}{:w$}",
                         indent_str,
                         collapse_char,
-                        item.info.path,
+                        path,
                         w = width as usize
                     )
                 } else {
                     format!(
                         "  {}{}{}",
-                        indent_str, collapse_char, item.info.path,
+                        indent_str, collapse_char, path,
                     )
                 };
 
                 Some(Text::Styled(
                     Cow::from(txt),
-                    theme.text(true, selected),
+                    self.theme.text(true, selected),
                 ))
             }
         }
     }
 
+    /// the name (or the path in a flat list) with what else there is
+    /// to know about the file
+    fn file_text(
+        &self,
+        status_item: &StatusItem,
+        shown: &str,
+    ) -> String {
+        let file = status_item.old_path.as_ref().map_or_else(
+            || shown.to_string(),
+            |old_path| {
+                format!(
+                    "{} \u{2192} {}",
+                    Self::old_path_text(
+                        old_path,
+                        &status_item.path,
+                        shown,
+                    ),
+                    shown
+                )
+            },
+        );
+
+        let file =
+            self.file_stats.get(&status_item.path).map_or_else(
+                || file.clone(),
+                |stat| format!("{} {}", file, Self::stat_text(stat)),
+            );
+
+        if self.untracked.contains(&status_item.path) {
+            format!("{} {}", file, strings::FILE_UNTRACKED)
+        } else {
+            file
+        }
+    }
+
+    /// just the name if only that changed and the new one is shown
+    /// by its name
+    fn old_path_text<'a>(
+        old_path: &'a str,
+        path: &str,
+        shown: &str,
+    ) -> &'a str {
+        let parent = |path| Path::new(path).parent();
+        if shown != path && parent(old_path) == parent(path) {
+            Path::new(old_path)
+                .file_name()
+                .and_then(OsStr::to_str)
+                .unwrap_or(old_path)
+        } else {
+            old_path
+        }
+    }
+
+    /// like `+12/-3` or `bin +1.2 KB`, flagged if it is a lot
+    fn stat_text(stat: &FileStat) -> String {
+        let delta = stat.size_delta();
+        let text = if stat.binary {
+            format!(
+                "{} {}{}",
+                strings::FILE_STAT_BINARY,
+                if delta < 0 { '-' } else { '+' },
+                ByteSize::b(delta.unsigned_abs())
+            )
+        } else {
+            format!("+{}/-{}", stat.insertions, stat.deletions)
+        };
+
+        let large = if stat.binary {
+            delta.unsigned_abs() > LARGE_CHANGE_BYTES
+        } else {
+            stat.lines() > LARGE_CHANGE_LINES
+        };
+
+        if large {
+            format!("{} {}", text, strings::FILE_STAT_LARGE)
+        } else {
+            text
+        }
+    }
+
+    /// like `(+1 ~2)`, zero counts are left out
+    fn dir_counts_text(counts: &DirCounts) -> String {
+        let parts = [
+            ('+', counts.added),
+            ('~', counts.modified),
+            ('-', counts.deleted),
+        ]
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(sign, count)| format!("{}{}", sign, count))
+        .collect::<Vec<_>>();
+
+        format!("({})", parts.join(" "))
+    }
+
     fn item_status_char(item_type: StatusItemType) -> char {
         match item_type {
             StatusItemType::Modified => 'M',
             StatusItemType::New => '+',
             StatusItemType::Deleted => '-',
             StatusItemType::Renamed => 'R',
+            StatusItemType::Copied => 'C',
             StatusItemType::Typechange => ' ',
+            StatusItemType::Conflicted => '!',
         }
     }
 }
@@ -221,6 +483,8 @@ impl DrawableComponent for FileTreeComponent {
         f: &mut Frame<B>,
         r: Rect,
     ) -> Result<()> {
+        self.current_area.set(r);
+
         if self.pending {
             let items = vec![Text::Styled(
                 Cow::from(strings::LOADING_TEXT),
@@ -273,7 +537,7 @@ impl DrawableComponent for FileTreeComponent {
                 .iter()
                 .enumerate()
                 .filter_map(|(idx, e)| {
-                    Self::item_to_text(
+                    self.item_to_text(
                         e,
                         r.width,
                         self.show_selection
@@ -281,7 +545,6 @@ impl DrawableComponent for FileTreeComponent {
                                 .tree
                                 .selection
                                 .map_or(false, |e| e == idx),
-                        &self.theme,
                     )
                 })
                 .skip(self.scroll_top.get());
@@ -320,29 +583,31 @@ impl Component for FileTreeComponent {
     }
 
     fn event(&mut self, ev: Event) -> Result<bool> {
+        if let Event::Mouse(m) = ev {
+            return Ok(self.handle_mouse_event(m));
+        }
+
         if self.focused {
             if let Event::Key(e) = ev {
-                return match e {
-                    keys::MOVE_DOWN => {
-                        Ok(self.move_selection(MoveSelection::Down))
-                    }
-                    keys::MOVE_UP => {
-                        Ok(self.move_selection(MoveSelection::Up))
-                    }
-                    keys::HOME | keys::SHIFT_UP => {
-                        Ok(self.move_selection(MoveSelection::Home))
-                    }
-                    keys::END | keys::SHIFT_DOWN => {
-                        Ok(self.move_selection(MoveSelection::End))
-                    }
-                    keys::MOVE_LEFT => {
-                        Ok(self.move_selection(MoveSelection::Left))
-                    }
-                    keys::MOVE_RIGHT => {
-                        Ok(self.move_selection(MoveSelection::Right))
-                    }
-                    _ => Ok(false),
-                };
+                let keys = Rc::clone(&self.key_config);
+                let selection =
+                    if keys.matches(&e, KeyAction::MoveDown) {
+                        MoveSelection::Down
+                    } else if keys.matches(&e, KeyAction::MoveUp) {
+                        MoveSelection::Up
+                    } else if keys.matches(&e, KeyAction::Home) {
+                        MoveSelection::Home
+                    } else if keys.matches(&e, KeyAction::End) {
+                        MoveSelection::End
+                    } else if keys.matches(&e, KeyAction::MoveLeft) {
+                        MoveSelection::Left
+                    } else if keys.matches(&e, KeyAction::MoveRight) {
+                        MoveSelection::Right
+                    } else {
+                        return Ok(false);
+                    };
+
+                return Ok(self.move_selection(selection));
             }
         }
 
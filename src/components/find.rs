@@ -0,0 +1,164 @@
+use super::{
+    textinput::TextInputComponent, utils::search::Search,
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks what to find in the focused diff or the hex view, every
+/// change is looked for right away
+pub struct FindComponent {
+    input: TextInputComponent,
+    case_sensitive: bool,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for FindComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for FindComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::FIND_CONFIRM,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::FIND_TOGGLE_CASE,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    // the matches stay highlighted
+                    self.input.hide();
+                    return Ok(true);
+                } else if self
+                    .key_config
+                    .matches(&e, KeyAction::FindToggleCase)
+                {
+                    self.case_sensitive = !self.case_sensitive;
+                    self.update_title();
+                    self.find();
+                    return Ok(true);
+                }
+            }
+
+            if self.input.event(ev)? {
+                if !self.is_visible() {
+                    // cancelled
+                    self.input.clear();
+                }
+                self.find();
+                return Ok(true);
+            }
+
+            return Ok(matches!(ev, Event::Key(_)));
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+        self.find();
+
+        Ok(())
+    }
+}
+
+impl FindComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        let mut find = Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                "",
+                strings::FIND_POPUP_MSG,
+            ),
+            case_sensitive: false,
+            queue,
+            key_config,
+        };
+        find.update_title();
+
+        find
+    }
+
+    fn update_title(&mut self) {
+        self.input.set_title(format!(
+            "{} {}",
+            strings::FIND_POPUP_TITLE,
+            if self.case_sensitive {
+                strings::FIND_MATCH_CASE
+            } else {
+                strings::FIND_IGNORE_CASE
+            }
+        ));
+    }
+
+    /// the text typed so far, no search at all if there is none
+    fn find(&self) {
+        let query = self.input.get_text();
+        let search = if query.is_empty() {
+            None
+        } else {
+            Some(Search {
+                query: query.clone(),
+                case_sensitive: self.case_sensitive,
+            })
+        };
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Find(search));
+    }
+}
@@ -0,0 +1,157 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// where to jump to, out of range targets end at the last line
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GoTo {
+    /// line of the file (1-based) or byte offset in the hex view
+    Line(usize),
+    /// first line of the hunk (1-based) of a diff, `h3`
+    Hunk(usize),
+}
+
+impl GoTo {
+    /// `42`, `0x2a` or `h3`
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+
+        if let Some(hunk) = text.strip_prefix('h') {
+            hunk.parse().ok().map(Self::Hunk)
+        } else if let Some(hex) = text.strip_prefix("0x") {
+            usize::from_str_radix(hex, 16).ok().map(Self::Line)
+        } else {
+            text.parse().ok().map(Self::Line)
+        }
+    }
+}
+
+/// asks where to jump to in the focused diff or the hex view
+pub struct GoToLineComponent {
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for GoToLineComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for GoToLineComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::GOTO_LINE_CONFIRM,
+                GoTo::parse(self.input.get_text()).is_some(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.confirm();
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl GoToLineComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::GOTO_LINE_POPUP_TITLE,
+                strings::GOTO_LINE_POPUP_MSG,
+            ),
+            queue,
+            key_config,
+        }
+    }
+
+    fn confirm(&mut self) {
+        if let Some(target) = GoTo::parse(self.input.get_text()) {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::GoToLine(target));
+
+            self.input.clear();
+            self.hide();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(GoTo::parse("42"), Some(GoTo::Line(42)));
+        assert_eq!(GoTo::parse(" 0x2A "), Some(GoTo::Line(42)));
+        assert_eq!(GoTo::parse("H3"), Some(GoTo::Hunk(3)));
+        assert_eq!(GoTo::parse(""), None);
+        assert_eq!(GoTo::parse("h"), None);
+        assert_eq!(GoTo::parse("-1"), None);
+    }
+}
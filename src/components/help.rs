@@ -1,17 +1,17 @@
 use super::{
-    visibility_blocking, CommandBlocking, CommandInfo, Component,
-    DrawableComponent,
+    mouse_scroll, visibility_blocking, CommandBlocking, CommandInfo,
+    Component, DrawableComponent, ScrollType,
 };
 use crate::{
-    keys,
+    keys::{key_name, KeyAction, SharedKeyConfig},
     strings::{self, commands},
     ui,
     version::Version,
 };
 use asyncgit::hash;
-use crossterm::event::Event;
+use crossterm::event::{Event, MouseEvent};
 use itertools::Itertools;
-use std::{borrow::Cow, cmp, convert::TryFrom};
+use std::{borrow::Cow, cell::Cell, cmp, convert::TryFrom, rc::Rc};
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -28,7 +28,9 @@ pub struct HelpComponent {
     cmds: Vec<CommandInfo>,
     visible: bool,
     selection: u16,
+    current_area: Cell<Rect>,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for HelpComponent {
@@ -39,13 +41,17 @@ impl DrawableComponent for HelpComponent {
     ) -> Result<()> {
         if self.visible {
             const SIZE: (u16, u16) = (65, 24);
-            let scroll_threshold = SIZE.1 / 3;
-            let scroll =
-                self.selection.saturating_sub(scroll_threshold);
 
             let area =
                 ui::centered_rect_absolute(SIZE.0, SIZE.1, f.size());
 
+            let (txt, selected_line) = self.get_text();
+            let scroll_threshold = area.height / 3;
+            let scroll =
+                selected_line.saturating_sub(scroll_threshold);
+
+            self.current_area.set(area);
+
             f.render_widget(Clear, area);
             f.render_widget(
                 Block::default()
@@ -66,7 +72,7 @@ impl DrawableComponent for HelpComponent {
                 .split(area);
 
             f.render_widget(
-                Paragraph::new(self.get_text().iter())
+                Paragraph::new(txt.iter())
                     .scroll(scroll)
                     .alignment(Alignment::Left),
                 chunks[0],
@@ -126,18 +132,26 @@ impl Component for HelpComponent {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.visible {
             if let Event::Key(e) = ev {
-                match e {
-                    keys::EXIT_POPUP => self.hide(),
-                    keys::MOVE_DOWN => self.move_selection(true),
-                    keys::MOVE_UP => self.move_selection(false),
-                    _ => (),
+                let keys = Rc::clone(&self.key_config);
+                if keys.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                } else if keys.matches(&e, KeyAction::MoveDown) {
+                    self.move_selection(true);
+                } else if keys.matches(&e, KeyAction::MoveUp) {
+                    self.move_selection(false);
                 }
+            } else if let Event::Mouse(m) = ev {
+                self.handle_mouse_event(m);
             }
 
             Ok(true)
-        } else if let Event::Key(keys::OPEN_HELP) = ev {
-            self.show()?;
-            Ok(true)
+        } else if let Event::Key(e) = ev {
+            if self.key_config.matches(&e, KeyAction::OpenHelp) {
+                self.show()?;
+                return Ok(true);
+            }
+
+            Ok(false)
         } else {
             Ok(false)
         }
@@ -159,12 +173,17 @@ impl Component for HelpComponent {
 }
 
 impl HelpComponent {
-    pub const fn new(theme: SharedTheme) -> Self {
+    pub fn new(
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
         Self {
             cmds: vec![],
             visible: false,
             selection: 0,
+            current_area: Cell::new(Rect::default()),
             theme,
+            key_config,
         }
     }
     ///
@@ -178,6 +197,20 @@ impl HelpComponent {
         self.cmds.sort_by_key(|e| hash(&e.text.group));
     }
 
+    /// scrolls if the mouse wheel turned above the popup
+    fn handle_mouse_event(&mut self, ev: MouseEvent) {
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                self.move_selection(matches!(
+                    scroll,
+                    ScrollType::Down
+                ));
+            }
+        }
+    }
+
     fn move_selection(&mut self, inc: bool) {
         let mut new_selection = self.selection;
 
@@ -188,17 +221,20 @@ impl HelpComponent {
         };
         new_selection = cmp::max(new_selection, 0);
 
-        if let Ok(max) =
-            u16::try_from(self.cmds.len().saturating_sub(1))
-        {
+        let count = self.cmds.len() + KeyAction::ALL.len();
+        if let Ok(max) = u16::try_from(count.saturating_sub(1)) {
             self.selection = cmp::min(new_selection, max);
         }
     }
 
-    fn get_text(&self) -> Vec<Text> {
+    /// context commands followed by every key binding, also returns
+    /// the line of the selection to scroll to
+    fn get_text(&self) -> (Vec<Text>, u16) {
         let mut txt = Vec::new();
 
         let mut processed = 0_u16;
+        let mut line = 0_u16;
+        let mut selected_line = 0_u16;
 
         for (key, group) in
             &self.cmds.iter().group_by(|e| e.text.group)
@@ -207,14 +243,19 @@ impl HelpComponent {
                 Cow::from(format!("{}\n", key)),
                 Style::default().modifier(Modifier::REVERSED),
             ));
+            line += 1;
 
             txt.extend(
                 group
                     .sorted_by_key(|e| e.order)
                     .map(|e| {
                         let is_selected = self.selection == processed;
+                        if is_selected {
+                            selected_line = line;
+                        }
 
                         processed += 1;
+                        line += 1;
 
                         let mut out = String::from(if is_selected {
                             ">"
@@ -222,7 +263,7 @@ impl HelpComponent {
                             " "
                         });
 
-                        e.print(&mut out);
+                        e.print(&mut out, &self.key_config);
                         out.push('\n');
 
                         if is_selected {
@@ -230,6 +271,7 @@ impl HelpComponent {
                                 format!("  {}\n", e.text.desc)
                                     .as_str(),
                             );
+                            line += 1;
                         }
 
                         Text::Styled(
@@ -241,6 +283,43 @@ impl HelpComponent {
             );
         }
 
-        txt
+        for (key, group) in
+            &KeyAction::ALL.iter().group_by(|action| action.group())
+        {
+            txt.push(Text::Styled(
+                Cow::from(format!("{}\n", key)),
+                Style::default().modifier(Modifier::REVERSED),
+            ));
+            line += 1;
+
+            for action in group {
+                let is_selected = self.selection == processed;
+                if is_selected {
+                    selected_line = line;
+                }
+
+                processed += 1;
+                line += 1;
+
+                let keys = self
+                    .key_config
+                    .keys(*action)
+                    .iter()
+                    .map(key_name)
+                    .join(", ");
+
+                txt.push(Text::Styled(
+                    Cow::from(format!(
+                        "{}{:<30}{}\n",
+                        if is_selected { ">" } else { " " },
+                        action.name(),
+                        if keys.is_empty() { "-" } else { &keys }
+                    )),
+                    self.theme.text(true, is_selected),
+                ));
+            }
+        }
+
+        (txt, selected_line)
     }
 }
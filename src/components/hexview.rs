@@ -0,0 +1,495 @@
+use super::{
+    mouse_scroll,
+    utils::search::{self, Search},
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, GoTo, ScrollType,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{
+        self,
+        style::{SharedTheme, Theme},
+    },
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::CommitId, AsyncBlob, AsyncNotification, BlobParams,
+};
+use bytesize::ByteSize;
+use crossbeam_channel::Sender;
+use crossterm::event::{Event, MouseEvent};
+use std::{borrow::Cow, cell::Cell, sync::Arc};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+pub(super) const BYTES_PER_ROW: usize = 16;
+/// width of a row plus borders
+const WIDTH: u16 = 82;
+
+/// offset, hex and ascii columns (like `hexdump -C`) of a file at a
+/// commit, only the visible rows get formatted
+pub struct HexViewComponent {
+    git_blob: AsyncBlob,
+    target: Option<BlobParams>,
+    content: Option<Arc<Vec<u8>>>,
+    search: Option<Search>,
+    /// offsets of the matches of `search`
+    matches: Vec<usize>,
+    scroll_top: usize,
+    current_height: Cell<usize>,
+    current_area: Cell<Rect>,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
+}
+
+impl HexViewComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            git_blob: AsyncBlob::new(sender),
+            target: None,
+            content: None,
+            search: None,
+            matches: Vec::new(),
+            scroll_top: 0,
+            current_height: Cell::new(0),
+            current_area: Cell::new(Rect::default()),
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+            config,
+        }
+    }
+
+    ///
+    pub fn open(
+        &mut self,
+        commit: CommitId,
+        path: String,
+    ) -> Result<()> {
+        let params = BlobParams { commit, path };
+
+        self.scroll_top = 0;
+        self.content = self.git_blob.current(&params)?;
+        if self.content.is_none() {
+            self.git_blob.fetch(params.clone())?;
+        }
+        self.target = Some(params);
+        self.update_matches();
+
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_blob.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.visible && ev == AsyncNotification::Blob {
+            if let Some(target) = &self.target {
+                self.content = self.git_blob.current(target)?;
+
+                // another file was requested while loading this one
+                if self.content.is_none() {
+                    self.git_blob.fetch(target.clone())?;
+                }
+                self.update_matches();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// scrolls the row of the byte offset to the top, hunks mean
+    /// nothing here
+    pub fn go_to(&mut self, target: GoTo) {
+        if let GoTo::Line(offset) = target {
+            let max =
+                self.rows().saturating_sub(self.current_height.get());
+            self.scroll_top = (offset / BYTES_PER_ROW).min(max);
+        }
+    }
+
+    /// highlights the matches of `search` and scrolls to the first
+    /// one from the top row on
+    pub fn set_search(&mut self, search: Option<Search>) {
+        self.search = search;
+        self.update_matches();
+
+        let rows = self.match_rows();
+        let first = rows
+            .iter()
+            .find(|row| **row >= self.scroll_top)
+            .or_else(|| rows.first());
+        if let Some(row) = first.copied() {
+            self.go_to(GoTo::Line(row * BYTES_PER_ROW));
+        }
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = match (&self.search, &self.content) {
+            (Some(search), Some(content)) => {
+                search.find_bytes(content)
+            }
+            _ => Vec::new(),
+        };
+    }
+
+    fn match_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<usize> = self
+            .matches
+            .iter()
+            .map(|offset| offset / BYTES_PER_ROW)
+            .collect();
+        rows.dedup();
+        rows
+    }
+
+    /// scrolls the next (or previous) row with a match to the top
+    fn find_step(&mut self, forward: bool) {
+        let rows = self.match_rows();
+        if let Some(row) =
+            search::step(&rows, self.scroll_top, forward)
+        {
+            self.go_to(GoTo::Line(row * BYTES_PER_ROW));
+        }
+    }
+
+    /// whether the byte at `offset` is part of a match
+    fn is_match(&self, offset: usize) -> bool {
+        let len = self.search.as_ref().map_or(0, |s| s.query.len());
+        let idx =
+            self.matches.partition_point(|start| *start <= offset);
+
+        idx > 0 && offset < self.matches[idx - 1] + len
+    }
+
+    /// the row with the hex and ascii of the matched bytes
+    /// highlighted
+    fn row_text(&self, offset: usize, bytes: &[u8]) -> Vec<Text<'_>> {
+        let decimal = self.config.hexview.decimal_offsets;
+        let row = format_row(offset, bytes, decimal);
+        if self.matches.is_empty() {
+            return vec![Text::Raw(Cow::from(row))];
+        }
+
+        let hex_start = if decimal { 12 } else { 10 };
+        let ascii_start = hex_start + BYTES_PER_ROW * 3 + 2;
+        let mut marked = vec![false; row.len()];
+        for i in
+            (0..bytes.len()).filter(|i| self.is_match(offset + i))
+        {
+            let hex = hex_start
+                + i * 3
+                + usize::from(i >= BYTES_PER_ROW / 2);
+            marked[hex] = true;
+            marked[hex + 1] = true;
+            marked[ascii_start + i] = true;
+        }
+
+        let mut text = Vec::new();
+        let mut start = 0;
+        for end in 1..=row.len() {
+            if end == row.len() || marked[end] != marked[start] {
+                let part = Cow::from(row[start..end].to_string());
+                text.push(if marked[start] {
+                    Text::Styled(
+                        part,
+                        Theme::search_match(Style::default()),
+                    )
+                } else {
+                    Text::Raw(part)
+                });
+                start = end;
+            }
+        }
+
+        text
+    }
+
+    fn rows(&self) -> usize {
+        self.content.as_ref().map_or(0, |content| {
+            (content.len() + BYTES_PER_ROW - 1) / BYTES_PER_ROW
+        })
+    }
+
+    /// scrolls if the mouse wheel turned above the dump
+    fn handle_mouse_event(&mut self, ev: MouseEvent) {
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                self.move_scroll(scroll);
+            }
+        }
+    }
+
+    fn move_scroll(&mut self, scroll: ScrollType) {
+        let height = self.current_height.get();
+        let max = self.rows().saturating_sub(height);
+        let old = self.scroll_top;
+
+        self.scroll_top = match scroll {
+            ScrollType::Up => old.saturating_sub(1),
+            ScrollType::Down => old.saturating_add(1),
+            ScrollType::PageUp => old.saturating_sub(height),
+            ScrollType::PageDown => old.saturating_add(height),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        }
+        .min(max);
+    }
+
+    fn title(&self) -> String {
+        let path =
+            self.target.as_ref().map_or("", |t| t.path.as_str());
+
+        match &self.content {
+            Some(content) => format!(
+                "{} {} ({})",
+                strings::HEXVIEW_TITLE,
+                path,
+                ByteSize::b(content.len() as u64)
+            ),
+            None => format!("{} {}", strings::HEXVIEW_TITLE, path),
+        }
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        match &self.content {
+            Some(content) => content
+                .chunks(BYTES_PER_ROW)
+                .enumerate()
+                .skip(self.scroll_top)
+                .take(height)
+                .flat_map(|(row, bytes)| {
+                    self.row_text(row * BYTES_PER_ROW, bytes)
+                })
+                .collect(),
+            None => vec![Text::Raw(Cow::from(
+                if self.any_work_pending() {
+                    strings::LOADING_TEXT
+                } else {
+                    strings::HEXVIEW_FAILED
+                },
+            ))],
+        }
+    }
+}
+
+/// `00000010  68 65 6c 6c 6f 0a ...  |hello.|`
+pub(super) fn format_row(
+    offset: usize,
+    bytes: &[u8],
+    decimal: bool,
+) -> String {
+    let offset = if decimal {
+        format!("{:>10}", offset)
+    } else {
+        format!("{:08x}", offset)
+    };
+
+    let hex = (0..BYTES_PER_ROW)
+        .map(|i| {
+            let gap = if i == BYTES_PER_ROW / 2 { " " } else { "" };
+            bytes.get(i).map_or_else(
+                || format!("{}   ", gap),
+                |b| format!("{}{:02x} ", gap, b),
+            )
+        })
+        .collect::<String>();
+
+    let ascii = bytes
+        .iter()
+        .map(|b| {
+            if b.is_ascii_graphic() || *b == b' ' {
+                *b as char
+            } else {
+                '.'
+            }
+        })
+        .collect::<String>();
+
+    format!("{}  {}|{}|\n", offset, hex, ascii)
+}
+
+impl DrawableComponent for HexViewComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let height = f.size().height.saturating_mul(4) / 5;
+            let area =
+                ui::centered_rect_absolute(WIDTH, height, f.size());
+
+            let height = area.height.saturating_sub(2) as usize;
+            self.current_height.set(height);
+            self.current_area.set(area);
+
+            f.render_widget(Clear, area);
+
+            let title = self.title();
+            let txt = self.get_text(height);
+            f.render_widget(
+                Paragraph::new(txt.iter()).block(
+                    Block::default()
+                        .title(title.as_str())
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true))
+                        .title_style(self.theme.title(true)),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for HexViewComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(commands::SCROLL, true, true));
+            out.push(CommandInfo::new(
+                commands::DIFF_GOTO_LINE,
+                self.content.is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_FIND,
+                self.content.is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_FIND_NEXT,
+                true,
+                self.search.is_some(),
+            ));
+            out.push(CommandInfo::new(
+                commands::DIFF_FIND_PREV,
+                true,
+                self.search.is_some(),
+            ));
+
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                let keys = &self.key_config;
+
+                let scroll = if keys.matches(&e, KeyAction::MoveUp) {
+                    Some(ScrollType::Up)
+                } else if keys.matches(&e, KeyAction::MoveDown) {
+                    Some(ScrollType::Down)
+                } else if keys.matches(&e, KeyAction::PageUp) {
+                    Some(ScrollType::PageUp)
+                } else if keys.matches(&e, KeyAction::PageDown) {
+                    Some(ScrollType::PageDown)
+                } else if keys.matches(&e, KeyAction::Home) {
+                    Some(ScrollType::Home)
+                } else if keys.matches(&e, KeyAction::End) {
+                    Some(ScrollType::End)
+                } else {
+                    None
+                };
+
+                if let Some(scroll) = scroll {
+                    self.move_scroll(scroll);
+                } else if keys.matches(&e, KeyAction::DiffGoToLine) {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenGoToLine);
+                } else if keys.matches(&e, KeyAction::DiffFind) {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenFind);
+                } else if keys.matches(&e, KeyAction::DiffFindNext) {
+                    self.find_step(true);
+                } else if keys.matches(&e, KeyAction::DiffFindPrev) {
+                    self.find_step(false);
+                } else if keys.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                }
+            } else if let Event::Mouse(m) = ev {
+                self.handle_mouse_event(m);
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_row() {
+        assert_eq!(
+            format_row(
+                16,
+                b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR",
+                false
+            ),
+            "00000010  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52 |.PNG........IHDR|\n"
+        );
+        assert_eq!(
+            format_row(32, b"hi there", true),
+            "        32  68 69 20 74 68 65 72 65                          |hi there|\n"
+        );
+    }
+}
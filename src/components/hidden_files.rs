@@ -0,0 +1,214 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, HiddenFile, IndexBit},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// rows of the popup without its borders
+const HEIGHT: u16 = 10;
+
+/// tracked files whose local changes git is told to not look at,
+/// enter clears the bits again
+pub struct HiddenFilesComponent {
+    files: Vec<HiddenFile>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl HiddenFilesComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            files: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.files = sync::get_hidden_files(CWD)?;
+        self.selection = 0;
+
+        self.show()
+    }
+
+    fn clear_selected(&mut self) -> Result<()> {
+        if let Some(file) = self.files.get(self.selection) {
+            let path = file.path.clone();
+            for (bit, set) in &[
+                (IndexBit::AssumeUnchanged, file.assume_unchanged),
+                (IndexBit::SkipWorktree, file.skip_worktree),
+            ] {
+                if *set {
+                    sync::set_index_bit(CWD, &path, *bit, false)?;
+                }
+            }
+
+            self.files = sync::get_hidden_files(CWD)?;
+            self.selection = self
+                .selection
+                .min(self.files.len().saturating_sub(1));
+
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::success(format!(
+                    "{} {}",
+                    strings::NOTIFY_UNHIDDEN,
+                    path
+                )),
+            ));
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        }
+
+        Ok(())
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        if self.files.is_empty() {
+            return vec![Text::Styled(
+                Cow::from(strings::HIDDEN_FILES_EMPTY),
+                self.theme.text(false, false),
+            )];
+        }
+
+        let rows = usize::from(HEIGHT);
+        let skip = (self.selection + 1).saturating_sub(rows);
+
+        self.files
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(rows)
+            .map(|(idx, file)| {
+                let bits =
+                    match (file.assume_unchanged, file.skip_worktree)
+                    {
+                        (true, true) => "h S",
+                        (true, false) => "h  ",
+                        _ => "  S",
+                    };
+
+                Text::Styled(
+                    Cow::from(format!("{} {}\n", bits, file.path)),
+                    self.theme.text(true, idx == self.selection),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for HiddenFilesComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area =
+                ui::centered_rect_absolute(60, HEIGHT + 2, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter()).block(
+                    Block::default()
+                        .title(strings::HIDDEN_FILES_TITLE)
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true)),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for HiddenFilesComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::HIDDEN_FILES_CLEAR,
+                !self.files.is_empty(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if keys.matches(&k, KeyAction::ExitPopup) {
+                self.hide();
+            } else if keys.matches(&k, KeyAction::MoveUp) {
+                self.selection = self.selection.saturating_sub(1);
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                self.selection = (self.selection + 1)
+                    .min(self.files.len().saturating_sub(1));
+            } else if keys.matches(&k, KeyAction::Enter) {
+                self.clear_selected()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -1,10 +1,14 @@
 use super::{
     command_pump, event_pump, visibility_blocking, CommandBlocking,
     CommandInfo, CommitDetailsComponent, Component, DiffComponent,
-    DrawableComponent,
+    DrawableComponent, SharedDiffOptions,
 };
 use crate::{
-    accessors, keys, queue::Queue, strings::commands,
+    accessors,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::commands,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
@@ -14,6 +18,7 @@ use asyncgit::{
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
+use std::rc::Rc;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -28,6 +33,8 @@ pub struct InspectCommitComponent {
     details: CommitDetailsComponent,
     git_diff: AsyncDiff,
     visible: bool,
+    queue: Queue,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for InspectCommitComponent {
@@ -93,6 +100,18 @@ impl Component for InspectCommitComponent {
                 true,
                 self.diff.focused() || force_all,
             ));
+
+            out.push(CommandInfo::new(
+                commands::INSPECT_SAVE_FILE,
+                self.can_focus_diff(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                commands::INSPECT_HEX_VIEW,
+                self.can_focus_diff(),
+                true,
+            ));
         }
 
         visibility_blocking(self)
@@ -105,19 +124,26 @@ impl Component for InspectCommitComponent {
             }
 
             if let Event::Key(e) = ev {
-                match e {
-                    keys::EXIT_POPUP => {
-                        self.hide();
-                    }
-                    keys::FOCUS_RIGHT if self.can_focus_diff() => {
-                        self.details.focus(false);
-                        self.diff.focus(true);
-                    }
-                    keys::FOCUS_LEFT if self.diff.focused() => {
-                        self.details.focus(true);
-                        self.diff.focus(false);
-                    }
-                    _ => (),
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                } else if keys.matches(&e, KeyAction::FocusRight)
+                    && self.can_focus_diff()
+                {
+                    self.details.focus(false);
+                    self.diff.focus(true);
+                } else if keys.matches(&e, KeyAction::FocusLeft)
+                    && self.diff.focused()
+                {
+                    self.details.focus(true);
+                    self.diff.focus(false);
+                } else if keys.matches(&e, KeyAction::InspectSaveFile)
+                {
+                    self.save_file();
+                } else if keys.matches(&e, KeyAction::InspectHexView)
+                {
+                    self.open_hex_view();
                 }
 
                 // stop key event propagation
@@ -147,23 +173,60 @@ impl Component for InspectCommitComponent {
 impl InspectCommitComponent {
     accessors!(self, [diff, details]);
 
+    /// the diff, focused or not
+    pub const fn diff(&self) -> &DiffComponent {
+        &self.diff
+    }
+
+    /// the diff if it has the focus
+    pub fn focused_diff(&self) -> Option<&DiffComponent> {
+        if self.diff.focused() {
+            Some(&self.diff)
+        } else {
+            None
+        }
+    }
+
+    ///
+    pub fn focused_diff_mut(&mut self) -> Option<&mut DiffComponent> {
+        if self.diff.focused() {
+            Some(&mut self.diff)
+        } else {
+            None
+        }
+    }
+
     ///
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        diff_options: &SharedDiffOptions,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
     ) -> Self {
         Self {
             details: CommitDetailsComponent::new(
                 queue,
                 sender,
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue,
+                true,
+                diff_options.clone(),
+                theme,
+                key_config.clone(),
+                config,
             ),
-            diff: DiffComponent::new(None, theme),
             commit_id: None,
             tags: None,
             git_diff: AsyncDiff::new(sender.clone()),
             visible: false,
+            queue: queue.clone(),
+            key_config,
         }
     }
 
@@ -191,7 +254,11 @@ impl InspectCommitComponent {
         ev: AsyncNotification,
     ) -> Result<()> {
         if self.is_visible() {
-            if let AsyncNotification::CommitFiles = ev {
+            if matches!(
+                ev,
+                AsyncNotification::CommitFiles
+                    | AsyncNotification::Signature
+            ) {
                 self.update()?
             } else if let AsyncNotification::Diff = ev {
                 self.update_diff()?
@@ -209,7 +276,9 @@ impl InspectCommitComponent {
                 {
                     let diff_params = DiffParams {
                         path: f.path.clone(),
+                        old_path: None,
                         diff_type: DiffType::Commit(id),
+                        options: self.diff.options(),
                     };
 
                     if let Some((params, last)) =
@@ -240,6 +309,26 @@ impl InspectCommitComponent {
         Ok(())
     }
 
+    fn save_file(&self) {
+        if let Some(id) = self.commit_id {
+            if let Some(f) = self.details.files().selection_file() {
+                self.queue
+                    .borrow_mut()
+                    .push_back(InternalEvent::SaveFile(id, f.path));
+            }
+        }
+    }
+
+    fn open_hex_view(&self) {
+        if let Some(id) = self.commit_id {
+            if let Some(f) = self.details.files().selection_file() {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::OpenHexView(id, f.path),
+                );
+            }
+        }
+    }
+
     fn can_focus_diff(&self) -> bool {
         self.details.files().selection_file().is_some()
     }
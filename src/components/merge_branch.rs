@@ -0,0 +1,293 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{
+        self,
+        notifications::{Notification, NotificationLevel},
+        style::SharedTheme,
+    },
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, MergeOutcome, MergeStrategy},
+    CWD,
+};
+use crossterm::event::Event;
+use std::{borrow::Cow, rc::Rc, time::Duration};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// rows of the popup, the last one starts the merge
+const FIELDS: [&str; 3] = ["branch", "strategy", ""];
+const FIELD_BRANCH: usize = 0;
+const FIELD_STRATEGY: usize = 1;
+const FIELD_MERGE: usize = 2;
+
+const STRATEGIES: [MergeStrategy; 3] = [
+    MergeStrategy::NoFastForward,
+    MergeStrategy::FastForwardOnly,
+    MergeStrategy::Squash,
+];
+
+/// merges a local branch of choice into the current one, a merge
+/// that needs a commit continues in the commit popup
+pub struct MergeBranchComponent {
+    branches: Vec<String>,
+    branch: usize,
+    strategy: usize,
+    selection: usize,
+    visible: bool,
+    hooks_timeout: Option<Duration>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl MergeBranchComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: &SharedConfig,
+    ) -> Self {
+        Self {
+            branches: Vec::new(),
+            branch: 0,
+            strategy: 0,
+            selection: FIELD_BRANCH,
+            visible: false,
+            hooks_timeout: config.hooks.timeout(),
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// offers every local branch but the current one
+    pub fn open(&mut self) -> Result<()> {
+        if sync::get_detached_head(CWD)?.is_some() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::MERGE_BRANCH_DETACHED,
+                )),
+            );
+            return Ok(());
+        }
+
+        let current = sync::get_branch_name(CWD)?;
+        self.branches = sync::get_local_branches(CWD)?;
+        self.branches.retain(|branch| *branch != current);
+        if self.branches.is_empty() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::MERGE_BRANCH_NONE,
+                )),
+            );
+            return Ok(());
+        }
+
+        self.branch = 0;
+        self.selection = FIELD_BRANCH;
+
+        self.show()
+    }
+
+    fn merge(&mut self) {
+        let branch = &self.branches[self.branch];
+        let strategy = STRATEGIES[self.strategy];
+
+        let result = sync::merge_branch(CWD, branch, strategy);
+
+        // a merge commit runs it once it is committed
+        if matches!(result, Ok(MergeOutcome::FastForwarded))
+            || (matches!(result, Ok(MergeOutcome::ReadyToCommit))
+                && strategy == MergeStrategy::Squash)
+        {
+            self.post_merge(strategy == MergeStrategy::Squash);
+        }
+
+        let event = match result {
+            Ok(MergeOutcome::UpToDate) => {
+                InternalEvent::Notify(Notification::success(
+                    strings::MERGE_BRANCH_UP_TO_DATE,
+                ))
+            }
+            Ok(MergeOutcome::FastForwarded) => {
+                InternalEvent::Notify(Notification::success(format!(
+                    "{} {}",
+                    strings::MERGE_BRANCH_DONE,
+                    branch
+                )))
+            }
+            Ok(MergeOutcome::ReadyToCommit) => {
+                InternalEvent::OpenCommit
+            }
+            // the status tab shows them
+            Ok(MergeOutcome::Conflicts) => InternalEvent::TabSwitch,
+            Err(e) => InternalEvent::Notify(Notification::error(
+                strings::MERGE_BRANCH_FAILED,
+                e.to_string(),
+            )),
+        };
+
+        self.hide();
+        let mut queue = self.queue.borrow_mut();
+        queue.push_back(event);
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    /// `git merge` is kept from running it, see `sync::merge_branch`
+    fn post_merge(&self, squash: bool) {
+        let error = match sync::hooks_post_merge(
+            CWD,
+            squash,
+            self.hooks_timeout,
+        ) {
+            Ok(res) => res.error(),
+            Err(e) => Some(e.to_string()),
+        };
+
+        if let Some(e) = error {
+            log::error!("post-merge hook error: {}", e);
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::from_report(
+                    NotificationLevel::Warning,
+                    &e,
+                ),
+            ));
+        }
+    }
+
+    fn value(&self, field: usize) -> &str {
+        match field {
+            FIELD_BRANCH => &self.branches[self.branch],
+            FIELD_STRATEGY => STRATEGIES[self.strategy].name(),
+            _ => "",
+        }
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        FIELDS
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                let line = if idx == FIELD_MERGE {
+                    format!("\n{}\n", strings::MERGE_BRANCH_START)
+                } else {
+                    format!("{:<10}{}\n", label, self.value(idx))
+                };
+
+                Text::Styled(
+                    Cow::from(line),
+                    self.theme.text(true, idx == self.selection),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for MergeBranchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect_absolute(50, 7, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter()).block(
+                    Block::default()
+                        .title(strings::MERGE_BRANCH_TITLE)
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true)),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for MergeBranchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::MERGE_BRANCH_ENTER,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if keys.matches(&k, KeyAction::ExitPopup) {
+                self.hide();
+            } else if keys.matches(&k, KeyAction::MoveUp) {
+                self.selection = self.selection.saturating_sub(1);
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                self.selection =
+                    (self.selection + 1).min(FIELD_MERGE);
+            } else if keys.matches(&k, KeyAction::Enter) {
+                match self.selection {
+                    FIELD_BRANCH => {
+                        self.branch =
+                            (self.branch + 1) % self.branches.len();
+                    }
+                    FIELD_STRATEGY => {
+                        self.strategy =
+                            (self.strategy + 1) % STRATEGIES.len();
+                    }
+                    _ => self.merge(),
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -1,41 +1,78 @@
+mod apply_patch;
+mod archive;
 mod changes;
 mod command;
 mod commit;
 mod commit_details;
 mod commitlist;
+mod conflict;
+mod contributors;
+mod create_branch;
 mod diff;
+mod export_patch;
 mod externaleditor;
 mod filetree;
+mod find;
+mod go_to_line;
 mod help;
+mod hexview;
+mod hidden_files;
 mod inspect_commit;
+mod merge_branch;
 mod msg;
+mod note;
+mod palette;
+mod push;
 mod reset;
+mod save_file;
+mod staged_preview;
 mod stashmsg;
+mod stats;
 mod tag_commit;
 mod textinput;
 mod utils;
 
 use anyhow::Result;
-use crossterm::event::Event;
+use crossterm::event::{Event, MouseEvent};
 
+pub use apply_patch::ApplyPatchComponent;
+pub use archive::ArchiveComponent;
 pub use changes::ChangesComponent;
 pub use command::{CommandInfo, CommandText};
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
-pub use diff::DiffComponent;
+pub use conflict::ConflictComponent;
+pub use contributors::ContributorsComponent;
+pub use create_branch::CreateBranchComponent;
+pub use diff::{DiffComponent, SharedDiffOptions};
+pub use export_patch::ExportPatchComponent;
 pub use externaleditor::ExternalEditorComponent;
 pub use filetree::FileTreeComponent;
+pub use find::FindComponent;
+pub use go_to_line::{GoTo, GoToLineComponent};
 pub use help::HelpComponent;
+pub use hexview::HexViewComponent;
+pub use hidden_files::HiddenFilesComponent;
 pub use inspect_commit::InspectCommitComponent;
+pub use merge_branch::MergeBranchComponent;
 pub use msg::MsgComponent;
+pub use note::NoteComponent;
+pub use palette::PaletteComponent;
+pub use push::PushComponent;
 pub use reset::ResetComponent;
+pub use save_file::SaveFileComponent;
+pub use staged_preview::StagedPreviewComponent;
 pub use stashmsg::StashMsgComponent;
+pub use stats::StatsComponent;
 pub use tag_commit::TagCommitComponent;
 pub use textinput::TextInputComponent;
 pub use utils::filetree::FileTreeItemKind;
+pub use utils::git_config;
+pub use utils::search::Search;
+pub use utils::split::HorizontalSplit;
 
-use crate::ui::style::Theme;
+use crate::ui::{self, style::Theme};
 use tui::{
     backend::Backend,
     layout::{Alignment, Rect},
@@ -105,6 +142,26 @@ pub enum ScrollType {
     PageDown,
 }
 
+/// `Up`/`Down` if `ev` turns the mouse wheel inside of `area`
+pub const fn mouse_scroll(
+    ev: MouseEvent,
+    area: Rect,
+) -> Option<ScrollType> {
+    match ev {
+        MouseEvent::ScrollUp(column, row, _)
+            if ui::rect_contains(area, column, row) =>
+        {
+            Some(ScrollType::Up)
+        }
+        MouseEvent::ScrollDown(column, row, _)
+            if ui::rect_contains(area, column, row) =>
+        {
+            Some(ScrollType::Down)
+        }
+        _ => None,
+    }
+}
+
 ///
 #[derive(PartialEq)]
 pub enum CommandBlocking {
@@ -0,0 +1,179 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, CommitId},
+    CWD,
+};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// adds or edits the `git notes` of a commit, saving an empty note
+/// asks to delete the existing one
+pub struct NoteComponent {
+    input: TextInputComponent,
+    commit_id: Option<CommitId>,
+    has_note: bool,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for NoteComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for NoteComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::NOTE_CONFIRM_MSG,
+                !self.input.get_text().is_empty() || self.has_note,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.save();
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl NoteComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::NOTE_POPUP_TITLE,
+                strings::NOTE_POPUP_MSG,
+            )
+            .multiline(),
+            queue,
+            key_config,
+            commit_id: None,
+            has_note: false,
+        }
+    }
+
+    /// starts out with the note the commit has
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        let note = sync::get_note(CWD, id)?;
+
+        self.commit_id = Some(id);
+        self.has_note = note.is_some();
+        self.input.set_text(note.unwrap_or_default());
+        self.input.cursor_end();
+        self.show()?;
+
+        Ok(())
+    }
+
+    fn save(&mut self) {
+        if let Some(id) = self.commit_id {
+            if self.input.get_text().trim().is_empty() {
+                if self.has_note {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ConfirmAction(
+                            Action::DeleteNote(id),
+                        ),
+                    );
+                }
+                self.hide();
+            } else {
+                let result =
+                    sync::set_note(CWD, id, self.input.get_text());
+                self.hide();
+                Self::done(
+                    &self.queue,
+                    result.map_err(Into::into),
+                    strings::NOTIFY_NOTE_SAVED,
+                );
+            }
+        }
+    }
+
+    /// deletes the note of the commit once confirmed
+    pub fn delete(queue: &Queue, id: CommitId) {
+        Self::done(
+            queue,
+            sync::delete_note(CWD, id).map_err(Into::into),
+            strings::NOTIFY_NOTE_DELETED,
+        );
+    }
+
+    fn done(queue: &Queue, result: Result<()>, success: &str) {
+        let event = match result {
+            Ok(()) => {
+                InternalEvent::Notify(Notification::success(success))
+            }
+            Err(e) => InternalEvent::Notify(Notification::error(
+                strings::NOTE_FAILED,
+                e.to_string(),
+            )),
+        };
+
+        let mut queue = queue.borrow_mut();
+        queue.push_back(event);
+        queue.push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+}
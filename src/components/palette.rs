@@ -0,0 +1,340 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys::{key_name, KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use itertools::Itertools;
+use std::{borrow::Cow, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// a command of the context the palette was opened in
+struct Entry {
+    label: &'static str,
+    desc: &'static str,
+    action: KeyAction,
+}
+
+/// lists the commands available where it was opened, filters them
+/// by what is typed and runs the chosen one
+pub struct PaletteComponent {
+    entries: Vec<Entry>,
+    query: String,
+    /// indices into `entries`, best match first
+    matches: Vec<usize>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl PaletteComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            entries: Vec::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// the commands of the current context, only runnable ones are
+    /// kept. ignored while open, the palette itself is the context
+    /// then
+    pub fn set_cmds(&mut self, cmds: &[CommandInfo]) {
+        if self.visible {
+            return;
+        }
+
+        self.entries = cmds
+            .iter()
+            .filter(|cmd| cmd.enabled && cmd.available)
+            .filter_map(|cmd| {
+                let action = cmd.text.action?;
+                if action == KeyAction::OpenPalette {
+                    return None;
+                }
+
+                Some(Entry {
+                    // the key is shown from the key config instead
+                    label: cmd.text.label(),
+                    desc: cmd.text.desc,
+                    action,
+                })
+            })
+            .unique_by(|entry| (entry.label, entry.action))
+            .collect();
+    }
+
+    fn filter(&mut self) {
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| {
+                fuzzy_score(
+                    &self.query,
+                    &format!("{} {}", entry.label, entry.desc),
+                )
+                .map(|score| (score, idx))
+            })
+            .sorted()
+            .map(|(_, idx)| idx)
+            .collect();
+        self.selection = 0;
+    }
+
+    fn run_selected(&mut self) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selection)
+            .and_then(|idx| self.entries.get(*idx))
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ExecuteAction(entry.action),
+            );
+        }
+
+        self.hide();
+    }
+
+    fn get_text(&self, height: usize) -> Vec<Text> {
+        let mut txt = vec![
+            Text::Styled(
+                Cow::from(format!("> {}\n", self.query)),
+                Style::default().modifier(Modifier::BOLD),
+            ),
+            Text::Raw(Cow::from("\n")),
+        ];
+
+        if self.matches.is_empty() {
+            txt.push(Text::Styled(
+                Cow::from(strings::PALETTE_EMPTY),
+                self.theme.text(false, false),
+            ));
+            return txt;
+        }
+
+        let rows = height.saturating_sub(txt.len()).max(1);
+        let skip = (self.selection + 1).saturating_sub(rows);
+
+        for (idx, entry) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .take(rows)
+            .filter_map(|(idx, entry)| {
+                self.entries.get(*entry).map(|entry| (idx, entry))
+            })
+        {
+            let keys = self
+                .key_config
+                .keys(entry.action)
+                .iter()
+                .map(key_name)
+                .join(", ");
+            let selected = idx == self.selection;
+
+            txt.push(Text::Styled(
+                Cow::from(format!(
+                    "{:<18}{:<14}{}\n",
+                    entry.label,
+                    if keys.is_empty() { "-" } else { &keys },
+                    entry.desc
+                )),
+                self.theme.text(true, selected),
+            ));
+        }
+
+        txt
+    }
+}
+
+/// how well `query` matches `text` as a case insensitive
+/// subsequence, lower is better, `None` if it does not match
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text = text.to_lowercase();
+    let mut chars = text.char_indices();
+    let mut score = 0;
+    let mut last = None;
+
+    for q in query.to_lowercase().chars().filter(|c| *c != ' ') {
+        let (idx, _) = chars.find(|(_, c)| *c == q)?;
+        // gaps between the matched chars count, so do leading ones
+        score += last.map_or(idx, |last| idx - last - 1);
+        last = Some(idx);
+    }
+
+    Some(score)
+}
+
+impl DrawableComponent for PaletteComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect_absolute(80, 20, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(usize::from(
+                        area.height.saturating_sub(2),
+                    ))
+                    .iter(),
+                )
+                .block(
+                    Block::default()
+                        .title(strings::PALETTE_TITLE)
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for PaletteComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible && !force_all {
+            out.clear();
+        }
+
+        if self.visible {
+            out.push(CommandInfo::new(
+                commands::PALETTE_RUN,
+                !self.matches.is_empty(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        if !self.visible || force_all {
+            out.push(
+                CommandInfo::new(commands::OPEN_PALETTE, true, true)
+                    .hidden(),
+            );
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if let Event::Key(e) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if !self.visible {
+                if keys.matches(&e, KeyAction::OpenPalette) {
+                    self.show()?;
+                    return Ok(true);
+                }
+                return Ok(false);
+            }
+
+            // typing wins over bindings to plain chars (like `j` of
+            // the vim preset)
+            match e.code {
+                KeyCode::Char(c)
+                    if !e.modifiers.intersects(
+                        KeyModifiers::CONTROL | KeyModifiers::ALT,
+                    ) =>
+                {
+                    self.query.push(c);
+                    self.filter();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.filter();
+                }
+                _ if keys.matches(&e, KeyAction::ExitPopup) => {
+                    self.hide();
+                }
+                _ if keys.matches(&e, KeyAction::Enter) => {
+                    self.run_selected();
+                }
+                _ if keys.matches(&e, KeyAction::MoveUp) => {
+                    self.selection = self.selection.saturating_sub(1);
+                }
+                _ if keys.matches(&e, KeyAction::MoveDown) => {
+                    self.selection = (self.selection + 1)
+                        .min(self.matches.len().saturating_sub(1));
+                }
+                _ => (),
+            }
+
+            return Ok(true);
+        }
+
+        Ok(self.visible)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.query.clear();
+        self.filter();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Stage All"), Some(0));
+        assert_eq!(fuzzy_score("stage", "Stage All"), Some(0));
+        assert_eq!(fuzzy_score("sa", "Stage All"), Some(1));
+        assert_eq!(fuzzy_score("st al", "Stage All"), Some(4));
+        assert_eq!(fuzzy_score("push", "Stage All"), None);
+        assert!(
+            fuzzy_score("cp", "Copy Patch")
+                < fuzzy_score("cp", "Commit in place")
+        );
+    }
+}
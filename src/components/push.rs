@@ -0,0 +1,593 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{Action, InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{
+        self, PushMode, PushProgress, PushRequest, PushTarget,
+        RemoteUrls,
+    },
+    AsyncNotification, AsyncPush, PushOutcome, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::{borrow::Cow, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// rows of the popup, the last one starts the push
+const FIELDS: [&str; 6] =
+    ["remote", "fetch", "push to", "branch", "mode", ""];
+const FIELD_REMOTE: usize = 0;
+const FIELD_FETCH_URL: usize = 1;
+const FIELD_PUSH_URL: usize = 2;
+const FIELD_TARGET: usize = 3;
+const FIELD_MODE: usize = 4;
+const FIELD_PUSH: usize = 5;
+
+/// pushes the current branch or a tag to a remote of choice, forcing
+/// only after a confirmation, the urls of the remote can be edited.
+/// the output of a failing `pre-push` hook is shown on top, from
+/// where the push can be retried without it
+pub struct PushComponent {
+    remotes: Vec<String>,
+    remote: usize,
+    urls: RemoteUrls,
+    input: TextInputComponent,
+    target: PushTarget,
+    mode: PushMode,
+    selection: usize,
+    git_push: AsyncPush,
+    hook_failed: Option<(PushRequest, String)>,
+    hook_scroll: u16,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl PushComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        let git_push = AsyncPush::new(sender)
+            .hooks_timeout(config.hooks.timeout());
+
+        Self {
+            remotes: Vec::new(),
+            remote: 0,
+            urls: RemoteUrls::default(),
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config,
+                "",
+                strings::PUSH_URL_MSG,
+            ),
+            target: PushTarget::Branch(String::new()),
+            mode: PushMode::Normal,
+            selection: FIELD_PUSH,
+            git_push,
+            hook_failed: None,
+            hook_scroll: 0,
+            visible: false,
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    /// pushes the current branch
+    pub fn open(&mut self) -> Result<()> {
+        if sync::get_detached_head(CWD)?.is_some() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::PUSH_DETACHED,
+                )),
+            );
+            return Ok(());
+        }
+
+        self.open_target(PushTarget::Branch(sync::get_branch_name(
+            CWD,
+        )?))
+    }
+
+    ///
+    pub fn open_tag(&mut self, tag: String) -> Result<()> {
+        self.open_target(PushTarget::Tag(tag))
+    }
+
+    /// starts with `origin` if there is one
+    fn open_target(&mut self, target: PushTarget) -> Result<()> {
+        self.remotes = sync::get_remotes(CWD)?;
+        if self.remotes.is_empty() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(String::from(
+                    strings::PUSH_NO_REMOTE,
+                )),
+            );
+            return Ok(());
+        }
+
+        self.target = target;
+        self.remote = self
+            .remotes
+            .iter()
+            .position(|remote| remote == "origin")
+            .unwrap_or_default();
+        self.load_urls();
+        self.mode = PushMode::Normal;
+        self.selection = FIELD_PUSH;
+        self.hook_failed = None;
+
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_push.is_pending()
+    }
+
+    fn hook_running(&self) -> bool {
+        self.any_work_pending()
+            && matches!(
+                self.git_push.progress(),
+                Ok(Some(PushProgress::Hook))
+            )
+    }
+
+    /// runs a push, force pushes come back here once confirmed
+    pub fn push(&mut self, request: PushRequest) -> Result<()> {
+        self.git_push.request(request)?;
+
+        Ok(())
+    }
+
+    /// reports the finished push, closes the popup on success
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if ev != AsyncNotification::Push {
+            return Ok(());
+        }
+
+        if let Some((request, outcome)) = self.git_push.take_last()? {
+            let notification = match outcome {
+                PushOutcome::Pushed => {
+                    self.hide();
+                    Notification::success(format!(
+                        "{} {}/{}",
+                        strings::PUSH_DONE,
+                        request.remote,
+                        request.target.name()
+                    ))
+                }
+                PushOutcome::HookFailed(output) => {
+                    self.hook_failed = Some((request, output));
+                    self.hook_scroll = 0;
+                    return Ok(());
+                }
+                PushOutcome::Failed(e) => {
+                    Notification::error(strings::PUSH_FAILED, e)
+                }
+            };
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Notify(notification));
+        }
+
+        Ok(())
+    }
+
+    /// pushes what the `pre-push` hook rejected once more, skipping it
+    fn retry_no_verify(&mut self) -> Result<()> {
+        if let Some((mut request, _)) = self.hook_failed.take() {
+            request.no_verify = true;
+            self.push(request)?;
+        }
+
+        Ok(())
+    }
+
+    /// stops at the last line of the output
+    fn scroll_hook_output_down(&mut self) {
+        if let Some((_, output)) = &self.hook_failed {
+            let last = output.lines().count().saturating_sub(1);
+            if usize::from(self.hook_scroll) < last {
+                self.hook_scroll += 1;
+            }
+        }
+    }
+
+    fn draw_hook_output<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        output: &str,
+    ) {
+        let area = ui::centered_rect(60, 50, f.size());
+        let txt = [Text::Raw(Cow::from(output))];
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(txt.iter())
+                .block(
+                    Block::default()
+                        .title(strings::PUSH_HOOK_FAILED_TITLE)
+                        .title_style(self.theme.text_danger())
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Thick),
+                )
+                .alignment(Alignment::Left)
+                .scroll(self.hook_scroll)
+                .wrap(true),
+            area,
+        );
+    }
+
+    fn load_urls(&mut self) {
+        self.urls =
+            sync::get_remote_urls(CWD, &self.remotes[self.remote])
+                .unwrap_or_default();
+    }
+
+    /// the urls as they would be with the edited one saved
+    fn preview(&self) -> RemoteUrls {
+        let mut urls = self.urls.clone();
+        if self.input.is_visible() {
+            let url = Some(self.input.get_text().trim().to_string());
+            if self.selection == FIELD_PUSH_URL {
+                urls.push_url = url;
+            } else {
+                urls.url = url;
+            }
+        }
+
+        urls
+    }
+
+    fn edit_url(&mut self) -> Result<()> {
+        let field = if self.selection == FIELD_PUSH_URL {
+            FIELD_PUSH_URL
+        } else {
+            FIELD_FETCH_URL
+        };
+        self.selection = field;
+
+        self.input.set_text(self.value(field));
+        self.input.cursor_end();
+        self.update_preview();
+        self.input.show()
+    }
+
+    /// the title of the input tells where fetch and push would go or
+    /// why the url is not valid
+    fn update_preview(&mut self) {
+        let url = self.input.get_text().trim().to_string();
+        let title = match sync::validate_remote_url(&url) {
+            Ok(()) => {
+                let preview = self.preview();
+                format!(
+                    "{}: {}, {}: {}",
+                    FIELDS[FIELD_FETCH_URL],
+                    preview.url.as_deref().unwrap_or_default(),
+                    FIELDS[FIELD_PUSH_URL],
+                    preview.push_target().unwrap_or_default()
+                )
+            }
+            Err(e) => format!("{}: {}", strings::PUSH_URL_INVALID, e),
+        };
+
+        self.input.set_title(title);
+    }
+
+    fn save_url(&mut self) {
+        let remote = self.remotes[self.remote].clone();
+        match sync::set_remote_url(
+            CWD,
+            &remote,
+            self.input.get_text(),
+            self.selection == FIELD_PUSH_URL,
+        ) {
+            Ok(()) => {
+                self.input.hide();
+                self.load_urls();
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::success(
+                        format!(
+                            "{} {}",
+                            strings::PUSH_URL_SET,
+                            remote
+                        ),
+                    )),
+                );
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "{}:\n{}",
+                        strings::PUSH_URL_INVALID,
+                        e
+                    )),
+                );
+            }
+        }
+    }
+
+    fn request(&self) -> PushRequest {
+        PushRequest {
+            remote: self.remotes[self.remote].clone(),
+            target: self.target.clone(),
+            mode: self.mode,
+            no_verify: false,
+        }
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        match self.selection {
+            FIELD_REMOTE => {
+                self.remote = (self.remote + 1) % self.remotes.len();
+                self.load_urls();
+            }
+            FIELD_FETCH_URL | FIELD_PUSH_URL => self.edit_url()?,
+            FIELD_MODE => {
+                let tag = matches!(self.target, PushTarget::Tag(_));
+                // a tag has no remote-tracking ref to lease against
+                self.mode = match self.mode {
+                    PushMode::Normal if tag => PushMode::Force,
+                    PushMode::Normal => PushMode::ForceWithLease,
+                    PushMode::ForceWithLease => PushMode::Force,
+                    PushMode::Force => PushMode::Normal,
+                };
+            }
+            FIELD_PUSH if self.mode == PushMode::Normal => {
+                self.push(self.request())?;
+            }
+            FIELD_PUSH => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(Action::ForcePush(
+                        self.request(),
+                    )),
+                );
+            }
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn value(&self, field: usize) -> String {
+        match field {
+            FIELD_REMOTE => self.remotes[self.remote].clone(),
+            FIELD_FETCH_URL => {
+                self.urls.url.clone().unwrap_or_default()
+            }
+            FIELD_PUSH_URL => self
+                .urls
+                .push_target()
+                .map(String::from)
+                .unwrap_or_default(),
+            FIELD_TARGET => self.target.name().to_string(),
+            FIELD_MODE => String::from(match self.mode {
+                PushMode::Normal => strings::PUSH_MODE_NORMAL,
+                mode => mode.name(),
+            }),
+            _ => String::new(),
+        }
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        FIELDS
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                let selected = idx == self.selection;
+                let line = if idx == FIELD_PUSH {
+                    format!(
+                        "\n{}\n",
+                        if self.hook_running() {
+                            strings::PUSH_HOOK_RUNNING
+                        } else if self.any_work_pending() {
+                            strings::PUSH_PUSHING
+                        } else {
+                            strings::PUSH_START
+                        }
+                    )
+                } else {
+                    let label = match self.target {
+                        PushTarget::Tag(_) if idx == FIELD_TARGET => {
+                            strings::PUSH_TAG_LABEL
+                        }
+                        _ => label,
+                    };
+                    format!("{:<8}{}\n", label, self.value(idx))
+                };
+
+                Text::Styled(
+                    Cow::from(line),
+                    self.theme.text(true, selected),
+                )
+            })
+            .collect()
+    }
+}
+
+impl DrawableComponent for PushComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect_absolute(70, 10, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter()).block(
+                    Block::default()
+                        .title(strings::PUSH_TITLE)
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true)),
+                ),
+                area,
+            );
+
+            self.input.draw(f, rect)?;
+
+            if let Some((_, output)) = &self.hook_failed {
+                self.draw_hook_output(f, output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for PushComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.hook_failed.is_some() {
+            out.push(CommandInfo::new(commands::SCROLL, true, true));
+            out.push(CommandInfo::new(
+                commands::PUSH_RETRY_NO_VERIFY,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+            return CommandBlocking::Blocking;
+        }
+
+        if self.input.is_visible() {
+            self.input.commands(out, force_all);
+            out.push(CommandInfo::new(
+                commands::PUSH_SAVE_URL,
+                true,
+                true,
+            ));
+            return CommandBlocking::Blocking;
+        }
+
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::PUSH_ENTER,
+                !self.any_work_pending(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::PUSH_EDIT_URL,
+                true,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        if self.hook_failed.is_some() {
+            if let Event::Key(k) = ev {
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&k, KeyAction::ExitPopup) {
+                    self.hook_failed = None;
+                } else if keys.matches(&k, KeyAction::PushNoVerify) {
+                    self.retry_no_verify()?;
+                } else if keys.matches(&k, KeyAction::MoveUp) {
+                    self.hook_scroll =
+                        self.hook_scroll.saturating_sub(1);
+                } else if keys.matches(&k, KeyAction::MoveDown) {
+                    self.scroll_hook_output_down();
+                }
+            }
+
+            // the hook output is modal
+            return Ok(true);
+        }
+
+        if self.input.is_visible() {
+            if self.input.event(ev)? {
+                self.update_preview();
+                return Ok(true);
+            }
+
+            if let Event::Key(k) = ev {
+                if self.key_config.matches(&k, KeyAction::Enter) {
+                    self.save_url();
+                }
+            }
+
+            // the input is modal
+            return Ok(true);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            if keys.matches(&k, KeyAction::ExitPopup) {
+                self.hide();
+            } else if keys.matches(&k, KeyAction::PushEditUrl) {
+                self.edit_url()?;
+            } else if keys.matches(&k, KeyAction::MoveUp) {
+                self.selection = self.selection.saturating_sub(1);
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                self.selection = (self.selection + 1).min(FIELD_PUSH);
+            } else if keys.matches(&k, KeyAction::Enter)
+                && !self.any_work_pending()
+            {
+                self.enter()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -3,12 +3,14 @@ use crate::{
         popup_paragraph, visibility_blocking, CommandBlocking,
         CommandInfo, Component, DrawableComponent,
     },
+    keys::{KeyAction, SharedKeyConfig},
     queue::{Action, InternalEvent, Queue},
     strings::{self, commands},
     ui,
 };
 use anyhow::Result;
-use crossterm::event::{Event, KeyCode};
+use asyncgit::sync::{BisectMark, PushTarget};
+use crossterm::event::Event;
 use std::borrow::Cow;
 use tui::{
     backend::Backend,
@@ -24,6 +26,7 @@ pub struct ResetComponent {
     visible: bool,
     queue: Queue,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for ResetComponent {
@@ -75,19 +78,16 @@ impl Component for ResetComponent {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.visible {
             if let Event::Key(e) = ev {
-                return match e.code {
-                    KeyCode::Esc => {
-                        self.hide();
-                        Ok(true)
-                    }
-
-                    KeyCode::Enter => {
-                        self.confirm();
-                        Ok(true)
-                    }
-
-                    _ => Ok(true),
-                };
+                if self.key_config.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                } else if self
+                    .key_config
+                    .matches(&e, KeyAction::Enter)
+                {
+                    self.confirm();
+                }
+
+                return Ok(true);
             }
         }
 
@@ -111,12 +111,17 @@ impl Component for ResetComponent {
 
 impl ResetComponent {
     ///
-    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
         Self {
             target: None,
             visible: false,
             queue,
             theme,
+            key_config,
         }
     }
     ///
@@ -144,14 +149,56 @@ impl ResetComponent {
                     strings::CONFIRM_TITLE_RESET,
                     strings::CONFIRM_MSG_RESET,
                 ),
+                Action::ResetItems(_) => (
+                    strings::CONFIRM_TITLE_RESET,
+                    strings::CONFIRM_MSG_RESET_MARKED,
+                ),
                 Action::StashDrop(_) => (
                     strings::CONFIRM_TITLE_STASHDROP,
                     strings::CONFIRM_MSG_STASHDROP,
                 ),
-                Action::ResetHunk(_, _) => (
+                Action::ResetHunk(_, _, _) => (
                     strings::CONFIRM_TITLE_RESET,
                     strings::CONFIRM_MSG_RESETHUNK,
                 ),
+                Action::OverwriteFile(_) => (
+                    strings::CONFIRM_TITLE_OVERWRITE,
+                    strings::CONFIRM_MSG_OVERWRITE,
+                ),
+                Action::ForcePush(request)
+                    if matches!(
+                        request.target,
+                        PushTarget::Tag(_)
+                    ) =>
+                {
+                    (
+                        strings::CONFIRM_TITLE_FORCE_PUSH,
+                        strings::CONFIRM_MSG_FORCE_PUSH_TAG,
+                    )
+                }
+                Action::ForcePush(_) => (
+                    strings::CONFIRM_TITLE_FORCE_PUSH,
+                    strings::CONFIRM_MSG_FORCE_PUSH,
+                ),
+                Action::CherryPick(_) => (
+                    strings::CONFIRM_TITLE_CHERRY_PICK,
+                    strings::CONFIRM_MSG_CHERRY_PICK,
+                ),
+                Action::DeleteNote(_) => (
+                    strings::CONFIRM_TITLE_DELETE_NOTE,
+                    strings::CONFIRM_MSG_DELETE_NOTE,
+                ),
+                Action::Undo(msg) => {
+                    (strings::CONFIRM_TITLE_UNDO, msg.as_str())
+                }
+                Action::BisectStart(_, BisectMark::Good) => (
+                    strings::CONFIRM_TITLE_BISECT,
+                    strings::CONFIRM_MSG_BISECT_GOOD,
+                ),
+                Action::BisectStart(_, BisectMark::Bad) => (
+                    strings::CONFIRM_TITLE_BISECT,
+                    strings::CONFIRM_MSG_BISECT_BAD,
+                ),
             };
         }
 
@@ -0,0 +1,174 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+};
+use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{Action, InternalEvent, Queue, SaveFileItem},
+    strings::{self, commands},
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, CommitId},
+    CWD,
+};
+use crossterm::event::Event;
+use std::path::Path;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the destination of a file as of a commit and saves it
+pub struct SaveFileComponent {
+    input: TextInputComponent,
+    target: Option<(CommitId, String)>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for SaveFileComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for SaveFileComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                commands::SAVE_FILE_CONFIRM,
+                !self.input.get_text().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.confirm();
+                }
+
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl SaveFileComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme,
+                key_config.clone(),
+                config,
+                strings::SAVE_FILE_POPUP_TITLE,
+                strings::SAVE_FILE_POPUP_MSG,
+            ),
+            queue,
+            key_config,
+            target: None,
+        }
+    }
+
+    /// suggests the file name as destination
+    pub fn open(&mut self, id: CommitId, path: String) -> Result<()> {
+        let name = Path::new(&path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        self.input.set_text(name);
+        self.target = Some((id, path));
+        self.show()?;
+
+        Ok(())
+    }
+
+    fn confirm(&mut self) {
+        if self.input.get_text().is_empty() {
+            return;
+        }
+
+        if let Some((commit, path)) = self.target.take() {
+            let item = SaveFileItem {
+                commit,
+                path,
+                dest: self.input.get_text().clone(),
+            };
+
+            if Path::new(&item.dest).exists() {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(
+                        Action::OverwriteFile(item),
+                    ),
+                );
+            } else {
+                Self::save(&self.queue, &item);
+            }
+        }
+
+        self.input.clear();
+        self.hide();
+    }
+
+    /// writes the file, errors are shown as message
+    pub fn save(queue: &Queue, item: &SaveFileItem) {
+        if let Err(e) = sync::save_file_at_commit(
+            CWD,
+            item.commit,
+            &item.path,
+            Path::new(&item.dest),
+        ) {
+            log::error!("save file error: {}", e);
+            queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "save file error:\n{}",
+                    e
+                )),
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DiffComponent, DrawableComponent, SharedDiffOptions,
+};
+use crate::{
+    clipboard,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{sync, CWD};
+use crossterm::event::Event;
+use std::rc::Rc;
+use tui::{backend::Backend, layout::Rect, widgets::Clear, Frame};
+
+/// everything that is staged as one diff against `HEAD`, i.e. what
+/// the next commit would contain
+pub struct StagedPreviewComponent {
+    diff: DiffComponent,
+    visible: bool,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
+}
+
+impl StagedPreviewComponent {
+    ///
+    pub fn new(
+        queue: &Queue,
+        diff_options: &SharedDiffOptions,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            diff: DiffComponent::new(
+                queue,
+                true,
+                diff_options.clone(),
+                theme,
+                key_config.clone(),
+                config.clone(),
+            ),
+            visible: false,
+            queue: queue.clone(),
+            key_config,
+            config,
+        }
+    }
+
+    /// reloads the preview if it is open
+    pub fn update(&mut self) -> Result<()> {
+        if self.visible {
+            self.diff.update(
+                String::from(strings::STAGED_PREVIEW_TITLE),
+                true,
+                sync::get_staged_preview(CWD)?,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// the whole preview, a per file patch would lose the renames
+    fn copy_patch(&self) -> Result<()> {
+        let notification = clipboard::copy_notification(
+            &sync::get_staged_patch(CWD)?,
+            strings::NOTIFY_COPIED_PATCH,
+            &self.config.clipboard,
+        );
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+
+        Ok(())
+    }
+}
+
+impl DrawableComponent for StagedPreviewComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect(80, 80, f.size());
+
+            f.render_widget(Clear, area);
+            self.diff.draw(f, area)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for StagedPreviewComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            self.diff.commands(out, force_all);
+
+            out.push(
+                CommandInfo::new(commands::CLOSE_POPUP, true, true)
+                    .order(1),
+            );
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                    return Ok(true);
+                } else if keys.matches(&e, KeyAction::DiffCopyPatch) {
+                    self.copy_patch()?;
+                    return Ok(true);
+                }
+            }
+
+            self.diff.event(ev)?;
+
+            // stop event propagation
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.diff.focus(true);
+        self.update()
+    }
+}
@@ -3,20 +3,23 @@ use super::{
     CommandBlocking, CommandInfo, Component, DrawableComponent,
 };
 use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings::{self, commands},
     tabs::StashingOptions,
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{sync, CWD};
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct StashMsgComponent {
     options: StashingOptions,
     input: TextInputComponent,
     queue: Queue,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for StashMsgComponent {
@@ -57,21 +60,43 @@ impl Component for StashMsgComponent {
             }
 
             if let Event::Key(e) = ev {
-                if let KeyCode::Enter = e.code {
-                    match sync::stash_save(
-                        CWD,
-                        if self.input.get_text().is_empty() {
-                            None
-                        } else {
-                            Some(self.input.get_text().as_str())
-                        },
-                        self.options.stash_untracked,
-                        self.options.keep_index,
-                    ) {
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    let message = if self.input.get_text().is_empty()
+                    {
+                        None
+                    } else {
+                        Some(self.input.get_text().as_str())
+                    };
+
+                    let result = if self.options.paths.is_empty() {
+                        sync::stash_save(
+                            CWD,
+                            message,
+                            self.options.stash_untracked,
+                            self.options.stash_ignored,
+                            self.options.keep_index,
+                        )
+                    } else {
+                        sync::stash_save_paths(
+                            CWD,
+                            message,
+                            &self.options.paths,
+                        )
+                    };
+
+                    match result {
                         Ok(_) => {
                             self.input.clear();
                             self.hide();
 
+                            self.queue.borrow_mut().push_back(
+                                InternalEvent::Notify(
+                                    Notification::success(
+                                        strings::NOTIFY_STASHED,
+                                    ),
+                                ),
+                            );
+
                             self.queue.borrow_mut().push_back(
                                 InternalEvent::Update(
                                     NeedsUpdate::ALL,
@@ -86,10 +111,15 @@ impl Component for StashMsgComponent {
                                 self.options
                             );
                             self.queue.borrow_mut().push_back(
-                                InternalEvent::ShowErrorMsg(format!(
-                                    "stash error:\n{}\noptions:\n{:?}",
-                                    e, self.options
-                                )),
+                                InternalEvent::Notify(
+                                    Notification::error(
+                                        "stash failed",
+                                        format!(
+                                            "{}\noptions:\n{:?}",
+                                            e, self.options
+                                        ),
+                                    ),
+                                ),
                             );
                         }
                     }
@@ -119,20 +149,54 @@ impl Component for StashMsgComponent {
 
 impl StashMsgComponent {
     ///
-    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
         Self {
             options: StashingOptions::default(),
-            queue,
             input: TextInputComponent::new(
+                queue.clone(),
                 theme,
+                key_config.clone(),
+                config,
                 strings::STASH_POPUP_TITLE,
                 strings::STASH_POPUP_MSG,
             ),
+            queue,
+            key_config,
         }
     }
 
     ///
+    /// how to stash, listed in the title
     pub fn options(&mut self, options: StashingOptions) {
+        let active = if options.paths.is_empty() {
+            [
+                (options.stash_untracked, strings::STASH_UNTRACKED),
+                (options.stash_ignored, strings::STASH_IGNORED),
+                (options.keep_index, strings::STASH_KEEP_INDEX),
+            ]
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>()
+        } else {
+            // marked files go with their untracked ones
+            vec![strings::STASH_MARKED, strings::STASH_UNTRACKED]
+        };
+
+        self.input.set_title(if active.is_empty() {
+            strings::STASH_POPUP_TITLE.to_string()
+        } else {
+            format!(
+                "{} ({})",
+                strings::STASH_POPUP_TITLE,
+                active.join(", ")
+            )
+        });
         self.options = options;
     }
 }
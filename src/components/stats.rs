@@ -0,0 +1,198 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent,
+};
+use crate::{
+    keys::{KeyAction, SharedKeyConfig},
+    strings::{self, commands},
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{format_commit_time, DateFormat, RepoStats},
+    AsyncNotification, AsyncRepoStats,
+};
+use bytesize::ByteSize;
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::borrow::Cow;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// overview numbers of the repository, computed in the background
+pub struct StatsComponent {
+    git_stats: AsyncRepoStats,
+    stats: Option<RepoStats>,
+    visible: bool,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl StatsComponent {
+    ///
+    pub fn new(
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            git_stats: AsyncRepoStats::new(sender),
+            stats: None,
+            visible: false,
+            theme,
+            key_config,
+        }
+    }
+
+    /// shows the cached stats right away and refreshes them if
+    /// `HEAD` moved since
+    pub fn open(&mut self) -> Result<()> {
+        self.stats = self.git_stats.last()?;
+        self.git_stats.fetch()?;
+        self.show()
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_stats.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.visible && ev == AsyncNotification::Stats {
+            self.stats = self.git_stats.last()?;
+        }
+
+        Ok(())
+    }
+
+    fn get_text(&self) -> Vec<Text> {
+        let stats = if let Some(stats) = &self.stats {
+            stats
+        } else {
+            return vec![Text::Raw(Cow::from(strings::LOADING_TEXT))];
+        };
+
+        let rows = [
+            ("commits", stats.commits.to_string()),
+            ("authors", stats.authors.to_string()),
+            (
+                "first commit",
+                stats
+                    .first_commit_time
+                    .map_or_else(String::new, |t| {
+                        format_commit_time(t, &DateFormat::Short)
+                    }),
+            ),
+            (
+                "branch",
+                stats.branch.clone().unwrap_or_else(|| {
+                    String::from(strings::STATS_NO_BRANCH)
+                }),
+            ),
+            ("branches", stats.branches.to_string()),
+            ("tags", stats.tags.to_string()),
+            ("tracked files", stats.tracked_files.to_string()),
+            (
+                "size of .git",
+                ByteSize::b(stats.size_on_disk).to_string(),
+            ),
+        ];
+
+        let mut txt = Vec::with_capacity(rows.len() * 2);
+        for (label, value) in &rows {
+            txt.push(Text::Styled(
+                Cow::from(format!("{:<14}", label)),
+                self.theme.text(false, false),
+            ));
+            txt.push(Text::Raw(Cow::from(format!("{}\n", value))));
+        }
+
+        if self.any_work_pending() {
+            txt.push(Text::Styled(
+                Cow::from(strings::STATS_UPDATING),
+                self.theme.text(false, false),
+            ));
+        }
+
+        txt
+    }
+}
+
+impl DrawableComponent for StatsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let area = ui::centered_rect_absolute(50, 12, f.size());
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(self.get_text().iter()).block(
+                    Block::default()
+                        .title(strings::STATS_TITLE)
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.block(true)),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for StatsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::CLOSE_POPUP,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::ExitPopup) {
+                    self.hide();
+                }
+            }
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
@@ -3,22 +3,35 @@ use super::{
     CommandBlocking, CommandInfo, Component, DrawableComponent,
 };
 use crate::{
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings::{self, commands},
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
     sync::{self, CommitId},
     CWD,
 };
-use crossterm::event::{Event, KeyCode};
+use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
+/// what the input asks for
+enum Mode {
+    Name,
+    /// the message of an annotated tag named `tag_name`
+    Annotation {
+        tag_name: String,
+    },
+}
+
 pub struct TagCommitComponent {
     input: TextInputComponent,
     commit_id: Option<CommitId>,
+    mode: Mode,
     queue: Queue,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for TagCommitComponent {
@@ -47,6 +60,16 @@ impl Component for TagCommitComponent {
                 true,
                 true,
             ));
+            out.push(CommandInfo::new(
+                commands::TAG_COMMIT_ANNOTATE,
+                !self.input.get_text().is_empty(),
+                matches!(self.mode, Mode::Name),
+            ));
+            out.push(CommandInfo::new(
+                commands::TAG_COMMIT_PUSH,
+                true,
+                true,
+            ));
         }
 
         visibility_blocking(self)
@@ -59,8 +82,18 @@ impl Component for TagCommitComponent {
             }
 
             if let Event::Key(e) = ev {
-                if let KeyCode::Enter = e.code {
-                    self.tag()
+                if self.key_config.matches(&e, KeyAction::Enter) {
+                    self.tag(false);
+                } else if self
+                    .key_config
+                    .matches(&e, KeyAction::TagPush)
+                {
+                    self.tag(true);
+                } else if self
+                    .key_config
+                    .matches(&e, KeyAction::TagAnnotate)
+                {
+                    self.annotate();
                 }
 
                 return Ok(true);
@@ -86,45 +119,103 @@ impl Component for TagCommitComponent {
 
 impl TagCommitComponent {
     ///
-    pub fn new(queue: Queue, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
         Self {
-            queue,
             input: TextInputComponent::new(
+                queue.clone(),
                 theme,
+                key_config.clone(),
+                config,
                 strings::TAG_COMMIT_POPUP_TITLE,
                 strings::TAG_COMMIT_POPUP_MSG,
-            ),
+            )
+            // for messages of annotated tags
+            .multiline(),
+            queue,
+            key_config,
             commit_id: None,
+            mode: Mode::Name,
         }
     }
 
     ///
     pub fn open(&mut self, id: CommitId) -> Result<()> {
         self.commit_id = Some(id);
+        self.mode = Mode::Name;
+        self.input
+            .set_title(strings::TAG_COMMIT_POPUP_TITLE.to_string());
+        self.input.clear();
         self.show()?;
 
         Ok(())
     }
 
-    ///
-    pub fn tag(&mut self) {
+    /// asks for the message of an annotated tag with the typed name
+    fn annotate(&mut self) {
+        if matches!(self.mode, Mode::Name)
+            && !self.input.get_text().is_empty()
+        {
+            let tag_name = self.input.get_text().clone();
+            self.input.set_title(format!(
+                "{} {}",
+                strings::TAG_COMMIT_ANNOTATION_TITLE,
+                tag_name
+            ));
+            self.input.clear();
+            self.mode = Mode::Annotation { tag_name };
+        }
+    }
+
+    /// a lightweight tag named as typed or an annotated tag with the
+    /// typed message, offered to be pushed if `push`
+    pub fn tag(&mut self, push: bool) {
+        let (name, message) = match &self.mode {
+            Mode::Name => (self.input.get_text().as_str(), None),
+            Mode::Annotation { tag_name } => (
+                tag_name.as_str(),
+                Some(self.input.get_text().as_str()),
+            ),
+        };
+
         if let Some(commit_id) = self.commit_id {
-            match sync::tag(CWD, &commit_id, self.input.get_text()) {
+            match sync::tag(CWD, &commit_id, name, message) {
                 Ok(_) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Notify(Notification::success(
+                            format!(
+                                "{} {}",
+                                strings::NOTIFY_TAGGED,
+                                name
+                            ),
+                        )),
+                    );
+                    if push {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::PushTag(name.to_string()),
+                        );
+                    }
+
                     self.input.clear();
                     self.hide();
 
                     self.queue.borrow_mut().push_back(
-                        InternalEvent::Update(NeedsUpdate::ALL),
+                        InternalEvent::Update(
+                            NeedsUpdate::ALL | NeedsUpdate::TAGS,
+                        ),
                     );
                 }
                 Err(e) => {
                     self.hide();
                     log::error!("e: {}", e,);
                     self.queue.borrow_mut().push_back(
-                        InternalEvent::ShowErrorMsg(format!(
-                            "tag error:\n{}",
-                            e,
+                        InternalEvent::Notify(Notification::error(
+                            "tag failed",
+                            e.to_string(),
                         )),
                     );
                 }
@@ -1,20 +1,27 @@
 use crate::{
+    clipboard,
     components::{
         popup_paragraph, visibility_blocking, CommandBlocking,
         CommandInfo, Component, DrawableComponent,
     },
-    strings::commands,
-    ui::{self, style::SharedTheme},
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    spell,
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use crossterm::event::{Event, KeyCode, KeyModifiers};
+use std::ops::Range;
 use tui::{
     backend::Backend,
     layout::Rect,
-    style::Modifier,
+    style::{Modifier, Style},
     widgets::{Clear, Text},
     Frame,
 };
+use unicode_width::UnicodeWidthChar;
 
 /// primarily a subcomponet for user input of text (used in `CommitComponent`)
 pub struct TextInputComponent {
@@ -22,27 +29,71 @@ pub struct TextInputComponent {
     default_msg: String,
     msg: String,
     visible: bool,
+    /// pasted newlines are kept instead of joining the lines
+    multiline: bool,
+    /// lines after the first one wider than this get their overflow
+    /// marked
+    line_limit: Option<usize>,
+    /// length of a prefix of the text to highlight
+    prefix_len: Option<fn(&str) -> usize>,
+    /// words to underline wherever they appear
+    misspelled: Vec<String>,
+    queue: Queue,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
     cursor_position: usize,
 }
 
 impl TextInputComponent {
     ///
     pub fn new(
+        queue: Queue,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
         title: &str,
         default_msg: &str,
     ) -> Self {
         Self {
             msg: String::default(),
             visible: false,
+            multiline: false,
+            line_limit: None,
+            prefix_len: None,
+            misspelled: Vec::new(),
+            queue,
             theme,
+            key_config,
+            config,
             title: title.to_string(),
             default_msg: default_msg.to_string(),
             cursor_position: 0,
         }
     }
 
+    /// Keep newlines when pasting.
+    pub fn multiline(mut self) -> Self {
+        self.multiline = true;
+        self
+    }
+
+    /// Mark what lines after the first one have beyond `limit`
+    /// columns.
+    pub const fn line_limit(mut self, limit: usize) -> Self {
+        self.line_limit = Some(limit);
+        self
+    }
+
+    /// Highlight the first `prefix_len(msg)` bytes.
+    pub fn highlight_prefix(
+        mut self,
+        prefix_len: fn(&str) -> usize,
+    ) -> Self {
+        self.prefix_len = Some(prefix_len);
+        self
+    }
+
     /// Clear the `msg`.
     pub fn clear(&mut self) {
         self.msg.clear();
@@ -99,23 +150,148 @@ impl TextInputComponent {
         self.cursor_position = 0;
     }
 
+    /// Move the cursor behind the last char.
+    pub fn cursor_end(&mut self) {
+        self.cursor_position = self.msg.len();
+    }
+
+    /// Insert the system clipboard at the cursor.
+    fn paste(&mut self) {
+        match clipboard::paste_string(&self.config.clipboard) {
+            Ok(text) => {
+                let text = sanitize_paste(&text, self.multiline);
+                self.msg.insert_str(self.cursor_position, &text);
+                self.cursor_position += text.len();
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::PASTE_FAILED,
+                        e.to_string(),
+                    )),
+                );
+            }
+        }
+    }
+
+    /// Underline `words`.
+    pub fn set_misspelled(&mut self, words: Vec<String>) {
+        self.misspelled = words;
+    }
+
+    /// Byte range of the word the cursor is in or right behind.
+    pub fn word_at_cursor(&self) -> Option<Range<usize>> {
+        spell::word_ranges(&self.msg).into_iter().find(|range| {
+            range.start <= self.cursor_position
+                && self.cursor_position <= range.end
+        })
+    }
+
+    /// Put `text` in place of `msg[range]`, the cursor behind it.
+    pub fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        self.msg.replace_range(range.clone(), text);
+        self.cursor_position = range.start + text.len();
+    }
+
     /// Set the `title`.
     pub fn set_title(&mut self, t: String) {
         self.title = t;
     }
 
+    /// pushes `msg[range]`, the parts within `marks` in their style
+    fn push_text<'a>(
+        &'a self,
+        txt: &mut Vec<Text<'a>>,
+        range: Range<usize>,
+        marks: &[(Range<usize>, Style)],
+    ) {
+        let style = self.theme.text(true, false);
+        let mut pos = range.start;
+
+        for (marked, mark_style) in marks.iter().filter(|(r, _)| {
+            r.end > range.start && r.start < range.end
+        }) {
+            let from = marked.start.max(range.start);
+            let to = marked.end.min(range.end);
+            if from > pos {
+                txt.push(Text::styled(&self.msg[pos..from], style));
+            }
+            txt.push(Text::styled(&self.msg[from..to], *mark_style));
+            pos = to;
+        }
+
+        if range.end > pos {
+            txt.push(Text::styled(&self.msg[pos..range.end], style));
+        }
+    }
+
+    /// the highlighted prefix, the overflow of lines and misspelled
+    /// words, in order and without overlaps
+    fn marks(&self) -> Vec<(Range<usize>, Style)> {
+        let mut marks = Vec::new();
+
+        if let Some(prefix_len) = self.prefix_len {
+            let len = prefix_len(&self.msg);
+            if len > 0 {
+                marks.push((0..len, self.theme.tags(false)));
+            }
+        }
+
+        if let Some(limit) = self.line_limit {
+            let style = self
+                .theme
+                .text_danger()
+                .modifier(Modifier::UNDERLINED);
+            marks.extend(
+                overflow(&self.msg, limit)
+                    .into_iter()
+                    .map(|range| (range, style)),
+            );
+        }
+
+        if !self.misspelled.is_empty() {
+            let style = self
+                .theme
+                .text_danger()
+                .modifier(Modifier::UNDERLINED);
+            marks.extend(
+                spell::word_ranges(&self.msg)
+                    .into_iter()
+                    .filter(|range| {
+                        self.misspelled.iter().any(|word| {
+                            *word == self.msg[range.clone()]
+                        })
+                    })
+                    .map(|range| (range, style)),
+            );
+        }
+
+        marks.sort_by_key(|(range, _)| range.start);
+        let mut end = 0;
+        marks
+            .into_iter()
+            .filter_map(|(range, style)| {
+                let range = range.start.max(end)..range.end;
+                end = end.max(range.end);
+                if range.is_empty() {
+                    None
+                } else {
+                    Some((range, style))
+                }
+            })
+            .collect()
+    }
+
     fn get_draw_text(&self) -> Vec<Text> {
         let style = self.theme.text(true, false);
+        let marks = self.marks();
 
         let mut txt = Vec::new();
 
         // the portion of the text before the cursor is added
         // if the cursor is not at the first character
         if self.cursor_position > 0 {
-            txt.push(Text::styled(
-                &self.msg[..self.cursor_position],
-                style,
-            ));
+            self.push_text(&mut txt, 0..self.cursor_position, &marks);
         }
 
         let cursor_str = if let Some(pos) = self.next_char_position()
@@ -136,29 +312,27 @@ impl TextInputComponent {
             ));
         }
 
-        txt.push(Text::styled(
-            cursor_str,
-            style.modifier(Modifier::UNDERLINED),
-        ));
+        let cursor_style = marks
+            .iter()
+            .find(|(r, _)| r.contains(&self.cursor_position))
+            .map_or(style, |(_, mark_style)| *mark_style)
+            .modifier(Modifier::UNDERLINED);
+        txt.push(Text::styled(cursor_str, cursor_style));
 
         // the final portion of the text is added if there is
         // still remaining characters
         if let Some(pos) = self.next_char_position() {
             if pos < self.msg.len() {
-                txt.push(Text::styled(&self.msg[pos..], style));
+                self.push_text(&mut txt, pos..self.msg.len(), &marks);
             }
         }
 
         txt
     }
-}
 
-impl DrawableComponent for TextInputComponent {
-    fn draw<B: Backend>(
-        &self,
-        f: &mut Frame<B>,
-        _rect: Rect,
-    ) -> Result<()> {
+    /// draws the popup into `area` instead of the default
+    /// centered position (used when embedding it into a bigger popup)
+    pub fn draw_in<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
         if self.visible {
             let txt = if self.msg.is_empty() {
                 vec![Text::styled(
@@ -169,9 +343,6 @@ impl DrawableComponent for TextInputComponent {
                 self.get_draw_text()
             };
 
-            let area = ui::centered_rect(60, 20, f.size());
-            let area = ui::rect_min(10, 3, area);
-
             f.render_widget(Clear, area);
             f.render_widget(
                 popup_paragraph(
@@ -183,6 +354,19 @@ impl DrawableComponent for TextInputComponent {
                 area,
             );
         }
+    }
+}
+
+impl DrawableComponent for TextInputComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        _rect: Rect,
+    ) -> Result<()> {
+        let area = ui::centered_rect(60, 20, f.size());
+        let area = ui::rect_min(10, 3, area);
+
+        self.draw_in(f, area);
 
         Ok(())
     }
@@ -202,19 +386,30 @@ impl Component for TextInputComponent {
             )
             .order(1),
         );
+        out.push(
+            CommandInfo::new(commands::PASTE, true, self.visible)
+                .hidden(),
+        );
         visibility_blocking(self)
     }
 
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.visible {
             if let Event::Key(e) = ev {
+                if self.key_config.matches(&e, KeyAction::Paste) {
+                    self.paste();
+                    return Ok(true);
+                } else if self
+                    .key_config
+                    .matches(&e, KeyAction::ExitPopup)
+                {
+                    self.hide();
+                    return Ok(true);
+                }
+
                 let is_ctrl =
                     e.modifiers.contains(KeyModifiers::CONTROL);
                 match e.code {
-                    KeyCode::Esc => {
-                        self.hide();
-                        return Ok(true);
-                    }
                     KeyCode::Char(c) if !is_ctrl => {
                         self.msg.insert(self.cursor_position, c);
                         self.incr_cursor();
@@ -268,14 +463,142 @@ impl Component for TextInputComponent {
     }
 }
 
+/// byte ranges of what the lines after the first one have beyond
+/// `limit` columns
+fn overflow(msg: &str, limit: usize) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut line_start = 0;
+
+    for (idx, line) in msg.split('\n').enumerate() {
+        if idx > 0 {
+            let mut width = 0;
+            for (pos, c) in line.char_indices() {
+                width += c.width().unwrap_or_default();
+                if width > limit {
+                    ranges.push(
+                        line_start + pos..line_start + line.len(),
+                    );
+                    break;
+                }
+            }
+        }
+        line_start += line.len() + 1;
+    }
+
+    ranges
+}
+
+/// drops control characters, multiple lines are joined by a space
+/// unless `multiline`
+fn sanitize_paste(text: &str, multiline: bool) -> String {
+    let text = text.replace("\r\n", "\n").replace('\t', " ");
+
+    let text = if multiline {
+        text
+    } else {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    text.chars()
+        .filter(|c| !c.is_control() || (multiline && *c == '\n'))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{config::Config, keys::KeyConfig};
+    use std::rc::Rc;
+
+    fn input() -> TextInputComponent {
+        TextInputComponent::new(
+            Queue::default(),
+            SharedTheme::default(),
+            Rc::new(KeyConfig::default()),
+            Rc::new(Config::default()),
+            "",
+            "",
+        )
+    }
+
+    #[test]
+    fn test_sanitize_paste() {
+        assert_eq!(sanitize_paste("a\x1b[31mb", false), "a[31mb");
+        assert_eq!(sanitize_paste("a\r\n b\n\n", false), "a b");
+        assert_eq!(sanitize_paste("a\r\n\tb\n", true), "a\n b\n");
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!(overflow(&"x".repeat(80), 5).is_empty());
+        assert_eq!(
+            overflow("subject\n\nabcdefg\nab", 5),
+            vec![14..16]
+        );
+        assert_eq!(overflow("s\näöüäöü", 4), vec![10..14]);
+    }
+
+    #[test]
+    fn test_draw_overflow() {
+        let mut comp = input().line_limit(3);
+        comp.set_text(String::from("subject\nbodyline"));
+        comp.cursor_end();
+
+        let txt = comp.get_draw_text();
+
+        assert_eq!(txt.len(), 3);
+        assert_eq!(get_text(&txt[0]), Some("subject\nbod"));
+        assert_eq!(get_text(&txt[1]), Some("yline"));
+        assert_eq!(get_text(&txt[2]), Some(" "));
+    }
+
+    #[test]
+    fn test_draw_prefix() {
+        let mut comp = input().highlight_prefix(|msg| {
+            if msg.starts_with("fix: ") {
+                5
+            } else {
+                0
+            }
+        });
+        comp.set_text(String::from("fix: typo"));
+
+        let txt = comp.get_draw_text();
+
+        assert_eq!(txt.len(), 3);
+        assert_eq!(get_text(&txt[0]), Some("f"));
+        assert_eq!(get_text(&txt[1]), Some("ix: "));
+        assert_eq!(get_text(&txt[2]), Some("typo"));
+    }
+
+    #[test]
+    fn test_draw_misspelled() {
+        let mut comp = input().line_limit(4);
+        comp.set_text(String::from("s\nteh word teh"));
+        comp.set_misspelled(vec![String::from("teh")]);
+
+        assert_eq!(
+            comp.marks()
+                .into_iter()
+                .map(|(r, _)| r)
+                .collect::<Vec<_>>(),
+            vec![2..5, 6..14]
+        );
+
+        comp.cursor_position = 4;
+        assert_eq!(comp.word_at_cursor(), Some(2..5));
+        comp.replace_range(2..5, "the");
+        assert_eq!(comp.get_text(), "s\nthe word teh");
+        assert_eq!(comp.cursor_position, 5);
+    }
 
     #[test]
     fn test_smoke() {
-        let mut comp =
-            TextInputComponent::new(SharedTheme::default(), "", "");
+        let mut comp = input();
 
         comp.set_text(String::from("a\nb"));
 
@@ -298,8 +621,7 @@ mod tests {
 
     #[test]
     fn test_visualize_newline() {
-        let mut comp =
-            TextInputComponent::new(SharedTheme::default(), "", "");
+        let mut comp = input();
 
         comp.set_text(String::from("a\nb"));
 
@@ -0,0 +1,116 @@
+use unicode_width::UnicodeWidthStr;
+
+/// violations of the subject/body length rules in `msg`
+pub fn lint(
+    msg: &str,
+    subject_max_len: usize,
+    body_wrap: usize,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    let mut lines = msg.lines();
+
+    let subject = lines.next().unwrap_or_default();
+    if subject.width() > subject_max_len {
+        violations.push(format!(
+            "subject is {} characters long, keep it within {}",
+            subject.width(),
+            subject_max_len
+        ));
+    }
+
+    if let Some(second) = lines.next() {
+        if !second.trim().is_empty() {
+            violations.push(String::from(
+                "subject and body need to be separated by a blank line",
+            ));
+        }
+    }
+
+    for (idx, line) in msg.lines().enumerate().skip(1) {
+        if line.width() > body_wrap {
+            violations.push(format!(
+                "line {} is {} characters long, wrap at {}",
+                idx + 1,
+                line.width(),
+                body_wrap
+            ));
+        }
+    }
+
+    violations
+}
+
+/// kind of an invisible or text direction changing char, `None`
+/// for regular ones
+fn unusual_char_kind(c: char) -> Option<&'static str> {
+    match c {
+        '\u{202a}'..='\u{202e}'
+        | '\u{2066}'..='\u{2069}'
+        | '\u{200e}'
+        | '\u{200f}'
+        | '\u{061c}' => Some("bidi control"),
+        '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}' => {
+            Some("zero-width")
+        }
+        '\u{00ad}' | '\u{2061}'..='\u{2064}' => Some("invisible"),
+        c if c.is_control() => Some("control"),
+        _ => None,
+    }
+}
+
+/// describes the unusual chars in the subject of `msg` together with
+/// their column
+pub fn unusual_chars(msg: &str) -> Vec<String> {
+    msg.lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .enumerate()
+        .filter_map(|(idx, c)| {
+            unusual_char_kind(c).map(|kind| {
+                format!(
+                    "U+{:04X} ({}) at column {}",
+                    u32::from(c),
+                    kind,
+                    idx + 1
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unusual_chars() {
+        assert!(
+            unusual_chars("fix: plain äscii\n\u{200b}").is_empty()
+        );
+        assert_eq!(
+            unusual_chars("a\u{202e}b\u{200b}"),
+            vec![
+                String::from("U+202E (bidi control) at column 2"),
+                String::from("U+200B (zero-width) at column 4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint() {
+        assert!(lint("fix typo\n\nin the readme", 50, 72).is_empty());
+        assert!(lint("", 50, 72).is_empty());
+
+        assert_eq!(lint(&"x".repeat(51), 50, 72).len(), 1);
+        assert_eq!(lint("subject\nbody", 50, 72).len(), 1);
+
+        let msg = format!("subject\n\nok\n{}", "y".repeat(73));
+        assert_eq!(
+            lint(&msg, 50, 72),
+            vec![String::from(
+                "line 4 is 73 characters long, wrap at 72"
+            )]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{anyhow, Result};
+
+/// `type(scope): ` or `type: ` if `scope` is empty
+pub fn prefix(commit_type: &str, scope: &str) -> String {
+    let scope = scope.trim();
+    if scope.is_empty() {
+        format!("{}: ", commit_type)
+    } else {
+        format!("{}({}): ", commit_type, scope)
+    }
+}
+
+/// what one of the standard types is for, empty for others
+pub fn description(commit_type: &str) -> &'static str {
+    match commit_type {
+        "feat" => "a new feature",
+        "fix" => "a bug fix",
+        "docs" => "documentation only",
+        "style" => "formatting, no change in behaviour",
+        "refactor" => "neither a fix nor a feature",
+        "perf" => "makes it faster",
+        "test" => "adds or corrects tests",
+        "build" => "build system or dependencies",
+        "ci" => "ci configuration and scripts",
+        "chore" => "maintenance not touching src or tests",
+        "revert" => "reverts an earlier commit",
+        _ => "",
+    }
+}
+
+/// length of a `type(scope)!: ` at the start of `msg`, 0 if there is
+/// none
+pub fn prefix_len(msg: &str) -> usize {
+    fn parse(subject: &str) -> Option<usize> {
+        let separator = subject.find(": ")?;
+        let head = &subject[..separator];
+        let head = head.strip_suffix('!').unwrap_or(head);
+
+        let (commit_type, scope) =
+            head.find('(').map_or(Some((head, None)), |open| {
+                head[open + 1..]
+                    .strip_suffix(')')
+                    .map(|scope| (&head[..open], Some(scope)))
+            })?;
+
+        let valid_type = !commit_type.is_empty()
+            && commit_type.chars().all(|c| {
+                c.is_ascii_alphanumeric() || c == '-' || c == '_'
+            });
+        let valid_scope = scope.map_or(true, |scope| {
+            !scope.is_empty() && !scope.contains(&['(', ')'][..])
+        });
+
+        if valid_type && valid_scope {
+            Some(separator + 2)
+        } else {
+            None
+        }
+    }
+
+    parse(msg.lines().next().unwrap_or_default()).unwrap_or_default()
+}
+
+/// checks that the subject of `msg` reads
+/// `type(optional scope)!: description` with one of `types`
+pub fn validate<S: AsRef<str>>(msg: &str, types: &[S]) -> Result<()> {
+    let subject = msg.lines().next().unwrap_or_default();
+
+    let separator = subject.find(": ").ok_or_else(|| {
+        anyhow!(
+            "subject has to start with `type: ` or `type(scope): `"
+        )
+    })?;
+    let (head, description) =
+        (&subject[..separator], &subject[separator + 2..]);
+
+    if description.trim().is_empty() {
+        return Err(anyhow!("description after `: ` is missing"));
+    }
+
+    let head = head.strip_suffix('!').unwrap_or(head);
+
+    let commit_type = if let Some(open) = head.find('(') {
+        let scope = head[open + 1..]
+            .strip_suffix(')')
+            .ok_or_else(|| anyhow!("scope is missing its `)`"))?;
+
+        if scope.is_empty() || scope.contains(&['(', ')'][..]) {
+            return Err(anyhow!("invalid scope `{}`", scope));
+        }
+
+        &head[..open]
+    } else {
+        head
+    };
+
+    if types.iter().any(|t| t.as_ref() == commit_type) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "unknown type `{}`, expected one of: {}",
+            commit_type,
+            types
+                .iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(prefix("feat", ""), "feat: ");
+        assert_eq!(prefix("fix", " ui "), "fix(ui): ");
+    }
+
+    #[test]
+    fn test_prefix_len() {
+        assert_eq!(prefix_len("feat: x"), 6);
+        assert_eq!(prefix_len("fix(ui)!: y\n\nbody"), 10);
+        assert_eq!(prefix_len("no prefix here"), 0);
+        assert_eq!(prefix_len("see: a: b"), 5);
+        assert_eq!(prefix_len("two words: x"), 0);
+        assert_eq!(prefix_len("fix(: x"), 0);
+        assert_eq!(prefix_len("x\nfeat: y"), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let types = &["feat", "fix", "refactor"];
+
+        assert!(validate("feat: add x", types).is_ok());
+        assert!(validate("fix(ui): y\n\nbody", types).is_ok());
+        assert!(validate("refactor(core)!: drop z", types).is_ok());
+
+        assert!(validate("add x", types).is_err());
+        assert!(validate("feat: ", types).is_err());
+        assert!(validate("wip: stuff", types).is_err());
+        assert!(validate("feat(: x", types).is_err());
+        assert!(validate("feat(): x", types).is_err());
+    }
+}
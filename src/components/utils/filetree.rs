@@ -1,7 +1,7 @@
 use anyhow::Result;
-use asyncgit::StatusItem;
+use asyncgit::{StatusItem, StatusItemType};
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeSet, HashMap},
     convert::TryFrom,
     ffi::OsStr,
     ops::{Index, IndexMut},
@@ -170,6 +170,25 @@ impl FileTreeItems {
         })
     }
 
+    /// just the files, by their full path and not indented
+    pub(crate) fn new_flat(list: &[StatusItem]) -> Result<Self> {
+        let items = list
+            .iter()
+            .map(|e| {
+                FileTreeItem::new_file(e).map(|mut item| {
+                    item.info.indent = 0;
+                    item.info.path.clone_from(&e.path);
+                    item
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            items,
+            file_count: list.len(),
+        })
+    }
+
     ///
     pub(crate) const fn items(&self) -> &Vec<FileTreeItem> {
         &self.items
@@ -234,6 +253,43 @@ impl FileTreeItems {
     }
 }
 
+/// files below a directory by how they changed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DirCounts {
+    pub added: usize,
+    pub modified: usize,
+    pub deleted: usize,
+}
+
+/// counts of every directory in `list` by its full path, renames,
+/// type changes and conflicts count as modified
+pub fn dir_counts(list: &[StatusItem]) -> HashMap<String, DirCounts> {
+    let mut res = HashMap::<String, DirCounts>::new();
+
+    for item in list {
+        for dir in Path::new(&item.path).ancestors().skip(1) {
+            if dir.parent().is_none() {
+                continue;
+            }
+
+            let counts =
+                res.entry(dir.to_string_lossy().into()).or_default();
+            match item.status {
+                StatusItemType::New | StatusItemType::Copied => {
+                    counts.added += 1;
+                }
+                StatusItemType::Deleted => counts.deleted += 1,
+                StatusItemType::Modified
+                | StatusItemType::Renamed
+                | StatusItemType::Typechange
+                | StatusItemType::Conflicted => counts.modified += 1,
+            }
+        }
+    }
+
+    res
+}
+
 impl IndexMut<usize> for FileTreeItems {
     fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
         &mut self.items[idx]
@@ -251,7 +307,6 @@ impl Index<usize> for FileTreeItems {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use asyncgit::StatusItemType;
 
     fn string_vec_to_status(items: &[&str]) -> Vec<StatusItem> {
         items
@@ -259,6 +314,7 @@ mod tests {
             .map(|a| StatusItem {
                 path: String::from(*a),
                 status: StatusItemType::Modified,
+                old_path: None,
             })
             .collect::<Vec<_>>()
     }
@@ -377,6 +433,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_flat() {
+        let items = string_vec_to_status(&[
+            "a/b/c", //
+            "a/d",   //
+            "e",     //
+        ]);
+
+        let res = FileTreeItems::new_flat(&items).unwrap();
+
+        assert_eq!(res.file_count(), 3);
+        assert_eq!(
+            res.items
+                .iter()
+                .map(|i| (i.info.indent, i.info.path.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "a/b/c"), (0, "a/d"), (0, "e")]
+        );
+        assert!(res
+            .items
+            .iter()
+            .all(|i| matches!(i.kind, FileTreeItemKind::File(_))));
+    }
+
     #[test]
     fn test_find_parent() {
         //0 a/
@@ -398,4 +478,26 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_dir_counts() {
+        let mut items = string_vec_to_status(&[
+            "a/b/c", //
+            "a/d", "e",
+        ]);
+        items[1].status = StatusItemType::New;
+
+        let res = dir_counts(&items);
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(
+            res["a"],
+            DirCounts {
+                added: 1,
+                modified: 1,
+                deleted: 0
+            }
+        );
+        assert_eq!(res["a/b"].modified, 1);
+    }
 }
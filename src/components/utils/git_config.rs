@@ -0,0 +1,119 @@
+use anyhow::{anyhow, Result};
+use std::{env, path::Path};
+
+/// keys listed in the config tab
+pub const KEYS: &[&str] = &[
+    "user.name",
+    "user.email",
+    "core.editor",
+    "pull.rebase",
+    "push.default",
+    "fetch.prune",
+    "commit.gpgsign",
+    "init.defaultBranch",
+];
+
+/// checks `value` before it is written to `key`, empty values
+/// (unsetting the key) are always fine
+pub fn validate(key: &str, value: &str) -> Result<()> {
+    if value.is_empty() {
+        return Ok(());
+    }
+
+    match key {
+        "user.email" => validate_email(value),
+        "core.editor" => validate_editor(value),
+        "pull.rebase" => {
+            one_of(value, &["true", "false", "merges", "interactive"])
+        }
+        "push.default" => one_of(
+            value,
+            &["nothing", "current", "upstream", "simple", "matching"],
+        ),
+        "fetch.prune" | "commit.gpgsign" => {
+            one_of(value, &["true", "false"])
+        }
+        _ => Ok(()),
+    }
+}
+
+fn one_of(value: &str, allowed: &[&str]) -> Result<()> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(anyhow!("expected one of: {}", allowed.join(", ")))
+    }
+}
+
+fn validate_email(value: &str) -> Result<()> {
+    let valid = value.find('@').map_or(false, |at| {
+        let (local, domain) = (&value[..at], &value[at + 1..]);
+        !local.is_empty()
+            && domain.contains('.')
+            && !domain.starts_with('.')
+            && !domain.ends_with('.')
+            && !value.contains(char::is_whitespace)
+    });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("`{}` is not a valid email address", value))
+    }
+}
+
+/// the program (first word) has to be an existing file or be found
+/// in `PATH`
+fn validate_editor(value: &str) -> Result<()> {
+    let program = value.split_whitespace().next().unwrap_or_default();
+
+    let found = Path::new(program).is_file()
+        || env::var_os("PATH").map_or(false, |paths| {
+            env::split_paths(&paths).any(|dir| {
+                dir.join(program).is_file()
+                    || dir.join(format!("{}.exe", program)).is_file()
+            })
+        });
+
+    if found {
+        Ok(())
+    } else {
+        Err(anyhow!("editor `{}` not found", program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email() {
+        assert!(validate("user.email", "me@example.com").is_ok());
+        assert!(validate("user.email", "").is_ok());
+
+        assert!(validate("user.email", "me").is_err());
+        assert!(validate("user.email", "@example.com").is_err());
+        assert!(validate("user.email", "me@example").is_err());
+        assert!(validate("user.email", "me@example.").is_err());
+        assert!(validate("user.email", "m e@example.com").is_err());
+    }
+
+    #[test]
+    fn test_choices() {
+        assert!(validate("pull.rebase", "merges").is_ok());
+        assert!(validate("pull.rebase", "yes").is_err());
+        assert!(validate("push.default", "simple").is_ok());
+        assert!(validate("fetch.prune", "1").is_err());
+        assert!(validate("user.name", "anything").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_editor() {
+        assert!(validate("core.editor", "sh -c").is_ok());
+        assert!(validate("core.editor", "/bin/sh").is_ok());
+        assert!(
+            validate("core.editor", "gitui-no-such-editor").is_err()
+        );
+    }
+}
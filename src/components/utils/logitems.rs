@@ -1,11 +1,11 @@
-use super::time_to_string;
 use asyncgit::sync::{CommitId, CommitInfo};
 use std::slice::Iter;
 
 static SLICE_OFFSET_RELOAD_THRESHOLD: usize = 100;
 
 pub struct LogEntry {
-    pub time: String,
+    /// secs since Unix epoch
+    pub time: i64,
     pub author: String,
     pub msg: String,
     pub hash_short: String,
@@ -19,7 +19,7 @@ impl From<CommitInfo> for LogEntry {
         Self {
             author: c.author,
             msg: c.message,
-            time: time_to_string(c.time, true),
+            time: c.time,
             hash_short: hash,
             id: c.id,
         }
@@ -1,19 +1,8 @@
-use chrono::{DateTime, Local, NaiveDateTime, Utc};
-
+pub mod commit_lint;
+pub mod conventional;
 pub mod filetree;
+pub mod git_config;
 pub mod logitems;
+pub mod search;
+pub mod split;
 pub mod statustree;
-
-/// helper func to convert unix time since epoch to formated time string in local timezone
-pub fn time_to_string(secs: i64, short: bool) -> String {
-    let time = DateTime::<Local>::from(DateTime::<Utc>::from_utc(
-        NaiveDateTime::from_timestamp(secs, 0),
-        Utc,
-    ));
-    time.format(if short {
-        "%Y-%m-%d"
-    } else {
-        "%Y-%m-%d %H:%M:%S"
-    })
-    .to_string()
-}
@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+/// text to find in a diff or the hex view, ignoring case unless
+/// `case_sensitive`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Search {
+    pub query: String,
+    pub case_sensitive: bool,
+}
+
+impl Search {
+    /// byte ranges of the matches in `text`, not overlapping
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let mut found = Vec::new();
+        if self.query.is_empty() {
+            return found;
+        }
+
+        let mut pos = 0;
+        while let Some(c) = text[pos..].chars().next() {
+            if let Some(len) = self.match_len(&text[pos..]) {
+                found.push(pos..pos + len);
+                pos += len;
+            } else {
+                pos += c.len_utf8();
+            }
+        }
+
+        found
+    }
+
+    /// offsets of the matches in `bytes`, only ascii letters ignore
+    /// their case here
+    pub fn find_bytes(&self, bytes: &[u8]) -> Vec<usize> {
+        let query = self.query.as_bytes();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        bytes
+            .windows(query.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                if self.case_sensitive {
+                    *window == query
+                } else {
+                    window.eq_ignore_ascii_case(query)
+                }
+            })
+            .map(|(offset, _)| offset)
+            .collect()
+    }
+
+    /// length in bytes of the match `text` starts with
+    fn match_len(&self, text: &str) -> Option<usize> {
+        let mut chars = text.char_indices();
+        for wanted in self.query.chars() {
+            let (_, c) = chars.next()?;
+            let same = c == wanted
+                || (!self.case_sensitive
+                    && c.to_lowercase().eq(wanted.to_lowercase()));
+            if !same {
+                return None;
+            }
+        }
+
+        Some(chars.next().map_or(text.len(), |(pos, _)| pos))
+    }
+}
+
+/// the position after (or before) `current` in the sorted
+/// `positions`, wrapping around at the end
+pub fn step(
+    positions: &[usize],
+    current: usize,
+    forward: bool,
+) -> Option<usize> {
+    if forward {
+        positions
+            .iter()
+            .find(|pos| **pos > current)
+            .or_else(|| positions.first())
+    } else {
+        positions
+            .iter()
+            .rev()
+            .find(|pos| **pos < current)
+            .or_else(|| positions.last())
+    }
+    .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query: &str, case_sensitive: bool) -> Search {
+        Search {
+            query: query.to_string(),
+            case_sensitive,
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            search("ab", false).find("xAb-aBab"),
+            vec![1..3, 4..6, 6..8]
+        );
+        assert_eq!(search("ab", true).find("xAb-aBab"), vec![6..8]);
+        assert_eq!(search("über", false).find("Ü ÜBER"), vec![3..8]);
+        assert!(search("", false).find("text").is_empty());
+
+        assert_eq!(
+            search("png", false).find_bytes(b"\x89PNG png"),
+            vec![1, 5]
+        );
+        assert_eq!(
+            search("png", true).find_bytes(b"\x89PNG png"),
+            vec![5]
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let lines = [2, 5, 9];
+        assert_eq!(step(&lines, 2, true), Some(5));
+        assert_eq!(step(&lines, 9, true), Some(2));
+        assert_eq!(step(&lines, 3, false), Some(2));
+        assert_eq!(step(&lines, 2, false), Some(9));
+        assert_eq!(step(&[], 0, true), None);
+    }
+}
@@ -0,0 +1,185 @@
+use crossterm::event::{MouseButton, MouseEvent};
+use std::cell::Cell;
+use tui::layout::Rect;
+
+/// columns each pane keeps at least
+const MIN_WIDTH: u16 = 10;
+
+/// left/right split whose divider can be moved, the left pane's
+/// share is kept as a ratio so that terminal resizes keep the
+/// proportions
+pub struct HorizontalSplit {
+    ratio: f32,
+    area: Cell<Rect>,
+    dragging: bool,
+}
+
+impl HorizontalSplit {
+    ///
+    pub fn new(ratio: f32) -> Self {
+        Self {
+            ratio: ratio.clamp(0.0, 1.0),
+            area: Cell::new(Rect::default()),
+            dragging: false,
+        }
+    }
+
+    /// left and right pane, remembers `area` for mouse and resize
+    pub fn split(&self, area: Rect) -> (Rect, Rect) {
+        self.area.set(area);
+
+        let left = self.left_width(area);
+
+        (
+            Rect {
+                width: left,
+                ..area
+            },
+            Rect {
+                x: area.x.saturating_add(left),
+                width: area.width.saturating_sub(left),
+                ..area
+            },
+        )
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn left_width(&self, area: Rect) -> u16 {
+        let left =
+            (f32::from(area.width) * self.ratio).round() as u16;
+
+        if area.width < MIN_WIDTH * 2 {
+            left
+        } else {
+            left.clamp(MIN_WIDTH, area.width - MIN_WIDTH)
+        }
+    }
+
+    fn set_left_width(&mut self, left: u16) -> bool {
+        let area = self.area.get();
+        if area.width == 0 {
+            return false;
+        }
+
+        let old = self.left_width(area);
+        self.ratio =
+            (f32::from(left) / f32::from(area.width)).clamp(0.0, 1.0);
+
+        old != self.left_width(area)
+    }
+
+    /// moves the divider by `delta` columns
+    pub fn resize(&mut self, delta: i16) -> bool {
+        let left = self.left_width(self.area.get());
+        let left = if delta < 0 {
+            left.saturating_sub(delta.unsigned_abs())
+        } else {
+            left.saturating_add(delta.unsigned_abs())
+        };
+
+        self.set_left_width(left)
+    }
+
+    /// drags the divider (the borders between both panes) with the
+    /// left mouse button
+    pub fn mouse_event(&mut self, ev: MouseEvent) -> bool {
+        let area = self.area.get();
+
+        match ev {
+            MouseEvent::Down(MouseButton::Left, column, row, _) => {
+                let divider = area.x + self.left_width(area);
+                self.dragging = row >= area.y
+                    && row < area.y + area.height
+                    && (column + 1 == divider || column == divider);
+                self.dragging
+            }
+            MouseEvent::Drag(MouseButton::Left, column, _, _)
+                if self.dragging =>
+            {
+                self.set_left_width(
+                    column.saturating_add(1).saturating_sub(area.x),
+                );
+                true
+            }
+            MouseEvent::Up(MouseButton::Left, _, _, _)
+                if self.dragging =>
+            {
+                self.dragging = false;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn test_split() {
+        let split = HorizontalSplit::new(0.3);
+        let (left, right) = split.split(Rect::new(0, 1, 100, 20));
+        assert_eq!(left, Rect::new(0, 1, 30, 20));
+        assert_eq!(right, Rect::new(30, 1, 70, 20));
+
+        // terminal got wider
+        let (left, _) = split.split(Rect::new(0, 1, 200, 20));
+        assert_eq!(left.width, 60);
+    }
+
+    #[test]
+    fn test_resize_and_drag() {
+        let mut split = HorizontalSplit::new(0.5);
+        let area = Rect::new(0, 0, 100, 20);
+        split.split(area);
+
+        assert!(split.resize(1));
+        assert_eq!(split.split(area).0.width, 51);
+        assert!(split.resize(-2));
+        assert_eq!(split.split(area).0.width, 49);
+
+        let m = KeyModifiers::empty();
+        assert!(!split.mouse_event(MouseEvent::Down(
+            MouseButton::Left,
+            10,
+            5,
+            m
+        )));
+        assert!(split.mouse_event(MouseEvent::Down(
+            MouseButton::Left,
+            48,
+            5,
+            m
+        )));
+        assert!(split.mouse_event(MouseEvent::Drag(
+            MouseButton::Left,
+            69,
+            5,
+            m
+        )));
+        assert!(split.mouse_event(MouseEvent::Up(
+            MouseButton::Left,
+            69,
+            5,
+            m
+        )));
+        assert_eq!(split.split(area).0.width, 70);
+
+        // panes keep a minimum width
+        assert!(split.mouse_event(MouseEvent::Down(
+            MouseButton::Left,
+            70,
+            5,
+            m
+        )));
+        split.mouse_event(MouseEvent::Drag(
+            MouseButton::Left,
+            99,
+            5,
+            m,
+        ));
+        assert_eq!(split.split(area).0.width, 90);
+    }
+}
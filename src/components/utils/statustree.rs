@@ -10,6 +10,17 @@ use std::{cmp, collections::BTreeSet};
 pub struct StatusTree {
     pub tree: FileTreeItems,
     pub selection: Option<usize>,
+    /// paths collapsed by the user, kept while they are not in the
+    /// list (like when moving between commits)
+    collapsed: BTreeSet<String>,
+    /// files only, by their full path
+    flat: bool,
+    /// files only like `flat`, in the order of the list given (like
+    /// biggest changes first), folders could not keep to that
+    keep_order: bool,
+    /// paths of the files marked by the user, kept while they are
+    /// in the list
+    marked: BTreeSet<String>,
 }
 
 ///
@@ -37,13 +48,22 @@ impl SelectionChange {
 impl StatusTree {
     /// update tree with a new list, try to retain selection and collapse states
     pub fn update(&mut self, list: &[StatusItem]) -> Result<()> {
-        let last_collapsed = self.all_collapsed();
+        let mut last_collapsed = self.all_collapsed();
+        last_collapsed.extend(self.collapsed.iter());
 
         let last_selection =
             self.selected_item().map(|e| e.info.full_path);
         let last_selection_index = self.selection.unwrap_or(0);
 
-        self.tree = FileTreeItems::new(list, &last_collapsed)?;
+        self.tree = if self.is_list() {
+            FileTreeItems::new_flat(list)?
+        } else {
+            FileTreeItems::new(list, &last_collapsed)?
+        };
+
+        let listed =
+            list.iter().map(|e| &e.path).collect::<BTreeSet<_>>();
+        self.marked.retain(|path| listed.contains(path));
         self.selection =
             if let Some(ref last_selection) = last_selection {
                 self.find_last_selection(
@@ -78,10 +98,49 @@ impl StatusTree {
         idx
     }
 
+    /// switches between the tree and the flat list, keeping the
+    /// files and the selection
+    pub fn set_flat(&mut self, flat: bool) -> Result<()> {
+        if self.flat == flat {
+            return Ok(());
+        }
+
+        let list = self.files().cloned().collect::<Vec<_>>();
+
+        self.flat = flat;
+        self.update(&list)
+    }
+
+    ///
+    pub const fn is_flat(&self) -> bool {
+        self.flat
+    }
+
+    /// takes effect with the next `update`
+    pub fn set_keep_order(&mut self, keep_order: bool) {
+        self.keep_order = keep_order;
+    }
+
+    ///
+    pub const fn is_keep_order(&self) -> bool {
+        self.keep_order
+    }
+
+    /// no folders, either way
+    const fn is_list(&self) -> bool {
+        self.flat || self.keep_order
+    }
+
     ///
     pub fn move_selection(&mut self, dir: MoveSelection) -> bool {
         if let Some(selection) = self.selection {
             let selection_change = match dir {
+                // no folders to collapse or to jump to
+                MoveSelection::Left | MoveSelection::Right
+                    if self.is_list() =>
+                {
+                    SelectionChange::new(selection, false)
+                }
                 MoveSelection::Up => {
                     self.selection_updown(selection, true)
                 }
@@ -107,6 +166,70 @@ impl StatusTree {
         }
     }
 
+    /// marks the selected file or all files below the selected
+    /// folder, unmarks them if they are all marked already
+    pub fn toggle_mark(&mut self) {
+        if let Some(item) = self.selected_item() {
+            self.toggle_marks(item);
+        }
+    }
+
+    fn toggle_marks(&mut self, item: FileTreeItem) {
+        let files = match item.kind {
+            FileTreeItemKind::File(status) => vec![status.path],
+            FileTreeItemKind::Path(_) => {
+                let prefix = format!("{}/", item.info.full_path);
+                self.files()
+                    .filter(|status| status.path.starts_with(&prefix))
+                    .map(|status| status.path.clone())
+                    .collect()
+            }
+        };
+
+        if files.iter().all(|path| self.marked.contains(path)) {
+            for path in &files {
+                self.marked.remove(path);
+            }
+        } else {
+            self.marked.extend(files);
+        }
+    }
+
+    ///
+    pub fn is_marked(&self, path: &str) -> bool {
+        self.marked.contains(path)
+    }
+
+    ///
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty()
+    }
+
+    /// the marked files in the order of the list
+    pub fn marked_items(&self) -> Vec<StatusItem> {
+        self.files()
+            .filter(|status| self.marked.contains(&status.path))
+            .cloned()
+            .collect()
+    }
+
+    /// returns true if anything was marked
+    pub fn clear_marks(&mut self) -> bool {
+        let had_marks = self.has_marks();
+        self.marked.clear();
+        had_marks
+    }
+
+    fn files(&self) -> impl Iterator<Item = &StatusItem> {
+        self.tree
+            .items()
+            .iter()
+            .filter_map(|item| match &item.kind {
+                FileTreeItemKind::File(status) => Some(status),
+                FileTreeItemKind::Path(_) => None,
+            })
+    }
+
     ///
     pub fn selected_item(&self) -> Option<FileTreeItem> {
         self.selection.map(|i| self.tree[i].clone())
@@ -269,6 +392,7 @@ impl StatusTree {
         {
             *collapsed = true;
         }
+        self.collapsed.insert(path.to_string());
 
         let path = format!("{}/", path);
 
@@ -290,6 +414,7 @@ impl StatusTree {
         {
             *collapsed = false;
         }
+        self.collapsed.remove(path);
 
         let path = format!("{}/", path);
 
@@ -359,10 +484,88 @@ mod tests {
             .map(|a| StatusItem {
                 path: String::from(*a),
                 status: StatusItemType::Modified,
+                old_path: None,
             })
             .collect::<Vec<_>>()
     }
 
+    #[test]
+    fn test_marks() {
+        let mut res = StatusTree::default();
+        res.update(&string_vec_to_status(&[
+            "a/b", //
+            "a/c", //
+            "d",   //
+        ]))
+        .unwrap();
+
+        // the folder `a`
+        res.toggle_mark();
+        assert!(res.is_marked("a/b"));
+        assert!(res.is_marked("a/c"));
+
+        res.selection = Some(2);
+        res.toggle_mark();
+        assert!(!res.is_marked("a/c"));
+
+        res.selection = Some(0);
+        res.toggle_mark();
+        assert!(res.is_marked("a/c"));
+
+        res.selection = Some(0);
+        res.toggle_mark();
+        assert!(!res.has_marks());
+
+        res.selection = Some(3);
+        res.toggle_mark();
+        res.selection = Some(1);
+        res.toggle_mark();
+
+        // gone files lose their mark, the others keep it
+        res.update(&string_vec_to_status(&["a/c", "d", "e"]))
+            .unwrap();
+        assert_eq!(
+            res.marked_items()
+                .iter()
+                .map(|e| e.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["d"]
+        );
+
+        assert!(res.clear_marks());
+        assert!(!res.clear_marks());
+    }
+
+    #[test]
+    fn test_toggle_flat() {
+        let items = string_vec_to_status(&[
+            "a/b", //
+            "a/c", //
+            "d",   //
+        ]);
+
+        let mut res = StatusTree::default();
+        res.update(&items).unwrap();
+        res.move_selection(MoveSelection::Down);
+        res.move_selection(MoveSelection::Down);
+        assert_eq!(
+            res.selected_item().unwrap().info.full_path,
+            "a/c"
+        );
+
+        res.set_flat(true).unwrap();
+        assert_eq!(res.tree.len(), 3);
+        assert_eq!(res.selection, Some(1));
+        assert!(!res.move_selection(MoveSelection::Left));
+
+        res.set_flat(false).unwrap();
+        assert_eq!(res.tree.len(), 4);
+        assert_eq!(
+            res.selected_item().unwrap().info.full_path,
+            "a/c"
+        );
+    }
+
     fn get_visibles(tree: &StatusTree) -> Vec<bool> {
         tree.tree
             .items()
@@ -483,6 +686,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_remember_collapsed_states() {
+        let mut res = StatusTree::default();
+        res.update(&string_vec_to_status(&[
+            "a/b", //
+            "c",
+        ]))
+        .unwrap();
+
+        res.collapse("a", 0);
+
+        res.update(&string_vec_to_status(&["c"])).unwrap();
+        res.update(&string_vec_to_status(&[
+            "a/b", //
+            "c",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            get_visibles(&res),
+            vec![
+                true,  //
+                false, //
+                true,  //
+            ]
+        );
+
+        res.expand("a", 0);
+        res.update(&string_vec_to_status(&["a/b"])).unwrap();
+
+        assert_eq!(get_visibles(&res), vec![true, true]);
+    }
+
     #[test]
     fn test_expand() {
         let items = string_vec_to_status(&[
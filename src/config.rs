@@ -0,0 +1,600 @@
+use crate::{
+    clipboard::ClipboardBackend,
+    get_app_config_path,
+    keys::{KeyAction, KeyPreset},
+};
+use anyhow::Result;
+use asyncgit::sync::{status::RenameDetection, DateFormat};
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+    rc::Rc,
+    time::Duration,
+};
+
+pub type SharedConfig = Rc<Config>;
+
+/// what to do about messages breaking the length rules
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitLintMode {
+    Off,
+    /// show the violations below the message
+    Warn,
+    /// show them and refuse to commit
+    Block,
+}
+
+/// what to do about trailing whitespace in the staged files
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TrailingWhitespace {
+    Keep,
+    /// clean the staged text files (and the work dir where it has
+    /// no other changes) before committing
+    Strip,
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CommitConfig {
+    /// allows committing without running the `pre-commit` and
+    /// `commit-msg` hooks (`--no-verify`)
+    pub allow_no_verify: bool,
+    /// moved to `conventional_commits.enabled`
+    #[serde(skip_serializing, deserialize_with = "deserialize_some")]
+    conventional: Option<bool>,
+    /// moved to `conventional_commits.standard_types`
+    #[serde(skip_serializing, deserialize_with = "deserialize_some")]
+    conventional_types: Option<Vec<String>>,
+    /// checks the message against `subject_max_len` and
+    /// `body_wrap` and that a blank line follows the subject
+    pub lint: CommitLintMode,
+    /// hints while typing regardless of `lint`: a counter for the
+    /// subject and what body lines have beyond `body_line_length`
+    /// underlined
+    pub lint_message: bool,
+    ///
+    pub subject_max_len: usize,
+    ///
+    pub body_wrap: usize,
+    /// body lines are underlined beyond this width by `lint_message`
+    pub body_line_length: usize,
+    ///
+    pub trailing_whitespace: TrailingWhitespace,
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self {
+            allow_no_verify: true,
+            conventional: None,
+            conventional_types: None,
+            lint: CommitLintMode::Off,
+            lint_message: true,
+            subject_max_len: 50,
+            body_wrap: 72,
+            body_line_length: 100,
+            trailing_whitespace: TrailingWhitespace::Keep,
+        }
+    }
+}
+
+/// reads keys of older versions without `Some(..)` around them
+fn deserialize_some<'de, D, T>(
+    deserializer: D,
+) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// the conventional commit picker of the commit popup
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ConventionalCommitsConfig {
+    /// opens the type picker with the commit popup and refuses
+    /// subjects not following the format
+    pub enabled: bool,
+    /// types offered and accepted, described by gitui
+    pub standard_types: Vec<String>,
+    /// more types and what they mean,
+    /// e.g. `[("wip", "work in progress")]`
+    pub types: Vec<(String, String)>,
+    /// picked from after the type, typed in if there are none
+    pub scopes: Vec<String>,
+}
+
+impl Default for ConventionalCommitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            standard_types: [
+                "feat", "fix", "docs", "style", "refactor", "perf",
+                "test", "build", "ci", "chore", "revert",
+            ]
+            .iter()
+            .map(|t| (*t).to_string())
+            .collect(),
+            types: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RevlogConfig {
+    /// `human`, `relative`, `iso`, `short` or a strftime format
+    pub date_format: String,
+}
+
+impl Default for RevlogConfig {
+    fn default() -> Self {
+        Self {
+            date_format: String::from("human"),
+        }
+    }
+}
+
+impl RevlogConfig {
+    pub fn date_format(&self) -> DateFormat {
+        DateFormat::from(self.date_format.as_str())
+    }
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct StatusConfig {
+    /// quiet period in ms before coalesced status refreshes run
+    pub refresh_debounce_ms: u64,
+    /// changed files as a list of paths instead of a tree
+    pub flat_file_list: bool,
+    /// changed files listed by the size of their change, biggest
+    /// first
+    pub sort_by_size: bool,
+    /// percent of similarity a deleted and a new file need to be
+    /// listed as one renamed file
+    pub rename_threshold: u16,
+    /// lists new files that are (close to) copies of others as such,
+    /// slow in big repos
+    pub detect_copies: bool,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self {
+            refresh_debounce_ms: 100,
+            flat_file_list: false,
+            sort_by_size: false,
+            rename_threshold: 50,
+            detect_copies: false,
+        }
+    }
+}
+
+impl StatusConfig {
+    pub const fn refresh_debounce(&self) -> Duration {
+        Duration::from_millis(self.refresh_debounce_ms)
+    }
+
+    pub const fn renames(&self) -> RenameDetection {
+        RenameDetection {
+            threshold: self.rename_threshold,
+            copies: self.detect_copies,
+        }
+    }
+}
+
+/// the toggles of the stashing tab, remembered when changed
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct StashConfig {
+    pub include_untracked: bool,
+    pub include_ignored: bool,
+    /// staged changes stay in the index and work dir too
+    pub keep_index: bool,
+}
+
+impl Default for StashConfig {
+    fn default() -> Self {
+        Self {
+            include_untracked: true,
+            include_ignored: false,
+            keep_index: false,
+        }
+    }
+}
+
+/// order of the tags of a commit
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TagSort {
+    /// plain order of the names
+    Name,
+    /// as semantic versions, `v1.9.0` before `v1.10.0`
+    Version,
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TagsConfig {
+    pub sort: TagSort,
+}
+
+impl Default for TagsConfig {
+    fn default() -> Self {
+        Self {
+            sort: TagSort::Name,
+        }
+    }
+}
+
+///
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// seconds after which a running hook gets killed,
+    /// `0` lets hooks run as long as they want
+    pub timeout_secs: u64,
+}
+
+impl HooksConfig {
+    pub fn timeout(&self) -> Option<Duration> {
+        Some(self.timeout_secs)
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// unchanged lines shown around changes at startup, like
+    /// `git diff -U<n>`
+    pub context_lines: u32,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self { context_lines: 3 }
+    }
+}
+
+///
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HexViewConfig {
+    /// show offsets as decimal instead of hex numbers
+    pub decimal_offsets: bool,
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// program and arguments getting the text on stdin, tried before
+    /// `backends`, e.g. `Some(["tmux", "load-buffer", "-"])`
+    pub command: Option<Vec<String>>,
+    /// tried in order until one succeeds,
+    /// e.g. `backends: [native, osc52, external]`
+    pub backends: Vec<ClipboardBackend>,
+    /// only use osc52 (ignoring `command` and `backends`), for
+    /// sessions over ssh or mosh without a clipboard program
+    pub force_osc52: bool,
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            backends: vec![
+                ClipboardBackend::Native,
+                ClipboardBackend::External,
+                ClipboardBackend::Osc52,
+            ],
+            force_osc52: false,
+        }
+    }
+}
+
+/// decides between `light_theme` and `dark_theme`
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    /// asks the terminal for its background color
+    Auto,
+    Light,
+    Dark,
+}
+
+/// how pictures in binary diffs are drawn
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImagePreview {
+    /// with the graphics protocol the terminal is known to speak
+    Auto,
+    Kitty,
+    Iterm,
+    /// dimensions and a hex dump only
+    Off,
+}
+
+///
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UiConfig {
+    /// lines moved per turn of the mouse wheel
+    pub mouse_scroll_lines: usize,
+    /// seconds until a notification disappears
+    pub notification_timeout_secs: u64,
+    /// share of the width taken by the file lists in the status tab
+    pub status_split_ratio: f32,
+    /// share of the width taken by the commit list while the commit
+    /// details are shown
+    pub revlog_split_ratio: f32,
+    /// hide the tab bar and the command bar while a diff is shown
+    /// full screen
+    pub fullscreen_hides_tabbar: bool,
+    /// underlines what `aspell` or `hunspell` do not know in the
+    /// commit message
+    pub spell_check: bool,
+    /// name of a theme in the `themes` directory, `theme.ron` is
+    /// used if not set
+    pub theme: Option<String>,
+    /// used instead of `theme` on a light terminal background
+    pub light_theme: Option<String>,
+    /// used instead of `theme` on a dark terminal background
+    pub dark_theme: Option<String>,
+    /// pins the background instead of detecting it
+    pub theme_mode: ThemeMode,
+    /// pins the graphics protocol for pictures in diffs
+    pub image_preview: ImagePreview,
+}
+
+impl UiConfig {
+    /// whether `theme_name` goes by the terminal background, only
+    /// then it is worth asking the terminal
+    pub fn detects_background(&self) -> bool {
+        self.theme_mode == ThemeMode::Auto
+            && (self.light_theme.is_some()
+                || self.dark_theme.is_some())
+    }
+
+    /// the theme to start with, `light` is the detected terminal
+    /// background (see `ui::background::is_light`) unless `theme_mode`
+    /// pins it
+    pub fn theme_name(&self, light: Option<bool>) -> Option<&str> {
+        if self.light_theme.is_none() && self.dark_theme.is_none() {
+            return self.theme.as_deref();
+        }
+
+        let light = match self.theme_mode {
+            ThemeMode::Auto => light,
+            ThemeMode::Light => Some(true),
+            ThemeMode::Dark => Some(false),
+        };
+        log::info!("light terminal background: {:?}", light);
+
+        match light {
+            Some(true) => self.light_theme.as_deref(),
+            Some(false) => self.dark_theme.as_deref(),
+            None => None,
+        }
+        .or(self.theme.as_deref())
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            mouse_scroll_lines: 3,
+            notification_timeout_secs: 5,
+            status_split_ratio: 0.5,
+            revlog_split_ratio: 0.6,
+            fullscreen_hides_tabbar: false,
+            spell_check: false,
+            theme: None,
+            light_theme: None,
+            dark_theme: None,
+            theme_mode: ThemeMode::Auto,
+            image_preview: ImagePreview::Auto,
+        }
+    }
+}
+
+/// general options stored in `config.ron`,
+/// missing entries fall back to their defaults
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    pub clipboard: ClipboardConfig,
+    pub commit: CommitConfig,
+    pub conventional_commits: ConventionalCommitsConfig,
+    pub diff: DiffConfig,
+    pub hexview: HexViewConfig,
+    pub hooks: HooksConfig,
+    pub revlog: RevlogConfig,
+    pub stash: StashConfig,
+    pub status: StatusConfig,
+    pub tags: TagsConfig,
+    pub ui: UiConfig,
+    /// built-in bindings to start from, `default` or `vim`
+    pub key_preset: KeyPreset,
+    /// keys per action replacing the defaults,
+    /// e.g. `status_stage_file: ["s", "space"]`
+    pub keybindings: HashMap<KeyAction, Vec<String>>,
+}
+
+/// what was toggled in the app, kept in `state.ron` next to
+/// `config.ron` so that remembering it leaves the user's file (and
+/// its comments) alone
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+struct SavedState {
+    flat_file_list: Option<bool>,
+    sort_by_size: Option<bool>,
+    stash: Option<StashConfig>,
+}
+
+impl SavedState {
+    fn get_file() -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("state.ron"))
+    }
+
+    fn read() -> Result<Self> {
+        let file = Self::get_file()?;
+        if !file.exists() {
+            return Ok(Self::default());
+        }
+
+        let mut f = File::open(file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn save(&self) -> Result<()> {
+        let mut file = File::create(Self::get_file()?)?;
+        let data = to_string_pretty(self, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    /// overrides the values of `config.ron` with the remembered ones
+    fn apply(self, config: &mut Config) {
+        if let Some(flat) = self.flat_file_list {
+            config.status.flat_file_list = flat;
+        }
+        if let Some(by_size) = self.sort_by_size {
+            config.status.sort_by_size = by_size;
+        }
+        if let Some(stash) = self.stash {
+            config.stash = stash;
+        }
+    }
+}
+
+impl Config {
+    fn save(&self) -> Result<()> {
+        let config_file = Self::get_config_file()?;
+        let mut file = File::create(config_file)?;
+        let data = to_string_pretty(self, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_config_file() -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("config.ron"))
+    }
+
+    fn read_file(config_file: PathBuf) -> Result<Self> {
+        let mut f = File::open(config_file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn init_internal() -> Result<Self> {
+        let file = Self::get_config_file()?;
+        let mut config = if file.exists() {
+            Self::read_file(file)?
+        } else {
+            let def = Self::default();
+            if def.save().is_err() {
+                log::warn!("failed to store default config to disk.")
+            }
+            def
+        };
+
+        config.migrate();
+
+        match SavedState::read() {
+            Ok(state) => state.apply(&mut config),
+            Err(e) => log::warn!("failed to load state: {}", e),
+        }
+
+        Ok(config)
+    }
+
+    /// moves keys of older versions to where they are read now
+    fn migrate(&mut self) {
+        if let Some(enabled) = self.commit.conventional.take() {
+            log::warn!(
+                "commit.conventional moved to conventional_commits.enabled"
+            );
+            self.conventional_commits.enabled = enabled;
+        }
+        if let Some(types) = self.commit.conventional_types.take() {
+            log::warn!(
+                "commit.conventional_types moved to conventional_commits.standard_types"
+            );
+            self.conventional_commits.standard_types = types;
+        }
+    }
+
+    /// changes the remembered toggles, not the config in use
+    fn save_with(change: impl FnOnce(&mut SavedState)) -> Result<()> {
+        let mut state = SavedState::read()?;
+        change(&mut state);
+        state.save()
+    }
+
+    /// remembers whether the status tab lists files flat
+    pub fn save_flat_file_list(flat: bool) -> Result<()> {
+        Self::save_with(|state| state.flat_file_list = Some(flat))
+    }
+
+    /// remembers whether the status tab sorts files by change size
+    pub fn save_sort_by_size(by_size: bool) -> Result<()> {
+        Self::save_with(|state| state.sort_by_size = Some(by_size))
+    }
+
+    /// remembers the toggles of the stashing tab
+    pub fn save_stash(
+        include_untracked: bool,
+        include_ignored: bool,
+        keep_index: bool,
+    ) -> Result<()> {
+        Self::save_with(|state| {
+            state.stash = Some(StashConfig {
+                include_untracked,
+                include_ignored,
+                keep_index,
+            });
+        })
+    }
+
+    pub fn init() -> Self {
+        Self::init_internal().unwrap_or_else(|e| {
+            log::error!("failed to load config: {}", e);
+            Self::default()
+        })
+    }
+}
@@ -1,4 +1,9 @@
+use crate::strings;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, rc::Rc};
+
+pub type SharedKeyConfig = Rc<KeyConfig>;
 
 const fn no_mod(code: KeyCode) -> KeyEvent {
     KeyEvent {
@@ -18,6 +23,8 @@ pub const TAB_1: KeyEvent = no_mod(KeyCode::Char('1'));
 pub const TAB_2: KeyEvent = no_mod(KeyCode::Char('2'));
 pub const TAB_3: KeyEvent = no_mod(KeyCode::Char('3'));
 pub const TAB_4: KeyEvent = no_mod(KeyCode::Char('4'));
+pub const TAB_5: KeyEvent = no_mod(KeyCode::Char('5'));
+pub const TAB_6: KeyEvent = no_mod(KeyCode::Char('6'));
 pub const TAB_TOGGLE: KeyEvent = no_mod(KeyCode::Tab);
 pub const TAB_TOGGLE_REVERSE: KeyEvent = no_mod(KeyCode::BackTab);
 //TODO: https://github.com/extrawurst/gitui/issues/112
@@ -37,6 +44,10 @@ pub const OPEN_COMMIT: KeyEvent = no_mod(KeyCode::Char('c'));
 pub const OPEN_COMMIT_EDITOR: KeyEvent =
     with_mod(KeyCode::Char('e'), KeyModifiers::CONTROL);
 pub const OPEN_HELP: KeyEvent = no_mod(KeyCode::Char('h'));
+pub const OPEN_HELP_ALT: KeyEvent = no_mod(KeyCode::Char('?'));
+// some terminals report `?` with shift held
+pub const OPEN_HELP_ALT_SHIFT: KeyEvent =
+    with_mod(KeyCode::Char('?'), KeyModifiers::SHIFT);
 pub const MOVE_LEFT: KeyEvent = no_mod(KeyCode::Left);
 pub const MOVE_RIGHT: KeyEvent = no_mod(KeyCode::Right);
 pub const HOME: KeyEvent = no_mod(KeyCode::Home);
@@ -57,17 +68,1116 @@ pub const STATUS_RESET_FILE: KeyEvent =
     with_mod(KeyCode::Char('D'), KeyModifiers::SHIFT);
 pub const DIFF_RESET_HUNK: KeyEvent = STATUS_RESET_FILE;
 pub const STATUS_IGNORE_FILE: KeyEvent = no_mod(KeyCode::Char('i'));
+pub const STATUS_PREVIEW_STAGED: KeyEvent =
+    no_mod(KeyCode::Char('p'));
+pub const STATUS_TOGGLE_UNTRACKED: KeyEvent =
+    no_mod(KeyCode::Char('u'));
+pub const STATUS_TOGGLE_FLAT: KeyEvent = no_mod(KeyCode::Char('f'));
+pub const STATUS_SORT_BY_SIZE: KeyEvent = no_mod(KeyCode::Char('o'));
+pub const STATUS_ASSUME_UNCHANGED: KeyEvent =
+    with_mod(KeyCode::Char('U'), KeyModifiers::SHIFT);
+pub const STATUS_SKIP_WORKTREE: KeyEvent =
+    with_mod(KeyCode::Char('K'), KeyModifiers::SHIFT);
+pub const STATUS_HIDDEN_FILES: KeyEvent =
+    with_mod(KeyCode::Char('H'), KeyModifiers::SHIFT);
+pub const STATUS_TOGGLE_EXECUTABLE: KeyEvent =
+    with_mod(KeyCode::Char('X'), KeyModifiers::SHIFT);
+pub const STATUS_TAKE_OURS: KeyEvent =
+    with_mod(KeyCode::Char('o'), KeyModifiers::CONTROL);
+pub const STATUS_TAKE_THEIRS: KeyEvent =
+    with_mod(KeyCode::Char('t'), KeyModifiers::CONTROL);
+pub const STATUS_CONFLICT_VIEW: KeyEvent =
+    with_mod(KeyCode::Char('M'), KeyModifiers::SHIFT);
+pub const STATUS_MARK_FILE: KeyEvent = no_mod(KeyCode::Char(' '));
+pub const STATUS_STASH_MARKED: KeyEvent =
+    with_mod(KeyCode::Char('s'), KeyModifiers::CONTROL);
+pub const STATUS_APPLY_PATCH: KeyEvent =
+    with_mod(KeyCode::Char('a'), KeyModifiers::CONTROL);
+pub const STATUS_CONTINUE: KeyEvent =
+    with_mod(KeyCode::Char('C'), KeyModifiers::SHIFT);
+pub const STATUS_SKIP: KeyEvent =
+    with_mod(KeyCode::Char('S'), KeyModifiers::SHIFT);
+pub const STATUS_ABORT: KeyEvent =
+    with_mod(KeyCode::Char('A'), KeyModifiers::SHIFT);
+pub const DIFF_COPY_PATCH: KeyEvent = no_mod(KeyCode::Char('y'));
+pub const DIFF_COPY_HUNK: KeyEvent =
+    with_mod(KeyCode::Char('Y'), KeyModifiers::SHIFT);
+pub const DIFF_COPY_LINES: KeyEvent =
+    with_mod(KeyCode::Char('y'), KeyModifiers::CONTROL);
+pub const DIFF_MORE_CONTEXT: KeyEvent = no_mod(KeyCode::Char('+'));
+// most layouts need shift for `+`
+pub const DIFF_MORE_CONTEXT_SHIFT: KeyEvent =
+    with_mod(KeyCode::Char('+'), KeyModifiers::SHIFT);
+pub const DIFF_LESS_CONTEXT: KeyEvent = no_mod(KeyCode::Char('-'));
+pub const DIFF_TOGGLE_WHITESPACE: KeyEvent =
+    with_mod(KeyCode::Char('W'), KeyModifiers::SHIFT);
+pub const DIFF_GOTO_LINE: KeyEvent = no_mod(KeyCode::Char(':'));
+// most layouts need shift for `:`
+pub const DIFF_GOTO_LINE_SHIFT: KeyEvent =
+    with_mod(KeyCode::Char(':'), KeyModifiers::SHIFT);
+pub const DIFF_FIND: KeyEvent = no_mod(KeyCode::Char('/'));
+// some layouts need shift for `/`
+pub const DIFF_FIND_SHIFT: KeyEvent =
+    with_mod(KeyCode::Char('/'), KeyModifiers::SHIFT);
+pub const DIFF_FIND_NEXT: KeyEvent = no_mod(KeyCode::Char('n'));
+pub const DIFF_FIND_PREV: KeyEvent =
+    with_mod(KeyCode::Char('N'), KeyModifiers::SHIFT);
+pub const FIND_TOGGLE_CASE: KeyEvent =
+    with_mod(KeyCode::Char('c'), KeyModifiers::ALT);
 pub const STASHING_SAVE: KeyEvent = no_mod(KeyCode::Char('s'));
 pub const STASHING_TOGGLE_UNTRACKED: KeyEvent =
     no_mod(KeyCode::Char('u'));
+pub const STASHING_TOGGLE_IGNORED: KeyEvent =
+    no_mod(KeyCode::Char('o'));
 pub const STASHING_TOGGLE_INDEX: KeyEvent =
     no_mod(KeyCode::Char('i'));
 pub const STASH_APPLY: KeyEvent = no_mod(KeyCode::Enter);
+pub const STASH_POP: KeyEvent = no_mod(KeyCode::Char('p'));
 pub const STASH_OPEN: KeyEvent = no_mod(KeyCode::Right);
+pub const STASH_BRANCH: KeyEvent = no_mod(KeyCode::Char('b'));
 pub const STASH_DROP: KeyEvent =
     with_mod(KeyCode::Char('D'), KeyModifiers::SHIFT);
 pub const CMD_BAR_TOGGLE: KeyEvent = no_mod(KeyCode::Char('.'));
 pub const LOG_COMMIT_DETAILS: KeyEvent = no_mod(KeyCode::Enter);
 pub const LOG_TAG_COMMIT: KeyEvent = no_mod(KeyCode::Char('t'));
+pub const LOG_NOTE: KeyEvent = no_mod(KeyCode::Char('n'));
+pub const LOG_FIRST_PARENT: KeyEvent = no_mod(KeyCode::Char('p'));
+pub const LOG_BISECT_GOOD: KeyEvent = no_mod(KeyCode::Char('g'));
+pub const LOG_BISECT_BAD: KeyEvent = no_mod(KeyCode::Char('b'));
+pub const LOG_BISECT_RESET: KeyEvent =
+    with_mod(KeyCode::Char('R'), KeyModifiers::SHIFT);
+pub const LOG_MERGE_BRANCH: KeyEvent =
+    with_mod(KeyCode::Char('M'), KeyModifiers::SHIFT);
+pub const LOG_CONTRIBUTORS: KeyEvent =
+    with_mod(KeyCode::Char('s'), KeyModifiers::CONTROL);
+pub const LOG_CLEAR_AUTHOR_FILTER: KeyEvent = EXIT_POPUP;
+pub const LOG_COPY_HASH: KeyEvent = no_mod(KeyCode::Char('y'));
+pub const LOG_MARK_RANGE: KeyEvent = no_mod(KeyCode::Char('v'));
+pub const LOG_MARK_COMMIT: KeyEvent = no_mod(KeyCode::Char(' '));
+pub const LOG_CREATE_BRANCH: KeyEvent =
+    with_mod(KeyCode::Char('B'), KeyModifiers::SHIFT);
+pub const LOG_CHERRY_PICK: KeyEvent =
+    with_mod(KeyCode::Char('C'), KeyModifiers::SHIFT);
+pub const LOG_EXPORT_PATCH: KeyEvent = no_mod(KeyCode::Char('e'));
+pub const LOG_COPY_PATCH: KeyEvent =
+    with_mod(KeyCode::Char('E'), KeyModifiers::SHIFT);
+pub const LOG_ARCHIVE: KeyEvent =
+    with_mod(KeyCode::Char('e'), KeyModifiers::CONTROL);
+pub const LOG_GOTO_ORIG_HEAD: KeyEvent = no_mod(KeyCode::Char('o'));
+pub const LOG_GOTO_FETCH_HEAD: KeyEvent = no_mod(KeyCode::Char('f'));
+pub const LOG_GOTO_MERGE_HEAD: KeyEvent = no_mod(KeyCode::Char('m'));
+pub const CONTRIBUTORS_FILTER: KeyEvent = ENTER;
+pub const TAG_ANNOTATE: KeyEvent =
+    with_mod(KeyCode::Char('a'), KeyModifiers::CONTROL);
+pub const TAG_PUSH: KeyEvent =
+    with_mod(KeyCode::Char('p'), KeyModifiers::CONTROL);
+pub const INSPECT_SAVE_FILE: KeyEvent = no_mod(KeyCode::Char('s'));
+pub const INSPECT_HEX_VIEW: KeyEvent = no_mod(KeyCode::Char('x'));
 pub const COMMIT_AMEND: KeyEvent =
     with_mod(KeyCode::Char('a'), KeyModifiers::CONTROL);
+pub const COMMIT_TOGGLE_DIFF: KeyEvent =
+    with_mod(KeyCode::Char('d'), KeyModifiers::CONTROL);
+pub const COMMIT_NO_VERIFY: KeyEvent =
+    with_mod(KeyCode::Char('n'), KeyModifiers::CONTROL);
+pub const COMMIT_PICK_TYPE: KeyEvent =
+    with_mod(KeyCode::Char('t'), KeyModifiers::CONTROL);
+pub const COMMIT_AUTHOR: KeyEvent =
+    with_mod(KeyCode::Char('u'), KeyModifiers::CONTROL);
+pub const COMMIT_SPELL_SUGGEST: KeyEvent =
+    with_mod(KeyCode::Char('s'), KeyModifiers::CONTROL);
+pub const SUBMODULE_INIT: KeyEvent = no_mod(KeyCode::Char('i'));
+pub const SUBMODULE_UPDATE: KeyEvent = no_mod(KeyCode::Char('u'));
+pub const SUBMODULE_OPEN: KeyEvent = no_mod(KeyCode::Enter);
+pub const CONFIG_EDIT: KeyEvent = no_mod(KeyCode::Enter);
+pub const CONFIG_TOGGLE_GLOBAL: KeyEvent = no_mod(KeyCode::Char('g'));
+pub const NOTIFICATION_DETAILS: KeyEvent =
+    with_mod(KeyCode::Char('N'), KeyModifiers::SHIFT);
+pub const SPLIT_SHRINK: KeyEvent =
+    with_mod(KeyCode::Left, KeyModifiers::ALT);
+pub const SPLIT_GROW: KeyEvent =
+    with_mod(KeyCode::Right, KeyModifiers::ALT);
+pub const PASTE: KeyEvent =
+    with_mod(KeyCode::Char('v'), KeyModifiers::CONTROL);
+pub const OPEN_STATS: KeyEvent =
+    with_mod(KeyCode::Char('I'), KeyModifiers::SHIFT);
+pub const PUSH_EDIT_URL: KeyEvent = no_mod(KeyCode::Char('e'));
+pub const PUSH_NO_VERIFY: KeyEvent =
+    with_mod(KeyCode::Char('n'), KeyModifiers::CONTROL);
+pub const OPEN_PUSH: KeyEvent =
+    with_mod(KeyCode::Char('P'), KeyModifiers::SHIFT);
+pub const OPEN_PALETTE: KeyEvent =
+    with_mod(KeyCode::Char('p'), KeyModifiers::CONTROL);
+pub const UNDO: KeyEvent =
+    with_mod(KeyCode::Char('z'), KeyModifiers::CONTROL);
+pub const TOGGLE_FULLSCREEN: KeyEvent =
+    with_mod(KeyCode::Char('F'), KeyModifiers::SHIFT);
+pub const TOGGLE_FULLSCREEN_ALT: KeyEvent =
+    with_mod(KeyCode::Enter, KeyModifiers::CONTROL);
+
+/// everything a key can be bound to, used as name in the
+/// `keybindings` section of the config
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    Tab1,
+    Tab2,
+    Tab3,
+    Tab4,
+    Tab5,
+    Tab6,
+    TabToggle,
+    TabToggleReverse,
+    FocusWorkdir,
+    FocusStage,
+    FocusRight,
+    FocusLeft,
+    FocusAbove,
+    FocusBelow,
+    Exit,
+    ExitPopup,
+    CloseMsg,
+    OpenCommit,
+    OpenCommitEditor,
+    OpenHelp,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    EditFile,
+    StatusStageFile,
+    StatusStageAll,
+    StatusResetFile,
+    StatusIgnoreFile,
+    StatusPreviewStaged,
+    StatusToggleUntracked,
+    StatusToggleFlat,
+    StatusSortBySize,
+    StatusAssumeUnchanged,
+    StatusSkipWorktree,
+    StatusHiddenFiles,
+    StatusToggleExecutable,
+    StatusTakeOurs,
+    StatusTakeTheirs,
+    StatusConflictView,
+    StatusMarkFile,
+    StatusStashMarked,
+    StatusApplyPatch,
+    // once only for `git am`
+    #[serde(alias = "status_am_continue")]
+    StatusContinue,
+    #[serde(alias = "status_am_skip")]
+    StatusSkip,
+    #[serde(alias = "status_am_abort")]
+    StatusAbort,
+    DiffResetHunk,
+    DiffCopyPatch,
+    DiffCopyHunk,
+    DiffCopyLines,
+    DiffMoreContext,
+    DiffLessContext,
+    DiffToggleWhitespace,
+    DiffGoToLine,
+    DiffFind,
+    DiffFindNext,
+    DiffFindPrev,
+    FindToggleCase,
+    StashingSave,
+    StashingToggleUntracked,
+    StashingToggleIgnored,
+    StashingToggleIndex,
+    StashApply,
+    StashPop,
+    StashBranch,
+    StashOpen,
+    StashDrop,
+    CmdBarToggle,
+    LogCommitDetails,
+    LogTagCommit,
+    LogNote,
+    TagAnnotate,
+    TagPush,
+    LogFirstParent,
+    LogBisectGood,
+    LogBisectBad,
+    LogBisectReset,
+    LogContributors,
+    LogMergeBranch,
+    LogClearAuthorFilter,
+    LogCopyHash,
+    LogMarkRange,
+    LogMarkCommit,
+    LogCreateBranch,
+    LogCherryPick,
+    LogExportPatch,
+    LogCopyPatch,
+    LogArchive,
+    LogGotoOrigHead,
+    LogGotoFetchHead,
+    LogGotoMergeHead,
+    ContributorsFilter,
+    InspectSaveFile,
+    InspectHexView,
+    CommitAmend,
+    CommitToggleDiff,
+    CommitNoVerify,
+    CommitPickType,
+    CommitAuthor,
+    CommitSpellSuggest,
+    SubmoduleInit,
+    SubmoduleUpdate,
+    SubmoduleOpen,
+    ConfigEdit,
+    ConfigToggleGlobal,
+    SplitShrink,
+    SplitGrow,
+    ToggleFullscreen,
+    NotificationDetails,
+    Paste,
+    OpenStats,
+    OpenPush,
+    PushEditUrl,
+    PushNoVerify,
+    OpenPalette,
+    Undo,
+}
+
+impl KeyAction {
+    /// every action, in the order they are listed in the help
+    pub const ALL: &'static [Self] = &[
+        Self::OpenHelp,
+        Self::CmdBarToggle,
+        Self::Exit,
+        Self::ExitPopup,
+        Self::CloseMsg,
+        Self::Tab1,
+        Self::Tab2,
+        Self::Tab3,
+        Self::Tab4,
+        Self::Tab5,
+        Self::Tab6,
+        Self::TabToggle,
+        Self::TabToggleReverse,
+        Self::SplitShrink,
+        Self::SplitGrow,
+        Self::ToggleFullscreen,
+        Self::NotificationDetails,
+        Self::Paste,
+        Self::OpenStats,
+        Self::OpenPush,
+        Self::PushEditUrl,
+        Self::PushNoVerify,
+        Self::OpenPalette,
+        Self::Undo,
+        Self::FocusWorkdir,
+        Self::FocusStage,
+        Self::FocusRight,
+        Self::FocusLeft,
+        Self::FocusAbove,
+        Self::FocusBelow,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::Home,
+        Self::End,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Enter,
+        Self::EditFile,
+        Self::StatusStageFile,
+        Self::StatusStageAll,
+        Self::StatusResetFile,
+        Self::StatusIgnoreFile,
+        Self::StatusPreviewStaged,
+        Self::StatusToggleUntracked,
+        Self::StatusToggleFlat,
+        Self::StatusSortBySize,
+        Self::StatusAssumeUnchanged,
+        Self::StatusSkipWorktree,
+        Self::StatusHiddenFiles,
+        Self::StatusToggleExecutable,
+        Self::StatusTakeOurs,
+        Self::StatusTakeTheirs,
+        Self::StatusConflictView,
+        Self::StatusMarkFile,
+        Self::StatusStashMarked,
+        Self::StatusApplyPatch,
+        Self::StatusContinue,
+        Self::StatusSkip,
+        Self::StatusAbort,
+        Self::DiffResetHunk,
+        Self::DiffCopyPatch,
+        Self::DiffCopyHunk,
+        Self::DiffCopyLines,
+        Self::DiffMoreContext,
+        Self::DiffLessContext,
+        Self::DiffToggleWhitespace,
+        Self::DiffGoToLine,
+        Self::DiffFind,
+        Self::DiffFindNext,
+        Self::DiffFindPrev,
+        Self::FindToggleCase,
+        Self::OpenCommit,
+        Self::OpenCommitEditor,
+        Self::CommitAmend,
+        Self::CommitToggleDiff,
+        Self::CommitNoVerify,
+        Self::CommitPickType,
+        Self::CommitAuthor,
+        Self::CommitSpellSuggest,
+        Self::StashingSave,
+        Self::StashingToggleUntracked,
+        Self::StashingToggleIgnored,
+        Self::StashingToggleIndex,
+        Self::StashApply,
+        Self::StashPop,
+        Self::StashBranch,
+        Self::StashOpen,
+        Self::StashDrop,
+        Self::LogCommitDetails,
+        Self::LogTagCommit,
+        Self::LogNote,
+        Self::TagAnnotate,
+        Self::TagPush,
+        Self::LogFirstParent,
+        Self::LogBisectGood,
+        Self::LogBisectBad,
+        Self::LogBisectReset,
+        Self::LogContributors,
+        Self::LogMergeBranch,
+        Self::LogClearAuthorFilter,
+        Self::LogCopyHash,
+        Self::LogMarkRange,
+        Self::LogMarkCommit,
+        Self::LogCreateBranch,
+        Self::LogCherryPick,
+        Self::LogExportPatch,
+        Self::LogCopyPatch,
+        Self::LogArchive,
+        Self::LogGotoOrigHead,
+        Self::LogGotoFetchHead,
+        Self::LogGotoMergeHead,
+        Self::ContributorsFilter,
+        Self::InspectSaveFile,
+        Self::InspectHexView,
+        Self::SubmoduleInit,
+        Self::SubmoduleUpdate,
+        Self::SubmoduleOpen,
+        Self::ConfigEdit,
+        Self::ConfigToggleGlobal,
+    ];
+
+    /// heading the action is listed under in the help
+    #[allow(clippy::too_many_lines)]
+    pub fn group(self) -> &'static str {
+        match self {
+            Self::OpenHelp
+            | Self::CmdBarToggle
+            | Self::Exit
+            | Self::ExitPopup
+            | Self::CloseMsg
+            | Self::Tab1
+            | Self::Tab2
+            | Self::Tab3
+            | Self::Tab4
+            | Self::Tab5
+            | Self::Tab6
+            | Self::TabToggle
+            | Self::TabToggleReverse
+            | Self::SplitShrink
+            | Self::SplitGrow
+            | Self::ToggleFullscreen
+            | Self::NotificationDetails
+            | Self::Paste
+            | Self::OpenStats
+            | Self::OpenPush
+            | Self::PushEditUrl
+            | Self::PushNoVerify
+            | Self::OpenPalette
+            | Self::Undo => strings::KEY_GROUP_GENERAL,
+            Self::FocusWorkdir
+            | Self::FocusStage
+            | Self::FocusRight
+            | Self::FocusLeft
+            | Self::FocusAbove
+            | Self::FocusBelow
+            | Self::MoveLeft
+            | Self::MoveRight
+            | Self::MoveUp
+            | Self::MoveDown
+            | Self::Home
+            | Self::End
+            | Self::PageUp
+            | Self::PageDown
+            | Self::Enter => strings::KEY_GROUP_NAVIGATION,
+            Self::EditFile
+            | Self::StatusStageFile
+            | Self::StatusStageAll
+            | Self::StatusResetFile
+            | Self::StatusIgnoreFile
+            | Self::StatusPreviewStaged
+            | Self::StatusToggleUntracked
+            | Self::StatusToggleFlat
+            | Self::StatusSortBySize
+            | Self::StatusAssumeUnchanged
+            | Self::StatusSkipWorktree
+            | Self::StatusHiddenFiles
+            | Self::StatusToggleExecutable
+            | Self::StatusTakeOurs
+            | Self::StatusTakeTheirs
+            | Self::StatusConflictView
+            | Self::StatusMarkFile
+            | Self::StatusStashMarked
+            | Self::StatusApplyPatch
+            | Self::StatusContinue
+            | Self::StatusSkip
+            | Self::StatusAbort
+            | Self::DiffResetHunk
+            | Self::DiffCopyPatch
+            | Self::DiffCopyHunk
+            | Self::DiffCopyLines
+            | Self::DiffMoreContext
+            | Self::DiffLessContext
+            | Self::DiffToggleWhitespace
+            | Self::DiffGoToLine
+            | Self::DiffFind
+            | Self::DiffFindNext
+            | Self::DiffFindPrev
+            | Self::FindToggleCase => strings::KEY_GROUP_CHANGES,
+            Self::OpenCommit
+            | Self::OpenCommitEditor
+            | Self::CommitAmend
+            | Self::CommitToggleDiff
+            | Self::CommitNoVerify
+            | Self::CommitPickType
+            | Self::CommitAuthor
+            | Self::CommitSpellSuggest => strings::KEY_GROUP_COMMIT,
+            Self::StashingSave
+            | Self::StashingToggleUntracked
+            | Self::StashingToggleIgnored
+            | Self::StashingToggleIndex
+            | Self::StashApply
+            | Self::StashPop
+            | Self::StashBranch
+            | Self::StashOpen
+            | Self::StashDrop => strings::KEY_GROUP_STASHING,
+            Self::LogCommitDetails
+            | Self::LogTagCommit
+            | Self::LogNote
+            | Self::TagAnnotate
+            | Self::TagPush
+            | Self::LogFirstParent
+            | Self::LogBisectGood
+            | Self::LogBisectBad
+            | Self::LogBisectReset
+            | Self::LogContributors
+            | Self::LogMergeBranch
+            | Self::LogClearAuthorFilter
+            | Self::LogCopyHash
+            | Self::LogMarkRange
+            | Self::LogMarkCommit
+            | Self::LogCreateBranch
+            | Self::LogCherryPick
+            | Self::LogExportPatch
+            | Self::LogCopyPatch
+            | Self::LogArchive
+            | Self::LogGotoOrigHead
+            | Self::LogGotoFetchHead
+            | Self::LogGotoMergeHead
+            | Self::ContributorsFilter
+            | Self::InspectSaveFile
+            | Self::InspectHexView => strings::KEY_GROUP_LOG,
+            Self::SubmoduleInit
+            | Self::SubmoduleUpdate
+            | Self::SubmoduleOpen => strings::KEY_GROUP_SUBMODULES,
+            Self::ConfigEdit | Self::ConfigToggleGlobal => {
+                strings::KEY_GROUP_CONFIG
+            }
+        }
+    }
+
+    /// name used in the `keybindings` section of the config
+    pub fn name(self) -> String {
+        let mut name = String::new();
+        for c in format!("{:?}", self).chars() {
+            if c.is_uppercase() && !name.is_empty() {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        }
+        name
+    }
+
+    /// built-in bindings
+    #[allow(clippy::too_many_lines)]
+    pub const fn default_keys(self) -> &'static [KeyEvent] {
+        match self {
+            Self::Tab1 => &[TAB_1],
+            Self::Tab2 => &[TAB_2],
+            Self::Tab3 => &[TAB_3],
+            Self::Tab4 => &[TAB_4],
+            Self::Tab5 => &[TAB_5],
+            Self::Tab6 => &[TAB_6],
+            Self::TabToggle => &[TAB_TOGGLE],
+            Self::TabToggleReverse => {
+                &[TAB_TOGGLE_REVERSE, TAB_TOGGLE_REVERSE_WINDOWS]
+            }
+            Self::FocusWorkdir => &[FOCUS_WORKDIR],
+            Self::FocusStage => &[FOCUS_STAGE],
+            Self::FocusRight => &[FOCUS_RIGHT],
+            Self::FocusLeft => &[FOCUS_LEFT],
+            Self::FocusAbove => &[FOCUS_ABOVE],
+            Self::FocusBelow => &[FOCUS_BELOW],
+            Self::Exit => &[EXIT],
+            Self::ExitPopup => &[EXIT_POPUP],
+            Self::CloseMsg => &[CLOSE_MSG],
+            Self::OpenCommit => &[OPEN_COMMIT],
+            Self::OpenCommitEditor => &[OPEN_COMMIT_EDITOR],
+            Self::OpenHelp => {
+                &[OPEN_HELP, OPEN_HELP_ALT, OPEN_HELP_ALT_SHIFT]
+            }
+            Self::MoveLeft => &[MOVE_LEFT],
+            Self::MoveRight => &[MOVE_RIGHT],
+            Self::MoveUp => &[MOVE_UP],
+            Self::MoveDown => &[MOVE_DOWN],
+            Self::Home => &[HOME, SHIFT_UP],
+            Self::End => &[END, SHIFT_DOWN],
+            Self::PageUp => &[PAGE_UP],
+            Self::PageDown => &[PAGE_DOWN],
+            Self::Enter => &[ENTER],
+            Self::EditFile => &[EDIT_FILE],
+            Self::StatusStageFile => &[STATUS_STAGE_FILE],
+            Self::StatusStageAll => &[STATUS_STAGE_ALL],
+            Self::StatusResetFile => &[STATUS_RESET_FILE],
+            Self::StatusIgnoreFile => &[STATUS_IGNORE_FILE],
+            Self::StatusPreviewStaged => &[STATUS_PREVIEW_STAGED],
+            Self::StatusToggleUntracked => &[STATUS_TOGGLE_UNTRACKED],
+            Self::StatusToggleFlat => &[STATUS_TOGGLE_FLAT],
+            Self::StatusSortBySize => &[STATUS_SORT_BY_SIZE],
+            Self::StatusAssumeUnchanged => &[STATUS_ASSUME_UNCHANGED],
+            Self::StatusSkipWorktree => &[STATUS_SKIP_WORKTREE],
+            Self::StatusHiddenFiles => &[STATUS_HIDDEN_FILES],
+            Self::StatusToggleExecutable => {
+                &[STATUS_TOGGLE_EXECUTABLE]
+            }
+            Self::StatusTakeOurs => &[STATUS_TAKE_OURS],
+            Self::StatusTakeTheirs => &[STATUS_TAKE_THEIRS],
+            Self::StatusConflictView => &[STATUS_CONFLICT_VIEW],
+            Self::StatusMarkFile => &[STATUS_MARK_FILE],
+            Self::StatusStashMarked => &[STATUS_STASH_MARKED],
+            Self::StatusApplyPatch => &[STATUS_APPLY_PATCH],
+            Self::StatusContinue => &[STATUS_CONTINUE],
+            Self::StatusSkip => &[STATUS_SKIP],
+            Self::StatusAbort => &[STATUS_ABORT],
+            Self::DiffResetHunk => &[DIFF_RESET_HUNK],
+            Self::DiffCopyPatch => &[DIFF_COPY_PATCH],
+            Self::DiffCopyHunk => &[DIFF_COPY_HUNK],
+            Self::DiffCopyLines => &[DIFF_COPY_LINES],
+            Self::DiffMoreContext => {
+                &[DIFF_MORE_CONTEXT, DIFF_MORE_CONTEXT_SHIFT]
+            }
+            Self::DiffLessContext => &[DIFF_LESS_CONTEXT],
+            Self::DiffToggleWhitespace => &[DIFF_TOGGLE_WHITESPACE],
+            Self::DiffGoToLine => {
+                &[DIFF_GOTO_LINE, DIFF_GOTO_LINE_SHIFT]
+            }
+            Self::DiffFind => &[DIFF_FIND, DIFF_FIND_SHIFT],
+            Self::DiffFindNext => &[DIFF_FIND_NEXT],
+            Self::DiffFindPrev => &[DIFF_FIND_PREV],
+            Self::FindToggleCase => &[FIND_TOGGLE_CASE],
+            Self::StashingSave => &[STASHING_SAVE],
+            Self::StashingToggleUntracked => {
+                &[STASHING_TOGGLE_UNTRACKED]
+            }
+            Self::StashingToggleIgnored => &[STASHING_TOGGLE_IGNORED],
+            Self::StashingToggleIndex => &[STASHING_TOGGLE_INDEX],
+            Self::StashApply => &[STASH_APPLY],
+            Self::StashPop => &[STASH_POP],
+            Self::StashBranch => &[STASH_BRANCH],
+            Self::StashOpen => &[STASH_OPEN],
+            Self::StashDrop => &[STASH_DROP],
+            Self::CmdBarToggle => &[CMD_BAR_TOGGLE],
+            Self::LogCommitDetails => &[LOG_COMMIT_DETAILS],
+            Self::LogTagCommit => &[LOG_TAG_COMMIT],
+            Self::LogNote => &[LOG_NOTE],
+            Self::TagAnnotate => &[TAG_ANNOTATE],
+            Self::TagPush => &[TAG_PUSH],
+            Self::LogFirstParent => &[LOG_FIRST_PARENT],
+            Self::LogBisectGood => &[LOG_BISECT_GOOD],
+            Self::LogBisectBad => &[LOG_BISECT_BAD],
+            Self::LogBisectReset => &[LOG_BISECT_RESET],
+            Self::LogContributors => &[LOG_CONTRIBUTORS],
+            Self::LogMergeBranch => &[LOG_MERGE_BRANCH],
+            Self::LogClearAuthorFilter => &[LOG_CLEAR_AUTHOR_FILTER],
+            Self::LogCopyHash => &[LOG_COPY_HASH],
+            Self::LogMarkRange => &[LOG_MARK_RANGE],
+            Self::LogMarkCommit => &[LOG_MARK_COMMIT],
+            Self::LogCreateBranch => &[LOG_CREATE_BRANCH],
+            Self::LogCherryPick => &[LOG_CHERRY_PICK],
+            Self::LogExportPatch => &[LOG_EXPORT_PATCH],
+            Self::LogCopyPatch => &[LOG_COPY_PATCH],
+            Self::LogArchive => &[LOG_ARCHIVE],
+            Self::LogGotoOrigHead => &[LOG_GOTO_ORIG_HEAD],
+            Self::LogGotoFetchHead => &[LOG_GOTO_FETCH_HEAD],
+            Self::LogGotoMergeHead => &[LOG_GOTO_MERGE_HEAD],
+            Self::ContributorsFilter => &[CONTRIBUTORS_FILTER],
+            Self::InspectSaveFile => &[INSPECT_SAVE_FILE],
+            Self::InspectHexView => &[INSPECT_HEX_VIEW],
+            Self::CommitAmend => &[COMMIT_AMEND],
+            Self::CommitToggleDiff => &[COMMIT_TOGGLE_DIFF],
+            Self::CommitNoVerify => &[COMMIT_NO_VERIFY],
+            Self::CommitPickType => &[COMMIT_PICK_TYPE],
+            Self::CommitAuthor => &[COMMIT_AUTHOR],
+            Self::CommitSpellSuggest => &[COMMIT_SPELL_SUGGEST],
+            Self::SubmoduleInit => &[SUBMODULE_INIT],
+            Self::SubmoduleUpdate => &[SUBMODULE_UPDATE],
+            Self::SubmoduleOpen => &[SUBMODULE_OPEN],
+            Self::ConfigEdit => &[CONFIG_EDIT],
+            Self::ConfigToggleGlobal => &[CONFIG_TOGGLE_GLOBAL],
+            Self::SplitShrink => &[SPLIT_SHRINK],
+            Self::SplitGrow => &[SPLIT_GROW],
+            Self::ToggleFullscreen => {
+                &[TOGGLE_FULLSCREEN, TOGGLE_FULLSCREEN_ALT]
+            }
+            Self::NotificationDetails => &[NOTIFICATION_DETAILS],
+            Self::Paste => &[PASTE],
+            Self::OpenStats => &[OPEN_STATS],
+            Self::OpenPush => &[OPEN_PUSH],
+            Self::PushEditUrl => &[PUSH_EDIT_URL],
+            Self::PushNoVerify => &[PUSH_NO_VERIFY],
+            Self::OpenPalette => &[OPEN_PALETTE],
+            Self::Undo => &[UNDO],
+        }
+    }
+}
+
+/// built-in sets of bindings on top of the defaults
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPreset {
+    Default,
+    /// hjkl to move, `g`/`G` to jump to the top/bottom and
+    /// `ctrl+b`/`ctrl+f` to page
+    Vim,
+}
+
+impl Default for KeyPreset {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl KeyPreset {
+    /// the actions the preset binds differently
+    pub const fn bindings(
+        self,
+    ) -> &'static [(KeyAction, &'static [KeyEvent])] {
+        const H: KeyEvent = no_mod(KeyCode::Char('h'));
+        const J: KeyEvent = no_mod(KeyCode::Char('j'));
+        const K: KeyEvent = no_mod(KeyCode::Char('k'));
+        const L: KeyEvent = no_mod(KeyCode::Char('l'));
+        const TOP: KeyEvent = no_mod(KeyCode::Char('g'));
+        const BOTTOM: KeyEvent =
+            with_mod(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        const CTRL_B: KeyEvent =
+            with_mod(KeyCode::Char('b'), KeyModifiers::CONTROL);
+        const CTRL_F: KeyEvent =
+            with_mod(KeyCode::Char('f'), KeyModifiers::CONTROL);
+        const CTRL_G: KeyEvent =
+            with_mod(KeyCode::Char('g'), KeyModifiers::CONTROL);
+
+        match self {
+            Self::Default => &[],
+            Self::Vim => &[
+                (KeyAction::MoveLeft, &[MOVE_LEFT, H]),
+                (KeyAction::MoveRight, &[MOVE_RIGHT, L]),
+                (KeyAction::MoveUp, &[MOVE_UP, K]),
+                (KeyAction::MoveDown, &[MOVE_DOWN, J]),
+                (KeyAction::FocusLeft, &[FOCUS_LEFT, H]),
+                (KeyAction::FocusRight, &[FOCUS_RIGHT, L]),
+                (KeyAction::FocusAbove, &[FOCUS_ABOVE, K]),
+                (KeyAction::FocusBelow, &[FOCUS_BELOW, J]),
+                (KeyAction::StashOpen, &[STASH_OPEN, L]),
+                (KeyAction::Home, &[HOME, SHIFT_UP, TOP]),
+                (KeyAction::End, &[END, SHIFT_DOWN, BOTTOM]),
+                (KeyAction::PageUp, &[PAGE_UP, CTRL_B]),
+                (KeyAction::PageDown, &[PAGE_DOWN, CTRL_F]),
+                // `h` moves, `g` jumps to the top
+                (
+                    KeyAction::OpenHelp,
+                    &[OPEN_HELP_ALT, OPEN_HELP_ALT_SHIFT],
+                ),
+                (KeyAction::LogBisectGood, &[CTRL_G]),
+                (KeyAction::ConfigToggleGlobal, &[CTRL_G]),
+            ],
+        }
+    }
+}
+
+/// two actions that are reachable at the same time sharing a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyClash {
+    pub key: KeyEvent,
+    pub actions: (KeyAction, KeyAction),
+}
+
+impl KeyClash {
+    /// like `g: log_bisect_good, home`
+    pub fn describe(&self) -> String {
+        format!(
+            "{}: {}, {}",
+            key_name(&self.key),
+            self.actions.0.name(),
+            self.actions.1.name()
+        )
+    }
+}
+
+/// the keys bound to each action, the user's bindings replace the
+/// ones of the preset which replace the built-in ones
+#[derive(Debug, Default)]
+pub struct KeyConfig {
+    overrides: HashMap<KeyAction, Vec<KeyEvent>>,
+    mouse_scroll_lines: usize,
+}
+
+impl KeyConfig {
+    /// invalid key combos are logged and skipped, an action keeps its
+    /// preset keys if none of its combos are valid
+    pub fn new(
+        bindings: &HashMap<KeyAction, Vec<String>>,
+        preset: KeyPreset,
+        mouse_scroll_lines: usize,
+    ) -> Self {
+        let mut overrides = preset
+            .bindings()
+            .iter()
+            .map(|(action, keys)| (*action, keys.to_vec()))
+            .collect::<HashMap<_, _>>();
+
+        for (action, combos) in bindings {
+            let keys = combos
+                .iter()
+                .filter_map(|combo| {
+                    let key = parse_key(combo);
+                    if key.is_none() {
+                        log::error!(
+                            "invalid key '{}' bound to {:?}",
+                            combo,
+                            action
+                        );
+                    }
+                    key
+                })
+                .collect::<Vec<_>>();
+
+            if keys.is_empty() && !combos.is_empty() {
+                continue;
+            }
+
+            overrides.insert(*action, keys);
+        }
+
+        Self {
+            overrides,
+            mouse_scroll_lines,
+        }
+    }
+
+    ///
+    pub fn keys(&self, action: KeyAction) -> &[KeyEvent] {
+        self.overrides
+            .get(&action)
+            .map_or_else(|| action.default_keys(), Vec::as_slice)
+    }
+
+    /// is `ev` bound to `action`
+    pub fn matches(&self, ev: &KeyEvent, action: KeyAction) -> bool {
+        self.keys(action).contains(ev)
+    }
+
+    /// keys the preset or the user bound to two actions of the same
+    /// group (general and navigation keys work everywhere). actions
+    /// sharing a key by default are meant for different places and
+    /// are left out
+    pub fn clashes(&self) -> Vec<KeyClash> {
+        let global = |action: KeyAction| {
+            action.group() == strings::KEY_GROUP_GENERAL
+                || action.group() == strings::KEY_GROUP_NAVIGATION
+        };
+
+        let mut clashes = Vec::new();
+
+        for (idx, a) in KeyAction::ALL.iter().enumerate() {
+            for b in &KeyAction::ALL[idx + 1..] {
+                let (a, b) = (*a, *b);
+
+                if !self.overrides.contains_key(&a)
+                    && !self.overrides.contains_key(&b)
+                {
+                    continue;
+                }
+                if a.group() != b.group() && !global(a) && !global(b)
+                {
+                    continue;
+                }
+                if a.default_keys()
+                    .iter()
+                    .any(|key| b.default_keys().contains(key))
+                {
+                    continue;
+                }
+
+                if let Some(key) = self
+                    .keys(a)
+                    .iter()
+                    .find(|key| self.keys(b).contains(key))
+                {
+                    clashes.push(KeyClash {
+                        key: *key,
+                        actions: (a, b),
+                    });
+                }
+            }
+        }
+
+        clashes
+    }
+
+    /// lines to move per turn of the mouse wheel
+    pub const fn mouse_scroll_lines(&self) -> usize {
+        self.mouse_scroll_lines
+    }
+}
+
+/// formats `key` the way `parse_key` reads it, like `ctrl+e`
+pub fn key_name(key: &KeyEvent) -> String {
+    let mut name = String::new();
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("alt+");
+    }
+    // upper case letters imply shift
+    let implied_shift = matches!(
+        key.code,
+        KeyCode::Char(c) if c.is_uppercase()
+    );
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !implied_shift {
+        name.push_str("shift+");
+    }
+
+    match key.code {
+        KeyCode::Char(' ') => name.push_str("space"),
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::F(n) => {
+            name.push('f');
+            name.push_str(&n.to_string());
+        }
+        code => name.push_str(&format!("{:?}", code).to_lowercase()),
+    }
+
+    name
+}
+
+/// parses combos like `s`, `space`, `ctrl+a` or `shift+D`
+fn parse_key(combo: &str) -> Option<KeyEvent> {
+    let mut parts = combo.rsplit('+');
+    let key = parts.next()?;
+
+    let mut modifiers = KeyModifiers::empty();
+    for modifier in parts {
+        modifiers |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "shift" => KeyModifiers::SHIFT,
+            "alt" => KeyModifiers::ALT,
+            _ => return None,
+        };
+    }
+
+    let code = match key.to_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        name => {
+            let mut chars = key.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                KeyCode::Char(c)
+            } else {
+                KeyCode::F(name.strip_prefix('f')?.parse().ok()?)
+            }
+        }
+    };
+
+    // terminals report upper case letters with shift held
+    if let KeyCode::Char(c) = code {
+        if c.is_uppercase() {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+    }
+
+    Some(KeyEvent { code, modifiers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("s"), Some(no_mod(KeyCode::Char('s'))));
+        assert_eq!(
+            parse_key("Space"),
+            Some(no_mod(KeyCode::Char(' ')))
+        );
+        assert_eq!(parse_key("ctrl+e"), Some(OPEN_COMMIT_EDITOR));
+        assert_eq!(parse_key("D"), Some(STATUS_RESET_FILE));
+        assert_eq!(parse_key("shift+D"), Some(STATUS_RESET_FILE));
+        assert_eq!(parse_key("F5"), Some(no_mod(KeyCode::F(5))));
+        assert_eq!(parse_key("hyper+a"), None);
+        assert_eq!(parse_key("foo"), None);
+    }
+
+    #[test]
+    fn test_key_name() {
+        for combo in &["s", "space", "ctrl+e", "D", "alt+left", "f5"]
+        {
+            let key = parse_key(combo).unwrap();
+            assert_eq!(&key_name(&key), combo);
+        }
+        assert_eq!(key_name(&OPEN_HELP_ALT_SHIFT), "shift+?");
+    }
+
+    #[test]
+    fn test_all_actions() {
+        assert_eq!(KeyAction::ALL.len(), 121);
+        assert_eq!(
+            KeyAction::StatusStageFile.name(),
+            "status_stage_file"
+        );
+        assert_eq!(KeyAction::Tab1.name(), "tab1");
+    }
+
+    #[test]
+    fn test_overrides() {
+        let mut bindings = HashMap::new();
+        bindings.insert(
+            KeyAction::StatusStageFile,
+            vec![String::from("s"), String::from("Space")],
+        );
+        bindings.insert(KeyAction::MoveUp, vec![String::from("foo")]);
+        bindings.insert(KeyAction::OpenHelp, Vec::new());
+
+        let config = KeyConfig::new(&bindings, KeyPreset::Default, 3);
+
+        let s = no_mod(KeyCode::Char('s'));
+        assert!(config.matches(&s, KeyAction::StatusStageFile));
+        assert!(!config.matches(&ENTER, KeyAction::StatusStageFile));
+        assert!(config.matches(&MOVE_UP, KeyAction::MoveUp));
+        assert!(!config.matches(&OPEN_HELP, KeyAction::OpenHelp));
+        assert!(config.matches(&SHIFT_UP, KeyAction::Home));
+    }
+
+    #[test]
+    fn test_vim_preset() {
+        let mut bindings = HashMap::new();
+        bindings.insert(KeyAction::MoveDown, vec![String::from("n")]);
+
+        let config = KeyConfig::new(&bindings, KeyPreset::Vim, 3);
+
+        let k = no_mod(KeyCode::Char('k'));
+        let j = no_mod(KeyCode::Char('j'));
+        assert!(config.matches(&k, KeyAction::MoveUp));
+        assert!(config.matches(&MOVE_UP, KeyAction::MoveUp));
+        assert!(!config.matches(&j, KeyAction::MoveDown));
+        assert!(!config.matches(&OPEN_HELP, KeyAction::OpenHelp));
+        assert!(config.matches(&TAB_1, KeyAction::Tab1));
+    }
+
+    #[test]
+    fn test_clashes() {
+        let mut bindings = HashMap::new();
+        assert!(KeyConfig::new(&bindings, KeyPreset::Default, 3)
+            .clashes()
+            .is_empty());
+        assert!(KeyConfig::new(&bindings, KeyPreset::Vim, 3)
+            .clashes()
+            .is_empty());
+
+        // `s` also focuses the staged changes
+        bindings.insert(
+            KeyAction::StatusStageAll,
+            vec![String::from("s")],
+        );
+        // `x` opens the hex view in the log tab only
+        bindings.insert(
+            KeyAction::SubmoduleInit,
+            vec![String::from("x")],
+        );
+
+        let clashes =
+            KeyConfig::new(&bindings, KeyPreset::Default, 3)
+                .clashes();
+        assert_eq!(
+            clashes,
+            vec![KeyClash {
+                key: no_mod(KeyCode::Char('s')),
+                actions: (
+                    KeyAction::FocusStage,
+                    KeyAction::StatusStageAll
+                ),
+            }]
+        );
+        assert_eq!(
+            clashes[0].describe(),
+            "s: focus_stage, status_stage_all"
+        );
+    }
+
+    #[test]
+    fn test_config_section() {
+        let bindings: HashMap<KeyAction, Vec<String>> =
+            ron::de::from_str(
+                r#"{ status_stage_file: ["s", "space"] }"#,
+            )
+            .unwrap();
+
+        let config = KeyConfig::new(&bindings, KeyPreset::Default, 3);
+
+        let space = no_mod(KeyCode::Char(' '));
+        assert!(config.matches(&space, KeyAction::StatusStageFile));
+    }
+}
@@ -8,13 +8,16 @@
 #![allow(clippy::multiple_crate_versions)]
 
 mod app;
+mod clipboard;
 mod cmdbar;
 mod components;
+mod config;
 mod input;
 mod keys;
 mod notify_mutex;
 mod profiler;
 mod queue;
+mod spell;
 mod spinner;
 mod strings;
 mod tabs;
@@ -31,6 +34,7 @@ use clap::{
 };
 use crossbeam_channel::{tick, unbounded, Receiver, Select};
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{
         disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
         LeaveAlternateScreen,
@@ -56,6 +60,7 @@ use tui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
+use ui::{background, style::Theme};
 
 static TICK_INTERVAL: Duration = Duration::from_secs(5);
 static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
@@ -74,8 +79,8 @@ fn main() -> Result<()> {
 
     let _profiler = Profiler::new();
 
-    if !valid_path()? {
-        eprintln!("invalid path\nplease run gitui inside of a non-bare git repository");
+    if !valid_path() {
+        eprintln!("invalid path\nplease run gitui inside of a git repository");
         return Ok(());
     }
 
@@ -96,13 +101,26 @@ fn main() -> Result<()> {
 
     let (tx_git, rx_git) = unbounded();
 
+    let config = config::Config::init();
+    // before the input thread runs, it would swallow the answer of
+    // the terminal to the background query
+    let (light, typed) = if config.ui.detects_background() {
+        background::is_light()
+    } else {
+        (None, Vec::new())
+    };
+    let theme = Theme::init(config.ui.theme_name(light));
+
     let input = Input::new();
 
     let rx_input = input.receiver();
     let ticker = tick(TICK_INTERVAL);
     let spinner_ticker = tick(SPINNER_INTERVAL);
 
-    let mut app = App::new(&tx_git, input);
+    let mut app = App::new(&tx_git, input, config, theme);
+    for ev in typed {
+        app.event(InputEvent::Input(ev))?;
+    }
 
     let mut spinner = Spinner::default();
     let mut first_update = true;
@@ -122,6 +140,11 @@ fn main() -> Result<()> {
 
         {
             if let QueueEvent::SpinnerUpdate = event {
+                let notified = app.update_notifications();
+                let batched = app.update_batch()?;
+                if app.update_spell_check() || notified || batched {
+                    draw(&mut terminal, &app)?;
+                }
                 spinner.update();
                 spinner.draw(&mut terminal)?;
                 continue;
@@ -165,10 +188,12 @@ fn main() -> Result<()> {
 fn setup_terminal() -> Result<()> {
     enable_raw_mode()?;
     io::stdout().execute(EnterAlternateScreen)?;
+    io::stdout().execute(EnableMouseCapture)?;
     Ok(())
 }
 
 fn shutdown_terminal() -> Result<()> {
+    io::stdout().execute(DisableMouseCapture)?;
     io::stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
@@ -186,12 +211,13 @@ fn draw<B: Backend>(
         if let Err(e) = app.draw(&mut f) {
             log::error!("failed to draw: {:?}", e)
         }
-    })
+    })?;
+
+    app.draw_image(&mut io::stdout())
 }
 
-fn valid_path() -> Result<bool> {
-    Ok(asyncgit::sync::is_repo(asyncgit::CWD)
-        && !asyncgit::sync::is_bare_repo(asyncgit::CWD)?)
+fn valid_path() -> bool {
+    asyncgit::sync::is_repo(asyncgit::CWD)
 }
 
 fn select_event(
@@ -1,5 +1,12 @@
-use crate::tabs::StashingOptions;
-use asyncgit::sync::{CommitId, CommitTags};
+use crate::{
+    components::{GoTo, Search},
+    keys::KeyAction,
+    tabs::StashingOptions,
+    ui::notifications::Notification,
+};
+use asyncgit::sync::{
+    BisectMark, CommitId, CommitTags, DiffOptions, PushRequest,
+};
 use bitflags::bitflags;
 use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
@@ -12,6 +19,8 @@ bitflags! {
         const DIFF = 0b010;
         /// commands might need updating (app::update_commands)
         const COMMANDS = 0b100;
+        /// tags were added (revlog::refresh_tags)
+        const TAGS = 0b1000;
     }
 }
 
@@ -23,11 +32,34 @@ pub struct ResetItem {
     pub is_folder: bool,
 }
 
+/// file at a commit that is supposed to be written to disk
+pub struct SaveFileItem {
+    ///
+    pub commit: CommitId,
+    /// path of the file inside the repo
+    pub path: String,
+    /// where to write it to
+    pub dest: String,
+}
+
 ///
 pub enum Action {
     Reset(ResetItem),
-    ResetHunk(String, u64),
+    /// the files marked in the status tab
+    ResetItems(Vec<ResetItem>),
+    /// hunks are found by the options they were shown with
+    ResetHunk(String, u64, DiffOptions),
     StashDrop(CommitId),
+    OverwriteFile(SaveFileItem),
+    ForcePush(PushRequest),
+    /// the commits in the order to apply them (oldest first)
+    CherryPick(Vec<CommitId>),
+    /// reverts the top of the undo stack, described by the text
+    Undo(String),
+    /// the `git notes` of the commit
+    DeleteNote(CommitId),
+    /// the first mark starts a bisect, which checks out commits
+    BisectStart(CommitId, BisectMark),
 }
 
 ///
@@ -38,6 +70,8 @@ pub enum InternalEvent {
     ConfirmedAction(Action),
     ///
     ShowErrorMsg(String),
+    /// toast with the result of an operation
+    Notify(Notification),
     ///
     Update(NeedsUpdate),
     /// open commit msg input
@@ -50,8 +84,52 @@ pub enum InternalEvent {
     InspectCommit(CommitId, Option<CommitTags>),
     ///
     TagCommit(CommitId),
+    /// choose the remote to push the tag to
+    PushTag(String),
+    /// ask for the name of a branch to create at the commit
+    CreateBranch(CommitId),
+    /// ask for the name of a branch to pop the stash onto
+    StashBranch(CommitId),
     ///
-    OpenExternalEditor(Option<String>),
+    OpenContributors,
+    /// choose a branch to merge into the current one
+    OpenMergeBranch,
+    /// add or edit the note of the commit
+    OpenNote(CommitId),
+    /// pick sides per conflict of the conflicted file (path)
+    OpenConflict(String),
+    /// only show commits of the author (email) in the log
+    FilterLogByAuthor(String),
+    /// ask where to save the file (path) as of the commit
+    SaveFile(CommitId, String),
+    /// ask for a directory to write the commits (oldest first) to as
+    /// `.patch` files
+    ExportPatches(Vec<CommitId>),
+    /// ask for patch files to apply via `git am`
+    ApplyPatches,
+    /// list files with assume-unchanged or skip-worktree set
+    OpenHiddenFiles,
+    /// show everything that is staged as one diff
+    PreviewStaged,
+    /// ask for the options to `git archive` the tree-ish
+    OpenArchive(String),
+    /// hex dump of the file (path) as of the commit
+    OpenHexView(CommitId, String),
+    /// ask where to jump to in the diff or hex view
+    OpenGoToLine,
+    /// jump there in the focused diff or the hex view
+    GoToLine(GoTo),
+    /// ask what to find in the diff or hex view
+    OpenFind,
+    /// highlight the matches in the focused diff or the hex view,
+    /// `None` stops finding
+    Find(Option<Search>),
+    /// file and line to edit, the commit message if `None`
+    OpenExternalEditor(Option<(String, Option<usize>)>),
+    /// run a nested instance inside the submodule at given path
+    OpenSubmodule(String),
+    /// run the action as if its key was pressed
+    ExecuteAction(KeyAction),
 }
 
 ///
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Result};
+use std::{
+    io::{self, Write},
+    ops::Range,
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+/// how long the text has to stay the same before it gets checked
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// spell checkers tried in order: the program, its args to list the
+/// misspelled words of stdin and its args for the ispell pipe mode
+/// giving suggestions
+const CHECKERS: [(&str, &[&str], &[&str]); 2] = [
+    ("aspell", &["list"], &["-a"]),
+    ("hunspell", &["-l"], &["-a"]),
+];
+
+/// checks a text once it stopped changing for a moment
+#[derive(Default)]
+pub struct SpellCheck {
+    /// text last seen and since when it is unchanged
+    pending: Option<(String, Instant)>,
+    checked: Option<String>,
+    /// no checker could be run, reported once
+    failed: bool,
+}
+
+impl SpellCheck {
+    /// forgets what was checked, to start over with a new text
+    pub fn reset(&mut self) {
+        self.pending = None;
+        self.checked = None;
+        self.failed = false;
+    }
+
+    /// the misspelled words of `text` if it changed since the last
+    /// check and stayed the same for `DEBOUNCE`
+    pub fn poll(
+        &mut self,
+        text: &str,
+    ) -> Option<Result<Vec<String>>> {
+        if self.failed || self.checked.as_deref() == Some(text) {
+            return None;
+        }
+
+        match &self.pending {
+            Some((pending, since))
+                if pending == text && since.elapsed() >= DEBOUNCE =>
+            {
+                self.checked = Some(text.to_string());
+                let res = misspelled(text);
+                self.failed = res.is_err();
+                Some(res)
+            }
+            Some((pending, _)) if pending == text => None,
+            _ => {
+                self.pending =
+                    Some((text.to_string(), Instant::now()));
+                None
+            }
+        }
+    }
+}
+
+/// byte ranges of the words of `text`
+pub fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+
+    for (pos, c) in text.char_indices() {
+        let in_word =
+            c.is_alphabetic() || (c == '\'' && start.is_some());
+        match (in_word, start) {
+            (true, None) => start = Some(pos),
+            (false, Some(from)) => {
+                ranges.push(from..pos);
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(from) = start {
+        ranges.push(from..text.len());
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| {
+            let word = text[range.clone()].trim_end_matches('\'');
+            range.start..range.start + word.len()
+        })
+        .collect()
+}
+
+/// words of `text` the first spell checker on `PATH` does not know
+pub fn misspelled(text: &str) -> Result<Vec<String>> {
+    let mut words: Vec<String> = run_checker(text, false)?
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect();
+    words.sort();
+    words.dedup();
+
+    Ok(words)
+}
+
+/// what the spell checker proposes instead of `word`
+pub fn suggestions(word: &str) -> Result<Vec<String>> {
+    // `^` keeps the line from being taken as a command
+    let output = run_checker(&format!("^{}\n", word), true)?;
+
+    Ok(parse_suggestions(&output))
+}
+
+/// the suggestions of the ispell pipe mode output: `& word count
+/// offset: one, two` for a misspelled word, `#` if there are none
+fn parse_suggestions(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .find(|line| line.starts_with('&'))
+        .and_then(|line| line.find(": ").map(|pos| &line[pos + 2..]))
+        .map(|list| {
+            list.split(", ").map(|s| s.trim().to_string()).collect()
+        })
+        .unwrap_or_default()
+}
+
+/// runs the first checker that can be started, in `pipe` mode or
+/// listing the misspelled words, with `text` on stdin
+fn run_checker(text: &str, pipe: bool) -> Result<String> {
+    for (program, list_args, pipe_args) in &CHECKERS {
+        let args = if pipe { pipe_args } else { list_args };
+        if let Some(output) = run_with_input(program, args, text)? {
+            return Ok(output);
+        }
+    }
+
+    Err(anyhow!("neither aspell nor hunspell found on PATH"))
+}
+
+/// stdout of `program` run with `text` on stdin, `None` if it is not
+/// installed
+fn run_with_input(
+    program: &str,
+    args: &[&str],
+    text: &str,
+) -> Result<Option<String>> {
+    let spawned = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match spawned {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(None)
+        }
+        child => child?,
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+    } else {
+        Err(anyhow!("{} exited with {}", program, output.status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_ranges() {
+        let text = "fix(ui): don't  crash'";
+        let words: Vec<&str> = word_ranges(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect();
+
+        assert_eq!(words, vec!["fix", "ui", "don't", "crash"]);
+        assert_eq!(word_ranges("ünïcode"), vec![0..9]);
+    }
+
+    #[test]
+    fn test_parse_suggestions() {
+        let output = "@(#) International Ispell Version 3.1.20\n\
+                      & teh 3 0: the, tech, ten\n\n";
+        assert_eq!(
+            parse_suggestions(output),
+            vec!["the", "tech", "ten"]
+        );
+        assert!(parse_suggestions("@(#) banner\n# xyzzy 0\n\n")
+            .is_empty());
+        assert!(parse_suggestions("@(#) banner\n*\n\n").is_empty());
+    }
+}
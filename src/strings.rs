@@ -1,12 +1,39 @@
 pub static TITLE_STATUS: &str = "Unstaged Changes [w]";
 pub static TITLE_DIFF: &str = "Diff: ";
+pub static DIFF_BINARY: &str = "Binary files differ\n";
+pub static DIFF_MODE_CHANGED: &str = "mode changed: ";
+pub static DIFF_IMAGE: &str = "image: ";
+pub static NOTIFY_EXECUTABLE: &str = "executable:";
+pub static NOTIFY_NOT_EXECUTABLE: &str = "not executable:";
+pub static NOTIFY_TOOK_OURS: &str = "took ours:";
+pub static NOTIFY_TOOK_THEIRS: &str = "took theirs:";
+pub static NOTIFY_RESOLVED: &str = "resolved:";
+pub static CONFLICT_TITLE: &str = "Conflict";
+pub static CONFLICT_MARKER_OURS: &str = "<<<<<<< ours";
+pub static CONFLICT_MARKER_BASE: &str = "||||||| base";
+pub static CONFLICT_MARKER_THEIRS: &str = "======= theirs";
+pub static CONFLICT_MARKER_END: &str = ">>>>>>>";
+pub static CONFLICT_BOTH_ADDED: &str =
+    "added on both sides, there is no base";
+pub static CONFLICT_DELETED_BY_US: &str =
+    "deleted on our side, changed on theirs";
+pub static CONFLICT_DELETED_BY_THEM: &str =
+    "deleted on their side, changed on ours";
+pub static CONFLICT_TAKE_FILE: &str =
+    "take ours or theirs for the whole file, the deleting side removes it";
+pub static CONFLICT_UNPICKED: &str = "pick a side for every conflict";
 pub static TITLE_INDEX: &str = "Staged Changes [s]";
 
 pub static TAB_STATUS: &str = "Status [1]";
 pub static TAB_LOG: &str = "Log [2]";
 pub static TAB_STASHING: &str = "Stashing [3]";
 pub static TAB_STASHES: &str = "Stashes [4]";
+pub static TAB_SUBMODULES: &str = "Submodules [5]";
+pub static TAB_CONFIG: &str = "Config [6]";
 pub static TAB_DIVIDER: &str = " | ";
+pub static TAB_BARE_REPO: &str = "not available in a bare repository";
+pub static TAB_BARE_REPO_DETAILS: &str =
+    "status and stashing need a work dir";
 
 pub static CMD_SPLITTER: &str = " ";
 
@@ -14,31 +41,238 @@ pub static MSG_OPENING_EDITOR: &str = "opening editor...";
 pub static MSG_TITLE_ERROR: &str = "Error";
 pub static COMMIT_TITLE: &str = "Commit";
 pub static COMMIT_TITLE_AMEND: &str = "Commit (Amend)";
+pub static COMMIT_TITLE_MERGE: &str = "Commit (Merge)";
+pub static COMMIT_TITLE_NO_VERIFY: &str = " (no verify)";
 pub static COMMIT_MSG: &str = "type commit message..";
+pub static COMMIT_DIFF_TITLE: &str = "staged changes";
+pub static STAGED_PREVIEW_TITLE: &str = "staged changes vs HEAD";
+pub static COMMIT_HOOKS_TITLE: &str = "Running hooks";
+pub static COMMIT_TYPE_TITLE: &str = "Type";
+pub static COMMIT_SCOPE_TITLE: &str = "Scope (optional)";
+pub static COMMIT_NO_SCOPE: &str = "(none)";
+pub static COMMIT_AUTHOR_TITLE: &str =
+    "Author `Name <email>` (empty for yours)";
+pub static COMMIT_DATE_TITLE: &str =
+    "Author date `YYYY-MM-DD [HH:MM[:SS]] [+HHMM]` (empty for now)";
+pub static COMMIT_TITLE_AUTHOR: &str = " as";
+pub static COMMIT_INVALID_AUTHOR: &str = "invalid author override";
+pub static COMMIT_SUGGESTIONS_TITLE: &str = "Suggestions for";
+pub static COMMIT_NO_SUGGESTIONS: &str = "no spelling suggestions";
+pub static SPELL_CHECK_FAILED: &str = "spell check failed";
+pub static COMMIT_LINT_TITLE: &str = "Message lint";
+pub static COMMIT_LINT_FAILED: &str =
+    "commit message breaks the lint rules";
+pub static COMMIT_UNUSUAL_CHARS: &str =
+    "unusual chars in subject, commit again to proceed";
+pub static COMMIT_NOT_CONVENTIONAL: &str =
+    "not a conventional commit";
 pub static COMMIT_EDITOR_MSG: &str = r##"
 # Edit your commit message
 # Lines starting with '#' will be ignored"##;
 pub static STASH_POPUP_TITLE: &str = "Stash";
 pub static STASH_POPUP_MSG: &str = "type name (optional)";
+pub static STASH_UNTRACKED: &str = "untracked";
+pub static STASH_IGNORED: &str = "ignored";
+pub static STASH_KEEP_INDEX: &str = "keeping index";
+pub static STASH_MARKED: &str = "marked files";
 pub static CONFIRM_TITLE_RESET: &str = "Reset";
 pub static CONFIRM_TITLE_STASHDROP: &str = "Drop";
 pub static CONFIRM_MSG_RESET: &str = "confirm file reset?";
+pub static CONFIRM_MSG_RESET_MARKED: &str =
+    "confirm reset of the marked files?";
 pub static CONFIRM_MSG_STASHDROP: &str = "confirm stash drop?";
 pub static CONFIRM_MSG_RESETHUNK: &str = "confirm reset hunk?";
+pub static CONFIRM_TITLE_OVERWRITE: &str = "Overwrite";
+pub static CONFIRM_MSG_OVERWRITE: &str = "file exists, overwrite it?";
+pub static CONFIRM_TITLE_FORCE_PUSH: &str = "Force Push";
+pub static CONFIRM_MSG_FORCE_PUSH: &str =
+    "overwrite the remote branch, commits only it has get lost?";
+pub static CONFIRM_MSG_FORCE_PUSH_TAG: &str =
+    "overwrite the tag of the same name on the remote?";
+pub static CONFIRM_TITLE_UNDO: &str = "Undo";
+pub static CONFIRM_TITLE_CHERRY_PICK: &str = "Cherry-pick";
+pub static CONFIRM_MSG_CHERRY_PICK: &str =
+    "apply the selected commits onto HEAD, oldest first?";
+pub static CONFIRM_TITLE_DELETE_NOTE: &str = "Delete note";
+pub static CONFIRM_MSG_DELETE_NOTE: &str =
+    "delete the note of the commit?";
+pub static CONFIRM_TITLE_BISECT: &str = "Bisect";
+pub static CONFIRM_MSG_BISECT_GOOD: &str =
+    "start a bisect with the commit as good, HEAD will move?";
+pub static CONFIRM_MSG_BISECT_BAD: &str =
+    "start a bisect with the commit as bad, HEAD will move?";
+
+pub static UNDO_DISCARD: &str = "restore the discarded changes of";
+pub static UNDO_STASH_DROP: &str = "restore the dropped stash";
+pub static UNDO_FILES: &str = "files";
+pub static UNDO_NOTHING: &str = "nothing to undo";
+pub static UNDO_DONE: &str = "undone";
+pub static UNDO_FAILED: &str = "undo failed";
+pub static UNDO_SNAPSHOT_FAILED: &str =
+    "could not keep a copy to undo this, nothing was changed:";
 
 pub static LOG_TITLE: &str = "Commit";
+pub static LOG_TAGS_BY_VERSION: &str = " [tags by version]";
+pub static LOG_TITLE_FIRST_PARENT: &str =
+    "Commit (first-parent mode)";
+pub static LOG_BISECT_STATUS: &str = "bisecting";
+pub static LOG_BISECT_FIRST_BAD: &str = "first bad commit:";
+pub static LOG_AUTHOR_FILTER: &str = "by";
+pub static LOG_NO_PSEUDO_REF: &str = "git did not write";
+
+pub static CONTRIBUTORS_TITLE: &str = "Contributors";
+pub static STATS_TITLE: &str = "Repository";
+pub static STATS_NO_BRANCH: &str = "(detached or unborn)";
+pub static STATS_UPDATING: &str = "updating ...";
 
 pub static TAG_COMMIT_POPUP_TITLE: &str = "Tag";
 pub static TAG_COMMIT_POPUP_MSG: &str = "type tag";
+pub static TAG_COMMIT_ANNOTATION_TITLE: &str = "Message of tag";
+
+pub static NOTE_POPUP_TITLE: &str = "Note";
+pub static NOTE_POPUP_MSG: &str = "type note (empty to delete)";
+
+pub static CREATE_BRANCH_POPUP_TITLE: &str = "Branch";
+pub static CREATE_BRANCH_POPUP_MSG: &str = "type branch name";
+pub static STASH_BRANCH_POPUP_TITLE: &str = "Branch from stash";
+
+pub static SAVE_FILE_POPUP_TITLE: &str = "Save file as";
+pub static SAVE_FILE_POPUP_MSG: &str = "type destination path";
+
+pub static EXPORT_PATCH_POPUP_TITLE: &str = "Export patches to";
+pub static EXPORT_PATCH_POPUP_MSG: &str =
+    "type destination directory";
+pub static APPLY_PATCH_POPUP_TITLE: &str = "Apply patches";
+pub static APPLY_PATCH_POPUP_MSG: &str =
+    "patch files or a directory of them, separated by spaces";
+pub static UNTRACKED_FILES_SHOWN: &str = "untracked files:";
+pub static FILES_FLAT: &str = "changed files: flat list";
+pub static FILES_TREE: &str = "changed files: tree";
+pub static FILES_BY_SIZE: &str =
+    "changed files: biggest changes first";
+pub static FILES_BY_PATH: &str = "changed files: by path";
+pub static FILE_STAT_BINARY: &str = "bin";
+pub static FILE_STAT_LARGE: &str = "(large)";
+pub static FILE_UNTRACKED: &str = "[untracked]";
+pub static HIDDEN_FILES_TITLE: &str = "Hidden Files";
+pub static HIDDEN_FILES_EMPTY: &str =
+    "no files with assume-unchanged or skip-worktree set";
+
+pub static ARCHIVE_TITLE: &str = "Export Archive";
+pub static ARCHIVE_EDIT_MSG: &str = "type the new value";
+pub static ARCHIVE_EXPORT: &str = "[ export ]";
+pub static ARCHIVE_WRITING: &str = "writing archive...";
+pub static ARCHIVE_WRITTEN: &str = "archive written to";
+pub static ARCHIVE_FAILED: &str = "archive failed";
+
+pub static PUSH_TITLE: &str = "Push";
+pub static PUSH_NO_REMOTE: &str = "no remote to push to";
+pub static PUSH_DETACHED: &str =
+    "HEAD is detached, check out a branch to push it";
+pub static PUSH_MODE_NORMAL: &str = "fast-forward only";
+pub static PUSH_START: &str = "[ push ]";
+pub static PUSH_PUSHING: &str = "pushing...";
+pub static PUSH_HOOK_RUNNING: &str = "running pre-push hook...";
+pub static PUSH_HOOK_FAILED_TITLE: &str = "pre-push hook failed";
+pub static PUSH_DONE: &str = "pushed to";
+pub static PUSH_TAG_LABEL: &str = "tag";
+pub static PUSH_FAILED: &str = "push failed";
+pub static PUSH_URL_MSG: &str = "https, ssh, git or file url";
+pub static PUSH_URL_INVALID: &str = "invalid url";
+pub static PUSH_URL_SET: &str = "url changed of";
+pub static OPERATION_FAILED: &str = "git could not go on";
+pub static AM_STOPPED_MSG: &str =
+    "a patch did not apply, resolve it and continue, skip or abort";
+
+pub static HEXVIEW_TITLE: &str = "Hex:";
+pub static HEXVIEW_FAILED: &str = "failed to load file";
+pub static GOTO_LINE_POPUP_TITLE: &str = "Go to";
+pub static GOTO_LINE_POPUP_MSG: &str =
+    "line, h<n> for the n-th hunk, 0x<offset> in the hex view";
+pub static FIND_POPUP_TITLE: &str = "Find";
+pub static FIND_POPUP_MSG: &str = "text to find";
+pub static FIND_IGNORE_CASE: &str = "(ignoring case)";
+pub static FIND_MATCH_CASE: &str = "(matching case)";
 
 pub static STASHLIST_TITLE: &str = "Stashes";
+pub static STASH_APPLY_CONFLICTS: &str =
+    "stash applied with conflicts, it is kept";
+
+pub static SUBMODULES_TITLE: &str = "Submodules";
+pub static CONFIG_TITLE_LOCAL: &str = "Git config (editing local)";
+pub static CONFIG_TITLE_GLOBAL: &str = "Git config (editing global)";
+pub static HOOKS_TITLE: &str = "Hooks";
+pub static HOOK_ACTIVE: &str = "runs";
+pub static HOOK_MISSING: &str = "-";
+pub static HOOK_NOT_EXECUTABLE: &str = "exists but is not executable";
+pub static CONFIG_EDIT_MSG: &str = "empty value unsets the key";
 
 pub static HELP_TITLE: &str = "Help: all commands";
+pub static PALETTE_TITLE: &str = "Run command";
+pub static PALETTE_EMPTY: &str = "no matching command";
+pub static KEY_CLASHES: &str =
+    "conflicting key bindings, see details";
+pub static KEY_GROUP_GENERAL: &str = "-- Keys: General --";
+pub static KEY_GROUP_NAVIGATION: &str = "-- Keys: Navigation --";
+pub static KEY_GROUP_CHANGES: &str = "-- Keys: Changes --";
+pub static KEY_GROUP_COMMIT: &str = "-- Keys: Commit --";
+pub static KEY_GROUP_STASHING: &str = "-- Keys: Stashing --";
+pub static KEY_GROUP_LOG: &str = "-- Keys: Log --";
+pub static KEY_GROUP_SUBMODULES: &str = "-- Keys: Submodules --";
+pub static KEY_GROUP_CONFIG: &str = "-- Keys: Config --";
 
 pub static STASHING_FILES_TITLE: &str = "Files to Stash";
 pub static STASHING_OPTIONS_TITLE: &str = "Options";
 
 pub static LOADING_TEXT: &str = "Loading ...";
+pub static NO_UPSTREAM: &str = "no upstream";
+pub static DETACHED_HEAD: &str = "detached HEAD at";
+pub static NOTIFICATION_DETAILS_HINT: &str = "details [N]";
+pub static NOTIFY_COMMITTED: &str = "committed";
+pub static NOTIFY_HOOKS_PASSED: &str = "passed";
+pub static NOTIFY_AMENDED: &str = "amended";
+pub static NOTIFY_WHITESPACE_STRIPPED: &str =
+    "trailing whitespace stripped, files:";
+pub static WHITESPACE_STRIP_FAILED: &str =
+    "stripping trailing whitespace failed";
+pub static NOTIFY_STASHED: &str = "stashed changes";
+pub static NOTIFY_STAGED: &str = "files staged:";
+pub static NOTIFY_UNSTAGED: &str = "files unstaged:";
+pub static NOTIFY_RESET: &str = "files reset:";
+pub static BATCH_STAGING: &str = "staging";
+pub static BATCH_UNSTAGING: &str = "unstaging";
+pub static BATCH_RESETTING: &str = "resetting";
+pub static NOTIFY_UNHIDDEN: &str = "unhidden:";
+pub static NOTIFY_HIDDEN_NOT_STAGED: &str =
+    "hidden files (assume-unchanged/skip-worktree) not staged:";
+pub static NOTIFY_TAGGED: &str = "tagged";
+pub static NOTIFY_NOTE_SAVED: &str = "note saved";
+pub static NOTIFY_NOTE_DELETED: &str = "note deleted";
+pub static NOTE_FAILED: &str = "note failed";
+pub static NOTIFY_BRANCH_CREATED: &str = "created branch";
+pub static NOTIFY_STASH_BRANCH: &str = "stash popped onto new branch";
+pub static NOTIFY_CHERRY_PICKED: &str = "commits cherry-picked:";
+pub static NOTIFY_COPIED_HASHES: &str = "hashes";
+pub static CHERRY_PICK_FAILED: &str = "cherry-pick stopped";
+pub static MERGE_BRANCH_TITLE: &str = "Merge into current branch";
+pub static MERGE_BRANCH_START: &str = "[ merge ]";
+pub static MERGE_BRANCH_DONE: &str = "merged";
+pub static MERGE_BRANCH_UP_TO_DATE: &str = "already up to date";
+pub static MERGE_BRANCH_FAILED: &str = "merge failed";
+pub static MERGE_BRANCH_NONE: &str = "no other local branch to merge";
+pub static MERGE_BRANCH_DETACHED: &str =
+    "HEAD is detached, check out the branch to merge into";
+pub static NOTIFY_COPIED: &str = "copied";
+pub static NOTIFY_COPIED_PATCH: &str = "patch";
+pub static NOTIFY_COPIED_HUNK: &str = "hunk";
+pub static NOTIFY_COPIED_LINES: &str = "code";
+pub static DIFF_CONTEXT_LINES: &str = "context lines:";
+pub static DIFF_WHITESPACE: &str = "whitespace";
+pub static DIFF_WHITESPACE_HUNKS: &str =
+    "hunks need all whitespace shown";
+pub static DIFF_WHITESPACE_HUNKS_DETAILS: &str =
+    "diffs ignoring whitespace do not apply to the files, show all whitespace to stage or reset hunks";
+pub static PASTE_FAILED: &str = "paste failed";
 
 pub mod commit {
     pub static DETAILS_AUTHOR: &str = "Author: ";
@@ -46,10 +280,22 @@ pub mod commit {
     pub static DETAILS_SHA: &str = "SHA: ";
     pub static DETAILS_DATE: &str = "Date: ";
     pub static DETAILS_TAGS: &str = "Tags: ";
+    pub static DETAILS_SIGNATURE: &str = "Signature: ";
+    pub static DETAILS_NOTE_PREFIX: &str = "\u{1f4dd} ";
+    pub static DETAILS_NOTE_INDENT: &str = "   ";
+
+    pub static SIGNATURE_GOOD: &str = "good";
+    pub static SIGNATURE_BAD: &str = "BAD";
+    pub static SIGNATURE_UNKNOWN: &str = "unknown";
 
     pub static DETAILS_INFO_TITLE: &str = "Info";
     pub static DETAILS_MESSAGE_TITLE: &str = "Message";
     pub static DETAILS_FILES_TITLE: &str = "Files:";
+    pub static DETAILS_FILES_UNTRACKED: &str = "untracked";
+
+    pub static STATS_FILES_CHANGED: &str = "files changed";
+    pub static STATS_INSERTIONS: &str = "insertions(+)";
+    pub static STATS_DELETIONS: &str = "deletions(-)";
 }
 
 pub mod order {
@@ -57,7 +303,7 @@ pub mod order {
 }
 
 pub mod commands {
-    use crate::components::CommandText;
+    use crate::{components::CommandText, keys::KeyAction};
 
     static CMD_GROUP_GENERAL: &str = "-- General --";
     static CMD_GROUP_DIFF: &str = "-- Diff --";
@@ -66,25 +312,37 @@ pub mod commands {
     static CMD_GROUP_STASHING: &str = "-- Stashing --";
     static CMD_GROUP_STASHES: &str = "-- Stashes --";
     static CMD_GROUP_LOG: &str = "-- Log --";
+    static CMD_GROUP_SUBMODULES: &str = "-- Submodules --";
+    static CMD_GROUP_CONFIG: &str = "-- Config --";
 
     ///
     pub static TOGGLE_TABS: CommandText = CommandText::new(
         "Next [tab]",
         "switch to next tab",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::TabToggle);
     ///
     pub static TOGGLE_TABS_DIRECT: CommandText = CommandText::new(
-        "Tab [1234]",
+        "Tab [12345]",
         "switch top level tabs directly",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .shown_keys(&[
+        KeyAction::Tab1,
+        KeyAction::Tab2,
+        KeyAction::Tab3,
+        KeyAction::Tab4,
+        KeyAction::Tab5,
+        KeyAction::Tab6,
+    ]);
     ///
     pub static HELP_OPEN: CommandText = CommandText::new(
-        "Help [h]",
+        "Help [h,?]",
         "open this help screen",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::OpenHelp);
     ///
     pub static NAVIGATE_COMMIT_MESSAGE: CommandText =
         CommandText::new(
@@ -97,220 +355,973 @@ pub mod commands {
         "Nav [\u{2190}\u{2191}\u{2192}\u{2193}]",
         "navigate tree view",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .shown_keys(&[
+        KeyAction::MoveLeft,
+        KeyAction::MoveUp,
+        KeyAction::MoveRight,
+        KeyAction::MoveDown,
+    ]);
     ///
     pub static SCROLL: CommandText = CommandText::new(
         "Scroll [\u{2191}\u{2193}]",
         "scroll up or down in focused view",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .shown_keys(&[KeyAction::MoveUp, KeyAction::MoveDown]);
+    ///
+    pub static NOTIFICATION_DETAILS: CommandText = CommandText::new(
+        "Details [N]",
+        "show the full output of the latest notification",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::NotificationDetails);
+    ///
+    pub static OPEN_STATS: CommandText = CommandText::new(
+        "Stats [I]",
+        "show commits, authors, branches, tags and size of the repository",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::OpenStats);
+    ///
+    pub static UNDO: CommandText = CommandText::new(
+        "Undo [^z]",
+        "revert the last discard or stash drop of this session",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Undo);
+    ///
+    pub static OPEN_PALETTE: CommandText = CommandText::new(
+        "Palette [^p]",
+        "search and run any command available right now",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::OpenPalette);
+    ///
+    pub static PALETTE_RUN: CommandText = CommandText::new(
+        "Run [enter]",
+        "run the selected command",
+        CMD_GROUP_GENERAL,
+    )
+    .shown_keys(&[KeyAction::Enter]);
+    ///
+    pub static RESIZE_SPLIT: CommandText = CommandText::new(
+        "Resize [alt+\u{2190}\u{2192}]",
+        "move the divider between the panes (or drag it)",
+        CMD_GROUP_GENERAL,
+    )
+    .shown_keys(&[KeyAction::SplitShrink, KeyAction::SplitGrow]);
+    ///
+    pub static PASTE: CommandText = CommandText::new(
+        "Paste [^v]",
+        "insert the clipboard at the cursor",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Paste);
+    ///
+    pub static TOGGLE_FULLSCREEN: CommandText = CommandText::new(
+        "Fullscreen [F]",
+        "show the focused diff on the whole screen (toggle)",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::ToggleFullscreen);
     ///
     pub static DIFF_HOME_END: CommandText = CommandText::new(
         "Jump up/down [home,end,\u{2191} up,\u{2193} down]",
         "scroll to top or bottom of diff",
         CMD_GROUP_DIFF,
-    );
+    )
+    .shown_keys(&[KeyAction::Home, KeyAction::End]);
     ///
     pub static DIFF_HUNK_ADD: CommandText = CommandText::new(
         "Add hunk [enter]",
         "adds selected hunk to stage",
         CMD_GROUP_DIFF,
-    );
+    )
+    .action(KeyAction::Enter);
     ///
     pub static DIFF_HUNK_REVERT: CommandText = CommandText::new(
         "Revert hunk [D]",
         "reverts selected hunk",
         CMD_GROUP_DIFF,
-    );
+    )
+    .action(KeyAction::DiffResetHunk);
+    ///
+    pub static DIFF_COPY_PATCH: CommandText = CommandText::new(
+        "Copy patch [y]",
+        "copy the diff of the file as a patch for `git apply`",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffCopyPatch);
+    ///
+    pub static DIFF_COPY_HUNK: CommandText = CommandText::new(
+        "Copy hunk [Y]",
+        "copy the selected hunk as a patch for `git apply`",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffCopyHunk);
+    ///
+    pub static DIFF_COPY_LINES: CommandText = CommandText::new(
+        "Copy code [^y]",
+        "copy the new version of the selected hunk without +/- prefixes",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffCopyLines);
+    ///
+    pub static DIFF_MORE_CONTEXT: CommandText = CommandText::new(
+        "More context [+]",
+        "show more unchanged lines around changes",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffMoreContext);
+    ///
+    pub static DIFF_GOTO_LINE: CommandText = CommandText::new(
+        "Go to [:]",
+        "jump to a line of the file or a hunk (h<n>) of the diff, to an offset in the hex view",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffGoToLine);
+    ///
+    pub static DIFF_FIND: CommandText = CommandText::new(
+        "Find [/]",
+        "find text in the diff or the hex view",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffFind);
+    ///
+    pub static DIFF_FIND_NEXT: CommandText = CommandText::new(
+        "Next [n]",
+        "go to the next line with a match",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffFindNext);
+    ///
+    pub static DIFF_FIND_PREV: CommandText = CommandText::new(
+        "Previous [N]",
+        "go to the previous line with a match",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffFindPrev);
+    ///
+    pub static DIFF_LESS_CONTEXT: CommandText = CommandText::new(
+        "Less context [-]",
+        "show fewer unchanged lines around changes",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffLessContext);
+    ///
+    pub static DIFF_TOGGLE_WHITESPACE: CommandText = CommandText::new(
+        "Whitespace [W]",
+        "cycle through showing and ignoring whitespace changes (at line ends, in amount, all)",
+        CMD_GROUP_DIFF,
+    )
+    .action(KeyAction::DiffToggleWhitespace);
     ///
     pub static DIFF_HUNK_REMOVE: CommandText = CommandText::new(
         "Remove hunk [enter]",
         "removes selected hunk from stage",
         CMD_GROUP_DIFF,
-    );
+    )
+    .action(KeyAction::Enter);
     ///
     pub static CLOSE_POPUP: CommandText = CommandText::new(
         "Close [esc]",
         "close overlay (e.g commit, help)",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::ExitPopup);
     ///
     pub static CLOSE_MSG: CommandText = CommandText::new(
         "Close [enter]",
         "close msg popup (e.g msg)",
         CMD_GROUP_GENERAL,
     )
-    .hide_help();
+    .hide_help()
+    .action(KeyAction::CloseMsg);
     ///
     pub static SELECT_STAGING: CommandText = CommandText::new(
         "To stage [s]",
         "focus/select staging area",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::FocusStage);
     ///
     pub static SELECT_STATUS: CommandText = CommandText::new(
         "To files [1,2]",
         "focus/select file tree of staged or unstaged files",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .shown_keys(&[KeyAction::FocusWorkdir, KeyAction::FocusStage]);
     ///
     pub static SELECT_UNSTAGED: CommandText = CommandText::new(
         "To unstaged [w]",
         "focus/select unstaged area",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::FocusWorkdir);
     ///
     pub static COMMIT_OPEN: CommandText = CommandText::new(
         "Commit [c]",
         "open commit popup (available in non-empty stage)",
         CMD_GROUP_COMMIT,
-    );
+    )
+    .action(KeyAction::OpenCommit);
     ///
     pub static COMMIT_OPEN_EDITOR: CommandText = CommandText::new(
         "Open editor [^e]",
         "open commit editor (available in non-empty stage)",
         CMD_GROUP_COMMIT,
-    );
+    )
+    .action(KeyAction::OpenCommitEditor);
     ///
     pub static COMMIT_ENTER: CommandText = CommandText::new(
         "Commit [enter]",
         "commit (available when commit message is non-empty)",
         CMD_GROUP_COMMIT,
-    );
+    )
+    .action(KeyAction::Enter);
     ///
     pub static COMMIT_AMEND: CommandText = CommandText::new(
         "Amend [^a]",
         "amend last commit",
         CMD_GROUP_COMMIT,
-    );
+    )
+    .action(KeyAction::CommitAmend);
+    ///
+    pub static COMMIT_TOGGLE_DIFF: CommandText = CommandText::new(
+        "Diff [^d]",
+        "toggle the staged diff below the commit message",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::CommitToggleDiff);
+    ///
+    pub static COMMIT_PICK_TYPE: CommandText = CommandText::new(
+        "Pick type [enter]",
+        "start the message with the selected conventional commit type",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static COMMIT_CONFIRM_SCOPE: CommandText = CommandText::new(
+        "Confirm scope [enter]",
+        "add the scope (leave empty for none) and write the message",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static COMMIT_HOOKS_CANCEL: CommandText = CommandText::new(
+        "Cancel hooks [esc]",
+        "kill the currently running hook",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::ExitPopup);
+    ///
+    pub static COMMIT_NO_VERIFY: CommandText = CommandText::new(
+        "No verify [^n]",
+        "toggle skipping pre-commit and commit-msg hooks for this commit",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::CommitNoVerify);
+    ///
+    pub static COMMIT_TYPE: CommandText = CommandText::new(
+        "Type [^t]",
+        "pick a conventional commit type (and scope) to start the message with",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::CommitPickType);
+    ///
+    pub static COMMIT_AUTHOR: CommandText = CommandText::new(
+        "Author [^u]",
+        "commit as someone else and/or with another date, you stay the committer",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::CommitAuthor);
+    ///
+    pub static COMMIT_SPELL_SUGGEST: CommandText = CommandText::new(
+        "Spelling [^s]",
+        "suggestions of the spell checker for the word at the cursor",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::CommitSpellSuggest);
+    ///
+    pub static COMMIT_PICK_SUGGESTION: CommandText =
+        CommandText::new(
+            "Replace [enter]",
+            "replace the word with the selected suggestion",
+            CMD_GROUP_COMMIT,
+        )
+        .action(KeyAction::Enter);
+    ///
+    pub static COMMIT_CONFIRM_AUTHOR: CommandText = CommandText::new(
+        "Confirm [enter]",
+        "use the author (or date) typed, leave empty to keep the default",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static COMMIT_PICK_SCOPE: CommandText = CommandText::new(
+        "Pick scope [enter]",
+        "start the message with the type and the selected scope",
+        CMD_GROUP_COMMIT,
+    )
+    .action(KeyAction::Enter);
     ///
     pub static EDIT_ITEM: CommandText = CommandText::new(
         "Edit Item [e]",
         "edit the currently selected file in an external editor",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::EditFile);
     ///
     pub static STAGE_ITEM: CommandText = CommandText::new(
         "Stage Item [enter]",
         "stage currently selected file or entire path",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusStageFile);
     ///
     pub static STAGE_ALL: CommandText = CommandText::new(
         "Stage All [a]",
         "stage all changes (in unstaged files)",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusStageAll);
     ///
     pub static UNSTAGE_ITEM: CommandText = CommandText::new(
         "Unstage Item [enter]",
         "unstage currently selected file or entire path",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusStageFile);
     ///
     pub static UNSTAGE_ALL: CommandText = CommandText::new(
         "Unstage all [a]",
         "unstage all files (in staged files)",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusStageAll);
     ///
     pub static RESET_ITEM: CommandText = CommandText::new(
         "Reset Item [D]",
         "revert changes in selected file or entire path",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusResetFile);
     ///
     pub static IGNORE_ITEM: CommandText = CommandText::new(
         "Ignore [i]",
         "Add file or path to .gitignore",
         CMD_GROUP_CHANGES,
-    );
+    )
+    .action(KeyAction::StatusIgnoreFile);
+    ///
+    pub static PREVIEW_STAGED: CommandText = CommandText::new(
+        "Preview Staged [p]",
+        "show everything that would be committed as one diff",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusPreviewStaged);
+    ///
+    pub static TOGGLE_UNTRACKED: CommandText = CommandText::new(
+        "Untracked [u]",
+        "cycle showing untracked files: no, normal (dirs), all",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusToggleUntracked);
+    ///
+    pub static TOGGLE_FLAT: CommandText = CommandText::new(
+        "Flat/Tree [f]",
+        "toggle listing changed files flat or as a tree",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusToggleFlat);
+    ///
+    pub static TOGGLE_SORT_BY_SIZE: CommandText = CommandText::new(
+        "Sort [o]",
+        "toggle sorting changed files by path or by size of the change",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusSortBySize);
+    ///
+    pub static ASSUME_UNCHANGED: CommandText = CommandText::new(
+        "Assume Unchanged [U]",
+        "hide local changes of the file from git (update-index --assume-unchanged)",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusAssumeUnchanged);
+    ///
+    pub static SKIP_WORKTREE: CommandText = CommandText::new(
+        "Skip Worktree [K]",
+        "keep local changes of the file out of git, even on checkout (update-index --skip-worktree)",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusSkipWorktree);
+    ///
+    pub static HIDDEN_FILES: CommandText = CommandText::new(
+        "Hidden Files [H]",
+        "list files with assume-unchanged or skip-worktree set",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusHiddenFiles);
+    ///
+    pub static TOGGLE_EXECUTABLE: CommandText = CommandText::new(
+        "Executable [X]",
+        "toggle the executable bit of the file (staged too in the stage list)",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusToggleExecutable);
+    ///
+    pub static TAKE_OURS: CommandText = CommandText::new(
+        "Take ours [^o]",
+        "resolve the conflicted file with the version of the current branch",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusTakeOurs);
+    ///
+    pub static TAKE_THEIRS: CommandText = CommandText::new(
+        "Take theirs [^t]",
+        "resolve the conflicted file with the version merged in",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusTakeTheirs);
+    ///
+    pub static CONFLICT_VIEW: CommandText = CommandText::new(
+        "Conflict [M]",
+        "show base, ours and theirs of the conflicted file to pick a side per conflict",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusConflictView);
+    ///
+    pub static CONFLICT_NAVIGATE: CommandText = CommandText::new(
+        "Nav [\u{2191}\u{2193}]",
+        "select the previous or next conflict",
+        CMD_GROUP_CHANGES,
+    )
+    .shown_keys(&[KeyAction::MoveUp, KeyAction::MoveDown]);
+    ///
+    pub static CONFLICT_PICK_OURS: CommandText = CommandText::new(
+        "Ours [^o]",
+        "pick our side of the selected conflict",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusTakeOurs);
+    ///
+    pub static CONFLICT_PICK_THEIRS: CommandText = CommandText::new(
+        "Theirs [^t]",
+        "pick their side of the selected conflict",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusTakeTheirs);
+    ///
+    pub static CONFLICT_WRITE: CommandText = CommandText::new(
+        "Write [enter]",
+        "write the picked sides to the file and stage it",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static HIDDEN_FILES_CLEAR: CommandText = CommandText::new(
+        "Unhide [enter]",
+        "clear assume-unchanged and skip-worktree of the file",
+        CMD_GROUP_CHANGES,
+    )
+    .shown_keys(&[KeyAction::Enter]);
+    ///
+    pub static MARK_ITEM: CommandText = CommandText::new(
+        "Mark [space]",
+        "mark the file (or the files of the folder) to stage, unstage, reset or stash it along with others",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusMarkFile);
+    ///
+    pub static UNMARK_ALL: CommandText = CommandText::new(
+        "Unmark [esc]",
+        "unmark all files",
+        CMD_GROUP_CHANGES,
+    )
+    .shown_keys(&[KeyAction::ExitPopup]);
+    ///
+    pub static STASH_MARKED: CommandText = CommandText::new(
+        "Stash marked [^s]",
+        "stash the changes of the marked files only",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusStashMarked);
+    ///
+    pub static APPLY_PATCH: CommandText = CommandText::new(
+        "Apply Patch [^a]",
+        "apply .patch files as commits (git am)",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusApplyPatch);
+    ///
+    pub static OPERATION_CONTINUE: CommandText = CommandText::new(
+        "Continue [C]",
+        "go on with the stopped operation once conflicts are resolved",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusContinue);
+    ///
+    pub static OPERATION_SKIP: CommandText = CommandText::new(
+        "Skip [S]",
+        "drop the commit or patch that did not apply and go on",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusSkip);
+    ///
+    pub static OPERATION_ABORT: CommandText = CommandText::new(
+        "Abort [A]",
+        "stop the operation and restore the branch",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::StatusAbort);
     ///
     pub static DIFF_FOCUS_LEFT: CommandText = CommandText::new(
         "Back [\u{2190}]", //←
         "view and select changed files",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::FocusLeft);
     ///
     pub static DIFF_FOCUS_RIGHT: CommandText = CommandText::new(
         "Diff [\u{2192}]", //→
         "inspect file diff",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::FocusRight);
     ///
     pub static QUIT: CommandText = CommandText::new(
         "Quit [^c]",
         "quit gitui application",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::Exit);
     ///
     pub static RESET_CONFIRM: CommandText = CommandText::new(
         "Confirm [enter]",
         "resets the file in question",
         CMD_GROUP_GENERAL,
-    );
+    )
+    .action(KeyAction::Enter);
 
     ///
     pub static STASHING_SAVE: CommandText = CommandText::new(
         "Save [s]",
         "opens stash name input popup",
         CMD_GROUP_STASHING,
-    );
+    )
+    .action(KeyAction::StashingSave);
     ///
     pub static STASHING_TOGGLE_INDEXED: CommandText =
         CommandText::new(
             "Toggle Staged [i]",
             "toggle including staged files into stash",
             CMD_GROUP_STASHING,
-        );
+        )
+        .action(KeyAction::StashingToggleIndex);
+    ///
+    pub static STASHING_TOGGLE_IGNORED: CommandText =
+        CommandText::new(
+            "Toggle Ignored [o]",
+            "toggle including ignored files into stash",
+            CMD_GROUP_STASHING,
+        )
+        .action(KeyAction::StashingToggleIgnored);
     ///
     pub static STASHING_TOGGLE_UNTRACKED: CommandText =
         CommandText::new(
             "Toggle Untracked [u]",
             "toggle including untracked files into stash",
             CMD_GROUP_STASHING,
-        );
+        )
+        .action(KeyAction::StashingToggleUntracked);
     ///
     pub static STASHING_CONFIRM_MSG: CommandText = CommandText::new(
         "Stash [enter]",
         "save files to stash",
         CMD_GROUP_STASHING,
-    );
+    )
+    .action(KeyAction::Enter);
     ///
     pub static STASHLIST_APPLY: CommandText = CommandText::new(
         "Apply [enter]",
-        "apply selected stash",
+        "apply selected stash, keeping it",
         CMD_GROUP_STASHES,
-    );
+    )
+    .action(KeyAction::StashApply);
+    ///
+    pub static STASHLIST_POP: CommandText = CommandText::new(
+        "Pop [p]",
+        "apply selected stash and drop it unless that conflicts",
+        CMD_GROUP_STASHES,
+    )
+    .action(KeyAction::StashPop);
+    ///
+    pub static STASHLIST_BRANCH: CommandText = CommandText::new(
+        "Branch [b]",
+        "check out a new branch where the stash was made and pop it there",
+        CMD_GROUP_STASHES,
+    )
+    .action(KeyAction::StashBranch);
     ///
     pub static STASHLIST_DROP: CommandText = CommandText::new(
         "Drop [D]",
         "drop selected stash",
         CMD_GROUP_STASHES,
-    );
+    )
+    .action(KeyAction::StashDrop);
     ///
     pub static STASHLIST_INSPECT: CommandText = CommandText::new(
         "Inspect [\u{2192}]", //→
         "open stash commit details (allows to diff files)",
         CMD_GROUP_STASHES,
-    );
+    )
+    .action(KeyAction::StashOpen);
+    ///
+    pub static SUBMODULE_INIT: CommandText = CommandText::new(
+        "Init [i]",
+        "initialize selected submodule (git submodule init)",
+        CMD_GROUP_SUBMODULES,
+    )
+    .action(KeyAction::SubmoduleInit);
+    ///
+    pub static SUBMODULE_UPDATE: CommandText = CommandText::new(
+        "Update [u]",
+        "clone/checkout recorded commit (git submodule update --init)",
+        CMD_GROUP_SUBMODULES,
+    )
+    .action(KeyAction::SubmoduleUpdate);
+    ///
+    pub static SUBMODULE_OPEN: CommandText = CommandText::new(
+        "Open [enter]",
+        "open selected submodule as nested repository",
+        CMD_GROUP_SUBMODULES,
+    )
+    .action(KeyAction::SubmoduleOpen);
+    ///
+    pub static CONFIG_EDIT: CommandText = CommandText::new(
+        "Edit [enter]",
+        "edit value of selected key",
+        CMD_GROUP_CONFIG,
+    )
+    .action(KeyAction::ConfigEdit);
+    ///
+    pub static CONFIG_TOGGLE_GLOBAL: CommandText = CommandText::new(
+        "Local/Global [g]",
+        "toggle whether edits go to the repository or the global config",
+        CMD_GROUP_CONFIG,
+    )
+    .action(KeyAction::ConfigToggleGlobal);
+    ///
+    pub static CONFIG_CONFIRM: CommandText = CommandText::new(
+        "Save [enter]",
+        "write value to the config",
+        CMD_GROUP_CONFIG,
+    )
+    .action(KeyAction::Enter);
 
     ///
     pub static LOG_DETAILS_TOGGLE: CommandText = CommandText::new(
         "Details [enter]",
         "open details of selected commit",
         CMD_GROUP_LOG,
-    );
+    )
+    .action(KeyAction::LogCommitDetails);
     ///
     pub static LOG_DETAILS_OPEN: CommandText = CommandText::new(
         "Inspect [\u{2192}]", //→
         "inspect selected commit in detail",
         CMD_GROUP_LOG,
-    );
+    )
+    .action(KeyAction::FocusRight);
     ///
     pub static LOG_TAG_COMMIT: CommandText =
-        CommandText::new("Tag [t]", "tag commit", CMD_GROUP_LOG);
+        CommandText::new("Tag [t]", "tag commit", CMD_GROUP_LOG)
+            .action(KeyAction::LogTagCommit);
+    ///
+    pub static LOG_NOTE: CommandText = CommandText::new(
+        "Note [n]",
+        "add or edit the note of the commit",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogNote);
+    ///
+    pub static LOG_FIRST_PARENT: CommandText = CommandText::new(
+        "First parent [p]",
+        "toggle following only the first parent of merges",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogFirstParent);
+    ///
+    pub static LOG_BISECT_GOOD: CommandText = CommandText::new(
+        "Good [g]",
+        "mark commit as good and check out the next one to bisect",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogBisectGood);
+    ///
+    pub static LOG_BISECT_BAD: CommandText = CommandText::new(
+        "Bad [b]",
+        "mark commit as bad and check out the next one to bisect",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogBisectBad);
+    ///
+    pub static LOG_BISECT_RESET: CommandText = CommandText::new(
+        "Bisect reset [R]",
+        "stop bisecting and return to the original HEAD",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogBisectReset);
+    ///
+    pub static LOG_COPY_HASH: CommandText = CommandText::new(
+        "Copy Hash [y]",
+        "copy the selected commit's hash to the clipboard",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogCopyHash);
+    ///
+    pub static LOG_MARK_RANGE: CommandText = CommandText::new(
+        "Mark Range [v]",
+        "start (or drop) a range of commits ending at the selection",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogMarkRange);
+    ///
+    pub static LOG_MARK_COMMIT: CommandText = CommandText::new(
+        "Mark [space]",
+        "mark (or unmark) the selected commit on its own",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogMarkCommit);
+    ///
+    pub static LOG_UNMARK_ALL: CommandText = CommandText::new(
+        "Unmark [esc]",
+        "drop the marked commits and range",
+        CMD_GROUP_LOG,
+    )
+    .shown_keys(&[KeyAction::ExitPopup]);
+    ///
+    pub static LOG_CREATE_BRANCH: CommandText = CommandText::new(
+        "Branch [B]",
+        "create a branch at the newest selected commit",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogCreateBranch);
+    ///
+    pub static LOG_CHERRY_PICK: CommandText = CommandText::new(
+        "Cherry-pick [C]",
+        "apply the selected commit(s) onto HEAD, oldest first",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogCherryPick);
+    ///
+    pub static LOG_EXPORT_PATCH: CommandText = CommandText::new(
+        "Export Patch [e]",
+        "write the selected commit(s) as numbered .patch files",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogExportPatch);
+    ///
+    pub static LOG_COPY_PATCH: CommandText = CommandText::new(
+        "Copy Patch [E]",
+        "copy the selected commit(s) as patch to the clipboard",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogCopyPatch);
+    ///
+    pub static LOG_ARCHIVE: CommandText = CommandText::new(
+        "Archive [^e]",
+        "export a snapshot of the selected commit as zip or tar.gz",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogArchive);
+    ///
+    pub static LOG_GOTO_ORIG_HEAD: CommandText = CommandText::new(
+        "ORIG_HEAD [o]",
+        "select where HEAD was before the last merge, rebase or reset",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogGotoOrigHead);
+    ///
+    pub static LOG_GOTO_FETCH_HEAD: CommandText = CommandText::new(
+        "FETCH_HEAD [f]",
+        "select the commit fetched last",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogGotoFetchHead);
+    ///
+    pub static LOG_GOTO_MERGE_HEAD: CommandText = CommandText::new(
+        "MERGE_HEAD [m]",
+        "select the commit being merged",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogGotoMergeHead);
+    ///
+    pub static OPEN_PUSH: CommandText = CommandText::new(
+        "Push [P]",
+        "push the current branch, optionally forced",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::OpenPush);
+    ///
+    pub static PUSH_ENTER: CommandText = CommandText::new(
+        "Change/Push [enter]",
+        "change the selected option or push",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static PUSH_EDIT_URL: CommandText = CommandText::new(
+        "Edit url [e]",
+        "edit the fetch url of the remote or its push url when selected",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::PushEditUrl);
+    ///
+    pub static PUSH_RETRY_NO_VERIFY: CommandText = CommandText::new(
+        "Retry without hooks [^n]",
+        "push again skipping the pre-push hook, like --no-verify",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::PushNoVerify);
+    ///
+    pub static PUSH_SAVE_URL: CommandText = CommandText::new(
+        "Save url [enter]",
+        "set the url of the remote",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static ARCHIVE_ENTER: CommandText = CommandText::new(
+        "Edit/Export [enter]",
+        "edit the selected option or write the archive",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static LOG_CONTRIBUTORS: CommandText = CommandText::new(
+        "Contributors [^s]",
+        "show commits per author of the current branch",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogContributors);
+    ///
+    pub static LOG_MERGE_BRANCH: CommandText = CommandText::new(
+        "Merge [M]",
+        "merge a local branch into the current one",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::LogMergeBranch);
+    ///
+    pub static MERGE_BRANCH_ENTER: CommandText = CommandText::new(
+        "Change/Merge [enter]",
+        "change the selected option or merge",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static LOG_CLEAR_AUTHOR_FILTER: CommandText =
+        CommandText::new(
+            "Clear filter [esc]",
+            "show the commits of all authors again",
+            CMD_GROUP_LOG,
+        )
+        .action(KeyAction::LogClearAuthorFilter);
+    ///
+    pub static CONTRIBUTORS_FILTER: CommandText = CommandText::new(
+        "Filter log [enter]",
+        "only show the commits of the selected author in the log",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::ContributorsFilter);
     ///
     pub static TAG_COMMIT_CONFIRM_MSG: CommandText =
-        CommandText::new("Tag [enter]", "tag commit", CMD_GROUP_LOG);
+        CommandText::new("Tag [enter]", "tag commit", CMD_GROUP_LOG)
+            .action(KeyAction::Enter);
+    ///
+    pub static NOTE_CONFIRM_MSG: CommandText = CommandText::new(
+        "Save [enter]",
+        "save the note, an empty one deletes it",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static TAG_COMMIT_ANNOTATE: CommandText = CommandText::new(
+        "Annotate [^a]",
+        "add a message, making the tag an annotated one",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::TagAnnotate);
+    ///
+    pub static TAG_COMMIT_PUSH: CommandText = CommandText::new(
+        "Tag & Push [^p]",
+        "create the tag, then push it to a remote of choice",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::TagPush);
+    ///
+    pub static CREATE_BRANCH_CONFIRM_MSG: CommandText =
+        CommandText::new(
+            "Create [enter]",
+            "create the branch",
+            CMD_GROUP_LOG,
+        )
+        .action(KeyAction::Enter);
+    ///
+    pub static INSPECT_SAVE_FILE: CommandText = CommandText::new(
+        "Save file [s]",
+        "save selected file as of this commit to disk",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::InspectSaveFile);
+    ///
+    pub static INSPECT_HEX_VIEW: CommandText = CommandText::new(
+        "Hex view [x]",
+        "show selected file as of this commit as hex dump",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::InspectHexView);
+    ///
+    pub static GOTO_LINE_CONFIRM: CommandText = CommandText::new(
+        "Go [enter]",
+        "jump to the line, hunk or offset",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static FIND_CONFIRM: CommandText = CommandText::new(
+        "Done [enter]",
+        "close, the matches stay highlighted",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static FIND_TOGGLE_CASE: CommandText = CommandText::new(
+        "Match case [alt+c]",
+        "toggle ignoring the case of letters",
+        CMD_GROUP_GENERAL,
+    )
+    .action(KeyAction::FindToggleCase);
+    ///
+    pub static SAVE_FILE_CONFIRM: CommandText = CommandText::new(
+        "Save [enter]",
+        "write file content to the given path",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static EXPORT_PATCH_CONFIRM: CommandText = CommandText::new(
+        "Export [enter]",
+        "write the patches into the given directory",
+        CMD_GROUP_LOG,
+    )
+    .action(KeyAction::Enter);
+    ///
+    pub static APPLY_PATCH_CONFIRM: CommandText = CommandText::new(
+        "Apply [enter]",
+        "apply the given patches via git am",
+        CMD_GROUP_CHANGES,
+    )
+    .action(KeyAction::Enter);
 }
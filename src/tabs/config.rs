@@ -0,0 +1,401 @@
+use crate::{
+    components::{
+        git_config, mouse_scroll, visibility_blocking,
+        CommandBlocking, CommandInfo, Component, DrawableComponent,
+        ScrollType, TextInputComponent,
+    },
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, ConfigScope, ConfigValue, HookStatus},
+    CWD,
+};
+use crossterm::event::{Event, MouseEvent};
+use std::{borrow::Cow, cell::Cell, convert::TryFrom, rc::Rc};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Text,
+    Frame,
+};
+
+/// common git settings, edits go to the repository's config unless
+/// toggled to the global one. below them the state of the hooks
+pub struct ConfigTab {
+    items: Vec<ConfigValue>,
+    hooks: Vec<HookStatus>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_area: Cell<Rect>,
+    visible: bool,
+    target: ConfigScope,
+    input: TextInputComponent,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl ConfigTab {
+    ///
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
+        Self {
+            items: Vec::new(),
+            hooks: Vec::new(),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_area: Cell::new(Rect::default()),
+            visible: false,
+            target: ConfigScope::Local,
+            input: TextInputComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config,
+                "",
+                strings::CONFIG_EDIT_MSG,
+            ),
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn update(&mut self) -> Result<()> {
+        if self.visible {
+            self.items =
+                sync::get_config_values(CWD, git_config::KEYS)?;
+            self.hooks = sync::hooks_status(CWD)?;
+        }
+
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&ConfigValue> {
+        self.items.get(self.selection)
+    }
+
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> bool {
+        let mut changed = false;
+
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                changed |= self.move_selection(scroll);
+            }
+        }
+
+        changed
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) -> bool {
+        let max = self.items.len().saturating_sub(1);
+        let old = self.selection;
+
+        self.selection = match scroll {
+            ScrollType::Up => old.saturating_sub(1),
+            ScrollType::Down => old.saturating_add(1).min(max),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+            _ => old,
+        };
+
+        old != self.selection
+    }
+
+    fn edit(&mut self) -> Result<()> {
+        if let Some(item) = self.selected() {
+            let title = format!(
+                "{} ({})",
+                item.key,
+                Self::scope_text(Some(self.target))
+            );
+            let value = item.value.clone().unwrap_or_default();
+
+            self.input.set_title(title);
+            self.input.set_text(value);
+            self.input.cursor_end();
+            self.input.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<()> {
+        let key = match self.selected() {
+            Some(item) => item.key.clone(),
+            None => return Ok(()),
+        };
+        let value = self.input.get_text().trim().to_string();
+
+        if let Err(e) = git_config::validate(&key, &value) {
+            self.notify(Notification::error(
+                format!("invalid {}", key),
+                e.to_string(),
+            ));
+            return Ok(());
+        }
+
+        let notification = match sync::set_config_value(
+            CWD,
+            &key,
+            &value,
+            self.target,
+        ) {
+            Ok(()) => Notification::success(format!(
+                "{} {} ({})",
+                if value.is_empty() { "unset" } else { "set" },
+                key,
+                Self::scope_text(Some(self.target))
+            )),
+            Err(e) => Notification::error(
+                format!("setting {} failed", key),
+                e.to_string(),
+            ),
+        };
+
+        self.notify(notification);
+        self.input.hide();
+        self.update()
+    }
+
+    fn notify(&self, notification: Notification) {
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+    }
+
+    const fn scope_text(scope: Option<ConfigScope>) -> &'static str {
+        match scope {
+            Some(ConfigScope::Local) => "local",
+            Some(ConfigScope::Global) => "global",
+            Some(ConfigScope::System) => "system",
+            Some(ConfigScope::Other) => "other",
+            None => "unset",
+        }
+    }
+
+    fn item_to_text<'a>(
+        item: &'a ConfigValue,
+        selected: bool,
+        theme: &SharedTheme,
+    ) -> Text<'a> {
+        Text::Styled(
+            Cow::from(format!(
+                "{:<20} {:<8} {}\n",
+                item.key,
+                Self::scope_text(item.scope),
+                item.value.as_deref().unwrap_or_default(),
+            )),
+            theme.text(item.value.is_some(), selected),
+        )
+    }
+
+    fn hook_to_text<'a>(
+        hook: &'a HookStatus,
+        theme: &SharedTheme,
+    ) -> Text<'a> {
+        let (state, style) = if hook.executable {
+            (strings::HOOK_ACTIVE, theme.text(true, false))
+        } else if hook.exists {
+            (strings::HOOK_NOT_EXECUTABLE, theme.text_danger())
+        } else {
+            (strings::HOOK_MISSING, theme.text(false, false))
+        };
+
+        Text::Styled(
+            Cow::from(format!(
+                "{:<20} {:<30} {}\n",
+                hook.name,
+                state,
+                hook.path.display(),
+            )),
+            style,
+        )
+    }
+}
+
+impl DrawableComponent for ConfigTab {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Min(3),
+                    Constraint::Length(
+                        u16::try_from(self.hooks.len()).unwrap_or(0)
+                            + 2,
+                    ),
+                ]
+                .as_ref(),
+            )
+            .split(rect);
+        let rect = chunks[0];
+        let height = rect.height.saturating_sub(2) as usize;
+
+        self.current_area.set(rect);
+        self.scroll_top.set(ui::calc_scroll_top(
+            self.scroll_top.get(),
+            height,
+            self.selection,
+        ));
+
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                Self::item_to_text(
+                    item,
+                    idx == self.selection,
+                    &self.theme,
+                )
+            })
+            .skip(self.scroll_top.get());
+
+        ui::draw_list(
+            f,
+            rect,
+            if self.target == ConfigScope::Global {
+                strings::CONFIG_TITLE_GLOBAL
+            } else {
+                strings::CONFIG_TITLE_LOCAL
+            },
+            items,
+            None,
+            !self.input.is_visible(),
+            &self.theme,
+        );
+
+        ui::draw_list(
+            f,
+            chunks[1],
+            strings::HOOKS_TITLE,
+            self.hooks
+                .iter()
+                .map(|hook| Self::hook_to_text(hook, &self.theme)),
+            None,
+            false,
+            &self.theme,
+        );
+
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ConfigTab {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.input.is_visible() {
+            self.input.commands(out, force_all);
+            out.push(CommandInfo::new(
+                commands::CONFIG_CONFIRM,
+                true,
+                true,
+            ));
+            return CommandBlocking::Blocking;
+        }
+
+        if self.visible || force_all {
+            out.push(CommandInfo::new(
+                commands::CONFIG_EDIT,
+                self.selected().is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::CONFIG_TOGGLE_GLOBAL,
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if !self.visible {
+            return Ok(false);
+        }
+
+        if self.input.is_visible() {
+            if self.input.event(ev)? {
+                return Ok(true);
+            }
+
+            if let Event::Key(k) = ev {
+                if self.key_config.matches(&k, KeyAction::Enter) {
+                    self.save()?;
+                }
+            }
+
+            // the input is modal
+            return Ok(true);
+        }
+
+        if let Event::Key(k) = ev {
+            let keys = Rc::clone(&self.key_config);
+
+            return if keys.matches(&k, KeyAction::MoveUp) {
+                Ok(self.move_selection(ScrollType::Up))
+            } else if keys.matches(&k, KeyAction::MoveDown) {
+                Ok(self.move_selection(ScrollType::Down))
+            } else if keys.matches(&k, KeyAction::Home) {
+                Ok(self.move_selection(ScrollType::Home))
+            } else if keys.matches(&k, KeyAction::End) {
+                Ok(self.move_selection(ScrollType::End))
+            } else if keys.matches(&k, KeyAction::ConfigEdit) {
+                self.edit()?;
+                Ok(true)
+            } else if keys.matches(&k, KeyAction::ConfigToggleGlobal)
+            {
+                self.target = if self.target == ConfigScope::Global {
+                    ConfigScope::Local
+                } else {
+                    ConfigScope::Global
+                };
+                Ok(true)
+            } else {
+                Ok(false)
+            };
+        } else if let Event::Mouse(m) = ev {
+            return Ok(self.handle_mouse_event(m));
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+        self.input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.update()?;
+        Ok(())
+    }
+}
@@ -1,9 +1,13 @@
+mod config;
 mod revlog;
 mod stashing;
 mod stashlist;
 mod status;
+mod submodules;
 
+pub use config::ConfigTab;
 pub use revlog::Revlog;
 pub use stashing::{Stashing, StashingOptions};
 pub use stashlist::StashList;
 pub use status::Status;
+pub use submodules::Submodules;
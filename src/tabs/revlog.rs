@@ -1,29 +1,27 @@
 use crate::{
+    clipboard,
     components::{
         visibility_blocking, CommandBlocking, CommandInfo,
         CommitDetailsComponent, CommitList, Component,
-        DrawableComponent,
+        DrawableComponent, HorizontalSplit,
     },
-    keys,
-    queue::{InternalEvent, Queue},
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings::{self, commands},
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
     cached,
-    sync::{self, CommitId},
+    sync::{self, BisectMark, BisectState, CommitId, HookResult},
     AsyncLog, AsyncNotification, AsyncTags, FetchStatus, CWD,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
-use std::time::Duration;
+use crossterm::event::{Event, KeyEvent};
+use std::{cmp::Reverse, rc::Rc, time::Duration};
 use sync::CommitTags;
-use tui::{
-    backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
-    Frame,
-};
+use tui::{backend::Backend, layout::Rect, Frame};
 
 const SLICE_SIZE: usize = 1200;
 
@@ -31,11 +29,16 @@ const SLICE_SIZE: usize = 1200;
 pub struct Revlog {
     commit_details: CommitDetailsComponent,
     list: CommitList,
+    split: HorizontalSplit,
     git_log: AsyncLog,
     git_tags: AsyncTags,
     queue: Queue,
     visible: bool,
     branch_name: cached::BranchName,
+    bisect: Option<BisectState>,
+    author_filter: Option<String>,
+    key_config: SharedKeyConfig,
+    config: SharedConfig,
 }
 
 impl Revlog {
@@ -44,6 +47,8 @@ impl Revlog {
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: &SharedConfig,
     ) -> Self {
         Self {
             queue: queue.clone(),
@@ -51,12 +56,24 @@ impl Revlog {
                 queue,
                 sender,
                 theme.clone(),
+                key_config.clone(),
+                config.clone(),
+            ),
+            list: CommitList::new(
+                strings::LOG_TITLE,
+                theme,
+                key_config.clone(),
+                config.clone(),
             ),
-            list: CommitList::new(strings::LOG_TITLE, theme),
+            split: HorizontalSplit::new(config.ui.revlog_split_ratio),
             git_log: AsyncLog::new(sender),
             git_tags: AsyncTags::new(sender),
             visible: false,
             branch_name: cached::BranchName::new(CWD),
+            bisect: None,
+            author_filter: None,
+            key_config,
+            config: config.clone(),
         }
     }
 
@@ -73,6 +90,10 @@ impl Revlog {
             let log_changed =
                 self.git_log.fetch()? == FetchStatus::Started;
 
+            if log_changed {
+                self.list.clear_mark();
+            }
+
             self.list.set_count_total(self.git_log.count()?);
 
             let selection = self.list.selection();
@@ -108,6 +129,7 @@ impl Revlog {
         if self.visible {
             match ev {
                 AsyncNotification::CommitFiles
+                | AsyncNotification::Signature
                 | AsyncNotification::Log => self.update()?,
                 AsyncNotification::Tags => {
                     if let Some(tags) = self.git_tags.last()? {
@@ -139,10 +161,381 @@ impl Revlog {
         Ok(())
     }
 
+    /// one hash per line, oldest first
+    fn copy_selected_hashes(&self) -> Result<bool> {
+        let commits = self.selected_commits()?;
+        if commits.is_empty() {
+            return Ok(false);
+        }
+
+        let hashes = commits
+            .iter()
+            .map(CommitId::to_string)
+            .collect::<Vec<_>>();
+        let label = if hashes.len() == 1 {
+            hashes[0].clone()
+        } else {
+            format!(
+                "{} {}",
+                hashes.len(),
+                strings::NOTIFY_COPIED_HASHES
+            )
+        };
+        let notification = clipboard::copy_notification(
+            &hashes.join("\n"),
+            &label,
+            &self.config.clipboard,
+        );
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+
+        Ok(true)
+    }
+
+    /// the commits marked one by one if there are any, otherwise the
+    /// marked range (or the selection), oldest first
+    fn selected_commits(&self) -> Result<Vec<CommitId>> {
+        if self.list.picked().is_empty() {
+            let (start, len) = self.list.selected_range();
+            let mut commits = self.git_log.get_slice(start, len)?;
+            commits.reverse();
+
+            return Ok(commits);
+        }
+
+        // marked commits no longer in the log are left out
+        let mut picked = Vec::with_capacity(self.list.picked().len());
+        for id in self.list.picked() {
+            if let Some(idx) = self.git_log.position(*id)? {
+                picked.push((idx, *id));
+            }
+        }
+        picked.sort_unstable_by_key(|(idx, _)| Reverse(*idx));
+
+        Ok(picked.into_iter().map(|(_, id)| id).collect())
+    }
+
+    /// the branch starts at the newest of the selected commits
+    fn create_branch(&self) -> Result<bool> {
+        let commits = self.selected_commits()?;
+        if let Some(id) = commits.last() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::CreateBranch(*id));
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn cherry_pick(&self) -> Result<bool> {
+        let commits = self.selected_commits()?;
+        if commits.is_empty() {
+            return Ok(false);
+        }
+
+        self.queue.borrow_mut().push_back(
+            InternalEvent::ConfirmAction(Action::CherryPick(commits)),
+        );
+
+        Ok(true)
+    }
+
+    fn export_patches(&self) -> Result<bool> {
+        let commits = self.selected_commits()?;
+        if commits.is_empty() {
+            return Ok(false);
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::ExportPatches(commits));
+
+        Ok(true)
+    }
+
+    fn copy_patches(&self) -> Result<bool> {
+        let commits = self.selected_commits()?;
+        if commits.is_empty() {
+            return Ok(false);
+        }
+
+        let notification =
+            match sync::format_patch_stdout(CWD, &commits) {
+                Ok(patch) => clipboard::copy_notification(
+                    &patch,
+                    &format!("{} patch(es)", commits.len()),
+                    &self.config.clipboard,
+                ),
+                Err(e) => Notification::error(
+                    "format-patch failed",
+                    e.to_string(),
+                ),
+            };
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+
+        Ok(true)
+    }
+
+    /// archives the selected commit, `HEAD` in an empty log
+    fn open_archive(&self) {
+        let treeish = self.selected_commit().map_or_else(
+            || String::from("HEAD"),
+            |id| id.to_string(),
+        );
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::OpenArchive(treeish));
+    }
+
+    fn toggle_first_parent(&mut self) -> Result<()> {
+        let enabled = !self.git_log.is_first_parent();
+        self.git_log.set_first_parent(enabled)?;
+
+        self.update_title();
+        self.list.clear();
+        self.update()
+    }
+
+    /// only show commits authored by `email`
+    pub fn set_author_filter(
+        &mut self,
+        email: Option<String>,
+    ) -> Result<()> {
+        self.git_log.set_author_filter(email.clone())?;
+        self.author_filter = email;
+
+        self.update_title();
+        self.list.clear();
+        self.update()
+    }
+
+    fn update_title(&mut self) {
+        let mut title =
+            String::from(if self.git_log.is_first_parent() {
+                strings::LOG_TITLE_FIRST_PARENT
+            } else {
+                strings::LOG_TITLE
+            });
+
+        if let Some(email) = &self.author_filter {
+            title = format!(
+                "{} {} {}",
+                title,
+                strings::LOG_AUTHOR_FILTER,
+                email
+            );
+        }
+
+        if let Some(state) = &self.bisect {
+            let status = match state.first_bad() {
+                Some(id) => format!(
+                    "{} {}",
+                    strings::LOG_BISECT_FIRST_BAD,
+                    id.to_string()
+                        .chars()
+                        .take(7)
+                        .collect::<String>()
+                ),
+                None if state.remaining > 0 => format!(
+                    "{} commits left, ~{} steps",
+                    state.remaining, state.steps
+                ),
+                None => String::from(strings::LOG_BISECT_STATUS),
+            };
+
+            self.list.set_title(&format!("{} [{}]", title, status));
+        } else {
+            self.list.set_title(&title);
+        }
+    }
+
+    fn update_bisect(&mut self) -> Result<()> {
+        self.bisect = sync::bisect_state(CWD)?;
+        self.update_title();
+        Ok(())
+    }
+
+    fn bisect_action<E: std::fmt::Display>(
+        &mut self,
+        res: std::result::Result<HookResult, E>,
+        what: &str,
+    ) -> Result<()> {
+        let msg = match res {
+            Err(e) => Some(format!("bisect {} error:\n{}", what, e)),
+            Ok(res) => res.error().map(|e| {
+                log::error!("post-checkout hook error: {}", e);
+                e
+            }),
+        };
+
+        if let Some(msg) = msg {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::ShowErrorMsg(msg));
+        }
+
+        // HEAD and the working dir change while bisecting
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        self.update_bisect()?;
+        self.list.clear();
+        self.update()
+    }
+
+    /// marks the selected commit, the mark that starts a bisect has
+    /// to be confirmed first
+    fn mark_selected(&mut self, mark: BisectMark) -> Result<bool> {
+        if let Some(id) = self.selected_commit() {
+            if self.bisect.is_some() {
+                self.bisect_mark(id, mark)?;
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(
+                        Action::BisectStart(id, mark),
+                    ),
+                );
+            }
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// starts a bisect if there is none, a confirmed start comes
+    /// back here
+    pub fn bisect_mark(
+        &mut self,
+        id: CommitId,
+        mark: BisectMark,
+    ) -> Result<()> {
+        let res = sync::bisect_mark(
+            CWD,
+            id,
+            mark,
+            self.config.hooks.timeout(),
+        )
+        .map(|(_, hook)| hook);
+        self.bisect_action(res, "mark")
+    }
+
+    /// marking commits and what works on all selected ones, `None`
+    /// if `key` is not about that
+    fn selection_event(
+        &mut self,
+        key: &KeyEvent,
+    ) -> Result<Option<bool>> {
+        let keys = Rc::clone(&self.key_config);
+
+        let used = if keys.matches(key, KeyAction::LogMarkRange) {
+            self.list.toggle_mark();
+            true
+        } else if keys.matches(key, KeyAction::LogMarkCommit) {
+            self.list.toggle_pick();
+            self.update()?;
+            true
+        } else if keys.matches(key, KeyAction::ExitPopup)
+            && self.list.clear_marks()
+        {
+            true
+        } else if keys.matches(key, KeyAction::LogCopyHash) {
+            self.copy_selected_hashes()?
+        } else if keys.matches(key, KeyAction::LogCreateBranch) {
+            self.create_branch()?
+        } else if keys.matches(key, KeyAction::LogCherryPick) {
+            self.cherry_pick()?
+        } else if keys.matches(key, KeyAction::LogExportPatch) {
+            self.export_patches()?
+        } else if keys.matches(key, KeyAction::LogCopyPatch) {
+            self.copy_patches()?
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(used))
+    }
+
+    /// the popup `key` opens that is not about the selected commit
+    fn popup_key(&self, key: &KeyEvent) -> Option<InternalEvent> {
+        if self.key_config.matches(key, KeyAction::LogContributors) {
+            Some(InternalEvent::OpenContributors)
+        } else if self
+            .key_config
+            .matches(key, KeyAction::LogMergeBranch)
+        {
+            Some(InternalEvent::OpenMergeBranch)
+        } else if self.key_config.matches(key, KeyAction::LogNote) {
+            self.selected_commit().map(InternalEvent::OpenNote)
+        } else {
+            None
+        }
+    }
+
+    /// the pseudo ref `key` is bound to jumping to
+    fn pseudo_ref_key(&self, key: &KeyEvent) -> Option<&'static str> {
+        [
+            (KeyAction::LogGotoOrigHead, "ORIG_HEAD"),
+            (KeyAction::LogGotoFetchHead, "FETCH_HEAD"),
+            (KeyAction::LogGotoMergeHead, "MERGE_HEAD"),
+        ]
+        .iter()
+        .find(|(action, _)| self.key_config.matches(key, *action))
+        .map(|(_, name)| *name)
+    }
+
+    /// selects the commit the pseudo ref `name` points to, one that
+    /// is not in the log (like a fetched one) opens in the inspect
+    /// popup instead
+    fn goto_pseudo_ref(&mut self, name: &str) -> Result<bool> {
+        match sync::get_pseudo_ref(CWD, name)? {
+            None => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::success(
+                        format!(
+                            "{} {}",
+                            strings::LOG_NO_PSEUDO_REF,
+                            name
+                        ),
+                    )),
+                );
+            }
+            Some(id) => {
+                if let Some(idx) = self.git_log.position(id)? {
+                    self.list.select(idx);
+                    self.update()?;
+                } else {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::InspectCommit(
+                            id,
+                            self.selected_commit_tags(&Some(id)),
+                        ),
+                    );
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
     fn selected_commit(&self) -> Option<CommitId> {
         self.list.selected_entry().map(|e| e.id)
     }
 
+    /// fetches the tags right away instead of when they are outdated
+    pub fn refresh_tags(&mut self) -> Result<()> {
+        self.git_tags.request(Duration::from_secs(0), true)?;
+
+        Ok(())
+    }
+
     fn selected_commit_tags(
         &self,
         commit: &Option<CommitId>,
@@ -169,20 +562,10 @@ impl DrawableComponent for Revlog {
         f: &mut Frame<B>,
         area: Rect,
     ) -> Result<()> {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                [
-                    Constraint::Percentage(60),
-                    Constraint::Percentage(40),
-                ]
-                .as_ref(),
-            )
-            .split(area);
-
         if self.commit_details.is_visible() {
-            self.list.draw(f, chunks[0])?;
-            self.commit_details.draw(f, chunks[1])?;
+            let (left, right) = self.split.split(area);
+            self.list.draw(f, left)?;
+            self.commit_details.draw(f, right)?;
         } else {
             self.list.draw(f, area)?;
         }
@@ -194,53 +577,94 @@ impl DrawableComponent for Revlog {
 impl Component for Revlog {
     fn event(&mut self, ev: Event) -> Result<bool> {
         if self.visible {
+            if let Event::Mouse(m) = ev {
+                if self.commit_details.is_visible()
+                    && self.split.mouse_event(m)
+                {
+                    return Ok(true);
+                }
+            }
+
             let event_used = self.list.event(ev)?;
 
             if event_used {
                 self.update()?;
                 return Ok(true);
-            } else {
-                match ev {
-                    Event::Key(keys::LOG_COMMIT_DETAILS) => {
-                        self.commit_details.toggle_visible()?;
-                        self.update()?;
-                        return Ok(true);
-                    }
-
-                    Event::Key(keys::LOG_TAG_COMMIT) => {
-                        return if let Some(id) =
-                            self.selected_commit()
-                        {
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::TagCommit(id),
-                            );
-                            Ok(true)
-                        } else {
-                            Ok(false)
-                        };
-                    }
-
-                    Event::Key(keys::FOCUS_RIGHT)
-                        if self.commit_details.is_visible() =>
-                    {
-                        return if let Some(id) =
-                            self.selected_commit()
-                        {
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::InspectCommit(
-                                    id,
-                                    self.selected_commit_tags(&Some(
-                                        id,
-                                    )),
-                                ),
-                            );
-                            Ok(true)
-                        } else {
-                            Ok(false)
-                        };
-                    }
-
-                    _ => (),
+            } else if let Event::Key(k) = ev {
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&k, KeyAction::SplitShrink)
+                    && self.commit_details.is_visible()
+                {
+                    return Ok(self.split.resize(-1));
+                } else if keys.matches(&k, KeyAction::SplitGrow)
+                    && self.commit_details.is_visible()
+                {
+                    return Ok(self.split.resize(1));
+                } else if keys
+                    .matches(&k, KeyAction::LogCommitDetails)
+                {
+                    self.commit_details.toggle_visible()?;
+                    self.update()?;
+                    return Ok(true);
+                } else if keys.matches(&k, KeyAction::LogTagCommit) {
+                    return if let Some(id) = self.selected_commit() {
+                        self.queue
+                            .borrow_mut()
+                            .push_back(InternalEvent::TagCommit(id));
+                        Ok(true)
+                    } else {
+                        Ok(false)
+                    };
+                } else if keys.matches(&k, KeyAction::LogFirstParent)
+                {
+                    self.toggle_first_parent()?;
+                    return Ok(true);
+                } else if let Some(used) = self.selection_event(&k)? {
+                    return Ok(used);
+                } else if keys.matches(&k, KeyAction::LogArchive) {
+                    self.open_archive();
+                    return Ok(true);
+                } else if let Some(event) = self.popup_key(&k) {
+                    self.queue.borrow_mut().push_back(event);
+                    return Ok(true);
+                } else if keys
+                    .matches(&k, KeyAction::LogClearAuthorFilter)
+                    && self.author_filter.is_some()
+                {
+                    self.set_author_filter(None)?;
+                    return Ok(true);
+                } else if keys.matches(&k, KeyAction::LogBisectGood) {
+                    return self.mark_selected(BisectMark::Good);
+                } else if keys.matches(&k, KeyAction::LogBisectBad) {
+                    return self.mark_selected(BisectMark::Bad);
+                } else if keys.matches(&k, KeyAction::LogBisectReset)
+                    && self.bisect.is_some()
+                {
+                    self.bisect_action(
+                        sync::bisect_reset(
+                            CWD,
+                            self.config.hooks.timeout(),
+                        ),
+                        "reset",
+                    )?;
+                    return Ok(true);
+                } else if let Some(name) = self.pseudo_ref_key(&k) {
+                    return self.goto_pseudo_ref(name);
+                } else if keys.matches(&k, KeyAction::FocusRight)
+                    && self.commit_details.is_visible()
+                {
+                    return if let Some(id) = self.selected_commit() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::InspectCommit(
+                                id,
+                                self.selected_commit_tags(&Some(id)),
+                            ),
+                        );
+                        Ok(true)
+                    } else {
+                        Ok(false)
+                    };
                 }
             }
         }
@@ -263,6 +687,16 @@ impl Component for Revlog {
             self.visible,
         ));
 
+        out.push(
+            CommandInfo::new(
+                commands::RESIZE_SPLIT,
+                true,
+                (self.visible && self.commit_details.is_visible())
+                    || force_all,
+            )
+            .hidden(),
+        );
+
         out.push(CommandInfo::new(
             commands::LOG_DETAILS_OPEN,
             true,
@@ -276,6 +710,85 @@ impl Component for Revlog {
             self.visible || force_all,
         ));
 
+        out.push(CommandInfo::new(
+            commands::LOG_FIRST_PARENT,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_MARK_RANGE,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_UNMARK_ALL,
+            true,
+            (self.visible && self.list.has_marks()) || force_all,
+        ));
+
+        for cmd in &[
+            commands::LOG_MARK_COMMIT,
+            commands::LOG_COPY_HASH,
+            commands::LOG_CREATE_BRANCH,
+            commands::LOG_NOTE,
+            commands::LOG_CHERRY_PICK,
+            commands::LOG_EXPORT_PATCH,
+            commands::LOG_COPY_PATCH,
+        ] {
+            out.push(CommandInfo::new(
+                *cmd,
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            ));
+        }
+
+        out.push(CommandInfo::new(
+            commands::LOG_ARCHIVE,
+            true,
+            self.visible || force_all,
+        ));
+
+        for cmd in &[
+            commands::LOG_GOTO_ORIG_HEAD,
+            commands::LOG_GOTO_FETCH_HEAD,
+            commands::LOG_GOTO_MERGE_HEAD,
+            commands::LOG_CONTRIBUTORS,
+            commands::LOG_MERGE_BRANCH,
+        ] {
+            out.push(CommandInfo::new(
+                *cmd,
+                true,
+                self.visible || force_all,
+            ));
+        }
+
+        out.push(CommandInfo::new(
+            commands::LOG_CLEAR_AUTHOR_FILTER,
+            true,
+            (self.visible && self.author_filter.is_some())
+                || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_BISECT_GOOD,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_BISECT_BAD,
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            commands::LOG_BISECT_RESET,
+            true,
+            (self.visible && self.bisect.is_some()) || force_all,
+        ));
+
         visibility_blocking(self)
     }
 
@@ -285,12 +798,14 @@ impl Component for Revlog {
 
     fn hide(&mut self) {
         self.visible = false;
+        self.list.clear_marks();
         self.git_log.set_background();
     }
 
     fn show(&mut self) -> Result<()> {
         self.visible = true;
         self.list.clear();
+        self.update_bisect()?;
         self.update()?;
 
         Ok(())
@@ -5,28 +5,32 @@ use crate::{
         CommandBlocking, CommandInfo, Component, DrawableComponent,
         FileTreeComponent,
     },
-    keys,
+    config::{Config, SharedConfig},
+    keys::{KeyAction, SharedKeyConfig},
     queue::{InternalEvent, Queue},
     strings::{self, commands},
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::status::StatusType, AsyncNotification, AsyncStatus,
-    StatusParams,
+    sync::status::{RenameDetection, ShowUntrackedFiles, StatusType},
+    AsyncNotification, AsyncStatus, StatusParams,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::borrow::Cow;
+use std::{borrow::Cow, rc::Rc};
 use tui::{
     layout::{Alignment, Constraint, Direction, Layout},
     widgets::{Block, Borders, Paragraph, Text},
 };
 
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct StashingOptions {
     pub stash_untracked: bool,
+    pub stash_ignored: bool,
     pub keep_index: bool,
+    /// stashes just these files (with their untracked ones) if set
+    pub paths: Vec<String>,
 }
 
 pub struct Stashing {
@@ -34,7 +38,9 @@ pub struct Stashing {
     visible: bool,
     options: StashingOptions,
     theme: SharedTheme,
+    key_config: SharedKeyConfig,
     git_status: AsyncStatus,
+    renames: RenameDetection,
     queue: Queue,
 }
 
@@ -46,6 +52,8 @@ impl Stashing {
         sender: &Sender<AsyncNotification>,
         queue: &Queue,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: &SharedConfig,
     ) -> Self {
         Self {
             index: FileTreeComponent::new(
@@ -53,14 +61,20 @@ impl Stashing {
                 true,
                 Some(queue.clone()),
                 theme.clone(),
+                key_config.clone(),
             ),
             visible: false,
             options: StashingOptions {
-                keep_index: false,
-                stash_untracked: true,
+                keep_index: config.stash.keep_index,
+                stash_untracked: config.stash.include_untracked,
+                stash_ignored: config.stash.include_ignored,
+                paths: Vec::new(),
             },
             theme,
-            git_status: AsyncStatus::new(sender.clone()),
+            key_config,
+            git_status: AsyncStatus::new(sender.clone())
+                .debounce(config.status.refresh_debounce()),
+            renames: config.status.renames(),
             queue: queue.clone(),
         }
     }
@@ -68,10 +82,17 @@ impl Stashing {
     ///
     pub fn update(&mut self) -> Result<()> {
         if self.visible {
-            self.git_status.fetch(StatusParams::new(
-                StatusType::Both,
-                self.options.stash_untracked,
-            ))?;
+            self.git_status.fetch(
+                StatusParams::new(
+                    StatusType::Both,
+                    if self.options.stash_untracked {
+                        ShowUntrackedFiles::All
+                    } else {
+                        ShowUntrackedFiles::No
+                    },
+                )
+                .renames(self.renames),
+            )?;
         }
 
         Ok(())
@@ -98,32 +119,48 @@ impl Stashing {
     }
 
     fn get_option_text(&self) -> Vec<Text> {
-        let bracket_open = Text::Raw(Cow::from("["));
-        let bracket_close = Text::Raw(Cow::from("]"));
-        let option_on =
-            Text::Styled(Cow::from("x"), self.theme.option(true));
-
-        let option_off =
-            Text::Styled(Cow::from("_"), self.theme.option(false));
-
-        vec![
-            bracket_open.clone(),
-            if self.options.stash_untracked {
-                option_on.clone()
-            } else {
-                option_off.clone()
-            },
-            bracket_close.clone(),
-            Text::Raw(Cow::from(" stash untracked\n")),
-            bracket_open,
-            if self.options.keep_index {
-                option_on.clone()
-            } else {
-                option_off.clone()
-            },
-            bracket_close,
-            Text::Raw(Cow::from(" keep index")),
+        let option = |on: bool, name: &'static str| {
+            vec![
+                Text::Raw(Cow::from("[")),
+                if on {
+                    Text::Styled(
+                        Cow::from("x"),
+                        self.theme.option(true),
+                    )
+                } else {
+                    Text::Styled(
+                        Cow::from("_"),
+                        self.theme.option(false),
+                    )
+                },
+                Text::Raw(Cow::from("] ")),
+                Text::Raw(Cow::from(name)),
+            ]
+        };
+
+        [
+            option(self.options.stash_untracked, "stash untracked\n"),
+            option(self.options.stash_ignored, "stash ignored\n"),
+            option(self.options.keep_index, "keep index"),
         ]
+        .concat()
+    }
+
+    /// remembers the toggles for the next start
+    fn save_options(&self) {
+        let options = &self.options;
+        if let Err(e) = Config::save_stash(
+            options.stash_untracked,
+            options.stash_ignored,
+            options.keep_index,
+        ) {
+            self.queue.borrow_mut().push_back(InternalEvent::Notify(
+                Notification::warning(
+                    strings::STASHING_OPTIONS_TITLE,
+                    format!("failed to remember them: {}", e),
+                ),
+            ));
+        }
     }
 }
 
@@ -143,7 +180,7 @@ impl DrawableComponent for Stashing {
         let right_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                [Constraint::Length(4), Constraint::Min(1)].as_ref(),
+                [Constraint::Length(5), Constraint::Min(1)].as_ref(),
             )
             .split(chunks[1]);
 
@@ -192,6 +229,11 @@ impl Component for Stashing {
                 self.visible,
                 self.visible || force_all,
             ));
+            out.push(CommandInfo::new(
+                commands::STASHING_TOGGLE_IGNORED,
+                self.visible,
+                self.visible || force_all,
+            ));
         }
 
         visibility_blocking(self)
@@ -204,29 +246,44 @@ impl Component for Stashing {
             }
 
             if let Event::Key(k) = ev {
-                return match k {
-                    keys::STASHING_SAVE if !self.index.is_empty() => {
-                        self.queue.borrow_mut().push_back(
-                            InternalEvent::PopupStashing(
-                                self.options,
-                            ),
-                        );
-
-                        Ok(true)
-                    }
-                    keys::STASHING_TOGGLE_INDEX => {
-                        self.options.keep_index =
-                            !self.options.keep_index;
-                        self.update()?;
-                        Ok(true)
-                    }
-                    keys::STASHING_TOGGLE_UNTRACKED => {
-                        self.options.stash_untracked =
-                            !self.options.stash_untracked;
-                        self.update()?;
-                        Ok(true)
-                    }
-                    _ => Ok(false),
+                let keys = Rc::clone(&self.key_config);
+
+                return if keys.matches(&k, KeyAction::StashingSave)
+                    && !self.index.is_empty()
+                {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::PopupStashing(
+                            self.options.clone(),
+                        ),
+                    );
+
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StashingToggleIndex)
+                {
+                    self.options.keep_index =
+                        !self.options.keep_index;
+                    self.save_options();
+                    self.update()?;
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StashingToggleUntracked)
+                {
+                    self.options.stash_untracked =
+                        !self.options.stash_untracked;
+                    self.save_options();
+                    self.update()?;
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StashingToggleIgnored)
+                {
+                    self.options.stash_ignored =
+                        !self.options.stash_ignored;
+                    self.save_options();
+                    self.update()?;
+                    Ok(true)
+                } else {
+                    Ok(false)
                 };
             }
         }
@@ -3,31 +3,45 @@ use crate::{
         visibility_blocking, CommandBlocking, CommandInfo,
         CommitList, Component, DrawableComponent,
     },
-    keys,
+    config::SharedConfig,
+    keys::{KeyAction, SharedKeyConfig},
     queue::{Action, InternalEvent, Queue},
     strings::{self, commands},
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, CommitId},
+    sync::{self, CommitId, StashApplyOutcome},
     CWD,
 };
 use crossterm::event::Event;
+use std::rc::Rc;
 
 pub struct StashList {
     list: CommitList,
     visible: bool,
     queue: Queue,
+    key_config: SharedKeyConfig,
 }
 
 impl StashList {
     ///
-    pub fn new(queue: &Queue, theme: SharedTheme) -> Self {
+    pub fn new(
+        queue: &Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: SharedConfig,
+    ) -> Self {
         Self {
             visible: false,
-            list: CommitList::new(strings::STASHLIST_TITLE, theme),
+            list: CommitList::new(
+                strings::STASHLIST_TITLE,
+                theme,
+                key_config.clone(),
+                config,
+            ),
             queue: queue.clone(),
+            key_config,
         }
     }
 
@@ -45,10 +59,27 @@ impl StashList {
         Ok(())
     }
 
-    fn apply_stash(&mut self) {
+    /// applies the selected stash, dropping it with `pop` unless
+    /// that conflicts. the status shows the conflicts
+    fn apply_stash(&mut self, pop: bool) -> Result<()> {
         if let Some(e) = self.list.selected_entry() {
-            match sync::stash_apply(CWD, e.id) {
-                Ok(_) => {
+            match sync::stash_apply(CWD, e.id, pop) {
+                Ok(outcome) => {
+                    if let StashApplyOutcome::Conflicts(paths) =
+                        outcome
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::Notify(
+                                Notification::warning(
+                                    strings::STASH_APPLY_CONFLICTS,
+                                    paths.join("\n"),
+                                ),
+                            ),
+                        );
+                    }
+
+                    // a popped stash is gone
+                    self.update()?;
                     self.queue
                         .borrow_mut()
                         .push_back(InternalEvent::TabSwitch);
@@ -63,6 +94,8 @@ impl StashList {
                 }
             }
         }
+
+        Ok(())
     }
 
     fn drop_stash(&mut self) {
@@ -73,6 +106,14 @@ impl StashList {
         }
     }
 
+    fn stash_branch(&self) {
+        if let Some(e) = self.list.selected_entry() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::StashBranch(e.id));
+        }
+    }
+
     fn inspect(&mut self) {
         if let Some(e) = self.list.selected_entry() {
             self.queue
@@ -115,6 +156,16 @@ impl Component for StashList {
                 selection_valid,
                 true,
             ));
+            out.push(CommandInfo::new(
+                commands::STASHLIST_POP,
+                selection_valid,
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::STASHLIST_BRANCH,
+                selection_valid,
+                true,
+            ));
             out.push(CommandInfo::new(
                 commands::STASHLIST_DROP,
                 selection_valid,
@@ -137,13 +188,19 @@ impl Component for StashList {
             }
 
             if let Event::Key(k) = ev {
-                match k {
-                    keys::STASH_APPLY => self.apply_stash(),
-                    keys::STASH_DROP => self.drop_stash(),
-                    keys::STASH_OPEN => self.inspect(),
-
-                    _ => (),
-                };
+                let keys = Rc::clone(&self.key_config);
+
+                if keys.matches(&k, KeyAction::StashApply) {
+                    self.apply_stash(false)?;
+                } else if keys.matches(&k, KeyAction::StashPop) {
+                    self.apply_stash(true)?;
+                } else if keys.matches(&k, KeyAction::StashBranch) {
+                    self.stash_branch();
+                } else if keys.matches(&k, KeyAction::StashDrop) {
+                    self.drop_stash();
+                } else if keys.matches(&k, KeyAction::StashOpen) {
+                    self.inspect();
+                }
             }
         }
 
@@ -4,21 +4,32 @@ use crate::{
         command_pump, event_pump, visibility_blocking,
         ChangesComponent, CommandBlocking, CommandInfo, Component,
         DiffComponent, DrawableComponent, FileTreeItemKind,
+        HorizontalSplit, SharedDiffOptions,
     },
-    keys,
-    queue::{InternalEvent, Queue, ResetItem},
+    config::{Config, SharedConfig},
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings::{self, commands, order},
-    ui::style::SharedTheme,
+    ui::{notifications::Notification, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, status::StatusType},
+    sync::{
+        self,
+        status::{RenameDetection, ShowUntrackedFiles, StatusType},
+        BranchCompare, OperationAction, OperationState,
+    },
     AsyncDiff, AsyncNotification, AsyncStatus, DiffParams, DiffType,
-    StatusParams, CWD,
+    StatusItem, StatusParams, CWD,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
-use tui::layout::{Constraint, Direction, Layout};
+use crossterm::event::{Event, KeyEvent};
+use std::{borrow::Cow, rc::Rc};
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    style::Modifier,
+    widgets::{Paragraph, Text},
+};
 
 ///
 #[derive(PartialEq)]
@@ -42,11 +53,21 @@ pub struct Status {
     index: ChangesComponent,
     index_wd: ChangesComponent,
     diff: DiffComponent,
+    split: HorizontalSplit,
     git_diff: AsyncDiff,
     git_status_workdir: AsyncStatus,
     git_status_stage: AsyncStatus,
     queue: Queue,
     git_action_executed: bool,
+    /// `None` while unknown, e.g. in an empty repo
+    upstream_summary: Option<String>,
+    /// a merge, rebase, .. that stopped half way
+    operation: Option<OperationState>,
+    /// starts as `status.showUntrackedFiles`, kept for the session
+    show_untracked: ShowUntrackedFiles,
+    renames: RenameDetection,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
 }
 
 impl DrawableComponent for Status {
@@ -55,23 +76,32 @@ impl DrawableComponent for Status {
         f: &mut tui::Frame<B>,
         rect: tui::layout::Rect,
     ) -> Result<()> {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(
-                if self.focus == Focus::Diff {
-                    [
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(70),
-                    ]
-                } else {
-                    [
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
-                    ]
-                }
-                .as_ref(),
-            )
-            .split(rect);
+        let rect = self.operation.map_or(rect, |state| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Length(1), Constraint::Min(0)]
+                        .as_ref(),
+                )
+                .split(rect);
+
+            f.render_widget(
+                Paragraph::new(
+                    [Text::Raw(Cow::from(Self::operation_banner(
+                        &state,
+                    )))]
+                    .iter(),
+                )
+                .style(
+                    self.theme.text_danger().modifier(Modifier::BOLD),
+                ),
+                chunks[0],
+            );
+
+            chunks[1]
+        });
+
+        let (left, right) = self.split.split(rect);
 
         let left_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -89,11 +119,11 @@ impl DrawableComponent for Status {
                 }
                 .as_ref(),
             )
-            .split(chunks[0]);
+            .split(left);
 
         self.index_wd.draw(f, left_chunks[0])?;
         self.index.draw(f, left_chunks[1])?;
-        self.diff.draw(f, chunks[1])?;
+        self.diff.draw(f, right)?;
 
         Ok(())
     }
@@ -102,13 +132,41 @@ impl DrawableComponent for Status {
 impl Status {
     accessors!(self, [index, index_wd, diff]);
 
+    /// the diff, focused or not
+    pub const fn diff(&self) -> &DiffComponent {
+        &self.diff
+    }
+
+    /// the diff if it has the focus
+    pub fn focused_diff(&self) -> Option<&DiffComponent> {
+        if self.focus == Focus::Diff {
+            Some(&self.diff)
+        } else {
+            None
+        }
+    }
+
+    ///
+    pub fn focused_diff_mut(&mut self) -> Option<&mut DiffComponent> {
+        if self.focus == Focus::Diff {
+            Some(&mut self.diff)
+        } else {
+            None
+        }
+    }
+
     ///
     pub fn new(
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
+        diff_options: &SharedDiffOptions,
         theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        config: &SharedConfig,
     ) -> Self {
-        Self {
+        let debounce = config.status.refresh_debounce();
+
+        let mut status = Self {
             queue: queue.clone(),
             visible: true,
             focus: Focus::WorkDir,
@@ -119,6 +177,7 @@ impl Status {
                 true,
                 queue.clone(),
                 theme.clone(),
+                key_config.clone(),
             ),
             index: ChangesComponent::new(
                 strings::TITLE_INDEX,
@@ -126,13 +185,53 @@ impl Status {
                 false,
                 queue.clone(),
                 theme.clone(),
+                key_config.clone(),
+            ),
+            diff: DiffComponent::new(
+                queue,
+                false,
+                diff_options.clone(),
+                theme.clone(),
+                key_config.clone(),
+                config.clone(),
             ),
-            diff: DiffComponent::new(Some(queue.clone()), theme),
+            split: HorizontalSplit::new(config.ui.status_split_ratio),
             git_diff: AsyncDiff::new(sender.clone()),
-            git_status_workdir: AsyncStatus::new(sender.clone()),
-            git_status_stage: AsyncStatus::new(sender.clone()),
+            git_status_workdir: AsyncStatus::new(sender.clone())
+                .debounce(debounce),
+            git_status_stage: AsyncStatus::new(sender.clone())
+                .debounce(debounce),
             git_action_executed: false,
+            upstream_summary: None,
+            operation: None,
+            show_untracked: ShowUntrackedFiles::from_config(CWD)
+                .unwrap_or_default(),
+            renames: config.status.renames(),
+            theme,
+            key_config,
+        };
+
+        if let Err(e) = status.set_flat(config.status.flat_file_list)
+        {
+            log::error!("flat file list: {}", e);
         }
+        if let Err(e) =
+            status.set_sort_by_size(config.status.sort_by_size)
+        {
+            log::error!("sort by size: {}", e);
+        }
+
+        status
+    }
+
+    fn set_flat(&mut self, flat: bool) -> Result<()> {
+        self.index_wd.set_flat(flat)?;
+        self.index.set_flat(flat)
+    }
+
+    fn set_sort_by_size(&mut self, by_size: bool) -> Result<()> {
+        self.index_wd.set_sort_by_size(by_size)?;
+        self.index.set_sort_by_size(by_size)
     }
 
     fn can_focus_diff(&self) -> bool {
@@ -181,6 +280,11 @@ impl Status {
     }
 
     pub fn selected_path(&self) -> Option<(String, bool)> {
+        self.selected_item()
+            .map(|(item, is_stage)| (item.path, is_stage))
+    }
+
+    fn selected_item(&self) -> Option<(StatusItem, bool)> {
         let (idx, is_stage) = match self.diff_target {
             DiffTarget::Stage => (&self.index, true),
             DiffTarget::WorkingDir => (&self.index_wd, false),
@@ -188,7 +292,7 @@ impl Status {
 
         if let Some(item) = idx.selection() {
             if let FileTreeItemKind::File(i) = item.kind {
-                return Some((i.path, is_stage));
+                return Some((i, is_stage));
             }
         }
         None
@@ -198,24 +302,186 @@ impl Status {
     pub fn update(&mut self) -> Result<()> {
         if self.is_visible() {
             self.git_diff.refresh()?;
-            self.git_status_workdir.fetch(StatusParams::new(
-                StatusType::WorkingDir,
-                true,
-            ))?;
-            self.git_status_stage
-                .fetch(StatusParams::new(StatusType::Stage, true))?;
+            self.git_status_workdir.fetch(
+                StatusParams::new(
+                    StatusType::WorkingDir,
+                    self.show_untracked,
+                )
+                .renames(self.renames)
+                .with_stats(),
+            )?;
+            self.git_status_stage.fetch(
+                StatusParams::new(
+                    StatusType::Stage,
+                    self.show_untracked,
+                )
+                .renames(self.renames)
+                .with_stats(),
+            )?;
 
             self.index_wd.update()?;
+
+            self.upstream_summary =
+                sync::branch_compare_upstream(CWD)
+                    .ok()
+                    .map(Self::format_upstream);
+
+            self.operation =
+                sync::operation_state(CWD).ok().flatten();
         }
 
         Ok(())
     }
 
+    /// `↑ahead ↓behind` of the current branch or `no upstream`
+    pub fn upstream_summary(&self) -> Option<&str> {
+        self.upstream_summary.as_deref()
+    }
+
+    /// like `REBASING (step 3/7)`
+    fn operation_banner(state: &OperationState) -> String {
+        state.progress.map_or_else(
+            || String::from(state.operation.name()),
+            |(step, total)| {
+                format!(
+                    "{} (step {}/{})",
+                    state.operation.name(),
+                    step,
+                    total
+                )
+            },
+        )
+    }
+
+    /// whether the operation in progress offers `action`
+    fn can_operation(&self, action: OperationAction) -> bool {
+        self.operation
+            .map_or(false, |state| state.operation.supports(action))
+    }
+
+    /// the action of the operation in progress `key` is bound to
+    fn operation_key(
+        &self,
+        key: &KeyEvent,
+    ) -> Option<OperationAction> {
+        [
+            (KeyAction::StatusContinue, OperationAction::Continue),
+            (KeyAction::StatusSkip, OperationAction::Skip),
+            (KeyAction::StatusAbort, OperationAction::Abort),
+        ]
+        .iter()
+        .find(|(bound, action)| {
+            self.key_config.matches(key, *bound)
+                && self.can_operation(*action)
+        })
+        .map(|(_, action)| *action)
+    }
+
+    fn operation_action(&self, action: OperationAction) -> bool {
+        if let Some(state) = self.operation {
+            if let Err(e) =
+                sync::operation_action(CWD, state.operation, action)
+            {
+                log::error!(
+                    "{} error: {}",
+                    state.operation.name(),
+                    e
+                );
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Notify(Notification::error(
+                        strings::OPERATION_FAILED,
+                        e.to_string(),
+                    )),
+                );
+            }
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        true
+    }
+
+    fn toggle_untracked(&mut self) -> Result<bool> {
+        self.show_untracked = self.show_untracked.next();
+
+        self.queue.borrow_mut().push_back(InternalEvent::Notify(
+            Notification::success(format!(
+                "{} {}",
+                strings::UNTRACKED_FILES_SHOWN,
+                self.show_untracked.name()
+            )),
+        ));
+
+        self.update()?;
+
+        Ok(true)
+    }
+
+    fn toggle_flat(&mut self) -> Result<bool> {
+        let flat = !self.index_wd.is_flat();
+        self.set_flat(flat)?;
+
+        let msg = if flat {
+            strings::FILES_FLAT
+        } else {
+            strings::FILES_TREE
+        };
+        self.queue.borrow_mut().push_back(InternalEvent::Notify(
+            match Config::save_flat_file_list(flat) {
+                Ok(()) => Notification::success(msg),
+                Err(e) => Notification::warning(
+                    msg,
+                    format!("failed to remember it: {}", e),
+                ),
+            },
+        ));
+
+        Ok(true)
+    }
+
+    fn toggle_sort_by_size(&mut self) -> Result<bool> {
+        let by_size = !self.index_wd.is_sorted_by_size();
+        self.set_sort_by_size(by_size)?;
+
+        let msg = if by_size {
+            strings::FILES_BY_SIZE
+        } else {
+            strings::FILES_BY_PATH
+        };
+        self.queue.borrow_mut().push_back(InternalEvent::Notify(
+            match Config::save_sort_by_size(by_size) {
+                Ok(()) => Notification::success(msg),
+                Err(e) => Notification::warning(
+                    msg,
+                    format!("failed to remember it: {}", e),
+                ),
+            },
+        ));
+
+        Ok(true)
+    }
+
+    fn format_upstream(compare: Option<BranchCompare>) -> String {
+        compare.map_or_else(
+            || String::from(strings::NO_UPSTREAM),
+            |compare| {
+                format!(
+                    "\u{2191}{} \u{2193}{}",
+                    compare.ahead, compare.behind
+                )
+            },
+        )
+    }
+
     ///
     pub fn anything_pending(&self) -> bool {
         self.git_diff.is_pending()
             || self.git_status_stage.is_pending()
             || self.git_status_workdir.is_pending()
+            || self.index_wd.is_batching()
+            || self.index.is_batching()
     }
 
     ///
@@ -234,10 +500,12 @@ impl Status {
 
     fn update_status(&mut self) -> Result<()> {
         let stage_status = self.git_status_stage.last()?;
-        self.index.set_items(&stage_status.items)?;
+        self.index
+            .set_items(&stage_status.items, stage_status.stats)?;
 
         let workdir_status = self.git_status_workdir.last()?;
-        self.index_wd.set_items(&workdir_status.items)?;
+        self.index_wd
+            .set_items(&workdir_status.items, workdir_status.stats)?;
 
         self.update_diff()?;
 
@@ -261,7 +529,8 @@ impl Status {
 
     ///
     pub fn update_diff(&mut self) -> Result<()> {
-        if let Some((path, is_stage)) = self.selected_path() {
+        if let Some((item, is_stage)) = self.selected_item() {
+            let path = item.path;
             let diff_type = if is_stage {
                 DiffType::Stage
             } else {
@@ -270,7 +539,9 @@ impl Status {
 
             let diff_params = DiffParams {
                 path: path.clone(),
+                old_path: item.old_path,
                 diff_type,
+                options: self.diff.options(),
             };
 
             if self.diff.current() == (path.clone(), is_stage) {
@@ -300,18 +571,18 @@ impl Status {
 
     /// called after confirmation
     pub fn reset(&mut self, item: &ResetItem) -> bool {
-        if let Err(e) = sync::reset_workdir(CWD, item.path.as_str()) {
-            self.queue.borrow_mut().push_back(
-                InternalEvent::ShowErrorMsg(format!(
-                    "reset failed:\n{}",
-                    e
-                )),
-            );
+        self.index_wd.reset(item)
+    }
 
-            false
-        } else {
-            true
-        }
+    /// resets the marked files, a step at a time for many
+    pub fn reset_marked(&mut self, items: Vec<ResetItem>) {
+        self.index_wd.reset_marked(items);
+    }
+
+    /// works off the next step of marked files being staged,
+    /// unstaged or reset, returns whether there was one
+    pub fn step_batch(&mut self) -> bool {
+        self.index_wd.step_batch() | self.index.step_batch()
     }
 }
 
@@ -352,6 +623,48 @@ impl Component for Status {
             ));
         }
 
+        out.push(CommandInfo::new(
+            commands::PREVIEW_STAGED,
+            !self.index.is_empty(),
+            self.visible || force_all,
+        ));
+
+        for cmd in &[
+            commands::TOGGLE_UNTRACKED,
+            commands::TOGGLE_FLAT,
+            commands::TOGGLE_SORT_BY_SIZE,
+            commands::APPLY_PATCH,
+            commands::HIDDEN_FILES,
+        ] {
+            out.push(CommandInfo::new(
+                *cmd,
+                true,
+                self.visible || force_all,
+            ));
+        }
+
+        for (cmd, action) in &[
+            (commands::OPERATION_CONTINUE, OperationAction::Continue),
+            (commands::OPERATION_SKIP, OperationAction::Skip),
+            (commands::OPERATION_ABORT, OperationAction::Abort),
+        ] {
+            out.push(CommandInfo::new(
+                *cmd,
+                true,
+                (self.visible && self.can_operation(*action))
+                    || force_all,
+            ));
+        }
+
+        out.push(
+            CommandInfo::new(
+                commands::RESIZE_SPLIT,
+                true,
+                self.visible || force_all,
+            )
+            .hidden(),
+        );
+
         out.push(
             CommandInfo::new(
                 commands::SELECT_STATUS,
@@ -387,56 +700,102 @@ impl Component for Status {
 
     fn event(&mut self, ev: crossterm::event::Event) -> Result<bool> {
         if self.visible {
+            if let Event::Mouse(m) = ev {
+                if self.split.mouse_event(m) {
+                    return Ok(true);
+                }
+            }
+
             if event_pump(ev, self.components_mut().as_mut_slice())? {
                 self.git_action_executed = true;
                 return Ok(true);
             }
 
             if let Event::Key(k) = ev {
-                return match k {
-                    keys::FOCUS_WORKDIR => {
-                        self.switch_focus(Focus::WorkDir)
-                    }
-                    keys::FOCUS_STAGE => {
-                        self.switch_focus(Focus::Stage)
-                    }
-                    keys::EDIT_FILE
-                        if self.can_focus_diff()
-                            || self.focus == Focus::Diff =>
-                    {
-                        if let Some((path, _)) = self.selected_path()
-                        {
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::OpenExternalEditor(
-                                    Some(path),
-                                ),
-                            );
-                        }
-                        Ok(true)
-                    }
-                    keys::FOCUS_RIGHT if self.can_focus_diff() => {
-                        self.switch_focus(Focus::Diff)
-                    }
-                    keys::FOCUS_LEFT => {
-                        self.switch_focus(match self.diff_target {
-                            DiffTarget::Stage => Focus::Stage,
-                            DiffTarget::WorkingDir => Focus::WorkDir,
-                        })
-                    }
-                    keys::MOVE_DOWN
-                        if self.focus == Focus::WorkDir
-                            && !self.index.is_empty() =>
-                    {
-                        self.switch_focus(Focus::Stage)
-                    }
-
-                    keys::MOVE_UP
-                        if self.focus == Focus::Stage
-                            && !self.index_wd.is_empty() =>
-                    {
-                        self.switch_focus(Focus::WorkDir)
+                let keys = Rc::clone(&self.key_config);
+
+                return if keys.matches(&k, KeyAction::SplitShrink) {
+                    Ok(self.split.resize(-1))
+                } else if keys.matches(&k, KeyAction::SplitGrow) {
+                    Ok(self.split.resize(1))
+                } else if keys.matches(&k, KeyAction::FocusWorkdir) {
+                    self.switch_focus(Focus::WorkDir)
+                } else if keys.matches(&k, KeyAction::FocusStage) {
+                    self.switch_focus(Focus::Stage)
+                } else if keys.matches(&k, KeyAction::EditFile)
+                    && (self.can_focus_diff()
+                        || self.focus == Focus::Diff)
+                {
+                    if let Some((path, _)) = self.selected_path() {
+                        let line = if self.focus == Focus::Diff {
+                            self.diff.selected_line_number()
+                        } else {
+                            None
+                        };
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::OpenExternalEditor(Some(
+                                (path, line),
+                            )),
+                        );
                     }
-                    _ => Ok(false),
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StatusPreviewStaged)
+                    && !self.index.is_empty()
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::PreviewStaged);
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StatusToggleUntracked)
+                {
+                    self.toggle_untracked()
+                } else if keys
+                    .matches(&k, KeyAction::StatusToggleFlat)
+                {
+                    self.toggle_flat()
+                } else if keys
+                    .matches(&k, KeyAction::StatusSortBySize)
+                {
+                    self.toggle_sort_by_size()
+                } else if keys
+                    .matches(&k, KeyAction::StatusApplyPatch)
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::ApplyPatches);
+                    Ok(true)
+                } else if keys
+                    .matches(&k, KeyAction::StatusHiddenFiles)
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenHiddenFiles);
+                    Ok(true)
+                } else if let Some(action) = self.operation_key(&k) {
+                    Ok(self.operation_action(action))
+                } else if keys.matches(&k, KeyAction::FocusRight)
+                    && self.can_focus_diff()
+                {
+                    self.switch_focus(Focus::Diff)
+                } else if keys.matches(&k, KeyAction::FocusLeft) {
+                    self.switch_focus(match self.diff_target {
+                        DiffTarget::Stage => Focus::Stage,
+                        DiffTarget::WorkingDir => Focus::WorkDir,
+                    })
+                } else if keys.matches(&k, KeyAction::MoveDown)
+                    && self.focus == Focus::WorkDir
+                    && !self.index.is_empty()
+                {
+                    self.switch_focus(Focus::Stage)
+                } else if keys.matches(&k, KeyAction::MoveUp)
+                    && self.focus == Focus::Stage
+                    && !self.index_wd.is_empty()
+                {
+                    self.switch_focus(Focus::WorkDir)
+                } else {
+                    Ok(false)
                 };
             }
         }
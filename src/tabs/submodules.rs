@@ -0,0 +1,364 @@
+use crate::{
+    components::{
+        mouse_scroll, visibility_blocking, CommandBlocking,
+        CommandInfo, Component, DrawableComponent, ScrollType,
+    },
+    keys::{KeyAction, SharedKeyConfig},
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings::{self, commands},
+    ui::{self, notifications::Notification, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    sync::{self, SubmoduleInfo, SubmoduleStatus},
+    AsyncNotification, AsyncSubmoduleUpdate, CWD,
+};
+use crossbeam_channel::Sender;
+use crossterm::{
+    event::{EnableMouseCapture, Event, MouseEvent},
+    terminal::{
+        enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    },
+    ExecutableCommand,
+};
+use scopeguard::defer;
+use std::{
+    borrow::Cow, cell::Cell, env, io, process::Command, rc::Rc,
+};
+use tui::{backend::Backend, layout::Rect, widgets::Text, Frame};
+
+///
+pub struct Submodules {
+    items: Vec<SubmoduleInfo>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_area: Cell<Rect>,
+    visible: bool,
+    git_update: AsyncSubmoduleUpdate,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl Submodules {
+    ///
+    pub fn new(
+        queue: &Queue,
+        sender: &Sender<AsyncNotification>,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            items: Vec::new(),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_area: Cell::new(Rect::default()),
+            visible: false,
+            git_update: AsyncSubmoduleUpdate::new(sender),
+            queue: queue.clone(),
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn update(&mut self) -> Result<()> {
+        if self.visible {
+            self.items = sync::get_submodules(CWD)?;
+            self.selection = self
+                .selection
+                .min(self.items.len().saturating_sub(1));
+        }
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_update.is_pending()
+    }
+
+    /// reports the finished submodule update
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if ev != AsyncNotification::SubmoduleUpdate {
+            return Ok(());
+        }
+
+        if let Some((name, err)) = self.git_update.take_last()? {
+            self.notify_done("update", &name, err);
+        }
+
+        Ok(())
+    }
+
+    /// runs a nested gitui instance inside the submodule at `path`
+    /// and returns once it was closed
+    pub fn open_nested(path: &str) -> Result<()> {
+        io::stdout().execute(LeaveAlternateScreen)?;
+        defer! {
+            io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
+            // the nested instance disables raw mode and mouse
+            // capture when quitting
+            enable_raw_mode().expect("reset terminal");
+            io::stdout().execute(EnableMouseCapture).expect("reset terminal");
+        }
+
+        Command::new(env::current_exe()?)
+            .arg("--directory")
+            .arg(path)
+            .status()?;
+
+        Ok(())
+    }
+
+    fn selected(&self) -> Option<&SubmoduleInfo> {
+        self.items.get(self.selection)
+    }
+
+    /// moves the selection if the mouse wheel turned above the list
+    fn handle_mouse_event(&mut self, ev: MouseEvent) -> bool {
+        let mut changed = false;
+
+        if let Some(scroll) =
+            mouse_scroll(ev, self.current_area.get())
+        {
+            for _ in 0..self.key_config.mouse_scroll_lines() {
+                changed |= self.move_selection(scroll);
+            }
+        }
+
+        changed
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) -> bool {
+        let max = self.items.len().saturating_sub(1);
+        let old = self.selection;
+
+        self.selection = match scroll {
+            ScrollType::Up => old.saturating_sub(1),
+            ScrollType::Down => old.saturating_add(1).min(max),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+            _ => old,
+        };
+
+        old != self.selection
+    }
+
+    fn run_on_selected<F, E>(&mut self, action: F, what: &str)
+    where
+        F: Fn(&str, &str) -> std::result::Result<(), E>,
+        E: std::fmt::Display,
+    {
+        if let Some(name) = self.selected().map(|s| s.name.clone()) {
+            let err = action(CWD, &name).err().map(|e| e.to_string());
+            self.notify_done(what, &name, err);
+        }
+    }
+
+    fn notify_done(
+        &self,
+        what: &str,
+        name: &str,
+        err: Option<String>,
+    ) {
+        let notification = match err {
+            Some(e) => Notification::error(
+                format!("submodule {} failed", what),
+                e,
+            ),
+            None => Notification::success(format!(
+                "submodule {}: {}",
+                what, name
+            )),
+        };
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Notify(notification));
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn open(&mut self) {
+        if let Some(name) = self.selected().map(|s| s.name.clone()) {
+            let event = match sync::submodule_path(CWD, &name) {
+                Ok(path) => InternalEvent::OpenSubmodule(path),
+                Err(e) => InternalEvent::ShowErrorMsg(format!(
+                    "open submodule error:\n{}",
+                    e
+                )),
+            };
+
+            self.queue.borrow_mut().push_back(event);
+        }
+    }
+
+    const fn status_text(status: SubmoduleStatus) -> &'static str {
+        match status {
+            SubmoduleStatus::Uninitialized => "uninitialized",
+            SubmoduleStatus::OutOfDate => "out-of-date",
+            SubmoduleStatus::Modified => "modified",
+            SubmoduleStatus::UpToDate => "up-to-date",
+        }
+    }
+
+    fn item_to_text<'a>(
+        item: &'a SubmoduleInfo,
+        selected: bool,
+        theme: &SharedTheme,
+    ) -> Text<'a> {
+        let id = item
+            .id
+            .map(|id| {
+                id.to_string().chars().take(7).collect::<String>()
+            })
+            .unwrap_or_default();
+
+        Text::Styled(
+            Cow::from(format!(
+                "{:<13} {:<7} {} ({})\n",
+                Self::status_text(item.status),
+                id,
+                item.path,
+                item.url.as_deref().unwrap_or_default(),
+            )),
+            theme.submodule(item.status, selected),
+        )
+    }
+}
+
+impl DrawableComponent for Submodules {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        let height = rect.height.saturating_sub(2) as usize;
+
+        self.current_area.set(rect);
+        self.scroll_top.set(ui::calc_scroll_top(
+            self.scroll_top.get(),
+            height,
+            self.selection,
+        ));
+
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                Self::item_to_text(
+                    item,
+                    idx == self.selection,
+                    &self.theme,
+                )
+            })
+            .skip(self.scroll_top.get());
+
+        ui::draw_list(
+            f,
+            rect,
+            strings::SUBMODULES_TITLE,
+            items,
+            None,
+            true,
+            &self.theme,
+        );
+
+        Ok(())
+    }
+}
+
+impl Component for Submodules {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            let selected = self.selected();
+            let initialized = selected.map_or(false, |s| {
+                s.status != SubmoduleStatus::Uninitialized
+            });
+
+            out.push(CommandInfo::new(
+                commands::SUBMODULE_INIT,
+                selected.is_some(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::SUBMODULE_UPDATE,
+                selected.is_some() && !self.any_work_pending(),
+                true,
+            ));
+            out.push(CommandInfo::new(
+                commands::SUBMODULE_OPEN,
+                initialized,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<bool> {
+        if self.visible {
+            if let Event::Key(k) = ev {
+                let keys = Rc::clone(&self.key_config);
+
+                return if keys.matches(&k, KeyAction::MoveUp) {
+                    Ok(self.move_selection(ScrollType::Up))
+                } else if keys.matches(&k, KeyAction::MoveDown) {
+                    Ok(self.move_selection(ScrollType::Down))
+                } else if keys.matches(&k, KeyAction::Home) {
+                    Ok(self.move_selection(ScrollType::Home))
+                } else if keys.matches(&k, KeyAction::End) {
+                    Ok(self.move_selection(ScrollType::End))
+                } else if keys.matches(&k, KeyAction::SubmoduleInit) {
+                    self.run_on_selected(
+                        sync::submodule_init,
+                        "init",
+                    );
+                    Ok(true)
+                } else if keys.matches(&k, KeyAction::SubmoduleUpdate)
+                {
+                    if let Some(name) =
+                        self.selected().map(|s| s.name.clone())
+                    {
+                        self.git_update.request(name)?;
+                    }
+                    Ok(true)
+                } else if keys.matches(&k, KeyAction::SubmoduleOpen) {
+                    self.open();
+                    Ok(true)
+                } else {
+                    Ok(false)
+                };
+            } else if let Event::Mouse(m) = ev {
+                return Ok(self.handle_mouse_event(m));
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+        self.update()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    tty::IsTty,
+};
+use std::{
+    env,
+    io::{self, BufRead, Write},
+};
+
+/// `true` for a light terminal background, `None` if it can not be
+/// told. asks the terminal (osc 11) and falls back to `COLORFGBG`.
+/// needs raw mode and has to run before anything else reads input,
+/// the answer arrives as input. returns the keys typed meanwhile too,
+/// to be handled like any other input
+pub fn is_light() -> (Option<bool>, Vec<Event>) {
+    let (response, typed) = query_background();
+
+    let light = response
+        .and_then(|response| parse_osc11(&response))
+        .map(|(r, g, b)| {
+            // perceived brightness
+            b.mul_add(0.114, g.mul_add(0.587, 0.299 * r)) > 0.5
+        })
+        .or_else(|| {
+            env::var("COLORFGBG")
+                .ok()
+                .and_then(|value| colorfgbg_is_light(&value))
+        });
+
+    (light, typed)
+}
+
+/// the answer to `OSC 11 ; ?` without the leading `ESC ] 11 ;`, like
+/// `rgb:ffff/ffff/ffff`, and the keys typed before it came.
+///
+/// the query is followed by a device attributes request (DA1), which
+/// every terminal answers, in order. so reading stops at that answer
+/// instead of waiting for one to osc 11 that might never come
+fn query_background() -> (Option<String>, Vec<Event>) {
+    // the console api of windows does not pass the answer on
+    if cfg!(windows) || !io::stdin().is_tty() {
+        return (None, Vec::new());
+    }
+
+    let mut stdout = io::stdout();
+    if stdout
+        .write_all(b"\x1b]11;?\x07\x1b[c")
+        .and_then(|()| stdout.flush())
+        .is_err()
+    {
+        return (None, Vec::new());
+    }
+
+    let input = read_replies();
+
+    let replies = split_replies(&input);
+    (replies.background, typed_keys(&replies.typed))
+}
+
+/// stdin up to the DA1 answer, or its end
+fn read_replies() -> Vec<u8> {
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+    let mut input = Vec::new();
+
+    // all of it, nothing may stay behind in the buffer of stdin
+    while let Ok(chunk) = stdin.fill_buf() {
+        if chunk.is_empty() {
+            break;
+        }
+
+        let len = chunk.len();
+        input.extend_from_slice(chunk);
+        stdin.consume(len);
+
+        if split_replies(&input).done {
+            break;
+        }
+    }
+
+    input
+}
+
+/// what the terminal sent while answering the queries
+#[derive(Default, Debug, PartialEq)]
+struct Replies {
+    /// the osc 11 answer without `ESC ] 11 ;`
+    background: Option<String>,
+    /// the DA1 answer arrived, nothing else is coming
+    done: bool,
+    /// everything that is no answer, typed by the user
+    typed: Vec<u8>,
+}
+
+/// picks `ESC ] 11 ; ... BEL` (or `ESC \\` for the end) and
+/// `ESC [ ? ... c` out of `input`, an incomplete answer at the end is
+/// left out until the rest arrives
+fn split_replies(input: &[u8]) -> Replies {
+    let mut replies = Replies::default();
+    let mut rest = input;
+
+    while let Some(&first) = rest.first() {
+        if let Some(answer) = rest.strip_prefix(b"\x1b]11;") {
+            let end = match answer
+                .iter()
+                .position(|b| *b == b'\x07' || *b == b'\x1b')
+            {
+                Some(end) => end,
+                None => break,
+            };
+            let terminator =
+                if answer[end] == b'\x07' { 1 } else { 2 };
+            if answer.len() < end + terminator {
+                break;
+            }
+
+            replies.background =
+                Some(String::from_utf8_lossy(&answer[..end]).into());
+            rest = &answer[end + terminator..];
+        } else if let Some(answer) = rest.strip_prefix(b"\x1b[?") {
+            match answer
+                .iter()
+                .position(|b| !b.is_ascii_digit() && *b != b';')
+            {
+                Some(end) if answer[end] == b'c' => {
+                    replies.done = true;
+                    rest = &answer[end + 1..];
+                }
+                Some(_) => {
+                    replies.typed.push(first);
+                    rest = &rest[1..];
+                }
+                None => break,
+            }
+        } else {
+            replies.typed.push(first);
+            rest = &rest[1..];
+        }
+    }
+
+    replies
+}
+
+/// plain keys, `ctrl` with a letter and enter, tab, backspace and esc.
+/// escape sequences (arrows and the like) are dropped
+fn typed_keys(typed: &[u8]) -> Vec<Event> {
+    let typed = String::from_utf8_lossy(typed);
+    let mut chars = typed.chars().peekable();
+    let mut keys = Vec::new();
+
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                // up to the final byte of the csi sequence
+                chars.find(|c| ('@'..='~').contains(c) && *c != '[');
+                continue;
+            }
+            '\x1b' if chars.peek() == Some(&'O') => {
+                chars.nth(1);
+                continue;
+            }
+            '\r' => KeyCode::Enter.into(),
+            '\t' => KeyCode::Tab.into(),
+            '\x7f' => KeyCode::Backspace.into(),
+            '\x1b' => KeyCode::Esc.into(),
+            '\x01'..='\x1a' => KeyEvent::new(
+                KeyCode::Char((b'a' + (c as u8 - 1)) as char),
+                KeyModifiers::CONTROL,
+            ),
+            c if c.is_control() => continue,
+            c if c.is_uppercase() => {
+                KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT)
+            }
+            c => KeyCode::Char(c).into(),
+        };
+
+        keys.push(Event::Key(key));
+    }
+
+    keys
+}
+
+/// `rgb:rrrr/gggg/bbbb` (1 to 4 hex digits each) as fractions
+fn parse_osc11(response: &str) -> Option<(f32, f32, f32)> {
+    let channel = |hex: &str| -> Option<f32> {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let max = u16::MAX >> (16 - 4 * hex.len());
+        let value = u16::from_str_radix(hex, 16).ok()?;
+        Some(f32::from(value) / f32::from(max))
+    };
+
+    let mut channels =
+        response.strip_prefix("rgb:")?.split('/').map(channel);
+
+    match (channels.next(), channels.next(), channels.next()) {
+        (Some(r), Some(g), Some(b)) => Some((r?, g?, b?)),
+        _ => None,
+    }
+}
+
+/// `COLORFGBG` is `fg;bg` (sometimes `fg;default;bg`) in terms of
+/// the 16 ansi colors, only 7 (light gray) and 9-15 are light
+fn colorfgbg_is_light(value: &str) -> Option<bool> {
+    let bg = value.rsplit(';').next()?.parse::<u8>().ok()?;
+
+    Some(bg == 7 || (9..=15).contains(&bg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_osc11() {
+        assert_eq!(
+            parse_osc11("rgb:ffff/ffff/ffff"),
+            Some((1.0, 1.0, 1.0))
+        );
+        assert_eq!(
+            parse_osc11("rgb:00/80/ff").map(|c| c.0),
+            Some(0.0)
+        );
+        assert!(parse_osc11("rgb:ffff/ffff").is_none());
+        assert!(parse_osc11("rgba:ffff/ffff/ffff/ffff").is_none());
+        assert!(parse_osc11("rgb:fffff/ffff/ffff").is_none());
+    }
+
+    #[test]
+    fn test_split_replies() {
+        let replies = split_replies(
+            b"a\x1b]11;rgb:ffff/ffff/ffff\x07b\x1b[?62;22cc",
+        );
+        assert_eq!(
+            replies,
+            Replies {
+                background: Some(String::from("rgb:ffff/ffff/ffff")),
+                done: true,
+                typed: b"abc".to_vec(),
+            }
+        );
+
+        // no osc 11 support, the DA1 answer ends it anyway
+        let replies = split_replies(b"\x1b[?1;2c");
+        assert_eq!(replies.background, None);
+        assert!(replies.done);
+
+        // ST as end, the DA1 answer still missing
+        let replies =
+            split_replies(b"\x1b]11;rgb:00/00/00\x1b\\\x1b[?6");
+        assert_eq!(
+            replies.background.as_deref(),
+            Some("rgb:00/00/00")
+        );
+        assert!(!replies.done);
+        assert!(replies.typed.is_empty());
+    }
+
+    #[test]
+    fn test_typed_keys() {
+        assert_eq!(
+            typed_keys(b"aB\r\x13\x1b[A\x1bOP\x1b"),
+            vec![
+                Event::Key(KeyCode::Char('a').into()),
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('B'),
+                    KeyModifiers::SHIFT
+                )),
+                Event::Key(KeyCode::Enter.into()),
+                Event::Key(KeyEvent::new(
+                    KeyCode::Char('s'),
+                    KeyModifiers::CONTROL
+                )),
+                Event::Key(KeyCode::Esc.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_colorfgbg() {
+        assert_eq!(colorfgbg_is_light("15;0"), Some(false));
+        assert_eq!(colorfgbg_is_light("0;15"), Some(true));
+        assert_eq!(colorfgbg_is_light("0;default;7"), Some(true));
+        assert_eq!(colorfgbg_is_light("15;8"), Some(false));
+        assert_eq!(colorfgbg_is_light("default"), None);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::ImagePreview;
+use asyncgit::sync::ImageFormat;
+use std::{env, fmt::Write};
+use tui::layout::Rect;
+
+/// base64 bytes per kitty escape, the protocol allows 4096
+const KITTY_CHUNK: usize = 4096;
+
+/// how the terminal can show pictures drawn from the encoded file,
+/// sixel would need the pixels decoded first
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// kitty graphics protocol, png only
+    Kitty,
+    /// inline images of `iTerm2` (and `WezTerm`)
+    Iterm,
+}
+
+impl GraphicsProtocol {
+    /// the protocol `setting` asks for or, for `auto`, the one the
+    /// environment tells the terminal speaks
+    pub fn detect(setting: ImagePreview) -> Option<Self> {
+        let var = |name| env::var(name).unwrap_or_default();
+
+        match setting {
+            ImagePreview::Off => None,
+            ImagePreview::Kitty => Some(Self::Kitty),
+            ImagePreview::Iterm => Some(Self::Iterm),
+            ImagePreview::Auto => {
+                if env::var_os("KITTY_WINDOW_ID").is_some()
+                    || var("TERM") == "xterm-kitty"
+                {
+                    Some(Self::Kitty)
+                } else if matches!(
+                    var("TERM_PROGRAM").as_str(),
+                    "iTerm.app" | "WezTerm"
+                ) {
+                    Some(Self::Iterm)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// whether the terminal can draw pictures of `format`
+    pub fn supports(self, format: ImageFormat) -> bool {
+        match self {
+            Self::Kitty => format == ImageFormat::Png,
+            Self::Iterm => true,
+        }
+    }
+
+    /// escapes drawing `image` scaled into `area`
+    pub fn draw(self, image: &[u8], area: Rect) -> String {
+        let data = base64::encode(image);
+        let goto = format!("\x1b[{};{}H", area.y + 1, area.x + 1);
+
+        match self {
+            Self::Kitty => {
+                let chunks: Vec<&[u8]> =
+                    data.as_bytes().chunks(KITTY_CHUNK).collect();
+                let last = chunks.len().saturating_sub(1);
+
+                chunks.iter().enumerate().fold(
+                    goto,
+                    |mut out, (idx, chunk)| {
+                        let more = u8::from(idx != last);
+                        // quiet, the answers would arrive as input
+                        let keys = if idx == 0 {
+                            format!(
+                                "a=T,f=100,q=2,C=1,c={},r={},m={}",
+                                area.width, area.height, more
+                            )
+                        } else {
+                            format!("m={}", more)
+                        };
+                        let _ = write!(
+                            out,
+                            "\x1b_G{};{}\x1b\\",
+                            keys,
+                            String::from_utf8_lossy(chunk)
+                        );
+                        out
+                    },
+                )
+            }
+            Self::Iterm => format!(
+                "{}\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+                goto,
+                image.len(),
+                area.width,
+                area.height,
+                data
+            ),
+        }
+    }
+
+    /// escapes removing what `draw` drew, `None` if only drawing the
+    /// screen anew gets rid of it
+    pub const fn clear(self) -> Option<&'static str> {
+        match self {
+            Self::Kitty => Some("\x1b_Ga=d,q=2\x1b\\"),
+            Self::Iterm => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kitty_chunks() {
+        let image = vec![0_u8; KITTY_CHUNK];
+        let out = GraphicsProtocol::Kitty
+            .draw(&image, Rect::new(2, 1, 10, 5));
+
+        assert!(out.starts_with(
+            "\x1b[2;3H\x1b_Ga=T,f=100,q=2,C=1,c=10,r=5,m=1;"
+        ));
+        assert_eq!(out.matches("\x1b_G").count(), 2);
+        assert!(out.contains("\x1b_Gm=0;"));
+    }
+}
@@ -1,3 +1,6 @@
+pub mod background;
+pub mod graphics;
+pub mod notifications;
 mod scrolllist;
 pub mod style;
 
@@ -72,6 +75,14 @@ pub fn rect_min(width: u16, height: u16, r: Rect) -> Rect {
     )
 }
 
+/// is the cell at `column`/`row` inside of `r`
+pub const fn rect_contains(r: Rect, column: u16, row: u16) -> bool {
+    column >= r.x
+        && column < r.x.saturating_add(r.width)
+        && row >= r.y
+        && row < r.y.saturating_add(r.height)
+}
+
 pub fn centered_rect_absolute(
     width: u16,
     height: u16,
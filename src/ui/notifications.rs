@@ -0,0 +1,238 @@
+use super::style::SharedTheme;
+use crate::strings;
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    widgets::{Block, Borders, Clear, Paragraph, Text},
+    Frame,
+};
+
+/// width of a toast including borders
+const WIDTH: u16 = 50;
+/// toasts are single line plus borders
+const HEIGHT: u16 = 3;
+
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    ///
+    Success,
+    ///
+    Warning,
+    ///
+    Error,
+}
+
+/// brief result of an operation, `details` hold the full output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub msg: String,
+    pub details: Option<String>,
+}
+
+impl Notification {
+    ///
+    pub fn success(msg: impl Into<String>) -> Self {
+        Self {
+            level: NotificationLevel::Success,
+            msg: msg.into(),
+            details: None,
+        }
+    }
+
+    ///
+    pub fn warning(
+        msg: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self {
+            level: NotificationLevel::Warning,
+            msg: msg.into(),
+            details: Some(details.into()),
+        }
+    }
+
+    ///
+    pub fn error(
+        msg: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self {
+            level: NotificationLevel::Error,
+            msg: msg.into(),
+            details: Some(details.into()),
+        }
+    }
+
+    /// the first line of `report` (like `HookResult::error`) becomes
+    /// the message, the rest the details
+    pub fn from_report(
+        level: NotificationLevel,
+        report: &str,
+    ) -> Self {
+        let mut lines = report.splitn(2, '\n');
+
+        Self {
+            level,
+            msg: lines
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(':')
+                .to_string(),
+            details: lines.next().map(String::from),
+        }
+    }
+}
+
+/// toasts stacked in the bottom right corner, dismissed
+/// automatically after `timeout`
+pub struct Notifications {
+    items: VecDeque<(Notification, Instant)>,
+    timeout: Duration,
+    theme: SharedTheme,
+}
+
+impl Notifications {
+    ///
+    pub fn new(timeout: Duration, theme: SharedTheme) -> Self {
+        Self {
+            items: VecDeque::new(),
+            timeout,
+            theme,
+        }
+    }
+
+    ///
+    pub fn push(&mut self, notification: Notification) {
+        log::info!(
+            "notification: {:?} {}",
+            notification.level,
+            notification.msg
+        );
+        self.items.push_back((notification, Instant::now()));
+    }
+
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// drops timed out toasts, returns whether any were dropped
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let count = self.items.len();
+        let timeout = self.timeout;
+
+        self.items.retain(|(_, created)| {
+            now.saturating_duration_since(*created) < timeout
+        });
+
+        count != self.items.len()
+    }
+
+    ///
+    pub fn has_details(&self) -> bool {
+        self.items.iter().any(|(item, _)| item.details.is_some())
+    }
+
+    /// removes the newest toast that has details and returns them
+    pub fn take_details(&mut self) -> Option<Notification> {
+        let idx = self
+            .items
+            .iter()
+            .rposition(|(item, _)| item.details.is_some())?;
+
+        self.items.remove(idx).map(|(item, _)| item)
+    }
+
+    ///
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let width = WIDTH.min(area.width);
+        let x = area.x + area.width - width;
+        let mut bottom = area.y + area.height;
+
+        // newest at the bottom, older ones above as long as they fit
+        for (item, _) in self.items.iter().rev() {
+            if bottom < area.y + HEIGHT {
+                break;
+            }
+            bottom -= HEIGHT;
+
+            let rect = Rect::new(x, bottom, width, HEIGHT);
+            let style = self.theme.notification(item.level);
+            let msg = item.msg.lines().next().unwrap_or_default();
+            let txt = [Text::Styled(Cow::from(msg), style)];
+
+            f.render_widget(Clear, rect);
+            f.render_widget(
+                Paragraph::new(txt.iter()).block(
+                    Block::default()
+                        .title(if item.details.is_some() {
+                            strings::NOTIFICATION_DETAILS_HINT
+                        } else {
+                            ""
+                        })
+                        .borders(Borders::ALL)
+                        .border_style(style),
+                ),
+                rect,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::style::Theme;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_expire_and_details() {
+        let mut notifications = Notifications::new(
+            Duration::from_secs(5),
+            Rc::new(Theme::default()),
+        );
+
+        notifications.push(Notification::error("push failed", "out"));
+        notifications.push(Notification::success("pushed"));
+
+        assert!(!notifications.expire(Instant::now()));
+        assert_eq!(
+            notifications.take_details(),
+            Some(Notification::error("push failed", "out"))
+        );
+        assert_eq!(notifications.take_details(), None);
+
+        assert!(notifications
+            .expire(Instant::now() + Duration::from_secs(6)));
+        assert!(notifications.is_empty());
+    }
+
+    #[test]
+    fn test_from_report() {
+        assert_eq!(
+            Notification::from_report(
+                NotificationLevel::Error,
+                "pre-commit hook failed (exit 1):\nrejected\n"
+            ),
+            Notification::error(
+                "pre-commit hook failed (exit 1)",
+                "rejected\n"
+            )
+        );
+        assert_eq!(
+            Notification::from_report(
+                NotificationLevel::Warning,
+                "post-commit hook timed out after 5s"
+            )
+            .details,
+            None
+        );
+    }
+}
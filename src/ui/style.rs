@@ -1,6 +1,7 @@
+use super::notifications::NotificationLevel;
 use crate::get_app_config_path;
 use anyhow::Result;
-use asyncgit::{DiffLineType, StatusItemType};
+use asyncgit::{sync::SubmoduleStatus, DiffLineType, StatusItemType};
 use ron::{
     de::from_bytes,
     ser::{to_string_pretty, PrettyConfig},
@@ -107,10 +108,32 @@ impl Theme {
             StatusItemType::Deleted => {
                 Style::default().fg(self.diff_file_removed)
             }
-            StatusItemType::Renamed => {
+            StatusItemType::Renamed | StatusItemType::Copied => {
                 Style::default().fg(self.diff_file_moved)
             }
             StatusItemType::Typechange => Style::default(),
+            StatusItemType::Conflicted => self.text_danger(),
+        };
+
+        self.apply_select(style, selected)
+    }
+
+    pub fn submodule(
+        &self,
+        status: SubmoduleStatus,
+        selected: bool,
+    ) -> Style {
+        let style = match status {
+            SubmoduleStatus::Uninitialized => {
+                Style::default().fg(self.disabled_fg)
+            }
+            SubmoduleStatus::OutOfDate => {
+                Style::default().fg(self.diff_file_removed)
+            }
+            SubmoduleStatus::Modified => {
+                Style::default().fg(self.diff_file_modified)
+            }
+            SubmoduleStatus::UpToDate => Style::default(),
         };
 
         self.apply_select(style, selected)
@@ -140,6 +163,11 @@ impl Theme {
         }
     }
 
+    /// a match of the find popup, on top of the colors of `style`
+    pub fn search_match(style: Style) -> Style {
+        style.modifier(style.modifier | Modifier::REVERSED)
+    }
+
     pub fn diff_line(
         &self,
         typ: DiffLineType,
@@ -165,6 +193,14 @@ impl Theme {
         self.apply_select(style, selected)
     }
 
+    pub fn notification(&self, level: NotificationLevel) -> Style {
+        Style::default().fg(match level {
+            NotificationLevel::Success => self.diff_line_add,
+            NotificationLevel::Warning => self.diff_file_modified,
+            NotificationLevel::Error => self.danger_fg,
+        })
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -214,6 +250,12 @@ impl Theme {
         Ok(app_home.join("theme.ron"))
     }
 
+    /// `<name>.ron` in the `themes` directory of the config path
+    fn get_named_theme_file(name: &str) -> Result<PathBuf> {
+        let app_home = get_app_config_path()?;
+        Ok(app_home.join("themes").join(format!("{}.ron", name)))
+    }
+
     fn read_file(theme_file: PathBuf) -> Result<Self> {
         let mut f = File::open(theme_file)?;
         let mut buffer = Vec::new();
@@ -221,7 +263,22 @@ impl Theme {
         Ok(from_bytes(&buffer)?)
     }
 
-    fn init_internal() -> Result<Self> {
+    fn init_internal(name: Option<&str>) -> Result<Self> {
+        if let Some(name) = name {
+            match Self::get_named_theme_file(name)
+                .and_then(Self::read_file)
+            {
+                Ok(theme) => return Ok(theme),
+                Err(e) => {
+                    log::warn!(
+                        "failed to load theme '{}': {}",
+                        name,
+                        e
+                    );
+                }
+            }
+        }
+
         let file = Self::get_theme_file()?;
         if file.exists() {
             Ok(Self::read_file(file)?)
@@ -234,8 +291,10 @@ impl Theme {
         }
     }
 
-    pub fn init() -> Self {
-        Self::init_internal().unwrap_or_default()
+    /// loads `name` from the `themes` directory, `theme.ron` if
+    /// not set or invalid
+    pub fn init(name: Option<&str>) -> Self {
+        Self::init_internal(name).unwrap_or_default()
     }
 }
 